@@ -69,9 +69,54 @@ PB_BIND(domes_config_SetPodIdRequest, domes_config_SetPodIdRequest, AUTO)
 PB_BIND(domes_config_SetPodIdResponse, domes_config_SetPodIdResponse, AUTO)
 
 
+PB_BIND(domes_config_SetBaudRateRequest, domes_config_SetBaudRateRequest, AUTO)
+
+
+PB_BIND(domes_config_SetBaudRateResponse, domes_config_SetBaudRateResponse, AUTO)
+
+
+PB_BIND(domes_config_SetDeviceNameRequest, domes_config_SetDeviceNameRequest, AUTO)
+
+
+PB_BIND(domes_config_SetDeviceNameResponse, domes_config_SetDeviceNameResponse, AUTO)
+
+
 PB_BIND(domes_config_TaskHealth, domes_config_TaskHealth, AUTO)
 
 
+PB_BIND(domes_config_TaskInfo, domes_config_TaskInfo, AUTO)
+
+
+PB_BIND(domes_config_GetTaskListRequest, domes_config_GetTaskListRequest, AUTO)
+
+
+PB_BIND(domes_config_GetTaskListResponse, domes_config_GetTaskListResponse, 2)
+
+
+PB_BIND(domes_config_PartitionInfo, domes_config_PartitionInfo, AUTO)
+
+
+PB_BIND(domes_config_GetPartitionTableRequest, domes_config_GetPartitionTableRequest, AUTO)
+
+
+PB_BIND(domes_config_GetPartitionTableResponse, domes_config_GetPartitionTableResponse, 2)
+
+
+PB_BIND(domes_config_WifiApConfigRequest, domes_config_WifiApConfigRequest, AUTO)
+
+
+PB_BIND(domes_config_WifiApConfigResponse, domes_config_WifiApConfigResponse, AUTO)
+
+
+PB_BIND(domes_config_WifiApStatusRequest, domes_config_WifiApStatusRequest, AUTO)
+
+
+PB_BIND(domes_config_WifiApClient, domes_config_WifiApClient, AUTO)
+
+
+PB_BIND(domes_config_WifiApStatusResponse, domes_config_WifiApStatusResponse, AUTO)
+
+
 PB_BIND(domes_config_GetHealthRequest, domes_config_GetHealthRequest, AUTO)
 
 
@@ -105,6 +150,24 @@ PB_BIND(domes_config_ClearCrashDumpRequest, domes_config_ClearCrashDumpRequest,
 PB_BIND(domes_config_ClearCrashDumpResponse, domes_config_ClearCrashDumpResponse, AUTO)
 
 
+PB_BIND(domes_config_CoreDumpReadRequest, domes_config_CoreDumpReadRequest, AUTO)
+
+
+PB_BIND(domes_config_CoreDumpReadResponse, domes_config_CoreDumpReadResponse, AUTO)
+
+
+PB_BIND(domes_config_CoreDumpChunk, domes_config_CoreDumpChunk, 2)
+
+
+PB_BIND(domes_config_CoreDumpComplete, domes_config_CoreDumpComplete, AUTO)
+
+
+PB_BIND(domes_config_ClearCoreDumpRequest, domes_config_ClearCoreDumpRequest, AUTO)
+
+
+PB_BIND(domes_config_ClearCoreDumpResponse, domes_config_ClearCoreDumpResponse, AUTO)
+
+
 PB_BIND(domes_config_HeapSample, domes_config_HeapSample, AUTO)
 
 
@@ -147,6 +210,24 @@ PB_BIND(domes_config_SetSimModeRequest, domes_config_SetSimModeRequest, AUTO)
 PB_BIND(domes_config_SetSimModeResponse, domes_config_SetSimModeResponse, AUTO)
 
 
+PB_BIND(domes_config_SubscribeEventsRequest, domes_config_SubscribeEventsRequest, AUTO)
+
+
+PB_BIND(domes_config_SubscribeEventsResponse, domes_config_SubscribeEventsResponse, AUTO)
+
+
+PB_BIND(domes_config_EventNotification, domes_config_EventNotification, AUTO)
+
+
+PB_BIND(domes_config_BatchCommand, domes_config_BatchCommand, 2)
+
+
+PB_BIND(domes_config_BatchRequest, domes_config_BatchRequest, 2)
+
+
+PB_BIND(domes_config_BatchResponse, domes_config_BatchResponse, 2)
+
+
 PB_BIND(domes_config_ConfigRequest, domes_config_ConfigRequest, AUTO)
 
 
@@ -164,3 +245,9 @@ PB_BIND(domes_config_ConfigResponse, domes_config_ConfigResponse, AUTO)
 
 
 
+
+
+
+
+
+
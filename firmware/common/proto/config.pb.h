@@ -64,7 +64,47 @@ typedef enum _domes_config_MsgType {
     domes_config_MsgType_MSG_TYPE_SIMULATE_TOUCH_RSP = 77,
     /* Sim drill mode commands (0x4E-0x4F) */
     domes_config_MsgType_MSG_TYPE_SET_SIM_MODE_REQ = 78,
-    domes_config_MsgType_MSG_TYPE_SET_SIM_MODE_RSP = 79
+    domes_config_MsgType_MSG_TYPE_SET_SIM_MODE_RSP = 79,
+    /* Batch commands (0x50-0x51) */
+    domes_config_MsgType_MSG_TYPE_BATCH_REQ = 80,
+    domes_config_MsgType_MSG_TYPE_BATCH_RSP = 81,
+    /* Device name commands (0x52-0x53) */
+    domes_config_MsgType_MSG_TYPE_SET_DEVICE_NAME_REQ = 82,
+    domes_config_MsgType_MSG_TYPE_SET_DEVICE_NAME_RSP = 83,
+    /* Event subscription (0x54-0x56) */
+    domes_config_MsgType_MSG_TYPE_SUBSCRIBE_EVENTS_REQ = 84,
+    domes_config_MsgType_MSG_TYPE_SUBSCRIBE_EVENTS_RSP = 85,
+    domes_config_MsgType_MSG_TYPE_EVENT_NOTIFICATION = 86, /* Unsolicited, device -> host */
+    /* Keepalive (0x57-0x58): empty request, empty response */
+    domes_config_MsgType_MSG_TYPE_PING_REQ = 87,
+    domes_config_MsgType_MSG_TYPE_PING_RSP = 88,
+    /* Serial baud rate (0x59-0x5A): the response is sent at the old rate,
+ then the device switches */
+    domes_config_MsgType_MSG_TYPE_SET_BAUD_RATE_REQ = 89,
+    domes_config_MsgType_MSG_TYPE_SET_BAUD_RATE_RSP = 90,
+    /* FreeRTOS task list (0x5B-0x5C) */
+    domes_config_MsgType_MSG_TYPE_GET_TASK_LIST_REQ = 91,
+    domes_config_MsgType_MSG_TYPE_GET_TASK_LIST_RSP = 92,
+    /* Flash partition table (0x5D-0x5E) */
+    domes_config_MsgType_MSG_TYPE_GET_PARTITION_TABLE_REQ = 93,
+    domes_config_MsgType_MSG_TYPE_GET_PARTITION_TABLE_RSP = 94,
+    /* Core dump read-out (0x5F-0x64) */
+    domes_config_MsgType_MSG_TYPE_CORE_DUMP_READ_REQ = 95,
+    domes_config_MsgType_MSG_TYPE_CORE_DUMP_READ_RSP = 96, /* Followed by DATA frames and END if valid */
+    domes_config_MsgType_MSG_TYPE_CORE_DUMP_DATA = 97,
+    domes_config_MsgType_MSG_TYPE_CORE_DUMP_END = 98,
+    domes_config_MsgType_MSG_TYPE_CORE_DUMP_CLEAR_REQ = 99,
+    domes_config_MsgType_MSG_TYPE_CORE_DUMP_CLEAR_RSP = 100,
+    /* WiFi access point mode (0x65-0x68) */
+    domes_config_MsgType_MSG_TYPE_WIFI_AP_CONFIG_REQ = 101,
+    domes_config_MsgType_MSG_TYPE_WIFI_AP_CONFIG_RSP = 102,
+    domes_config_MsgType_MSG_TYPE_WIFI_AP_STATUS_REQ = 103,
+    domes_config_MsgType_MSG_TYPE_WIFI_AP_STATUS_RSP = 104,
+    /* WiFi station static IP (0x69-0x6A). Not protobuf: the request is five
+ packed big-endian IPv4 addresses (ip, netmask, gateway, dns1, dns2),
+ all zero to revert to DHCP; the response is a status byte. */
+    domes_config_MsgType_MSG_TYPE_WIFI_STATIC_IP_REQ = 105,
+    domes_config_MsgType_MSG_TYPE_WIFI_STATIC_IP_RSP = 106
 } domes_config_MsgType;
 
 /* Status codes for responses */
@@ -107,6 +147,39 @@ typedef enum _domes_config_SystemMode {
     domes_config_SystemMode_SYSTEM_MODE_ERROR = 5
 } domes_config_SystemMode;
 
+/* FreeRTOS task state (eTaskState, shifted so 0 means unknown) */
+typedef enum _domes_config_TaskState {
+    domes_config_TaskState_TASK_STATE_UNKNOWN = 0,
+    domes_config_TaskState_TASK_STATE_RUNNING = 1,
+    domes_config_TaskState_TASK_STATE_READY = 2,
+    domes_config_TaskState_TASK_STATE_BLOCKED = 3,
+    domes_config_TaskState_TASK_STATE_SUSPENDED = 4,
+    domes_config_TaskState_TASK_STATE_DELETED = 5
+} domes_config_TaskState;
+
+/* Partition kind; data partitions with a well-known subtype get their own */
+typedef enum _domes_config_PartitionType {
+    domes_config_PartitionType_PARTITION_TYPE_UNKNOWN = 0,
+    domes_config_PartitionType_PARTITION_TYPE_APP = 1, /* Factory, OTA or test app image */
+    domes_config_PartitionType_PARTITION_TYPE_DATA = 2, /* Data partition with another subtype */
+    domes_config_PartitionType_PARTITION_TYPE_NVS = 3,
+    domes_config_PartitionType_PARTITION_TYPE_OTADATA = 4, /* Which OTA slot boots next */
+    domes_config_PartitionType_PARTITION_TYPE_PHY = 5, /* RF calibration data */
+    domes_config_PartitionType_PARTITION_TYPE_COREDUMP = 6,
+    domes_config_PartitionType_PARTITION_TYPE_SPIFFS = 7,
+    domes_config_PartitionType_PARTITION_TYPE_FAT = 8
+} domes_config_PartitionType;
+
+/* Event categories, used as bits in SubscribeEventsRequest.categories */
+typedef enum _domes_config_EventCategory {
+    domes_config_EventCategory_EVENT_CATEGORY_NONE = 0,
+    domes_config_EventCategory_EVENT_CATEGORY_MODE = 1, /* System mode transitions */
+    domes_config_EventCategory_EVENT_CATEGORY_LED = 2, /* LED pattern changes */
+    domes_config_EventCategory_EVENT_CATEGORY_WIFI = 4, /* WiFi connect/disconnect */
+    domes_config_EventCategory_EVENT_CATEGORY_FEATURE = 8, /* Feature enable/disable */
+    domes_config_EventCategory_EVENT_CATEGORY_TOUCH = 16 /* Pad touches */
+} domes_config_EventCategory;
+
 /* Struct definitions */
 /* RGBW color (0-255 per channel) */
 typedef struct _domes_config_Color {
@@ -191,10 +264,13 @@ typedef struct _domes_config_GetModeRequest { /* Empty - returns current mode */
 typedef struct _domes_config_GetModeResponse {
     domes_config_SystemMode mode;
     uint32_t time_in_mode_ms;
+    domes_config_SystemMode previous_mode; /* Mode before the last transition */
+    bool has_previous_mode; /* False until the first transition */
 } domes_config_GetModeResponse;
 
 typedef struct _domes_config_SetModeRequest {
     domes_config_SystemMode mode;
+    bool force; /* Skip the transition guards (testing); older firmware ignores it */
 } domes_config_SetModeRequest;
 
 typedef struct _domes_config_SetModeResponse {
@@ -214,6 +290,7 @@ typedef struct _domes_config_GetSystemInfoResponse {
     domes_config_SystemMode mode;
     uint32_t feature_mask;
     uint32_t pod_id; /* Pod identity (0 = not set) */
+    uint32_t max_payload; /* Largest frame payload accepted (0 = not reported) */
 } domes_config_GetSystemInfoResponse;
 
 /* Set pod ID (persisted to NVS) */
@@ -225,6 +302,24 @@ typedef struct _domes_config_SetPodIdResponse {
     uint32_t pod_id; /* New pod ID after write */
 } domes_config_SetPodIdResponse;
 
+/* Switch the USB serial link's baud rate (not persisted; resets on reboot) */
+typedef struct _domes_config_SetBaudRateRequest {
+    uint32_t baud_rate;
+} domes_config_SetBaudRateRequest;
+
+typedef struct _domes_config_SetBaudRateResponse {
+    uint32_t baud_rate; /* Rate the device switches to */
+} domes_config_SetBaudRateResponse;
+
+/* Set custom BLE advertised name (persisted to NVS, replaces DOMES-Pod-NN) */
+typedef struct _domes_config_SetDeviceNameRequest {
+    char name[21]; /* Printable ASCII, 1-20 chars */
+} domes_config_SetDeviceNameRequest;
+
+typedef struct _domes_config_SetDeviceNameResponse {
+    char name[21]; /* New name after write */
+} domes_config_SetDeviceNameResponse;
+
 /* FreeRTOS task health snapshot */
 typedef struct _domes_config_TaskHealth {
     char name[16];
@@ -233,6 +328,83 @@ typedef struct _domes_config_TaskHealth {
     uint32_t core;
 } domes_config_TaskHealth;
 
+/* One task from uxTaskGetSystemState */
+typedef struct _domes_config_TaskInfo {
+    char name[16];
+    domes_config_TaskState state;
+    uint32_t priority;
+    uint32_t stack_watermark_bytes; /* Least free stack since the task started */
+    float runtime_percent; /* Share of CPU time since boot */
+    uint32_t core_id;
+    bool has_core_id; /* False if the task is not pinned to a core */
+} domes_config_TaskInfo;
+
+typedef struct _domes_config_GetTaskListRequest { /* Empty - returns every task */
+    char dummy_field;
+} domes_config_GetTaskListRequest;
+
+typedef struct _domes_config_GetTaskListResponse {
+    pb_size_t tasks_count;
+    domes_config_TaskInfo tasks[22];
+} domes_config_GetTaskListResponse;
+
+/* One entry of the flash partition table (esp_partition_t) */
+typedef struct _domes_config_PartitionInfo {
+    char name[17]; /* Partition label */
+    domes_config_PartitionType type;
+    uint32_t subtype; /* Raw ESP-IDF subtype (e.g. 0x10 for ota_0) */
+    uint32_t offset; /* Flash address in bytes */
+    uint32_t size; /* Bytes */
+    bool encrypted;
+    bool active; /* The app partition currently running */
+} domes_config_PartitionInfo;
+
+typedef struct _domes_config_GetPartitionTableRequest { /* Empty - returns every partition, in flash order */
+    char dummy_field;
+} domes_config_GetPartitionTableRequest;
+
+typedef struct _domes_config_GetPartitionTableResponse {
+    pb_size_t partitions_count;
+    domes_config_PartitionInfo partitions[16];
+} domes_config_GetPartitionTableResponse;
+
+/* Run WiFi as an access point (persisted to NVS) for standalone deployments */
+typedef struct _domes_config_WifiApConfigRequest {
+    char ssid[33];
+    char password[64]; /* Empty for an open network, else 8-63 chars (WPA2-PSK) */
+    uint32_t channel; /* 1-13 */
+    uint32_t max_connections; /* 1-10 stations */
+} domes_config_WifiApConfigRequest;
+
+/* Echoes the applied configuration (without the password) */
+typedef struct _domes_config_WifiApConfigResponse {
+    char ssid[33];
+    uint32_t channel;
+    uint32_t max_connections;
+    bool secured; /* false for an open network */
+    uint32_t ip; /* AP gateway IPv4 address, first octet in the high byte */
+} domes_config_WifiApConfigResponse;
+
+typedef struct _domes_config_WifiApStatusRequest { /* Empty - returns AP state and connected stations */
+    char dummy_field;
+} domes_config_WifiApStatusRequest;
+
+typedef PB_BYTES_ARRAY_T(6) domes_config_WifiApClient_mac_t;
+/* A station connected to the AP */
+typedef struct _domes_config_WifiApClient {
+    domes_config_WifiApClient_mac_t mac;
+    int32_t rssi; /* dBm */
+} domes_config_WifiApClient;
+
+typedef struct _domes_config_WifiApStatusResponse {
+    bool active; /* false if the pod is not running as an AP */
+    char ssid[33];
+    uint32_t channel;
+    uint32_t ip; /* AP gateway IPv4 address, first octet in the high byte */
+    pb_size_t clients_count;
+    domes_config_WifiApClient clients[10];
+} domes_config_WifiApStatusResponse;
+
 /* System health diagnostics */
 typedef struct _domes_config_GetHealthRequest { /* Empty - returns health diagnostics */
     char dummy_field;
@@ -312,6 +484,38 @@ typedef struct _domes_config_ClearCrashDumpResponse {
     bool cleared; /* true if dump was cleared */
 } domes_config_ClearCrashDumpResponse;
 
+/* Core dump from the coredump partition, written by ESP-IDF on panic */
+typedef struct _domes_config_CoreDumpReadRequest { /* Empty - streams the stored core dump */
+    char dummy_field;
+} domes_config_CoreDumpReadRequest;
+
+/* Sent first; CORE_DUMP_DATA frames and CORE_DUMP_END follow only if valid */
+typedef struct _domes_config_CoreDumpReadResponse {
+    bool valid; /* true if the partition holds a valid core dump */
+    uint32_t size; /* Core dump size in bytes */
+    char reset_reason[32]; /* Reset reason of the boot that crashed (e.g. "panic") */
+} domes_config_CoreDumpReadResponse;
+
+typedef PB_BYTES_ARRAY_T(512) domes_config_CoreDumpChunk_data_t;
+/* CORE_DUMP_DATA payload (no status byte) */
+typedef struct _domes_config_CoreDumpChunk {
+    uint32_t offset; /* Offset of data within the core dump */
+    domes_config_CoreDumpChunk_data_t data;
+} domes_config_CoreDumpChunk;
+
+/* CORE_DUMP_END payload (no status byte) */
+typedef struct _domes_config_CoreDumpComplete {
+    uint32_t checksum; /* CRC32 of the whole core dump */
+} domes_config_CoreDumpComplete;
+
+typedef struct _domes_config_ClearCoreDumpRequest { /* Empty - erases the coredump partition */
+    char dummy_field;
+} domes_config_ClearCoreDumpRequest;
+
+typedef struct _domes_config_ClearCoreDumpResponse {
+    bool erased; /* true if the core dump was erased */
+} domes_config_ClearCoreDumpResponse;
+
 /* Single heap sample */
 typedef struct _domes_config_HeapSample {
     uint32_t timestamp_s; /* Uptime when sample was taken (seconds) */
@@ -398,6 +602,49 @@ typedef struct _domes_config_SetSimModeResponse {
     uint32_t pad_index;
 } domes_config_SetSimModeResponse;
 
+/* Subscribe to events (replaces the previous subscription) */
+typedef struct _domes_config_SubscribeEventsRequest {
+    uint32_t categories; /* Bitmask of EventCategory; 0 unsubscribes */
+} domes_config_SubscribeEventsRequest;
+
+typedef struct _domes_config_SubscribeEventsResponse {
+    uint32_t categories; /* Categories now subscribed */
+} domes_config_SubscribeEventsResponse;
+
+/* Pushed by the device while subscribed (protobuf only, no status byte).
+ Only the field matching the category is set. */
+typedef struct _domes_config_EventNotification {
+    domes_config_EventCategory category;
+    uint32_t timestamp_ms; /* Device uptime when the event occurred */
+    domes_config_SystemMode mode; /* EVENT_CATEGORY_MODE: new mode */
+    bool has_led_pattern;
+    domes_config_LedPattern led_pattern; /* EVENT_CATEGORY_LED: new pattern */
+    bool wifi_connected; /* EVENT_CATEGORY_WIFI: new state */
+    bool has_feature;
+    domes_config_FeatureState feature; /* EVENT_CATEGORY_FEATURE: new state */
+    uint32_t pad_index; /* EVENT_CATEGORY_TOUCH: pad touched */
+} domes_config_EventNotification;
+
+typedef PB_BYTES_ARRAY_T(256) domes_config_BatchCommand_payload_t;
+/* One command inside a batch: a config request type and its payload,
+ or a response type and its payload ([status][protobuf], as sent alone) */
+typedef struct _domes_config_BatchCommand {
+    uint32_t msg_type;
+    domes_config_BatchCommand_payload_t payload;
+} domes_config_BatchCommand;
+
+/* Several config requests handled in one round trip, in order */
+typedef struct _domes_config_BatchRequest {
+    pb_size_t commands_count;
+    domes_config_BatchCommand commands[3];
+} domes_config_BatchRequest;
+
+/* Responses to a BatchRequest, in request order */
+typedef struct _domes_config_BatchResponse {
+    pb_size_t responses_count;
+    domes_config_BatchCommand responses[3];
+} domes_config_BatchResponse;
+
 /* Top-level request envelope */
 typedef struct _domes_config_ConfigRequest {
     pb_size_t which_request;
@@ -424,8 +671,8 @@ extern "C" {
 
 /* Helper constants for enums */
 #define _domes_config_MsgType_MIN domes_config_MsgType_MSG_TYPE_UNKNOWN
-#define _domes_config_MsgType_MAX domes_config_MsgType_MSG_TYPE_SET_SIM_MODE_RSP
-#define _domes_config_MsgType_ARRAYSIZE ((domes_config_MsgType)(domes_config_MsgType_MSG_TYPE_SET_SIM_MODE_RSP+1))
+#define _domes_config_MsgType_MAX domes_config_MsgType_MSG_TYPE_WIFI_STATIC_IP_RSP
+#define _domes_config_MsgType_ARRAYSIZE ((domes_config_MsgType)(domes_config_MsgType_MSG_TYPE_WIFI_STATIC_IP_RSP+1))
 
 #define _domes_config_Status_MIN domes_config_Status_STATUS_OK
 #define _domes_config_Status_MAX domes_config_Status_STATUS_NO_DATA
@@ -443,6 +690,18 @@ extern "C" {
 #define _domes_config_SystemMode_MAX domes_config_SystemMode_SYSTEM_MODE_ERROR
 #define _domes_config_SystemMode_ARRAYSIZE ((domes_config_SystemMode)(domes_config_SystemMode_SYSTEM_MODE_ERROR+1))
 
+#define _domes_config_TaskState_MIN domes_config_TaskState_TASK_STATE_UNKNOWN
+#define _domes_config_TaskState_MAX domes_config_TaskState_TASK_STATE_DELETED
+#define _domes_config_TaskState_ARRAYSIZE ((domes_config_TaskState)(domes_config_TaskState_TASK_STATE_DELETED+1))
+
+#define _domes_config_PartitionType_MIN domes_config_PartitionType_PARTITION_TYPE_UNKNOWN
+#define _domes_config_PartitionType_MAX domes_config_PartitionType_PARTITION_TYPE_FAT
+#define _domes_config_PartitionType_ARRAYSIZE ((domes_config_PartitionType)(domes_config_PartitionType_PARTITION_TYPE_FAT+1))
+
+#define _domes_config_EventCategory_MIN domes_config_EventCategory_EVENT_CATEGORY_NONE
+#define _domes_config_EventCategory_MAX domes_config_EventCategory_EVENT_CATEGORY_TOUCH
+#define _domes_config_EventCategory_ARRAYSIZE ((domes_config_EventCategory)(domes_config_EventCategory_EVENT_CATEGORY_TOUCH+1))
+
 
 #define domes_config_FeatureState_feature_ENUMTYPE domes_config_Feature
 
@@ -461,6 +720,7 @@ extern "C" {
 
 
 #define domes_config_GetModeResponse_mode_ENUMTYPE domes_config_SystemMode
+#define domes_config_GetModeResponse_previous_mode_ENUMTYPE domes_config_SystemMode
 
 #define domes_config_SetModeRequest_mode_ENUMTYPE domes_config_SystemMode
 
@@ -476,6 +736,29 @@ extern "C" {
 
 
 
+#define domes_config_TaskInfo_state_ENUMTYPE domes_config_TaskState
+
+
+
+#define domes_config_PartitionInfo_type_ENUMTYPE domes_config_PartitionType
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
 
 
 
@@ -500,6 +783,14 @@ extern "C" {
 #define domes_config_SetSimModeResponse_status_ENUMTYPE domes_config_Status
 
 
+
+#define domes_config_EventNotification_category_ENUMTYPE domes_config_EventCategory
+#define domes_config_EventNotification_mode_ENUMTYPE domes_config_SystemMode
+
+
+
+
+
 #define domes_config_ConfigResponse_status_ENUMTYPE domes_config_Status
 
 
@@ -518,14 +809,29 @@ extern "C" {
 #define domes_config_SetImuTriageRequest_init_default {0}
 #define domes_config_SetImuTriageResponse_init_default {0}
 #define domes_config_GetModeRequest_init_default {0}
-#define domes_config_GetModeResponse_init_default {_domes_config_SystemMode_MIN, 0}
-#define domes_config_SetModeRequest_init_default {_domes_config_SystemMode_MIN}
+#define domes_config_GetModeResponse_init_default {_domes_config_SystemMode_MIN, 0, _domes_config_SystemMode_MIN, 0}
+#define domes_config_SetModeRequest_init_default {_domes_config_SystemMode_MIN, 0}
 #define domes_config_SetModeResponse_init_default {_domes_config_SystemMode_MIN, 0}
 #define domes_config_GetSystemInfoRequest_init_default {0}
-#define domes_config_GetSystemInfoResponse_init_default {"", 0, 0, 0, _domes_config_SystemMode_MIN, 0, 0}
+#define domes_config_GetSystemInfoResponse_init_default {"", 0, 0, 0, _domes_config_SystemMode_MIN, 0, 0, 0}
 #define domes_config_SetPodIdRequest_init_default {0}
 #define domes_config_SetPodIdResponse_init_default {0}
+#define domes_config_SetBaudRateRequest_init_default {0}
+#define domes_config_SetBaudRateResponse_init_default {0}
+#define domes_config_SetDeviceNameRequest_init_default {""}
+#define domes_config_SetDeviceNameResponse_init_default {""}
 #define domes_config_TaskHealth_init_default     {"", 0, 0, 0}
+#define domes_config_TaskInfo_init_default       {"", _domes_config_TaskState_MIN, 0, 0, 0, 0, 0}
+#define domes_config_GetTaskListRequest_init_default {0}
+#define domes_config_GetTaskListResponse_init_default {0, {domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default, domes_config_TaskInfo_init_default}}
+#define domes_config_PartitionInfo_init_default  {"", _domes_config_PartitionType_MIN, 0, 0, 0, 0, 0}
+#define domes_config_GetPartitionTableRequest_init_default {0}
+#define domes_config_GetPartitionTableResponse_init_default {0, {domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default, domes_config_PartitionInfo_init_default}}
+#define domes_config_WifiApConfigRequest_init_default {"", "", 0, 0}
+#define domes_config_WifiApConfigResponse_init_default {"", 0, 0, 0, 0}
+#define domes_config_WifiApStatusRequest_init_default {0}
+#define domes_config_WifiApClient_init_default   {{0, {0}}, 0}
+#define domes_config_WifiApStatusResponse_init_default {0, "", 0, 0, 0, {domes_config_WifiApClient_init_default, domes_config_WifiApClient_init_default, domes_config_WifiApClient_init_default, domes_config_WifiApClient_init_default, domes_config_WifiApClient_init_default, domes_config_WifiApClient_init_default, domes_config_WifiApClient_init_default, domes_config_WifiApClient_init_default, domes_config_WifiApClient_init_default, domes_config_WifiApClient_init_default}}
 #define domes_config_GetHealthRequest_init_default {0}
 #define domes_config_GetHealthResponse_init_default {0, 0, 0, 0, 0, {domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default, domes_config_TaskHealth_init_default}}
 #define domes_config_EspNowPeer_init_default     {{0, {0}}, 0, 0}
@@ -537,6 +843,12 @@ extern "C" {
 #define domes_config_CrashDumpResponse_init_default {0, "", "", 0, 0, 0, {0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0}, 0}
 #define domes_config_ClearCrashDumpRequest_init_default {0}
 #define domes_config_ClearCrashDumpResponse_init_default {0}
+#define domes_config_CoreDumpReadRequest_init_default {0}
+#define domes_config_CoreDumpReadResponse_init_default {0, 0, ""}
+#define domes_config_CoreDumpChunk_init_default  {0, {0, {0}}}
+#define domes_config_CoreDumpComplete_init_default {0}
+#define domes_config_ClearCoreDumpRequest_init_default {0}
+#define domes_config_ClearCoreDumpResponse_init_default {0}
 #define domes_config_HeapSample_init_default     {0, 0, 0, 0}
 #define domes_config_GetMemoryProfileRequest_init_default {0}
 #define domes_config_GetMemoryProfileResponse_init_default {0, 0, 0, 0, 0, {domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default, domes_config_HeapSample_init_default}}
@@ -551,6 +863,12 @@ extern "C" {
 #define domes_config_SimulateTouchResponse_init_default {_domes_config_Status_MIN}
 #define domes_config_SetSimModeRequest_init_default {0, 0, 0}
 #define domes_config_SetSimModeResponse_init_default {_domes_config_Status_MIN, 0, 0, 0}
+#define domes_config_SubscribeEventsRequest_init_default {0}
+#define domes_config_SubscribeEventsResponse_init_default {0}
+#define domes_config_EventNotification_init_default {_domes_config_EventCategory_MIN, 0, _domes_config_SystemMode_MIN, false, domes_config_LedPattern_init_default, 0, false, domes_config_FeatureState_init_default, 0}
+#define domes_config_BatchCommand_init_default   {0, {0, {0}}}
+#define domes_config_BatchRequest_init_default   {0, {domes_config_BatchCommand_init_default, domes_config_BatchCommand_init_default, domes_config_BatchCommand_init_default}}
+#define domes_config_BatchResponse_init_default  {0, {domes_config_BatchCommand_init_default, domes_config_BatchCommand_init_default, domes_config_BatchCommand_init_default}}
 #define domes_config_ConfigRequest_init_default  {0, {domes_config_ListFeaturesRequest_init_default}}
 #define domes_config_ConfigResponse_init_default {_domes_config_Status_MIN, 0, {domes_config_ListFeaturesResponse_init_default}}
 #define domes_config_Color_init_zero             {0, 0, 0, 0}
@@ -567,14 +885,29 @@ extern "C" {
 #define domes_config_SetImuTriageRequest_init_zero {0}
 #define domes_config_SetImuTriageResponse_init_zero {0}
 #define domes_config_GetModeRequest_init_zero    {0}
-#define domes_config_GetModeResponse_init_zero   {_domes_config_SystemMode_MIN, 0}
-#define domes_config_SetModeRequest_init_zero    {_domes_config_SystemMode_MIN}
+#define domes_config_GetModeResponse_init_zero   {_domes_config_SystemMode_MIN, 0, _domes_config_SystemMode_MIN, 0}
+#define domes_config_SetModeRequest_init_zero    {_domes_config_SystemMode_MIN, 0}
 #define domes_config_SetModeResponse_init_zero   {_domes_config_SystemMode_MIN, 0}
 #define domes_config_GetSystemInfoRequest_init_zero {0}
-#define domes_config_GetSystemInfoResponse_init_zero {"", 0, 0, 0, _domes_config_SystemMode_MIN, 0, 0}
+#define domes_config_GetSystemInfoResponse_init_zero {"", 0, 0, 0, _domes_config_SystemMode_MIN, 0, 0, 0}
 #define domes_config_SetPodIdRequest_init_zero   {0}
 #define domes_config_SetPodIdResponse_init_zero  {0}
+#define domes_config_SetBaudRateRequest_init_zero {0}
+#define domes_config_SetBaudRateResponse_init_zero {0}
+#define domes_config_SetDeviceNameRequest_init_zero {""}
+#define domes_config_SetDeviceNameResponse_init_zero {""}
 #define domes_config_TaskHealth_init_zero        {"", 0, 0, 0}
+#define domes_config_TaskInfo_init_zero          {"", _domes_config_TaskState_MIN, 0, 0, 0, 0, 0}
+#define domes_config_GetTaskListRequest_init_zero {0}
+#define domes_config_GetTaskListResponse_init_zero {0, {domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero, domes_config_TaskInfo_init_zero}}
+#define domes_config_PartitionInfo_init_zero     {"", _domes_config_PartitionType_MIN, 0, 0, 0, 0, 0}
+#define domes_config_GetPartitionTableRequest_init_zero {0}
+#define domes_config_GetPartitionTableResponse_init_zero {0, {domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero, domes_config_PartitionInfo_init_zero}}
+#define domes_config_WifiApConfigRequest_init_zero {"", "", 0, 0}
+#define domes_config_WifiApConfigResponse_init_zero {"", 0, 0, 0, 0}
+#define domes_config_WifiApStatusRequest_init_zero {0}
+#define domes_config_WifiApClient_init_zero      {{0, {0}}, 0}
+#define domes_config_WifiApStatusResponse_init_zero {0, "", 0, 0, 0, {domes_config_WifiApClient_init_zero, domes_config_WifiApClient_init_zero, domes_config_WifiApClient_init_zero, domes_config_WifiApClient_init_zero, domes_config_WifiApClient_init_zero, domes_config_WifiApClient_init_zero, domes_config_WifiApClient_init_zero, domes_config_WifiApClient_init_zero, domes_config_WifiApClient_init_zero, domes_config_WifiApClient_init_zero}}
 #define domes_config_GetHealthRequest_init_zero  {0}
 #define domes_config_GetHealthResponse_init_zero {0, 0, 0, 0, 0, {domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero, domes_config_TaskHealth_init_zero}}
 #define domes_config_EspNowPeer_init_zero        {{0, {0}}, 0, 0}
@@ -586,6 +919,12 @@ extern "C" {
 #define domes_config_CrashDumpResponse_init_zero {0, "", "", 0, 0, 0, {0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0}, 0}
 #define domes_config_ClearCrashDumpRequest_init_zero {0}
 #define domes_config_ClearCrashDumpResponse_init_zero {0}
+#define domes_config_CoreDumpReadRequest_init_zero {0}
+#define domes_config_CoreDumpReadResponse_init_zero {0, 0, ""}
+#define domes_config_CoreDumpChunk_init_zero     {0, {0, {0}}}
+#define domes_config_CoreDumpComplete_init_zero  {0}
+#define domes_config_ClearCoreDumpRequest_init_zero {0}
+#define domes_config_ClearCoreDumpResponse_init_zero {0}
 #define domes_config_HeapSample_init_zero        {0, 0, 0, 0}
 #define domes_config_GetMemoryProfileRequest_init_zero {0}
 #define domes_config_GetMemoryProfileResponse_init_zero {0, 0, 0, 0, 0, {domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero, domes_config_HeapSample_init_zero}}
@@ -600,6 +939,12 @@ extern "C" {
 #define domes_config_SimulateTouchResponse_init_zero {_domes_config_Status_MIN}
 #define domes_config_SetSimModeRequest_init_zero {0, 0, 0}
 #define domes_config_SetSimModeResponse_init_zero {_domes_config_Status_MIN, 0, 0, 0}
+#define domes_config_SubscribeEventsRequest_init_zero {0}
+#define domes_config_SubscribeEventsResponse_init_zero {0}
+#define domes_config_EventNotification_init_zero {_domes_config_EventCategory_MIN, 0, _domes_config_SystemMode_MIN, false, domes_config_LedPattern_init_zero, 0, false, domes_config_FeatureState_init_zero, 0}
+#define domes_config_BatchCommand_init_zero      {0, {0, {0}}}
+#define domes_config_BatchRequest_init_zero      {0, {domes_config_BatchCommand_init_zero, domes_config_BatchCommand_init_zero, domes_config_BatchCommand_init_zero}}
+#define domes_config_BatchResponse_init_zero     {0, {domes_config_BatchCommand_init_zero, domes_config_BatchCommand_init_zero, domes_config_BatchCommand_init_zero}}
 #define domes_config_ConfigRequest_init_zero     {0, {domes_config_ListFeaturesRequest_init_zero}}
 #define domes_config_ConfigResponse_init_zero    {_domes_config_Status_MIN, 0, {domes_config_ListFeaturesResponse_init_zero}}
 
@@ -627,7 +972,10 @@ extern "C" {
 #define domes_config_SetImuTriageResponse_enabled_tag 1
 #define domes_config_GetModeResponse_mode_tag    1
 #define domes_config_GetModeResponse_time_in_mode_ms_tag 2
+#define domes_config_GetModeResponse_previous_mode_tag 3
+#define domes_config_GetModeResponse_has_previous_mode_tag 4
 #define domes_config_SetModeRequest_mode_tag     1
+#define domes_config_SetModeRequest_force_tag    2
 #define domes_config_SetModeResponse_mode_tag    1
 #define domes_config_SetModeResponse_transition_ok_tag 2
 #define domes_config_GetSystemInfoResponse_firmware_version_tag 1
@@ -637,12 +985,49 @@ extern "C" {
 #define domes_config_GetSystemInfoResponse_mode_tag 5
 #define domes_config_GetSystemInfoResponse_feature_mask_tag 6
 #define domes_config_GetSystemInfoResponse_pod_id_tag 7
+#define domes_config_GetSystemInfoResponse_max_payload_tag 8
 #define domes_config_SetPodIdRequest_pod_id_tag  1
 #define domes_config_SetPodIdResponse_pod_id_tag 1
+#define domes_config_SetBaudRateRequest_baud_rate_tag 1
+#define domes_config_SetBaudRateResponse_baud_rate_tag 1
+#define domes_config_SetDeviceNameRequest_name_tag 1
+#define domes_config_SetDeviceNameResponse_name_tag 1
 #define domes_config_TaskHealth_name_tag         1
 #define domes_config_TaskHealth_stack_high_water_tag 2
 #define domes_config_TaskHealth_priority_tag     3
 #define domes_config_TaskHealth_core_tag         4
+#define domes_config_TaskInfo_name_tag           1
+#define domes_config_TaskInfo_state_tag          2
+#define domes_config_TaskInfo_priority_tag       3
+#define domes_config_TaskInfo_stack_watermark_bytes_tag 4
+#define domes_config_TaskInfo_runtime_percent_tag 5
+#define domes_config_TaskInfo_core_id_tag        6
+#define domes_config_TaskInfo_has_core_id_tag    7
+#define domes_config_GetTaskListResponse_tasks_tag 1
+#define domes_config_PartitionInfo_name_tag      1
+#define domes_config_PartitionInfo_type_tag      2
+#define domes_config_PartitionInfo_subtype_tag   3
+#define domes_config_PartitionInfo_offset_tag    4
+#define domes_config_PartitionInfo_size_tag      5
+#define domes_config_PartitionInfo_encrypted_tag 6
+#define domes_config_PartitionInfo_active_tag    7
+#define domes_config_GetPartitionTableResponse_partitions_tag 1
+#define domes_config_WifiApConfigRequest_ssid_tag 1
+#define domes_config_WifiApConfigRequest_password_tag 2
+#define domes_config_WifiApConfigRequest_channel_tag 3
+#define domes_config_WifiApConfigRequest_max_connections_tag 4
+#define domes_config_WifiApConfigResponse_ssid_tag 1
+#define domes_config_WifiApConfigResponse_channel_tag 2
+#define domes_config_WifiApConfigResponse_max_connections_tag 3
+#define domes_config_WifiApConfigResponse_secured_tag 4
+#define domes_config_WifiApConfigResponse_ip_tag 5
+#define domes_config_WifiApClient_mac_tag        1
+#define domes_config_WifiApClient_rssi_tag       2
+#define domes_config_WifiApStatusResponse_active_tag 1
+#define domes_config_WifiApStatusResponse_ssid_tag 2
+#define domes_config_WifiApStatusResponse_channel_tag 3
+#define domes_config_WifiApStatusResponse_ip_tag 4
+#define domes_config_WifiApStatusResponse_clients_tag 5
 #define domes_config_GetHealthResponse_free_heap_tag 1
 #define domes_config_GetHealthResponse_min_free_heap_tag 2
 #define domes_config_GetHealthResponse_uptime_seconds_tag 3
@@ -676,6 +1061,13 @@ extern "C" {
 #define domes_config_CrashDumpResponse_backtrace_tag 6
 #define domes_config_CrashDumpResponse_timestamp_tag 7
 #define domes_config_ClearCrashDumpResponse_cleared_tag 1
+#define domes_config_CoreDumpReadResponse_valid_tag 1
+#define domes_config_CoreDumpReadResponse_size_tag 2
+#define domes_config_CoreDumpReadResponse_reset_reason_tag 3
+#define domes_config_CoreDumpChunk_offset_tag    1
+#define domes_config_CoreDumpChunk_data_tag      2
+#define domes_config_CoreDumpComplete_checksum_tag 1
+#define domes_config_ClearCoreDumpResponse_erased_tag 1
 #define domes_config_HeapSample_timestamp_s_tag  1
 #define domes_config_HeapSample_free_heap_tag    2
 #define domes_config_HeapSample_largest_block_tag 3
@@ -707,6 +1099,19 @@ extern "C" {
 #define domes_config_SetSimModeResponse_enabled_tag 2
 #define domes_config_SetSimModeResponse_delay_ms_tag 3
 #define domes_config_SetSimModeResponse_pad_index_tag 4
+#define domes_config_SubscribeEventsRequest_categories_tag 1
+#define domes_config_SubscribeEventsResponse_categories_tag 1
+#define domes_config_EventNotification_category_tag 1
+#define domes_config_EventNotification_timestamp_ms_tag 2
+#define domes_config_EventNotification_mode_tag  3
+#define domes_config_EventNotification_led_pattern_tag 4
+#define domes_config_EventNotification_wifi_connected_tag 5
+#define domes_config_EventNotification_feature_tag 6
+#define domes_config_EventNotification_pad_index_tag 7
+#define domes_config_BatchCommand_msg_type_tag   1
+#define domes_config_BatchCommand_payload_tag    2
+#define domes_config_BatchRequest_commands_tag   1
+#define domes_config_BatchResponse_responses_tag 1
 #define domes_config_ConfigRequest_list_features_tag 1
 #define domes_config_ConfigRequest_set_feature_tag 2
 #define domes_config_ConfigResponse_status_tag   1
@@ -803,12 +1208,15 @@ X(a, STATIC,   SINGULAR, BOOL,     enabled,           1)
 
 #define domes_config_GetModeResponse_FIELDLIST(X, a) \
 X(a, STATIC,   SINGULAR, UENUM,    mode,              1) \
-X(a, STATIC,   SINGULAR, UINT32,   time_in_mode_ms,   2)
+X(a, STATIC,   SINGULAR, UINT32,   time_in_mode_ms,   2) \
+X(a, STATIC,   SINGULAR, UENUM,    previous_mode,     3) \
+X(a, STATIC,   SINGULAR, BOOL,     has_previous_mode,   4)
 #define domes_config_GetModeResponse_CALLBACK NULL
 #define domes_config_GetModeResponse_DEFAULT NULL
 
 #define domes_config_SetModeRequest_FIELDLIST(X, a) \
-X(a, STATIC,   SINGULAR, UENUM,    mode,              1)
+X(a, STATIC,   SINGULAR, UENUM,    mode,              1) \
+X(a, STATIC,   SINGULAR, BOOL,     force,             2)
 #define domes_config_SetModeRequest_CALLBACK NULL
 #define domes_config_SetModeRequest_DEFAULT NULL
 
@@ -830,7 +1238,8 @@ X(a, STATIC,   SINGULAR, UINT32,   free_heap,         3) \
 X(a, STATIC,   SINGULAR, UINT32,   boot_count,        4) \
 X(a, STATIC,   SINGULAR, UENUM,    mode,              5) \
 X(a, STATIC,   SINGULAR, UINT32,   feature_mask,      6) \
-X(a, STATIC,   SINGULAR, UINT32,   pod_id,            7)
+X(a, STATIC,   SINGULAR, UINT32,   pod_id,            7) \
+X(a, STATIC,   SINGULAR, UINT32,   max_payload,       8)
 #define domes_config_GetSystemInfoResponse_CALLBACK NULL
 #define domes_config_GetSystemInfoResponse_DEFAULT NULL
 
@@ -844,6 +1253,26 @@ X(a, STATIC,   SINGULAR, UINT32,   pod_id,            1)
 #define domes_config_SetPodIdResponse_CALLBACK NULL
 #define domes_config_SetPodIdResponse_DEFAULT NULL
 
+#define domes_config_SetBaudRateRequest_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, UINT32,   baud_rate,         1)
+#define domes_config_SetBaudRateRequest_CALLBACK NULL
+#define domes_config_SetBaudRateRequest_DEFAULT NULL
+
+#define domes_config_SetBaudRateResponse_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, UINT32,   baud_rate,         1)
+#define domes_config_SetBaudRateResponse_CALLBACK NULL
+#define domes_config_SetBaudRateResponse_DEFAULT NULL
+
+#define domes_config_SetDeviceNameRequest_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, STRING,   name,              1)
+#define domes_config_SetDeviceNameRequest_CALLBACK NULL
+#define domes_config_SetDeviceNameRequest_DEFAULT NULL
+
+#define domes_config_SetDeviceNameResponse_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, STRING,   name,              1)
+#define domes_config_SetDeviceNameResponse_CALLBACK NULL
+#define domes_config_SetDeviceNameResponse_DEFAULT NULL
+
 #define domes_config_TaskHealth_FIELDLIST(X, a) \
 X(a, STATIC,   SINGULAR, STRING,   name,              1) \
 X(a, STATIC,   SINGULAR, UINT32,   stack_high_water,   2) \
@@ -852,6 +1281,88 @@ X(a, STATIC,   SINGULAR, UINT32,   core,              4)
 #define domes_config_TaskHealth_CALLBACK NULL
 #define domes_config_TaskHealth_DEFAULT NULL
 
+#define domes_config_TaskInfo_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, STRING,   name,              1) \
+X(a, STATIC,   SINGULAR, UENUM,    state,             2) \
+X(a, STATIC,   SINGULAR, UINT32,   priority,          3) \
+X(a, STATIC,   SINGULAR, UINT32,   stack_watermark_bytes,   4) \
+X(a, STATIC,   SINGULAR, FLOAT,    runtime_percent,   5) \
+X(a, STATIC,   SINGULAR, UINT32,   core_id,           6) \
+X(a, STATIC,   SINGULAR, BOOL,     has_core_id,       7)
+#define domes_config_TaskInfo_CALLBACK NULL
+#define domes_config_TaskInfo_DEFAULT NULL
+
+#define domes_config_GetTaskListRequest_FIELDLIST(X, a) \
+
+#define domes_config_GetTaskListRequest_CALLBACK NULL
+#define domes_config_GetTaskListRequest_DEFAULT NULL
+
+#define domes_config_GetTaskListResponse_FIELDLIST(X, a) \
+X(a, STATIC,   REPEATED, MESSAGE,  tasks,             1)
+#define domes_config_GetTaskListResponse_CALLBACK NULL
+#define domes_config_GetTaskListResponse_DEFAULT NULL
+#define domes_config_GetTaskListResponse_tasks_MSGTYPE domes_config_TaskInfo
+
+#define domes_config_PartitionInfo_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, STRING,   name,              1) \
+X(a, STATIC,   SINGULAR, UENUM,    type,              2) \
+X(a, STATIC,   SINGULAR, UINT32,   subtype,           3) \
+X(a, STATIC,   SINGULAR, UINT32,   offset,            4) \
+X(a, STATIC,   SINGULAR, UINT32,   size,              5) \
+X(a, STATIC,   SINGULAR, BOOL,     encrypted,         6) \
+X(a, STATIC,   SINGULAR, BOOL,     active,            7)
+#define domes_config_PartitionInfo_CALLBACK NULL
+#define domes_config_PartitionInfo_DEFAULT NULL
+
+#define domes_config_GetPartitionTableRequest_FIELDLIST(X, a) \
+
+#define domes_config_GetPartitionTableRequest_CALLBACK NULL
+#define domes_config_GetPartitionTableRequest_DEFAULT NULL
+
+#define domes_config_GetPartitionTableResponse_FIELDLIST(X, a) \
+X(a, STATIC,   REPEATED, MESSAGE,  partitions,        1)
+#define domes_config_GetPartitionTableResponse_CALLBACK NULL
+#define domes_config_GetPartitionTableResponse_DEFAULT NULL
+#define domes_config_GetPartitionTableResponse_partitions_MSGTYPE domes_config_PartitionInfo
+
+#define domes_config_WifiApConfigRequest_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, STRING,   ssid,              1) \
+X(a, STATIC,   SINGULAR, STRING,   password,          2) \
+X(a, STATIC,   SINGULAR, UINT32,   channel,           3) \
+X(a, STATIC,   SINGULAR, UINT32,   max_connections,   4)
+#define domes_config_WifiApConfigRequest_CALLBACK NULL
+#define domes_config_WifiApConfigRequest_DEFAULT NULL
+
+#define domes_config_WifiApConfigResponse_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, STRING,   ssid,              1) \
+X(a, STATIC,   SINGULAR, UINT32,   channel,           2) \
+X(a, STATIC,   SINGULAR, UINT32,   max_connections,   3) \
+X(a, STATIC,   SINGULAR, BOOL,     secured,           4) \
+X(a, STATIC,   SINGULAR, FIXED32,  ip,                5)
+#define domes_config_WifiApConfigResponse_CALLBACK NULL
+#define domes_config_WifiApConfigResponse_DEFAULT NULL
+
+#define domes_config_WifiApStatusRequest_FIELDLIST(X, a) \
+
+#define domes_config_WifiApStatusRequest_CALLBACK NULL
+#define domes_config_WifiApStatusRequest_DEFAULT NULL
+
+#define domes_config_WifiApClient_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, BYTES,    mac,               1) \
+X(a, STATIC,   SINGULAR, INT32,    rssi,              2)
+#define domes_config_WifiApClient_CALLBACK NULL
+#define domes_config_WifiApClient_DEFAULT NULL
+
+#define domes_config_WifiApStatusResponse_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, BOOL,     active,            1) \
+X(a, STATIC,   SINGULAR, STRING,   ssid,              2) \
+X(a, STATIC,   SINGULAR, UINT32,   channel,           3) \
+X(a, STATIC,   SINGULAR, FIXED32,  ip,                4) \
+X(a, STATIC,   REPEATED, MESSAGE,  clients,           5)
+#define domes_config_WifiApStatusResponse_CALLBACK NULL
+#define domes_config_WifiApStatusResponse_DEFAULT NULL
+#define domes_config_WifiApStatusResponse_clients_MSGTYPE domes_config_WifiApClient
+
 #define domes_config_GetHealthRequest_FIELDLIST(X, a) \
 
 #define domes_config_GetHealthRequest_CALLBACK NULL
@@ -935,6 +1446,39 @@ X(a, STATIC,   SINGULAR, BOOL,     cleared,           1)
 #define domes_config_ClearCrashDumpResponse_CALLBACK NULL
 #define domes_config_ClearCrashDumpResponse_DEFAULT NULL
 
+#define domes_config_CoreDumpReadRequest_FIELDLIST(X, a) \
+
+#define domes_config_CoreDumpReadRequest_CALLBACK NULL
+#define domes_config_CoreDumpReadRequest_DEFAULT NULL
+
+#define domes_config_CoreDumpReadResponse_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, BOOL,     valid,             1) \
+X(a, STATIC,   SINGULAR, UINT32,   size,              2) \
+X(a, STATIC,   SINGULAR, STRING,   reset_reason,      3)
+#define domes_config_CoreDumpReadResponse_CALLBACK NULL
+#define domes_config_CoreDumpReadResponse_DEFAULT NULL
+
+#define domes_config_CoreDumpChunk_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, UINT32,   offset,            1) \
+X(a, STATIC,   SINGULAR, BYTES,    data,              2)
+#define domes_config_CoreDumpChunk_CALLBACK NULL
+#define domes_config_CoreDumpChunk_DEFAULT NULL
+
+#define domes_config_CoreDumpComplete_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, UINT32,   checksum,          1)
+#define domes_config_CoreDumpComplete_CALLBACK NULL
+#define domes_config_CoreDumpComplete_DEFAULT NULL
+
+#define domes_config_ClearCoreDumpRequest_FIELDLIST(X, a) \
+
+#define domes_config_ClearCoreDumpRequest_CALLBACK NULL
+#define domes_config_ClearCoreDumpRequest_DEFAULT NULL
+
+#define domes_config_ClearCoreDumpResponse_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, BOOL,     erased,            1)
+#define domes_config_ClearCoreDumpResponse_CALLBACK NULL
+#define domes_config_ClearCoreDumpResponse_DEFAULT NULL
+
 #define domes_config_HeapSample_FIELDLIST(X, a) \
 X(a, STATIC,   SINGULAR, UINT32,   timestamp_s,       1) \
 X(a, STATIC,   SINGULAR, UINT32,   free_heap,         2) \
@@ -1027,6 +1571,47 @@ X(a, STATIC,   SINGULAR, UINT32,   pad_index,         4)
 #define domes_config_SetSimModeResponse_CALLBACK NULL
 #define domes_config_SetSimModeResponse_DEFAULT NULL
 
+#define domes_config_SubscribeEventsRequest_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, UINT32,   categories,        1)
+#define domes_config_SubscribeEventsRequest_CALLBACK NULL
+#define domes_config_SubscribeEventsRequest_DEFAULT NULL
+
+#define domes_config_SubscribeEventsResponse_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, UINT32,   categories,        1)
+#define domes_config_SubscribeEventsResponse_CALLBACK NULL
+#define domes_config_SubscribeEventsResponse_DEFAULT NULL
+
+#define domes_config_EventNotification_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, UENUM,    category,          1) \
+X(a, STATIC,   SINGULAR, UINT32,   timestamp_ms,      2) \
+X(a, STATIC,   SINGULAR, UENUM,    mode,              3) \
+X(a, STATIC,   OPTIONAL, MESSAGE,  led_pattern,       4) \
+X(a, STATIC,   SINGULAR, BOOL,     wifi_connected,    5) \
+X(a, STATIC,   OPTIONAL, MESSAGE,  feature,           6) \
+X(a, STATIC,   SINGULAR, UINT32,   pad_index,         7)
+#define domes_config_EventNotification_CALLBACK NULL
+#define domes_config_EventNotification_DEFAULT NULL
+#define domes_config_EventNotification_led_pattern_MSGTYPE domes_config_LedPattern
+#define domes_config_EventNotification_feature_MSGTYPE domes_config_FeatureState
+
+#define domes_config_BatchCommand_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, UINT32,   msg_type,          1) \
+X(a, STATIC,   SINGULAR, BYTES,    payload,           2)
+#define domes_config_BatchCommand_CALLBACK NULL
+#define domes_config_BatchCommand_DEFAULT NULL
+
+#define domes_config_BatchRequest_FIELDLIST(X, a) \
+X(a, STATIC,   REPEATED, MESSAGE,  commands,          1)
+#define domes_config_BatchRequest_CALLBACK NULL
+#define domes_config_BatchRequest_DEFAULT NULL
+#define domes_config_BatchRequest_commands_MSGTYPE domes_config_BatchCommand
+
+#define domes_config_BatchResponse_FIELDLIST(X, a) \
+X(a, STATIC,   REPEATED, MESSAGE,  responses,         1)
+#define domes_config_BatchResponse_CALLBACK NULL
+#define domes_config_BatchResponse_DEFAULT NULL
+#define domes_config_BatchResponse_responses_MSGTYPE domes_config_BatchCommand
+
 #define domes_config_ConfigRequest_FIELDLIST(X, a) \
 X(a, STATIC,   ONEOF,    MESSAGE,  (request,list_features,request.list_features),   1) \
 X(a, STATIC,   ONEOF,    MESSAGE,  (request,set_feature,request.set_feature),   2)
@@ -1065,7 +1650,22 @@ extern const pb_msgdesc_t domes_config_GetSystemInfoRequest_msg;
 extern const pb_msgdesc_t domes_config_GetSystemInfoResponse_msg;
 extern const pb_msgdesc_t domes_config_SetPodIdRequest_msg;
 extern const pb_msgdesc_t domes_config_SetPodIdResponse_msg;
+extern const pb_msgdesc_t domes_config_SetBaudRateRequest_msg;
+extern const pb_msgdesc_t domes_config_SetBaudRateResponse_msg;
+extern const pb_msgdesc_t domes_config_SetDeviceNameRequest_msg;
+extern const pb_msgdesc_t domes_config_SetDeviceNameResponse_msg;
 extern const pb_msgdesc_t domes_config_TaskHealth_msg;
+extern const pb_msgdesc_t domes_config_TaskInfo_msg;
+extern const pb_msgdesc_t domes_config_GetTaskListRequest_msg;
+extern const pb_msgdesc_t domes_config_GetTaskListResponse_msg;
+extern const pb_msgdesc_t domes_config_PartitionInfo_msg;
+extern const pb_msgdesc_t domes_config_GetPartitionTableRequest_msg;
+extern const pb_msgdesc_t domes_config_GetPartitionTableResponse_msg;
+extern const pb_msgdesc_t domes_config_WifiApConfigRequest_msg;
+extern const pb_msgdesc_t domes_config_WifiApConfigResponse_msg;
+extern const pb_msgdesc_t domes_config_WifiApStatusRequest_msg;
+extern const pb_msgdesc_t domes_config_WifiApClient_msg;
+extern const pb_msgdesc_t domes_config_WifiApStatusResponse_msg;
 extern const pb_msgdesc_t domes_config_GetHealthRequest_msg;
 extern const pb_msgdesc_t domes_config_GetHealthResponse_msg;
 extern const pb_msgdesc_t domes_config_EspNowPeer_msg;
@@ -1077,6 +1677,12 @@ extern const pb_msgdesc_t domes_config_GetCrashDumpRequest_msg;
 extern const pb_msgdesc_t domes_config_CrashDumpResponse_msg;
 extern const pb_msgdesc_t domes_config_ClearCrashDumpRequest_msg;
 extern const pb_msgdesc_t domes_config_ClearCrashDumpResponse_msg;
+extern const pb_msgdesc_t domes_config_CoreDumpReadRequest_msg;
+extern const pb_msgdesc_t domes_config_CoreDumpReadResponse_msg;
+extern const pb_msgdesc_t domes_config_CoreDumpChunk_msg;
+extern const pb_msgdesc_t domes_config_CoreDumpComplete_msg;
+extern const pb_msgdesc_t domes_config_ClearCoreDumpRequest_msg;
+extern const pb_msgdesc_t domes_config_ClearCoreDumpResponse_msg;
 extern const pb_msgdesc_t domes_config_HeapSample_msg;
 extern const pb_msgdesc_t domes_config_GetMemoryProfileRequest_msg;
 extern const pb_msgdesc_t domes_config_GetMemoryProfileResponse_msg;
@@ -1091,6 +1697,12 @@ extern const pb_msgdesc_t domes_config_SimulateTouchRequest_msg;
 extern const pb_msgdesc_t domes_config_SimulateTouchResponse_msg;
 extern const pb_msgdesc_t domes_config_SetSimModeRequest_msg;
 extern const pb_msgdesc_t domes_config_SetSimModeResponse_msg;
+extern const pb_msgdesc_t domes_config_SubscribeEventsRequest_msg;
+extern const pb_msgdesc_t domes_config_SubscribeEventsResponse_msg;
+extern const pb_msgdesc_t domes_config_EventNotification_msg;
+extern const pb_msgdesc_t domes_config_BatchCommand_msg;
+extern const pb_msgdesc_t domes_config_BatchRequest_msg;
+extern const pb_msgdesc_t domes_config_BatchResponse_msg;
 extern const pb_msgdesc_t domes_config_ConfigRequest_msg;
 extern const pb_msgdesc_t domes_config_ConfigResponse_msg;
 
@@ -1116,7 +1728,22 @@ extern const pb_msgdesc_t domes_config_ConfigResponse_msg;
 #define domes_config_GetSystemInfoResponse_fields &domes_config_GetSystemInfoResponse_msg
 #define domes_config_SetPodIdRequest_fields &domes_config_SetPodIdRequest_msg
 #define domes_config_SetPodIdResponse_fields &domes_config_SetPodIdResponse_msg
+#define domes_config_SetBaudRateRequest_fields &domes_config_SetBaudRateRequest_msg
+#define domes_config_SetBaudRateResponse_fields &domes_config_SetBaudRateResponse_msg
+#define domes_config_SetDeviceNameRequest_fields &domes_config_SetDeviceNameRequest_msg
+#define domes_config_SetDeviceNameResponse_fields &domes_config_SetDeviceNameResponse_msg
 #define domes_config_TaskHealth_fields &domes_config_TaskHealth_msg
+#define domes_config_TaskInfo_fields &domes_config_TaskInfo_msg
+#define domes_config_GetTaskListRequest_fields &domes_config_GetTaskListRequest_msg
+#define domes_config_GetTaskListResponse_fields &domes_config_GetTaskListResponse_msg
+#define domes_config_PartitionInfo_fields &domes_config_PartitionInfo_msg
+#define domes_config_GetPartitionTableRequest_fields &domes_config_GetPartitionTableRequest_msg
+#define domes_config_GetPartitionTableResponse_fields &domes_config_GetPartitionTableResponse_msg
+#define domes_config_WifiApConfigRequest_fields &domes_config_WifiApConfigRequest_msg
+#define domes_config_WifiApConfigResponse_fields &domes_config_WifiApConfigResponse_msg
+#define domes_config_WifiApStatusRequest_fields &domes_config_WifiApStatusRequest_msg
+#define domes_config_WifiApClient_fields &domes_config_WifiApClient_msg
+#define domes_config_WifiApStatusResponse_fields &domes_config_WifiApStatusResponse_msg
 #define domes_config_GetHealthRequest_fields &domes_config_GetHealthRequest_msg
 #define domes_config_GetHealthResponse_fields &domes_config_GetHealthResponse_msg
 #define domes_config_EspNowPeer_fields &domes_config_EspNowPeer_msg
@@ -1128,6 +1755,12 @@ extern const pb_msgdesc_t domes_config_ConfigResponse_msg;
 #define domes_config_CrashDumpResponse_fields &domes_config_CrashDumpResponse_msg
 #define domes_config_ClearCrashDumpRequest_fields &domes_config_ClearCrashDumpRequest_msg
 #define domes_config_ClearCrashDumpResponse_fields &domes_config_ClearCrashDumpResponse_msg
+#define domes_config_CoreDumpReadRequest_fields &domes_config_CoreDumpReadRequest_msg
+#define domes_config_CoreDumpReadResponse_fields &domes_config_CoreDumpReadResponse_msg
+#define domes_config_CoreDumpChunk_fields &domes_config_CoreDumpChunk_msg
+#define domes_config_CoreDumpComplete_fields &domes_config_CoreDumpComplete_msg
+#define domes_config_ClearCoreDumpRequest_fields &domes_config_ClearCoreDumpRequest_msg
+#define domes_config_ClearCoreDumpResponse_fields &domes_config_ClearCoreDumpResponse_msg
 #define domes_config_HeapSample_fields &domes_config_HeapSample_msg
 #define domes_config_GetMemoryProfileRequest_fields &domes_config_GetMemoryProfileRequest_msg
 #define domes_config_GetMemoryProfileResponse_fields &domes_config_GetMemoryProfileResponse_msg
@@ -1142,22 +1775,38 @@ extern const pb_msgdesc_t domes_config_ConfigResponse_msg;
 #define domes_config_SimulateTouchResponse_fields &domes_config_SimulateTouchResponse_msg
 #define domes_config_SetSimModeRequest_fields &domes_config_SetSimModeRequest_msg
 #define domes_config_SetSimModeResponse_fields &domes_config_SetSimModeResponse_msg
+#define domes_config_SubscribeEventsRequest_fields &domes_config_SubscribeEventsRequest_msg
+#define domes_config_SubscribeEventsResponse_fields &domes_config_SubscribeEventsResponse_msg
+#define domes_config_EventNotification_fields &domes_config_EventNotification_msg
+#define domes_config_BatchCommand_fields &domes_config_BatchCommand_msg
+#define domes_config_BatchRequest_fields &domes_config_BatchRequest_msg
+#define domes_config_BatchResponse_fields &domes_config_BatchResponse_msg
 #define domes_config_ConfigRequest_fields &domes_config_ConfigRequest_msg
 #define domes_config_ConfigResponse_fields &domes_config_ConfigResponse_msg
 
 /* Maximum encoded size of messages (where known) */
 #define DOMES_CONFIG_CONFIG_PB_H_MAX_SIZE        domes_config_GetMemoryProfileResponse_size
+#define domes_config_BatchCommand_size           265
+#define domes_config_BatchRequest_size           804
+#define domes_config_BatchResponse_size          804
 #define domes_config_CheckUpdateRequest_size     0
 #define domes_config_CheckUpdateResponse_size    44
+#define domes_config_ClearCoreDumpRequest_size   0
+#define domes_config_ClearCoreDumpResponse_size  2
 #define domes_config_ClearCrashDumpRequest_size  0
 #define domes_config_ClearCrashDumpResponse_size 2
 #define domes_config_Color_size                  24
 #define domes_config_ConfigRequest_size          6
 #define domes_config_ConfigResponse_size         106
+#define domes_config_CoreDumpChunk_size          521
+#define domes_config_CoreDumpComplete_size       6
+#define domes_config_CoreDumpReadRequest_size    0
+#define domes_config_CoreDumpReadResponse_size   41
 #define domes_config_CrashDumpResponse_size      198
 #define domes_config_EspNowBenchRequest_size     6
 #define domes_config_EspNowBenchResponse_size    48
 #define domes_config_EspNowPeer_size             25
+#define domes_config_EventNotification_size      275
 #define domes_config_FeatureState_size           4
 #define domes_config_GetCrashDumpRequest_size    0
 #define domes_config_GetEspNowStatusRequest_size 0
@@ -1169,25 +1818,34 @@ extern const pb_msgdesc_t domes_config_ConfigResponse_msg;
 #define domes_config_GetMemoryProfileRequest_size 0
 #define domes_config_GetMemoryProfileResponse_size 1584
 #define domes_config_GetModeRequest_size         0
-#define domes_config_GetModeResponse_size        8
+#define domes_config_GetModeResponse_size        12
+#define domes_config_GetPartitionTableRequest_size 0
+#define domes_config_GetPartitionTableResponse_size 704
 #define domes_config_GetSystemInfoRequest_size   0
-#define domes_config_GetSystemInfoResponse_size  65
+#define domes_config_GetSystemInfoResponse_size  71
+#define domes_config_GetTaskListRequest_size     0
+#define domes_config_GetTaskListResponse_size    1012
 #define domes_config_HeapSample_size             24
 #define domes_config_LedPattern_size             248
 #define domes_config_ListFeaturesRequest_size    0
 #define domes_config_ListFeaturesResponse_size   102
+#define domes_config_PartitionInfo_size          42
 #define domes_config_SelfTestRequest_size        0
 #define domes_config_SelfTestResponse_size       712
 #define domes_config_SelfTestResult_size         68
 #define domes_config_SetAutoUpdateRequest_size   2
 #define domes_config_SetAutoUpdateResponse_size  2
+#define domes_config_SetBaudRateRequest_size     6
+#define domes_config_SetBaudRateResponse_size    6
+#define domes_config_SetDeviceNameRequest_size   22
+#define domes_config_SetDeviceNameResponse_size  22
 #define domes_config_SetFeatureRequest_size      4
 #define domes_config_SetFeatureResponse_size     6
 #define domes_config_SetImuTriageRequest_size    2
 #define domes_config_SetImuTriageResponse_size   2
 #define domes_config_SetLedPatternRequest_size   251
 #define domes_config_SetLedPatternResponse_size  251
-#define domes_config_SetModeRequest_size         2
+#define domes_config_SetModeRequest_size         4
 #define domes_config_SetModeResponse_size        4
 #define domes_config_SetPodIdRequest_size        6
 #define domes_config_SetPodIdResponse_size       6
@@ -1195,7 +1853,15 @@ extern const pb_msgdesc_t domes_config_ConfigResponse_msg;
 #define domes_config_SetSimModeResponse_size     16
 #define domes_config_SimulateTouchRequest_size   6
 #define domes_config_SimulateTouchResponse_size  2
+#define domes_config_SubscribeEventsRequest_size 6
+#define domes_config_SubscribeEventsResponse_size 6
 #define domes_config_TaskHealth_size             35
+#define domes_config_TaskInfo_size               44
+#define domes_config_WifiApClient_size           19
+#define domes_config_WifiApConfigRequest_size    111
+#define domes_config_WifiApConfigResponse_size   53
+#define domes_config_WifiApStatusRequest_size    0
+#define domes_config_WifiApStatusResponse_size   257
 
 #ifdef __cplusplus
 } /* extern "C" */
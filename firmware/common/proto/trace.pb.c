@@ -12,6 +12,9 @@ PB_BIND(domes_trace_Event, domes_trace_Event, AUTO)
 PB_BIND(domes_trace_TaskEntry, domes_trace_TaskEntry, AUTO)
 
 
+PB_BIND(domes_trace_AnnotationEntry, domes_trace_AnnotationEntry, AUTO)
+
+
 PB_BIND(domes_trace_TraceSessionInfo, domes_trace_TraceSessionInfo, 2)
 
 
@@ -27,6 +30,21 @@ PB_BIND(domes_trace_TraceStatusResponse, domes_trace_TraceStatusResponse, AUTO)
 PB_BIND(domes_trace_AckResponse, domes_trace_AckResponse, AUTO)
 
 
+PB_BIND(domes_trace_DumpRequest, domes_trace_DumpRequest, AUTO)
+
+
+PB_BIND(domes_trace_SetBufferSizeRequest, domes_trace_SetBufferSizeRequest, AUTO)
+
+
+PB_BIND(domes_trace_SetBufferSizeResponse, domes_trace_SetBufferSizeResponse, AUTO)
+
+
+PB_BIND(domes_trace_AnnotateRequest, domes_trace_AnnotateRequest, AUTO)
+
+
+PB_BIND(domes_trace_AnnotateResponse, domes_trace_AnnotateResponse, AUTO)
+
+
 PB_BIND(domes_trace_StreamConfig, domes_trace_StreamConfig, AUTO)
 
 
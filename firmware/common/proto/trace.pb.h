@@ -16,7 +16,7 @@ typedef enum _domes_trace_MsgType {
     domes_trace_MsgType_MSG_TYPE_UNKNOWN = 0,
     domes_trace_MsgType_MSG_TYPE_START = 16, /* Start trace recording (host -> device, empty payload) */
     domes_trace_MsgType_MSG_TYPE_STOP = 17, /* Stop trace recording (host -> device, empty payload) */
-    domes_trace_MsgType_MSG_TYPE_DUMP = 18, /* Request trace dump (host -> device, empty payload) */
+    domes_trace_MsgType_MSG_TYPE_DUMP = 18, /* Request trace dump (host -> device, DumpRequest or empty) */
     domes_trace_MsgType_MSG_TYPE_DATA = 19, /* Trace data chunk (device -> host, TraceDataChunk) */
     domes_trace_MsgType_MSG_TYPE_END = 20, /* End of trace dump (device -> host, TraceDumpComplete) */
     domes_trace_MsgType_MSG_TYPE_CLEAR = 21, /* Clear trace buffer (host -> device, empty payload) */
@@ -25,7 +25,11 @@ typedef enum _domes_trace_MsgType {
     domes_trace_MsgType_MSG_TYPE_STREAM_CFG = 24, /* Configure streaming (host -> device, StreamConfig) */
     domes_trace_MsgType_MSG_TYPE_STREAM_DATA = 25, /* Streamed event batch (device -> host, StreamBatch) */
     domes_trace_MsgType_MSG_TYPE_SESSION_INFO = 26, /* Session metadata (device -> host, TraceSessionInfo) */
-    domes_trace_MsgType_MSG_TYPE_ACK = 27 /* Acknowledge command (device -> host, AckResponse) */
+    domes_trace_MsgType_MSG_TYPE_ACK = 27, /* Acknowledge command (device -> host, AckResponse) */
+    domes_trace_MsgType_MSG_TYPE_SET_BUFFER_SIZE_REQ = 28, /* Resize ring buffer (host -> device, SetBufferSizeRequest) */
+    domes_trace_MsgType_MSG_TYPE_SET_BUFFER_SIZE_RSP = 29, /* Resize result (device -> host, SetBufferSizeResponse) */
+    domes_trace_MsgType_MSG_TYPE_ANNOTATE_REQ = 30, /* Record a user marker (host -> device, AnnotateRequest) */
+    domes_trace_MsgType_MSG_TYPE_ANNOTATE_RSP = 31 /* Marker recorded (device -> host, AnnotateResponse) */
 } domes_trace_MsgType;
 
 /* Status codes for trace ACK responses */
@@ -35,6 +39,7 @@ typedef enum _domes_trace_Status {
     domes_trace_Status_STATUS_ALREADY_ON = 2, /* Tracing already enabled */
     domes_trace_Status_STATUS_ALREADY_OFF = 3, /* Tracing already disabled */
     domes_trace_Status_STATUS_BUFFER_EMPTY = 4, /* No events to dump */
+    domes_trace_Status_STATUS_NO_MEMORY = 5, /* Not enough heap to allocate the requested buffer */
     domes_trace_Status_STATUS_ERROR = 255 /* Generic error */
 } domes_trace_Status;
 
@@ -101,6 +106,12 @@ typedef struct _domes_trace_TaskEntry {
     char name[16]; /* Max 16 chars */
 } domes_trace_TaskEntry;
 
+/* User marker label (hash -> label), like TaskEntry for task names */
+typedef struct _domes_trace_AnnotationEntry {
+    uint32_t hash; /* FNV-1a hash of the label (the marker event's arg1) */
+    char label[33]; /* Max 32 chars */
+} domes_trace_AnnotationEntry;
+
 /* Session metadata sent at start of dump (MsgType: SESSION_INFO 0x1A)
  Replaces the old binary TraceMetadata struct. */
 typedef struct _domes_trace_TraceSessionInfo {
@@ -113,6 +124,8 @@ typedef struct _domes_trace_TraceSessionInfo {
     domes_trace_TaskEntry tasks[32]; /* Registered task names */
     uint32_t buffer_size_bytes; /* Ring buffer size */
     int64_t clock_offset_us; /* Offset from master clock (multi-pod sync) */
+    pb_size_t annotations_count;
+    domes_trace_AnnotationEntry annotations[4]; /* Labels of recorded user markers */
 } domes_trace_TraceSessionInfo;
 
 typedef PB_BYTES_ARRAY_T(256) domes_trace_TraceDataChunk_events_t;
@@ -127,7 +140,7 @@ typedef struct _domes_trace_TraceDataChunk {
 /* End-of-dump marker (MsgType: END 0x14) */
 typedef struct _domes_trace_TraceDumpComplete {
     uint32_t total_events;
-    uint32_t checksum; /* Simple byte-sum checksum of all event data */
+    uint32_t checksum; /* CRC32 (IEEE) over all event bytes, in send order */
 } domes_trace_TraceDumpComplete;
 
 /* Trace status response (MsgType: STATUS_RESP 0x17) */
@@ -146,6 +159,42 @@ typedef struct _domes_trace_AckResponse {
     domes_trace_Status status;
 } domes_trace_AckResponse;
 
+/* Dump request (MsgType: DUMP 0x12, host -> device)
+ An empty payload (all fields zero) dumps every buffered event. */
+typedef struct _domes_trace_DumpRequest {
+    uint32_t since_us; /* Only dump events with timestamp > since_us (0 = all) */
+} domes_trace_DumpRequest;
+
+/* Resize the trace ring buffer (MsgType: SET_BUFFER_SIZE_REQ 0x1C)
+ The firmware reallocates the buffer, discarding any recorded events. */
+typedef struct _domes_trace_SetBufferSizeRequest {
+    uint32_t event_count; /* Requested capacity in events */
+} domes_trace_SetBufferSizeRequest;
+
+/* Resize result (MsgType: SET_BUFFER_SIZE_RSP 0x1D)
+ allocated_count may be lower than requested (alignment or heap limits). */
+typedef struct _domes_trace_SetBufferSizeResponse {
+    domes_trace_Status status;
+    uint32_t allocated_count; /* Actual capacity in events */
+    uint32_t bytes_used; /* Heap bytes used by the buffer */
+    bool was_cleared; /* Existing events were discarded */
+} domes_trace_SetBufferSizeResponse;
+
+/* Record a user marker (MsgType: ANNOTATE_REQ 0x1E)
+ The firmware records an INSTANT event in CATEGORY_USER with
+ arg1 = FNV-1a hash of the label and arg2 = the marker number, and lists
+ the label in the next TraceSessionInfo so the dump can name it. */
+typedef struct _domes_trace_AnnotateRequest {
+    char label[33]; /* Max 32 chars */
+} domes_trace_AnnotateRequest;
+
+/* Marker recorded (MsgType: ANNOTATE_RSP 0x1F) */
+typedef struct _domes_trace_AnnotateResponse {
+    domes_trace_Status status;
+    uint32_t marker; /* Marker number, counting from 1 since the last clear */
+    uint32_t timestamp_us; /* Timestamp of the recorded event */
+} domes_trace_AnnotateResponse;
+
 /* Configure real-time streaming (MsgType: STREAM_CFG 0x18, host -> device) */
 typedef struct _domes_trace_StreamConfig {
     bool enable; /* true = start streaming, false = stop */
@@ -200,8 +249,8 @@ extern "C" {
 
 /* Helper constants for enums */
 #define _domes_trace_MsgType_MIN domes_trace_MsgType_MSG_TYPE_UNKNOWN
-#define _domes_trace_MsgType_MAX domes_trace_MsgType_MSG_TYPE_ACK
-#define _domes_trace_MsgType_ARRAYSIZE ((domes_trace_MsgType)(domes_trace_MsgType_MSG_TYPE_ACK+1))
+#define _domes_trace_MsgType_MAX domes_trace_MsgType_MSG_TYPE_ANNOTATE_RSP
+#define _domes_trace_MsgType_ARRAYSIZE ((domes_trace_MsgType)(domes_trace_MsgType_MSG_TYPE_ANNOTATE_RSP+1))
 
 #define _domes_trace_Status_MIN domes_trace_Status_STATUS_OK
 #define _domes_trace_Status_MAX domes_trace_Status_STATUS_ERROR
@@ -223,10 +272,18 @@ extern "C" {
 
 
 
+
 #define domes_trace_AckResponse_status_ENUMTYPE domes_trace_Status
 
 
 
+#define domes_trace_SetBufferSizeResponse_status_ENUMTYPE domes_trace_Status
+
+
+#define domes_trace_AnnotateResponse_status_ENUMTYPE domes_trace_Status
+
+
+
 
 
 
@@ -235,11 +292,17 @@ extern "C" {
 /* Initializer values for message structs */
 #define domes_trace_Event_init_default           {0, 0, _domes_trace_EventType_MIN, _domes_trace_Category_MIN, 0, 0}
 #define domes_trace_TaskEntry_init_default       {0, ""}
-#define domes_trace_TraceSessionInfo_init_default {0, 0, 0, 0, 0, 0, {domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default}, 0, 0}
+#define domes_trace_AnnotationEntry_init_default {0, ""}
+#define domes_trace_TraceSessionInfo_init_default {0, 0, 0, 0, 0, 0, {domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default}, 0, 0, 0, {domes_trace_AnnotationEntry_init_default, domes_trace_AnnotationEntry_init_default, domes_trace_AnnotationEntry_init_default, domes_trace_AnnotationEntry_init_default}}
 #define domes_trace_TraceDataChunk_init_default  {0, 0, {0, {0}}}
 #define domes_trace_TraceDumpComplete_init_default {0, 0}
 #define domes_trace_TraceStatusResponse_init_default {0, 0, 0, 0, 0, 0, 0}
 #define domes_trace_AckResponse_init_default     {_domes_trace_Status_MIN}
+#define domes_trace_DumpRequest_init_default     {0}
+#define domes_trace_SetBufferSizeRequest_init_default {0}
+#define domes_trace_SetBufferSizeResponse_init_default {_domes_trace_Status_MIN, 0, 0, 0}
+#define domes_trace_AnnotateRequest_init_default {""}
+#define domes_trace_AnnotateResponse_init_default {_domes_trace_Status_MIN, 0, 0}
 #define domes_trace_StreamConfig_init_default    {0, 0, 0, 0}
 #define domes_trace_StreamBatch_init_default     {0, 0, {0, {0}}}
 #define domes_trace_Metadata_init_default        {0, 0, 0, 0, 0, {domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default, domes_trace_TaskEntry_init_default}}
@@ -248,11 +311,17 @@ extern "C" {
 #define domes_trace_StatusResponse_init_default  {0, 0, 0, 0, 0}
 #define domes_trace_Event_init_zero              {0, 0, _domes_trace_EventType_MIN, _domes_trace_Category_MIN, 0, 0}
 #define domes_trace_TaskEntry_init_zero          {0, ""}
-#define domes_trace_TraceSessionInfo_init_zero   {0, 0, 0, 0, 0, 0, {domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero}, 0, 0}
+#define domes_trace_AnnotationEntry_init_zero    {0, ""}
+#define domes_trace_TraceSessionInfo_init_zero   {0, 0, 0, 0, 0, 0, {domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero}, 0, 0, 0, {domes_trace_AnnotationEntry_init_zero, domes_trace_AnnotationEntry_init_zero, domes_trace_AnnotationEntry_init_zero, domes_trace_AnnotationEntry_init_zero}}
 #define domes_trace_TraceDataChunk_init_zero     {0, 0, {0, {0}}}
 #define domes_trace_TraceDumpComplete_init_zero  {0, 0}
 #define domes_trace_TraceStatusResponse_init_zero {0, 0, 0, 0, 0, 0, 0}
 #define domes_trace_AckResponse_init_zero        {_domes_trace_Status_MIN}
+#define domes_trace_DumpRequest_init_zero        {0}
+#define domes_trace_SetBufferSizeRequest_init_zero {0}
+#define domes_trace_SetBufferSizeResponse_init_zero {_domes_trace_Status_MIN, 0, 0, 0}
+#define domes_trace_AnnotateRequest_init_zero    {""}
+#define domes_trace_AnnotateResponse_init_zero   {_domes_trace_Status_MIN, 0, 0}
 #define domes_trace_StreamConfig_init_zero       {0, 0, 0, 0}
 #define domes_trace_StreamBatch_init_zero        {0, 0, {0, {0}}}
 #define domes_trace_Metadata_init_zero           {0, 0, 0, 0, 0, {domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero, domes_trace_TaskEntry_init_zero}}
@@ -269,6 +338,8 @@ extern "C" {
 #define domes_trace_Event_arg2_tag               6
 #define domes_trace_TaskEntry_task_id_tag        1
 #define domes_trace_TaskEntry_name_tag           2
+#define domes_trace_AnnotationEntry_hash_tag     1
+#define domes_trace_AnnotationEntry_label_tag    2
 #define domes_trace_TraceSessionInfo_pod_id_tag  1
 #define domes_trace_TraceSessionInfo_event_count_tag 2
 #define domes_trace_TraceSessionInfo_dropped_count_tag 3
@@ -277,6 +348,7 @@ extern "C" {
 #define domes_trace_TraceSessionInfo_tasks_tag   6
 #define domes_trace_TraceSessionInfo_buffer_size_bytes_tag 7
 #define domes_trace_TraceSessionInfo_clock_offset_us_tag 8
+#define domes_trace_TraceSessionInfo_annotations_tag 9
 #define domes_trace_TraceDataChunk_offset_tag    1
 #define domes_trace_TraceDataChunk_count_tag     2
 #define domes_trace_TraceDataChunk_events_tag    3
@@ -290,6 +362,16 @@ extern "C" {
 #define domes_trace_TraceStatusResponse_buffer_size_tag 6
 #define domes_trace_TraceStatusResponse_stream_category_mask_tag 7
 #define domes_trace_AckResponse_status_tag       1
+#define domes_trace_DumpRequest_since_us_tag     1
+#define domes_trace_SetBufferSizeRequest_event_count_tag 1
+#define domes_trace_SetBufferSizeResponse_status_tag 1
+#define domes_trace_SetBufferSizeResponse_allocated_count_tag 2
+#define domes_trace_SetBufferSizeResponse_bytes_used_tag 3
+#define domes_trace_SetBufferSizeResponse_was_cleared_tag 4
+#define domes_trace_AnnotateRequest_label_tag    1
+#define domes_trace_AnnotateResponse_status_tag  1
+#define domes_trace_AnnotateResponse_marker_tag  2
+#define domes_trace_AnnotateResponse_timestamp_us_tag 3
 #define domes_trace_StreamConfig_enable_tag      1
 #define domes_trace_StreamConfig_category_mask_tag 2
 #define domes_trace_StreamConfig_max_rate_hz_tag 3
@@ -329,6 +411,12 @@ X(a, STATIC,   SINGULAR, STRING,   name,              2)
 #define domes_trace_TaskEntry_CALLBACK NULL
 #define domes_trace_TaskEntry_DEFAULT NULL
 
+#define domes_trace_AnnotationEntry_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, UINT32,   hash,              1) \
+X(a, STATIC,   SINGULAR, STRING,   label,             2)
+#define domes_trace_AnnotationEntry_CALLBACK NULL
+#define domes_trace_AnnotationEntry_DEFAULT NULL
+
 #define domes_trace_TraceSessionInfo_FIELDLIST(X, a) \
 X(a, STATIC,   SINGULAR, UINT32,   pod_id,            1) \
 X(a, STATIC,   SINGULAR, UINT32,   event_count,       2) \
@@ -337,10 +425,12 @@ X(a, STATIC,   SINGULAR, UINT32,   start_timestamp_us,   4) \
 X(a, STATIC,   SINGULAR, UINT32,   end_timestamp_us,   5) \
 X(a, STATIC,   REPEATED, MESSAGE,  tasks,             6) \
 X(a, STATIC,   SINGULAR, UINT32,   buffer_size_bytes,   7) \
-X(a, STATIC,   SINGULAR, INT64,    clock_offset_us,   8)
+X(a, STATIC,   SINGULAR, INT64,    clock_offset_us,   8) \
+X(a, STATIC,   REPEATED, MESSAGE,  annotations,       9)
 #define domes_trace_TraceSessionInfo_CALLBACK NULL
 #define domes_trace_TraceSessionInfo_DEFAULT NULL
 #define domes_trace_TraceSessionInfo_tasks_MSGTYPE domes_trace_TaskEntry
+#define domes_trace_TraceSessionInfo_annotations_MSGTYPE domes_trace_AnnotationEntry
 
 #define domes_trace_TraceDataChunk_FIELDLIST(X, a) \
 X(a, STATIC,   SINGULAR, UINT32,   offset,            1) \
@@ -371,6 +461,36 @@ X(a, STATIC,   SINGULAR, UENUM,    status,            1)
 #define domes_trace_AckResponse_CALLBACK NULL
 #define domes_trace_AckResponse_DEFAULT NULL
 
+#define domes_trace_DumpRequest_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, UINT32,   since_us,          1)
+#define domes_trace_DumpRequest_CALLBACK NULL
+#define domes_trace_DumpRequest_DEFAULT NULL
+
+#define domes_trace_SetBufferSizeRequest_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, UINT32,   event_count,       1)
+#define domes_trace_SetBufferSizeRequest_CALLBACK NULL
+#define domes_trace_SetBufferSizeRequest_DEFAULT NULL
+
+#define domes_trace_SetBufferSizeResponse_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, UENUM,    status,            1) \
+X(a, STATIC,   SINGULAR, UINT32,   allocated_count,   2) \
+X(a, STATIC,   SINGULAR, UINT32,   bytes_used,        3) \
+X(a, STATIC,   SINGULAR, BOOL,     was_cleared,       4)
+#define domes_trace_SetBufferSizeResponse_CALLBACK NULL
+#define domes_trace_SetBufferSizeResponse_DEFAULT NULL
+
+#define domes_trace_AnnotateRequest_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, STRING,   label,             1)
+#define domes_trace_AnnotateRequest_CALLBACK NULL
+#define domes_trace_AnnotateRequest_DEFAULT NULL
+
+#define domes_trace_AnnotateResponse_FIELDLIST(X, a) \
+X(a, STATIC,   SINGULAR, UENUM,    status,            1) \
+X(a, STATIC,   SINGULAR, UINT32,   marker,            2) \
+X(a, STATIC,   SINGULAR, UINT32,   timestamp_us,      3)
+#define domes_trace_AnnotateResponse_CALLBACK NULL
+#define domes_trace_AnnotateResponse_DEFAULT NULL
+
 #define domes_trace_StreamConfig_FIELDLIST(X, a) \
 X(a, STATIC,   SINGULAR, BOOL,     enable,            1) \
 X(a, STATIC,   SINGULAR, UINT32,   category_mask,     2) \
@@ -419,11 +539,17 @@ X(a, STATIC,   SINGULAR, UINT32,   buffer_size,       5)
 
 extern const pb_msgdesc_t domes_trace_Event_msg;
 extern const pb_msgdesc_t domes_trace_TaskEntry_msg;
+extern const pb_msgdesc_t domes_trace_AnnotationEntry_msg;
 extern const pb_msgdesc_t domes_trace_TraceSessionInfo_msg;
 extern const pb_msgdesc_t domes_trace_TraceDataChunk_msg;
 extern const pb_msgdesc_t domes_trace_TraceDumpComplete_msg;
 extern const pb_msgdesc_t domes_trace_TraceStatusResponse_msg;
 extern const pb_msgdesc_t domes_trace_AckResponse_msg;
+extern const pb_msgdesc_t domes_trace_DumpRequest_msg;
+extern const pb_msgdesc_t domes_trace_SetBufferSizeRequest_msg;
+extern const pb_msgdesc_t domes_trace_SetBufferSizeResponse_msg;
+extern const pb_msgdesc_t domes_trace_AnnotateRequest_msg;
+extern const pb_msgdesc_t domes_trace_AnnotateResponse_msg;
 extern const pb_msgdesc_t domes_trace_StreamConfig_msg;
 extern const pb_msgdesc_t domes_trace_StreamBatch_msg;
 extern const pb_msgdesc_t domes_trace_Metadata_msg;
@@ -434,11 +560,17 @@ extern const pb_msgdesc_t domes_trace_StatusResponse_msg;
 /* Defines for backwards compatibility with code written before nanopb-0.4.0 */
 #define domes_trace_Event_fields &domes_trace_Event_msg
 #define domes_trace_TaskEntry_fields &domes_trace_TaskEntry_msg
+#define domes_trace_AnnotationEntry_fields &domes_trace_AnnotationEntry_msg
 #define domes_trace_TraceSessionInfo_fields &domes_trace_TraceSessionInfo_msg
 #define domes_trace_TraceDataChunk_fields &domes_trace_TraceDataChunk_msg
 #define domes_trace_TraceDumpComplete_fields &domes_trace_TraceDumpComplete_msg
 #define domes_trace_TraceStatusResponse_fields &domes_trace_TraceStatusResponse_msg
 #define domes_trace_AckResponse_fields &domes_trace_AckResponse_msg
+#define domes_trace_DumpRequest_fields &domes_trace_DumpRequest_msg
+#define domes_trace_SetBufferSizeRequest_fields &domes_trace_SetBufferSizeRequest_msg
+#define domes_trace_SetBufferSizeResponse_fields &domes_trace_SetBufferSizeResponse_msg
+#define domes_trace_AnnotateRequest_fields &domes_trace_AnnotateRequest_msg
+#define domes_trace_AnnotateResponse_fields &domes_trace_AnnotateResponse_msg
 #define domes_trace_StreamConfig_fields &domes_trace_StreamConfig_msg
 #define domes_trace_StreamBatch_fields &domes_trace_StreamBatch_msg
 #define domes_trace_Metadata_fields &domes_trace_Metadata_msg
@@ -449,17 +581,23 @@ extern const pb_msgdesc_t domes_trace_StatusResponse_msg;
 /* Maximum encoded size of messages (where known) */
 #define DOMES_TRACE_TRACE_PB_H_MAX_SIZE          domes_trace_StreamBatch_size
 #define domes_trace_AckResponse_size             3
+#define domes_trace_AnnotateRequest_size         34
+#define domes_trace_AnnotateResponse_size        15
+#define domes_trace_AnnotationEntry_size         40
 #define domes_trace_DataHeader_size              12
 #define domes_trace_DumpEnd_size                 12
+#define domes_trace_DumpRequest_size             6
 #define domes_trace_Event_size                   28
 #define domes_trace_Metadata_size                824
+#define domes_trace_SetBufferSizeRequest_size    6
+#define domes_trace_SetBufferSizeResponse_size   17
 #define domes_trace_StatusResponse_size          22
 #define domes_trace_StreamBatch_size             1039
 #define domes_trace_StreamConfig_size            16
 #define domes_trace_TaskEntry_size               23
 #define domes_trace_TraceDataChunk_size          271
 #define domes_trace_TraceDumpComplete_size       12
-#define domes_trace_TraceSessionInfo_size        847
+#define domes_trace_TraceSessionInfo_size        1015
 #define domes_trace_TraceStatusResponse_size     30
 
 #ifdef __cplusplus
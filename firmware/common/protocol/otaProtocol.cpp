@@ -90,6 +90,19 @@ TransportError serializeOtaEnd(uint8_t* buf, size_t bufSize, size_t* outLen) {
     return TransportError::kOk;
 }
 
+TransportError serializeOtaEnd(uint8_t flags, uint8_t* buf, size_t bufSize, size_t* outLen) {
+    if (buf == nullptr || outLen == nullptr) {
+        return TransportError::kInvalidArg;
+    }
+    if (bufSize < 1) {
+        return TransportError::kInvalidArg;
+    }
+
+    buf[0] = flags;
+    *outLen = 1;
+    return TransportError::kOk;
+}
+
 TransportError serializeOtaAck(OtaStatus status, uint32_t nextOffset, uint8_t* buf, size_t bufSize,
                                size_t* outLen) {
     constexpr size_t payloadSize = sizeof(OtaAckPayload);
@@ -184,6 +197,22 @@ TransportError deserializeOtaData(const uint8_t* payload, size_t payloadLen, uin
     return TransportError::kOk;
 }
 
+TransportError deserializeOtaEnd(const uint8_t* payload, size_t payloadLen, uint8_t* flags) {
+    if (flags == nullptr) {
+        return TransportError::kInvalidArg;
+    }
+    if (payloadLen == 0) {
+        *flags = 0;
+        return TransportError::kOk;
+    }
+    if (payload == nullptr) {
+        return TransportError::kInvalidArg;
+    }
+
+    *flags = payload[0];
+    return TransportError::kOk;
+}
+
 TransportError deserializeOtaAck(const uint8_t* payload, size_t payloadLen, OtaStatus* status,
                                  uint32_t* nextOffset) {
     constexpr size_t expectedSize = sizeof(OtaAckPayload);
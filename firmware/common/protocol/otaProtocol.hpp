@@ -13,8 +13,8 @@
  *   ESP32 → Host: OTA_ACK (status=OK, nextOffset=1024)
  *   Host → ESP32: OTA_DATA (offset=1024, data[1024..2047])
  *   ...
- *   Host → ESP32: OTA_END
- *   ESP32 → Host: OTA_ACK (status=OK) → reboot
+ *   Host → ESP32: OTA_END [flags]
+ *   ESP32 → Host: OTA_ACK (status=OK) → reboot (unless kOtaEndNoReboot is set)
  *
  * On error:
  *   ESP32 → Host: OTA_ABORT (reason)
@@ -38,6 +38,10 @@ constexpr size_t kOtaVersionMaxLen = 32;
 /// SHA256 hash size in bytes
 constexpr size_t kSha256Size = 32;
 
+/// OTA_END flag: set the boot partition but don't reboot into it yet.
+/// The new image runs on the next reboot, whenever that happens.
+constexpr uint8_t kOtaEndNoReboot = 0x01;
+
 /**
  * @brief OTA message types
  */
@@ -144,6 +148,17 @@ TransportError serializeOtaData(uint32_t offset, const uint8_t* data, size_t dat
  */
 TransportError serializeOtaEnd(uint8_t* buf, size_t bufSize, size_t* outLen);
 
+/**
+ * @brief Serialize OTA_END message with flags
+ *
+ * @param flags Bitmask of kOtaEnd* flags
+ * @param buf Output buffer
+ * @param bufSize Buffer size
+ * @param outLen [out] Bytes written (1)
+ * @return TransportError::kOk on success
+ */
+TransportError serializeOtaEnd(uint8_t flags, uint8_t* buf, size_t bufSize, size_t* outLen);
+
 /**
  * @brief Serialize OTA_ACK message
  *
@@ -200,6 +215,18 @@ TransportError deserializeOtaBegin(const uint8_t* payload, size_t payloadLen,
 TransportError deserializeOtaData(const uint8_t* payload, size_t payloadLen, uint32_t* offset,
                                   const uint8_t** data, size_t* dataLen);
 
+/**
+ * @brief Deserialize OTA_END payload
+ *
+ * Older hosts send OTA_END without a payload, which reads as no flags.
+ *
+ * @param payload Raw payload bytes (may be null if payloadLen is 0)
+ * @param payloadLen Payload length
+ * @param flags [out] Bitmask of kOtaEnd* flags
+ * @return TransportError::kOk on success
+ */
+TransportError deserializeOtaEnd(const uint8_t* payload, size_t payloadLen, uint8_t* flags);
+
 /**
  * @brief Deserialize OTA_ACK payload
  *
@@ -21,13 +21,20 @@
 #include "interfaces/iOtaManager.hpp"
 #include "transport/espNowTransport.hpp"
 #include "services/espNowService.hpp"
+#include "services/wifiAccessPoint.hpp"
+#include "services/wifiStaticIp.hpp"
+#include "utils/crc32.hpp"
 
 #include "config.pb.h"
 #include "pb_encode.h"
 #include "pb_decode.h"
 
+#include "esp_core_dump.h"
+#include "esp_flash.h"
 #include "esp_heap_caps.h"
 #include "esp_log.h"
+#include "esp_ota_ops.h"
+#include "esp_partition.h"
 #include "esp_system.h"
 #include "esp_timer.h"
 #include "esp_wifi.h"
@@ -35,7 +42,10 @@
 #include "freertos/FreeRTOS.h"
 #include "freertos/task.h"
 
+#include <algorithm>
 #include <array>
+#include <memory>
+#include <new>
 #include <cstring>
 
 // Version string from CMake
@@ -54,6 +64,47 @@ uint8_t readPodIdFromNvs() {
     config.close();
     return id;
 }
+
+/// Event categories the firmware can report
+constexpr uint32_t kAllEventCategories =
+    domes_config_EventCategory_EVENT_CATEGORY_MODE |
+    domes_config_EventCategory_EVENT_CATEGORY_LED |
+    domes_config_EventCategory_EVENT_CATEGORY_WIFI |
+    domes_config_EventCategory_EVENT_CATEGORY_FEATURE |
+    domes_config_EventCategory_EVENT_CATEGORY_TOUCH;
+
+bool sameColor(const domes_config_Color& a, const domes_config_Color& b) {
+    return a.r == b.r && a.g == b.g && a.b == b.b && a.w == b.w;
+}
+
+bool sameLedPattern(const domes_config_LedPattern& a, const domes_config_LedPattern& b) {
+    if (a.type != b.type || a.period_ms != b.period_ms || a.brightness != b.brightness ||
+        a.has_color != b.has_color || !sameColor(a.color, b.color) ||
+        a.colors_count != b.colors_count) {
+        return false;
+    }
+    for (pb_size_t i = 0; i < a.colors_count; ++i) {
+        if (!sameColor(a.colors[i], b.colors[i])) return false;
+    }
+    return true;
+}
+
+/// Short name for the reason of the current boot
+const char* resetReasonName(esp_reset_reason_t reason) {
+    switch (reason) {
+        case ESP_RST_POWERON:   return "power-on";
+        case ESP_RST_EXT:       return "external";
+        case ESP_RST_SW:        return "software";
+        case ESP_RST_PANIC:     return "panic";
+        case ESP_RST_INT_WDT:   return "interrupt-watchdog";
+        case ESP_RST_TASK_WDT:  return "task-watchdog";
+        case ESP_RST_WDT:       return "watchdog";
+        case ESP_RST_DEEPSLEEP: return "deep-sleep";
+        case ESP_RST_BROWNOUT:  return "brownout";
+        case ESP_RST_SDIO:      return "sdio";
+        default:                return "unknown";
+    }
+}
 }
 
 namespace domes::config {
@@ -126,11 +177,31 @@ bool ConfigCommandHandler::handleCommand(uint8_t type, const uint8_t* payload, s
             handleSetPodId(payload, len);
             return true;
 
+        case MsgType::kSetBaudRateReq:
+            ESP_LOGD(kTag, "Received SET_BAUD_RATE");
+            handleSetBaudRate(payload, len);
+            return true;
+
+        case MsgType::kSetDeviceNameReq:
+            ESP_LOGD(kTag, "Received SET_DEVICE_NAME");
+            handleSetDeviceName(payload, len);
+            return true;
+
         case MsgType::kGetHealthReq:
             ESP_LOGD(kTag, "Received GET_HEALTH");
             handleGetHealth();
             return true;
 
+        case MsgType::kGetTaskListReq:
+            ESP_LOGD(kTag, "Received GET_TASK_LIST");
+            handleGetTaskList();
+            return true;
+
+        case MsgType::kGetPartitionTableReq:
+            ESP_LOGD(kTag, "Received GET_PARTITION_TABLE");
+            handleGetPartitionTable();
+            return true;
+
         case MsgType::kGetEspNowStatusReq:
             ESP_LOGD(kTag, "Received GET_ESPNOW_STATUS");
             handleGetEspNowStatus();
@@ -151,6 +222,31 @@ bool ConfigCommandHandler::handleCommand(uint8_t type, const uint8_t* payload, s
             handleClearCrashDump();
             return true;
 
+        case MsgType::kCoreDumpReadReq:
+            ESP_LOGD(kTag, "Received CORE_DUMP_READ");
+            handleCoreDumpRead();
+            return true;
+
+        case MsgType::kCoreDumpClearReq:
+            ESP_LOGD(kTag, "Received CORE_DUMP_CLEAR");
+            handleCoreDumpClear();
+            return true;
+
+        case MsgType::kWifiApConfigReq:
+            ESP_LOGD(kTag, "Received WIFI_AP_CONFIG");
+            handleWifiApConfig(payload, len);
+            return true;
+
+        case MsgType::kWifiApStatusReq:
+            ESP_LOGD(kTag, "Received WIFI_AP_STATUS");
+            handleWifiApStatus();
+            return true;
+
+        case MsgType::kWifiStaticIpReq:
+            ESP_LOGD(kTag, "Received WIFI_STATIC_IP");
+            handleWifiStaticIp(payload, len);
+            return true;
+
         case MsgType::kGetMemoryProfileReq:
             ESP_LOGD(kTag, "Received GET_MEMORY_PROFILE");
             handleGetMemoryProfile();
@@ -181,6 +277,22 @@ bool ConfigCommandHandler::handleCommand(uint8_t type, const uint8_t* payload, s
             handleSetSimMode(payload, len);
             return true;
 
+        case MsgType::kSubscribeEventsReq:
+            ESP_LOGD(kTag, "Received SUBSCRIBE_EVENTS");
+            handleSubscribeEvents(payload, len);
+            return true;
+
+        case MsgType::kPingReq:
+            // Keepalive: the activity timer reset above is the point; reply empty
+            ESP_LOGD(kTag, "Received PING");
+            sendFrame(MsgType::kPingRsp, nullptr, 0);
+            return true;
+
+        case MsgType::kBatchReq:
+            ESP_LOGD(kTag, "Received BATCH");
+            handleBatch(payload, len);
+            return true;
+
         default:
             ESP_LOGW(kTag, "Unknown config command: 0x%02X", type);
             return false;
@@ -327,6 +439,10 @@ void ConfigCommandHandler::sendGetFeatureResponse(
 }
 
 bool ConfigCommandHandler::sendFrame(MsgType type, const uint8_t* payload, size_t len) {
+    if (batchCapture_) {
+        return captureBatchResponse(type, payload, len);
+    }
+
     std::array<uint8_t, kMaxFrameSize> frameBuf;
     size_t frameLen = 0;
 
@@ -484,6 +600,11 @@ void ConfigCommandHandler::handleGetMode() {
     domes_config_GetModeResponse resp = domes_config_GetModeResponse_init_zero;
     resp.mode = static_cast<domes_config_SystemMode>(modeManager_->currentMode());
     resp.time_in_mode_ms = modeManager_->timeInModeMs();
+    SystemMode previous;
+    if (modeManager_->previousMode(previous)) {
+        resp.has_previous_mode = true;
+        resp.previous_mode = static_cast<domes_config_SystemMode>(previous);
+    }
 
     // Encode to buffer: [status_byte][protobuf]
     std::array<uint8_t, domes_config_GetModeResponse_size + 10> payload;
@@ -520,9 +641,11 @@ void ConfigCommandHandler::handleSetMode(const uint8_t* payload, size_t len) {
     }
 
     auto targetMode = static_cast<SystemMode>(req.mode);
-    ESP_LOGI(kTag, "Set mode request: %s", systemModeToString(targetMode));
+    ESP_LOGI(kTag, "Set mode request: %s%s", systemModeToString(targetMode),
+             req.force ? " (forced)" : "");
 
-    bool ok = modeManager_->transitionTo(targetMode);
+    bool ok = req.force ? modeManager_->forceTransitionTo(targetMode)
+                        : modeManager_->transitionTo(targetMode);
 
     // Build response
     domes_config_SetModeResponse resp = domes_config_SetModeResponse_init_zero;
@@ -567,6 +690,9 @@ void ConfigCommandHandler::handleGetSystemInfo() {
     // Pod identity
     resp.pod_id = readPodIdFromNvs();
 
+    // Largest payload the frame decoder accepts, so hosts can size requests
+    resp.max_payload = static_cast<uint32_t>(kMaxPayloadSize);
+
     // Encode to buffer: [status_byte][protobuf]
     std::array<uint8_t, domes_config_GetSystemInfoResponse_size + 10> payload;
     payload[0] = static_cast<uint8_t>(Status::kOk);
@@ -632,6 +758,97 @@ void ConfigCommandHandler::handleSetPodId(const uint8_t* payload, size_t len) {
     sendFrame(MsgType::kSetPodIdRsp, respPayload.data(), 1 + ostream.bytes_written);
 }
 
+void ConfigCommandHandler::handleSetBaudRate(const uint8_t* payload, size_t len) {
+    domes_config_SetBaudRateRequest req = domes_config_SetBaudRateRequest_init_zero;
+    pb_istream_t stream = pb_istream_from_buffer(payload, len);
+
+    if (!pb_decode(&stream, domes_config_SetBaudRateRequest_fields, &req) || req.baud_rate == 0) {
+        ESP_LOGW(kTag, "Invalid SET_BAUD_RATE request");
+        std::array<uint8_t, 1> errPayload;
+        errPayload[0] = static_cast<uint8_t>(Status::kError);
+        sendFrame(MsgType::kSetBaudRateRsp, errPayload.data(), 1);
+        return;
+    }
+
+    // The serial link is the USB Serial/JTAG controller, which has no line
+    // rate: data moves at USB speed whatever rate the host sets. There is
+    // nothing to reconfigure, so the host's new rate simply works after the ACK.
+    ESP_LOGI(kTag, "Host switching to %lu baud", static_cast<unsigned long>(req.baud_rate));
+
+    domes_config_SetBaudRateResponse resp = domes_config_SetBaudRateResponse_init_zero;
+    resp.baud_rate = req.baud_rate;
+
+    std::array<uint8_t, domes_config_SetBaudRateResponse_size + 10> respPayload;
+    respPayload[0] = static_cast<uint8_t>(Status::kOk);
+
+    pb_ostream_t ostream = pb_ostream_from_buffer(respPayload.data() + 1, respPayload.size() - 1);
+    if (!pb_encode(&ostream, domes_config_SetBaudRateResponse_fields, &resp)) {
+        ESP_LOGE(kTag, "Failed to encode SetBaudRateResponse: %s", PB_GET_ERROR(&ostream));
+        return;
+    }
+
+    sendFrame(MsgType::kSetBaudRateRsp, respPayload.data(), 1 + ostream.bytes_written);
+}
+
+void ConfigCommandHandler::handleSetDeviceName(const uint8_t* payload, size_t len) {
+    domes_config_SetDeviceNameRequest req = domes_config_SetDeviceNameRequest_init_zero;
+    pb_istream_t stream = pb_istream_from_buffer(payload, len);
+
+    if (!pb_decode(&stream, domes_config_SetDeviceNameRequest_fields, &req)) {
+        ESP_LOGW(kTag, "Failed to decode SET_DEVICE_NAME: %s", PB_GET_ERROR(&stream));
+        std::array<uint8_t, 1> errPayload;
+        errPayload[0] = static_cast<uint8_t>(Status::kError);
+        sendFrame(MsgType::kSetDeviceNameRsp, errPayload.data(), 1);
+        return;
+    }
+
+    // Printable ASCII, 1-20 chars (nanopb already rejects anything longer than the buffer)
+    size_t nameLen = strnlen(req.name, sizeof(req.name));
+    bool valid = nameLen > 0 && nameLen <= kMaxDeviceNameLen;
+    for (size_t i = 0; valid && i < nameLen; ++i) {
+        valid = req.name[i] >= 0x20 && req.name[i] <= 0x7E;
+    }
+    if (!valid) {
+        ESP_LOGW(kTag, "Invalid device name (must be 1-%u printable ASCII chars)",
+                 static_cast<unsigned>(kMaxDeviceNameLen));
+        std::array<uint8_t, 1> errPayload;
+        errPayload[0] = static_cast<uint8_t>(Status::kError);
+        sendFrame(MsgType::kSetDeviceNameRsp, errPayload.data(), 1);
+        return;
+    }
+
+    // Write to NVS
+    infra::NvsConfig config;
+    if (config.open(infra::nvs_ns::kConfig) != ESP_OK ||
+        config.setBlob(infra::config_key::kDeviceName, req.name, nameLen) != ESP_OK ||
+        config.commit() != ESP_OK) {
+        ESP_LOGE(kTag, "Failed to write device name to NVS");
+        config.close();
+        std::array<uint8_t, 1> errPayload;
+        errPayload[0] = static_cast<uint8_t>(Status::kError);
+        sendFrame(MsgType::kSetDeviceNameRsp, errPayload.data(), 1);
+        return;
+    }
+    config.close();
+
+    ESP_LOGI(kTag, "Device name set to '%s' (reboot to apply BLE name change)", req.name);
+
+    // Send response
+    domes_config_SetDeviceNameResponse resp = domes_config_SetDeviceNameResponse_init_zero;
+    std::memcpy(resp.name, req.name, nameLen);
+
+    std::array<uint8_t, domes_config_SetDeviceNameResponse_size + 10> respPayload;
+    respPayload[0] = static_cast<uint8_t>(Status::kOk);
+
+    pb_ostream_t ostream = pb_ostream_from_buffer(respPayload.data() + 1, respPayload.size() - 1);
+    if (!pb_encode(&ostream, domes_config_SetDeviceNameResponse_fields, &resp)) {
+        ESP_LOGE(kTag, "Failed to encode SetDeviceNameResponse: %s", PB_GET_ERROR(&ostream));
+        return;
+    }
+
+    sendFrame(MsgType::kSetDeviceNameRsp, respPayload.data(), 1 + ostream.bytes_written);
+}
+
 void ConfigCommandHandler::handleGetHealth() {
     domes_config_GetHealthResponse resp = domes_config_GetHealthResponse_init_zero;
 
@@ -689,6 +906,152 @@ void ConfigCommandHandler::handleGetHealth() {
     sendFrame(MsgType::kGetHealthRsp, payload.data(), 1 + stream.bytes_written);
 }
 
+void ConfigCommandHandler::handleGetTaskList() {
+    constexpr UBaseType_t kMaxTasks = sizeof(domes_config_GetTaskListResponse::tasks) /
+                                      sizeof(domes_config_GetTaskListResponse::tasks[0]);
+    static_assert(domes_config_GetTaskListResponse_size <= kMaxPayloadSize - 1,
+                  "GetTaskListResponse must fit in one frame");
+
+    std::array<uint8_t, 1> errPayload;
+    errPayload[0] = static_cast<uint8_t>(Status::kError);
+
+    // A few spare slots cover tasks created between counting and the snapshot
+    constexpr UBaseType_t kSpareTasks = 4;
+    const UBaseType_t capacity = uxTaskGetNumberOfTasks() + kSpareTasks;
+
+    // The task statuses plus the response are too much for the task stack
+    std::unique_ptr<TaskStatus_t[]> taskStatuses(new (std::nothrow) TaskStatus_t[capacity]);
+    std::unique_ptr<domes_config_GetTaskListResponse> resp(
+        new (std::nothrow) domes_config_GetTaskListResponse());
+    std::unique_ptr<uint8_t[]> respPayload(new (std::nothrow) uint8_t[kMaxPayloadSize]);
+    if (!taskStatuses || !resp || !respPayload) {
+        ESP_LOGE(kTag, "Out of memory for GET_TASK_LIST");
+        sendFrame(MsgType::kGetTaskListRsp, errPayload.data(), 1);
+        return;
+    }
+
+    // uxTaskGetSystemState fills nothing if the array is smaller than the task count
+    configRUN_TIME_COUNTER_TYPE totalRunTime = 0;
+    UBaseType_t got = uxTaskGetSystemState(taskStatuses.get(), capacity, &totalRunTime);
+
+    // Only kMaxTasks fit in the response, so keep the busiest ones
+    if (got > kMaxTasks) {
+        ESP_LOGW(kTag, "%u tasks, listing the %u busiest", static_cast<unsigned>(got),
+                 static_cast<unsigned>(kMaxTasks));
+        std::partial_sort(taskStatuses.get(), taskStatuses.get() + kMaxTasks,
+                          taskStatuses.get() + got,
+                          [](const TaskStatus_t& a, const TaskStatus_t& b) {
+                              return a.ulRunTimeCounter > b.ulRunTimeCounter;
+                          });
+        got = kMaxTasks;
+    }
+
+    for (UBaseType_t i = 0; i < got; ++i) {
+        const TaskStatus_t& status = taskStatuses[i];
+        auto& t = resp->tasks[resp->tasks_count];
+        strncpy(t.name, status.pcTaskName, sizeof(t.name) - 1);
+        // TaskState is eTaskState shifted by one, so eInvalid maps to unknown
+        t.state = status.eCurrentState < eInvalid
+                      ? static_cast<domes_config_TaskState>(status.eCurrentState + 1)
+                      : domes_config_TaskState_TASK_STATE_UNKNOWN;
+        t.priority = status.uxCurrentPriority;
+        // ESP-IDF stacks are counted in bytes
+        t.stack_watermark_bytes = status.usStackHighWaterMark;
+        if (totalRunTime > 0) {
+            t.runtime_percent = 100.0f * static_cast<float>(status.ulRunTimeCounter) /
+                                static_cast<float>(totalRunTime);
+        }
+#if ( configUSE_CORE_AFFINITY == 1 ) && ( configNUMBER_OF_CORES > 1 )
+        auto mask = status.uxCoreAffinityMask;
+        if (mask == 0x01 || mask == 0x02) {
+            t.core_id = mask == 0x01 ? 0 : 1;
+            t.has_core_id = true;
+        }
+#endif
+        resp->tasks_count++;
+    }
+
+    respPayload[0] = static_cast<uint8_t>(Status::kOk);
+    pb_ostream_t stream = pb_ostream_from_buffer(respPayload.get() + 1, kMaxPayloadSize - 1);
+    if (!pb_encode(&stream, domes_config_GetTaskListResponse_fields, resp.get())) {
+        ESP_LOGE(kTag, "Failed to encode GetTaskListResponse: %s", PB_GET_ERROR(&stream));
+        sendFrame(MsgType::kGetTaskListRsp, errPayload.data(), 1);
+        return;
+    }
+
+    sendFrame(MsgType::kGetTaskListRsp, respPayload.get(), 1 + stream.bytes_written);
+}
+
+void ConfigCommandHandler::handleGetPartitionTable() {
+    domes_config_GetPartitionTableResponse resp = domes_config_GetPartitionTableResponse_init_zero;
+    constexpr pb_size_t kMaxPartitions = sizeof(resp.partitions) / sizeof(resp.partitions[0]);
+
+    const esp_partition_t* running = esp_ota_get_running_partition();
+
+    esp_partition_iterator_t it =
+        esp_partition_find(ESP_PARTITION_TYPE_ANY, ESP_PARTITION_SUBTYPE_ANY, nullptr);
+    for (; it != nullptr && resp.partitions_count < kMaxPartitions; it = esp_partition_next(it)) {
+        const esp_partition_t* part = esp_partition_get(it);
+        auto& p = resp.partitions[resp.partitions_count];
+        strncpy(p.name, part->label, sizeof(p.name) - 1);
+        p.subtype = part->subtype;
+        p.offset = part->address;
+        p.size = part->size;
+        p.encrypted = part->encrypted;
+        p.active = part == running;
+
+        if (part->type == ESP_PARTITION_TYPE_APP) {
+            p.type = domes_config_PartitionType_PARTITION_TYPE_APP;
+        } else if (part->type == ESP_PARTITION_TYPE_DATA) {
+            switch (part->subtype) {
+                case ESP_PARTITION_SUBTYPE_DATA_NVS:
+                    p.type = domes_config_PartitionType_PARTITION_TYPE_NVS;
+                    break;
+                case ESP_PARTITION_SUBTYPE_DATA_OTA:
+                    p.type = domes_config_PartitionType_PARTITION_TYPE_OTADATA;
+                    break;
+                case ESP_PARTITION_SUBTYPE_DATA_PHY:
+                    p.type = domes_config_PartitionType_PARTITION_TYPE_PHY;
+                    break;
+                case ESP_PARTITION_SUBTYPE_DATA_COREDUMP:
+                    p.type = domes_config_PartitionType_PARTITION_TYPE_COREDUMP;
+                    break;
+                case ESP_PARTITION_SUBTYPE_DATA_SPIFFS:
+                    p.type = domes_config_PartitionType_PARTITION_TYPE_SPIFFS;
+                    break;
+                case ESP_PARTITION_SUBTYPE_DATA_FAT:
+                    p.type = domes_config_PartitionType_PARTITION_TYPE_FAT;
+                    break;
+                default:
+                    p.type = domes_config_PartitionType_PARTITION_TYPE_DATA;
+                    break;
+            }
+        } else {
+            p.type = domes_config_PartitionType_PARTITION_TYPE_UNKNOWN;
+        }
+        resp.partitions_count++;
+    }
+    // esp_partition_next releases the iterator once it reaches the end
+    esp_partition_iterator_release(it);
+
+    // The partition table need not be sorted by address
+    std::sort(resp.partitions, resp.partitions + resp.partitions_count,
+              [](const domes_config_PartitionInfo& a, const domes_config_PartitionInfo& b) {
+                  return a.offset < b.offset;
+              });
+
+    std::array<uint8_t, domes_config_GetPartitionTableResponse_size + 10> payload;
+    payload[0] = static_cast<uint8_t>(Status::kOk);
+
+    pb_ostream_t stream = pb_ostream_from_buffer(payload.data() + 1, payload.size() - 1);
+    if (!pb_encode(&stream, domes_config_GetPartitionTableResponse_fields, &resp)) {
+        ESP_LOGE(kTag, "Failed to encode GetPartitionTableResponse: %s", PB_GET_ERROR(&stream));
+        return;
+    }
+
+    sendFrame(MsgType::kGetPartitionTableRsp, payload.data(), 1 + stream.bytes_written);
+}
+
 void ConfigCommandHandler::handleGetEspNowStatus() {
     domes_config_GetEspNowStatusResponse resp = domes_config_GetEspNowStatusResponse_init_zero;
 
@@ -872,6 +1235,258 @@ void ConfigCommandHandler::handleClearCrashDump() {
     sendFrame(MsgType::kClearCrashDumpRsp, payload.data(), 1 + stream.bytes_written);
 }
 
+// ============================================================================
+// Core dump handlers
+// ============================================================================
+
+void ConfigCommandHandler::handleCoreDumpRead() {
+    std::array<uint8_t, 1> errPayload;
+    errPayload[0] = static_cast<uint8_t>(Status::kError);
+
+    // A streamed dump can't be captured as one batch response
+    if (batchCapture_) {
+        ESP_LOGW(kTag, "CORE_DUMP_READ not allowed in a batch");
+        sendFrame(MsgType::kCoreDumpReadRsp, errPayload.data(), 1);
+        return;
+    }
+
+    domes_config_CoreDumpReadResponse resp = domes_config_CoreDumpReadResponse_init_zero;
+
+    size_t addr = 0;
+    size_t size = 0;
+    resp.valid = esp_core_dump_image_check() == ESP_OK &&
+                 esp_core_dump_image_get(&addr, &size) == ESP_OK;
+    if (resp.valid) {
+        resp.size = static_cast<uint32_t>(size);
+        // The dump was written by the panic that caused this boot
+        std::strncpy(resp.reset_reason, resetReasonName(esp_reset_reason()),
+                     sizeof(resp.reset_reason) - 1);
+    }
+
+    // Chunk and its encoding would take over 1KB of the task stack
+    std::unique_ptr<domes_config_CoreDumpChunk> chunk(
+        new (std::nothrow) domes_config_CoreDumpChunk());
+    std::unique_ptr<uint8_t[]> chunkPayload(
+        new (std::nothrow) uint8_t[domes_config_CoreDumpChunk_size]);
+    if (resp.valid && (!chunk || !chunkPayload)) {
+        ESP_LOGE(kTag, "Out of memory for CORE_DUMP_READ");
+        sendFrame(MsgType::kCoreDumpReadRsp, errPayload.data(), 1);
+        return;
+    }
+
+    std::array<uint8_t, domes_config_CoreDumpReadResponse_size + 10> respPayload;
+    respPayload[0] = static_cast<uint8_t>(Status::kOk);
+
+    pb_ostream_t ostream = pb_ostream_from_buffer(respPayload.data() + 1, respPayload.size() - 1);
+    if (!pb_encode(&ostream, domes_config_CoreDumpReadResponse_fields, &resp)) {
+        ESP_LOGE(kTag, "Failed to encode CoreDumpReadResponse: %s", PB_GET_ERROR(&ostream));
+        return;
+    }
+
+    sendFrame(MsgType::kCoreDumpReadRsp, respPayload.data(), 1 + ostream.bytes_written);
+
+    if (!resp.valid) {
+        return;
+    }
+
+    ESP_LOGI(kTag, "Streaming %zu-byte core dump from 0x%zx", size, addr);
+
+    // Suppress logging so ESP_LOG text can't corrupt frames on the shared serial port
+    esp_log_level_set("*", ESP_LOG_NONE);
+
+    uint32_t checksum = kCrc32Init;
+    size_t offset = 0;
+    while (offset < size) {
+        size_t len = std::min(size - offset, sizeof(chunk->data.bytes));
+        if (esp_flash_read(nullptr, chunk->data.bytes, addr + offset, len) != ESP_OK) {
+            // The host sees a short dump and reports it incomplete
+            break;
+        }
+        chunk->offset = static_cast<uint32_t>(offset);
+        chunk->data.size = static_cast<pb_size_t>(len);
+        checksum = crc32Update(chunk->data.bytes, len, checksum);
+
+        pb_ostream_t chunkStream =
+            pb_ostream_from_buffer(chunkPayload.get(), domes_config_CoreDumpChunk_size);
+        if (!pb_encode(&chunkStream, domes_config_CoreDumpChunk_fields, chunk.get())) {
+            break;
+        }
+        sendFrame(MsgType::kCoreDumpData, chunkPayload.get(), chunkStream.bytes_written);
+        vTaskDelay(pdMS_TO_TICKS(10));
+        offset += len;
+    }
+
+    domes_config_CoreDumpComplete end = domes_config_CoreDumpComplete_init_zero;
+    end.checksum = crc32Finalize(checksum);
+
+    std::array<uint8_t, domes_config_CoreDumpComplete_size> endPayload;
+    pb_ostream_t endStream = pb_ostream_from_buffer(endPayload.data(), endPayload.size());
+    if (pb_encode(&endStream, domes_config_CoreDumpComplete_fields, &end)) {
+        sendFrame(MsgType::kCoreDumpEnd, endPayload.data(), endStream.bytes_written);
+    }
+
+    esp_log_level_set("*", ESP_LOG_INFO);
+
+    ESP_LOGI(kTag, "Core dump sent: %zu of %zu bytes, checksum 0x%08lX", offset, size,
+             static_cast<unsigned long>(end.checksum));
+}
+
+void ConfigCommandHandler::handleCoreDumpClear() {
+    // Only report erased when there was a dump to erase
+    size_t addr = 0;
+    size_t size = 0;
+    bool hadDump = esp_core_dump_image_get(&addr, &size) == ESP_OK;
+
+    esp_err_t err = esp_core_dump_image_erase();
+    if (err != ESP_OK) {
+        ESP_LOGE(kTag, "Failed to erase core dump: %s", esp_err_to_name(err));
+    }
+
+    domes_config_ClearCoreDumpResponse resp = domes_config_ClearCoreDumpResponse_init_zero;
+    resp.erased = hadDump && err == ESP_OK;
+
+    std::array<uint8_t, domes_config_ClearCoreDumpResponse_size + 10> payload;
+    payload[0] = static_cast<uint8_t>(err == ESP_OK ? Status::kOk : Status::kError);
+
+    pb_ostream_t stream = pb_ostream_from_buffer(payload.data() + 1, payload.size() - 1);
+    if (!pb_encode(&stream, domes_config_ClearCoreDumpResponse_fields, &resp)) {
+        ESP_LOGE(kTag, "Failed to encode ClearCoreDumpResponse: %s", PB_GET_ERROR(&stream));
+        return;
+    }
+
+    sendFrame(MsgType::kCoreDumpClearRsp, payload.data(), 1 + stream.bytes_written);
+}
+
+// ============================================================================
+// WiFi access point and station addressing handlers
+// ============================================================================
+
+void ConfigCommandHandler::handleWifiApConfig(const uint8_t* payload, size_t len) {
+    std::array<uint8_t, 1> errPayload;
+    errPayload[0] = static_cast<uint8_t>(Status::kError);
+
+    domes_config_WifiApConfigRequest req = domes_config_WifiApConfigRequest_init_zero;
+    pb_istream_t istream = pb_istream_from_buffer(payload, len);
+    if (!pb_decode(&istream, domes_config_WifiApConfigRequest_fields, &req)) {
+        ESP_LOGW(kTag, "Failed to decode WIFI_AP_CONFIG: %s", PB_GET_ERROR(&istream));
+        sendFrame(MsgType::kWifiApConfigRsp, errPayload.data(), 1);
+        return;
+    }
+
+    WifiApSettings settings;
+    std::strncpy(settings.ssid, req.ssid, sizeof(settings.ssid) - 1);
+    std::strncpy(settings.password, req.password, sizeof(settings.password) - 1);
+    settings.channel = static_cast<uint8_t>(std::min<uint32_t>(req.channel, UINT8_MAX));
+    settings.maxConnections =
+        static_cast<uint8_t>(std::min<uint32_t>(req.max_connections, UINT8_MAX));
+
+    // Includes WPA2 passwords under 8 chars, which ESP-IDF refuses
+    if (!WifiAccessPoint::isValid(settings)) {
+        ESP_LOGW(kTag, "Invalid AP settings: ssid '%s', channel %lu, max %lu", req.ssid,
+                 static_cast<unsigned long>(req.channel),
+                 static_cast<unsigned long>(req.max_connections));
+        sendFrame(MsgType::kWifiApConfigRsp, errPayload.data(), 1);
+        return;
+    }
+
+    // Only save settings that started an AP, so a bad one can't come back at boot
+    if (WifiAccessPoint::start(settings) != ESP_OK ||
+        WifiAccessPoint::saveSettings(settings) != ESP_OK) {
+        sendFrame(MsgType::kWifiApConfigRsp, errPayload.data(), 1);
+        return;
+    }
+
+    domes_config_WifiApConfigResponse resp = domes_config_WifiApConfigResponse_init_zero;
+    std::strncpy(resp.ssid, settings.ssid, sizeof(resp.ssid) - 1);
+    resp.channel = settings.channel;
+    resp.max_connections = settings.maxConnections;
+    resp.secured = settings.password[0] != '\0';
+    resp.ip = WifiAccessPoint::gatewayIp();
+
+    std::array<uint8_t, domes_config_WifiApConfigResponse_size + 10> respPayload;
+    respPayload[0] = static_cast<uint8_t>(Status::kOk);
+
+    pb_ostream_t ostream = pb_ostream_from_buffer(respPayload.data() + 1, respPayload.size() - 1);
+    if (!pb_encode(&ostream, domes_config_WifiApConfigResponse_fields, &resp)) {
+        ESP_LOGE(kTag, "Failed to encode WifiApConfigResponse: %s", PB_GET_ERROR(&ostream));
+        return;
+    }
+
+    sendFrame(MsgType::kWifiApConfigRsp, respPayload.data(), 1 + ostream.bytes_written);
+}
+
+void ConfigCommandHandler::handleWifiApStatus() {
+    domes_config_WifiApStatusResponse resp = domes_config_WifiApStatusResponse_init_zero;
+
+    WifiApSettings settings;
+    resp.active = WifiAccessPoint::isActive() && WifiAccessPoint::getSettings(settings) == ESP_OK;
+    if (resp.active) {
+        std::strncpy(resp.ssid, settings.ssid, sizeof(resp.ssid) - 1);
+        resp.channel = settings.channel;
+        resp.ip = WifiAccessPoint::gatewayIp();
+
+        std::array<WifiApClient, sizeof(resp.clients) / sizeof(resp.clients[0])> clients;
+        size_t count = WifiAccessPoint::getClients(clients.data(), clients.size());
+        for (size_t i = 0; i < count; ++i) {
+            resp.clients[i].mac.size = sizeof(clients[i].mac);
+            std::memcpy(resp.clients[i].mac.bytes, clients[i].mac, sizeof(clients[i].mac));
+            resp.clients[i].rssi = clients[i].rssi;
+        }
+        resp.clients_count = static_cast<pb_size_t>(count);
+    }
+
+    std::array<uint8_t, domes_config_WifiApStatusResponse_size + 10> payload;
+    payload[0] = static_cast<uint8_t>(Status::kOk);
+
+    pb_ostream_t stream = pb_ostream_from_buffer(payload.data() + 1, payload.size() - 1);
+    if (!pb_encode(&stream, domes_config_WifiApStatusResponse_fields, &resp)) {
+        ESP_LOGE(kTag, "Failed to encode WifiApStatusResponse: %s", PB_GET_ERROR(&stream));
+        return;
+    }
+
+    sendFrame(MsgType::kWifiApStatusRsp, payload.data(), 1 + stream.bytes_written);
+}
+
+void ConfigCommandHandler::handleWifiStaticIp(const uint8_t* payload, size_t len) {
+    std::array<uint8_t, 1> respPayload;
+    respPayload[0] = static_cast<uint8_t>(Status::kError);
+
+    // [ip][netmask][gateway][dns1][dns2], 4 bytes each, big-endian
+    constexpr size_t kAddrCount = 5;
+    if (len != kAddrCount * 4) {
+        ESP_LOGW(kTag, "WIFI_STATIC_IP payload is %zu bytes, expected %zu", len, kAddrCount * 4);
+        sendFrame(MsgType::kWifiStaticIpRsp, respPayload.data(), 1);
+        return;
+    }
+
+    std::array<uint32_t, kAddrCount> addrs;
+    for (size_t i = 0; i < kAddrCount; ++i) {
+        const uint8_t* p = payload + i * 4;
+        addrs[i] = (static_cast<uint32_t>(p[0]) << 24) | (static_cast<uint32_t>(p[1]) << 16) |
+                   (static_cast<uint32_t>(p[2]) << 8) | static_cast<uint32_t>(p[3]);
+    }
+
+    WifiStaticIpSettings settings;
+    settings.ip = addrs[0];
+    settings.netmask = addrs[1];
+    settings.gateway = addrs[2];
+    settings.dns1 = addrs[3];
+    settings.dns2 = addrs[4];
+
+    if (!WifiStaticIp::isValid(settings)) {
+        ESP_LOGW(kTag, "Invalid static IP settings");
+        sendFrame(MsgType::kWifiStaticIpRsp, respPayload.data(), 1);
+        return;
+    }
+
+    // Only save settings that applied, so a bad one can't come back at boot
+    if (WifiStaticIp::apply(settings) == ESP_OK && WifiStaticIp::saveSettings(settings) == ESP_OK) {
+        respPayload[0] = static_cast<uint8_t>(Status::kOk);
+    }
+
+    sendFrame(MsgType::kWifiStaticIpRsp, respPayload.data(), 1);
+}
+
 // ============================================================================
 // Memory profile handler
 // ============================================================================
@@ -1141,4 +1756,240 @@ void ConfigCommandHandler::handleSetSimMode(const uint8_t* payload, size_t len)
     sendFrame(MsgType::kSetSimModeRsp, respPayload.data(), 1 + ostream.bytes_written);
 }
 
+// =============================================================================
+// Event Subscription
+// =============================================================================
+
+void ConfigCommandHandler::handleSubscribeEvents(const uint8_t* payload, size_t len) {
+    domes_config_SubscribeEventsRequest req = domes_config_SubscribeEventsRequest_init_zero;
+    if (len > 0) {
+        pb_istream_t stream = pb_istream_from_buffer(payload, len);
+        if (!pb_decode(&stream, domes_config_SubscribeEventsRequest_fields, &req)) {
+            ESP_LOGW(kTag, "Failed to decode SUBSCRIBE_EVENTS: %s", PB_GET_ERROR(&stream));
+            std::array<uint8_t, 1> errPayload;
+            errPayload[0] = static_cast<uint8_t>(Status::kError);
+            sendFrame(MsgType::kSubscribeEventsRsp, errPayload.data(), 1);
+            return;
+        }
+    }
+
+    // Replace the previous subscription; changes are reported from now on
+    eventCategories_ = req.categories & kAllEventCategories;
+    takeEventSnapshot(lastEvents_);
+
+    ESP_LOGI(kTag, "Event subscription: 0x%02lX", static_cast<unsigned long>(eventCategories_));
+
+    domes_config_SubscribeEventsResponse resp = domes_config_SubscribeEventsResponse_init_zero;
+    resp.categories = eventCategories_;
+
+    std::array<uint8_t, domes_config_SubscribeEventsResponse_size + 10> respPayload;
+    respPayload[0] = static_cast<uint8_t>(Status::kOk);
+
+    pb_ostream_t ostream = pb_ostream_from_buffer(respPayload.data() + 1, respPayload.size() - 1);
+    if (!pb_encode(&ostream, domes_config_SubscribeEventsResponse_fields, &resp)) {
+        ESP_LOGE(kTag, "Failed to encode SubscribeEventsResponse: %s", PB_GET_ERROR(&ostream));
+        return;
+    }
+
+    sendFrame(MsgType::kSubscribeEventsRsp, respPayload.data(), 1 + ostream.bytes_written);
+}
+
+void ConfigCommandHandler::pollEvents() {
+    if (eventCategories_ == 0) {
+        return;
+    }
+
+    EventSnapshot now;
+    takeEventSnapshot(now);
+    uint32_t timestampMs = static_cast<uint32_t>(esp_timer_get_time() / 1000);
+
+    if ((eventCategories_ & domes_config_EventCategory_EVENT_CATEGORY_MODE) &&
+        now.mode != lastEvents_.mode) {
+        domes_config_EventNotification event = domes_config_EventNotification_init_zero;
+        event.category = domes_config_EventCategory_EVENT_CATEGORY_MODE;
+        event.timestamp_ms = timestampMs;
+        event.mode = static_cast<domes_config_SystemMode>(now.mode);
+        sendEvent(event);
+    }
+
+    if ((eventCategories_ & domes_config_EventCategory_EVENT_CATEGORY_LED) &&
+        !sameLedPattern(now.ledPattern, lastEvents_.ledPattern)) {
+        domes_config_EventNotification event = domes_config_EventNotification_init_zero;
+        event.category = domes_config_EventCategory_EVENT_CATEGORY_LED;
+        event.timestamp_ms = timestampMs;
+        event.has_led_pattern = true;
+        event.led_pattern = now.ledPattern;
+        sendEvent(event);
+    }
+
+    if ((eventCategories_ & domes_config_EventCategory_EVENT_CATEGORY_WIFI) &&
+        now.wifiConnected != lastEvents_.wifiConnected) {
+        domes_config_EventNotification event = domes_config_EventNotification_init_zero;
+        event.category = domes_config_EventCategory_EVENT_CATEGORY_WIFI;
+        event.timestamp_ms = timestampMs;
+        event.wifi_connected = now.wifiConnected;
+        sendEvent(event);
+    }
+
+    if (eventCategories_ & domes_config_EventCategory_EVENT_CATEGORY_FEATURE) {
+        uint32_t changed = now.featureMask ^ lastEvents_.featureMask;
+        for (uint8_t bit = 1; bit < static_cast<uint8_t>(Feature::kCount); ++bit) {
+            if (!(changed & (1u << bit))) continue;
+            domes_config_EventNotification event = domes_config_EventNotification_init_zero;
+            event.category = domes_config_EventCategory_EVENT_CATEGORY_FEATURE;
+            event.timestamp_ms = timestampMs;
+            event.has_feature = true;
+            event.feature.feature = static_cast<domes_config_Feature>(bit);
+            event.feature.enabled = (now.featureMask & (1u << bit)) != 0;
+            sendEvent(event);
+        }
+    }
+
+    if (eventCategories_ & domes_config_EventCategory_EVENT_CATEGORY_TOUCH) {
+        // Report new touches only, not releases
+        uint32_t pressed = now.touchedMask & ~lastEvents_.touchedMask;
+        for (uint8_t pad = 0; pad < 32; ++pad) {
+            if (!(pressed & (1u << pad))) continue;
+            domes_config_EventNotification event = domes_config_EventNotification_init_zero;
+            event.category = domes_config_EventCategory_EVENT_CATEGORY_TOUCH;
+            event.timestamp_ms = timestampMs;
+            event.pad_index = pad;
+            sendEvent(event);
+        }
+    }
+
+    lastEvents_ = now;
+}
+
+void ConfigCommandHandler::takeEventSnapshot(EventSnapshot& snapshot) const {
+    snapshot.mode = modeManager_ ? modeManager_->currentMode() : SystemMode::kBooting;
+
+    snapshot.ledPattern = domes_config_LedPattern_init_zero;
+    if (ledService_) {
+        ledService_->getPattern(snapshot.ledPattern);
+    }
+
+    wifi_ap_record_t apInfo;
+    snapshot.wifiConnected = esp_wifi_sta_get_ap_info(&apInfo) == ESP_OK;
+
+    snapshot.featureMask = features_.getMask();
+
+    snapshot.touchedMask = 0;
+    if (injectableTouch_) {
+        uint8_t padCount = injectableTouch_->getPadCount();
+        for (uint8_t pad = 0; pad < padCount && pad < 32; ++pad) {
+            if (injectableTouch_->isTouched(pad)) {
+                snapshot.touchedMask |= 1u << pad;
+            }
+        }
+    }
+}
+
+void ConfigCommandHandler::sendEvent(const domes_config_EventNotification& event) {
+    std::array<uint8_t, domes_config_EventNotification_size> payload;
+
+    pb_ostream_t ostream = pb_ostream_from_buffer(payload.data(), payload.size());
+    if (!pb_encode(&ostream, domes_config_EventNotification_fields, &event)) {
+        ESP_LOGE(kTag, "Failed to encode EventNotification: %s", PB_GET_ERROR(&ostream));
+        return;
+    }
+
+    sendFrame(MsgType::kEventNotification, payload.data(), ostream.bytes_written);
+}
+
+// =============================================================================
+// Batch Commands
+// =============================================================================
+
+void ConfigCommandHandler::handleBatch(const uint8_t* payload, size_t len) {
+    std::array<uint8_t, 1> errPayload;
+    errPayload[0] = static_cast<uint8_t>(Status::kError);
+
+    if (batchCapture_) {
+        ESP_LOGW(kTag, "Nested BATCH rejected");
+        sendFrame(MsgType::kBatchRsp, errPayload.data(), 1);
+        return;
+    }
+
+    static_assert(domes_config_BatchResponse_size <= kMaxPayloadSize - 1,
+                  "BatchResponse must fit in one frame");
+
+    // Request and response hold up to 3 x 256 bytes each, too much for the task stack
+    std::unique_ptr<domes_config_BatchRequest> req(new (std::nothrow) domes_config_BatchRequest());
+    std::unique_ptr<domes_config_BatchResponse> resp(
+        new (std::nothrow) domes_config_BatchResponse());
+    std::unique_ptr<uint8_t[]> respPayload(new (std::nothrow) uint8_t[kMaxPayloadSize]);
+    if (!req || !resp || !respPayload) {
+        ESP_LOGE(kTag, "Out of memory for BATCH");
+        sendFrame(MsgType::kBatchRsp, errPayload.data(), 1);
+        return;
+    }
+
+    pb_istream_t istream = pb_istream_from_buffer(payload, len);
+    if (!pb_decode(&istream, domes_config_BatchRequest_fields, req.get())) {
+        ESP_LOGW(kTag, "Failed to decode BATCH: %s", PB_GET_ERROR(&istream));
+        sendFrame(MsgType::kBatchRsp, errPayload.data(), 1);
+        return;
+    }
+
+    ESP_LOGI(kTag, "Running batch of %u commands", static_cast<unsigned>(req->commands_count));
+
+    batchCapture_ = resp.get();
+    for (pb_size_t i = 0; i < req->commands_count; ++i) {
+        const domes_config_BatchCommand& cmd = req->commands[i];
+        batchCaptured_ = false;
+
+        if (cmd.msg_type <= 0xFF && isConfigMessage(static_cast<uint8_t>(cmd.msg_type))) {
+            handleCommand(static_cast<uint8_t>(cmd.msg_type), cmd.payload.bytes, cmd.payload.size);
+        } else {
+            ESP_LOGW(kTag, "Batched command 0x%02lX is not a config command",
+                     static_cast<unsigned long>(cmd.msg_type));
+        }
+
+        // Keep responses aligned with commands: no response is an empty entry
+        if (!batchCaptured_) {
+            resp->responses[resp->responses_count++] = domes_config_BatchCommand_init_zero;
+        }
+    }
+    batchCapture_ = nullptr;
+
+    respPayload[0] = static_cast<uint8_t>(Status::kOk);
+    pb_ostream_t ostream = pb_ostream_from_buffer(respPayload.get() + 1, kMaxPayloadSize - 1);
+    if (!pb_encode(&ostream, domes_config_BatchResponse_fields, resp.get())) {
+        ESP_LOGW(kTag, "Batch response too large: %s", PB_GET_ERROR(&ostream));
+        sendFrame(MsgType::kBatchRsp, errPayload.data(), 1);
+        return;
+    }
+
+    sendFrame(MsgType::kBatchRsp, respPayload.get(), 1 + ostream.bytes_written);
+}
+
+bool ConfigCommandHandler::captureBatchResponse(
+    MsgType type, const uint8_t* payload, size_t len
+) {
+    if (batchCaptured_) {
+        ESP_LOGW(kTag, "Dropping extra batched response 0x%02X", static_cast<uint8_t>(type));
+        return false;
+    }
+    batchCaptured_ = true;
+
+    domes_config_BatchCommand& entry = batchCapture_->responses[batchCapture_->responses_count++];
+    entry = domes_config_BatchCommand_init_zero;
+    entry.msg_type = static_cast<uint8_t>(type);
+
+    if (len > sizeof(entry.payload.bytes)) {
+        ESP_LOGW(kTag, "Batched response 0x%02X too large (%u bytes)",
+                 static_cast<uint8_t>(type), static_cast<unsigned>(len));
+        entry.payload.bytes[0] = static_cast<uint8_t>(Status::kError);
+        entry.payload.size = 1;
+        return false;
+    }
+
+    if (len > 0) {
+        std::memcpy(entry.payload.bytes, payload, len);
+    }
+    entry.payload.size = static_cast<pb_size_t>(len);
+    return true;
+}
+
 }  // namespace domes::config
@@ -94,6 +94,15 @@ public:
      */
     bool handleCommand(uint8_t type, const uint8_t* payload, size_t len);
 
+    /**
+     * @brief Push EVENT_NOTIFICATION frames for state changes since the last poll
+     *
+     * Call periodically from the receive loop. Does nothing until the host
+     * subscribes with SUBSCRIBE_EVENTS. State is sampled, so a touch shorter
+     * than the poll interval can be missed.
+     */
+    void pollEvents();
+
 private:
     /**
      * @brief Handle LIST_FEATURES request
@@ -157,11 +166,31 @@ private:
      */
     void handleSetPodId(const uint8_t* payload, size_t len);
 
+    /**
+     * @brief Handle SET_BAUD_RATE request
+     */
+    void handleSetBaudRate(const uint8_t* payload, size_t len);
+
+    /**
+     * @brief Handle SET_DEVICE_NAME request
+     */
+    void handleSetDeviceName(const uint8_t* payload, size_t len);
+
     /**
      * @brief Handle GET_HEALTH request
      */
     void handleGetHealth();
 
+    /**
+     * @brief Handle GET_TASK_LIST request
+     */
+    void handleGetTaskList();
+
+    /**
+     * @brief Handle GET_PARTITION_TABLE request
+     */
+    void handleGetPartitionTable();
+
     /**
      * @brief Handle GET_ESPNOW_STATUS request
      */
@@ -174,6 +203,36 @@ private:
 
     void handleGetCrashDump();
     void handleClearCrashDump();
+
+    /**
+     * @brief Handle CORE_DUMP_READ request
+     *
+     * Streams the core dump from the coredump partition as CORE_DUMP_DATA
+     * frames followed by CORE_DUMP_END, like a trace dump.
+     */
+    void handleCoreDumpRead();
+
+    /**
+     * @brief Handle CORE_DUMP_CLEAR request
+     */
+    void handleCoreDumpClear();
+
+    /**
+     * @brief Handle WIFI_AP_CONFIG request (start AP and save to NVS)
+     */
+    void handleWifiApConfig(const uint8_t* payload, size_t len);
+
+    /**
+     * @brief Handle WIFI_AP_STATUS request
+     */
+    void handleWifiApStatus();
+
+    /**
+     * @brief Handle WIFI_STATIC_IP request (apply and save to NVS)
+     *
+     * Not protobuf: five big-endian IPv4 addresses, all zero for DHCP.
+     */
+    void handleWifiStaticIp(const uint8_t* payload, size_t len);
     void handleGetMemoryProfile();
 
     /**
@@ -201,6 +260,45 @@ private:
      */
     void handleSetSimMode(const uint8_t* payload, size_t len);
 
+    /**
+     * @brief Handle SUBSCRIBE_EVENTS request
+     */
+    void handleSubscribeEvents(const uint8_t* payload, size_t len);
+
+    /**
+     * @brief Handle BATCH request
+     *
+     * Runs each command through handleCommand() in order and answers with
+     * one BATCH_RSP carrying the first response frame of each command.
+     */
+    void handleBatch(const uint8_t* payload, size_t len);
+
+    /**
+     * @brief Record a response frame for the batch command being run
+     *
+     * @return true if the response was recorded
+     */
+    bool captureBatchResponse(MsgType type, const uint8_t* payload, size_t len);
+
+    /// Last observed state, compared by pollEvents() to detect changes
+    struct EventSnapshot {
+        SystemMode mode = SystemMode::kBooting;
+        domes_config_LedPattern ledPattern = domes_config_LedPattern_init_zero;
+        bool wifiConnected = false;
+        uint32_t featureMask = 0;
+        uint32_t touchedMask = 0;  ///< Bit per pad
+    };
+
+    /**
+     * @brief Sample the state reported by event notifications
+     */
+    void takeEventSnapshot(EventSnapshot& snapshot) const;
+
+    /**
+     * @brief Send an EVENT_NOTIFICATION frame (protobuf only, no status byte)
+     */
+    void sendEvent(const domes_config_EventNotification& event);
+
     /**
      * @brief Send list features response
      */
@@ -258,6 +356,14 @@ private:
     EspNowService* espNowService_ = nullptr;
     IOtaManager* otaManager_ = nullptr;
     InjectableTouchDriver* injectableTouch_ = nullptr;
+
+    // EventCategory bitmask subscribed by the host (0 = no events)
+    uint32_t eventCategories_ = 0;
+    EventSnapshot lastEvents_;
+
+    // Set while a batch runs: sendFrame() records responses here instead of sending
+    domes_config_BatchResponse* batchCapture_ = nullptr;
+    bool batchCaptured_ = false;
 };
 
 }  // namespace domes::config
@@ -72,6 +72,38 @@ enum class MsgType : uint8_t {
     kSimulateTouchRsp     = domes_config_MsgType_MSG_TYPE_SIMULATE_TOUCH_RSP,
     kSetSimModeReq        = domes_config_MsgType_MSG_TYPE_SET_SIM_MODE_REQ,
     kSetSimModeRsp        = domes_config_MsgType_MSG_TYPE_SET_SIM_MODE_RSP,
+    // Batch commands (0x50-0x51)
+    kBatchReq             = domes_config_MsgType_MSG_TYPE_BATCH_REQ,
+    kBatchRsp             = domes_config_MsgType_MSG_TYPE_BATCH_RSP,
+    // Device name commands (0x52-0x53)
+    kSetDeviceNameReq     = domes_config_MsgType_MSG_TYPE_SET_DEVICE_NAME_REQ,
+    kSetDeviceNameRsp     = domes_config_MsgType_MSG_TYPE_SET_DEVICE_NAME_RSP,
+    // Event subscription (0x54-0x56)
+    kSubscribeEventsReq   = domes_config_MsgType_MSG_TYPE_SUBSCRIBE_EVENTS_REQ,
+    kSubscribeEventsRsp   = domes_config_MsgType_MSG_TYPE_SUBSCRIBE_EVENTS_RSP,
+    kEventNotification    = domes_config_MsgType_MSG_TYPE_EVENT_NOTIFICATION,
+    // Keepalive (0x57-0x58)
+    kPingReq              = domes_config_MsgType_MSG_TYPE_PING_REQ,
+    kPingRsp              = domes_config_MsgType_MSG_TYPE_PING_RSP,
+    // Serial baud rate (0x59-0x5A)
+    kSetBaudRateReq       = domes_config_MsgType_MSG_TYPE_SET_BAUD_RATE_REQ,
+    kSetBaudRateRsp       = domes_config_MsgType_MSG_TYPE_SET_BAUD_RATE_RSP,
+    kGetTaskListReq       = domes_config_MsgType_MSG_TYPE_GET_TASK_LIST_REQ,
+    kGetTaskListRsp       = domes_config_MsgType_MSG_TYPE_GET_TASK_LIST_RSP,
+    kGetPartitionTableReq = domes_config_MsgType_MSG_TYPE_GET_PARTITION_TABLE_REQ,
+    kGetPartitionTableRsp = domes_config_MsgType_MSG_TYPE_GET_PARTITION_TABLE_RSP,
+    kCoreDumpReadReq      = domes_config_MsgType_MSG_TYPE_CORE_DUMP_READ_REQ,
+    kCoreDumpReadRsp      = domes_config_MsgType_MSG_TYPE_CORE_DUMP_READ_RSP,
+    kCoreDumpData         = domes_config_MsgType_MSG_TYPE_CORE_DUMP_DATA,
+    kCoreDumpEnd          = domes_config_MsgType_MSG_TYPE_CORE_DUMP_END,
+    kCoreDumpClearReq     = domes_config_MsgType_MSG_TYPE_CORE_DUMP_CLEAR_REQ,
+    kCoreDumpClearRsp     = domes_config_MsgType_MSG_TYPE_CORE_DUMP_CLEAR_RSP,
+    kWifiApConfigReq      = domes_config_MsgType_MSG_TYPE_WIFI_AP_CONFIG_REQ,
+    kWifiApConfigRsp      = domes_config_MsgType_MSG_TYPE_WIFI_AP_CONFIG_RSP,
+    kWifiApStatusReq      = domes_config_MsgType_MSG_TYPE_WIFI_AP_STATUS_REQ,
+    kWifiApStatusRsp      = domes_config_MsgType_MSG_TYPE_WIFI_AP_STATUS_RSP,
+    kWifiStaticIpReq      = domes_config_MsgType_MSG_TYPE_WIFI_STATIC_IP_REQ,
+    kWifiStaticIpRsp      = domes_config_MsgType_MSG_TYPE_WIFI_STATIC_IP_RSP,
 };
 
 /**
@@ -102,11 +134,11 @@ enum class Status : uint8_t {
 };
 
 /**
- * @brief Check if a message type is a config/system command (0x20-0x4F range)
+ * @brief Check if a message type is a config/system command (0x20-0x6A range)
  */
 inline bool isConfigMessage(uint8_t type) {
     return type >= static_cast<uint8_t>(MsgType::kListFeaturesReq) &&
-           type <= static_cast<uint8_t>(MsgType::kSetSimModeRsp);
+           type <= static_cast<uint8_t>(MsgType::kWifiStaticIpRsp);
 }
 
 /**
@@ -140,6 +172,9 @@ inline const char* statusToString(Status status) {
     }
 }
 
+/// Maximum custom BLE device name length (matches SetDeviceNameRequest.name max_size - 1)
+constexpr size_t kMaxDeviceNameLen = 20;
+
 /// Maximum features supported
 constexpr size_t kMaxFeatures = static_cast<size_t>(Feature::kCount);
 
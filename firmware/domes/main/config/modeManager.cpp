@@ -47,6 +47,7 @@ const char* systemModeToString(SystemMode mode) {
 ModeManager::ModeManager(FeatureManager& features)
     : features_(features)
     , currentMode_(static_cast<uint8_t>(SystemMode::kBooting))
+    , previousMode_(kNoPreviousMode)
     , modeEnteredAt_(esp_timer_get_time())
     , lastActivityAt_(esp_timer_get_time()) {
 }
@@ -56,27 +57,47 @@ SystemMode ModeManager::currentMode() const {
 }
 
 bool ModeManager::transitionTo(SystemMode newMode) {
+    return enterMode(newMode, false);
+}
+
+bool ModeManager::forceTransitionTo(SystemMode newMode) {
+    return enterMode(newMode, true);
+}
+
+bool ModeManager::enterMode(SystemMode newMode, bool force) {
     SystemMode oldMode = currentMode();
 
     if (oldMode == newMode) {
         return true;  // Already in target mode
     }
 
-    if (!isValidTransition(oldMode, newMode)) {
+    if (static_cast<size_t>(newMode) >= sizeof(kModeMasks) / sizeof(kModeMasks[0])) {
+        ESP_LOGW(kTag, "Unknown mode %u", static_cast<unsigned>(newMode));
+        return false;
+    }
+
+    if (force) {
+        ESP_LOGW(kTag, "Forcing transition: %s -> %s",
+                 systemModeToString(oldMode), systemModeToString(newMode));
+    } else if (!isValidTransition(oldMode, newMode)) {
         ESP_LOGW(kTag, "Invalid transition: %s -> %s",
                  systemModeToString(oldMode), systemModeToString(newMode));
         return false;
     }
 
-    // Track which mode we entered GAME from (for timeout exit destination)
+    // Track which mode we entered GAME from (for timeout exit destination).
+    // GAME can only leave to CONNECTED or IDLE, so a forced entry from
+    // elsewhere exits to IDLE.
     if (newMode == SystemMode::kGame) {
-        gameEnteredFrom_ = oldMode;
+        gameEnteredFrom_ = oldMode == SystemMode::kConnected ? SystemMode::kConnected
+                                                             : SystemMode::kIdle;
     }
 
     // Apply feature mask first, then update mode
     applyFeatureMask(newMode);
 
     int64_t now = esp_timer_get_time();
+    previousMode_.store(static_cast<uint8_t>(oldMode), std::memory_order_release);
     currentMode_.store(static_cast<uint8_t>(newMode), std::memory_order_release);
     modeEnteredAt_.store(now, std::memory_order_release);
     lastActivityAt_.store(now, std::memory_order_release);
@@ -93,6 +114,15 @@ bool ModeManager::transitionTo(SystemMode newMode) {
     return true;
 }
 
+bool ModeManager::previousMode(SystemMode& mode) const {
+    uint8_t previous = previousMode_.load(std::memory_order_acquire);
+    if (previous == kNoPreviousMode) {
+        return false;
+    }
+    mode = static_cast<SystemMode>(previous);
+    return true;
+}
+
 uint32_t ModeManager::timeInModeMs() const {
     int64_t now = esp_timer_get_time();
     int64_t entered = modeEnteredAt_.load(std::memory_order_acquire);
@@ -73,6 +73,25 @@ public:
      */
     bool transitionTo(SystemMode newMode);
 
+    /**
+     * @brief Transition to a new mode, skipping the transition rules (testing)
+     *
+     * Applies the feature mask and notifies like transitionTo(). A forced
+     * GAME exits to CONNECTED if entered from there, otherwise to IDLE.
+     *
+     * @param newMode Target mode
+     * @return true if transition succeeded, false if newMode is not a mode
+     */
+    bool forceTransitionTo(SystemMode newMode);
+
+    /**
+     * @brief Get the mode before the last transition
+     *
+     * @param[out] mode Previous mode, written only if there is one
+     * @return false until the first transition
+     */
+    bool previousMode(SystemMode& mode) const;
+
     /**
      * @brief Get time spent in current mode (milliseconds)
      */
@@ -113,17 +132,22 @@ public:
     static uint32_t featureMaskForMode(SystemMode mode);
 
 private:
+    bool enterMode(SystemMode newMode, bool force);
     bool isValidTransition(SystemMode from, SystemMode to) const;
     void applyFeatureMask(SystemMode mode);
 
     FeatureManager& features_;
     std::atomic<uint8_t> currentMode_;    // Stored as uint8_t for atomic compatibility
+    std::atomic<uint8_t> previousMode_;   // kNoPreviousMode until the first transition
     std::atomic<int64_t> modeEnteredAt_;  // esp_timer_get_time() value
     std::atomic<int64_t> lastActivityAt_; // esp_timer_get_time() value
     SystemMode gameEnteredFrom_{SystemMode::kIdle};
     ModeTransitionCallback transitionCb_;
 };
 
+/// previousMode_ value before the first transition
+constexpr uint8_t kNoPreviousMode = 0xFF;
+
 // Timeout constants
 constexpr int64_t kTriageTimeoutUs  = 30'000'000;   // 30s inactivity -> IDLE
 constexpr int64_t kErrorRecoveryUs  = 10'000'000;   // 10s in ERROR -> IDLE
@@ -30,6 +30,9 @@ constexpr const char* kVolume = "volume";                ///< uint8_t 0-100
 constexpr const char* kTouchThreshold = "touch_thresh";  ///< uint16_t
 constexpr const char* kPodId = "pod_id";                 ///< uint8_t
 constexpr const char* kAutoUpdate = "auto_update";       ///< uint8_t (0=disabled, 1=enabled)
+constexpr const char* kDeviceName = "device_name";       ///< blob, BLE name up to 20 chars
+constexpr const char* kWifiAp = "wifi_ap";               ///< blob, WifiApSettings
+constexpr const char* kWifiStaticIp = "wifi_static_ip";  ///< blob, WifiStaticIpSettings
 }  // namespace config_key
 
 /**
@@ -25,6 +25,8 @@
 #include "services/ledService.hpp"
 #include "services/otaManager.hpp"
 #include "services/touchService.hpp"
+#include "services/wifiAccessPoint.hpp"
+#include "services/wifiStaticIp.hpp"
 #include "trace/traceApi.hpp"
 #include "config/featureManager.hpp"
 #include "config/modeManager.hpp"
@@ -98,8 +100,9 @@ static domes::InjectableTouchDriver* injectableTouchDriver = nullptr;  // Touch
 static domes::TouchService* touchService = nullptr;  // Touch monitoring service
 static domes::game::GameEngine* gameEngine = nullptr;  // Game logic FSM
 
-#ifdef CONFIG_DOMES_WIFI_AUTO_CONNECT
 static domes::TcpConfigServer* tcpConfigServer = nullptr;  // WiFi config server
+
+#ifdef CONFIG_DOMES_WIFI_AUTO_CONNECT
 static domes::WifiManager* wifiManager = nullptr;
 static domes::infra::NvsConfig wifiStorage;
 #endif
@@ -766,7 +769,6 @@ static esp_err_t initBleOta(uint8_t podId = 0) {
     return ESP_OK;
 }
 
-#ifdef CONFIG_DOMES_WIFI_AUTO_CONNECT
 /**
  * @brief Initialize TCP config server
  *
@@ -828,7 +830,6 @@ static esp_err_t initTcpConfigServer() {
     ESP_LOGI(kTag, "TCP config server started on port %u", domes::kConfigServerPort);
     return ESP_OK;
 }
-#endif  // CONFIG_DOMES_WIFI_AUTO_CONNECT
 
 #ifdef CONFIG_DOMES_WIFI_AUTO_CONNECT
 /**
@@ -853,6 +854,9 @@ static esp_err_t initWifi() {
         return err;
     }
 
+    // Use the static IP saved by `wifi static` instead of a DHCP lease, if any
+    domes::WifiStaticIp::applyFromNvs();
+
     // Connect with credentials from secrets.hpp
     ESP_LOGI(kTag, "Connecting to WiFi: %s", domes::secrets::kWifiSsid);
     err = wifiManager->connect(domes::secrets::kWifiSsid, domes::secrets::kWifiPassword,
@@ -1160,6 +1164,9 @@ extern "C" void app_main() {
     }
 #endif
 
+    // Bring up the access point saved by `wifi configure-ap`, if any
+    bool wifiApActive = domes::WifiAccessPoint::startFromNvs() == ESP_OK;
+
     // Initialize OTA subsystem
     if (initOta() != ESP_OK) {
         ESP_LOGW(kTag, "OTA init failed, continuing without OTA support");
@@ -1270,13 +1277,20 @@ extern "C" void app_main() {
 
     // Initialize TCP config server (WiFi-based config) - BEFORE serial OTA takes console
 #ifdef CONFIG_DOMES_WIFI_AUTO_CONNECT
-    if (wifiManager && wifiManager->isConnected()) {
-        ESP_LOGI(kTag, "WiFi connected, starting TCP config server...");
+    bool wifiConnected = wifiManager && wifiManager->isConnected();
+#else
+    bool wifiConnected = false;
+#endif
+    if (wifiConnected || wifiApActive) {
+        ESP_LOGI(kTag, "WiFi %s, starting TCP config server...",
+                 wifiConnected ? "connected" : "access point up");
         if (initTcpConfigServer() != ESP_OK) {
             ESP_LOGW(kTag, "TCP config server init failed");
         }
+#ifdef CONFIG_DOMES_WIFI_AUTO_CONNECT
         // Advertise via mDNS for device discovery
         initMdns(podId);
+#endif
 
         // Start trace stream server (port 5001 for live trace streaming)
         static domes::trace::TraceStreamServer traceStreamServer;
@@ -1290,7 +1304,6 @@ extern "C" void app_main() {
     } else {
         ESP_LOGI(kTag, "TCP config server not started (WiFi not connected)");
     }
-#endif
 
     // Initialize serial OTA receiver (USB-CDC based) - this takes over console
     if (initSerialOta(podId) != ESP_OK) {
@@ -0,0 +1,172 @@
+/**
+ * @file wifiAccessPoint.cpp
+ * @brief WiFi access point mode implementation
+ */
+
+#include "wifiAccessPoint.hpp"
+
+#include "esp_log.h"
+#include "esp_netif.h"
+#include "esp_wifi.h"
+#include "infra/nvsConfig.hpp"
+
+#include <algorithm>
+#include <cstring>
+
+namespace domes {
+
+namespace {
+constexpr const char* kTag = "wifi_ap";
+
+/// esp_netif key of the default AP interface (runs the DHCP server)
+constexpr const char* kApNetifKey = "WIFI_AP_DEF";
+}  // namespace
+
+bool WifiAccessPoint::isValid(const WifiApSettings& settings) {
+    size_t ssidLen = strnlen(settings.ssid, sizeof(settings.ssid));
+    size_t passwordLen = strnlen(settings.password, sizeof(settings.password));
+
+    return ssidLen > 0 && ssidLen <= kWifiApMaxSsidLen &&
+           (passwordLen == 0 ||
+            (passwordLen >= kWifiApMinPasswordLen && passwordLen <= kWifiApMaxPasswordLen)) &&
+           settings.channel >= 1 && settings.channel <= kWifiApMaxChannel &&
+           settings.maxConnections >= 1 && settings.maxConnections <= kWifiApMaxConnections;
+}
+
+esp_err_t WifiAccessPoint::start(const WifiApSettings& settings) {
+    if (!isValid(settings)) {
+        return ESP_ERR_INVALID_ARG;
+    }
+
+    wifi_mode_t mode = WIFI_MODE_NULL;
+    esp_err_t err = esp_wifi_get_mode(&mode);
+    if (err != ESP_OK) {
+        ESP_LOGE(kTag, "WiFi not initialized: %s", esp_err_to_name(err));
+        return err;
+    }
+
+    // Create the AP interface once; it hands out 192.168.4.x addresses
+    if (esp_netif_get_handle_from_ifkey(kApNetifKey) == nullptr &&
+        esp_netif_create_default_wifi_ap() == nullptr) {
+        ESP_LOGE(kTag, "Failed to create WiFi AP netif");
+        return ESP_FAIL;
+    }
+
+    // Keep the station side for ESP-NOW and infrastructure WiFi
+    if (mode != WIFI_MODE_APSTA) {
+        err = esp_wifi_set_mode(WIFI_MODE_APSTA);
+        if (err != ESP_OK) {
+            ESP_LOGE(kTag, "esp_wifi_set_mode failed: %s", esp_err_to_name(err));
+            return err;
+        }
+    }
+
+    wifi_config_t config = {};
+    size_t ssidLen = strnlen(settings.ssid, sizeof(settings.ssid));
+    std::memcpy(config.ap.ssid, settings.ssid, ssidLen);
+    config.ap.ssid_len = static_cast<uint8_t>(ssidLen);
+    std::strncpy(reinterpret_cast<char*>(config.ap.password), settings.password,
+                 sizeof(config.ap.password) - 1);
+    config.ap.channel = settings.channel;
+    config.ap.max_connection = settings.maxConnections;
+    config.ap.authmode = settings.password[0] != '\0' ? WIFI_AUTH_WPA2_PSK : WIFI_AUTH_OPEN;
+
+    err = esp_wifi_set_config(WIFI_IF_AP, &config);
+    if (err != ESP_OK) {
+        ESP_LOGE(kTag, "esp_wifi_set_config(AP) failed: %s", esp_err_to_name(err));
+        return err;
+    }
+
+    ESP_LOGI(kTag, "Access point '%s' on channel %u (%s, up to %u stations)", settings.ssid,
+             settings.channel, settings.password[0] != '\0' ? "WPA2" : "open",
+             settings.maxConnections);
+    return ESP_OK;
+}
+
+esp_err_t WifiAccessPoint::startFromNvs() {
+    infra::NvsConfig config;
+    esp_err_t err = config.open(infra::nvs_ns::kConfig);
+    if (err != ESP_OK) {
+        return err;
+    }
+
+    WifiApSettings settings;
+    size_t len = sizeof(settings);
+    err = config.getBlob(infra::config_key::kWifiAp, &settings, len);
+    config.close();
+    if (err != ESP_OK || len != sizeof(settings)) {
+        return ESP_ERR_NOT_FOUND;
+    }
+
+    // Blobs from NVS may be corrupted; never trust their terminators
+    settings.ssid[kWifiApMaxSsidLen] = '\0';
+    settings.password[kWifiApMaxPasswordLen] = '\0';
+    return start(settings);
+}
+
+esp_err_t WifiAccessPoint::saveSettings(const WifiApSettings& settings) {
+    infra::NvsConfig config;
+    esp_err_t err = config.open(infra::nvs_ns::kConfig);
+    if (err == ESP_OK) {
+        err = config.setBlob(infra::config_key::kWifiAp, &settings, sizeof(settings));
+    }
+    if (err == ESP_OK) {
+        err = config.commit();
+    }
+    config.close();
+
+    if (err != ESP_OK) {
+        ESP_LOGE(kTag, "Failed to save AP settings: %s", esp_err_to_name(err));
+    }
+    return err;
+}
+
+bool WifiAccessPoint::isActive() {
+    wifi_mode_t mode = WIFI_MODE_NULL;
+    return esp_wifi_get_mode(&mode) == ESP_OK &&
+           (mode == WIFI_MODE_AP || mode == WIFI_MODE_APSTA);
+}
+
+esp_err_t WifiAccessPoint::getSettings(WifiApSettings& settings) {
+    wifi_config_t config = {};
+    esp_err_t err = esp_wifi_get_config(WIFI_IF_AP, &config);
+    if (err != ESP_OK) {
+        return err;
+    }
+
+    size_t ssidLen = std::min<size_t>(config.ap.ssid_len, kWifiApMaxSsidLen);
+    std::memcpy(settings.ssid, config.ap.ssid, ssidLen);
+    settings.ssid[ssidLen] = '\0';
+    settings.channel = config.ap.channel;
+    settings.maxConnections = config.ap.max_connection;
+    return ESP_OK;
+}
+
+uint32_t WifiAccessPoint::gatewayIp() {
+    esp_netif_t* netif = esp_netif_get_handle_from_ifkey(kApNetifKey);
+    esp_netif_ip_info_t info = {};
+    if (netif == nullptr || esp_netif_get_ip_info(netif, &info) != ESP_OK) {
+        return 0;
+    }
+
+    return (static_cast<uint32_t>(esp_ip4_addr1(&info.ip)) << 24) |
+           (static_cast<uint32_t>(esp_ip4_addr2(&info.ip)) << 16) |
+           (static_cast<uint32_t>(esp_ip4_addr3(&info.ip)) << 8) |
+           static_cast<uint32_t>(esp_ip4_addr4(&info.ip));
+}
+
+size_t WifiAccessPoint::getClients(WifiApClient* clients, size_t maxClients) {
+    wifi_sta_list_t list = {};
+    if (esp_wifi_ap_get_sta_list(&list) != ESP_OK) {
+        return 0;
+    }
+
+    size_t count = std::min<size_t>(static_cast<size_t>(list.num), maxClients);
+    for (size_t i = 0; i < count; ++i) {
+        std::memcpy(clients[i].mac, list.sta[i].mac, sizeof(clients[i].mac));
+        clients[i].rssi = list.sta[i].rssi;
+    }
+    return count;
+}
+
+}  // namespace domes
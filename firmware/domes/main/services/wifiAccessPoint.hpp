@@ -0,0 +1,121 @@
+#pragma once
+
+/**
+ * @file wifiAccessPoint.hpp
+ * @brief WiFi access point mode for standalone deployments
+ *
+ * Runs the WiFi radio as an access point next to the station interface
+ * (APSTA), so pods and the CLI can reach each other without infrastructure
+ * WiFi. Settings are saved to NVS ("config" namespace, key "wifi_ap") and
+ * applied again at boot.
+ *
+ * NOTE: Station and AP share one radio channel. ESP-NOW pins channel 1, so
+ * pods using ESP-NOW should run their AP on channel 1 as well.
+ */
+
+#include "esp_err.h"
+
+#include <cstddef>
+#include <cstdint>
+
+namespace domes {
+
+/// Longest SSID (802.11 limit)
+constexpr size_t kWifiApMaxSsidLen = 32;
+
+/// Shortest WPA2-PSK passphrase ESP-IDF accepts
+constexpr size_t kWifiApMinPasswordLen = 8;
+
+/// Longest WPA2-PSK passphrase
+constexpr size_t kWifiApMaxPasswordLen = 63;
+
+/// Highest 2.4 GHz channel an AP can use
+constexpr uint8_t kWifiApMaxChannel = 13;
+
+/// Stations an ESP32-S3 AP accepts at once
+constexpr uint8_t kWifiApMaxConnections = 10;
+
+/**
+ * @brief Access point settings (stored in NVS as a blob)
+ */
+struct WifiApSettings {
+    char ssid[kWifiApMaxSsidLen + 1] = {};          ///< Null-terminated SSID
+    char password[kWifiApMaxPasswordLen + 1] = {};  ///< Empty for an open network
+    uint8_t channel = 1;                            ///< 1-13
+    uint8_t maxConnections = 4;                     ///< 1-10 stations
+};
+
+/**
+ * @brief A station connected to the access point
+ */
+struct WifiApClient {
+    uint8_t mac[6] = {};
+    int8_t rssi = 0;  ///< dBm
+};
+
+/**
+ * @brief WiFi access point control
+ *
+ * Requires the WiFi stack to be initialized (esp_wifi_init) first.
+ */
+class WifiAccessPoint {
+public:
+    /**
+     * @brief Check settings before starting an AP with them
+     *
+     * @return true if SSID, password, channel and station limit are usable
+     */
+    static bool isValid(const WifiApSettings& settings);
+
+    /**
+     * @brief Start (or reconfigure) the access point
+     *
+     * Switches the radio to APSTA mode, keeping the station interface.
+     *
+     * @param settings Validated AP settings
+     * @return ESP_OK on success
+     */
+    static esp_err_t start(const WifiApSettings& settings);
+
+    /**
+     * @brief Start the access point saved in NVS, if any
+     *
+     * @return ESP_OK if started, ESP_ERR_NOT_FOUND if none is saved
+     */
+    static esp_err_t startFromNvs();
+
+    /**
+     * @brief Save settings to NVS so the AP starts again at boot
+     */
+    static esp_err_t saveSettings(const WifiApSettings& settings);
+
+    /**
+     * @brief Check if the radio is running as an access point
+     */
+    static bool isActive();
+
+    /**
+     * @brief Get the current AP SSID and channel
+     *
+     * @return ESP_OK on success, error if WiFi is not initialized
+     */
+    static esp_err_t getSettings(WifiApSettings& settings);
+
+    /**
+     * @brief Get the AP gateway IPv4 address, first octet in the high byte
+     *
+     * @return Address, or 0 if the AP interface has none
+     */
+    static uint32_t gatewayIp();
+
+    /**
+     * @brief List stations connected to the access point
+     *
+     * @param clients Output array
+     * @param maxClients Capacity of clients
+     * @return Number of stations written
+     */
+    static size_t getClients(WifiApClient* clients, size_t maxClients);
+};
+
+}  // namespace domes
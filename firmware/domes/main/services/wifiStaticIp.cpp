@@ -0,0 +1,137 @@
+/**
+ * @file wifiStaticIp.cpp
+ * @brief Static IPv4 addressing implementation
+ */
+
+#include "wifiStaticIp.hpp"
+
+#include "esp_log.h"
+#include "esp_netif.h"
+#include "infra/nvsConfig.hpp"
+
+namespace domes {
+
+namespace {
+constexpr const char* kTag = "wifi_ip";
+
+/// esp_netif key of the default station interface
+constexpr const char* kStaNetifKey = "WIFI_STA_DEF";
+
+esp_ip4_addr_t toIp4(uint32_t addr) {
+    esp_ip4_addr_t out;
+    out.addr = esp_netif_htonl(addr);
+    return out;
+}
+
+esp_err_t setDns(esp_netif_t* netif, esp_netif_dns_type_t type, uint32_t addr) {
+    esp_netif_dns_info_t dns = {};
+    dns.ip.type = ESP_IPADDR_TYPE_V4;
+    dns.ip.u_addr.ip4 = toIp4(addr);
+    return esp_netif_set_dns_info(netif, type, &dns);
+}
+}  // namespace
+
+bool WifiStaticIp::isValid(const WifiStaticIpSettings& settings) {
+    if (settings.ip == 0) {
+        return true;
+    }
+
+    // Contiguous mask: inverted, it is one less than a power of two
+    uint32_t hostBits = ~settings.netmask;
+    bool contiguous = settings.netmask != 0 && (hostBits & (hostBits + 1)) == 0;
+
+    return contiguous && settings.ip != UINT32_MAX && settings.ip != settings.gateway &&
+           (settings.ip & settings.netmask) == (settings.gateway & settings.netmask);
+}
+
+esp_err_t WifiStaticIp::apply(const WifiStaticIpSettings& settings) {
+    if (!isValid(settings)) {
+        return ESP_ERR_INVALID_ARG;
+    }
+
+    esp_netif_t* netif = esp_netif_get_handle_from_ifkey(kStaNetifKey);
+    if (netif == nullptr) {
+        ESP_LOGE(kTag, "WiFi station netif not created");
+        return ESP_ERR_INVALID_STATE;
+    }
+
+    if (settings.ip == 0) {
+        esp_err_t err = esp_netif_dhcpc_start(netif);
+        if (err != ESP_OK && err != ESP_ERR_ESP_NETIF_DHCP_ALREADY_STARTED) {
+            ESP_LOGE(kTag, "esp_netif_dhcpc_start failed: %s", esp_err_to_name(err));
+            return err;
+        }
+        ESP_LOGI(kTag, "Station using DHCP");
+        return ESP_OK;
+    }
+
+    esp_err_t err = esp_netif_dhcpc_stop(netif);
+    if (err != ESP_OK && err != ESP_ERR_ESP_NETIF_DHCP_ALREADY_STOPPED) {
+        ESP_LOGE(kTag, "esp_netif_dhcpc_stop failed: %s", esp_err_to_name(err));
+        return err;
+    }
+
+    esp_netif_ip_info_t info = {};
+    info.ip = toIp4(settings.ip);
+    info.netmask = toIp4(settings.netmask);
+    info.gw = toIp4(settings.gateway);
+    err = esp_netif_set_ip_info(netif, &info);
+    if (err != ESP_OK) {
+        ESP_LOGE(kTag, "esp_netif_set_ip_info failed: %s", esp_err_to_name(err));
+        return err;
+    }
+
+    err = setDns(netif, ESP_NETIF_DNS_MAIN, settings.dns1);
+    if (err == ESP_OK && settings.dns2 != 0) {
+        err = setDns(netif, ESP_NETIF_DNS_BACKUP, settings.dns2);
+    }
+    if (err != ESP_OK) {
+        ESP_LOGE(kTag, "esp_netif_set_dns_info failed: %s", esp_err_to_name(err));
+        return err;
+    }
+
+    ESP_LOGI(kTag, "Station static IP " IPSTR ", gateway " IPSTR, IP2STR(&info.ip),
+             IP2STR(&info.gw));
+    return ESP_OK;
+}
+
+esp_err_t WifiStaticIp::applyFromNvs() {
+    infra::NvsConfig config;
+    esp_err_t err = config.open(infra::nvs_ns::kConfig);
+    if (err != ESP_OK) {
+        return err;
+    }
+
+    WifiStaticIpSettings settings;
+    size_t len = sizeof(settings);
+    err = config.getBlob(infra::config_key::kWifiStaticIp, &settings, len);
+    config.close();
+    if (err != ESP_OK || len != sizeof(settings) || settings.ip == 0) {
+        return ESP_ERR_NOT_FOUND;
+    }
+
+    return apply(settings);
+}
+
+esp_err_t WifiStaticIp::saveSettings(const WifiStaticIpSettings& settings) {
+    infra::NvsConfig config;
+    esp_err_t err = config.open(infra::nvs_ns::kConfig);
+    if (err == ESP_OK) {
+        if (settings.ip == 0) {
+            err = config.eraseKey(infra::config_key::kWifiStaticIp);
+        } else {
+            err = config.setBlob(infra::config_key::kWifiStaticIp, &settings, sizeof(settings));
+        }
+    }
+    if (err == ESP_OK) {
+        err = config.commit();
+    }
+    config.close();
+
+    if (err != ESP_OK) {
+        ESP_LOGE(kTag, "Failed to save static IP settings: %s", esp_err_to_name(err));
+    }
+    return err;
+}
+
+}  // namespace domes
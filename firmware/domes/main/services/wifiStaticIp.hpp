@@ -0,0 +1,70 @@
+#pragma once
+
+/**
+ * @file wifiStaticIp.hpp
+ * @brief Static IPv4 addressing for the WiFi station interface
+ *
+ * Replaces the DHCP lease on the station interface with fixed settings so
+ * `--wifi` addresses stay stable. Settings are saved to NVS ("config"
+ * namespace, key "wifi_static_ip") and applied again at boot, before the
+ * station connects.
+ */
+
+#include "esp_err.h"
+
+#include <cstdint>
+
+namespace domes {
+
+/**
+ * @brief Static IPv4 settings (stored in NVS as a blob)
+ *
+ * Addresses are host-order integers with the first octet in the high byte.
+ * An ip of 0 means DHCP.
+ */
+struct WifiStaticIpSettings {
+    uint32_t ip = 0;
+    uint32_t netmask = 0;
+    uint32_t gateway = 0;
+    uint32_t dns1 = 0;
+    uint32_t dns2 = 0;  ///< 0 for no backup DNS server
+};
+
+/**
+ * @brief WiFi station static IP control
+ *
+ * Requires the station netif to exist (esp_netif_create_default_wifi_sta).
+ */
+class WifiStaticIp {
+public:
+    /**
+     * @brief Check static settings before applying them
+     *
+     * @return true for DHCP, or for a contiguous netmask with the gateway
+     *         on the same subnet as ip
+     */
+    static bool isValid(const WifiStaticIpSettings& settings);
+
+    /**
+     * @brief Apply settings to the station interface
+     *
+     * Stops the DHCP client for static settings and restarts it for DHCP.
+     *
+     * @return ESP_OK on success, ESP_ERR_INVALID_STATE without a station netif
+     */
+    static esp_err_t apply(const WifiStaticIpSettings& settings);
+
+    /**
+     * @brief Apply the settings saved in NVS, if any
+     *
+     * @return ESP_OK if applied, ESP_ERR_NOT_FOUND if none are saved
+     */
+    static esp_err_t applyFromNvs();
+
+    /**
+     * @brief Save settings to NVS (DHCP erases the saved settings)
+     */
+    static esp_err_t saveSettings(const WifiStaticIpSettings& settings);
+};
+
+}  // namespace domes
@@ -6,6 +6,7 @@
 #include "traceBuffer.hpp"
 
 #include "esp_log.h"
+#include "freertos/task.h"
 
 #include <cstring>
 
@@ -50,6 +51,26 @@ esp_err_t TraceBuffer::init() {
     return ESP_OK;
 }
 
+esp_err_t TraceBuffer::resize(size_t bufferSize) {
+    bool wasPaused = paused_.load();
+    paused_.store(true);
+    initialized_.store(false);
+
+    // Let a record() that already passed the checks finish with the old buffer
+    vTaskDelay(pdMS_TO_TICKS(1));
+
+    if (ringBuf_ != nullptr) {
+        vRingbufferDelete(ringBuf_);
+        ringBuf_ = nullptr;
+    }
+    droppedCount_.store(0);
+    bufferSize_ = bufferSize;
+
+    esp_err_t err = init();
+    paused_.store(wasPaused);
+    return err;
+}
+
 bool TraceBuffer::record(const TraceEvent& event) {
     if (!initialized_.load() || paused_.load()) {
         return false;
@@ -128,9 +149,8 @@ size_t TraceBuffer::count() const {
     }
 
     // Account for ring buffer overhead (8 bytes per item for NOSPLIT type)
-    constexpr size_t kItemOverhead = 8;
     size_t usedSize = bufferSize_ - freeSize;
-    return usedSize / (kEventSize + kItemOverhead);
+    return usedSize / kBytesPerEvent;
 }
 
 void TraceBuffer::clear() {
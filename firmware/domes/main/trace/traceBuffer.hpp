@@ -39,6 +39,12 @@ public:
     /// Maximum events that can be stored (approximate, due to ring buffer overhead)
     static constexpr size_t kMaxEvents = kDefaultBufferSize / kEventSize;
 
+    /// Ring buffer header stored with each item (NOSPLIT type)
+    static constexpr size_t kItemOverhead = 8;
+
+    /// Ring buffer bytes taken by one stored event
+    static constexpr size_t kBytesPerEvent = kEventSize + kItemOverhead;
+
     /**
      * @brief Construct trace buffer
      *
@@ -62,6 +68,18 @@ public:
      */
     esp_err_t init();
 
+    /**
+     * @brief Reallocate the ring buffer with a new size
+     *
+     * All recorded events and the dropped count are discarded. Events
+     * recorded during the resize are dropped.
+     *
+     * @param bufferSize New size of the ring buffer in bytes
+     * @return ESP_OK on success, ESP_ERR_NO_MEM if the buffer could not be
+     *         allocated (the buffer is then left uninitialized)
+     */
+    esp_err_t resize(size_t bufferSize);
+
     /**
      * @brief Record an event to the buffer (task context)
      *
@@ -111,6 +129,11 @@ public:
      */
     bool isInitialized() const { return initialized_.load(); }
 
+    /**
+     * @brief Get the ring buffer size in bytes
+     */
+    size_t bufferSize() const { return bufferSize_; }
+
     /**
      * @brief Pause recording (for consistent dump)
      *
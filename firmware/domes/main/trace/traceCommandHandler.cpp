@@ -8,16 +8,26 @@
 
 #include "traceCommandHandler.hpp"
 
+#include "esp_heap_caps.h"
 #include "esp_log.h"
+#include "pb_decode.h"
 #include "pb_encode.h"
 #include "protocol/frameCodec.hpp"
+#include "traceApi.hpp"
 #include "traceRecorder.hpp"
+#include "utils/crc32.hpp"
 
+#include <algorithm>
 #include <array>
 #include <cstring>
+#include <memory>
+#include <new>
 
 namespace {
 constexpr const char* kTag = "trace_cmd";
+
+/// Heap left free after a buffer resize, for WiFi/BLE and other allocations
+constexpr size_t kResizeHeapReserve = 32 * 1024;
 }
 
 namespace domes::trace {
@@ -26,9 +36,6 @@ CommandHandler::CommandHandler(ITransport& transport, uint8_t podId)
     : transport_(transport), podId_(podId) {}
 
 bool CommandHandler::handleCommand(uint8_t type, const uint8_t* payload, size_t len) {
-    (void)payload;
-    (void)len;
-
     auto msgType = static_cast<MsgType>(type);
 
     switch (msgType) {
@@ -41,7 +48,7 @@ bool CommandHandler::handleCommand(uint8_t type, const uint8_t* payload, size_t
             return true;
 
         case MsgType::kDump:
-            handleDump();
+            handleDump(payload, len);
             return true;
 
         case MsgType::kClear:
@@ -52,6 +59,14 @@ bool CommandHandler::handleCommand(uint8_t type, const uint8_t* payload, size_t
             handleStatus();
             return true;
 
+        case MsgType::kSetBufferSizeReq:
+            handleSetBufferSize(payload, len);
+            return true;
+
+        case MsgType::kAnnotateReq:
+            handleAnnotate(payload, len);
+            return true;
+
         default:
             ESP_LOGW(kTag, "Unknown trace command: 0x%02X", type);
             return false;
@@ -92,7 +107,7 @@ void CommandHandler::handleStop() {
     sendAck(Status::kOk);
 }
 
-void CommandHandler::handleDump() {
+void CommandHandler::handleDump(const uint8_t* payload, size_t len) {
     ESP_LOGI(kTag, "Received TRACE_DUMP");
 
     if (!Recorder::isInitialized()) {
@@ -100,6 +115,18 @@ void CommandHandler::handleDump() {
         return;
     }
 
+    // Empty payload (older hosts) dumps everything
+    domes_trace_DumpRequest req = domes_trace_DumpRequest_init_zero;
+    if (len > 0) {
+        pb_istream_t stream = pb_istream_from_buffer(payload, len);
+        if (!pb_decode(&stream, domes_trace_DumpRequest_fields, &req)) {
+            ESP_LOGW(kTag, "Failed to decode DumpRequest: %s", PB_GET_ERROR(&stream));
+            sendAck(Status::kError);
+            return;
+        }
+    }
+    const uint32_t sinceUs = req.since_us;
+
     // Pause recording during dump
     bool wasEnabled = Recorder::isEnabled();
     Recorder::setEnabled(false);
@@ -120,11 +147,19 @@ void CommandHandler::handleDump() {
 
     ESP_LOGI(kTag, "Dumping ~%lu events", static_cast<unsigned long>(eventCount));
 
-    // Peek first event for start timestamp
+    // Peek first event for start timestamp. Events at or before since_us are
+    // consumed like the rest of the dump, but not sent.
     uint32_t startTs = 0;
+    uint32_t skippedCount = 0;
     TraceEvent firstEvent;
-    if (Recorder::buffer().read(&firstEvent, 0)) {
-        startTs = firstEvent.timestamp;
+    bool haveFirst = false;
+    while (Recorder::buffer().read(&firstEvent, 0)) {
+        if (firstEvent.timestamp > sinceUs) {
+            startTs = firstEvent.timestamp;
+            haveFirst = true;
+            break;
+        }
+        skippedCount++;
     }
 
     // Suppress ALL logging during binary data transfer to prevent
@@ -132,37 +167,40 @@ void CommandHandler::handleDump() {
     esp_log_level_set("*", ESP_LOG_NONE);
 
     // Send session info (protobuf-encoded metadata)
-    sendSessionInfo(eventCount, droppedCount, startTs, 0);
+    sendSessionInfo(eventCount - skippedCount, droppedCount, startTs, 0);
 
     // Stream events directly from ring buffer in chunks
     std::array<TraceEvent, kEventsPerChunk> chunk;
     uint32_t offset = 0;
-    uint32_t checksum = 0;
+    uint32_t checksum = kCrc32Init;
     uint32_t totalSent = 0;
     uint32_t endTs = startTs;
 
     // First chunk starts with the already-read event
-    chunk[0] = firstEvent;
-    size_t chunkFill = 1;
-
-    // Update checksum for first event
-    {
-        const auto* bytes = reinterpret_cast<const uint8_t*>(&firstEvent);
-        for (size_t j = 0; j < sizeof(TraceEvent); ++j) {
-            checksum += bytes[j];
-        }
+    size_t chunkFill = 0;
+    if (haveFirst) {
+        chunk[0] = firstEvent;
+        chunkFill = 1;
+
+        // Update checksum for first event
+        checksum = crc32Update(reinterpret_cast<const uint8_t*>(&firstEvent),
+                               sizeof(TraceEvent), checksum);
     }
 
     TraceEvent event;
     while (Recorder::buffer().read(&event, 0)) {
+        // Tasks record concurrently, so timestamps are not strictly ordered
+        if (event.timestamp <= sinceUs) {
+            skippedCount++;
+            continue;
+        }
+
         chunk[chunkFill] = event;
         endTs = event.timestamp;
 
         // Update checksum
-        const auto* bytes = reinterpret_cast<const uint8_t*>(&event);
-        for (size_t j = 0; j < sizeof(TraceEvent); ++j) {
-            checksum += bytes[j];
-        }
+        checksum = crc32Update(reinterpret_cast<const uint8_t*>(&event), sizeof(TraceEvent),
+                               checksum);
 
         chunkFill++;
 
@@ -182,13 +220,15 @@ void CommandHandler::handleDump() {
     }
 
     // Send end marker with actual total
+    checksum = crc32Finalize(checksum);
     sendDumpComplete(totalSent, checksum);
 
     // Restore logging
     esp_log_level_set("*", ESP_LOG_INFO);
 
-    ESP_LOGI(kTag, "Dump complete: %lu events, checksum 0x%08lX",
-             static_cast<unsigned long>(totalSent), static_cast<unsigned long>(checksum));
+    ESP_LOGI(kTag, "Dump complete: %lu events, %lu skipped by since_us, checksum 0x%08lX",
+             static_cast<unsigned long>(totalSent), static_cast<unsigned long>(skippedCount),
+             static_cast<unsigned long>(checksum));
 
     // Clear buffer and reset dropped count
     Recorder::buffer().resetDroppedCount();
@@ -209,6 +249,7 @@ void CommandHandler::handleClear() {
     }
 
     Recorder::buffer().clear();
+    Recorder::clearAnnotations();
     sendAck(Status::kOk);
 }
 
@@ -223,6 +264,110 @@ void CommandHandler::handleStatus() {
     sendStatusResponse();
 }
 
+void CommandHandler::handleSetBufferSize(const uint8_t* payload, size_t len) {
+    ESP_LOGI(kTag, "Received TRACE_SET_BUFFER_SIZE");
+
+    domes_trace_SetBufferSizeResponse resp = domes_trace_SetBufferSizeResponse_init_zero;
+
+    if (!Recorder::isInitialized()) {
+        resp.status = static_cast<domes_trace_Status>(Status::kNotInit);
+        sendSetBufferSizeResponse(resp);
+        return;
+    }
+
+    domes_trace_SetBufferSizeRequest req = domes_trace_SetBufferSizeRequest_init_zero;
+    pb_istream_t stream = pb_istream_from_buffer(payload, len);
+    if (!pb_decode(&stream, domes_trace_SetBufferSizeRequest_fields, &req) ||
+        req.event_count == 0) {
+        ESP_LOGW(kTag, "Invalid SetBufferSizeRequest");
+        resp.status = static_cast<domes_trace_Status>(Status::kError);
+        sendSetBufferSizeResponse(resp);
+        return;
+    }
+
+    TraceBuffer& buffer = Recorder::buffer();
+    size_t oldSize = buffer.bufferSize();
+
+    // The old buffer is freed first, so its bytes count towards the new one
+    size_t largestBlock = heap_caps_get_largest_free_block(MALLOC_CAP_8BIT);
+    size_t available = largestBlock + oldSize;
+    available = available > kResizeHeapReserve ? available - kResizeHeapReserve : 0;
+
+    size_t allocatedCount =
+        std::min<size_t>(req.event_count, available / TraceBuffer::kBytesPerEvent);
+    if (allocatedCount == 0) {
+        ESP_LOGW(kTag, "No heap for a %lu-event buffer",
+                 static_cast<unsigned long>(req.event_count));
+        resp.status = static_cast<domes_trace_Status>(Status::kNoMemory);
+        sendSetBufferSizeResponse(resp);
+        return;
+    }
+
+    bool hadEvents = buffer.count() > 0;
+    size_t newSize = allocatedCount * TraceBuffer::kBytesPerEvent;
+
+    if (buffer.resize(newSize) != ESP_OK) {
+        ESP_LOGE(kTag, "Failed to allocate %zu-byte trace buffer, restoring %zu", newSize,
+                 oldSize);
+        buffer.resize(oldSize);
+        resp.status = static_cast<domes_trace_Status>(Status::kNoMemory);
+        resp.was_cleared = hadEvents;
+        sendSetBufferSizeResponse(resp);
+        return;
+    }
+
+    ESP_LOGI(kTag, "Trace buffer resized to %zu events (%zu bytes)", allocatedCount, newSize);
+    Recorder::clearAnnotations();
+
+    resp.status = static_cast<domes_trace_Status>(Status::kOk);
+    resp.allocated_count = static_cast<uint32_t>(allocatedCount);
+    resp.bytes_used = static_cast<uint32_t>(newSize);
+    resp.was_cleared = hadEvents;
+    sendSetBufferSizeResponse(resp);
+}
+
+void CommandHandler::handleAnnotate(const uint8_t* payload, size_t len) {
+    ESP_LOGI(kTag, "Received TRACE_ANNOTATE");
+
+    domes_trace_AnnotateResponse resp = domes_trace_AnnotateResponse_init_zero;
+
+    if (!Recorder::isInitialized()) {
+        resp.status = static_cast<domes_trace_Status>(Status::kNotInit);
+        sendAnnotateResponse(resp);
+        return;
+    }
+
+    domes_trace_AnnotateRequest req = domes_trace_AnnotateRequest_init_zero;
+    pb_istream_t stream = pb_istream_from_buffer(payload, len);
+    if (!pb_decode(&stream, domes_trace_AnnotateRequest_fields, &req) || req.label[0] == '\0') {
+        ESP_LOGW(kTag, "Invalid AnnotateRequest");
+        resp.status = static_cast<domes_trace_Status>(Status::kError);
+        sendAnnotateResponse(resp);
+        return;
+    }
+
+    // The marker event would be dropped while recording is off
+    if (!Recorder::isEnabled()) {
+        resp.status = static_cast<domes_trace_Status>(Status::kAlreadyOff);
+        sendAnnotateResponse(resp);
+        return;
+    }
+
+    uint32_t hash = fnv1aHash(req.label, std::strlen(req.label));
+    uint32_t marker = Recorder::nextMarker();
+    TraceEvent event = makeEvent(EventType::kInstant, Category::kUser, hash, marker);
+    Recorder::record(event);
+    Recorder::addAnnotation(hash, req.label);
+
+    ESP_LOGI(kTag, "Marker #%lu '%s' at %lu us", static_cast<unsigned long>(marker), req.label,
+             static_cast<unsigned long>(event.timestamp));
+
+    resp.status = static_cast<domes_trace_Status>(Status::kOk);
+    resp.marker = marker;
+    resp.timestamp_us = event.timestamp;
+    sendAnnotateResponse(resp);
+}
+
 // ============================================================================
 // Protobuf-encoded response senders
 // ============================================================================
@@ -243,13 +388,25 @@ void CommandHandler::sendAck(Status status) {
 
 void CommandHandler::sendSessionInfo(uint32_t eventCount, uint32_t droppedCount,
                                      uint32_t startTs, uint32_t endTs) {
-    domes_trace_TraceSessionInfo msg = domes_trace_TraceSessionInfo_init_zero;
+    static_assert(domes_trace_TraceSessionInfo_size <= kMaxPayloadSize,
+                  "TraceSessionInfo must fit in one frame");
+
+    // Task and marker label tables make the message too large for the task stack
+    auto msgPtr = std::unique_ptr<domes_trace_TraceSessionInfo>(
+        new (std::nothrow) domes_trace_TraceSessionInfo(domes_trace_TraceSessionInfo_init_zero));
+    auto buf = std::unique_ptr<uint8_t[]>(new (std::nothrow) uint8_t[kMaxPayloadSize]);
+    if (!msgPtr || !buf) {
+        ESP_LOGE(kTag, "No memory for TraceSessionInfo");
+        return;
+    }
+    domes_trace_TraceSessionInfo& msg = *msgPtr;
+
     msg.pod_id = podId_;
     msg.event_count = eventCount;
     msg.dropped_count = droppedCount;
     msg.start_timestamp_us = startTs;
     msg.end_timestamp_us = endTs;
-    msg.buffer_size_bytes = TraceBuffer::kDefaultBufferSize;
+    msg.buffer_size_bytes = Recorder::buffer().bufferSize();
     msg.clock_offset_us = 0;  // TODO: populate from ESP-NOW sync
 
     // Fill task entries
@@ -265,14 +422,26 @@ void CommandHandler::sendSessionInfo(uint32_t eventCount, uint32_t droppedCount,
     }
     msg.tasks_count = taskIdx;
 
-    std::array<uint8_t, kMaxProtobufPayload> buf;
-    pb_ostream_t stream = pb_ostream_from_buffer(buf.data(), buf.size());
+    // Fill marker labels
+    const auto& annotations = Recorder::getAnnotations();
+    size_t annotationIdx = 0;
+    for (const auto& entry : annotations) {
+        if (entry.valid && annotationIdx < sizeof(msg.annotations) / sizeof(msg.annotations[0])) {
+            msg.annotations[annotationIdx].hash = entry.hash;
+            std::strncpy(msg.annotations[annotationIdx].label, entry.label,
+                         sizeof(msg.annotations[annotationIdx].label) - 1);
+            annotationIdx++;
+        }
+    }
+    msg.annotations_count = annotationIdx;
+
+    pb_ostream_t stream = pb_ostream_from_buffer(buf.get(), kMaxPayloadSize);
     if (!pb_encode(&stream, domes_trace_TraceSessionInfo_fields, &msg)) {
         ESP_LOGE(kTag, "Failed to encode TraceSessionInfo: %s", PB_GET_ERROR(&stream));
         return;
     }
 
-    sendFrame(MsgType::kSessionInfo, buf.data(), stream.bytes_written);
+    sendFrame(MsgType::kSessionInfo, buf.get(), stream.bytes_written);
 }
 
 void CommandHandler::sendDataChunk(uint32_t offset, const TraceEvent* events, size_t count) {
@@ -317,7 +486,7 @@ void CommandHandler::sendStatusResponse() {
     msg.streaming = Recorder::isStreaming();
     msg.event_count = static_cast<uint32_t>(Recorder::buffer().count());
     msg.dropped_count = Recorder::buffer().droppedCount();
-    msg.buffer_size = TraceBuffer::kDefaultBufferSize;
+    msg.buffer_size = Recorder::buffer().bufferSize();
     msg.stream_category_mask = 0;
 
     std::array<uint8_t, 32> buf;
@@ -330,8 +499,31 @@ void CommandHandler::sendStatusResponse() {
     sendFrame(MsgType::kStatusResp, buf.data(), stream.bytes_written);
 }
 
+void CommandHandler::sendSetBufferSizeResponse(const domes_trace_SetBufferSizeResponse& msg) {
+    std::array<uint8_t, domes_trace_SetBufferSizeResponse_size> buf;
+    pb_ostream_t stream = pb_ostream_from_buffer(buf.data(), buf.size());
+    if (!pb_encode(&stream, domes_trace_SetBufferSizeResponse_fields, &msg)) {
+        ESP_LOGE(kTag, "Failed to encode SetBufferSizeResponse");
+        return;
+    }
+
+    sendFrame(MsgType::kSetBufferSizeRsp, buf.data(), stream.bytes_written);
+}
+
+void CommandHandler::sendAnnotateResponse(const domes_trace_AnnotateResponse& msg) {
+    std::array<uint8_t, domes_trace_AnnotateResponse_size> buf;
+    pb_ostream_t stream = pb_ostream_from_buffer(buf.data(), buf.size());
+    if (!pb_encode(&stream, domes_trace_AnnotateResponse_fields, &msg)) {
+        ESP_LOGE(kTag, "Failed to encode AnnotateResponse");
+        return;
+    }
+
+    sendFrame(MsgType::kAnnotateRsp, buf.data(), stream.bytes_written);
+}
+
 bool CommandHandler::sendFrame(MsgType type, const uint8_t* payload, size_t len) {
-    std::array<uint8_t, kMaxFrameSize> frameBuf;
+    // Session info can use the full frame payload, beyond the trace kMaxFrameSize
+    std::array<uint8_t, domes::kMaxFrameSize> frameBuf;
     size_t frameLen = 0;
 
     TransportError err = encodeFrame(static_cast<uint8_t>(type), payload, len, frameBuf.data(),
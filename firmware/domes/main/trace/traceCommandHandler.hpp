@@ -45,9 +45,11 @@ public:
 private:
     void handleStart();
     void handleStop();
-    void handleDump();
+    void handleDump(const uint8_t* payload, size_t len);
     void handleClear();
     void handleStatus();
+    void handleSetBufferSize(const uint8_t* payload, size_t len);
+    void handleAnnotate(const uint8_t* payload, size_t len);
 
     /**
      * @brief Send ACK response (protobuf AckResponse)
@@ -75,6 +77,16 @@ private:
      */
     void sendStatusResponse();
 
+    /**
+     * @brief Send buffer resize result (protobuf SetBufferSizeResponse)
+     */
+    void sendSetBufferSizeResponse(const domes_trace_SetBufferSizeResponse& msg);
+
+    /**
+     * @brief Send marker result (protobuf AnnotateResponse)
+     */
+    void sendAnnotateResponse(const domes_trace_AnnotateResponse& msg);
+
     /**
      * @brief Send a frame with given type and payload
      */
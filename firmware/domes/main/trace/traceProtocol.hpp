@@ -40,6 +40,10 @@ enum class MsgType : uint8_t {
     kStreamData  = domes_trace_MsgType_MSG_TYPE_STREAM_DATA,
     kSessionInfo = domes_trace_MsgType_MSG_TYPE_SESSION_INFO,
     kAck         = domes_trace_MsgType_MSG_TYPE_ACK,
+    kSetBufferSizeReq = domes_trace_MsgType_MSG_TYPE_SET_BUFFER_SIZE_REQ,
+    kSetBufferSizeRsp = domes_trace_MsgType_MSG_TYPE_SET_BUFFER_SIZE_RSP,
+    kAnnotateReq = domes_trace_MsgType_MSG_TYPE_ANNOTATE_REQ,
+    kAnnotateRsp = domes_trace_MsgType_MSG_TYPE_ANNOTATE_RSP,
 };
 
 /**
@@ -51,6 +55,7 @@ enum class Status : uint8_t {
     kAlreadyOn   = domes_trace_Status_STATUS_ALREADY_ON,
     kAlreadyOff  = domes_trace_Status_STATUS_ALREADY_OFF,
     kBufferEmpty = domes_trace_Status_STATUS_BUFFER_EMPTY,
+    kNoMemory    = domes_trace_Status_STATUS_NO_MEMORY,
     kError       = domes_trace_Status_STATUS_ERROR,
 };
 
@@ -59,7 +64,7 @@ enum class Status : uint8_t {
  */
 inline bool isTraceMessage(uint8_t type) {
     return (type >= static_cast<uint8_t>(MsgType::kStart) &&
-            type <= static_cast<uint8_t>(MsgType::kAck));
+            type <= static_cast<uint8_t>(MsgType::kAnnotateRsp));
 }
 
 /**
@@ -72,6 +77,7 @@ inline const char* statusToString(Status status) {
         case Status::kAlreadyOn:   return "already-on";
         case Status::kAlreadyOff:  return "already-off";
         case Status::kBufferEmpty: return "buffer-empty";
+        case Status::kNoMemory:    return "no-memory";
         case Status::kError:       return "error";
         default:                   return "unknown";
     }
@@ -87,7 +93,4 @@ constexpr size_t kMaxTraceDataPayload = 280;
 /// Maximum frame size for trace messages
 constexpr size_t kMaxFrameSize = 512;
 
-/// Maximum protobuf encoding buffer
-constexpr size_t kMaxProtobufPayload = 512;
-
 }  // namespace domes::trace
@@ -21,6 +21,8 @@ std::atomic<bool> Recorder::enabled_{false};
 std::atomic<bool> Recorder::initialized_{false};
 std::array<TaskNameEntry, kMaxRegisteredTasks> Recorder::taskNames_{};
 size_t Recorder::taskNameCount_{0};
+std::array<AnnotationEntry, kMaxAnnotations> Recorder::annotations_{};
+std::atomic<uint32_t> Recorder::markerCount_{0};
 std::atomic<Recorder::StreamCallback> Recorder::streamCallback_{nullptr};
 
 esp_err_t Recorder::init(size_t bufferSize) {
@@ -46,6 +48,8 @@ esp_err_t Recorder::init(size_t bufferSize) {
     }
     taskNameCount_ = 0;
 
+    clearAnnotations();
+
     initialized_.store(true);
     enabled_.store(false);  // Start disabled by default
 
@@ -174,6 +178,48 @@ size_t Recorder::getRegisteredTaskCount() {
     return taskNameCount_;
 }
 
+void Recorder::addAnnotation(uint32_t hash, const char* label) {
+    if (!initialized_.load() || label == nullptr) {
+        return;
+    }
+
+    // Repeated labels share one entry
+    for (const auto& entry : annotations_) {
+        if (entry.valid && entry.hash == hash) {
+            return;
+        }
+    }
+
+    for (auto& entry : annotations_) {
+        if (!entry.valid) {
+            entry.hash = hash;
+            strncpy(entry.label, label, kMaxAnnotationLength - 1);
+            entry.label[kMaxAnnotationLength - 1] = '\0';
+            entry.valid = true;
+            return;
+        }
+    }
+
+    ESP_LOGW(kTag, "Marker label table full, cannot keep '%s'", label);
+}
+
+const std::array<AnnotationEntry, kMaxAnnotations>& Recorder::getAnnotations() {
+    return annotations_;
+}
+
+uint32_t Recorder::nextMarker() {
+    return markerCount_.fetch_add(1) + 1;
+}
+
+void Recorder::clearAnnotations() {
+    for (auto& entry : annotations_) {
+        entry.valid = false;
+        entry.hash = 0;
+        entry.label[0] = '\0';
+    }
+    markerCount_.store(0);
+}
+
 void Recorder::setStreamCallback(StreamCallback cb) {
     streamCallback_.store(cb, std::memory_order_release);
 }
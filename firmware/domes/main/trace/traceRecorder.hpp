@@ -8,6 +8,7 @@
  * - Initialization and shutdown
  * - Enable/disable recording
  * - Task name registration
+ * - User marker labels
  * - Access to the trace buffer for dump operations
  */
 
@@ -39,6 +40,22 @@ struct TaskNameEntry {
     bool valid;                     ///< Entry is valid
 };
 
+/// Maximum number of user marker labels kept for trace metadata
+/// (matches the TraceSessionInfo.annotations max_count)
+constexpr size_t kMaxAnnotations = 4;
+
+/// Maximum marker label length (including null terminator)
+constexpr size_t kMaxAnnotationLength = 33;
+
+/**
+ * @brief User marker label entry for trace metadata
+ */
+struct AnnotationEntry {
+    uint32_t hash;                     ///< FNV-1a hash of the label (marker event arg1)
+    char label[kMaxAnnotationLength];  ///< Marker label (null-terminated)
+    bool valid;                        ///< Entry is valid
+};
+
 /**
  * @brief Singleton trace recorder
  *
@@ -167,6 +184,37 @@ public:
      */
     static size_t getRegisteredTaskCount();
 
+    /**
+     * @brief Keep a user marker label for trace output
+     *
+     * Labels are listed in the session info so the dump can name marker
+     * events by their hash. If the table is full the label is dropped and
+     * the marker is shown by number only.
+     *
+     * @param hash FNV-1a hash of the label
+     * @param label Marker label (will be truncated if too long)
+     */
+    static void addAnnotation(uint32_t hash, const char* label);
+
+    /**
+     * @brief Get all kept marker labels
+     *
+     * @return Reference to the marker label table
+     */
+    static const std::array<AnnotationEntry, kMaxAnnotations>& getAnnotations();
+
+    /**
+     * @brief Allocate the next marker number (counting from 1)
+     */
+    static uint32_t nextMarker();
+
+    /**
+     * @brief Forget all marker labels and restart marker numbering
+     *
+     * Called when the trace buffer is cleared.
+     */
+    static void clearAnnotations();
+
     /**
      * @brief Callback type for live trace streaming
      *
@@ -197,6 +245,8 @@ private:
     static std::atomic<bool> initialized_;
     static std::array<TaskNameEntry, kMaxRegisteredTasks> taskNames_;
     static size_t taskNameCount_;
+    static std::array<AnnotationEntry, kMaxAnnotations> annotations_;
+    static std::atomic<uint32_t> markerCount_;
     static std::atomic<StreamCallback> streamCallback_;
 };
 
@@ -323,17 +323,23 @@ TransportError BleOtaService::init() {
         return TransportError::kIoError;
     }
 
-    // Build dynamic device name from pod_id (NVS) or BT MAC suffix
+    // Build dynamic device name from custom name or pod_id (NVS), or BT MAC suffix
     {
         char bleName[32] = {};
+        size_t nameLen = sizeof(bleName) - 1;
+        bool hasCustomName = false;
         domes::infra::NvsConfig nvsConfig;
         uint8_t podId = 0;
         if (nvsConfig.open(domes::infra::nvs_ns::kConfig) == ESP_OK) {
+            const char* nameKey = domes::infra::config_key::kDeviceName;
+            hasCustomName = nvsConfig.getBlob(nameKey, bleName, nameLen) == ESP_OK && nameLen > 0;
             podId = nvsConfig.getOrDefault<uint8_t>(domes::infra::config_key::kPodId, 0);
             nvsConfig.close();
         }
 
-        if (podId > 0) {
+        if (hasCustomName) {
+            bleName[nameLen] = '\0';
+        } else if (podId > 0) {
             snprintf(bleName, sizeof(bleName), "DOMES-Pod-%02u", podId);
         } else {
             // Fall back to last 2 bytes of Bluetooth MAC address
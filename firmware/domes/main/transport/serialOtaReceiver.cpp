@@ -51,6 +51,11 @@ void SerialOtaReceiver::run() {
         size_t rxLen = rxBuf.size();
         TransportError err = transport_.receive(rxBuf.data(), &rxLen, 100);
 
+        // Push subscribed events at most one receive timeout late
+        if (configHandler_) {
+            configHandler_->pollEvents();
+        }
+
         if (err == TransportError::kTimeout) {
             continue;
         }
@@ -97,7 +102,7 @@ void SerialOtaReceiver::run() {
                         break;
 
                     case OtaMsgType::kEnd:
-                        handleOtaEnd();
+                        handleOtaEnd(payload, payloadLen);
                         break;
 
                     case OtaMsgType::kAbort:
@@ -238,9 +243,15 @@ void SerialOtaReceiver::handleOtaData(const uint8_t* payload, size_t len) {
     sendAck(OtaStatus::kOk, expectedOffset_);
 }
 
-void SerialOtaReceiver::handleOtaEnd() {
+void SerialOtaReceiver::handleOtaEnd(const uint8_t* payload, size_t len) {
     ESP_LOGI(TAG, "Received OTA_END");
 
+    uint8_t flags = 0;
+    if (!isOk(deserializeOtaEnd(payload, len, &flags))) {
+        ESP_LOGW(TAG, "Invalid OTA_END payload, ignoring flags");
+        flags = 0;
+    }
+
     if (!otaInProgress_.load()) {
         ESP_LOGW(TAG, "Received OTA_END without OTA_BEGIN");
         sendAck(OtaStatus::kAborted, 0);
@@ -274,9 +285,15 @@ void SerialOtaReceiver::handleOtaEnd() {
         return;
     }
 
-    ESP_LOGI(TAG, "OTA complete! Rebooting in 1 second...");
-
     otaInProgress_.store(false);
+
+    if (flags & kOtaEndNoReboot) {
+        ESP_LOGI(TAG, "OTA complete! New image boots on next reboot");
+        sendAck(OtaStatus::kOk, bytesReceived_);
+        return;
+    }
+
+    ESP_LOGI(TAG, "OTA complete! Rebooting in 1 second...");
     sendAck(OtaStatus::kOk, bytesReceived_);
 
     // Give time for ACK to be sent
@@ -164,8 +164,11 @@ private:
 
     /**
      * @brief Handle OTA_END message
+     *
+     * @param payload Message payload (optional flags byte)
+     * @param len Payload length
      */
-    void handleOtaEnd();
+    void handleOtaEnd(const uint8_t* payload, size_t len);
 
     /**
      * @brief Send ACK response
@@ -194,6 +194,9 @@ void TcpConfigServer::handleClient(int clientSock) {
         size_t rxLen = rxBuf.size();
         err = transport.receive(rxBuf.data(), &rxLen, 100);
 
+        // Push subscribed events at most one receive timeout late
+        handler.pollEvents();
+
         if (err == TransportError::kTimeout) {
             continue;
         }
@@ -8,6 +8,7 @@
 #include <gtest/gtest.h>
 #include "config/configProtocol.hpp"
 #include "config.pb.h"
+#include "protocol/frameCodec.hpp"
 #include "pb_encode.h"
 #include "pb_decode.h"
 
@@ -55,7 +56,7 @@ TEST(ConfigMsgType, IsConfigMessageOutOfRange) {
     EXPECT_FALSE(isConfigMessage(0x00));  // Unknown
     EXPECT_FALSE(isConfigMessage(0xFF));  // Unknown
     EXPECT_FALSE(isConfigMessage(0x1F));  // Just before config range
-    EXPECT_FALSE(isConfigMessage(0x50));  // Just past sim mode range
+    EXPECT_FALSE(isConfigMessage(0x6B));  // Just past WiFi static IP range
 }
 
 TEST(ConfigMsgType, IsConfigMessageObservabilityRange) {
@@ -309,6 +310,7 @@ TEST(Protobuf, GetSystemInfoResponseEncodeDecode) {
     resp.boot_count = 42;
     resp.mode = domes_config_SystemMode_SYSTEM_MODE_IDLE;
     resp.feature_mask = 0x000000EE;
+    resp.max_payload = 1024;
 
     std::array<uint8_t, 128> buffer{};
     pb_ostream_t ostream = pb_ostream_from_buffer(buffer.data(), buffer.size());
@@ -325,6 +327,7 @@ TEST(Protobuf, GetSystemInfoResponseEncodeDecode) {
     EXPECT_EQ(decoded.boot_count, 42u);
     EXPECT_EQ(decoded.mode, domes_config_SystemMode_SYSTEM_MODE_IDLE);
     EXPECT_EQ(decoded.feature_mask, 0x000000EEu);
+    EXPECT_EQ(decoded.max_payload, 1024u);
 }
 
 // =============================================================================
@@ -477,8 +480,75 @@ TEST(ConfigMsgType, IsConfigMessageTouchInjectionRange) {
     EXPECT_TRUE(isConfigMessage(0x4F));  // SetSimModeRsp
 }
 
+TEST(ConfigMsgType, IsConfigMessageBatchRange) {
+    // Batch commands (0x50-0x51) should be config messages
+    EXPECT_TRUE(isConfigMessage(0x50));  // BatchReq
+    EXPECT_TRUE(isConfigMessage(0x51));  // BatchRsp
+}
+
+TEST(ConfigMsgType, IsConfigMessageDeviceNameRange) {
+    // Device name commands (0x52-0x53) should be config messages
+    EXPECT_TRUE(isConfigMessage(0x52));  // SetDeviceNameReq
+    EXPECT_TRUE(isConfigMessage(0x53));  // SetDeviceNameRsp
+}
+
+TEST(ConfigMsgType, IsConfigMessageEventRange) {
+    // Event subscription commands (0x54-0x56) should be config messages
+    EXPECT_TRUE(isConfigMessage(0x54));  // SubscribeEventsReq
+    EXPECT_TRUE(isConfigMessage(0x55));  // SubscribeEventsRsp
+    EXPECT_TRUE(isConfigMessage(0x56));  // EventNotification
+}
+
+TEST(ConfigMsgType, IsConfigMessagePingRange) {
+    // Keepalive commands (0x57-0x58) should be config messages
+    EXPECT_TRUE(isConfigMessage(0x57));  // PingReq
+    EXPECT_TRUE(isConfigMessage(0x58));  // PingRsp
+}
+
+TEST(ConfigMsgType, IsConfigMessageBaudRateRange) {
+    // Baud rate commands (0x59-0x5A) should be config messages
+    EXPECT_TRUE(isConfigMessage(0x59));  // SetBaudRateReq
+    EXPECT_TRUE(isConfigMessage(0x5A));  // SetBaudRateRsp
+}
+
+TEST(ConfigMsgType, IsConfigMessageTaskListRange) {
+    // Task list commands (0x5B-0x5C) should be config messages
+    EXPECT_TRUE(isConfigMessage(0x5B));  // GetTaskListReq
+    EXPECT_TRUE(isConfigMessage(0x5C));  // GetTaskListRsp
+}
+
+TEST(ConfigMsgType, IsConfigMessagePartitionTableRange) {
+    // Partition table commands (0x5D-0x5E) should be config messages
+    EXPECT_TRUE(isConfigMessage(0x5D));  // GetPartitionTableReq
+    EXPECT_TRUE(isConfigMessage(0x5E));  // GetPartitionTableRsp
+}
+
+TEST(ConfigMsgType, IsConfigMessageCoreDumpRange) {
+    // Core dump commands (0x5F-0x64) should be config messages
+    EXPECT_TRUE(isConfigMessage(0x5F));  // CoreDumpReadReq
+    EXPECT_TRUE(isConfigMessage(0x60));  // CoreDumpReadRsp
+    EXPECT_TRUE(isConfigMessage(0x61));  // CoreDumpData
+    EXPECT_TRUE(isConfigMessage(0x62));  // CoreDumpEnd
+    EXPECT_TRUE(isConfigMessage(0x63));  // CoreDumpClearReq
+    EXPECT_TRUE(isConfigMessage(0x64));  // CoreDumpClearRsp
+}
+
+TEST(ConfigMsgType, IsConfigMessageWifiApRange) {
+    // WiFi access point commands (0x65-0x68) should be config messages
+    EXPECT_TRUE(isConfigMessage(0x65));  // WifiApConfigReq
+    EXPECT_TRUE(isConfigMessage(0x66));  // WifiApConfigRsp
+    EXPECT_TRUE(isConfigMessage(0x67));  // WifiApStatusReq
+    EXPECT_TRUE(isConfigMessage(0x68));  // WifiApStatusRsp
+}
+
+TEST(ConfigMsgType, IsConfigMessageWifiStaticIpRange) {
+    // WiFi static IP commands (0x69-0x6A) should be config messages
+    EXPECT_TRUE(isConfigMessage(0x69));  // WifiStaticIpReq
+    EXPECT_TRUE(isConfigMessage(0x6A));  // WifiStaticIpRsp
+}
+
 TEST(ConfigMsgType, GapValues0x4A0x4BAreInRange) {
-    // Gap values 0x4A-0x4B fall within the simple range check (0x20-0x4F).
+    // Gap values 0x4A-0x4B fall within the simple range check.
     // They're routed to the config handler but safely ignored by the switch.
     EXPECT_TRUE(isConfigMessage(0x4A));
     EXPECT_TRUE(isConfigMessage(0x4B));
@@ -560,3 +630,200 @@ TEST(Protobuf, SelfTestResponseEmpty) {
     EXPECT_EQ(decoded.tests_passed, 0u);
     EXPECT_EQ(decoded.results_count, 0u);
 }
+
+TEST(Protobuf, SetDeviceNameRequestEncodeDecode) {
+    domes_config_SetDeviceNameRequest req = domes_config_SetDeviceNameRequest_init_zero;
+    std::strncpy(req.name, "DOMES-Lobby", sizeof(req.name) - 1);
+
+    std::array<uint8_t, domes_config_SetDeviceNameRequest_size> buffer{};
+    pb_ostream_t ostream = pb_ostream_from_buffer(buffer.data(), buffer.size());
+    ASSERT_TRUE(pb_encode(&ostream, domes_config_SetDeviceNameRequest_fields, &req));
+
+    domes_config_SetDeviceNameRequest decoded = domes_config_SetDeviceNameRequest_init_zero;
+    pb_istream_t istream = pb_istream_from_buffer(buffer.data(), ostream.bytes_written);
+    ASSERT_TRUE(pb_decode(&istream, domes_config_SetDeviceNameRequest_fields, &decoded));
+
+    EXPECT_STREQ(decoded.name, "DOMES-Lobby");
+}
+
+TEST(Protobuf, SetDeviceNameRequestMaxLength) {
+    // Field holds kMaxDeviceNameLen chars plus the terminator
+    domes_config_SetDeviceNameRequest req = domes_config_SetDeviceNameRequest_init_zero;
+    EXPECT_EQ(sizeof(req.name), kMaxDeviceNameLen + 1);
+}
+
+TEST(Protobuf, EventNotificationEncodeDecode) {
+    domes_config_EventNotification event = domes_config_EventNotification_init_zero;
+    event.category = domes_config_EventCategory_EVENT_CATEGORY_FEATURE;
+    event.timestamp_ms = 12345;
+    event.has_feature = true;
+    event.feature.feature = domes_config_Feature_FEATURE_WIFI;
+    event.feature.enabled = true;
+
+    std::array<uint8_t, domes_config_EventNotification_size> buffer{};
+    pb_ostream_t ostream = pb_ostream_from_buffer(buffer.data(), buffer.size());
+    ASSERT_TRUE(pb_encode(&ostream, domes_config_EventNotification_fields, &event));
+
+    domes_config_EventNotification decoded = domes_config_EventNotification_init_zero;
+    pb_istream_t istream = pb_istream_from_buffer(buffer.data(), ostream.bytes_written);
+    ASSERT_TRUE(pb_decode(&istream, domes_config_EventNotification_fields, &decoded));
+
+    EXPECT_EQ(decoded.category, domes_config_EventCategory_EVENT_CATEGORY_FEATURE);
+    EXPECT_EQ(decoded.timestamp_ms, 12345u);
+    EXPECT_FALSE(decoded.has_led_pattern);
+    ASSERT_TRUE(decoded.has_feature);
+    EXPECT_EQ(decoded.feature.feature, domes_config_Feature_FEATURE_WIFI);
+    EXPECT_TRUE(decoded.feature.enabled);
+}
+
+TEST(Protobuf, GetTaskListResponseEncodeDecode) {
+    domes_config_GetTaskListResponse resp = domes_config_GetTaskListResponse_init_zero;
+    resp.tasks_count = 2;
+    std::strncpy(resp.tasks[0].name, "led_svc", sizeof(resp.tasks[0].name) - 1);
+    resp.tasks[0].state = domes_config_TaskState_TASK_STATE_BLOCKED;
+    resp.tasks[0].priority = 5;
+    resp.tasks[0].stack_watermark_bytes = 1200;
+    resp.tasks[0].runtime_percent = 2.5f;
+    resp.tasks[0].core_id = 1;
+    resp.tasks[0].has_core_id = true;
+    std::strncpy(resp.tasks[1].name, "IDLE0", sizeof(resp.tasks[1].name) - 1);
+    resp.tasks[1].state = domes_config_TaskState_TASK_STATE_READY;
+
+    std::array<uint8_t, domes_config_GetTaskListResponse_size> buffer{};
+    pb_ostream_t ostream = pb_ostream_from_buffer(buffer.data(), buffer.size());
+    ASSERT_TRUE(pb_encode(&ostream, domes_config_GetTaskListResponse_fields, &resp));
+
+    domes_config_GetTaskListResponse decoded = domes_config_GetTaskListResponse_init_zero;
+    pb_istream_t istream = pb_istream_from_buffer(buffer.data(), ostream.bytes_written);
+    ASSERT_TRUE(pb_decode(&istream, domes_config_GetTaskListResponse_fields, &decoded));
+
+    ASSERT_EQ(decoded.tasks_count, 2u);
+    EXPECT_STREQ(decoded.tasks[0].name, "led_svc");
+    EXPECT_EQ(decoded.tasks[0].state, domes_config_TaskState_TASK_STATE_BLOCKED);
+    EXPECT_EQ(decoded.tasks[0].stack_watermark_bytes, 1200u);
+    EXPECT_FLOAT_EQ(decoded.tasks[0].runtime_percent, 2.5f);
+    EXPECT_TRUE(decoded.tasks[0].has_core_id);
+    EXPECT_EQ(decoded.tasks[0].core_id, 1u);
+    EXPECT_STREQ(decoded.tasks[1].name, "IDLE0");
+    EXPECT_FALSE(decoded.tasks[1].has_core_id);
+}
+
+TEST(Protobuf, PartitionInfoHoldsFullLabel) {
+    // esp_partition_t labels are up to 16 chars
+    domes_config_GetPartitionTableResponse resp = domes_config_GetPartitionTableResponse_init_zero;
+    resp.partitions_count = 1;
+    std::strncpy(resp.partitions[0].name, "sixteen_chars_xx", sizeof(resp.partitions[0].name) - 1);
+    resp.partitions[0].type = domes_config_PartitionType_PARTITION_TYPE_APP;
+    resp.partitions[0].subtype = 0x10;
+    resp.partitions[0].offset = 0x20000;
+    resp.partitions[0].size = 0x1E0000;
+    resp.partitions[0].active = true;
+
+    std::array<uint8_t, domes_config_GetPartitionTableResponse_size> buffer{};
+    pb_ostream_t ostream = pb_ostream_from_buffer(buffer.data(), buffer.size());
+    ASSERT_TRUE(pb_encode(&ostream, domes_config_GetPartitionTableResponse_fields, &resp));
+
+    domes_config_GetPartitionTableResponse decoded =
+        domes_config_GetPartitionTableResponse_init_zero;
+    pb_istream_t istream = pb_istream_from_buffer(buffer.data(), ostream.bytes_written);
+    ASSERT_TRUE(pb_decode(&istream, domes_config_GetPartitionTableResponse_fields, &decoded));
+
+    ASSERT_EQ(decoded.partitions_count, 1u);
+    EXPECT_STREQ(decoded.partitions[0].name, "sixteen_chars_xx");
+    EXPECT_EQ(decoded.partitions[0].type, domes_config_PartitionType_PARTITION_TYPE_APP);
+    EXPECT_EQ(decoded.partitions[0].offset, 0x20000u);
+    EXPECT_TRUE(decoded.partitions[0].active);
+}
+
+TEST(Protobuf, CoreDumpChunkFitsInFrame) {
+    // A full 512-byte chunk must fit in one frame payload
+    domes_config_CoreDumpChunk chunk = domes_config_CoreDumpChunk_init_zero;
+    chunk.offset = 0x10000;
+    chunk.data.size = sizeof(chunk.data.bytes);
+    std::memset(chunk.data.bytes, 0xA5, sizeof(chunk.data.bytes));
+
+    std::array<uint8_t, domes_config_CoreDumpChunk_size> buffer{};
+    pb_ostream_t ostream = pb_ostream_from_buffer(buffer.data(), buffer.size());
+    ASSERT_TRUE(pb_encode(&ostream, domes_config_CoreDumpChunk_fields, &chunk));
+    EXPECT_LE(ostream.bytes_written, domes::kMaxPayloadSize);
+
+    domes_config_CoreDumpChunk decoded = domes_config_CoreDumpChunk_init_zero;
+    pb_istream_t istream = pb_istream_from_buffer(buffer.data(), ostream.bytes_written);
+    ASSERT_TRUE(pb_decode(&istream, domes_config_CoreDumpChunk_fields, &decoded));
+
+    EXPECT_EQ(decoded.offset, 0x10000u);
+    ASSERT_EQ(decoded.data.size, sizeof(chunk.data.bytes));
+    EXPECT_EQ(decoded.data.bytes[0], 0xA5);
+}
+
+TEST(Protobuf, WifiApConfigRequestHoldsLongestCredentials) {
+    // 32-char SSID and 63-char WPA2 passphrase, each plus the terminator
+    domes_config_WifiApConfigRequest req = domes_config_WifiApConfigRequest_init_zero;
+    EXPECT_EQ(sizeof(req.ssid), 33u);
+    EXPECT_EQ(sizeof(req.password), 64u);
+
+    std::memset(req.ssid, 's', sizeof(req.ssid) - 1);
+    std::memset(req.password, 'p', sizeof(req.password) - 1);
+    req.channel = 6;
+    req.max_connections = 4;
+
+    std::array<uint8_t, domes_config_WifiApConfigRequest_size> buffer{};
+    pb_ostream_t ostream = pb_ostream_from_buffer(buffer.data(), buffer.size());
+    ASSERT_TRUE(pb_encode(&ostream, domes_config_WifiApConfigRequest_fields, &req));
+
+    domes_config_WifiApConfigRequest decoded = domes_config_WifiApConfigRequest_init_zero;
+    pb_istream_t istream = pb_istream_from_buffer(buffer.data(), ostream.bytes_written);
+    ASSERT_TRUE(pb_decode(&istream, domes_config_WifiApConfigRequest_fields, &decoded));
+
+    EXPECT_EQ(std::strlen(decoded.ssid), 32u);
+    EXPECT_EQ(std::strlen(decoded.password), 63u);
+    EXPECT_EQ(decoded.channel, 6u);
+    EXPECT_EQ(decoded.max_connections, 4u);
+}
+
+// =============================================================================
+// Batch Protobuf Tests
+// =============================================================================
+
+TEST(Protobuf, BatchRequestEncodeDecode) {
+    domes_config_BatchRequest req = domes_config_BatchRequest_init_zero;
+    req.commands_count = 2;
+    req.commands[0].msg_type = static_cast<uint32_t>(MsgType::kGetModeReq);
+    req.commands[1].msg_type = static_cast<uint32_t>(MsgType::kSetPodIdReq);
+    req.commands[1].payload.bytes[0] = 0x08;
+    req.commands[1].payload.bytes[1] = 0x05;
+    req.commands[1].payload.size = 2;
+
+    std::array<uint8_t, 64> buffer{};
+    pb_ostream_t ostream = pb_ostream_from_buffer(buffer.data(), buffer.size());
+    ASSERT_TRUE(pb_encode(&ostream, domes_config_BatchRequest_fields, &req));
+
+    domes_config_BatchRequest decoded = domes_config_BatchRequest_init_zero;
+    pb_istream_t istream = pb_istream_from_buffer(buffer.data(), ostream.bytes_written);
+    ASSERT_TRUE(pb_decode(&istream, domes_config_BatchRequest_fields, &decoded));
+
+    ASSERT_EQ(decoded.commands_count, 2u);
+    EXPECT_EQ(decoded.commands[0].msg_type, static_cast<uint32_t>(MsgType::kGetModeReq));
+    EXPECT_EQ(decoded.commands[0].payload.size, 0u);
+    EXPECT_EQ(decoded.commands[1].msg_type, static_cast<uint32_t>(MsgType::kSetPodIdReq));
+    ASSERT_EQ(decoded.commands[1].payload.size, 2u);
+    EXPECT_EQ(decoded.commands[1].payload.bytes[1], 0x05);
+}
+
+TEST(Protobuf, BatchResponseEmptyEntry) {
+    // A command without a response is an entry with msg_type 0 and no payload
+    domes_config_BatchResponse resp = domes_config_BatchResponse_init_zero;
+    resp.responses_count = 1;
+
+    std::array<uint8_t, 16> buffer{};
+    pb_ostream_t ostream = pb_ostream_from_buffer(buffer.data(), buffer.size());
+    ASSERT_TRUE(pb_encode(&ostream, domes_config_BatchResponse_fields, &resp));
+
+    domes_config_BatchResponse decoded = domes_config_BatchResponse_init_zero;
+    pb_istream_t istream = pb_istream_from_buffer(buffer.data(), ostream.bytes_written);
+    ASSERT_TRUE(pb_decode(&istream, domes_config_BatchResponse_fields, &decoded));
+
+    ASSERT_EQ(decoded.responses_count, 1u);
+    EXPECT_EQ(decoded.responses[0].msg_type, 0u);
+    EXPECT_EQ(decoded.responses[0].payload.size, 0u);
+}
@@ -220,6 +220,39 @@ TEST_F(ModeManagerTest, GameToTriageInvalid) {
     EXPECT_EQ(mgr_->currentMode(), SystemMode::kGame);
 }
 
+// =============================================================================
+// Forced Transition Tests
+// =============================================================================
+
+TEST_F(ModeManagerTest, ForcedTransitionBypassesRules) {
+    mgr_->transitionTo(SystemMode::kIdle);
+    mgr_->transitionTo(SystemMode::kConnected);
+    mgr_->transitionTo(SystemMode::kGame);
+
+    // GAME -> TRIAGE is invalid unforced
+    EXPECT_TRUE(mgr_->forceTransitionTo(SystemMode::kTriage));
+    EXPECT_EQ(mgr_->currentMode(), SystemMode::kTriage);
+    EXPECT_EQ(features_->getMask(), ModeManager::featureMaskForMode(SystemMode::kTriage));
+}
+
+TEST_F(ModeManagerTest, ForcedTransitionRejectsUnknownMode) {
+    mgr_->transitionTo(SystemMode::kIdle);
+    EXPECT_FALSE(mgr_->forceTransitionTo(static_cast<SystemMode>(42)));
+    EXPECT_EQ(mgr_->currentMode(), SystemMode::kIdle);
+}
+
+TEST_F(ModeManagerTest, ForcedGameFromTriageTimesOutToIdle) {
+    mgr_->transitionTo(SystemMode::kIdle);
+    mgr_->transitionTo(SystemMode::kTriage);
+    ASSERT_TRUE(mgr_->forceTransitionTo(SystemMode::kGame));
+
+    // GAME -> TRIAGE would be rejected, so the timeout goes to IDLE
+    EXPECT_EQ(mgr_->gameEnteredFrom(), SystemMode::kIdle);
+    advanceTimeS(301);
+    mgr_->tick();
+    EXPECT_EQ(mgr_->currentMode(), SystemMode::kIdle);
+}
+
 // =============================================================================
 // Feature Mask Application Tests
 // =============================================================================
@@ -376,6 +409,47 @@ TEST_F(ModeManagerTest, SoloDrillTimeoutToIdle) {
     EXPECT_EQ(mgr_->currentMode(), SystemMode::kIdle);
 }
 
+// =============================================================================
+// Previous Mode Tests
+// =============================================================================
+
+TEST_F(ModeManagerTest, NoPreviousModeBeforeFirstTransition) {
+    SystemMode previous = SystemMode::kError;
+    EXPECT_FALSE(mgr_->previousMode(previous));
+    EXPECT_EQ(previous, SystemMode::kError);  // Left untouched
+}
+
+TEST_F(ModeManagerTest, PreviousModeTracksLastTransition) {
+    mgr_->transitionTo(SystemMode::kIdle);
+    mgr_->transitionTo(SystemMode::kConnected);
+
+    SystemMode previous = SystemMode::kError;
+    ASSERT_TRUE(mgr_->previousMode(previous));
+    EXPECT_EQ(previous, SystemMode::kIdle);
+}
+
+TEST_F(ModeManagerTest, PreviousModeUnchangedByRejectedTransition) {
+    mgr_->transitionTo(SystemMode::kIdle);
+    mgr_->transitionTo(SystemMode::kTriage);
+    mgr_->transitionTo(SystemMode::kGame);  // TRIAGE -> GAME is invalid
+
+    SystemMode previous = SystemMode::kError;
+    ASSERT_TRUE(mgr_->previousMode(previous));
+    EXPECT_EQ(previous, SystemMode::kIdle);
+}
+
+TEST_F(ModeManagerTest, PreviousModeSetByTimeout) {
+    mgr_->transitionTo(SystemMode::kIdle);
+    mgr_->transitionTo(SystemMode::kTriage);
+
+    advanceTimeS(31);
+    mgr_->tick();
+
+    SystemMode previous = SystemMode::kError;
+    ASSERT_TRUE(mgr_->previousMode(previous));
+    EXPECT_EQ(previous, SystemMode::kTriage);
+}
+
 // =============================================================================
 // Mode Transition Callback Tests
 // =============================================================================
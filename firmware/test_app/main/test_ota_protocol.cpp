@@ -192,6 +192,28 @@ TEST(OtaEnd, SerializeWithNullOutLenReturnsError) {
     EXPECT_EQ(TransportError::kInvalidArg, err);
 }
 
+TEST(OtaEnd, SerializeDeserializeFlagsRoundTrip) {
+    std::array<uint8_t, 32> buf{};
+    size_t len = 0;
+
+    TransportError err = serializeOtaEnd(kOtaEndNoReboot, buf.data(), buf.size(), &len);
+    EXPECT_EQ(TransportError::kOk, err);
+    EXPECT_EQ(1u, len);
+
+    uint8_t flags = 0;
+    err = deserializeOtaEnd(buf.data(), len, &flags);
+    EXPECT_EQ(TransportError::kOk, err);
+    EXPECT_EQ(kOtaEndNoReboot, flags);
+}
+
+TEST(OtaEnd, DeserializeEmptyPayloadHasNoFlags) {
+    uint8_t flags = 0xFF;
+
+    TransportError err = deserializeOtaEnd(nullptr, 0, &flags);
+    EXPECT_EQ(TransportError::kOk, err);
+    EXPECT_EQ(0u, flags);
+}
+
 // =============================================================================
 // OTA_ACK Tests
 // =============================================================================
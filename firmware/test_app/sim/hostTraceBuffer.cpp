@@ -43,6 +43,13 @@ esp_err_t TraceBuffer::init() {
     return ESP_OK;
 }
 
+esp_err_t TraceBuffer::resize(size_t bufferSize) {
+    initialized_.store(false);
+    droppedCount_.store(0);
+    bufferSize_ = bufferSize;
+    return init();
+}
+
 bool TraceBuffer::record(const TraceEvent& event) {
     if (!initialized_.load() || paused_.load()) return false;
     state().events.push_back(event);
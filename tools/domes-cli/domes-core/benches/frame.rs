@@ -0,0 +1,56 @@
+//! Frame codec benchmarks
+//!
+//! `cargo bench -p domes-core --bench frame` times `encode_frame` across
+//! payload sizes, and decoding a full-size frame one byte at a time
+//! (`feed_byte`, as the transports read) against 64-byte blocks
+//! (`feed_slice`).
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use domes_core::transport::frame::{encode_frame, FrameDecoder, MAX_PAYLOAD_SIZE};
+
+fn bench_encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode_frame");
+    for size in [64, 256, 512, 1024] {
+        let payload = vec![0xA5u8; size];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &payload, |b, payload| {
+            b.iter(|| encode_frame(0x21, black_box(payload)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_decode(c: &mut Criterion) {
+    let frame = encode_frame(0x21, &vec![0xA5u8; MAX_PAYLOAD_SIZE]).unwrap();
+    let mut group = c.benchmark_group("decode_1024");
+    group.throughput(Throughput::Bytes(frame.len() as u64));
+
+    group.bench_function("feed_byte", |b| {
+        let mut decoder = FrameDecoder::new();
+        b.iter(|| {
+            decoder.reset();
+            let mut decoded = None;
+            for &byte in black_box(&frame) {
+                if let Some(result) = decoder.feed_byte(byte) {
+                    decoded = Some(result.unwrap());
+                }
+            }
+            decoded.unwrap()
+        })
+    });
+
+    group.bench_function("feed_slice_64", |b| {
+        let mut decoder = FrameDecoder::new();
+        b.iter(|| {
+            let mut decoded = Vec::new();
+            for chunk in black_box(&frame).chunks(64) {
+                decoded.extend(decoder.feed_slice(chunk));
+            }
+            decoded.pop().unwrap().unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_encode, bench_decode);
+criterion_main!(benches);
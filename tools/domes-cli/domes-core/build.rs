@@ -1,14 +1,17 @@
-//! Build script for domes-cli
+//! Build script for domes-core
 //!
 //! Generates Rust code from Protocol Buffer definitions.
 //! The proto files are the SINGLE SOURCE OF TRUTH for protocol definitions.
 //! DO NOT hand-roll protocol types - they are generated here.
+//!
+//! proto/perfetto_trace.proto is not part of the device protocol: it is the
+//! subset of Perfetto's trace format used to write binary trace files.
 
 use std::path::PathBuf;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Path to proto files (relative to this crate's Cargo.toml)
-    let proto_dir = PathBuf::from("../../firmware/common/proto");
+    let proto_dir = PathBuf::from("../../../firmware/common/proto");
     let config_proto = proto_dir.join("config.proto");
     let trace_proto = proto_dir.join("trace.proto");
 
@@ -23,5 +26,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // Compile all proto files
         .compile_protos(&[&config_proto, &trace_proto], &[&proto_dir])?;
 
+    // Perfetto trace file format (host side only)
+    let perfetto_dir = PathBuf::from("proto");
+    let perfetto_proto = perfetto_dir.join("perfetto_trace.proto");
+    println!("cargo:rerun-if-changed={}", perfetto_proto.display());
+    prost_build::Config::new().compile_protos(&[&perfetto_proto], &[&perfetto_dir])?;
+
     Ok(())
 }
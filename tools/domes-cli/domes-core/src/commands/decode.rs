@@ -0,0 +1,171 @@
+//! Offline frame decoding
+//!
+//! Decodes a captured byte dump (e.g. from a logic analyzer) without a
+//! device: every frame is run through `FrameDecoder`, checked against its
+//! CRC, and its payload decoded the same way the sniffer does.
+
+use super::sniff::{decode_payload, identify_message};
+use crate::transport::frame::{FrameDecoder, FrameError, FRAME_OVERHEAD};
+
+/// One frame found in a byte dump
+#[derive(Debug, Clone)]
+pub struct DecodedFrame {
+    /// Offset of the 0xAA start byte in the input
+    pub offset: usize,
+    /// Total frame size including header and CRC
+    pub size: usize,
+    pub msg_type: u8,
+    pub msg_name: String,
+    pub protocol: &'static str,
+    pub direction: &'static str,
+    pub payload: Vec<u8>,
+    /// CRC carried in the frame
+    pub crc_received: u32,
+    /// CRC computed over type + payload
+    pub crc_computed: u32,
+    /// Decoded payload fields (empty if the CRC is invalid or the type is unknown)
+    pub fields: Vec<(String, String)>,
+}
+
+impl DecodedFrame {
+    pub fn crc_valid(&self) -> bool {
+        self.crc_received == self.crc_computed
+    }
+}
+
+/// Result of decoding a byte dump
+#[derive(Debug, Default)]
+pub struct DecodeReport {
+    pub frames: Vec<DecodedFrame>,
+    /// Framing errors (offset, message), e.g. an invalid length field
+    pub errors: Vec<(usize, String)>,
+    /// Bytes after the last complete frame that did not form a frame
+    pub trailing: usize,
+}
+
+/// Decode all frames in `bytes`
+pub fn decode_bytes(bytes: &[u8]) -> DecodeReport {
+    let mut report = DecodeReport::default();
+    let mut decoder = FrameDecoder::new();
+    let mut consumed = 0;
+
+    for (i, &byte) in bytes.iter().enumerate() {
+        let Some(result) = decoder.feed_byte(byte) else {
+            continue;
+        };
+        decoder.reset();
+
+        match result {
+            Ok(frame) => {
+                let size = FRAME_OVERHEAD + frame.payload.len();
+                let offset = i + 1 - size;
+                let crc = u32::from_le_bytes(bytes[i - 3..=i].try_into().unwrap());
+                report
+                    .frames
+                    .push(describe(offset, frame.msg_type, frame.payload, crc, crc));
+            }
+            Err(FrameError::CrcMismatch { expected, actual }) => {
+                // The decoder drops the frame on a bad CRC; recover it from the input
+                if let Some(offset) = find_frame_start(bytes, i) {
+                    let msg_type = bytes[offset + 4];
+                    let payload = bytes[offset + 5..i - 3].to_vec();
+                    report
+                        .frames
+                        .push(describe(offset, msg_type, payload, actual, expected));
+                }
+            }
+            Err(e) => report.errors.push((i, e.to_string())),
+        }
+        consumed = i + 1;
+    }
+
+    report.trailing = bytes.len() - consumed;
+    report
+}
+
+fn describe(
+    offset: usize,
+    msg_type: u8,
+    payload: Vec<u8>,
+    crc_received: u32,
+    crc_computed: u32,
+) -> DecodedFrame {
+    let (msg_name, direction, protocol) = identify_message(msg_type);
+    let fields = if crc_received == crc_computed {
+        decode_payload(msg_type, &payload)
+    } else {
+        Vec::new()
+    };
+    DecodedFrame {
+        offset,
+        size: FRAME_OVERHEAD + payload.len(),
+        msg_type,
+        msg_name,
+        protocol,
+        direction,
+        payload,
+        crc_received,
+        crc_computed,
+        fields,
+    }
+}
+
+/// Find the start of the frame whose last CRC byte is at `end`
+fn find_frame_start(bytes: &[u8], end: usize) -> Option<usize> {
+    (0..=end.checked_sub(FRAME_OVERHEAD - 1)?).rev().find(|&start| {
+        bytes[start] == 0xAA
+            && bytes[start + 1] == 0x55
+            && start + u16::from_le_bytes([bytes[start + 2], bytes[start + 3]]) as usize + 8
+                == end + 1
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::frame::encode_frame;
+
+    #[test]
+    fn test_decode_valid_frames_with_noise() {
+        let mut bytes = vec![0x00, 0x13];
+        bytes.extend(encode_frame(0x20, &[]).unwrap());
+        let second = bytes.len();
+        bytes.extend(encode_frame(0x31, &[0x00, 0x08, 0x01]).unwrap());
+        bytes.extend([0xAA, 0x55, 0x05]);
+
+        let report = decode_bytes(&bytes);
+        assert_eq!(report.frames.len(), 2);
+        assert_eq!(report.frames[0].offset, 2);
+        assert_eq!(report.frames[0].msg_name, "LIST_FEATURES_REQ");
+        assert_eq!(report.frames[1].offset, second);
+        assert_eq!(report.frames[1].payload, [0x00, 0x08, 0x01]);
+        assert!(report.frames.iter().all(|f| f.crc_valid()));
+        assert_eq!(report.frames[1].fields[0], ("status".into(), "STATUS_OK".into()));
+        assert_eq!(report.trailing, 3);
+    }
+
+    #[test]
+    fn test_decode_crc_mismatch_keeps_frame() {
+        let mut bytes = encode_frame(0x34, &[0x01, 0x02]).unwrap();
+        let last = bytes.len() - 1;
+        let good_crc = u32::from_le_bytes(bytes[last - 3..].try_into().unwrap());
+        bytes[last] ^= 0xFF;
+
+        let report = decode_bytes(&bytes);
+        assert_eq!(report.frames.len(), 1);
+        let frame = &report.frames[0];
+        assert!(!frame.crc_valid());
+        assert_eq!(frame.msg_type, 0x34);
+        assert_eq!(frame.payload, [0x01, 0x02]);
+        assert_eq!(frame.crc_computed, good_crc);
+        assert_eq!(frame.crc_received, good_crc ^ 0xFF00_0000);
+        assert!(frame.fields.is_empty());
+    }
+
+    #[test]
+    fn test_decode_invalid_length() {
+        let report = decode_bytes(&[0xAA, 0x55, 0x00, 0x00]);
+        assert!(report.frames.is_empty());
+        assert_eq!(report.errors.len(), 1);
+    }
+}
@@ -1,27 +1,23 @@
 //! ESP-NOW status and benchmark commands
 
+use crate::error::{Context, Result};
 use crate::protocol::{
     parse_espnow_bench_response, parse_get_espnow_status_response, parse_set_sim_mode_response,
     serialize_espnow_bench, serialize_set_sim_mode, CliBenchResult, CliEspNowStatus,
     CliSimModeState, ConfigMsgType,
 };
 use crate::transport::Transport;
-use anyhow::{Context, Result};
 
 /// Get ESP-NOW subsystem status
 pub fn espnow_status(transport: &mut dyn Transport) -> Result<CliEspNowStatus> {
     let frame = transport
-        .send_command(ConfigMsgType::GetEspnowStatusReq as u8, &[])
+        .send_command_expect(
+            ConfigMsgType::GetEspnowStatusReq as u8,
+            &[],
+            ConfigMsgType::GetEspnowStatusRsp as u8,
+        )
         .context("Failed to send get espnow status command")?;
 
-    if frame.msg_type != ConfigMsgType::GetEspnowStatusRsp as u8 {
-        anyhow::bail!(
-            "Unexpected response type: 0x{:02X}, expected 0x{:02X}",
-            frame.msg_type,
-            ConfigMsgType::GetEspnowStatusRsp as u8
-        );
-    }
-
     parse_get_espnow_status_response(&frame.payload)
         .context("Failed to parse get espnow status response")
 }
@@ -41,13 +37,7 @@ pub fn espnow_bench(transport: &mut dyn Transport, rounds: u32) -> Result<CliBen
         .receive_frame(timeout_ms)
         .context("Benchmark timed out waiting for response (may need more time)")?;
 
-    if frame.msg_type != ConfigMsgType::EspnowBenchRsp as u8 {
-        anyhow::bail!(
-            "Unexpected response type: 0x{:02X}, expected 0x{:02X}",
-            frame.msg_type,
-            ConfigMsgType::EspnowBenchRsp as u8
-        );
-    }
+    super::expect_response(&frame, ConfigMsgType::EspnowBenchRsp as u8)?;
 
     parse_espnow_bench_response(&frame.payload)
         .context("Failed to parse espnow bench response")
@@ -63,17 +53,13 @@ pub fn espnow_sim_mode(
     let payload = serialize_set_sim_mode(enabled, delay_ms, pad_index);
 
     let frame = transport
-        .send_command(ConfigMsgType::SetSimModeReq as u8, &payload)
+        .send_command_expect(
+            ConfigMsgType::SetSimModeReq as u8,
+            &payload,
+            ConfigMsgType::SetSimModeRsp as u8,
+        )
         .context("Failed to send set sim mode command")?;
 
-    if frame.msg_type != ConfigMsgType::SetSimModeRsp as u8 {
-        anyhow::bail!(
-            "Unexpected response type: 0x{:02X}, expected 0x{:02X}",
-            frame.msg_type,
-            ConfigMsgType::SetSimModeRsp as u8
-        );
-    }
-
     parse_set_sim_mode_response(&frame.payload)
         .context("Failed to parse set sim mode response")
 }
@@ -0,0 +1,146 @@
+//! Event subscription commands
+//!
+//! Instead of polling, the host subscribes to categories of state changes
+//! and the device pushes an `EventNotification` frame whenever one happens.
+
+use crate::error::{Context, DomesError, Result};
+use crate::proto::config::EventCategory;
+use crate::protocol::{
+    parse_event_notification, parse_subscribe_events_response, serialize_subscribe_events,
+    CliEventNotification, ConfigMsgType,
+};
+use crate::transport::notifications::NotificationReceiver;
+use crate::transport::{ResponseTimeout, Transport};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// How long to wait for the subscribe/unsubscribe response
+const SUBSCRIBE_TIMEOUT_MS: u64 = 2000;
+
+/// Parse a comma-separated category list (e.g. "mode,led") into a subscription mask
+///
+/// An empty list or "all" selects every category.
+pub fn parse_event_categories(list: &str) -> Result<u32> {
+    let names: Vec<&str> = list
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    if names.is_empty() || names.iter().any(|n| n.eq_ignore_ascii_case("all")) {
+        return Ok(EventCategory::ALL.iter().map(EventCategory::mask).sum());
+    }
+
+    names.iter().try_fold(0, |mask, name| {
+        let category = EventCategory::from_cli_name(name).ok_or_else(|| {
+            DomesError::InvalidArgument(format!(
+                "Unknown event category: {}. Valid: mode, led, wifi, feature, touch, all",
+                name
+            ))
+        })?;
+        Ok(mask | category.mask())
+    })
+}
+
+/// Names of the categories set in a subscription mask
+pub fn event_category_names(mask: u32) -> Vec<&'static str> {
+    EventCategory::ALL
+        .iter()
+        .filter(|c| mask & c.mask() != 0)
+        .map(EventCategory::cli_name)
+        .collect()
+}
+
+/// Subscribe to the categories in `mask` (0 unsubscribes); returns the active mask
+///
+/// Notifications from an earlier subscription may still be in flight, so
+/// they are skipped while waiting for the response.
+pub fn events_subscribe(transport: &mut dyn Transport, mask: u32) -> Result<u32> {
+    let payload = serialize_subscribe_events(mask);
+    transport
+        .send_frame(ConfigMsgType::SubscribeEventsReq as u8, &payload)
+        .context("Failed to send subscribe events command")?;
+
+    let deadline = Instant::now() + Duration::from_millis(SUBSCRIBE_TIMEOUT_MS);
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(DomesError::Timeout {
+                context: "Failed to receive subscribe events response".to_string(),
+                source: Box::new(ResponseTimeout),
+            });
+        }
+
+        let frame = transport
+            .receive_frame(remaining.as_millis() as u64)
+            .context("Failed to receive subscribe events response")?;
+        if frame.msg_type == ConfigMsgType::EventNotification as u8 {
+            continue;
+        }
+        super::expect_response(&frame, ConfigMsgType::SubscribeEventsRsp as u8)?;
+
+        return parse_subscribe_events_response(&frame.payload)
+            .context("Failed to parse subscribe events response");
+    }
+}
+
+/// Subscribe to `mask`, pass each notification to `on_event` until
+/// `interrupted` is set, then unsubscribe
+pub fn events_listen(
+    transport: &mut dyn Transport,
+    mask: u32,
+    interrupted: &AtomicBool,
+    mut on_event: impl FnMut(&CliEventNotification),
+) -> Result<()> {
+    let active = events_subscribe(transport, mask)?;
+    progress!(
+        "Subscribed to {} events (Ctrl+C to stop)",
+        event_category_names(active).join(", ")
+    );
+
+    let listened = std::thread::scope(|scope| {
+        let receiver = NotificationReceiver::spawn(scope, &mut *transport);
+        while !interrupted.load(Ordering::Relaxed) {
+            let frame = match receiver.frames().recv_timeout(Duration::from_millis(100)) {
+                Ok(frame) => frame,
+                Err(crossbeam_channel::RecvTimeoutError::Timeout) => continue,
+                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
+            };
+            if frame.msg_type != ConfigMsgType::EventNotification as u8 {
+                continue;
+            }
+            match parse_event_notification(&frame.payload) {
+                Ok(notification) => on_event(&notification),
+                Err(e) => progress!("Ignoring malformed event: {}", e),
+            }
+        }
+        receiver.stop()
+    });
+    listened.context("Lost connection while listening for events")?;
+
+    // Unsubscribe so the device stops pushing events
+    events_subscribe(transport, 0)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_event_categories() {
+        let mode = EventCategory::Mode.mask();
+        let led = EventCategory::Led.mask();
+        let wifi = EventCategory::Wifi.mask();
+        assert_eq!(parse_event_categories("mode,led,wifi").unwrap(), mode | led | wifi);
+        assert_eq!(parse_event_categories(" LED , mode ").unwrap(), mode | led);
+        assert_eq!(parse_event_categories("all").unwrap(), 0x1F);
+        assert_eq!(parse_event_categories("").unwrap(), 0x1F);
+        assert!(parse_event_categories("mode,bogus").is_err());
+    }
+
+    #[test]
+    fn test_event_category_names() {
+        assert_eq!(event_category_names(0x05), vec!["mode", "wifi"]);
+        assert!(event_category_names(0).is_empty());
+    }
+}
@@ -0,0 +1,246 @@
+//! Feature toggle commands
+
+use crate::error::{Context, Result};
+use crate::proto::config::Feature;
+use crate::protocol::{
+    parse_feature_response, parse_list_features_response, serialize_set_feature, CliFeatureState,
+    ConfigMsgType,
+};
+use crate::transport::Transport;
+use std::path::Path;
+
+/// List all features and their current state
+pub fn feature_list(transport: &mut dyn Transport) -> Result<Vec<CliFeatureState>> {
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::ListFeaturesReq as u8,
+            &[],
+            ConfigMsgType::ListFeaturesRsp as u8,
+        )
+        .context("Failed to send list features command")?;
+
+    parse_list_features_response(&frame.payload).context("Failed to parse list features response")
+}
+
+/// Enable a feature
+pub fn feature_enable(transport: &mut dyn Transport, feature: Feature) -> Result<CliFeatureState> {
+    let payload = serialize_set_feature(feature, true);
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::SetFeatureReq as u8,
+            &payload,
+            ConfigMsgType::SetFeatureRsp as u8,
+        )
+        .context("Failed to send set feature command")?;
+
+    parse_feature_response(&frame.payload).context("Failed to parse set feature response")
+}
+
+/// Disable a feature
+pub fn feature_disable(transport: &mut dyn Transport, feature: Feature) -> Result<CliFeatureState> {
+    let payload = serialize_set_feature(feature, false);
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::SetFeatureReq as u8,
+            &payload,
+            ConfigMsgType::SetFeatureRsp as u8,
+        )
+        .context("Failed to send set feature command")?;
+
+    parse_feature_response(&frame.payload).context("Failed to parse set feature response")
+}
+
+/// Order of `feature list` output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureSort {
+    /// Alphabetical by CLI name
+    Name,
+    /// Enabled features first, each group in device order
+    Status,
+}
+
+impl std::str::FromStr for FeatureSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(Self::Name),
+            "status" => Ok(Self::Status),
+            _ => Err(format!("Unknown sort: {} (expected name or status)", s)),
+        }
+    }
+}
+
+/// Which features `feature list` shows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FeatureFilter {
+    #[default]
+    All,
+    Enabled,
+    Disabled,
+}
+
+impl std::str::FromStr for FeatureFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Self::All),
+            "enabled" => Ok(Self::Enabled),
+            "disabled" => Ok(Self::Disabled),
+            _ => Err(format!(
+                "Unknown filter: {} (expected enabled, disabled or all)",
+                s
+            )),
+        }
+    }
+}
+
+/// Filter `feature_list` results, then sort them (`None` keeps device order)
+pub fn sort_and_filter_features(
+    mut features: Vec<CliFeatureState>,
+    sort: Option<FeatureSort>,
+    filter: FeatureFilter,
+) -> Vec<CliFeatureState> {
+    features.retain(|state| match filter {
+        FeatureFilter::All => true,
+        FeatureFilter::Enabled => state.enabled,
+        FeatureFilter::Disabled => !state.enabled,
+    });
+    match sort {
+        Some(FeatureSort::Name) => features.sort_by_key(|state| state.feature.cli_name()),
+        Some(FeatureSort::Status) => features.sort_by_key(|state| !state.enabled),
+        None => {}
+    }
+    features
+}
+
+/// Write `features` to `path` as a JSON feature profile
+///
+/// The file holds the same entries as `feature list --format json`.
+pub fn save_feature_profile(path: &Path, features: &[CliFeatureState]) -> Result<()> {
+    // Only names and booleans, so serializing cannot fail
+    let json = serde_json::to_string_pretty(features).unwrap_or_default();
+    std::fs::write(path, json + "\n")
+        .with_context(|| format!("Failed to write feature profile {}", path.display()))
+}
+
+/// Read a feature profile written by `save_feature_profile`
+pub fn load_feature_profile(path: &Path) -> Result<Vec<CliFeatureState>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read feature profile {}", path.display()))?;
+    serde_json::from_str(&text)
+        .map_err(std::io::Error::from)
+        .with_context(|| format!("Invalid feature profile {}", path.display()))
+}
+
+/// A feature whose state on the device differs from a profile
+///
+/// `expected` is `None` for a feature the profile does not list, `actual`
+/// for one the device did not report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureDrift {
+    pub feature: Feature,
+    pub expected: Option<bool>,
+    pub actual: Option<bool>,
+}
+
+/// Compare live feature states against a profile, in profile order
+///
+/// Features the profile does not list come last, in device order.
+pub fn diff_feature_profile(
+    profile: &[CliFeatureState],
+    live: &[CliFeatureState],
+) -> Vec<FeatureDrift> {
+    let state_of = |states: &[CliFeatureState], feature| {
+        states.iter().find(|s| s.feature == feature).map(|s| s.enabled)
+    };
+    let expected = profile.iter().map(|s| s.feature);
+    let unlisted = live
+        .iter()
+        .map(|s| s.feature)
+        .filter(|&f| state_of(profile, f).is_none());
+    expected
+        .chain(unlisted)
+        .map(|feature| FeatureDrift {
+            feature,
+            expected: state_of(profile, feature),
+            actual: state_of(live, feature),
+        })
+        .filter(|drift| drift.expected != drift.actual)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_and_filter_features() {
+        let state = |feature, enabled| CliFeatureState { feature, enabled };
+        let features = vec![
+            state(Feature::Wifi, false),
+            state(Feature::BleAdvertising, true),
+            state(Feature::Audio, false),
+            state(Feature::Touch, true),
+        ];
+        let names = |features: Vec<CliFeatureState>| -> Vec<&'static str> {
+            features.iter().map(|s| s.feature.cli_name()).collect()
+        };
+
+        let all = FeatureFilter::All;
+        assert_eq!(
+            names(sort_and_filter_features(features.clone(), None, all)),
+            ["wifi", "ble", "audio", "touch"]
+        );
+        assert_eq!(
+            names(sort_and_filter_features(features.clone(), Some(FeatureSort::Name), all)),
+            ["audio", "ble", "touch", "wifi"]
+        );
+        assert_eq!(
+            names(sort_and_filter_features(features.clone(), Some(FeatureSort::Status), all)),
+            ["ble", "touch", "wifi", "audio"]
+        );
+        assert_eq!(
+            names(sort_and_filter_features(features, None, FeatureFilter::Disabled)),
+            ["wifi", "audio"]
+        );
+    }
+
+    #[test]
+    fn test_feature_profile_round_trip_and_diff() {
+        let state = |feature, enabled| CliFeatureState { feature, enabled };
+        let profile = vec![
+            state(Feature::Wifi, true),
+            state(Feature::BleAdvertising, true),
+            state(Feature::Touch, false),
+        ];
+        let name = format!("domes-feature-profile-{}.json", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        save_feature_profile(&path, &profile).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains(r#""feature": "ble""#));
+        assert_eq!(load_feature_profile(&path).unwrap(), profile);
+
+        std::fs::write(&path, r#"[{"feature": "jetpack", "enabled": true}]"#).unwrap();
+        let err = load_feature_profile(&path).unwrap_err();
+        assert!(err.to_string().starts_with("Invalid feature profile"));
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(diff_feature_profile(&profile, &profile).is_empty());
+        let live = vec![
+            state(Feature::Audio, true),
+            state(Feature::Wifi, false),
+            state(Feature::Touch, false),
+        ];
+        let drift = |feature, expected, actual| FeatureDrift { feature, expected, actual };
+        assert_eq!(
+            diff_feature_profile(&profile, &live),
+            [
+                drift(Feature::Wifi, Some(true), Some(false)),
+                drift(Feature::BleAdvertising, Some(true), None),
+                drift(Feature::Audio, None, Some(true)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! LED pattern commands
+
+use crate::error::{Context, Result};
+use crate::proto::config::{LedPattern, LedPatternType};
+use crate::protocol::{
+    parse_led_pattern_proto, parse_led_pattern_response, serialize_set_led_pattern,
+    CliLedPattern, ConfigMsgType,
+};
+use crate::transport::Transport;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Get current LED pattern
+pub fn led_get(transport: &mut dyn Transport) -> Result<CliLedPattern> {
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::GetLedPatternReq as u8,
+            &[],
+            ConfigMsgType::GetLedPatternRsp as u8,
+        )
+        .context("Failed to send get LED pattern command")?;
+
+    parse_led_pattern_response(&frame.payload).context("Failed to parse get LED pattern response")
+}
+
+/// Get current LED pattern exactly as the device reports it (`led get --raw`)
+pub fn led_get_raw(transport: &mut dyn Transport) -> Result<LedPattern> {
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::GetLedPatternReq as u8,
+            &[],
+            ConfigMsgType::GetLedPatternRsp as u8,
+        )
+        .context("Failed to send get LED pattern command")?;
+
+    parse_led_pattern_proto(&frame.payload).context("Failed to parse get LED pattern response")
+}
+
+/// Set LED pattern
+pub fn led_set(transport: &mut dyn Transport, pattern: &CliLedPattern) -> Result<CliLedPattern> {
+    let payload = serialize_set_led_pattern(pattern);
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::SetLedPatternReq as u8,
+            &payload,
+            ConfigMsgType::SetLedPatternRsp as u8,
+        )
+        .context("Failed to send set LED pattern command")?;
+
+    parse_led_pattern_response(&frame.payload).context("Failed to parse set LED pattern response")
+}
+
+/// Turn LEDs off
+pub fn led_off(transport: &mut dyn Transport) -> Result<CliLedPattern> {
+    led_set(transport, &CliLedPattern::off())
+}
+
+/// Change brightness, keeping the current pattern type, colors and period
+///
+/// Returns `None` without changing anything if the LEDs are off, since an off
+/// pattern has no brightness to adjust.
+pub fn led_set_brightness(
+    transport: &mut dyn Transport,
+    brightness: u8,
+) -> Result<Option<CliLedPattern>> {
+    let mut pattern = led_get(transport)?;
+    if pattern.pattern_type == LedPatternType::LedPatternOff {
+        return Ok(None);
+    }
+
+    pattern.brightness = brightness;
+    led_set(transport, &pattern).map(Some)
+}
+
+/// An RGBW color, as in `CliLedPattern::color`
+pub type Rgbw = (u8, u8, u8, u8);
+
+/// Colors of the `led test` sequence, shown in this order before turning off
+///
+/// White lights every channel, including W on RGBW rings.
+pub const LED_TEST_COLORS: [(&str, Rgbw); 4] = [
+    ("red", (255, 0, 0, 0)),
+    ("green", (0, 255, 0, 0)),
+    ("blue", (0, 0, 255, 0)),
+    ("white", (255, 255, 255, 255)),
+];
+
+/// Show each of `LED_TEST_COLORS` as a solid pattern for `dwell`, then turn
+/// the LEDs off
+///
+/// `on_step` is called with each color name (and "off") as it is set. Setting
+/// `interrupted` ends the sequence early; the LEDs are still turned off.
+/// Returns the number of colors shown.
+pub fn led_test(
+    transport: &mut dyn Transport,
+    dwell: Duration,
+    brightness: u8,
+    interrupted: &AtomicBool,
+    on_step: &mut dyn FnMut(&str),
+) -> Result<usize> {
+    let mut shown = 0;
+    for (name, (r, g, b, w)) in LED_TEST_COLORS {
+        if interrupted.load(Ordering::Relaxed) {
+            break;
+        }
+        let mut pattern = CliLedPattern::solid(r, g, b);
+        pattern.color = Some((r, g, b, w));
+        pattern.brightness = brightness;
+        on_step(name);
+        led_set(transport, &pattern)?;
+        shown += 1;
+
+        let started = Instant::now();
+        while started.elapsed() < dwell && !interrupted.load(Ordering::Relaxed) {
+            let remaining = dwell.saturating_sub(started.elapsed());
+            std::thread::sleep(remaining.min(Duration::from_millis(100)));
+        }
+    }
+    on_step("off");
+    led_off(transport)?;
+    Ok(shown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::config::{SetLedPatternRequest, SetLedPatternResponse};
+    use crate::transport::Frame;
+    use prost::Message;
+
+    /// Accepts every SetLedPattern request and records the patterns
+    #[derive(Default)]
+    struct LedRecorder {
+        colors: Vec<Option<Rgbw>>,
+        periods: Vec<u32>,
+    }
+
+    impl Transport for LedRecorder {
+        fn send_frame(&mut self, _msg_type: u8, _payload: &[u8]) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn receive_frame(&mut self, _timeout_ms: u64) -> anyhow::Result<Frame> {
+            anyhow::bail!("not used")
+        }
+
+        fn send_command(&mut self, _msg_type: u8, payload: &[u8]) -> anyhow::Result<Frame> {
+            let pattern = SetLedPatternRequest::decode(payload)?.pattern;
+            let mut rsp = vec![0];
+            rsp.extend(SetLedPatternResponse { pattern }.encode_to_vec());
+            let sent = parse_led_pattern_response(&rsp)?;
+            self.colors.push(sent.color);
+            self.periods.push(sent.period_ms);
+            Ok(Frame {
+                msg_type: ConfigMsgType::SetLedPatternRsp as u8,
+                payload: rsp,
+            })
+        }
+    }
+
+    #[test]
+    fn test_led_test_sequence_ends_off() {
+        let mut device = LedRecorder::default();
+        let mut steps = Vec::new();
+        let interrupted = AtomicBool::new(false);
+        let shown = led_test(&mut device, Duration::from_millis(1), 64, &interrupted, &mut |n| {
+            steps.push(n.to_string())
+        })
+        .unwrap();
+        assert_eq!(shown, 4);
+        assert_eq!(steps, ["red", "green", "blue", "white", "off"]);
+        assert_eq!(device.colors[0], Some((255, 0, 0, 0)));
+        assert_eq!(device.colors[3], Some((255, 255, 255, 255)));
+        assert_eq!(device.colors.len(), 5);
+
+        // Interrupted before the first color: straight to off
+        let mut device = LedRecorder::default();
+        interrupted.store(true, Ordering::Relaxed);
+        let shown = led_test(&mut device, Duration::from_secs(60), 64, &interrupted, &mut |_| {});
+        assert_eq!(shown.unwrap(), 0);
+        assert_eq!(device.colors.len(), 1);
+    }
+
+    #[test]
+    fn test_led_get_raw_keeps_unexpected_values() {
+        use crate::proto::config::{Color, GetLedPatternResponse};
+        use crate::transport::ReplayTransport;
+
+        let pattern = LedPattern {
+            r#type: 7,
+            color: None,
+            colors: vec![Color { r: 300, g: 0, b: 16, w: 1 }],
+            period_ms: 500,
+            brightness: 1000,
+        };
+        let mut payload = vec![0];
+        payload.extend(GetLedPatternResponse { pattern: Some(pattern.clone()) }.encode_to_vec());
+        let rsp = Frame {
+            msg_type: ConfigMsgType::GetLedPatternRsp as u8,
+            payload,
+        };
+
+        let mut transport = ReplayTransport::new(vec![rsp.clone()]);
+        assert_eq!(led_get_raw(&mut transport).unwrap(), pattern);
+
+        // The friendly view clamps what it cannot show
+        let mut transport = ReplayTransport::new(vec![rsp]);
+        let cli = led_get(&mut transport).unwrap();
+        assert_eq!(cli.pattern_type, LedPatternType::LedPatternOff);
+    }
+
+    #[test]
+    fn test_exported_pattern_file_round_trips() {
+        use crate::proto::config::GetLedPatternResponse;
+        use crate::transport::ReplayTransport;
+
+        let current = serialize_set_led_pattern(&CliLedPattern::breathing(0, 255, 0, 2000));
+        let pattern = SetLedPatternRequest::decode(&*current).unwrap().pattern;
+        let mut payload = vec![0];
+        payload.extend(GetLedPatternResponse { pattern }.encode_to_vec());
+        let rsp = Frame {
+            msg_type: ConfigMsgType::GetLedPatternRsp as u8,
+            payload,
+        };
+        let path = std::env::temp_dir().join(format!("domes-led-{}.json", std::process::id()));
+        led_get(&mut ReplayTransport::new(vec![rsp])).unwrap().to_json_file(&path).unwrap();
+
+        let json = std::fs::read_to_string(&path).unwrap();
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["pattern_type"], "breathing");
+        assert_eq!(value["color"], serde_json::json!([0, 255, 0, 0]));
+        value["period_ms"] = 500.into();
+        std::fs::write(&path, value.to_string()).unwrap();
+
+        let mut device = LedRecorder::default();
+        led_set(&mut device, &CliLedPattern::from_json_file(&path).unwrap()).unwrap();
+        assert_eq!(device.periods, [500]);
+        assert_eq!(device.colors, [Some((0, 255, 0, 0))]);
+
+        // A cycle with no colors is refused before anything is sent
+        std::fs::write(&path, r#"{"pattern_type":"color_cycle","period_ms":500}"#).unwrap();
+        let err = CliLedPattern::from_json_file(&path).unwrap_err();
+        assert!(format!("{:#}", err).contains("needs at least one color"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,65 @@
+//! CLI commands for DOMES CLI
+
+pub mod decode;
+pub mod espnow;
+pub mod events;
+pub mod feature;
+pub mod health;
+pub mod imu;
+pub mod led;
+pub mod ota;
+pub mod proto;
+pub mod raw;
+pub mod sniff;
+pub mod system;
+pub mod touch;
+pub mod trace;
+pub mod wifi;
+
+pub use espnow::{espnow_bench, espnow_sim_mode, espnow_status};
+pub use events::{events_listen, events_subscribe};
+pub use feature::{
+    diff_feature_profile, feature_disable, feature_enable, feature_list, load_feature_profile,
+    save_feature_profile, sort_and_filter_features, FeatureDrift, FeatureFilter, FeatureSort,
+};
+pub use health::system_health;
+pub use imu::imu_triage_set;
+pub use led::{led_get, led_get_raw, led_off, led_set, led_set_brightness, led_test};
+pub use ota::{
+    ota_auto_update, ota_check, ota_flash, ota_verify_boot, stdout_progress_callback, OtaOptions,
+    OtaProgress, OtaResult, OtaStage,
+};
+pub use raw::raw_command;
+pub use system::{
+    system_clear_core_dump, system_clear_crash_dump, system_crash_dump, system_get_core_dump,
+    system_get_mode, system_get_partitions,
+    system_get_tasks, system_info,
+    system_memory_profile, system_ping, system_ping_with, system_self_test,
+    system_set_baud_rate, system_set_mode, system_set_name, system_set_pod_id,
+    validate_device_name, validate_pod_id, PingStats, PING_TIMEOUT_MS,
+};
+pub use touch::touch_simulate;
+pub use trace::{
+    trace_annotate, trace_capture, trace_clear, trace_dump, trace_set_buffer, trace_start,
+    trace_status, trace_stop, trace_stream, DumpOptions, TraceAnnotation, TraceBufferResult,
+    TraceFormat, TraceOutput, TraceRates, ANNOTATION_LABEL_MAX_LEN,
+};
+pub use wifi::{
+    validate_ap_config, validate_static_ip, wifi_ap_status, wifi_clear_static_ip,
+    wifi_configure_ap, wifi_disable, wifi_enable, wifi_set_static_ip, wifi_status,
+    WPA2_MIN_PASSWORD_LEN,
+};
+
+use crate::error::{DomesError, Result};
+use crate::transport::Frame;
+
+/// Check that `frame` is the response type the command expects
+pub(crate) fn expect_response(frame: &Frame, expected: u8) -> Result<()> {
+    if frame.msg_type != expected {
+        return Err(DomesError::UnexpectedResponse {
+            expected,
+            actual: frame.msg_type,
+        });
+    }
+    Ok(())
+}
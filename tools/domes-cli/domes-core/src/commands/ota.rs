@@ -0,0 +1,1355 @@
+//! OTA update commands
+//!
+//! Sends firmware updates to DOMES devices over serial or WiFi.
+//! Also includes GitHub OTA check and auto-update configuration commands.
+//!
+//! A firmware argument of the form `@<alias>` is looked up in
+//! `~/.domes/firmware.toml`, which maps aliases to image paths:
+//!
+//! ```toml
+//! latest = "/home/me/domes/firmware/build/domes.bin"
+//! bench = "images/bench.bin"   # relative to ~/.domes
+//! ```
+
+use crate::error::{Context, DomesError, Result};
+use crate::progress;
+use crate::protocol::{
+    parse_check_update_response, parse_set_auto_update_response, serialize_set_auto_update,
+    CliUpdateInfo, ConfigMsgType, ProtocolError,
+};
+use crate::transport::frame::MAX_PAYLOAD_SIZE;
+use crate::transport::{Transport, TransportType};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Write as IoWrite};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// OTA message types
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OtaMsgType {
+    Begin = 0x01,
+    Data = 0x02,
+    End = 0x03,
+    Ack = 0x04,
+    Abort = 0x05,
+}
+
+impl OtaMsgType {
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0x01 => Some(OtaMsgType::Begin),
+            0x02 => Some(OtaMsgType::Data),
+            0x03 => Some(OtaMsgType::End),
+            0x04 => Some(OtaMsgType::Ack),
+            0x05 => Some(OtaMsgType::Abort),
+            _ => None,
+        }
+    }
+}
+
+/// OTA status codes
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OtaStatus {
+    Ok = 0,
+    Busy = 1,
+    FlashError = 2,
+    VerifyFailed = 3,
+    SizeMismatch = 4,
+    OffsetMismatch = 5,
+    VersionError = 6,
+    PartitionError = 7,
+    Aborted = 8,
+}
+
+impl OtaStatus {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => OtaStatus::Ok,
+            1 => OtaStatus::Busy,
+            2 => OtaStatus::FlashError,
+            3 => OtaStatus::VerifyFailed,
+            4 => OtaStatus::SizeMismatch,
+            5 => OtaStatus::OffsetMismatch,
+            6 => OtaStatus::VersionError,
+            7 => OtaStatus::PartitionError,
+            _ => OtaStatus::Aborted,
+        }
+    }
+
+    /// Short lowercase name, for JSON output
+    pub fn cli_name(self) -> &'static str {
+        match self {
+            OtaStatus::Ok => "ok",
+            OtaStatus::Busy => "busy",
+            OtaStatus::FlashError => "flash-error",
+            OtaStatus::VerifyFailed => "verify-failed",
+            OtaStatus::SizeMismatch => "size-mismatch",
+            OtaStatus::OffsetMismatch => "offset-mismatch",
+            OtaStatus::VersionError => "version-error",
+            OtaStatus::PartitionError => "partition-error",
+            OtaStatus::Aborted => "aborted",
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            OtaStatus::Ok => "OK",
+            OtaStatus::Busy => "Busy",
+            OtaStatus::FlashError => "Flash error",
+            OtaStatus::VerifyFailed => "Verification failed",
+            OtaStatus::SizeMismatch => "Size mismatch",
+            OtaStatus::OffsetMismatch => "Offset mismatch",
+            OtaStatus::VersionError => "Version error",
+            OtaStatus::PartitionError => "Partition error",
+            OtaStatus::Aborted => "Aborted",
+        }
+    }
+
+    /// Actionable hint for the user, if there is something they can do
+    pub fn remediation(&self) -> Option<&'static str> {
+        match self {
+            OtaStatus::Busy => Some(
+                "The device is already in the middle of an OTA update (an interrupted \
+                 flash?). Retry with --force to abort it first. Power-cycle the device \
+                 if it stays busy.",
+            ),
+            OtaStatus::FlashError => Some(
+                "Writing to flash failed. Retry; if it keeps failing, check the image \
+                 fits the OTA partition.",
+            ),
+            OtaStatus::VerifyFailed => Some(
+                "The SHA256 of the received image did not match. The transfer was \
+                 corrupted; retry the update.",
+            ),
+            OtaStatus::SizeMismatch => Some(
+                "The device received a different number of bytes than announced. \
+                 Make sure the firmware file is not changing during the upload.",
+            ),
+            OtaStatus::OffsetMismatch => Some(
+                "A chunk arrived out of order, usually after a dropped frame. \
+                 Retry, preferably over a more reliable link (USB instead of BLE).",
+            ),
+            OtaStatus::VersionError => Some(
+                "The device rejected the version string (likely a downgrade or an \
+                 invalid version). Pass a newer version with --version.",
+            ),
+            OtaStatus::PartitionError => Some(
+                "No usable OTA partition. Check the partition table has ota_0/ota_1 \
+                 slots and reflash it over USB with idf.py flash.",
+            ),
+            OtaStatus::Ok | OtaStatus::Aborted => None,
+        }
+    }
+
+    /// Status name followed by the remediation hint, for error messages
+    fn describe(self) -> String {
+        match self.remediation() {
+            Some(hint) => format!("{}. {}", self.as_str(), hint),
+            None => self.as_str().to_string(),
+        }
+    }
+}
+
+// Note: OTA chunk size is now determined by the transport's max_ota_chunk_size() method
+// to handle different MTU limits (BLE uses smaller chunks than serial/TCP)
+
+/// SHA256 size
+const SHA256_SIZE: usize = 32;
+
+/// Version string max length
+const VERSION_MAX_LEN: usize = 32;
+
+/// Timeout for OTA operations (ms)
+const OTA_TIMEOUT_MS: u64 = 5000;
+
+/// Timeout for OTA_END (device reboots) (ms)
+const OTA_END_TIMEOUT_MS: u64 = 30000;
+
+/// OTA_END flag: set the boot partition but don't reboot into it yet
+const OTA_END_NO_REBOOT: u8 = 0x01;
+
+/// OTA_DATA header before the chunk: [u32 offset][u16 length]
+const OTA_DATA_HEADER_SIZE: usize = 6;
+
+/// Time for the device to clean up after OTA_ABORT (it sends no reply)
+const OTA_ABORT_SETTLE: Duration = Duration::from_millis(200);
+
+/// Time for the device to reconfigure its UART after a baud rate switch
+const BAUD_SWITCH_SETTLE: Duration = Duration::from_millis(20);
+
+/// Time for the device to go down after OTA_END before it is first asked
+/// for its version
+const REBOOT_SETTLE: Duration = Duration::from_millis(1000);
+
+/// Pause between attempts to reach a rebooting device
+const REBOOT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How to run an update; `Default` is a plain flash that reboots when done
+#[derive(Debug, Clone, Default)]
+pub struct OtaOptions<'a> {
+    /// Version string sent in OTA_BEGIN ("unknown" if not given)
+    pub version: Option<&'a str>,
+    /// Keep running the current image; the new one boots on the next reboot.
+    /// Firmware that predates the flag ignores it and reboots immediately.
+    /// Serial and BLE only: `ota_flash` rejects it over WiFi.
+    pub no_reboot: bool,
+    /// Firmware bytes per OTA_DATA frame; `None` uses the transport's maximum
+    pub chunk_size: Option<usize>,
+    /// If OTA_BEGIN gets `Busy`, send OTA_ABORT to end the stale session and
+    /// retry OTA_BEGIN once
+    pub force: bool,
+    /// Serial baud rate to send the image at, switching back afterwards;
+    /// `None`, a rate no faster than the current one, or firmware that
+    /// cannot switch keeps the current rate
+    pub baud: Option<u32>,
+}
+
+/// Chunk size to use: `requested` (or the transport's maximum), capped at
+/// `transport_max` and at what fits in one frame with the OTA_DATA header
+pub fn ota_chunk_size(transport_max: usize, requested: Option<usize>) -> usize {
+    let max = transport_max.min(MAX_PAYLOAD_SIZE - OTA_DATA_HEADER_SIZE);
+    requested.unwrap_or(max).clamp(1, max)
+}
+
+/// A completed OTA update
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtaResult {
+    /// Image size in bytes
+    pub bytes: usize,
+    /// SHA256 of the image, hex-encoded
+    pub sha256: String,
+    /// Version string sent in OTA_BEGIN
+    pub version: String,
+    /// Time from OTA_BEGIN to the OTA_END acknowledgement
+    pub duration: Duration,
+}
+
+/// Stage of an update, as reported to the progress callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtaStage {
+    /// About to send OTA_BEGIN
+    Preparing,
+    /// A chunk was acknowledged
+    Sending,
+    /// All data sent; OTA_END asks the device to verify the image
+    Verifying,
+    /// The device accepted the image
+    Complete,
+    /// The update stopped; the counts are from the last acknowledged chunk
+    Failed,
+}
+
+/// Progress of an update, passed to the `ota_flash` callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OtaProgress {
+    pub stage: OtaStage,
+    /// Image bytes the device has acknowledged
+    pub bytes_sent: usize,
+    /// Image size in bytes
+    pub bytes_total: usize,
+    /// Chunks the device has acknowledged
+    pub chunk_index: u32,
+    pub chunk_total: u32,
+}
+
+/// Progress callback that draws the CLI's progress bar on stderr
+///
+/// Suppressed by `--quiet`; with several devices sharing the terminal, a line
+/// is printed at each 10% step instead.
+pub fn stdout_progress_callback() -> impl FnMut(OtaProgress) {
+    let mut previous = 0;
+    move |update: OtaProgress| match update.stage {
+        OtaStage::Sending => {
+            print_progress(previous, update.bytes_sent, update.bytes_total);
+            previous = update.bytes_sent;
+        }
+        // End the bar's line
+        OtaStage::Verifying | OtaStage::Failed if previous > 0 => {
+            if !progress::is_interleaved() {
+                progress!();
+            }
+            previous = 0;
+        }
+        _ => {}
+    }
+}
+
+/// Passes progress to the callback, remembering the last update for `Failed`
+struct ProgressReporter<F> {
+    callback: F,
+    last: OtaProgress,
+}
+
+impl<F: FnMut(OtaProgress)> ProgressReporter<F> {
+    fn report(&mut self, stage: OtaStage, bytes_sent: usize, chunk_index: u32) {
+        self.last = OtaProgress {
+            stage,
+            bytes_sent,
+            chunk_index,
+            ..self.last
+        };
+        (self.callback)(self.last);
+    }
+}
+
+/// Send firmware OTA update to device
+///
+/// `progress` is called before OTA_BEGIN, after each acknowledged chunk,
+/// before OTA_END, and once more when the update completes or fails; pass
+/// `stdout_progress_callback()` for the CLI's progress bar.
+///
+/// A refused or aborted update fails with `DomesError::OtaRejected`, which
+/// carries the device's status and the image offset it stopped at.
+pub fn ota_flash(
+    transport: &mut dyn Transport,
+    firmware_path: &Path,
+    options: &OtaOptions,
+    progress: impl FnMut(OtaProgress),
+) -> Result<OtaResult> {
+    // Only the serial/BLE OTA receiver reads OTA_END flags
+    if options.no_reboot && transport.transport_type() == TransportType::Tcp {
+        return Err(DomesError::InvalidArgument(
+            "--no-reboot is only supported over serial and BLE".to_string(),
+        ));
+    }
+
+    // Read firmware file
+    let resolved = resolve_firmware_path(firmware_path)?;
+    if resolved != firmware_path {
+        progress!(
+            "Reading firmware from '{}' ({})...",
+            firmware_path.display(),
+            resolved.display()
+        );
+    } else {
+        progress!("Reading firmware from '{}'...", firmware_path.display());
+    }
+    let firmware = read_firmware_file(&resolved)?;
+    progress!("Firmware size: {}", progress::format_bytes(firmware.len() as u64));
+
+    // Compute SHA256
+    progress!("Computing SHA256...");
+    let sha256 = compute_sha256(&firmware);
+    progress!("SHA256: {}", hex::encode(sha256));
+
+    let version_str = options.version.unwrap_or("unknown");
+    let started = Instant::now();
+
+    // Use transport-specific chunk size (BLE needs smaller chunks due to MTU limits)
+    // A device with a smaller frame limit also caps the chunk
+    let payload_max = transport.max_payload().saturating_sub(OTA_DATA_HEADER_SIZE);
+    let transport_max = transport.max_ota_chunk_size().min(payload_max);
+    let ota_chunk_size = ota_chunk_size(transport_max, options.chunk_size);
+    let total = firmware.len();
+
+    let mut reporter = ProgressReporter {
+        callback: progress,
+        last: OtaProgress {
+            stage: OtaStage::Preparing,
+            bytes_sent: 0,
+            bytes_total: total,
+            chunk_index: 0,
+            chunk_total: total.div_ceil(ota_chunk_size) as u32,
+        },
+    };
+    reporter.report(OtaStage::Preparing, 0, 0);
+    let result = send_image(transport, &firmware, &sha256, options, ota_chunk_size, &mut reporter);
+    let last = reporter.last;
+    match &result {
+        Ok(()) => reporter.report(OtaStage::Complete, total, last.chunk_total),
+        Err(_) => reporter.report(OtaStage::Failed, last.bytes_sent, last.chunk_index),
+    }
+    result?;
+
+    Ok(OtaResult {
+        bytes: total,
+        sha256: hex::encode(sha256),
+        version: version_str.to_string(),
+        duration: started.elapsed(),
+    })
+}
+
+/// OTA_BEGIN, the image in chunks of `ota_chunk_size`, then OTA_END
+///
+/// A serial link runs at `options.baud` between OTA_BEGIN and OTA_END.
+fn send_image<F: FnMut(OtaProgress)>(
+    transport: &mut dyn Transport,
+    firmware: &[u8],
+    sha256: &[u8; SHA256_SIZE],
+    options: &OtaOptions,
+    ota_chunk_size: usize,
+    reporter: &mut ProgressReporter<F>,
+) -> Result<()> {
+    let version_str = options.version.unwrap_or("unknown");
+
+    // Send OTA_BEGIN
+    progress!("Sending OTA_BEGIN (version: {})...", version_str);
+    let begin_payload = serialize_ota_begin(firmware.len() as u32, sha256, version_str);
+
+    let mut begin =
+        send_and_wait_ack(transport, OtaMsgType::Begin, &begin_payload, 0, OTA_TIMEOUT_MS);
+    if options.force && is_busy(&begin) {
+        progress!("Device busy, aborting stale session and retrying");
+        transport
+            .send_frame(OtaMsgType::Abort as u8, &[OtaStatus::Aborted as u8])
+            .context("Failed to send OTA_ABORT")?;
+        std::thread::sleep(OTA_ABORT_SETTLE);
+        begin = send_and_wait_ack(transport, OtaMsgType::Begin, &begin_payload, 0, OTA_TIMEOUT_MS);
+    }
+    let (status, _next_offset) = begin?;
+
+    if status != OtaStatus::Ok {
+        return Err(rejected(status, 0, "Device rejected OTA_BEGIN"));
+    }
+    progress!("Device accepted OTA_BEGIN.");
+
+    let original_baud = switch_baud_rate(transport, options.baud)?;
+    let result = send_data(transport, firmware, options, ota_chunk_size, reporter);
+    if let Some(baud) = original_baud {
+        // A device that reboots comes back at its default rate by itself
+        let rebooting = result.is_ok() && !options.no_reboot;
+        if let Err(e) = restore_baud_rate(transport, baud, rebooting) {
+            result?;
+            return Err(e);
+        }
+    }
+    result
+}
+
+/// Switch a serial link to `target` if it is faster than the current rate;
+/// returns the rate to go back to
+///
+/// Firmware that cannot switch is not an error: the update runs at the
+/// current rate.
+fn switch_baud_rate(transport: &mut dyn Transport, target: Option<u32>) -> Result<Option<u32>> {
+    let Some(target) = target else {
+        return Ok(None);
+    };
+    if transport.transport_type() != TransportType::Serial {
+        return Ok(None);
+    }
+    let Some(current) = transport.baud_rate() else {
+        return Ok(None);
+    };
+    if current >= target {
+        return Ok(None);
+    }
+
+    progress!("Switching to {} baud for the transfer...", target);
+    match super::system_set_baud_rate(transport, target) {
+        Ok(baud) => {
+            transport
+                .set_baud_rate(baud)
+                .context("Failed to switch the serial port")?;
+            std::thread::sleep(BAUD_SWITCH_SETTLE);
+            Ok(Some(current))
+        }
+        Err(e) => {
+            progress!("Device did not switch baud rate ({}); staying at {} baud", e, current);
+            Ok(None)
+        }
+    }
+}
+
+/// Put the link back to `baud` after the transfer; a rebooting device is
+/// already on its way back to it
+fn restore_baud_rate(transport: &mut dyn Transport, baud: u32, rebooting: bool) -> Result<()> {
+    progress!("Switching back to {} baud...", baud);
+    if !rebooting {
+        super::system_set_baud_rate(transport, baud)?;
+    }
+    transport
+        .set_baud_rate(baud)
+        .context("Failed to switch the serial port back")?;
+    Ok(())
+}
+
+/// The image in chunks of `ota_chunk_size`, then OTA_END
+fn send_data<F: FnMut(OtaProgress)>(
+    transport: &mut dyn Transport,
+    firmware: &[u8],
+    options: &OtaOptions,
+    ota_chunk_size: usize,
+    reporter: &mut ProgressReporter<F>,
+) -> Result<()> {
+    // Send firmware chunks
+    if options.chunk_size.is_some_and(|requested| requested > ota_chunk_size) {
+        progress!(
+            "Chunk size limited to {} bytes (transport maximum)",
+            ota_chunk_size
+        );
+    }
+    progress!(
+        "Sending firmware data (chunk size: {} bytes)...",
+        ota_chunk_size
+    );
+    let mut offset: usize = 0;
+    let mut chunk_index = 0;
+    let total = firmware.len();
+
+    while offset < total {
+        let chunk_size = std::cmp::min(ota_chunk_size, total - offset);
+        let chunk = &firmware[offset..offset + chunk_size];
+
+        let data_payload = serialize_ota_data(offset as u32, chunk);
+
+        let (status, _next_offset) = send_and_wait_ack(
+            transport,
+            OtaMsgType::Data,
+            &data_payload,
+            offset,
+            OTA_TIMEOUT_MS,
+        )?;
+
+        if status != OtaStatus::Ok {
+            let context = format!("Device rejected chunk at offset {}", offset);
+            return Err(rejected(status, offset, context));
+        }
+
+        offset += chunk_size;
+        chunk_index += 1;
+        reporter.report(OtaStage::Sending, offset, chunk_index);
+    }
+
+    // Send OTA_END
+    reporter.report(OtaStage::Verifying, total, chunk_index);
+    progress!("Sending OTA_END...");
+    let end_payload = serialize_ota_end(options.no_reboot);
+    let (status, _) = send_and_wait_ack(
+        transport,
+        OtaMsgType::End,
+        &end_payload,
+        total,
+        OTA_END_TIMEOUT_MS,
+    )?;
+
+    if status != OtaStatus::Ok {
+        return Err(rejected(status, total, "Device rejected OTA_END"));
+    }
+    Ok(())
+}
+
+/// Whether OTA_BEGIN was refused because another update is in progress
+fn is_busy(begin: &Result<(OtaStatus, u32)>) -> bool {
+    matches!(
+        begin,
+        Ok((OtaStatus::Busy, _))
+            | Err(DomesError::OtaRejected {
+                status: OtaStatus::Busy,
+                ..
+            })
+    )
+}
+
+/// `OtaRejected` error: "<context>: <status and hint>"
+fn rejected(status: OtaStatus, offset: usize, context: impl std::fmt::Display) -> DomesError {
+    DomesError::OtaRejected {
+        status,
+        offset,
+        message: format!("{}: {}", context, status.describe()),
+    }
+}
+
+/// Wait up to `timeout` for a device rebooting after OTA_END to answer
+/// again, and check that it runs `expected_version` (`ota flash --verify-boot`)
+///
+/// Each attempt asks for the system info and, if the device does not
+/// answer, reconnects the transport: a USB serial port re-enumerates and a
+/// TCP connection drops when the device reboots. An answer with more
+/// uptime than the time waited still comes from the old image. Returns the
+/// running version; a different one fails with `DomesError::Rejected`, as
+/// the bootloader rolled back to the previous image.
+pub fn ota_verify_boot(
+    transport: &mut dyn Transport,
+    expected_version: &str,
+    timeout: Duration,
+) -> Result<String> {
+    verify_boot(transport, expected_version, timeout, REBOOT_SETTLE, REBOOT_POLL_INTERVAL)
+}
+
+fn verify_boot(
+    transport: &mut dyn Transport,
+    expected_version: &str,
+    timeout: Duration,
+    settle: Duration,
+    poll_interval: Duration,
+) -> Result<String> {
+    progress!("Waiting up to {} ms for the device to reboot...", timeout.as_millis());
+    let started = Instant::now();
+    let deadline = started + timeout;
+    std::thread::sleep(settle.min(timeout));
+
+    let info = loop {
+        let error = match super::system_info(transport) {
+            Ok(info) if info.uptime_s as u64 <= started.elapsed().as_secs() + 1 => break info,
+            Ok(_) => DomesError::Rejected("The device has not rebooted yet".to_string()),
+            Err(e) => e,
+        };
+        if Instant::now() >= deadline {
+            return Err(DomesError::Timeout {
+                context: format!(
+                    "The device did not come back within {} ms of the update",
+                    timeout.as_millis()
+                ),
+                source: Box::new(error),
+            });
+        }
+        std::thread::sleep(poll_interval.min(deadline.saturating_duration_since(Instant::now())));
+        // A failed reconnect shows up as the next attempt's error
+        let _ = transport.reconnect();
+    };
+
+    let version = info.firmware_version.trim();
+    if version != expected_version.trim() {
+        let found = if version.is_empty() { "an unknown version" } else { version };
+        return Err(DomesError::Rejected(format!(
+            "The device rebooted into {}, not {} (it may have rolled back to the previous image)",
+            found, expected_version
+        )));
+    }
+    Ok(version.to_string())
+}
+
+/// Read firmware file into memory
+/// The image path for a firmware argument: `@<alias>` is looked up in
+/// `~/.domes/firmware.toml`, anything else is returned unchanged
+pub fn resolve_firmware_path(path: &Path) -> Result<PathBuf> {
+    let Some(alias) = path.to_str().and_then(|p| p.strip_prefix('@')) else {
+        return Ok(path.to_path_buf());
+    };
+    let aliases_path = firmware_aliases_path();
+    let content = match std::fs::read_to_string(&aliases_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(DomesError::InvalidArgument(format!(
+                "Firmware alias '@{}' needs {} (e.g. {} = \"/path/to/domes.bin\")",
+                alias,
+                aliases_path.display(),
+                alias
+            )));
+        }
+        Err(e) => return Err(e).context(format!("Failed to read {}", aliases_path.display())),
+    };
+    let base = aliases_path.parent().unwrap_or(Path::new("."));
+    lookup_firmware_alias(&content, alias, base).map_err(|e| {
+        DomesError::InvalidArgument(format!("{}: {}", aliases_path.display(), e))
+    })
+}
+
+/// Look `alias` up in the contents of firmware.toml; relative paths are
+/// taken from `base`, the file's directory
+fn lookup_firmware_alias(
+    content: &str,
+    alias: &str,
+    base: &Path,
+) -> std::result::Result<PathBuf, String> {
+    let aliases: HashMap<String, PathBuf> =
+        toml::from_str(content).map_err(|e| format!("invalid firmware aliases: {}", e))?;
+    match aliases.get(alias) {
+        Some(path) => Ok(base.join(path)),
+        None => {
+            let mut known: Vec<&str> = aliases.keys().map(String::as_str).collect();
+            known.sort();
+            Err(format!(
+                "no firmware alias '@{}' (defined: {})",
+                alias,
+                if known.is_empty() { "none".to_string() } else { known.join(", ") }
+            ))
+        }
+    }
+}
+
+fn firmware_aliases_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".domes").join("firmware.toml")
+}
+
+fn read_firmware_file(path: &Path) -> Result<Vec<u8>> {
+    let mut file = File::open(path)
+        .with_context(|| format!("Cannot open firmware file {}", path.display()))?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)
+        .context("Failed to read firmware file")?;
+
+    if data.is_empty() {
+        return Err(DomesError::InvalidArgument(
+            "Firmware file is empty".to_string(),
+        ));
+    }
+
+    Ok(data)
+}
+
+/// Compute SHA256 hash
+fn compute_sha256(data: &[u8]) -> [u8; SHA256_SIZE] {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    let result = hasher.finalize();
+    let mut hash = [0u8; SHA256_SIZE];
+    hash.copy_from_slice(&result);
+    hash
+}
+
+/// Serialize OTA_BEGIN payload
+/// Format: [u32 firmwareSize][32 bytes sha256][32 bytes version]
+fn serialize_ota_begin(firmware_size: u32, sha256: &[u8; 32], version: &str) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(4 + 32 + 32);
+
+    // Firmware size (little-endian)
+    payload.extend_from_slice(&firmware_size.to_le_bytes());
+
+    // SHA256
+    payload.extend_from_slice(sha256);
+
+    // Version (null-terminated, padded to 32 bytes)
+    let mut version_bytes = [0u8; VERSION_MAX_LEN];
+    let version_slice = version.as_bytes();
+    let copy_len = std::cmp::min(version_slice.len(), VERSION_MAX_LEN - 1);
+    version_bytes[..copy_len].copy_from_slice(&version_slice[..copy_len]);
+    payload.extend_from_slice(&version_bytes);
+
+    payload
+}
+
+/// Serialize OTA_DATA payload
+/// Format: [u32 offset][u16 length][data...]
+fn serialize_ota_data(offset: u32, data: &[u8]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(4 + 2 + data.len());
+
+    // Offset (little-endian)
+    payload.extend_from_slice(&offset.to_le_bytes());
+
+    // Length (little-endian)
+    payload.extend_from_slice(&(data.len() as u16).to_le_bytes());
+
+    // Data
+    payload.extend_from_slice(data);
+
+    payload
+}
+
+/// Serialize OTA_END payload
+/// Format: [u8 flags]
+fn serialize_ota_end(no_reboot: bool) -> Vec<u8> {
+    let mut flags = 0;
+    if no_reboot {
+        flags |= OTA_END_NO_REBOOT;
+    }
+    vec![flags]
+}
+
+/// Deserialize OTA_ACK payload
+/// Format: [u8 status][u32 nextOffset]
+fn deserialize_ota_ack(payload: &[u8]) -> Result<(OtaStatus, u32)> {
+    if payload.len() < 5 {
+        return Err(ProtocolError::PayloadTooShort {
+            expected: 5,
+            actual: payload.len(),
+        })
+        .context("Invalid OTA_ACK payload");
+    }
+
+    let status = OtaStatus::from_u8(payload[0]);
+    let next_offset = u32::from_le_bytes([payload[1], payload[2], payload[3], payload[4]]);
+
+    Ok((status, next_offset))
+}
+
+/// Deserialize OTA_ABORT payload
+fn deserialize_ota_abort(payload: &[u8]) -> Result<OtaStatus> {
+    if payload.is_empty() {
+        return Err(ProtocolError::PayloadTooShort {
+            expected: 1,
+            actual: 0,
+        })
+        .context("Invalid OTA_ABORT payload");
+    }
+    Ok(OtaStatus::from_u8(payload[0]))
+}
+
+/// Send a frame and wait for ACK
+///
+/// `offset` is where in the image the frame belongs, for an abort error.
+fn send_and_wait_ack(
+    transport: &mut dyn Transport,
+    msg_type: OtaMsgType,
+    payload: &[u8],
+    offset: usize,
+    timeout_ms: u64,
+) -> Result<(OtaStatus, u32)> {
+    // Send the frame
+    transport
+        .send_frame(msg_type as u8, payload)
+        .context("Failed to send OTA frame")?;
+
+    // Wait for response
+    let frame = transport
+        .receive_frame(timeout_ms)
+        .context("Timeout waiting for OTA response")?;
+
+    match OtaMsgType::from_u8(frame.msg_type) {
+        Some(OtaMsgType::Ack) => deserialize_ota_ack(&frame.payload),
+        Some(OtaMsgType::Abort) => {
+            let reason = deserialize_ota_abort(&frame.payload)?;
+            Err(rejected(reason, offset, "Device aborted OTA"))
+        }
+        _ => Err(DomesError::UnexpectedResponse {
+            expected: OtaMsgType::Ack as u8,
+            actual: frame.msg_type,
+        }),
+    }
+}
+
+/// Timeout for update check (device queries GitHub API over HTTPS)
+const OTA_CHECK_TIMEOUT_MS: u64 = 15000;
+
+/// Check for firmware updates via GitHub releases
+pub fn ota_check(transport: &mut dyn Transport) -> Result<CliUpdateInfo> {
+    let frame = transport
+        .send_command_expect_with_timeout(
+            ConfigMsgType::CheckUpdateReq as u8,
+            &[],
+            ConfigMsgType::CheckUpdateRsp as u8,
+            OTA_CHECK_TIMEOUT_MS,
+        )
+        .context("Failed to send check update command")?;
+
+    parse_check_update_response(&frame.payload).context("Failed to parse check update response")
+}
+
+/// Set auto-update enabled/disabled
+pub fn ota_auto_update(transport: &mut dyn Transport, enabled: bool) -> Result<bool> {
+    let payload = serialize_set_auto_update(enabled);
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::SetAutoUpdateReq as u8,
+            &payload,
+            ConfigMsgType::SetAutoUpdateRsp as u8,
+        )
+        .context("Failed to send set auto-update command")?;
+
+    parse_set_auto_update_response(&frame.payload)
+        .context("Failed to parse set auto-update response")
+}
+
+/// Print progress bar to stderr (suppressed by `--quiet`)
+///
+/// When several devices share the terminal, a line is printed at each 10%
+/// step instead of redrawing the bar.
+fn print_progress(previous: usize, current: usize, total: usize) {
+    const BAR_WIDTH: usize = 40;
+    if progress::is_quiet() {
+        return;
+    }
+    if progress::is_interleaved() {
+        if previous * 10 / total != current * 10 / total {
+            progress!(
+                "{} ({:.0}%)",
+                format_transferred(current, total),
+                current as f64 / total as f64 * 100.0
+            );
+        }
+        return;
+    }
+    let progress = current as f64 / total as f64;
+    let pos = (BAR_WIDTH as f64 * progress) as usize;
+
+    eprint!("\r[");
+    for i in 0..BAR_WIDTH {
+        if i < pos {
+            eprint!("=");
+        } else if i == pos {
+            eprint!(">");
+        } else {
+            eprint!(" ");
+        }
+    }
+    eprint!(
+        "] {} ({:.1}%)",
+        format_transferred(current, total),
+        progress * 100.0
+    );
+    std::io::stderr().flush().ok();
+}
+
+/// `1024 / 4096 bytes`, or `1.2 MB / 2.1 MB` with `--human-readable`
+fn format_transferred(current: usize, total: usize) -> String {
+    if progress::is_human_readable() {
+        format!(
+            "{} / {}",
+            progress::format_bytes_human(current as u64),
+            progress::format_bytes_human(total as u64)
+        )
+    } else {
+        format!("{} / {} bytes", current, total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_ota_end_flags() {
+        assert_eq!(serialize_ota_end(false), vec![0x00]);
+        assert_eq!(serialize_ota_end(true), vec![OTA_END_NO_REBOOT]);
+    }
+
+    #[test]
+    fn test_remediation_actionable_statuses() {
+        assert!(OtaStatus::VersionError
+            .remediation()
+            .unwrap()
+            .contains("--version"));
+        assert!(OtaStatus::PartitionError
+            .remediation()
+            .unwrap()
+            .contains("partition table"));
+        assert!(OtaStatus::Busy.remediation().unwrap().contains("Power-cycle"));
+        assert!(OtaStatus::Busy.remediation().unwrap().contains("--force"));
+    }
+
+    #[test]
+    fn test_remediation_none_for_ok_and_aborted() {
+        assert_eq!(OtaStatus::Ok.remediation(), None);
+        assert_eq!(OtaStatus::Aborted.remediation(), None);
+    }
+
+    #[test]
+    fn test_every_error_status_has_hint() {
+        for code in 1..=7u8 {
+            let status = OtaStatus::from_u8(code);
+            assert!(status.remediation().is_some(), "{:?} has no hint", status);
+        }
+    }
+
+    #[test]
+    fn test_abort_error_includes_hint() {
+        let reason = deserialize_ota_abort(&[OtaStatus::VersionError as u8]).unwrap();
+        let msg = reason.describe();
+        assert!(msg.starts_with("Version error. "));
+        assert!(msg.contains("downgrade"));
+        assert_eq!(OtaStatus::Aborted.describe(), "Aborted");
+    }
+
+    #[test]
+    fn test_ota_flash_result_and_rejection_offset() {
+        use crate::transport::batch::ReplayTransport;
+        use crate::transport::Frame;
+
+        let ack = |status: OtaStatus| Frame {
+            msg_type: OtaMsgType::Ack as u8,
+            payload: vec![status as u8, 0, 0, 0, 0],
+        };
+        let path = std::env::temp_dir().join(format!("domes-ota-test-{}.bin", std::process::id()));
+        std::fs::write(&path, [0xAB; 10]).unwrap();
+
+        let mut device = ReplayTransport::new(vec![ack(OtaStatus::Ok); 3]);
+        let options = OtaOptions {
+            version: Some("v1.2.0"),
+            ..Default::default()
+        };
+        let result = ota_flash(&mut device, &path, &options, |_| {}).unwrap();
+        assert_eq!(result.bytes, 10);
+        assert_eq!(result.sha256, hex::encode(compute_sha256(&[0xAB; 10])));
+        assert_eq!(result.version, "v1.2.0");
+
+        let mut device = ReplayTransport::new(vec![ack(OtaStatus::Ok), ack(OtaStatus::FlashError)]);
+        let err = ota_flash(&mut device, &path, &OtaOptions::default(), |_| {}).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            err,
+            DomesError::OtaRejected {
+                status: OtaStatus::FlashError,
+                offset: 0,
+                ..
+            }
+        ));
+        assert!(err
+            .to_string()
+            .starts_with("Device rejected chunk at offset 0: Flash error. "));
+    }
+
+    #[test]
+    fn test_ota_flash_force_retries_busy_begin() {
+        use crate::transport::batch::ReplayTransport;
+        use crate::transport::Frame;
+
+        let ack = |status: OtaStatus| Frame {
+            msg_type: OtaMsgType::Ack as u8,
+            payload: vec![status as u8, 0, 0, 0, 0],
+        };
+        let name = format!("domes-ota-force-{}.bin", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, [0xCD; 10]).unwrap();
+
+        let mut device = ReplayTransport::new(vec![ack(OtaStatus::Busy)]);
+        let err = ota_flash(&mut device, &path, &OtaOptions::default(), |_| {}).unwrap_err();
+        assert!(matches!(err, DomesError::OtaRejected { status: OtaStatus::Busy, .. }));
+
+        // Busy, then OK for the retried OTA_BEGIN, the chunk and OTA_END
+        let mut responses = vec![ack(OtaStatus::Busy)];
+        responses.extend(vec![ack(OtaStatus::Ok); 3]);
+        let mut device = ReplayTransport::new(responses);
+        let options = OtaOptions {
+            force: true,
+            ..Default::default()
+        };
+        let result = ota_flash(&mut device, &path, &options, |_| {});
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap().bytes, 10);
+    }
+
+    #[test]
+    fn test_ota_flash_reports_progress() {
+        use crate::transport::batch::ReplayTransport;
+        use crate::transport::Frame;
+
+        let ack = |status: OtaStatus| Frame {
+            msg_type: OtaMsgType::Ack as u8,
+            payload: vec![status as u8, 0, 0, 0, 0],
+        };
+        let name = format!("domes-ota-progress-{}.bin", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, [0x11; 10]).unwrap();
+        let options = OtaOptions {
+            chunk_size: Some(4),
+            ..Default::default()
+        };
+        let summary = |updates: &[OtaProgress]| -> Vec<(OtaStage, usize, u32)> {
+            updates
+                .iter()
+                .map(|u| (u.stage, u.bytes_sent, u.chunk_index))
+                .collect()
+        };
+
+        let mut updates = Vec::new();
+        let mut device = ReplayTransport::new(vec![ack(OtaStatus::Ok); 5]);
+        ota_flash(&mut device, &path, &options, |u| updates.push(u)).unwrap();
+        assert!(updates.iter().all(|u| u.bytes_total == 10 && u.chunk_total == 3));
+        assert_eq!(
+            summary(&updates),
+            [
+                (OtaStage::Preparing, 0, 0),
+                (OtaStage::Sending, 4, 1),
+                (OtaStage::Sending, 8, 2),
+                (OtaStage::Sending, 10, 3),
+                (OtaStage::Verifying, 10, 3),
+                (OtaStage::Complete, 10, 3),
+            ]
+        );
+
+        // The second chunk is refused
+        let mut updates = Vec::new();
+        let responses = vec![ack(OtaStatus::Ok), ack(OtaStatus::Ok), ack(OtaStatus::FlashError)];
+        let mut device = ReplayTransport::new(responses);
+        assert!(ota_flash(&mut device, &path, &options, |u| updates.push(u)).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            summary(&updates),
+            [
+                (OtaStage::Preparing, 0, 0),
+                (OtaStage::Sending, 4, 1),
+                (OtaStage::Failed, 4, 1),
+            ]
+        );
+    }
+
+    /// Serial device that acknowledges OTA frames and, if `switches`,
+    /// baud rate changes; records the rates the port is set to
+    struct SerialDevice {
+        switches: bool,
+        baud: u32,
+        port_rates: Vec<u32>,
+        last_sent: u8,
+    }
+
+    impl Transport for SerialDevice {
+        fn send_frame(&mut self, msg_type: u8, _payload: &[u8]) -> anyhow::Result<()> {
+            self.last_sent = msg_type;
+            Ok(())
+        }
+
+        fn receive_frame(&mut self, _timeout_ms: u64) -> anyhow::Result<crate::transport::Frame> {
+            if self.last_sent != ConfigMsgType::SetBaudRateReq as u8 {
+                return Ok(crate::transport::Frame {
+                    msg_type: OtaMsgType::Ack as u8,
+                    payload: vec![OtaStatus::Ok as u8, 0, 0, 0, 0],
+                });
+            }
+            if !self.switches {
+                return Err(crate::transport::ResponseTimeout.into());
+            }
+            use prost::Message;
+            let response = crate::proto::config::SetBaudRateResponse { baud_rate: 921600 };
+            let mut payload = vec![crate::proto::config::Status::Ok as u8];
+            payload.extend(response.encode_to_vec());
+            Ok(crate::transport::Frame {
+                msg_type: ConfigMsgType::SetBaudRateRsp as u8,
+                payload,
+            })
+        }
+
+        fn send_command(
+            &mut self,
+            msg_type: u8,
+            payload: &[u8],
+        ) -> anyhow::Result<crate::transport::Frame> {
+            self.send_frame(msg_type, payload)?;
+            self.receive_frame(0)
+        }
+
+        fn transport_type(&self) -> TransportType {
+            TransportType::Serial
+        }
+
+        fn baud_rate(&self) -> Option<u32> {
+            Some(self.baud)
+        }
+
+        fn set_baud_rate(&mut self, baud_rate: u32) -> anyhow::Result<()> {
+            self.baud = baud_rate;
+            self.port_rates.push(baud_rate);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_ota_flash_switches_serial_baud_rate() {
+        let name = format!("domes-ota-baud-{}.bin", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, [0x42; 10]).unwrap();
+        let options = OtaOptions {
+            baud: Some(921600),
+            ..Default::default()
+        };
+        let device = |switches| SerialDevice {
+            switches,
+            baud: 115200,
+            port_rates: Vec::new(),
+            last_sent: 0,
+        };
+
+        let mut switching = device(true);
+        ota_flash(&mut switching, &path, &options, |_| {}).unwrap();
+        assert_eq!(switching.port_rates, [921600, 115200]);
+
+        // Firmware without the command never answers; the update still runs
+        let mut old = device(false);
+        ota_flash(&mut old, &path, &options, |_| {}).unwrap();
+        assert!(old.port_rates.is_empty());
+
+        // Already at the rate
+        let mut fast = device(true);
+        fast.baud = 921600;
+        ota_flash(&mut fast, &path, &options, |_| {}).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(fast.port_rates.is_empty());
+    }
+
+    /// Stops answering until it has been reconnected `down_for` times, then
+    /// reports `version` with `uptime_s`
+    struct RebootingDevice {
+        down_for: u32,
+        reconnects: u32,
+        version: &'static str,
+        uptime_s: u32,
+    }
+
+    impl Transport for RebootingDevice {
+        fn send_frame(&mut self, _msg_type: u8, _payload: &[u8]) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn receive_frame(&mut self, _timeout_ms: u64) -> anyhow::Result<crate::transport::Frame> {
+            use prost::Message;
+            if self.reconnects < self.down_for {
+                return Err(crate::transport::ResponseTimeout.into());
+            }
+            let info = crate::proto::config::GetSystemInfoResponse {
+                firmware_version: self.version.to_string(),
+                uptime_s: self.uptime_s,
+                ..Default::default()
+            };
+            let mut payload = vec![crate::proto::config::Status::Ok as u8];
+            payload.extend(info.encode_to_vec());
+            Ok(crate::transport::Frame {
+                msg_type: ConfigMsgType::GetSystemInfoRsp as u8,
+                payload,
+            })
+        }
+
+        fn send_command(
+            &mut self,
+            msg_type: u8,
+            payload: &[u8],
+        ) -> anyhow::Result<crate::transport::Frame> {
+            self.send_frame(msg_type, payload)?;
+            self.receive_frame(0)
+        }
+
+        fn reconnect(&mut self) -> anyhow::Result<()> {
+            self.reconnects += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_verify_boot_reconnects_and_checks_version() {
+        let device = |version, uptime_s| RebootingDevice {
+            down_for: 2,
+            reconnects: 0,
+            version,
+            uptime_s,
+        };
+        let verify = |device: &mut RebootingDevice, timeout_ms| {
+            let (timeout, poll) = (Duration::from_millis(timeout_ms), Duration::from_millis(1));
+            verify_boot(device, "v1.3.0", timeout, Duration::ZERO, poll)
+        };
+
+        let mut updated = device("v1.3.0", 0);
+        assert_eq!(verify(&mut updated, 1000).unwrap(), "v1.3.0");
+        assert_eq!(updated.reconnects, 2);
+
+        let mut rolled_back = device("v1.2.0", 0);
+        let err = verify(&mut rolled_back, 1000).unwrap_err();
+        assert!(matches!(err, DomesError::Rejected(_)));
+        assert!(err.to_string().contains("rebooted into v1.2.0, not v1.3.0"));
+
+        // Still up since before the update: never rebooted
+        let mut stuck = device("v1.2.0", 3600);
+        assert!(verify(&mut stuck, 50).unwrap_err().is_timeout());
+    }
+
+    /// Acknowledges every OTA frame and records the OTA_DATA payloads
+    struct AckingDevice {
+        max_chunk: usize,
+        data_payloads: Vec<Vec<u8>>,
+        transport_type: TransportType,
+    }
+
+    impl Transport for AckingDevice {
+        fn send_frame(&mut self, msg_type: u8, payload: &[u8]) -> anyhow::Result<()> {
+            if msg_type == OtaMsgType::Data as u8 {
+                self.data_payloads.push(payload.to_vec());
+            }
+            Ok(())
+        }
+
+        fn receive_frame(&mut self, _timeout_ms: u64) -> anyhow::Result<crate::transport::Frame> {
+            Ok(crate::transport::Frame {
+                msg_type: OtaMsgType::Ack as u8,
+                payload: vec![OtaStatus::Ok as u8, 0, 0, 0, 0],
+            })
+        }
+
+        fn send_command(
+            &mut self,
+            msg_type: u8,
+            payload: &[u8],
+        ) -> anyhow::Result<crate::transport::Frame> {
+            self.send_frame(msg_type, payload)?;
+            self.receive_frame(0)
+        }
+
+        fn max_ota_chunk_size(&self) -> usize {
+            self.max_chunk
+        }
+
+        fn transport_type(&self) -> TransportType {
+            self.transport_type
+        }
+    }
+
+    #[test]
+    fn test_ble_chunks_never_exceed_transport_max() {
+        use crate::transport::OTA_CHUNK_SIZE_BLE;
+
+        let name = format!("domes-ota-chunks-{}.bin", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, vec![0x5A; 3000]).unwrap();
+
+        for requested in [None, Some(1000), Some(100)] {
+            let mut device = AckingDevice {
+                max_chunk: OTA_CHUNK_SIZE_BLE,
+                data_payloads: Vec::new(),
+                transport_type: TransportType::Ble,
+            };
+            let options = OtaOptions {
+                chunk_size: requested,
+                ..Default::default()
+            };
+            ota_flash(&mut device, &path, &options, |_| {}).unwrap();
+            let chunks: Vec<usize> = device
+                .data_payloads
+                .iter()
+                .map(|p| p.len() - OTA_DATA_HEADER_SIZE)
+                .collect();
+            assert!(chunks.iter().all(|&len| len <= OTA_CHUNK_SIZE_BLE));
+            assert_eq!(chunks.iter().sum::<usize>(), 3000);
+            if requested == Some(100) {
+                assert_eq!(chunks.len(), 30);
+            }
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        // Serial/TCP: the default fills a frame; larger requests are capped
+        assert_eq!(ota_chunk_size(1016, None), 1016);
+        assert_eq!(ota_chunk_size(1016, Some(4096)), 1016);
+        assert_eq!(ota_chunk_size(4096, None), MAX_PAYLOAD_SIZE - OTA_DATA_HEADER_SIZE);
+        assert_eq!(ota_chunk_size(1016, Some(0)), 1);
+    }
+
+    #[test]
+    fn test_no_reboot_rejected_over_wifi() {
+        let name = format!("domes-ota-no-reboot-{}.bin", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, [0x42; 10]).unwrap();
+        let options = OtaOptions {
+            no_reboot: true,
+            ..Default::default()
+        };
+        let device = |transport_type| AckingDevice {
+            max_chunk: 1016,
+            data_payloads: Vec::new(),
+            transport_type,
+        };
+
+        let mut wifi = device(TransportType::Tcp);
+        let err = ota_flash(&mut wifi, &path, &options, |_| {}).unwrap_err();
+        assert!(matches!(err, DomesError::InvalidArgument(_)));
+        assert!(wifi.data_payloads.is_empty());
+
+        let mut ble = device(TransportType::Ble);
+        ota_flash(&mut ble, &path, &options, |_| {}).unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_firmware_alias_lookup() {
+        let content = r#"
+latest = "/ci/firmware/build/domes.bin"
+bench = "images/bench.bin"
+"#;
+        let base = Path::new("/home/me/.domes");
+        assert_eq!(
+            lookup_firmware_alias(content, "latest", base).unwrap(),
+            PathBuf::from("/ci/firmware/build/domes.bin")
+        );
+        assert_eq!(
+            lookup_firmware_alias(content, "bench", base).unwrap(),
+            PathBuf::from("/home/me/.domes/images/bench.bin")
+        );
+        let err = lookup_firmware_alias(content, "nightly", base).unwrap_err();
+        assert!(err.contains("defined: bench, latest"), "{}", err);
+        assert!(lookup_firmware_alias("latest = 3", "latest", base).is_err());
+
+        // Only a leading '@' makes an alias
+        let plain = Path::new("build/domes.bin");
+        assert_eq!(resolve_firmware_path(plain).unwrap(), plain);
+    }
+}
@@ -0,0 +1,104 @@
+//! Message type introspection (`proto list`, no device needed)
+//!
+//! Lists every message type in the generated proto enums next to whether the
+//! CLI handles it, i.e. whether it is in the `TryFrom<u8>` table of
+//! `ConfigMsgType` or `TraceMsgType`. A type the firmware defines but the CLI
+//! does not handle shows up as a gap, which is the point of the listing when
+//! bringing up new firmware.
+
+use crate::protocol::{ConfigMsgType, TraceMsgType};
+
+/// Message-type enum a type belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Namespace {
+    Config,
+    Trace,
+}
+
+impl Namespace {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Namespace::Config => "config",
+            Namespace::Trace => "trace",
+        }
+    }
+}
+
+/// One message type from a proto enum
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageTypeInfo {
+    pub namespace: Namespace,
+    /// Proto name without the `MSG_TYPE_` prefix (e.g. `LIST_FEATURES_REQ`)
+    pub name: &'static str,
+    pub value: u8,
+    /// Whether the CLI has a serialize/parse handler for it
+    pub handled: bool,
+}
+
+/// Every config and trace message type, each namespace in value order
+///
+/// The `UNKNOWN` (0) placeholders are left out.
+pub fn list_message_types() -> Vec<MessageTypeInfo> {
+    let mut types = Vec::new();
+    for value in 1..=u8::MAX {
+        if let Ok(msg) = ConfigMsgType::try_from(value as i32) {
+            types.push(MessageTypeInfo {
+                namespace: Namespace::Config,
+                name: short_name(msg.as_str_name()),
+                value,
+                handled: ConfigMsgType::try_from(value).is_ok(),
+            });
+        }
+    }
+    for value in 1..=u8::MAX {
+        if let Ok(msg) = TraceMsgType::try_from(value as i32) {
+            types.push(MessageTypeInfo {
+                namespace: Namespace::Trace,
+                name: short_name(msg.as_str_name()),
+                value,
+                handled: TraceMsgType::try_from(value).is_ok(),
+            });
+        }
+    }
+    types
+}
+
+fn short_name(name: &'static str) -> &'static str {
+    name.strip_prefix("MSG_TYPE_").unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_message_types_matches_handler_tables() {
+        let types = list_message_types();
+
+        let list_features = types
+            .iter()
+            .find(|t| t.namespace == Namespace::Config && t.value == 0x20)
+            .unwrap();
+        assert_eq!(list_features.name, "LIST_FEATURES_REQ");
+        assert!(list_features.handled);
+
+        // Defined by the firmware, never sent by the CLI
+        let stream_cfg = types
+            .iter()
+            .find(|t| t.namespace == Namespace::Trace && t.value == 0x18)
+            .unwrap();
+        assert_eq!(stream_cfg.name, "STREAM_CFG");
+        assert!(!stream_cfg.handled);
+
+        // Every handled value is a listed proto enum variant
+        for value in 0..=u8::MAX {
+            if ConfigMsgType::try_from(value).is_ok() {
+                assert!(types.iter().any(|t| t.namespace == Namespace::Config && t.value == value));
+            }
+            if TraceMsgType::try_from(value).is_ok() {
+                assert!(types.iter().any(|t| t.namespace == Namespace::Trace && t.value == value));
+            }
+        }
+        assert!(types.iter().all(|t| t.value != 0));
+    }
+}
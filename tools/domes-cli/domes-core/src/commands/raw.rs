@@ -0,0 +1,88 @@
+//! Raw frame command
+//!
+//! Sends a hand-crafted frame and returns the response as-is, for protocol
+//! bring-up and for message types the CLI does not model yet.
+
+use crate::error::{Context, DomesError, Result};
+use crate::transport::frame::MAX_PAYLOAD_SIZE;
+use crate::transport::{Frame, Transport};
+
+/// Parse a message type given as hex, with or without a `0x` prefix
+pub fn parse_msg_type(s: &str) -> Result<u8> {
+    let digits = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    u8::from_str_radix(digits, 16).map_err(|_| {
+        DomesError::InvalidArgument(format!(
+            "Invalid message type '{}' (expected hex byte, e.g. 0x20)",
+            s
+        ))
+    })
+}
+
+/// Parse hex bytes, e.g. `aa5501`, `aa 55 01`, `AA:55:01` or `0xAA, 0x55, 0x01`
+pub fn parse_hex_bytes(s: &str) -> Result<Vec<u8>> {
+    let digits: String = s
+        .split(|c: char| c.is_whitespace() || c == ':' || c == ',')
+        .map(|token| {
+            token
+                .strip_prefix("0x")
+                .or_else(|| token.strip_prefix("0X"))
+                .unwrap_or(token)
+        })
+        .collect();
+    hex::decode(&digits)
+        .map_err(|e| DomesError::InvalidArgument(format!("Invalid hex bytes '{}': {}", s, e)))
+}
+
+/// Parse a hex payload (see `parse_hex_bytes`) that fits in one frame
+pub fn parse_hex_payload(s: &str) -> Result<Vec<u8>> {
+    let payload = parse_hex_bytes(s)?;
+
+    if payload.len() > MAX_PAYLOAD_SIZE {
+        return Err(DomesError::InvalidArgument(format!(
+            "Payload too large: {} bytes (max {})",
+            payload.len(),
+            MAX_PAYLOAD_SIZE
+        )));
+    }
+    Ok(payload)
+}
+
+/// Send an arbitrary frame and return the response frame
+pub fn raw_command(transport: &mut dyn Transport, msg_type: u8, payload: &[u8]) -> Result<Frame> {
+    if payload.len() > MAX_PAYLOAD_SIZE {
+        return Err(DomesError::InvalidArgument(format!(
+            "Payload too large: {} bytes (max {})",
+            payload.len(),
+            MAX_PAYLOAD_SIZE
+        )));
+    }
+    transport
+        .send_command(msg_type, payload)
+        .with_context(|| format!("Failed to send raw frame 0x{:02X}", msg_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_msg_type() {
+        assert_eq!(parse_msg_type("0x20").unwrap(), 0x20);
+        assert_eq!(parse_msg_type("1C").unwrap(), 0x1C);
+        assert!(parse_msg_type("0x100").is_err());
+        assert!(parse_msg_type("zz").is_err());
+    }
+
+    #[test]
+    fn test_parse_hex_payload() {
+        assert_eq!(parse_hex_payload("0a021001").unwrap(), [0x0a, 0x02, 0x10, 0x01]);
+        assert_eq!(parse_hex_payload("0a 02:10 01").unwrap(), [0x0a, 0x02, 0x10, 0x01]);
+        assert_eq!(parse_hex_payload("0x10 0x20,0x0A").unwrap(), [0x10, 0x20, 0x0a]);
+        assert!(parse_hex_payload("").unwrap().is_empty());
+        assert!(parse_hex_payload("abc").is_err());
+        assert!(parse_hex_payload(&"00".repeat(MAX_PAYLOAD_SIZE + 1)).is_err());
+    }
+}
@@ -18,7 +18,7 @@ pub enum ProtocolFilter {
 }
 
 impl ProtocolFilter {
-    pub fn from_str(s: &str) -> Option<Self> {
+    pub fn from_cli_name(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "config" => Some(Self::Config),
             "trace" => Some(Self::Trace),
@@ -115,7 +115,6 @@ pub fn sniff_serial(port_name: &str, opts: &SniffOptions) -> Result<()> {
                                 eprintln!("[FRAME ERROR] {}", e);
                             }
                         }
-                        decoder.reset();
                     }
                 }
             }
@@ -156,7 +155,7 @@ fn decode_frame(timestamp: Duration, frame: &Frame) -> DecodedFrame {
 }
 
 /// Identify a message type by name, direction, and protocol
-fn identify_message(msg_type: u8) -> (String, &'static str, &'static str) {
+pub(crate) fn identify_message(msg_type: u8) -> (String, &'static str, &'static str) {
     match msg_type {
         // OTA messages (0x01-0x05)
         0x01 => ("OTA_BEGIN".into(), "host->dev", "ota"),
@@ -178,6 +177,10 @@ fn identify_message(msg_type: u8) -> (String, &'static str, &'static str) {
         0x19 => ("TRACE_STREAM_DATA".into(), "dev->host", "trace"),
         0x1A => ("TRACE_SESSION_INFO".into(), "dev->host", "trace"),
         0x1B => ("TRACE_ACK".into(), "dev->host", "trace"),
+        0x1C => ("TRACE_SET_BUFFER_SIZE_REQ".into(), "host->dev", "trace"),
+        0x1D => ("TRACE_SET_BUFFER_SIZE_RSP".into(), "dev->host", "trace"),
+        0x1E => ("TRACE_ANNOTATE_REQ".into(), "host->dev", "trace"),
+        0x1F => ("TRACE_ANNOTATE_RSP".into(), "dev->host", "trace"),
 
         // Config messages (0x20-0x3F)
         0x20 => ("LIST_FEATURES_REQ".into(), "host->dev", "config"),
@@ -206,19 +209,47 @@ fn identify_message(msg_type: u8) -> (String, &'static str, &'static str) {
         0x3B => ("GET_ESPNOW_STATUS_RSP".into(), "dev->host", "config"),
         0x3C => ("ESPNOW_BENCH_REQ".into(), "host->dev", "config"),
         0x3D => ("ESPNOW_BENCH_RSP".into(), "dev->host", "config"),
+        0x50 => ("BATCH_REQ".into(), "host->dev", "config"),
+        0x51 => ("BATCH_RSP".into(), "dev->host", "config"),
+        0x52 => ("SET_DEVICE_NAME_REQ".into(), "host->dev", "config"),
+        0x53 => ("SET_DEVICE_NAME_RSP".into(), "dev->host", "config"),
+        0x54 => ("SUBSCRIBE_EVENTS_REQ".into(), "host->dev", "config"),
+        0x55 => ("SUBSCRIBE_EVENTS_RSP".into(), "dev->host", "config"),
+        0x56 => ("EVENT_NOTIFICATION".into(), "dev->host", "config"),
+        0x57 => ("PING_REQ".into(), "host->dev", "config"),
+        0x58 => ("PING_RSP".into(), "dev->host", "config"),
+        0x59 => ("SET_BAUD_RATE_REQ".into(), "host->dev", "config"),
+        0x5A => ("SET_BAUD_RATE_RSP".into(), "dev->host", "config"),
+        0x5B => ("GET_TASK_LIST_REQ".into(), "host->dev", "config"),
+        0x5C => ("GET_TASK_LIST_RSP".into(), "dev->host", "config"),
+        0x5D => ("GET_PARTITION_TABLE_REQ".into(), "host->dev", "config"),
+        0x5E => ("GET_PARTITION_TABLE_RSP".into(), "dev->host", "config"),
+        0x5F => ("CORE_DUMP_READ_REQ".into(), "host->dev", "config"),
+        0x60 => ("CORE_DUMP_READ_RSP".into(), "dev->host", "config"),
+        0x61 => ("CORE_DUMP_DATA".into(), "dev->host", "config"),
+        0x62 => ("CORE_DUMP_END".into(), "dev->host", "config"),
+        0x63 => ("CORE_DUMP_CLEAR_REQ".into(), "host->dev", "config"),
+        0x64 => ("CORE_DUMP_CLEAR_RSP".into(), "dev->host", "config"),
+        0x65 => ("WIFI_AP_CONFIG_REQ".into(), "host->dev", "config"),
+        0x66 => ("WIFI_AP_CONFIG_RSP".into(), "dev->host", "config"),
+        0x67 => ("WIFI_AP_STATUS_REQ".into(), "host->dev", "config"),
+        0x68 => ("WIFI_AP_STATUS_RSP".into(), "dev->host", "config"),
+        0x69 => ("WIFI_STATIC_IP_REQ".into(), "host->dev", "config"),
+        0x6A => ("WIFI_STATIC_IP_RSP".into(), "dev->host", "config"),
 
         _ => (format!("UNKNOWN_0x{:02X}", msg_type), "unknown", "unknown"),
     }
 }
 
 /// Decode protobuf payload fields into key-value pairs
-fn decode_payload(msg_type: u8, payload: &[u8]) -> Vec<(String, String)> {
+pub(crate) fn decode_payload(msg_type: u8, payload: &[u8]) -> Vec<(String, String)> {
     let mut fields = Vec::new();
 
     // Skip status byte for response messages that include one
     let (status_byte, proto_payload) = match msg_type {
         // Responses that have a leading status byte
-        0x23 | 0x25 | 0x27 | 0x29 | 0x2B | 0x31 | 0x33 | 0x35 | 0x37 | 0x39 | 0x3B | 0x3D => {
+        0x23 | 0x25 | 0x27 | 0x29 | 0x2B | 0x31 | 0x33 | 0x35 | 0x37 | 0x39 | 0x3B | 0x3D
+        | 0x5C | 0x5E | 0x60 | 0x64 | 0x66 | 0x68 | 0x6A => {
             if payload.is_empty() {
                 return fields;
             }
@@ -293,6 +324,9 @@ fn decode_payload(msg_type: u8, payload: &[u8]) -> Vec<(String, String)> {
                     "time_in_mode_ms".into(),
                     resp.time_in_mode_ms.to_string(),
                 ));
+                if resp.has_previous_mode {
+                    fields.push(("previous_mode".into(), mode_name(resp.previous_mode)));
+                }
             }
         }
 
@@ -302,6 +336,9 @@ fn decode_payload(msg_type: u8, payload: &[u8]) -> Vec<(String, String)> {
                 crate::proto::config::SetModeRequest::decode(proto_payload)
             {
                 fields.push(("mode".into(), mode_name(req.mode)));
+                if req.force {
+                    fields.push(("force".into(), "true".into()));
+                }
             }
         }
 
@@ -319,6 +356,93 @@ fn decode_payload(msg_type: u8, payload: &[u8]) -> Vec<(String, String)> {
             }
         }
 
+        // GET_TASK_LIST_RSP
+        0x5C => {
+            if let Ok(resp) =
+                crate::proto::config::GetTaskListResponse::decode(proto_payload)
+            {
+                fields.push(("tasks".into(), resp.tasks.len().to_string()));
+            }
+        }
+
+        // GET_PARTITION_TABLE_RSP
+        0x5E => {
+            if let Ok(resp) =
+                crate::proto::config::GetPartitionTableResponse::decode(proto_payload)
+            {
+                fields.push(("partitions".into(), resp.partitions.len().to_string()));
+            }
+        }
+
+        // WIFI_AP_CONFIG_RSP
+        0x66 => {
+            if let Ok(resp) =
+                crate::proto::config::WifiApConfigResponse::decode(proto_payload)
+            {
+                fields.push(("ssid".into(), resp.ssid));
+                fields.push(("channel".into(), resp.channel.to_string()));
+                fields.push(("ip".into(), std::net::Ipv4Addr::from(resp.ip).to_string()));
+            }
+        }
+
+        // WIFI_STATIC_IP_REQ (packed big-endian addresses, not protobuf)
+        0x69 => {
+            let addrs: Vec<std::net::Ipv4Addr> = proto_payload
+                .chunks_exact(4)
+                .map(|b| std::net::Ipv4Addr::new(b[0], b[1], b[2], b[3]))
+                .collect();
+            match addrs.as_slice() {
+                [ip, ..] if ip.is_unspecified() => fields.push(("mode".into(), "dhcp".into())),
+                [ip, netmask, gateway, ..] => {
+                    fields.push(("ip".into(), ip.to_string()));
+                    fields.push(("netmask".into(), netmask.to_string()));
+                    fields.push(("gateway".into(), gateway.to_string()));
+                }
+                _ => {}
+            }
+        }
+
+        // WIFI_AP_STATUS_RSP
+        0x68 => {
+            if let Ok(resp) =
+                crate::proto::config::WifiApStatusResponse::decode(proto_payload)
+            {
+                fields.push(("active".into(), resp.active.to_string()));
+                if resp.active {
+                    fields.push(("ssid".into(), resp.ssid));
+                    fields.push(("clients".into(), resp.clients.len().to_string()));
+                }
+            }
+        }
+
+        // CORE_DUMP_READ_RSP
+        0x60 => {
+            if let Ok(resp) =
+                crate::proto::config::CoreDumpReadResponse::decode(proto_payload)
+            {
+                fields.push(("valid".into(), resp.valid.to_string()));
+                if resp.valid {
+                    fields.push(("size".into(), resp.size.to_string()));
+                    fields.push(("reset_reason".into(), resp.reset_reason));
+                }
+            }
+        }
+
+        // CORE_DUMP_DATA
+        0x61 => {
+            if let Ok(chunk) = crate::proto::config::CoreDumpChunk::decode(proto_payload) {
+                fields.push(("offset".into(), chunk.offset.to_string()));
+                fields.push(("bytes".into(), chunk.data.len().to_string()));
+            }
+        }
+
+        // CORE_DUMP_END
+        0x62 => {
+            if let Ok(end) = crate::proto::config::CoreDumpComplete::decode(proto_payload) {
+                fields.push(("checksum".into(), format!("0x{:08X}", end.checksum)));
+            }
+        }
+
         // GET_HEALTH_RSP
         0x39 => {
             if let Ok(resp) =
@@ -366,6 +490,60 @@ fn decode_payload(msg_type: u8, payload: &[u8]) -> Vec<(String, String)> {
             }
         }
 
+        // TRACE_DUMP
+        0x12 => {
+            if let Ok(req) = crate::proto::trace::DumpRequest::decode(proto_payload) {
+                if req.since_us != 0 {
+                    fields.push(("since_us".into(), req.since_us.to_string()));
+                }
+            }
+        }
+
+        // TRACE_SET_BUFFER_SIZE_REQ
+        0x1C => {
+            if let Ok(req) =
+                crate::proto::trace::SetBufferSizeRequest::decode(proto_payload)
+            {
+                fields.push(("event_count".into(), req.event_count.to_string()));
+            }
+        }
+
+        // TRACE_SET_BUFFER_SIZE_RSP
+        0x1D => {
+            if let Ok(resp) =
+                crate::proto::trace::SetBufferSizeResponse::decode(proto_payload)
+            {
+                let status = crate::proto::trace::Status::try_from(resp.status)
+                    .map(|s| format!("{}", s))
+                    .unwrap_or_else(|_| format!("unknown({})", resp.status));
+                fields.push(("status".into(), status));
+                fields.push(("allocated".into(), resp.allocated_count.to_string()));
+                fields.push(("bytes".into(), resp.bytes_used.to_string()));
+                fields.push(("cleared".into(), resp.was_cleared.to_string()));
+            }
+        }
+
+        // TRACE_ANNOTATE_REQ
+        0x1E => {
+            if let Ok(req) = crate::proto::trace::AnnotateRequest::decode(proto_payload) {
+                fields.push(("label".into(), req.label));
+            }
+        }
+
+        // TRACE_ANNOTATE_RSP
+        0x1F => {
+            if let Ok(resp) =
+                crate::proto::trace::AnnotateResponse::decode(proto_payload)
+            {
+                let status = crate::proto::trace::Status::try_from(resp.status)
+                    .map(|s| format!("{}", s))
+                    .unwrap_or_else(|_| format!("unknown({})", resp.status));
+                fields.push(("status".into(), status));
+                fields.push(("marker".into(), resp.marker.to_string()));
+                fields.push(("timestamp_us".into(), resp.timestamp_us.to_string()));
+            }
+        }
+
         // TRACE_SESSION_INFO
         0x1A => {
             if let Ok(info) =
@@ -0,0 +1,626 @@
+//! System mode and diagnostics commands
+
+use crate::error::{Context, DomesError, Result};
+use crate::proto::config::SystemMode;
+use crate::proto::config::{CoreDumpChunk, CoreDumpComplete, ListFeaturesResponse};
+use crate::protocol::{
+    parse_clear_core_dump_response, parse_clear_crash_dump_response,
+    parse_core_dump_read_response, parse_crash_dump_response, parse_get_mode_response,
+    parse_get_partition_table_response, parse_get_system_info_response,
+    parse_get_task_list_response, parse_memory_profile_response, parse_self_test_response,
+    parse_set_baud_rate_response, parse_set_device_name_response, parse_set_mode_response,
+    parse_set_pod_id_response, serialize_set_baud_rate, serialize_set_device_name,
+    serialize_set_mode, serialize_set_pod_id,
+    CliCrashDump, CliMemoryProfile, CliModeInfo, CliSelfTestInfo, CliSystemInfo, ConfigMsgType,
+    CoreDumpInfo, PartitionInfo, ProtocolError, TaskInfo,
+};
+use crate::transport::{keepalive, ResponseTimeout, Transport};
+use prost::Message;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Get the current system mode
+pub fn system_get_mode(transport: &mut dyn Transport) -> Result<CliModeInfo> {
+    let frame = transport
+        .send_command_expect(ConfigMsgType::GetModeReq as u8, &[], ConfigMsgType::GetModeRsp as u8)
+        .context("Failed to send get mode command")?;
+
+    parse_get_mode_response(&frame.payload).context("Failed to parse get mode response")
+}
+
+/// Set the system mode; `force` bypasses the firmware's transition rules
+///
+/// Returns the mode the device is in afterwards and whether it accepted
+/// the transition.
+pub fn system_set_mode(
+    transport: &mut dyn Transport,
+    mode: SystemMode,
+    force: bool,
+) -> Result<(SystemMode, bool)> {
+    let payload = serialize_set_mode(mode, force);
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::SetModeReq as u8,
+            &payload,
+            ConfigMsgType::SetModeRsp as u8,
+        )
+        .context("Failed to send set mode command")?;
+
+    parse_set_mode_response(&frame.payload).context("Failed to parse set mode response")
+}
+
+/// Get system information
+///
+/// Firmware that does not answer `GetSystemInfo` is still identified from
+/// `GetMode`: the result then has `partial` set, with only the mode, feature
+/// mask and pod ID known.
+pub fn system_info(transport: &mut dyn Transport) -> Result<CliSystemInfo> {
+    let error = match full_system_info(transport) {
+        Ok(info) => return Ok(info),
+        Err(e @ (DomesError::Transport { .. } | DomesError::Io { .. })) => return Err(e),
+        Err(e) => e,
+    };
+
+    // Older firmware ignores GetSystemInfo (or answers it in an older format),
+    // but a mode answer still shows it is a DOMES device. The feature list
+    // adds the feature mask and pod ID, if the device sends one.
+    let Ok(mode) = system_get_mode(transport) else {
+        return Err(error);
+    };
+    let features = transport
+        .send_command_expect(
+            ConfigMsgType::ListFeaturesReq as u8,
+            &[],
+            ConfigMsgType::ListFeaturesRsp as u8,
+        )
+        .ok()
+        .and_then(|frame| ListFeaturesResponse::decode(frame.payload.as_slice()).ok())
+        .unwrap_or_default();
+    let feature_mask = features
+        .features
+        .iter()
+        .filter(|state| state.enabled)
+        .fold(0, |mask, state| mask | 1u32.checked_shl(state.feature as u32).unwrap_or(0));
+    Ok(CliSystemInfo {
+        firmware_version: String::new(),
+        uptime_s: 0,
+        free_heap: 0,
+        boot_count: 0,
+        mode: mode.mode,
+        feature_mask,
+        pod_id: features.pod_id,
+        max_payload: None,
+        partial: true,
+    })
+}
+
+fn full_system_info(transport: &mut dyn Transport) -> Result<CliSystemInfo> {
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::GetSystemInfoReq as u8,
+            &[],
+            ConfigMsgType::GetSystemInfoRsp as u8,
+        )
+        .context("Failed to send get system info command")?;
+
+    parse_get_system_info_response(&frame.payload)
+        .context("Failed to parse get system info response")
+}
+
+/// List the device's FreeRTOS tasks with their state, stack and CPU use
+pub fn system_get_tasks(transport: &mut dyn Transport) -> Result<Vec<TaskInfo>> {
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::GetTaskListReq as u8,
+            &[],
+            ConfigMsgType::GetTaskListRsp as u8,
+        )
+        .context("Failed to send get task list command")?;
+
+    parse_get_task_list_response(&frame.payload).context("Failed to parse get task list response")
+}
+
+/// Read the flash partition table, in flash order
+pub fn system_get_partitions(transport: &mut dyn Transport) -> Result<Vec<PartitionInfo>> {
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::GetPartitionTableReq as u8,
+            &[],
+            ConfigMsgType::GetPartitionTableRsp as u8,
+        )
+        .context("Failed to send get partition table command")?;
+
+    parse_get_partition_table_response(&frame.payload)
+        .context("Failed to parse get partition table response")
+}
+
+/// Valid pod IDs (the pod ID is advertised in the BLE name, e.g. DOMES-Pod-12)
+pub const POD_ID_RANGE: std::ops::RangeInclusive<u32> = 1..=255;
+
+/// Check a pod ID is in range before sending it to a device
+pub fn validate_pod_id(pod_id: u32) -> Result<()> {
+    if !POD_ID_RANGE.contains(&pod_id) {
+        return Err(DomesError::InvalidArgument(format!(
+            "Pod ID must be {}-{} (got {})",
+            POD_ID_RANGE.start(),
+            POD_ID_RANGE.end(),
+            pod_id
+        )));
+    }
+    Ok(())
+}
+
+/// Set the pod ID (persisted to NVS, takes effect on next reboot for BLE name)
+pub fn system_set_pod_id(transport: &mut dyn Transport, pod_id: u32) -> Result<u32> {
+    validate_pod_id(pod_id)?;
+
+    let payload = serialize_set_pod_id(pod_id);
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::SetPodIdReq as u8,
+            &payload,
+            ConfigMsgType::SetPodIdRsp as u8,
+        )
+        .context("Failed to send set pod id command")?;
+
+    parse_set_pod_id_response(&frame.payload).context("Failed to parse set pod id response")
+}
+
+/// Timeout for the baud rate switch; firmware without it never answers
+const SET_BAUD_RATE_TIMEOUT_MS: u64 = 1000;
+
+/// Ask the device to switch its serial link to `baud_rate`
+///
+/// The device answers at the old rate and then switches, so the caller
+/// follows with `Transport::set_baud_rate`. Returns the rate it switches to.
+pub fn system_set_baud_rate(transport: &mut dyn Transport, baud_rate: u32) -> Result<u32> {
+    let payload = serialize_set_baud_rate(baud_rate);
+    let frame = transport
+        .send_command_expect_with_timeout(
+            ConfigMsgType::SetBaudRateReq as u8,
+            &payload,
+            ConfigMsgType::SetBaudRateRsp as u8,
+            SET_BAUD_RATE_TIMEOUT_MS,
+        )
+        .context("Failed to send set baud rate command")?;
+
+    parse_set_baud_rate_response(&frame.payload).context("Failed to parse set baud rate response")
+}
+
+/// How long each `system_ping` waits for its response before counting it lost
+pub const PING_TIMEOUT_MS: u64 = 1000;
+
+/// Round-trip times measured by `system_ping`, in milliseconds
+///
+/// The times cover the pings that were answered; all are 0 if none were.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PingStats {
+    pub count: u32,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub avg_ms: f64,
+    pub stddev_ms: f64,
+    pub lost: u32,
+}
+
+impl PingStats {
+    fn from_round_trips(count: u32, round_trips: &[f64]) -> Self {
+        let lost = count - round_trips.len() as u32;
+        if round_trips.is_empty() {
+            return Self { count, lost, ..Default::default() };
+        }
+        let n = round_trips.len() as f64;
+        let avg_ms = round_trips.iter().sum::<f64>() / n;
+        let variance = round_trips.iter().map(|ms| (ms - avg_ms).powi(2)).sum::<f64>() / n;
+        Self {
+            count,
+            min_ms: round_trips.iter().copied().fold(f64::INFINITY, f64::min),
+            max_ms: round_trips.iter().copied().fold(0.0, f64::max),
+            avg_ms,
+            stddev_ms: variance.sqrt(),
+            lost,
+        }
+    }
+}
+
+/// Ping the device `count` times, one after another, and time each round trip
+///
+/// A ping unanswered within `PING_TIMEOUT_MS` is counted as lost; any other
+/// failure (e.g. a broken link) ends the run with an error.
+pub fn system_ping(transport: &mut dyn Transport, count: u32) -> Result<PingStats> {
+    system_ping_with(transport, count, |_, _| {})
+}
+
+/// `system_ping`, calling `on_reply` with each sequence number (from 1) and
+/// its round-trip time, or `None` if the ping was lost
+pub fn system_ping_with(
+    transport: &mut dyn Transport,
+    count: u32,
+    mut on_reply: impl FnMut(u32, Option<Duration>),
+) -> Result<PingStats> {
+    let mut round_trips = Vec::with_capacity(count as usize);
+    for seq in 1..=count {
+        let started = Instant::now();
+        match keepalive::ping(transport, PING_TIMEOUT_MS) {
+            Ok(()) => {
+                let elapsed = started.elapsed();
+                round_trips.push(elapsed.as_secs_f64() * 1000.0);
+                on_reply(seq, Some(elapsed));
+            }
+            Err(e) if e.chain().any(|cause| cause.is::<ResponseTimeout>()) => on_reply(seq, None),
+            Err(e) => return Err(e).context("Failed to send ping"),
+        }
+    }
+    Ok(PingStats::from_round_trips(count, &round_trips))
+}
+
+/// Longest device name that fits in a BLE advertisement alongside the service UUID
+pub const DEVICE_NAME_MAX_LEN: usize = 20;
+
+/// Check a device name is safe to advertise over BLE (printable ASCII, 1-20 chars)
+pub fn validate_device_name(name: &str) -> Result<()> {
+    let invalid = |reason: String| Err(DomesError::InvalidArgument(reason));
+    if name.is_empty() {
+        return invalid("Device name must not be empty".to_string());
+    }
+    if name.len() > DEVICE_NAME_MAX_LEN {
+        return invalid(format!(
+            "Device name must be at most {} characters (got {})",
+            DEVICE_NAME_MAX_LEN,
+            name.len()
+        ));
+    }
+    if let Some(c) = name.chars().find(|c| !(c.is_ascii_graphic() || *c == ' ')) {
+        return invalid(format!("Device name must be printable ASCII (found {:?})", c));
+    }
+    Ok(())
+}
+
+/// Set the BLE advertised name (persisted to NVS, takes effect on next reboot)
+pub fn system_set_name(transport: &mut dyn Transport, name: &str) -> Result<String> {
+    validate_device_name(name)?;
+
+    let payload = serialize_set_device_name(name);
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::SetDeviceNameReq as u8,
+            &payload,
+            ConfigMsgType::SetDeviceNameRsp as u8,
+        )
+        .context("Failed to send set device name command")?;
+
+    parse_set_device_name_response(&frame.payload)
+        .context("Failed to parse set device name response")
+}
+
+/// Get crash dump from device
+pub fn system_crash_dump(transport: &mut dyn Transport) -> Result<CliCrashDump> {
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::GetCrashDumpReq as u8,
+            &[],
+            ConfigMsgType::GetCrashDumpRsp as u8,
+        )
+        .context("Failed to send get crash dump command")?;
+
+    parse_crash_dump_response(&frame.payload).context("Failed to parse crash dump response")
+}
+
+/// Clear crash dump from device
+pub fn system_clear_crash_dump(transport: &mut dyn Transport) -> Result<bool> {
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::ClearCrashDumpReq as u8,
+            &[],
+            ConfigMsgType::ClearCrashDumpRsp as u8,
+        )
+        .context("Failed to send clear crash dump command")?;
+
+    parse_clear_crash_dump_response(&frame.payload)
+        .context("Failed to parse clear crash dump response")
+}
+
+/// How long to wait for each core dump chunk
+const CORE_DUMP_CHUNK_TIMEOUT_MS: u64 = 5000;
+
+/// Read the core dump from the coredump partition and write it to `output`
+///
+/// The dump arrives in chunks like a trace dump and is written only once
+/// all of it arrived with a matching checksum. Nothing is written if the
+/// partition holds no valid core dump (`valid` is false).
+pub fn system_get_core_dump(transport: &mut dyn Transport, output: &Path) -> Result<CoreDumpInfo> {
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::CoreDumpReadReq as u8,
+            &[],
+            ConfigMsgType::CoreDumpReadRsp as u8,
+        )
+        .context("Failed to send core dump read command")?;
+
+    let info = parse_core_dump_read_response(&frame.payload)
+        .context("Failed to parse core dump read response")?;
+    if !info.valid {
+        return Ok(info);
+    }
+
+    let mut data = Vec::with_capacity(info.size as usize);
+    let checksum = loop {
+        let frame = transport
+            .receive_frame(CORE_DUMP_CHUNK_TIMEOUT_MS)
+            .context("Failed to receive core dump data")?;
+
+        if frame.msg_type == ConfigMsgType::CoreDumpData as u8 {
+            let chunk = CoreDumpChunk::decode(frame.payload.as_slice())
+                .context("Failed to decode CoreDumpChunk")?;
+            if chunk.offset as usize != data.len() {
+                return Err(DomesError::Rejected(format!(
+                    "Core dump chunk at offset {}, expected {}",
+                    chunk.offset,
+                    data.len()
+                )));
+            }
+            data.extend_from_slice(&chunk.data);
+        } else if frame.msg_type == ConfigMsgType::CoreDumpEnd as u8 {
+            let end = CoreDumpComplete::decode(frame.payload.as_slice())
+                .context("Failed to decode CoreDumpComplete")?;
+            break end.checksum;
+        } else {
+            return Err(DomesError::UnexpectedResponse {
+                expected: ConfigMsgType::CoreDumpData as u8,
+                actual: frame.msg_type,
+            });
+        }
+    };
+
+    if data.len() != info.size as usize {
+        return Err(ProtocolError::PayloadTooShort {
+            expected: info.size as usize,
+            actual: data.len(),
+        })
+        .context("Core dump is incomplete");
+    }
+    let actual = crc32fast::hash(&data);
+    if actual != checksum {
+        return Err(ProtocolError::ChecksumMismatch {
+            expected: checksum,
+            actual,
+        })
+        .context("Core dump is corrupted");
+    }
+
+    std::fs::write(output, &data)
+        .with_context(|| format!("Failed to write core dump to {}", output.display()))?;
+    Ok(info)
+}
+
+/// Erase the core dump partition; true if a dump was erased
+pub fn system_clear_core_dump(transport: &mut dyn Transport) -> Result<bool> {
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::CoreDumpClearReq as u8,
+            &[],
+            ConfigMsgType::CoreDumpClearRsp as u8,
+        )
+        .context("Failed to send core dump clear command")?;
+
+    parse_clear_core_dump_response(&frame.payload)
+        .context("Failed to parse core dump clear response")
+}
+
+/// Get memory profile from device
+pub fn system_memory_profile(transport: &mut dyn Transport) -> Result<CliMemoryProfile> {
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::GetMemoryProfileReq as u8,
+            &[],
+            ConfigMsgType::GetMemoryProfileRsp as u8,
+        )
+        .context("Failed to send get memory profile command")?;
+
+    parse_memory_profile_response(&frame.payload)
+        .context("Failed to parse memory profile response")
+}
+
+/// Timeout for self-test command (tests WiFi scan, BLE, NVS, etc.)
+const SELF_TEST_TIMEOUT_MS: u64 = 15000;
+
+/// Run on-device self-test suite
+pub fn system_self_test(transport: &mut dyn Transport) -> Result<CliSelfTestInfo> {
+    let frame = transport
+        .send_command_expect_with_timeout(
+            ConfigMsgType::SelfTestReq as u8,
+            &[],
+            ConfigMsgType::SelfTestRsp as u8,
+            SELF_TEST_TIMEOUT_MS,
+        )
+        .context("Failed to send self-test command")?;
+
+    parse_self_test_response(&frame.payload).context("Failed to parse self-test response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::ReplayTransport;
+
+    #[test]
+    fn test_validate_pod_id_boundaries() {
+        assert!(validate_pod_id(0).is_err());
+        assert!(validate_pod_id(1).is_ok());
+        assert!(validate_pod_id(255).is_ok());
+        assert!(validate_pod_id(256).is_err());
+    }
+
+    #[test]
+    fn test_set_pod_id_out_of_range_sends_nothing() {
+        // No responses queued: any transport I/O would fail with a different error
+        let mut transport = ReplayTransport::new(Vec::new());
+        let err = system_set_pod_id(&mut transport, 256).unwrap_err();
+        assert!(err.to_string().starts_with("Pod ID must be 1-255"));
+    }
+
+    #[test]
+    fn test_ping_stats_count_lost_pings() {
+        let stats = PingStats::from_round_trips(4, &[2.0, 4.0, 6.0]);
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.lost, 1);
+        assert_eq!((stats.min_ms, stats.max_ms, stats.avg_ms), (2.0, 6.0, 4.0));
+        assert!((stats.stddev_ms - (8.0f64 / 3.0).sqrt()).abs() < 1e-9);
+
+        let silent = PingStats::from_round_trips(3, &[]);
+        assert_eq!(silent, PingStats { count: 3, lost: 3, ..Default::default() });
+
+        // Every ping answered
+        let pong = || crate::transport::Frame {
+            msg_type: ConfigMsgType::PingRsp as u8,
+            payload: Vec::new(),
+        };
+        let mut transport = ReplayTransport::new(vec![pong(), pong()]);
+        let mut seqs = Vec::new();
+        let stats = system_ping_with(&mut transport, 2, |seq, rtt| seqs.push((seq, rtt.is_some())))
+            .unwrap();
+        assert_eq!(seqs, [(1, true), (2, true)]);
+        assert_eq!((stats.count, stats.lost), (2, 0));
+    }
+
+    #[test]
+    fn test_forced_set_mode_keeps_old_encoding_when_unforced() {
+        use crate::proto::config::{SetModeRequest, SetModeResponse};
+        use crate::transport::Frame;
+
+        // Unforced requests are byte-for-byte what older firmware expects
+        assert_eq!(serialize_set_mode(SystemMode::Idle, false), [0x08, 0x01]);
+        let forced = SetModeRequest::decode(&serialize_set_mode(SystemMode::Idle, true)[..]);
+        assert!(forced.unwrap().force);
+
+        // Firmware that ignores the flag still rejects the transition
+        let mut payload = vec![0];
+        payload.extend(
+            SetModeResponse {
+                mode: SystemMode::Game as i32,
+                transition_ok: false,
+            }
+            .encode_to_vec(),
+        );
+        let mut transport = ReplayTransport::new(vec![Frame {
+            msg_type: ConfigMsgType::SetModeRsp as u8,
+            payload,
+        }]);
+        let result = system_set_mode(&mut transport, SystemMode::Idle, true).unwrap();
+        assert_eq!(result, (SystemMode::Game, false));
+    }
+
+    #[test]
+    fn test_system_info_falls_back_to_mode() {
+        use crate::proto::config::{FeatureState, GetModeResponse};
+        use crate::transport::Frame;
+
+        let mut mode = vec![0];
+        mode.extend(
+            GetModeResponse {
+                mode: SystemMode::Idle as i32,
+                ..Default::default()
+            }
+            .encode_to_vec(),
+        );
+        let features = ListFeaturesResponse {
+            features: vec![
+                FeatureState { feature: 1, enabled: true },
+                FeatureState { feature: 3, enabled: false },
+                FeatureState { feature: 5, enabled: true },
+            ],
+            pod_id: 7,
+        };
+        let mut old_firmware = ReplayTransport::new(vec![
+            Frame {
+                msg_type: ConfigMsgType::GetSystemInfoRsp as u8,
+                payload: vec![1],
+            },
+            Frame {
+                msg_type: ConfigMsgType::GetModeRsp as u8,
+                payload: mode,
+            },
+            Frame {
+                msg_type: ConfigMsgType::ListFeaturesRsp as u8,
+                payload: features.encode_to_vec(),
+            },
+        ]);
+        let info = system_info(&mut old_firmware).unwrap();
+        assert!(info.partial);
+        assert_eq!(info.mode, SystemMode::Idle);
+        assert_eq!((info.feature_mask, info.pod_id), (0b100010, 7));
+
+        // Without a mode answer either, the original error is kept
+        let mut unknown = ReplayTransport::new(vec![Frame {
+            msg_type: ConfigMsgType::GetSystemInfoRsp as u8,
+            payload: vec![1],
+        }]);
+        assert!(matches!(system_info(&mut unknown), Err(DomesError::Status(_))));
+    }
+
+    #[test]
+    fn test_core_dump_written_after_checksum() {
+        use crate::proto::config::CoreDumpReadResponse;
+        use crate::transport::Frame;
+
+        let dump: Vec<u8> = (0..=255u8).cycle().take(700).collect();
+        let read_rsp = |valid: bool| {
+            let mut payload = vec![0];
+            payload.extend(
+                CoreDumpReadResponse {
+                    valid,
+                    size: if valid { dump.len() as u32 } else { 0 },
+                    reset_reason: if valid { "panic".into() } else { String::new() },
+                }
+                .encode_to_vec(),
+            );
+            Frame {
+                msg_type: ConfigMsgType::CoreDumpReadRsp as u8,
+                payload,
+            }
+        };
+        let data = |offset: usize, end: usize| Frame {
+            msg_type: ConfigMsgType::CoreDumpData as u8,
+            payload: CoreDumpChunk {
+                offset: offset as u32,
+                data: dump[offset..end].to_vec(),
+            }
+            .encode_to_vec(),
+        };
+        let end = |checksum| Frame {
+            msg_type: ConfigMsgType::CoreDumpEnd as u8,
+            payload: CoreDumpComplete { checksum }.encode_to_vec(),
+        };
+        let path = std::env::temp_dir().join(format!("domes-core-{}.bin", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut none = ReplayTransport::new(vec![read_rsp(false)]);
+        let info = system_get_core_dump(&mut none, &path).unwrap();
+        assert!(!info.valid);
+        assert!(!path.exists());
+
+        let checksum = crc32fast::hash(&dump);
+        let mut corrupt =
+            ReplayTransport::new(vec![read_rsp(true), data(0, 512), data(512, 700), end(!checksum)]);
+        assert!(system_get_core_dump(&mut corrupt, &path).is_err());
+        assert!(!path.exists());
+
+        let mut device =
+            ReplayTransport::new(vec![read_rsp(true), data(0, 512), data(512, 700), end(checksum)]);
+        let info = system_get_core_dump(&mut device, &path).unwrap();
+        assert_eq!((info.size, info.reset_reason.as_str()), (700, "panic"));
+        assert_eq!(std::fs::read(&path).unwrap(), dump);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_validate_device_name() {
+        assert!(validate_device_name("DOMES-Lobby").is_ok());
+        assert!(validate_device_name("Front Desk 2").is_ok());
+        assert!(validate_device_name(&"a".repeat(DEVICE_NAME_MAX_LEN)).is_ok());
+        assert!(validate_device_name(&"a".repeat(DEVICE_NAME_MAX_LEN + 1)).is_err());
+        assert!(validate_device_name("").is_err());
+        assert!(validate_device_name("caf\u{e9}").is_err());
+        assert!(validate_device_name("tab\there").is_err());
+    }
+}
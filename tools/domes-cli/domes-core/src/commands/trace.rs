@@ -0,0 +1,1501 @@
+//! Trace/perfetto commands
+//!
+//! Uses protobuf-encoded messages for all control/metadata (prost).
+//! TraceEvent data is 16-byte binary carried in protobuf 'bytes' fields.
+
+use crate::error::{Context, DomesError, Result};
+use crate::proto::trace::{
+    AckResponse, AnnotateRequest, AnnotateResponse, DumpRequest, MsgType as TraceMsgType,
+    SetBufferSizeRequest, SetBufferSizeResponse, Status as TraceStatus, StreamBatch,
+    TraceDataChunk, TraceDumpComplete, TraceSessionInfo, TraceStatusResponse,
+};
+use crate::progress;
+use crate::protocol::ProtocolError;
+use crate::transport::Transport;
+use prost::Message;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Compact trace event (16 bytes, binary)
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+struct TraceEvent {
+    timestamp: u32,
+    task_id: u16,
+    event_type: u8,
+    flags: u8,
+    arg1: u32,
+    arg2: u32,
+}
+
+/// Trace status information
+#[derive(Debug)]
+pub struct TraceStatusInfo {
+    pub initialized: bool,
+    pub enabled: bool,
+    pub streaming: bool,
+    pub event_count: u32,
+    pub dropped_count: u32,
+    pub buffer_size: u32,
+}
+
+/// Events recorded and dropped per second between two status polls
+/// (`trace status --watch`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TraceRates {
+    pub events_per_sec: f64,
+    pub drops_per_sec: f64,
+}
+
+impl TraceRates {
+    /// Rates from two snapshots taken `elapsed` apart
+    ///
+    /// A counter that went down was reset in between (`trace clear`, a
+    /// dump or a resize), so everything it holds now was counted since.
+    pub fn between(prev: &TraceStatusInfo, next: &TraceStatusInfo, elapsed: Duration) -> Self {
+        let delta = |prev: u32, next: u32| {
+            if next < prev {
+                next
+            } else {
+                next - prev
+            }
+        };
+        let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        TraceRates {
+            events_per_sec: delta(prev.event_count, next.event_count) as f64 / secs,
+            drops_per_sec: delta(prev.dropped_count, next.dropped_count) as f64 / secs,
+        }
+    }
+}
+
+/// Result of resizing the trace ring buffer
+#[derive(Debug)]
+pub struct TraceBufferResult {
+    /// Actual capacity in events (may be less than requested)
+    pub allocated_count: u32,
+    /// Heap bytes used by the buffer
+    pub bytes_used: u32,
+    /// Whether previously recorded events were discarded
+    pub was_cleared: bool,
+}
+
+/// Helper to decode a protobuf AckResponse and check status
+fn decode_ack(payload: &[u8]) -> Result<TraceStatus> {
+    let ack = AckResponse::decode(payload).context("Failed to decode AckResponse")?;
+    decode_status(ack.status)
+}
+
+/// Map a raw trace status code, rejecting codes this CLI doesn't know
+fn decode_status(status: i32) -> Result<TraceStatus> {
+    TraceStatus::try_from(status)
+        .map_err(|_| ProtocolError::UnknownStatus(status))
+        .context("Invalid trace status")
+}
+
+/// Error for a trace request the device refused
+fn rejected<T>(message: impl Into<String>) -> Result<T> {
+    Err(DomesError::Rejected(message.into()))
+}
+
+/// Start tracing
+pub fn trace_start(transport: &mut dyn Transport) -> Result<()> {
+    let frame = transport
+        .send_command_expect(TraceMsgType::Start.as_u8(), &[], TraceMsgType::Ack.as_u8())
+        .context("Failed to send trace start command")?;
+
+    let status = decode_ack(&frame.payload)?;
+    match status {
+        TraceStatus::Ok => Ok(()),
+        TraceStatus::NotInit => rejected("Trace system not initialized"),
+        TraceStatus::AlreadyOn => rejected("Tracing is already enabled"),
+        _ => rejected(format!("Trace start failed: {}", status)),
+    }
+}
+
+/// Stop tracing
+pub fn trace_stop(transport: &mut dyn Transport) -> Result<()> {
+    let frame = transport
+        .send_command_expect(TraceMsgType::Stop.as_u8(), &[], TraceMsgType::Ack.as_u8())
+        .context("Failed to send trace stop command")?;
+
+    let status = decode_ack(&frame.payload)?;
+    match status {
+        TraceStatus::Ok => Ok(()),
+        TraceStatus::NotInit => rejected("Trace system not initialized"),
+        TraceStatus::AlreadyOff => rejected("Tracing is already disabled"),
+        _ => rejected(format!("Trace stop failed: {}", status)),
+    }
+}
+
+/// Clear trace buffer
+pub fn trace_clear(transport: &mut dyn Transport) -> Result<()> {
+    let frame = transport
+        .send_command_expect(TraceMsgType::Clear.as_u8(), &[], TraceMsgType::Ack.as_u8())
+        .context("Failed to send trace clear command")?;
+
+    let status = decode_ack(&frame.payload)?;
+    match status {
+        TraceStatus::Ok => Ok(()),
+        TraceStatus::NotInit => rejected("Trace system not initialized"),
+        _ => rejected(format!("Trace clear failed: {}", status)),
+    }
+}
+
+/// Get trace status
+pub fn trace_status(transport: &mut dyn Transport) -> Result<TraceStatusInfo> {
+    let frame = transport
+        .send_command(TraceMsgType::StatusReq.as_u8(), &[])
+        .context("Failed to send trace status command")?;
+
+    // Check for ACK with error first
+    if frame.msg_type == TraceMsgType::Ack.as_u8() {
+        let status = decode_ack(&frame.payload)?;
+        if status == TraceStatus::NotInit {
+            return rejected("Trace system not initialized");
+        }
+        return rejected(format!("Trace status failed: {}", status));
+    }
+
+    super::expect_response(&frame, TraceMsgType::StatusResp.as_u8())?;
+
+    let resp = TraceStatusResponse::decode(frame.payload.as_slice())
+        .context("Failed to decode TraceStatusResponse")?;
+
+    Ok(TraceStatusInfo {
+        initialized: resp.initialized,
+        enabled: resp.enabled,
+        streaming: resp.streaming,
+        event_count: resp.event_count,
+        dropped_count: resp.dropped_count,
+        buffer_size: resp.buffer_size,
+    })
+}
+
+/// Destination for dumped trace JSON
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceOutput {
+    /// Write to a file at the given path
+    File(PathBuf),
+    /// Write to stdout (`--output -`)
+    Stdout,
+}
+
+impl TraceOutput {
+    /// Parse an `--output` argument, treating `-` as stdout
+    pub fn from_arg(arg: &Path) -> Self {
+        if arg == Path::new("-") {
+            TraceOutput::Stdout
+        } else {
+            TraceOutput::File(arg.to_path_buf())
+        }
+    }
+}
+
+/// File format of a trace dump
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// Chrome trace event JSON
+    #[default]
+    Json,
+    /// Perfetto protobuf (`perfetto.protos.Trace`), smaller and faster to load
+    PerfettoProto,
+}
+
+impl TraceFormat {
+    /// The format an output path asks for: `.pftrace` and `.perfetto-trace`
+    /// files are Perfetto protobuf, anything else JSON
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("pftrace" | "perfetto-trace") => TraceFormat::PerfettoProto,
+            _ => TraceFormat::Json,
+        }
+    }
+
+    /// Get user-friendly name for CLI display
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            TraceFormat::Json => "json",
+            TraceFormat::PerfettoProto => "perfetto-proto",
+        }
+    }
+}
+
+impl std::fmt::Display for TraceFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.cli_name())
+    }
+}
+
+impl std::str::FromStr for TraceFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(TraceFormat::Json),
+            "perfetto-proto" | "proto" => Ok(TraceFormat::PerfettoProto),
+            _ => Err(format!("Unknown trace format: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for TraceOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TraceOutput::File(path) => write!(f, "{}", path.display()),
+            TraceOutput::Stdout => write!(f, "<stdout>"),
+        }
+    }
+}
+
+/// Resize the trace ring buffer (clears recorded events)
+pub fn trace_set_buffer(transport: &mut dyn Transport, event_count: u32) -> Result<TraceBufferResult> {
+    let req = SetBufferSizeRequest { event_count };
+    let frame = transport
+        .send_command(TraceMsgType::SetBufferSizeReq.as_u8(), &req.encode_to_vec())
+        .context("Failed to send trace set-buffer-size command")?;
+
+    // Older firmware answers unknown trace commands with an error ACK
+    if frame.msg_type == TraceMsgType::Ack.as_u8() {
+        let status = decode_ack(&frame.payload)?;
+        return rejected(format!("Trace set-buffer-size failed: {}", status));
+    }
+
+    super::expect_response(&frame, TraceMsgType::SetBufferSizeRsp.as_u8())?;
+
+    let resp = SetBufferSizeResponse::decode(frame.payload.as_slice())
+        .context("Failed to decode SetBufferSizeResponse")?;
+    let status = decode_status(resp.status)?;
+    match status {
+        TraceStatus::Ok => Ok(TraceBufferResult {
+            allocated_count: resp.allocated_count,
+            bytes_used: resp.bytes_used,
+            was_cleared: resp.was_cleared,
+        }),
+        TraceStatus::NotInit => rejected("Trace system not initialized"),
+        TraceStatus::NoMemory => rejected(format!(
+            "Not enough memory on device for {} events",
+            event_count
+        )),
+        _ => rejected(format!("Trace set-buffer-size failed: {}", status)),
+    }
+}
+
+/// Longest marker label the firmware keeps (nanopb `max_size` in trace.options)
+pub const ANNOTATION_LABEL_MAX_LEN: usize = 32;
+
+/// A user marker recorded by `trace_annotate`
+#[derive(Debug)]
+pub struct TraceAnnotation {
+    /// Marker number, counting from 1 since the trace was last cleared
+    pub marker: u32,
+    /// Device timestamp of the marker event (microseconds since boot)
+    pub timestamp_us: u32,
+}
+
+/// Record a user marker with `label` in the trace timeline
+///
+/// The marker is an instant event in the `user` category, named `label` in
+/// the dump (or `marker #N` if the device could not keep the label).
+pub fn trace_annotate(transport: &mut dyn Transport, label: &str) -> Result<TraceAnnotation> {
+    if label.is_empty() || label.len() > ANNOTATION_LABEL_MAX_LEN {
+        return Err(DomesError::InvalidArgument(format!(
+            "Marker label must be 1-{} bytes (got {})",
+            ANNOTATION_LABEL_MAX_LEN,
+            label.len()
+        )));
+    }
+
+    let req = AnnotateRequest {
+        label: label.to_string(),
+    };
+    let frame = transport
+        .send_command(TraceMsgType::AnnotateReq.as_u8(), &req.encode_to_vec())
+        .context("Failed to send trace annotate command")?;
+
+    // Older firmware answers unknown trace commands with an error ACK
+    if frame.msg_type == TraceMsgType::Ack.as_u8() {
+        let status = decode_ack(&frame.payload)?;
+        return rejected(format!("Trace annotate failed: {}", status));
+    }
+
+    super::expect_response(&frame, TraceMsgType::AnnotateRsp.as_u8())?;
+
+    let resp = AnnotateResponse::decode(frame.payload.as_slice())
+        .context("Failed to decode AnnotateResponse")?;
+    match decode_status(resp.status)? {
+        TraceStatus::Ok => Ok(TraceAnnotation {
+            marker: resp.marker,
+            timestamp_us: resp.timestamp_us,
+        }),
+        TraceStatus::NotInit => rejected("Trace system not initialized"),
+        TraceStatus::AlreadyOff => rejected("Tracing is disabled; run trace start first"),
+        status => rejected(format!("Trace annotate failed: {}", status)),
+    }
+}
+
+/// Result of a trace dump operation
+pub struct DumpResult {
+    pub event_count: u32,
+    pub dropped_count: u32,
+    pub duration_us: u32,
+    pub pod_id: u32,
+    /// Newest event timestamp seen (µs since boot), for the next `since`;
+    /// `None` if the device sent no events
+    pub max_timestamp_us: Option<u32>,
+    /// Events at or before `since` that were left out of the output
+    pub skipped_count: u32,
+    pub output: TraceOutput,
+    /// Whether the CRC32 over received event bytes matched the device's checksum
+    pub checksum_valid: bool,
+    /// With `append`, the offset (µs) added to the new events: the end of
+    /// the existing trace. `None` if a new file was written
+    pub appended_after_us: Option<u64>,
+}
+
+/// How to run a dump; `Default` writes every event and only flags a bad checksum
+#[derive(Debug, Clone, Default)]
+pub struct DumpOptions<'a> {
+    /// Only write events newer than this `max_timestamp_us` of an earlier
+    /// dump. The timestamp is sent in the request; firmware that ignores it
+    /// sends everything, so older events are also dropped here.
+    pub since: Option<u32>,
+    /// Add the events after those already in the JSON output file (see
+    /// `merge_trace_json`). JSON files only
+    pub append: bool,
+    /// Name of the session's own track in the JSON output
+    pub session_label: Option<&'a str>,
+    /// Fail on a checksum mismatch before anything is written
+    pub strict: bool,
+}
+
+/// Dump traces as JSON compatible with Perfetto
+///
+/// A checksum mismatch is reported in `DumpResult::checksum_valid`; with
+/// `options.strict` it fails instead and the output is left untouched.
+pub fn trace_dump(
+    transport: &mut dyn Transport,
+    output: TraceOutput,
+    format: TraceFormat,
+    names_path: Option<&Path>,
+    options: &DumpOptions,
+) -> Result<DumpResult> {
+    let DumpOptions { since, append, session_label, strict } = *options;
+    // Load span names if provided (or auto-discover)
+    let mut span_names = load_span_names(names_path)?;
+
+    let request = DumpRequest {
+        since_us: since.unwrap_or(0),
+    };
+    let frame = transport
+        .send_command(TraceMsgType::Dump.as_u8(), &request.encode_to_vec())
+        .context("Failed to send trace dump command")?;
+
+    // Check for ACK with error (e.g., buffer empty)
+    if frame.msg_type == TraceMsgType::Ack.as_u8() {
+        let status = decode_ack(&frame.payload)?;
+        return match status {
+            TraceStatus::NotInit => rejected("Trace system not initialized"),
+            TraceStatus::BufferEmpty => rejected("Trace buffer is empty"),
+            _ => rejected(format!("Trace dump failed: {}", status)),
+        };
+    }
+
+    // First response should be SESSION_INFO with metadata
+    super::expect_response(&frame, TraceMsgType::SessionInfo.as_u8())?;
+
+    // Parse session info (protobuf)
+    let session_info = TraceSessionInfo::decode(frame.payload.as_slice())
+        .context("Failed to decode TraceSessionInfo")?;
+
+    // Build task name lookup
+    let task_names: HashMap<u32, String> = session_info
+        .tasks
+        .iter()
+        .map(|t| (t.task_id, t.name.clone()))
+        .collect();
+
+    // User marker labels name their events like span names do
+    for annotation in &session_info.annotations {
+        span_names.insert(annotation.hash, annotation.label.clone());
+    }
+
+    // Collect all events
+    let mut events: Vec<TraceEvent> = Vec::with_capacity(session_info.event_count as usize);
+    let mut total_received = 0u32;
+    // Rolling CRC32 over raw event bytes, compared against TraceDumpComplete.checksum
+    let mut hasher = crc32fast::Hasher::new();
+    let expected_checksum;
+
+    loop {
+        let frame = transport
+            .receive_frame(5000) // 5 second timeout for trace data
+            .context("Failed to receive trace data")?;
+
+        if frame.msg_type == TraceMsgType::Data.as_u8() {
+            // Parse data chunk (protobuf)
+            let chunk = TraceDataChunk::decode(frame.payload.as_slice())
+                .context("Failed to decode TraceDataChunk")?;
+
+            // Extract binary events from bytes field
+            let event_bytes = &chunk.events;
+            let event_size = std::mem::size_of::<TraceEvent>();
+            let event_count = event_bytes.len() / event_size;
+
+            for i in 0..event_count {
+                let offset = i * event_size;
+                if offset + event_size <= event_bytes.len() {
+                    hasher.update(&event_bytes[offset..offset + event_size]);
+                    let event = unsafe {
+                        std::ptr::read_unaligned(
+                            event_bytes[offset..].as_ptr() as *const TraceEvent,
+                        )
+                    };
+                    events.push(event);
+                    total_received += 1;
+                }
+            }
+        } else if frame.msg_type == TraceMsgType::End.as_u8() {
+            // Parse dump complete (protobuf)
+            let end = TraceDumpComplete::decode(frame.payload.as_slice())
+                .context("Failed to decode TraceDumpComplete")?;
+            expected_checksum = end.checksum;
+            break;
+        } else {
+            return Err(DomesError::UnexpectedResponse {
+                expected: TraceMsgType::Data.as_u8(),
+                actual: frame.msg_type,
+            });
+        }
+    }
+
+    let actual_checksum = hasher.finalize();
+    let checksum_valid = actual_checksum == expected_checksum;
+    if strict && !checksum_valid {
+        return Err(ProtocolError::ChecksumMismatch {
+            expected: expected_checksum,
+            actual: actual_checksum,
+        })
+        .context("Trace checksum mismatch: dump data is corrupt");
+    }
+
+    let max_timestamp_us = events.iter().map(|e| e.timestamp).max();
+    let skipped_count = match since {
+        Some(since) => drop_events_up_to(&mut events, since),
+        None => 0,
+    };
+
+    let mut appended_after_us = None;
+    let data = match format {
+        TraceFormat::Json => {
+            // Chrome JSON trace format, which Perfetto also opens
+            let existing = match (append, &output) {
+                (true, TraceOutput::File(path)) if path.exists() => Some(
+                    std::fs::read_to_string(path)
+                        .context("Failed to read trace file to append to")?,
+                ),
+                _ => None,
+            };
+            let json = match existing {
+                Some(existing) => {
+                    let offset = trace_end_us(&existing)?;
+                    appended_after_us = Some(u64::from(offset));
+                    merge_trace_json(
+                        &existing,
+                        &events,
+                        offset,
+                        &task_names,
+                        &span_names,
+                        session_info.pod_id,
+                        session_label,
+                    )?
+                }
+                None => convert_to_perfetto_json(
+                    &events,
+                    &task_names,
+                    &span_names,
+                    session_info.pod_id,
+                    session_label,
+                ),
+            };
+            json.into_bytes()
+        }
+        TraceFormat::PerfettoProto => {
+            convert_to_perfetto_proto(&events, &task_names, &span_names, session_info.pod_id)
+        }
+    };
+
+    match &output {
+        TraceOutput::File(path) => {
+            let mut file = File::create(path).context("Failed to create output file")?;
+            file.write_all(&data)
+                .context("Failed to write trace file")?;
+        }
+        TraceOutput::Stdout => {
+            let mut stdout = std::io::stdout().lock();
+            // A newline ends JSON text; it would corrupt a binary trace
+            let newline: &[u8] = match format {
+                TraceFormat::Json => b"\n",
+                TraceFormat::PerfettoProto => b"",
+            };
+            stdout
+                .write_all(&data)
+                .and_then(|_| stdout.write_all(newline))
+                .and_then(|_| stdout.flush())
+                .context("Failed to write trace to stdout")?;
+        }
+    }
+
+    Ok(DumpResult {
+        event_count: total_received - skipped_count,
+        dropped_count: session_info.dropped_count,
+        duration_us: session_info
+            .end_timestamp_us
+            .saturating_sub(session_info.start_timestamp_us),
+        pod_id: session_info.pod_id,
+        max_timestamp_us,
+        skipped_count,
+        output,
+        checksum_valid,
+        appended_after_us,
+    })
+}
+
+/// Timed capture: start tracing, wait, stop, then dump
+///
+/// A countdown is shown on stderr. Setting `interrupted` (e.g. from a Ctrl-C
+/// handler) ends the wait early; tracing is still stopped and dumped.
+pub fn trace_capture(
+    transport: &mut dyn Transport,
+    duration: Duration,
+    output: TraceOutput,
+    format: TraceFormat,
+    names_path: Option<&Path>,
+    interrupted: &AtomicBool,
+) -> Result<DumpResult> {
+    trace_start(transport)?;
+
+    let started = Instant::now();
+    let mut last_shown = None;
+    while started.elapsed() < duration && !interrupted.load(Ordering::Relaxed) {
+        let remaining = duration.saturating_sub(started.elapsed()).as_secs() + 1;
+        if last_shown != Some(remaining) && !progress::is_quiet() {
+            eprint!("\rCapturing... {}s remaining (Ctrl-C to stop early) ", remaining);
+            std::io::stderr().flush().ok();
+            last_shown = Some(remaining);
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    if last_shown.is_some() {
+        eprintln!();
+    }
+    if interrupted.load(Ordering::Relaxed) {
+        progress!("Interrupted after {:.1}s, stopping capture", started.elapsed().as_secs_f64());
+    }
+
+    trace_stop(transport)?;
+    trace_dump(transport, output, format, names_path, &DumpOptions::default())
+}
+
+/// Remove events with `timestamp <= since`; returns how many were removed
+fn drop_events_up_to(events: &mut Vec<TraceEvent>, since: u32) -> u32 {
+    let before = events.len();
+    events.retain(|e| { e.timestamp } > since);
+    (before - events.len()) as u32
+}
+
+/// Load span name mappings from a JSON file
+///
+/// Format: { "hash_decimal": "Module.SpanName", ... }
+/// Auto-discovers tools/trace/trace_names.json if no path given.
+fn load_span_names(names_path: Option<&Path>) -> Result<HashMap<u32, String>> {
+    let path = match names_path {
+        Some(p) => {
+            if !p.exists() {
+                return Err(DomesError::InvalidArgument(format!(
+                    "Span names file not found: {}",
+                    p.display()
+                )));
+            }
+            p.to_path_buf()
+        }
+        None => {
+            // Auto-discover trace_names.json relative to the binary
+            let candidates = [
+                Path::new("tools/trace/trace_names.json").to_path_buf(),
+                Path::new("trace_names.json").to_path_buf(),
+            ];
+            match candidates.iter().find(|p| p.exists()) {
+                Some(p) => p.clone(),
+                None => return Ok(HashMap::new()), // No names file, use defaults
+            }
+        }
+    };
+
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read span names from {}", path.display()))?;
+
+    let raw: HashMap<String, String> = serde_json::from_str(&content).map_err(|e| {
+        DomesError::InvalidArgument(format!(
+            "Failed to parse span names JSON {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    let mut names = HashMap::new();
+    for (key, value) in raw {
+        if let Ok(hash) = key.parse::<u32>() {
+            names.insert(hash, value);
+        }
+    }
+
+    Ok(names)
+}
+
+/// Parse a Chrome trace JSON file written by `trace dump`: an array of events
+fn parse_trace_json(json: &str) -> Result<Vec<serde_json::Value>> {
+    match serde_json::from_str(json) {
+        Ok(serde_json::Value::Array(events)) => Ok(events),
+        Ok(_) => Err(DomesError::InvalidArgument(
+            "Existing trace file is not a JSON array of trace events".to_string(),
+        )),
+        Err(e) => Err(DomesError::InvalidArgument(format!(
+            "Failed to parse existing trace file: {}",
+            e
+        ))),
+    }
+}
+
+/// End of a Chrome trace JSON timeline (µs): the latest event end
+fn trace_end_us(json: &str) -> Result<u32> {
+    let end = parse_trace_json(json)?
+        .iter()
+        .map(|event| {
+            let ts = event["ts"].as_f64().unwrap_or(0.0);
+            ts + event["dur"].as_f64().unwrap_or(0.0)
+        })
+        .fold(0.0, f64::max);
+    if end > f64::from(u32::MAX) {
+        return Err(DomesError::InvalidArgument(
+            "Existing trace file is too long to append to".to_string(),
+        ));
+    }
+    Ok(end.ceil() as u32)
+}
+
+/// Append `new_events` to the Chrome trace JSON array in `existing`
+///
+/// `time_offset` (µs, usually the end of the existing trace from
+/// `trace_end_us`) is added to each new timestamp as the events are built,
+/// so a session captured after a reboot or a cleared buffer still comes
+/// after the sessions already in the file. The other arguments are as for
+/// `convert_to_perfetto_json`.
+fn merge_trace_json(
+    existing: &str,
+    new_events: &[TraceEvent],
+    time_offset: u32,
+    task_names: &HashMap<u32, String>,
+    span_names: &HashMap<u32, String>,
+    pod_id: u32,
+    session_label: Option<&str>,
+) -> Result<String> {
+    let shifted = new_events
+        .iter()
+        .map(|event| {
+            let timestamp = { event.timestamp }.checked_add(time_offset)?;
+            Some(TraceEvent { timestamp, ..*event })
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| {
+            DomesError::InvalidArgument(
+                "Appended events would overflow the 32-bit trace timestamps".to_string(),
+            )
+        })?;
+
+    let mut events = parse_trace_json(existing)?;
+    let json_events = session_json_events(&shifted, task_names, span_names, pod_id, session_label);
+    // Strings and numbers only, so converting and serializing cannot fail
+    events.extend(json_events.iter().filter_map(|e| serde_json::to_value(e).ok()));
+    Ok(serde_json::to_string(&events).unwrap_or_default())
+}
+
+/// Thread ID of the track holding `session_label` slices
+const SESSION_TID: u16 = u16::MAX;
+
+/// A slice named `label` covering `events`, on a track named "sessions"
+///
+/// Appended sessions each get their slice on the same track, which shows
+/// where one session ends and the next begins.
+fn session_events(label: &str, events: &[PerfettoEvent], pod_id: u32) -> [PerfettoEvent; 2] {
+    let start = events.iter().map(|e| e.ts).min().unwrap_or(0);
+    let end = events
+        .iter()
+        .map(|e| e.ts.saturating_add(e.dur.unwrap_or(0)))
+        .max()
+        .unwrap_or(start);
+    [
+        // Metadata event naming the track
+        PerfettoEvent {
+            name: "thread_name".to_string(),
+            cat: "__metadata",
+            ph: "M",
+            ts: 0,
+            pid: pod_id,
+            tid: SESSION_TID,
+            dur: None,
+            args: serde_json::json!({ "name": "sessions" }),
+        },
+        PerfettoEvent {
+            name: label.to_string(),
+            cat: "session",
+            ph: "X",
+            ts: start,
+            pid: pod_id,
+            tid: SESSION_TID,
+            dur: Some(end - start),
+            args: serde_json::json!({}),
+        },
+    ]
+}
+
+/// One event in Chrome trace JSON format
+#[derive(Serialize)]
+struct PerfettoEvent {
+    name: String,
+    cat: &'static str,
+    ph: &'static str,
+    ts: u32,
+    pid: u32,
+    tid: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dur: Option<u32>,
+    args: serde_json::Value,
+}
+
+/// Convert trace events to Perfetto-compatible Chrome JSON format
+///
+/// Task and span names are escaped by the serializer, so a name buffer
+/// holding quotes or garbage bytes still gives valid JSON. `session_label`
+/// adds a slice naming the whole session (see `session_events`).
+fn convert_to_perfetto_json(
+    events: &[TraceEvent],
+    task_names: &HashMap<u32, String>,
+    span_names: &HashMap<u32, String>,
+    pod_id: u32,
+    session_label: Option<&str>,
+) -> String {
+    let json_events = session_json_events(events, task_names, span_names, pod_id, session_label);
+    // Strings and numbers only, so serializing cannot fail
+    serde_json::to_string(&json_events).unwrap_or_default()
+}
+
+/// `perfetto_events`, plus the session slice if `session_label` is set
+fn session_json_events(
+    events: &[TraceEvent],
+    task_names: &HashMap<u32, String>,
+    span_names: &HashMap<u32, String>,
+    pod_id: u32,
+    session_label: Option<&str>,
+) -> Vec<PerfettoEvent> {
+    let mut json_events = perfetto_events(events, task_names, span_names, pod_id);
+    if let Some(label) = session_label {
+        let session = session_events(label, &json_events, pod_id);
+        json_events.extend(session);
+    }
+    json_events
+}
+
+/// Name, category, phase and args of each event, as Chrome trace events
+fn perfetto_events(
+    events: &[TraceEvent],
+    task_names: &HashMap<u32, String>,
+    span_names: &HashMap<u32, String>,
+    pod_id: u32,
+) -> Vec<PerfettoEvent> {
+    let mut json_events = Vec::with_capacity(events.len());
+
+    for event in events {
+        // Copy packed struct fields to local variables to avoid unaligned access
+        let timestamp = { event.timestamp };
+        let task_id = { event.task_id };
+        let event_type = { event.event_type };
+        let flags = { event.flags };
+        let arg1 = { event.arg1 };
+        let arg2 = { event.arg2 };
+
+        let task_name = task_names
+            .get(&(task_id as u32))
+            .map(|s| s.as_str())
+            .unwrap_or("unknown");
+        let category = category_name((flags >> 4) & 0x0F);
+
+        // Chrome trace event format
+        let phase = match event_type {
+            0x20 => "B", // SPAN_BEGIN -> Begin
+            0x21 => "E", // SPAN_END -> End
+            0x22 => "i", // INSTANT -> Instant
+            0x23 => "C", // COUNTER -> Counter
+            0x24 => "X", // COMPLETE -> Complete (duration in arg2)
+            0x01 => "B", // TASK_SWITCH_IN -> Begin
+            0x02 => "E", // TASK_SWITCH_OUT -> End
+            0x05 => "B", // ISR_ENTER -> Begin
+            0x06 => "E", // ISR_EXIT -> End
+            0x09 => "B", // MUTEX_LOCK -> Begin (lock held)
+            0x0A => "E", // MUTEX_UNLOCK -> End (lock released)
+            0x0B => "i", // MUTEX_CONTENTION -> Instant (with wait time)
+            0x0C => "i", // SEM_TAKE -> Instant
+            0x0D => "i", // SEM_GIVE -> Instant
+            _ => "i",    // Default to instant
+        };
+
+        // Resolve span name from hash
+        let name = match event_type {
+            0x01 | 0x02 => format!("task:{}", task_name),
+            0x05 | 0x06 => format!("isr:{}", arg1),
+            0x09 | 0x0A => {
+                // Mutex lock/unlock: resolve name from hash
+                span_names
+                    .get(&arg1)
+                    .cloned()
+                    .unwrap_or_else(|| format!("mutex:{}", arg1))
+            }
+            0x0B => {
+                // Mutex contention: resolve name, arg2 = wait time us
+                span_names
+                    .get(&arg1)
+                    .cloned()
+                    .unwrap_or_else(|| format!("mutex:{}", arg1))
+            }
+            0x0C | 0x0D => {
+                // Semaphore take/give: resolve name from hash
+                span_names
+                    .get(&arg1)
+                    .cloned()
+                    .unwrap_or_else(|| format!("sem:{}", arg1))
+            }
+            0x23 => {
+                // Counter: resolve name from hash
+                span_names
+                    .get(&arg1)
+                    .cloned()
+                    .unwrap_or_else(|| format!("counter:{}", arg1))
+            }
+            0x24 => {
+                // Complete: resolve span name from hash, arg2 = duration us
+                span_names
+                    .get(&arg1)
+                    .cloned()
+                    .unwrap_or_else(|| format!("span:{}", arg1))
+            }
+            0x22 if category == "user" => {
+                // User marker: label from its hash, arg2 = marker number
+                span_names
+                    .get(&arg1)
+                    .cloned()
+                    .unwrap_or_else(|| format!("marker #{}", arg2))
+            }
+            _ => {
+                // Span/instant: resolve name from hash
+                span_names
+                    .get(&arg1)
+                    .cloned()
+                    .unwrap_or_else(|| format!("span:{}", arg1))
+            }
+        };
+
+        let args = match event_type {
+            // Counter value; every counter arg is plotted as a series, so
+            // the raw args are left out
+            0x23 => serde_json::json!({ "value": arg2 }),
+            // Mutex contention wait time
+            0x0B => serde_json::json!({ "arg1": arg1, "arg2": arg2, "wait_us": arg2 }),
+            // Raw values for the detail panel
+            _ => serde_json::json!({ "arg1": arg1, "arg2": arg2 }),
+        };
+
+        json_events.push(PerfettoEvent {
+            name,
+            cat: category,
+            ph: phase,
+            ts: timestamp,
+            pid: pod_id,
+            tid: task_id,
+            // Duration for complete events
+            dur: (event_type == 0x24).then_some(arg2),
+            args,
+        });
+    }
+
+    json_events
+}
+
+/// Convert trace events to a Perfetto protobuf trace (`perfetto.protos.Trace`)
+///
+/// The pod is a process track with a thread track per task, like `pid` and
+/// `tid` in the JSON format, and each counter gets a counter track. Complete
+/// events are written as a begin and an end slice.
+fn convert_to_perfetto_proto(
+    events: &[TraceEvent],
+    task_names: &HashMap<u32, String>,
+    span_names: &HashMap<u32, String>,
+    pod_id: u32,
+) -> Vec<u8> {
+    use crate::proto::perfetto::{
+        track_event::Type, CounterDescriptor, DebugAnnotation, ProcessDescriptor,
+        ThreadDescriptor, Trace, TracePacket, TrackDescriptor, TrackEvent,
+    };
+
+    const SEQUENCE_ID: u32 = 1;
+    const PROCESS_UUID: u64 = 1;
+    const THREAD_UUID_BASE: u64 = 1 << 32;
+    const COUNTER_UUID_BASE: u64 = 2 << 32;
+
+    let mut descriptors = vec![TrackDescriptor {
+        uuid: Some(PROCESS_UUID),
+        process: Some(ProcessDescriptor {
+            pid: Some(pod_id as i32),
+            process_name: Some(format!("pod {}", pod_id)),
+        }),
+        ..Default::default()
+    }];
+    let mut threads: HashMap<u16, u64> = HashMap::new();
+    let mut counters: HashMap<String, u64> = HashMap::new();
+    let mut track_events: Vec<(u64, TrackEvent)> = Vec::with_capacity(events.len());
+
+    for event in perfetto_events(events, task_names, span_names, pod_id) {
+        let ts_ns = event.ts as u64 * 1000;
+
+        if event.ph == "C" {
+            let next_uuid = COUNTER_UUID_BASE + counters.len() as u64;
+            let uuid = *counters.entry(event.name.clone()).or_insert_with(|| {
+                descriptors.push(TrackDescriptor {
+                    uuid: Some(next_uuid),
+                    name: Some(event.name.clone()),
+                    parent_uuid: Some(PROCESS_UUID),
+                    counter: Some(CounterDescriptor {}),
+                    ..Default::default()
+                });
+                next_uuid
+            });
+            let value = event.args.get("value").and_then(|v| v.as_i64());
+            track_events.push((
+                ts_ns,
+                TrackEvent {
+                    r#type: Some(Type::Counter as i32),
+                    track_uuid: Some(uuid),
+                    counter_value: value,
+                    ..Default::default()
+                },
+            ));
+            continue;
+        }
+
+        let uuid = *threads.entry(event.tid).or_insert_with(|| {
+            let uuid = THREAD_UUID_BASE + event.tid as u64;
+            descriptors.push(TrackDescriptor {
+                uuid: Some(uuid),
+                parent_uuid: Some(PROCESS_UUID),
+                thread: Some(ThreadDescriptor {
+                    pid: Some(pod_id as i32),
+                    tid: Some(event.tid as i32),
+                    thread_name: task_names.get(&(event.tid as u32)).cloned(),
+                }),
+                ..Default::default()
+            });
+            uuid
+        });
+        let annotations = event
+            .args
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| DebugAnnotation {
+                name: Some(name.clone()),
+                uint_value: value.as_u64(),
+            })
+            .collect();
+        let slice = |kind: Type| TrackEvent {
+            r#type: Some(kind as i32),
+            track_uuid: Some(uuid),
+            ..Default::default()
+        };
+        let begin = TrackEvent {
+            categories: vec![event.cat.to_string()],
+            name: Some(event.name),
+            debug_annotations: annotations,
+            ..slice(match event.ph {
+                "B" | "X" => Type::SliceBegin,
+                "E" => Type::SliceEnd,
+                _ => Type::Instant,
+            })
+        };
+        track_events.push((ts_ns, begin));
+        if let Some(dur) = event.dur {
+            track_events.push((ts_ns + dur as u64 * 1000, slice(Type::SliceEnd)));
+        }
+    }
+
+    // Ends of complete events are out of order; ties keep their dump order
+    track_events.sort_by_key(|(ts, _)| *ts);
+
+    let descriptors = descriptors.into_iter().map(|track| TracePacket {
+        trusted_packet_sequence_id: Some(SEQUENCE_ID),
+        track_descriptor: Some(track),
+        ..Default::default()
+    });
+    let track_events = track_events.into_iter().map(|(ts, event)| TracePacket {
+        timestamp: Some(ts),
+        trusted_packet_sequence_id: Some(SEQUENCE_ID),
+        track_event: Some(event),
+        ..Default::default()
+    });
+    Trace {
+        packet: descriptors.chain(track_events).collect(),
+    }
+    .encode_to_vec()
+}
+
+/// Stream trace events in real-time from a TCP connection
+///
+/// Connects to the trace stream port (5001) on the device and prints
+/// events as they arrive. Runs until interrupted (Ctrl+C) or error.
+pub fn trace_stream(addr: &str) -> Result<()> {
+    use std::net::TcpStream;
+
+    // Connect to trace stream port (5001)
+    let stream_addr = if addr.contains(':') {
+        // Replace port with 5001
+        let parts: Vec<&str> = addr.splitn(2, ':').collect();
+        format!("{}:5001", parts[0])
+    } else {
+        format!("{}:5001", addr)
+    };
+
+    progress!("Connecting to trace stream at {}...", stream_addr);
+    let mut stream = TcpStream::connect(&stream_addr)
+        .with_context(|| format!("Failed to connect to trace stream at {}", stream_addr))?;
+
+    stream
+        .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+        .ok();
+
+    progress!("Connected. Streaming trace events (Ctrl+C to stop)...");
+    eprintln!(
+        "{:<12} {:<6} {:<12} {:<12} {:>10} {:>10}",
+        "TIMESTAMP", "TASK", "TYPE", "CATEGORY", "ARG1", "ARG2"
+    );
+    eprintln!(
+        "{:-<12} {:-<6} {:-<12} {:-<12} {:->10} {:->10}",
+        "", "", "", "", "", ""
+    );
+
+    // Load span names for resolving
+    let span_names = load_span_names(None).unwrap_or_default();
+
+    let mut frame_decoder = crate::transport::frame::FrameDecoder::new();
+    let mut buf = [0u8; 1024];
+
+    loop {
+        use std::io::Read;
+        let n = match stream.read(&mut buf) {
+            Ok(0) => {
+                eprintln!("\nConnection closed by device");
+                break;
+            }
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut
+                || e.kind() == std::io::ErrorKind::WouldBlock => {
+                continue;
+            }
+            Err(e) => {
+                return Err(DomesError::Io {
+                    context: "Failed to read trace stream".to_string(),
+                    source: e,
+                });
+            }
+        };
+
+        // Feed bytes to frame decoder
+        for &byte in &buf[..n] {
+            if let Some(Ok(frame)) = frame_decoder.feed_byte(byte) {
+                if frame.msg_type == TraceMsgType::StreamData.as_u8() {
+                    // Decode StreamBatch
+                    if let Ok(batch) = StreamBatch::decode(frame.payload.as_slice()) {
+                        if batch.dropped > 0 {
+                            eprintln!("  [dropped {} events]", batch.dropped);
+                        }
+
+                        // Parse binary events
+                        let event_size = std::mem::size_of::<TraceEvent>();
+                        let event_count = batch.events.len() / event_size;
+
+                        for i in 0..event_count {
+                            let offset = i * event_size;
+                            if offset + event_size <= batch.events.len() {
+                                let event = unsafe {
+                                    std::ptr::read_unaligned(
+                                        batch.events[offset..].as_ptr() as *const TraceEvent,
+                                    )
+                                };
+
+                                let timestamp = { event.timestamp };
+                                let task_id = { event.task_id };
+                                let event_type = { event.event_type };
+                                let flags = { event.flags };
+                                let arg1 = { event.arg1 };
+                                let arg2 = { event.arg2 };
+
+                                let type_name = match event_type {
+                                    0x20 => "BEGIN",
+                                    0x21 => "END",
+                                    0x22 => "INSTANT",
+                                    0x23 => "COUNTER",
+                                    0x24 => "COMPLETE",
+                                    0x01 => "TASK_IN",
+                                    0x02 => "TASK_OUT",
+                                    _ => "UNKNOWN",
+                                };
+
+                                let cat = category_name((flags >> 4) & 0x0F);
+
+                                let name = span_names
+                                    .get(&arg1)
+                                    .map(|s| s.as_str())
+                                    .unwrap_or("");
+
+                                if event_type == 0x23 {
+                                    // Counter
+                                    println!(
+                                        "{:<12} {:<6} {:<12} {:<12} {} = {}",
+                                        timestamp, task_id, type_name, cat, name, arg2
+                                    );
+                                } else {
+                                    println!(
+                                        "{:<12} {:<6} {:<12} {:<12} {:>10} {:>10}  {}",
+                                        timestamp, task_id, type_name, cat, arg1, arg2, name
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn category_name(cat: u8) -> &'static str {
+    match cat {
+        0 => "kernel",
+        1 => "transport",
+        2 => "ota",
+        3 => "wifi",
+        4 => "led",
+        5 => "audio",
+        6 => "touch",
+        7 => "game",
+        8 => "user",
+        9 => "haptic",
+        10 => "ble",
+        11 => "nvs",
+        12 => "espnow",
+        13 => "sync",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(timestamp: u32) -> TraceEvent {
+        TraceEvent {
+            timestamp,
+            task_id: 1,
+            event_type: 0x22,
+            flags: 0,
+            arg1: 0,
+            arg2: 0,
+        }
+    }
+
+    #[test]
+    fn test_trace_rates_between_polls() {
+        let status = |event_count, dropped_count| TraceStatusInfo {
+            initialized: true,
+            enabled: true,
+            streaming: false,
+            event_count,
+            dropped_count,
+            buffer_size: 4096,
+        };
+        let half_second = Duration::from_millis(500);
+        let rates = TraceRates::between(&status(100, 0), &status(350, 10), half_second);
+        assert_eq!(rates, TraceRates { events_per_sec: 500.0, drops_per_sec: 20.0 });
+
+        // Cleared in between: the new counts are all since the clear
+        let rates = TraceRates::between(&status(4000, 10), &status(50, 0), half_second);
+        assert_eq!(rates, TraceRates { events_per_sec: 100.0, drops_per_sec: 0.0 });
+    }
+
+    #[test]
+    fn test_drop_events_up_to_since() {
+        let mut events = vec![event(100), event(250), event(200), event(300)];
+        assert_eq!(drop_events_up_to(&mut events, 200), 2);
+        let kept: Vec<u32> = events.iter().map(|e| e.timestamp).collect();
+        assert_eq!(kept, [250, 300]);
+    }
+
+    #[test]
+    fn test_perfetto_json_args() {
+        let complete = TraceEvent {
+            timestamp: 1000,
+            task_id: 2,
+            event_type: 0x24,
+            flags: 0x40,
+            arg1: 42,
+            arg2: 350,
+        };
+        let counter = TraceEvent {
+            event_type: 0x23,
+            arg1: 7,
+            arg2: 12,
+            ..complete
+        };
+        let task_names = HashMap::from([(2, "main".to_string())]);
+        let span_names = HashMap::from([(7, "queue \"depth\"".to_string())]);
+
+        let json =
+            convert_to_perfetto_json(&[complete, counter], &task_names, &span_names, 3, None);
+        assert_eq!(
+            json,
+            concat!(
+                r#"[{"name":"span:42","cat":"led","ph":"X","ts":1000,"pid":3,"tid":2,"#,
+                r#""dur":350,"args":{"arg1":42,"arg2":350}},"#,
+                r#"{"name":"queue \"depth\"","cat":"led","ph":"C","ts":1000,"pid":3,"tid":2,"#,
+                r#""args":{"value":12}}]"#
+            )
+        );
+        assert!(serde_json::from_str::<serde_json::Value>(&json).is_ok());
+    }
+
+    #[test]
+    fn test_perfetto_proto_tracks_and_slices() {
+        use crate::proto::perfetto::{track_event::Type, Trace};
+
+        let complete = TraceEvent {
+            timestamp: 1000,
+            task_id: 2,
+            event_type: 0x24,
+            flags: 0x40,
+            arg1: 42,
+            arg2: 350,
+        };
+        let counter = TraceEvent {
+            timestamp: 1200,
+            event_type: 0x23,
+            arg1: 7,
+            arg2: 12,
+            ..complete
+        };
+        let task_names = HashMap::from([(2, "main".to_string())]);
+        let span_names = HashMap::from([(7, "queue depth".to_string())]);
+
+        let bytes = convert_to_perfetto_proto(&[complete, counter], &task_names, &span_names, 3);
+        let trace = Trace::decode(bytes.as_slice()).unwrap();
+        let tracks: Vec<_> = trace
+            .packet
+            .iter()
+            .filter_map(|p| p.track_descriptor.as_ref())
+            .collect();
+        assert_eq!(tracks[0].process.as_ref().unwrap().pid, Some(3));
+        assert_eq!(tracks[1].thread.as_ref().unwrap().thread_name.as_deref(), Some("main"));
+        assert_eq!(tracks[2].name.as_deref(), Some("queue depth"));
+        assert!(tracks[2].counter.is_some());
+
+        // The complete event's end comes after the counter sample
+        let events: Vec<_> = trace
+            .packet
+            .iter()
+            .filter_map(|p| Some((p.timestamp?, p.track_event.as_ref()?)))
+            .collect();
+        let kinds: Vec<_> = events.iter().map(|(ts, e)| (*ts, e.r#type())).collect();
+        assert_eq!(
+            kinds,
+            [
+                (1_000_000, Type::SliceBegin),
+                (1_200_000, Type::Counter),
+                (1_350_000, Type::SliceEnd)
+            ]
+        );
+        assert_eq!(events[0].1.name.as_deref(), Some("span:42"));
+        assert_eq!(events[0].1.categories, ["led"]);
+        assert_eq!(events[0].1.debug_annotations[1].uint_value, Some(350));
+        assert_eq!(events[1].1.counter_value, Some(12));
+        assert_eq!(events[1].1.track_uuid, tracks[2].uuid);
+        assert_eq!(events[2].1.track_uuid, tracks[1].uuid);
+
+        assert_eq!(TraceFormat::for_path(Path::new("t.pftrace")), TraceFormat::PerfettoProto);
+        assert_eq!(TraceFormat::for_path(Path::new("trace.json")), TraceFormat::Json);
+    }
+
+    #[test]
+    fn test_append_session_after_existing_trace() {
+        let none = HashMap::new();
+        let events = [event(100), event(900)];
+        let first = convert_to_perfetto_json(&events, &none, &none, 1, Some("run-1"));
+        let complete = TraceEvent {
+            event_type: 0x24,
+            arg2: 50,
+            ..event(200)
+        };
+        let second = [complete];
+
+        // The session slice spans the first event to the end of the last
+        let end = trace_end_us(&first).unwrap();
+        assert_eq!(end, 900);
+        let merged =
+            merge_trace_json(&first, &second, end, &none, &none, 1, Some("run-2")).unwrap();
+        let events: Vec<serde_json::Value> = serde_json::from_str(&merged).unwrap();
+        let sessions: Vec<_> = events
+            .iter()
+            .filter(|e| e["cat"] == "session")
+            .map(|e| (e["name"].as_str().unwrap(), e["ts"].as_u64().unwrap(), e["dur"].as_u64()))
+            .collect();
+        assert_eq!(sessions, [("run-1", 100, Some(800)), ("run-2", 1100, Some(50))]);
+        assert_eq!(events.iter().filter(|e| e["ph"] == "M").count(), 2);
+        assert_eq!(trace_end_us(&merged).unwrap(), 1150);
+
+        assert!(merge_trace_json("{}", &second, 0, &none, &none, 1, None).is_err());
+        assert!(merge_trace_json(&first, &second, u32::MAX, &none, &none, 1, None).is_err());
+    }
+
+    #[test]
+    fn test_trace_annotate_markers() {
+        use crate::transport::{Frame, ReplayTransport};
+
+        let resp = AnnotateResponse {
+            status: TraceStatus::Ok as i32,
+            marker: 3,
+            timestamp_us: 1_250_000,
+        };
+        let frame = Frame {
+            msg_type: TraceMsgType::AnnotateRsp.as_u8(),
+            payload: resp.encode_to_vec(),
+        };
+        let mut transport = ReplayTransport::new(vec![frame]);
+        let annotation = trace_annotate(&mut transport, "tapped pad 2").unwrap();
+        assert_eq!((annotation.marker, annotation.timestamp_us), (3, 1_250_000));
+        let too_long = "x".repeat(ANNOTATION_LABEL_MAX_LEN + 1);
+        assert!(trace_annotate(&mut transport, &too_long).is_err());
+
+        // Labelled markers are named from the session's labels, others numbered
+        let labelled = TraceEvent {
+            flags: 0x80,
+            arg1: 99,
+            arg2: 3,
+            ..event(10)
+        };
+        let unlabelled = TraceEvent { arg1: 5, arg2: 4, ..labelled };
+        let span_names = HashMap::from([(99, "tapped pad 2".to_string())]);
+        let events = [labelled, unlabelled];
+        let json = convert_to_perfetto_json(&events, &HashMap::new(), &span_names, 0, None);
+        let events: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(events[0]["name"], "tapped pad 2");
+        assert_eq!(events[1]["name"], "marker #4");
+        assert_eq!(events[1]["cat"], "user");
+    }
+
+    #[test]
+    fn test_perfetto_json_escapes_task_names() {
+        let switch_in = TraceEvent {
+            timestamp: 5,
+            task_id: 9,
+            event_type: 0x01,
+            flags: 0,
+            arg1: 0,
+            arg2: 0,
+        };
+        let task_name = "led\"task\x01\\".to_string();
+        let task_names = HashMap::from([(9, task_name.clone())]);
+
+        let json = convert_to_perfetto_json(&[switch_in], &task_names, &HashMap::new(), 1, None);
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0]["name"], format!("task:{}", task_name));
+    }
+
+    #[test]
+    fn test_trace_dump_checksum_mismatch() {
+        use crate::transport::{Frame, ReplayTransport};
+
+        let event_bytes = vec![7u8; std::mem::size_of::<TraceEvent>()];
+        let dump = |checksum: u32| {
+            let session = TraceSessionInfo {
+                pod_id: 2,
+                event_count: 1,
+                ..Default::default()
+            };
+            let chunk = TraceDataChunk {
+                offset: 0,
+                count: 1,
+                events: event_bytes.clone(),
+            };
+            let end = TraceDumpComplete {
+                total_events: 1,
+                checksum,
+            };
+            ReplayTransport::new(vec![
+                Frame {
+                    msg_type: TraceMsgType::SessionInfo.as_u8(),
+                    payload: session.encode_to_vec(),
+                },
+                Frame {
+                    msg_type: TraceMsgType::Data.as_u8(),
+                    payload: chunk.encode_to_vec(),
+                },
+                Frame {
+                    msg_type: TraceMsgType::End.as_u8(),
+                    payload: end.encode_to_vec(),
+                },
+            ])
+        };
+        let good = crc32fast::hash(&event_bytes);
+        let path = std::env::temp_dir().join(format!("domes-dump-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let output = || TraceOutput::File(path.clone());
+        let strict = DumpOptions {
+            strict: true,
+            ..Default::default()
+        };
+
+        // Strict: the mismatch fails before the output file is created
+        let err = trace_dump(&mut dump(good ^ 1), output(), TraceFormat::Json, None, &strict)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            DomesError::Protocol { source: ProtocolError::ChecksumMismatch { .. }, .. }
+        ));
+        assert!(!path.exists());
+
+        // Otherwise the dump is written and flagged
+        let result =
+            trace_dump(&mut dump(good ^ 1), output(), TraceFormat::Json, None, &Default::default())
+                .unwrap();
+        assert!(!result.checksum_valid);
+        assert!(path.exists());
+
+        let result =
+            trace_dump(&mut dump(good), output(), TraceFormat::Json, None, &strict).unwrap();
+        assert!(result.checksum_valid);
+        assert_eq!(result.event_count, 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,274 @@
+//! WiFi subsystem commands
+
+use crate::error::{Context, DomesError, Result};
+use crate::proto::config::Feature;
+use crate::protocol::{
+    parse_wifi_ap_config_response, parse_wifi_ap_status_response,
+    parse_wifi_static_ip_response, serialize_wifi_ap_config, serialize_wifi_static_ip,
+    ConfigMsgType, WifiApConfig, WifiApStatus, WifiStaticIp,
+};
+use crate::transport::Transport;
+use std::net::Ipv4Addr;
+use std::ops::RangeInclusive;
+
+/// Longest SSID, in bytes
+pub const WIFI_AP_SSID_MAX_LEN: usize = 32;
+
+/// Shortest WPA2-PSK passphrase; ESP-IDF refuses to start an AP with a shorter one
+pub const WPA2_MIN_PASSWORD_LEN: usize = 8;
+
+/// Longest WPA2-PSK passphrase
+pub const WPA2_MAX_PASSWORD_LEN: usize = 63;
+
+/// 2.4 GHz channels an AP can use
+pub const WIFI_AP_CHANNELS: RangeInclusive<u8> = 1..=13;
+
+/// Stations an ESP32-S3 AP accepts at once
+pub const WIFI_AP_MAX_CONNECTIONS: RangeInclusive<u8> = 1..=10;
+
+/// Enable WiFi subsystem
+pub fn wifi_enable(transport: &mut dyn Transport) -> Result<bool> {
+    let state = super::feature_enable(transport, Feature::Wifi)?;
+    Ok(state.enabled)
+}
+
+/// Disable WiFi subsystem
+pub fn wifi_disable(transport: &mut dyn Transport) -> Result<bool> {
+    let state = super::feature_disable(transport, Feature::Wifi)?;
+    Ok(!state.enabled)
+}
+
+/// Get WiFi subsystem status
+pub fn wifi_status(transport: &mut dyn Transport) -> Result<bool> {
+    let features = super::feature_list(transport)?;
+    let wifi_state = features
+        .iter()
+        .find(|f| f.feature == Feature::Wifi)
+        .map(|f| f.enabled)
+        .unwrap_or(false);
+    Ok(wifi_state)
+}
+
+/// Check access point settings before sending them
+///
+/// An empty password makes an open network. A non-empty password shorter
+/// than `WPA2_MIN_PASSWORD_LEN` passes, so callers can warn about it instead.
+pub fn validate_ap_config(
+    ssid: &str,
+    password: &str,
+    channel: u8,
+    max_connections: u8,
+) -> Result<()> {
+    let invalid = |reason: String| Err(DomesError::InvalidArgument(reason));
+    if ssid.is_empty() || ssid.len() > WIFI_AP_SSID_MAX_LEN {
+        return invalid(format!(
+            "SSID must be 1-{} bytes (got {})",
+            WIFI_AP_SSID_MAX_LEN,
+            ssid.len()
+        ));
+    }
+    if password.len() > WPA2_MAX_PASSWORD_LEN {
+        return invalid(format!(
+            "Password must be at most {} characters (got {})",
+            WPA2_MAX_PASSWORD_LEN,
+            password.len()
+        ));
+    }
+    if !WIFI_AP_CHANNELS.contains(&channel) {
+        return invalid(format!(
+            "Channel must be {}-{} (got {})",
+            WIFI_AP_CHANNELS.start(),
+            WIFI_AP_CHANNELS.end(),
+            channel
+        ));
+    }
+    if !WIFI_AP_MAX_CONNECTIONS.contains(&max_connections) {
+        return invalid(format!(
+            "Max connections must be {}-{} (got {})",
+            WIFI_AP_MAX_CONNECTIONS.start(),
+            WIFI_AP_MAX_CONNECTIONS.end(),
+            max_connections
+        ));
+    }
+    Ok(())
+}
+
+/// Run WiFi as an access point (persisted to NVS)
+pub fn wifi_configure_ap(
+    transport: &mut dyn Transport,
+    ssid: &str,
+    password: &str,
+    channel: u8,
+    max_connections: u8,
+) -> Result<WifiApConfig> {
+    validate_ap_config(ssid, password, channel, max_connections)?;
+
+    let payload = serialize_wifi_ap_config(ssid, password, channel, max_connections);
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::WifiApConfigReq as u8,
+            &payload,
+            ConfigMsgType::WifiApConfigRsp as u8,
+        )
+        .context("Failed to send WiFi AP config command")?;
+
+    parse_wifi_ap_config_response(&frame.payload)
+        .context("Failed to parse WiFi AP config response")
+}
+
+/// Get access point state and the stations connected to it
+pub fn wifi_ap_status(transport: &mut dyn Transport) -> Result<WifiApStatus> {
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::WifiApStatusReq as u8,
+            &[],
+            ConfigMsgType::WifiApStatusRsp as u8,
+        )
+        .context("Failed to send WiFi AP status command")?;
+
+    parse_wifi_ap_status_response(&frame.payload)
+        .context("Failed to parse WiFi AP status response")
+}
+
+/// Check static IP settings before sending them
+///
+/// The netmask must be contiguous, and the gateway must be a different
+/// address on the same subnet as `ip`.
+pub fn validate_static_ip(ip: Ipv4Addr, netmask: Ipv4Addr, gateway: Ipv4Addr) -> Result<()> {
+    let invalid = |reason: String| Err(DomesError::InvalidArgument(reason));
+    let mask = u32::from(netmask);
+    if mask == 0 || mask.leading_ones() + mask.trailing_zeros() != 32 {
+        return invalid(format!("Invalid netmask {}", netmask));
+    }
+    if ip.is_unspecified() || ip.is_broadcast() {
+        return invalid(format!("Invalid IP address {}", ip));
+    }
+    if u32::from(ip) & mask != u32::from(gateway) & mask {
+        return invalid(format!(
+            "Gateway {} is not on the same subnet as {}/{}",
+            gateway,
+            ip,
+            mask.leading_ones()
+        ));
+    }
+    if ip == gateway {
+        return invalid(format!("IP address {} is the gateway address", ip));
+    }
+    Ok(())
+}
+
+/// Use a static IP in WiFi station mode instead of DHCP (persisted to NVS)
+pub fn wifi_set_static_ip(
+    transport: &mut dyn Transport,
+    ip: Ipv4Addr,
+    netmask: Ipv4Addr,
+    gateway: Ipv4Addr,
+    dns1: Ipv4Addr,
+    dns2: Option<Ipv4Addr>,
+) -> Result<()> {
+    validate_static_ip(ip, netmask, gateway)?;
+
+    let config = WifiStaticIp {
+        ip,
+        netmask,
+        gateway,
+        dns1,
+        dns2,
+    };
+    send_static_ip(transport, Some(&config))
+}
+
+/// Revert WiFi station mode to DHCP
+pub fn wifi_clear_static_ip(transport: &mut dyn Transport) -> Result<()> {
+    send_static_ip(transport, None)
+}
+
+fn send_static_ip(transport: &mut dyn Transport, config: Option<&WifiStaticIp>) -> Result<()> {
+    let payload = serialize_wifi_static_ip(config);
+    let frame = transport
+        .send_command_expect(
+            ConfigMsgType::WifiStaticIpReq as u8,
+            &payload,
+            ConfigMsgType::WifiStaticIpRsp as u8,
+        )
+        .context("Failed to send WiFi static IP command")?;
+
+    parse_wifi_static_ip_response(&frame.payload)
+        .context("Failed to parse WiFi static IP response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::config::WifiApConfigResponse;
+    use crate::transport::{Frame, ReplayTransport};
+    use prost::Message;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_configure_ap_validates_and_parses_gateway() {
+        assert!(validate_ap_config("DOMES-Field", "", 6, 4).is_ok());
+        assert!(validate_ap_config("DOMES-Field", "short", 6, 4).is_ok());
+        assert!(validate_ap_config("", "password", 6, 4).is_err());
+        assert!(validate_ap_config(&"s".repeat(33), "password", 6, 4).is_err());
+        assert!(validate_ap_config("DOMES-Field", &"p".repeat(64), 6, 4).is_err());
+        assert!(validate_ap_config("DOMES-Field", "password", 14, 4).is_err());
+        assert!(validate_ap_config("DOMES-Field", "password", 6, 11).is_err());
+
+        let mut payload = vec![0];
+        payload.extend(
+            WifiApConfigResponse {
+                ssid: "DOMES-Field".into(),
+                channel: 6,
+                max_connections: 4,
+                secured: true,
+                ip: u32::from(Ipv4Addr::new(192, 168, 4, 1)),
+            }
+            .encode_to_vec(),
+        );
+        let mut device = ReplayTransport::new(vec![Frame {
+            msg_type: ConfigMsgType::WifiApConfigRsp as u8,
+            payload,
+        }]);
+        let config = wifi_configure_ap(&mut device, "DOMES-Field", "password", 6, 4).unwrap();
+        assert_eq!(config.ip, Ipv4Addr::new(192, 168, 4, 1));
+        assert_eq!((config.channel, config.max_connections, config.secured), (6, 4, true));
+    }
+
+    #[test]
+    fn test_static_ip_checks_subnet_before_sending() {
+        let ip = Ipv4Addr::new(192, 168, 1, 50);
+        let mask = Ipv4Addr::new(255, 255, 255, 0);
+        let gateway = Ipv4Addr::new(192, 168, 1, 1);
+        assert!(validate_static_ip(ip, mask, gateway).is_ok());
+        assert!(validate_static_ip(ip, mask, Ipv4Addr::new(192, 168, 2, 1)).is_err());
+        assert!(validate_static_ip(ip, Ipv4Addr::new(255, 0, 255, 0), gateway).is_err());
+        assert!(validate_static_ip(ip, mask, ip).is_err());
+        assert!(validate_static_ip(Ipv4Addr::UNSPECIFIED, mask, gateway).is_err());
+
+        // Rejected before any transport I/O: no responses are queued
+        let mut transport = ReplayTransport::new(Vec::new());
+        let err = wifi_set_static_ip(
+            &mut transport,
+            ip,
+            mask,
+            Ipv4Addr::new(10, 0, 0, 1),
+            Ipv4Addr::new(8, 8, 8, 8),
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, DomesError::InvalidArgument(_)));
+
+        let config = WifiStaticIp {
+            ip,
+            netmask: mask,
+            gateway,
+            dns1: Ipv4Addr::new(8, 8, 8, 8),
+            dns2: None,
+        };
+        let payload = serialize_wifi_static_ip(Some(&config));
+        assert_eq!(&payload[..8], &[192, 168, 1, 50, 255, 255, 255, 0]);
+        assert_eq!(&payload[12..], &[8, 8, 8, 8, 0, 0, 0, 0]);
+        assert_eq!(serialize_wifi_static_ip(None), vec![0; 20]);
+    }
+}
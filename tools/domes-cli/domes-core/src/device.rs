@@ -0,0 +1,1414 @@
+//! Multi-device support for DOMES CLI
+//!
+//! Provides device targeting, registry, and multi-transport management.
+
+use crate::error::DomesError;
+use crate::transport::serial::COMMON_BAUD_RATES;
+use crate::transport::tcp::retry_refused;
+use crate::transport::{
+    is_common_baud_rate, BleTarget, BleTransport, DebugTransport, SerialConfig, SerialTransport,
+    TcpTransport, Transport,
+};
+use anyhow::{Context, Result};
+use fs4::fs_std::FileExt;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// A named device connection
+pub struct DeviceConnection {
+    pub name: String,
+    pub transport: Box<dyn Transport>,
+}
+
+/// Device registry entry from config file
+#[derive(Debug, Clone, Default)]
+pub struct DeviceEntry {
+    pub name: String,
+    pub transport_type: String,
+    pub address: String,
+    pub description: Option<String>,
+    pub timeout_ms: Option<u64>,
+    /// Serial baud rate (serial devices only)
+    pub baud: Option<u32>,
+    pub tags: Option<Vec<String>>,
+    /// Extra connection attempts after a failed one
+    pub connect_retries: Option<u32>,
+    /// How long to scan for the device (BLE devices only)
+    pub scan_timeout_secs: Option<u64>,
+}
+
+impl DeviceEntry {
+    /// Whether the entry carries every one of `tags` (true for none)
+    pub fn has_tags(&self, tags: &[String]) -> bool {
+        let own = self.tags.as_deref().unwrap_or_default();
+        tags.iter().all(|tag| own.contains(tag))
+    }
+
+    /// `<name> (<transport> @ <address>)`, with the description if it has one
+    pub fn connect_label(&self, name: &str) -> String {
+        match &self.description {
+            Some(description) => format!(
+                "{} \"{}\" ({} @ {})",
+                name, description, self.transport_type, self.address
+            ),
+            None => format!("{} ({} @ {})", name, self.transport_type, self.address),
+        }
+    }
+}
+
+/// On-disk layout of devices.toml
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct DevicesFile {
+    #[serde(default, serialize_with = "sorted_map")]
+    pub devices: HashMap<String, DeviceEntryToml>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "sorted_optional_map"
+    )]
+    pub groups: Option<HashMap<String, Vec<String>>>,
+}
+
+/// One `[devices.<name>]` table in devices.toml
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DeviceEntryToml {
+    pub transport: String,
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baud: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_retries: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scan_timeout_secs: Option<u64>,
+}
+
+/// Write maps in key order so the file is stable across saves
+fn sorted_map<S: Serializer, V: Serialize>(
+    map: &HashMap<String, V>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+fn sorted_optional_map<S: Serializer, V: Serialize>(
+    map: &Option<HashMap<String, V>>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match map {
+        Some(map) => sorted_map(map, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Contents of the device registry file
+#[derive(Debug, Clone, Default)]
+pub struct DeviceRegistry {
+    pub devices: HashMap<String, DeviceEntry>,
+    /// Group name -> member device names
+    pub groups: BTreeMap<String, Vec<String>>,
+}
+
+/// Load the device registry (devices and groups) from devices.toml
+///
+/// Format:
+/// ```toml
+/// [devices.pod1]
+/// transport = "serial"
+/// address = "/dev/ttyACM0"
+///
+/// [devices.pod2]
+/// transport = "serial"
+/// address = "/dev/ttyACM1"
+///
+/// [groups]
+/// living-room = ["pod1", "pod2"]
+/// ```
+pub fn load_registry() -> Result<DeviceRegistry> {
+    load_registry_at(&get_config_path())
+}
+
+/// Load the registered devices from devices.toml
+pub fn load_device_registry() -> Result<HashMap<String, DeviceEntry>> {
+    Ok(load_registry()?.devices)
+}
+
+/// Save a device entry to the registry
+pub fn save_device_entry(name: &str, entry: &DeviceEntry) -> Result<()> {
+    update_registry(&get_config_path(), |registry| {
+        registry.devices.insert(name.to_string(), entry.clone());
+        Ok(true)
+    })?;
+    Ok(())
+}
+
+/// Remove a device from the registry (and from any groups it belongs to)
+pub fn remove_device_entry(name: &str) -> Result<bool> {
+    let config_path = get_config_path();
+    if !config_path.exists() {
+        return Ok(false);
+    }
+
+    update_registry(&config_path, |registry| {
+        let removed = registry.devices.remove(name).is_some();
+        if removed {
+            for members in registry.groups.values_mut() {
+                members.retain(|member| member != name);
+            }
+        }
+        Ok(removed)
+    })
+}
+
+/// Create or replace a group of registered devices
+pub fn save_group(name: &str, members: &[String]) -> Result<()> {
+    update_registry(&get_config_path(), |registry| {
+        for member in members {
+            if !registry.devices.contains_key(member) {
+                anyhow::bail!("Device '{}' not found in registry", member);
+            }
+        }
+
+        let mut unique: Vec<String> = Vec::new();
+        for member in members {
+            if !unique.contains(member) {
+                unique.push(member.clone());
+            }
+        }
+        registry.groups.insert(name.to_string(), unique);
+        Ok(true)
+    })?;
+    Ok(())
+}
+
+/// Remove a group from the registry (its devices stay registered)
+pub fn remove_group(name: &str) -> Result<bool> {
+    update_registry(&get_config_path(), |registry| {
+        Ok(registry.groups.remove(name).is_some())
+    })
+}
+
+/// A device found by `devices scan`
+#[derive(Debug, Clone)]
+pub struct ScannedDevice {
+    pub transport_type: String,
+    pub address: String,
+    /// Pod ID probed over the connection (serial)
+    pub pod_id: Option<u32>,
+    /// Advertised name (BLE)
+    pub advertised_name: Option<String>,
+}
+
+/// What `devices scan --register` did with one scanned device
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanRegistration {
+    /// Registered under this name
+    Added(String),
+    /// The address is already registered under this name
+    AlreadyRegistered(String),
+}
+
+/// Register scanned devices that are not in the registry yet
+///
+/// Serial devices are named `<prefix>-<pod id>` (prefix defaults to `pod`),
+/// BLE devices by their advertised name, and devices without a pod ID
+/// `unknown-<N>`. Taken names get a numeric suffix.
+pub fn register_scanned(
+    found: &[ScannedDevice],
+    prefix: Option<&str>,
+) -> Result<Vec<ScanRegistration>> {
+    let mut outcomes = Vec::new();
+    update_registry(&get_config_path(), |registry| {
+        outcomes = assign_scanned_names(registry, found, prefix);
+        Ok(outcomes
+            .iter()
+            .any(|o| matches!(o, ScanRegistration::Added(_))))
+    })?;
+    Ok(outcomes)
+}
+
+fn assign_scanned_names(
+    registry: &mut DeviceRegistry,
+    found: &[ScannedDevice],
+    prefix: Option<&str>,
+) -> Vec<ScanRegistration> {
+    let mut next_unknown = 1;
+    found
+        .iter()
+        .map(|dev| {
+            if let Some(existing) = registry
+                .devices
+                .values()
+                .find(|e| e.transport_type == dev.transport_type && e.address == dev.address)
+            {
+                return ScanRegistration::AlreadyRegistered(existing.name.clone());
+            }
+
+            let base = match (&dev.advertised_name, dev.pod_id) {
+                (Some(name), _) if !name.is_empty() => name.clone(),
+                (_, Some(id)) if id > 0 => format!("{}-{}", prefix.unwrap_or("pod"), id),
+                _ => loop {
+                    let candidate = format!("unknown-{}", next_unknown);
+                    next_unknown += 1;
+                    if !registry.devices.contains_key(&candidate) {
+                        break candidate;
+                    }
+                },
+            };
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while registry.devices.contains_key(&name) {
+                name = format!("{}-{}", base, suffix);
+                suffix += 1;
+            }
+
+            registry.devices.insert(
+                name.clone(),
+                DeviceEntry {
+                    name: name.clone(),
+                    transport_type: dev.transport_type.clone(),
+                    address: dev.address.clone(),
+                    ..Default::default()
+                },
+            );
+            ScanRegistration::Added(name)
+        })
+        .collect()
+}
+
+/// How long to wait for the registry lock, in milliseconds
+static LOCK_TIMEOUT_MS: AtomicU64 = AtomicU64::new(5000);
+
+/// Set how long registry reads and writes wait for another process's lock
+pub fn set_registry_lock_timeout(timeout: Duration) {
+    LOCK_TIMEOUT_MS.store(timeout.as_millis() as u64, Ordering::Relaxed);
+}
+
+/// Lock `devices.lock` next to the registry file, waiting up to the lock timeout
+///
+/// Writers take an exclusive lock around the whole read-modify-write, readers
+/// a shared one. The lock is released when the returned file is dropped.
+fn lock_registry(config_path: &Path, exclusive: bool) -> Result<File> {
+    let lock_path = config_path.with_file_name("devices.lock");
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+
+    let timeout = Duration::from_millis(LOCK_TIMEOUT_MS.load(Ordering::Relaxed));
+    let start = Instant::now();
+    loop {
+        let locked = if exclusive {
+            FileExt::try_lock_exclusive(&file)
+        } else {
+            FileExt::try_lock_shared(&file)
+        }
+        .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+        if locked {
+            return Ok(file);
+        }
+        if start.elapsed() >= timeout {
+            anyhow::bail!(
+                "Timed out after {} ms waiting for {} (another domes-cli may be updating \
+                 the registry; see --registry-lock-timeout)",
+                timeout.as_millis(),
+                lock_path.display()
+            );
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+fn load_registry_at(config_path: &Path) -> Result<DeviceRegistry> {
+    if !config_path.exists() {
+        return Ok(DeviceRegistry::default());
+    }
+    let _lock = lock_registry(config_path, false)?;
+    read_registry_file(config_path)
+}
+
+/// Read, modify and write the registry under an exclusive lock
+///
+/// `modify` returns whether it changed anything; the file is only rewritten
+/// if it did. Returns that flag.
+fn update_registry(
+    config_path: &Path,
+    modify: impl FnOnce(&mut DeviceRegistry) -> Result<bool>,
+) -> Result<bool> {
+    let _lock = lock_registry(config_path, true)?;
+    let mut registry = read_registry_file(config_path)?;
+
+    let changed = modify(&mut registry)?;
+    if changed {
+        fs::write(config_path, serialize_devices_toml(&registry)?)
+            .with_context(|| format!("Failed to write {}", config_path.display()))?;
+    }
+    Ok(changed)
+}
+
+fn read_registry_file(config_path: &Path) -> Result<DeviceRegistry> {
+    if !config_path.exists() {
+        return Ok(DeviceRegistry::default());
+    }
+
+    let content = fs::read_to_string(config_path)
+        .with_context(|| format!("Failed to read {}", config_path.display()))?;
+
+    parse_devices_toml(&content)
+}
+
+/// Default BLE scan timeout when no connection timeout is configured
+const DEFAULT_BLE_SCAN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delay before the first `connect_retries` retry, doubling for each one after
+const CONNECT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Connect to a device by registry entry
+///
+/// The entry's `timeout_ms` and `baud` take precedence over `default_timeout`
+/// and `default_baud` (the `--timeout` and `--baud` flags); with neither,
+/// each transport uses its own default. Its `scan_timeout_secs` and
+/// `connect_retries` apply as well.
+pub fn connect_device(
+    entry: &DeviceEntry,
+    default_timeout: Option<Duration>,
+    default_baud: Option<u32>,
+) -> Result<Box<dyn Transport>> {
+    let timeout = entry.timeout_ms.map(Duration::from_millis).or(default_timeout);
+    if let Some(baud) = entry.baud {
+        warn_unusual_baud(baud, &format!("device '{}'", entry.name));
+    }
+    let baud = entry.baud.or(default_baud);
+    let scan_timeout = entry.scan_timeout_secs.map(Duration::from_secs);
+    let retries = entry.connect_retries.unwrap_or(0);
+    retry_connect(&entry.name, retries, CONNECT_RETRY_DELAY, || {
+        let (kind, address) = (&entry.transport_type, &entry.address);
+        open_transport(kind, address, timeout, scan_timeout, baud, None, None)
+    })
+}
+
+/// Run `connect`, trying again up to `retries` more times after a failure
+///
+/// The nth retry waits `base_delay * 2^(n-1)` first and is logged with the
+/// error that caused it.
+fn retry_connect<T>(
+    label: &str,
+    retries: u32,
+    base_delay: Duration,
+    mut connect: impl FnMut() -> Result<T>,
+) -> Result<T> {
+    let mut retry = 0;
+    loop {
+        match connect() {
+            Err(e) if retry < retries => {
+                retry += 1;
+                progress!("Connecting to {} failed ({:#}), retry {}/{}...", label, e, retry, retries);
+                std::thread::sleep(base_delay.saturating_mul(1 << (retry - 1).min(16)));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Warn about a baud rate boards are not usually run at (likely a typo)
+fn warn_unusual_baud(baud: u32, source: &str) {
+    if !is_common_baud_rate(baud) {
+        let common: Vec<String> = COMMON_BAUD_RATES.iter().map(u32::to_string).collect();
+        eprintln!(
+            "Warning: unusual baud rate {} for {} (common rates: {})",
+            baud,
+            source,
+            common.join(", ")
+        );
+    }
+}
+
+/// Open a transport of the given type
+///
+/// `timeout` is the serial read timeout, the TCP connect timeout, or the BLE
+/// scan timeout unless `scan_timeout` is given. `baud` only applies to
+/// serial ports, `retry` (attempts, first delay) to a refused TCP
+/// connection, and `rate_limit_kbps` to the TCP link once connected.
+///
+/// Whatever the device sent before the connection was opened (boot log,
+/// trace events, half a frame) is flushed, so the first command reads its
+/// own response.
+fn open_transport(
+    transport_type: &str,
+    address: &str,
+    timeout: Option<Duration>,
+    scan_timeout: Option<Duration>,
+    baud: Option<u32>,
+    retry: Option<(u32, Duration)>,
+    rate_limit_kbps: Option<u32>,
+) -> Result<Box<dyn Transport>> {
+    let mut transport: Box<dyn Transport> = match transport_type {
+        "serial" => {
+            let mut config = SerialConfig::default();
+            if let Some(timeout) = timeout {
+                config.timeout = timeout;
+            }
+            if let Some(baud) = baud {
+                config.baud_rate = baud;
+            }
+            Box::new(SerialTransport::open_with_config(address, &config)?)
+        }
+        "wifi" | "tcp" => {
+            let connect = || match timeout {
+                Some(timeout) => TcpTransport::connect_with_timeout(address, timeout),
+                None => TcpTransport::connect(address),
+            };
+            let mut transport = match retry {
+                Some((attempts, delay)) => retry_refused(address, attempts, delay, connect)?,
+                None => connect()?,
+            };
+            transport.set_rate_limit(rate_limit_kbps);
+            Box::new(transport)
+        }
+        "ble" => {
+            let target = BleTarget::parse(address);
+            let scan_timeout = scan_timeout.or(timeout).unwrap_or(DEFAULT_BLE_SCAN_TIMEOUT);
+            Box::new(BleTransport::connect(target, scan_timeout, true)?)
+        }
+        other => anyhow::bail!("Unknown transport type: {}", other),
+    };
+    transport
+        .flush()
+        .context("Failed to discard stale data from the device")?;
+    Ok(transport)
+}
+
+/// Deduplicate a list of addresses, warning on duplicates
+fn dedup_addresses(addrs: &[String], transport_label: &str) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    let mut result = Vec::new();
+    for addr in addrs {
+        if seen.insert(addr.clone()) {
+            result.push(addr.clone());
+        } else {
+            eprintln!(
+                "Warning: duplicate {} address '{}' ignored",
+                transport_label, addr
+            );
+        }
+    }
+    result
+}
+
+/// Expand --port-glob patterns into serial port paths
+///
+/// Each pattern is matched against the filesystem (e.g. `/dev/ttyACM*`,
+/// `/dev/domes-pod-*` udev symlinks) and against the ports reported by
+/// `SerialTransport::list_ports`, so it also works where ports are not files.
+/// The result is sorted and free of duplicates.
+pub fn expand_port_globs(patterns: &[String]) -> Result<Vec<String>> {
+    if patterns.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Enumeration failing just means there is nothing extra to match
+    let available = SerialTransport::list_ports().unwrap_or_default();
+    let mut matches = std::collections::BTreeSet::new();
+
+    for pattern in patterns {
+        let paths = glob::glob(pattern)
+            .with_context(|| format!("Invalid port glob '{}'", pattern))?;
+        for path in paths.flatten() {
+            matches.insert(path.to_string_lossy().into_owned());
+        }
+
+        let compiled = glob::Pattern::new(pattern)
+            .with_context(|| format!("Invalid port glob '{}'", pattern))?;
+        for port in &available {
+            if compiled.matches(port) {
+                matches.insert(port.clone());
+            }
+        }
+    }
+
+    Ok(matches.into_iter().collect())
+}
+
+/// Device label for a port matched by --port-glob (its file name)
+fn port_label(port: &str) -> String {
+    std::path::Path::new(port)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| port.to_string())
+}
+
+/// Which devices to connect to, from the CLI targeting flags
+#[derive(Debug, Clone, Default)]
+pub struct DeviceSelection {
+    /// --port
+    pub ports: Vec<String>,
+    /// --port-glob
+    pub port_globs: Vec<String>,
+    /// --wifi
+    pub wifis: Vec<String>,
+    /// --ble
+    pub bles: Vec<String>,
+    /// --target
+    pub targets: Vec<String>,
+    /// --group
+    pub groups: Vec<String>,
+    /// --all
+    pub all: bool,
+    /// --timeout (or its default from the environment or config file), used
+    /// when a registry entry has no `timeout_ms`
+    pub timeout: Option<Duration>,
+    /// Whether `timeout` was given on the command line, overriding registry
+    /// `timeout_ms` too
+    pub timeout_from_flag: bool,
+    /// --connect-retries: extra attempts after a failed connection,
+    /// overriding registry `connect_retries`
+    pub connect_retries: Option<u32>,
+    /// --scan-timeout-secs: how long to scan for a BLE device, overriding
+    /// registry `scan_timeout_secs`
+    pub scan_timeout: Option<Duration>,
+    /// --baud, used when a registry entry has no `baud`
+    pub baud: Option<u32>,
+    /// Number of `-v` flags: 1 logs frames and connection events, 2 also
+    /// hex-dumps each frame
+    pub verbosity: u8,
+    /// --connect-timeout-ms: how long to wait for each device to connect
+    pub connect_timeout: Option<Duration>,
+    /// --fail-fast: a device that fails to connect is an error rather than
+    /// a warning
+    pub fail_fast: bool,
+    /// --wifi-retry and --wifi-retry-delay-ms: attempts and first backoff
+    /// delay for a WiFi device that refuses the connection
+    pub wifi_retry: Option<(u32, Duration)>,
+    /// --tcp-rate-limit: pace WiFi traffic to this many kbps each way
+    pub tcp_rate_limit: Option<u32>,
+}
+
+/// Devices `resolve_devices` connected to, and the ones it left out
+pub struct ResolvedDevices {
+    pub connections: Vec<DeviceConnection>,
+    /// Devices that failed to connect (name, or address if unnamed); each
+    /// was reported as a warning
+    pub unreachable: Vec<String>,
+}
+
+/// Resolve CLI arguments into device connections
+///
+/// Priority:
+/// 1. --target / --group names (look up in registry)
+/// 2. --port / --port-glob / --wifi / --ble (direct connections)
+/// 3. If --all, connect to all registry devices
+///
+/// With more than one device they are connected concurrently. A device that
+/// fails to connect is reported as a warning and left out, unless
+/// `fail_fast` is set or it is the only one; failing to connect to every
+/// device is an error. Ports matched by --port-glob that fail to open are
+/// skipped without counting as unreachable.
+///
+/// With `verbosity` set, each transport is wrapped in a `DebugTransport`.
+pub fn resolve_devices(selection: &DeviceSelection) -> Result<ResolvedDevices> {
+    let pending = pending_connections(selection)?;
+    let count = pending.len();
+    let results = connect_all(&pending, selection.connect_timeout);
+
+    let mut resolved = ResolvedDevices {
+        connections: Vec::new(),
+        unreachable: Vec::new(),
+    };
+    let mut last_error = None;
+    for (device, result) in pending.into_iter().zip(results) {
+        match result {
+            Ok(transport) => resolved.connections.push(DeviceConnection {
+                transport: debug_wrap(transport, &device.address, selection.verbosity),
+                name: device.name,
+            }),
+            Err(e) if device.optional => {
+                eprintln!("Warning: skipping {}: {:#}", device.address, e)
+            }
+            Err(e) if count == 1 || selection.fail_fast => return Err(e),
+            Err(e) => {
+                eprintln!("Warning: {:#}; continuing without it", e);
+                resolved.unreachable.push(device.label());
+                last_error = Some(e);
+            }
+        }
+    }
+    if let (true, Some(e)) = (resolved.connections.is_empty(), last_error) {
+        let count = resolved.unreachable.len();
+        return Err(e.context(format!("Could not connect to any of the {} devices", count)));
+    }
+    Ok(resolved)
+}
+
+/// One device `resolve_devices` is about to connect to
+#[derive(Clone)]
+struct PendingConnection {
+    /// Output label; empty for a lone --port/--wifi/--ble device
+    name: String,
+    transport_type: &'static str,
+    address: String,
+    timeout: Option<Duration>,
+    /// BLE scan timeout, if not `timeout`
+    scan_timeout: Option<Duration>,
+    baud: Option<u32>,
+    /// Extra attempts after a failed connection
+    connect_retries: u32,
+    /// Context for a failure, e.g. "Failed to connect to pod1"
+    context: Option<String>,
+    /// A --port-glob match: a failure only skips it
+    optional: bool,
+    /// Attempts and first backoff delay while a TCP connection is refused
+    retry: Option<(u32, Duration)>,
+    /// Kilobits per second to pace a TCP link to
+    rate_limit_kbps: Option<u32>,
+}
+
+impl PendingConnection {
+    fn label(&self) -> String {
+        if self.name.is_empty() {
+            self.address.clone()
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// A failure to connect, as a `DomesError::Transport`
+    fn connect_error(&self, error: anyhow::Error) -> anyhow::Error {
+        let context = self
+            .context
+            .clone()
+            .unwrap_or_else(|| format!("Failed to connect to {}", self.address));
+        DomesError::Transport { context, source: error.into() }.into()
+    }
+}
+
+/// The devices selected by the CLI flags, in connection order, each with a
+/// progress line printed
+fn pending_connections(selection: &DeviceSelection) -> Result<Vec<PendingConnection>> {
+    let timeout = selection.timeout;
+    let baud = selection.baud;
+    if let Some(baud) = baud {
+        warn_unusual_baud(baud, "--baud");
+    }
+    let mut pending = Vec::new();
+    let from_registry = |name: &str, entry: &DeviceEntry| {
+        progress!("Connecting to {}...", entry.connect_label(name));
+        if let Some(baud) = entry.baud {
+            warn_unusual_baud(baud, &format!("device '{}'", name));
+        }
+        let transport_type = match entry.transport_type.as_str() {
+            "serial" => "serial",
+            "wifi" | "tcp" => "wifi",
+            "ble" => "ble",
+            other => anyhow::bail!("Unknown transport type: {}", other),
+        };
+        let settings = registry_settings(entry, selection);
+        Ok(PendingConnection {
+            name: name.to_string(),
+            transport_type,
+            address: entry.address.clone(),
+            timeout: settings.timeout,
+            scan_timeout: settings.scan_timeout,
+            baud: entry.baud.or(baud),
+            connect_retries: settings.connect_retries,
+            context: Some(format!("Failed to connect to {}", name)),
+            optional: false,
+            retry: if transport_type == "wifi" { selection.wifi_retry } else { None },
+            rate_limit_kbps: if transport_type == "wifi" { selection.tcp_rate_limit } else { None },
+        })
+    };
+
+    // Deduplicate addresses to prevent double-open corruption
+    let ports = dedup_addresses(&selection.ports, "serial");
+    let wifis = dedup_addresses(&selection.wifis, "wifi");
+    let bles = dedup_addresses(&selection.bles, "ble");
+    let port_globs = &selection.port_globs;
+    let globbed: Vec<String> = expand_port_globs(port_globs)?
+        .into_iter()
+        .filter(|port| !ports.contains(port))
+        .collect();
+    if !port_globs.is_empty() && globbed.is_empty() {
+        eprintln!("Warning: no serial ports match {}", port_globs.join(", "));
+    }
+
+    // If --all, load entire registry
+    if selection.all {
+        let registry = load_device_registry()?;
+        if registry.is_empty() {
+            anyhow::bail!("No devices in registry. Use 'devices add' to register devices.");
+        }
+        let mut names: Vec<&String> = registry.keys().collect();
+        names.sort();
+        for name in names {
+            pending.push(from_registry(name, &registry[name])?);
+        }
+        return Ok(pending);
+    }
+
+    // If --target or --group, look up in registry
+    let targets = if selection.groups.is_empty() {
+        selection.targets.clone()
+    } else {
+        expand_groups(&load_registry()?, &selection.targets, &selection.groups)?
+    };
+    if !targets.is_empty() {
+        let registry = load_device_registry()?;
+        for target_name in &targets {
+            let entry = registry
+                .get(target_name)
+                .with_context(|| format!("Device '{}' not found in registry", target_name))?;
+            pending.push(from_registry(target_name, entry)?);
+        }
+    }
+
+    // Direct connections via --port
+    for (i, port) in ports.iter().enumerate() {
+        let name = if ports.len() == 1
+            && globbed.is_empty()
+            && wifis.is_empty()
+            && bles.is_empty()
+            && targets.is_empty()
+        {
+            // Single device, no label needed (backward compat)
+            String::new()
+        } else {
+            format!("serial-{}", i)
+        };
+        pending.push(PendingConnection {
+            name,
+            transport_type: "serial",
+            address: port.clone(),
+            timeout,
+            scan_timeout: selection.scan_timeout,
+            baud,
+            connect_retries: selection.connect_retries.unwrap_or(0),
+            context: None,
+            optional: false,
+            retry: None,
+            rate_limit_kbps: None,
+        });
+    }
+
+    // Serial ports matched by --port-glob
+    for port in &globbed {
+        progress!("Opening {}...", port);
+        pending.push(PendingConnection {
+            name: port_label(port),
+            transport_type: "serial",
+            address: port.clone(),
+            timeout,
+            scan_timeout: selection.scan_timeout,
+            baud,
+            connect_retries: selection.connect_retries.unwrap_or(0),
+            context: None,
+            optional: true,
+            retry: None,
+            rate_limit_kbps: None,
+        });
+    }
+
+    // Direct connections via --wifi
+    for (i, addr) in wifis.iter().enumerate() {
+        let name = if wifis.len() == 1
+            && ports.is_empty()
+            && globbed.is_empty()
+            && bles.is_empty()
+            && targets.is_empty()
+        {
+            String::new()
+        } else {
+            format!("wifi-{}", i)
+        };
+        progress!("Connecting to {} via WiFi...", addr);
+        pending.push(PendingConnection {
+            name,
+            transport_type: "wifi",
+            address: addr.clone(),
+            timeout,
+            scan_timeout: selection.scan_timeout,
+            baud: None,
+            connect_retries: selection.connect_retries.unwrap_or(0),
+            context: None,
+            optional: false,
+            retry: selection.wifi_retry,
+            rate_limit_kbps: selection.tcp_rate_limit,
+        });
+    }
+
+    // Direct connections via --ble
+    for (i, ble_target) in bles.iter().enumerate() {
+        let name = if bles.len() == 1
+            && ports.is_empty()
+            && globbed.is_empty()
+            && wifis.is_empty()
+            && targets.is_empty()
+        {
+            String::new()
+        } else {
+            format!("ble-{}", i)
+        };
+        progress!("Scanning for BLE device '{}'...", ble_target);
+        pending.push(PendingConnection {
+            name,
+            transport_type: "ble",
+            address: ble_target.clone(),
+            timeout,
+            scan_timeout: selection.scan_timeout,
+            baud: None,
+            connect_retries: selection.connect_retries.unwrap_or(0),
+            context: None,
+            optional: false,
+            retry: None,
+            rate_limit_kbps: None,
+        });
+    }
+
+    Ok(pending)
+}
+
+/// How to connect to a registry device
+#[derive(Debug, PartialEq)]
+struct RegistrySettings {
+    timeout: Option<Duration>,
+    scan_timeout: Option<Duration>,
+    connect_retries: u32,
+}
+
+/// Settings for connecting to `entry`: flags given on the command line win
+/// over the entry's own settings, which win over defaults from the
+/// environment or config file
+fn registry_settings(entry: &DeviceEntry, selection: &DeviceSelection) -> RegistrySettings {
+    let entry_timeout = entry.timeout_ms.map(Duration::from_millis);
+    let timeout = if selection.timeout_from_flag {
+        selection.timeout.or(entry_timeout)
+    } else {
+        entry_timeout.or(selection.timeout)
+    };
+    RegistrySettings {
+        timeout,
+        scan_timeout: selection
+            .scan_timeout
+            .or(entry.scan_timeout_secs.map(Duration::from_secs)),
+        connect_retries: selection.connect_retries.or(entry.connect_retries).unwrap_or(0),
+    }
+}
+
+/// Open every pending connection, one thread per device when there are
+/// several; results are in the order of `pending`
+///
+/// With `connect_timeout`, a device still connecting when it runs out fails
+/// (its thread is left to finish and the late connection is dropped).
+fn connect_all(
+    pending: &[PendingConnection],
+    connect_timeout: Option<Duration>,
+) -> Vec<Result<Box<dyn Transport>>> {
+    let open = |device: &PendingConnection| {
+        retry_connect(&device.label(), device.connect_retries, CONNECT_RETRY_DELAY, || {
+            open_transport(
+                device.transport_type,
+                &device.address,
+                device.timeout,
+                device.scan_timeout,
+                device.baud,
+                device.retry,
+                device.rate_limit_kbps,
+            )
+        })
+        .map_err(|e| device.connect_error(e))
+    };
+    if pending.len() == 1 && connect_timeout.is_none() {
+        return vec![open(&pending[0])];
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    for (i, device) in pending.iter().enumerate() {
+        let device = device.clone();
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            let result = open(&device);
+            // The receiver is gone if this device ran out of time
+            let _ = tx.send((i, result));
+        });
+    }
+    drop(tx);
+
+    let mut results: Vec<Option<Result<Box<dyn Transport>>>> =
+        pending.iter().map(|_| None).collect();
+    let deadline = connect_timeout.map(|timeout| Instant::now() + timeout);
+    for _ in 0..pending.len() {
+        let received = match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                rx.recv_timeout(remaining).ok()
+            }
+            None => rx.recv().ok(),
+        };
+        match received {
+            Some((i, result)) => results[i] = Some(result),
+            None => break,
+        }
+    }
+    results
+        .into_iter()
+        .zip(pending)
+        .map(|(result, device)| {
+            result.unwrap_or_else(|| {
+                let ms = connect_timeout.map_or(0, |timeout| timeout.as_millis());
+                Err(device.connect_error(anyhow::anyhow!(
+                    "No connection within {} ms (--connect-timeout-ms)",
+                    ms
+                )))
+            })
+        })
+        .collect()
+}
+
+/// Wrap `transport` for `-v`/`-vv` logging; unchanged at verbosity 0
+fn debug_wrap(transport: Box<dyn Transport>, address: &str, verbosity: u8) -> Box<dyn Transport> {
+    if verbosity == 0 {
+        return transport;
+    }
+    Box::new(DebugTransport::connected(transport, address).hex_dump(verbosity >= 2))
+}
+
+/// Combine --target names with the members of each --group, without duplicates
+fn expand_groups(
+    registry: &DeviceRegistry,
+    targets: &[String],
+    groups: &[String],
+) -> Result<Vec<String>> {
+    let mut names = dedup_addresses(targets, "target");
+    for group in groups {
+        let members = registry
+            .groups
+            .get(group)
+            .with_context(|| format!("Group '{}' not found in registry", group))?;
+        if members.is_empty() {
+            eprintln!("Warning: group '{}' has no devices", group);
+        }
+        for member in members {
+            if !names.contains(member) {
+                names.push(member.clone());
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// Set by the CLI when stdout is a terminal and color is allowed
+static PREFIX_COLOR: AtomicBool = AtomicBool::new(false);
+
+/// Readable ANSI foreground colors: cyan, green, yellow, magenta, blue, red
+const PREFIX_PALETTE: [u8; 6] = [36, 32, 33, 35, 34, 31];
+
+/// Color device prefixes (off by default)
+pub fn set_prefix_color(enabled: bool) {
+    PREFIX_COLOR.store(enabled, Ordering::Relaxed);
+}
+
+/// ANSI color code for `name`; the same name always gets the same color
+pub fn device_color(name: &str) -> u8 {
+    let hash = name.bytes().fold(0u8, |acc, b| acc ^ b);
+    PREFIX_PALETTE[hash as usize % PREFIX_PALETTE.len()]
+}
+
+/// Format a device label prefix for output, colored if enabled
+pub fn device_prefix(name: &str) -> String {
+    format_prefix(name, PREFIX_COLOR.load(Ordering::Relaxed))
+}
+
+fn format_prefix(name: &str, color: bool) -> String {
+    if name.is_empty() {
+        String::new()
+    } else if color {
+        format!("\x1b[{}m[{}]\x1b[0m ", device_color(name), name)
+    } else {
+        format!("[{}] ", name)
+    }
+}
+
+fn get_config_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".domes").join("devices.toml")
+}
+
+fn parse_devices_toml(content: &str) -> Result<DeviceRegistry> {
+    let file: DevicesFile = toml::from_str(content).context("Invalid device registry")?;
+
+    let devices = file
+        .devices
+        .into_iter()
+        .map(|(name, entry)| {
+            let entry = DeviceEntry {
+                name: name.clone(),
+                transport_type: entry.transport,
+                address: entry.address,
+                description: entry.description,
+                timeout_ms: entry.timeout_ms,
+                baud: entry.baud,
+                tags: entry.tags,
+                connect_retries: entry.connect_retries,
+                scan_timeout_secs: entry.scan_timeout_secs,
+            };
+            (name, entry)
+        })
+        .collect();
+    let groups = file.groups.unwrap_or_default().into_iter().collect();
+
+    Ok(DeviceRegistry { devices, groups })
+}
+
+fn serialize_devices_toml(registry: &DeviceRegistry) -> Result<String> {
+    let file = DevicesFile {
+        devices: registry
+            .devices
+            .iter()
+            .map(|(name, entry)| {
+                let entry = DeviceEntryToml {
+                    transport: entry.transport_type.clone(),
+                    address: entry.address.clone(),
+                    description: entry.description.clone(),
+                    timeout_ms: entry.timeout_ms,
+                    baud: entry.baud,
+                    tags: entry.tags.clone(),
+                    connect_retries: entry.connect_retries,
+                    scan_timeout_secs: entry.scan_timeout_secs,
+                };
+                (name.clone(), entry)
+            })
+            .collect(),
+        groups: if registry.groups.is_empty() {
+            None
+        } else {
+            Some(registry.groups.clone().into_iter().collect())
+        },
+    };
+
+    let body = toml::to_string_pretty(&file).context("Failed to serialize device registry")?;
+    Ok(format!(
+        "# DOMES device registry\n# Managed by: domes-cli devices add/remove\n\n{}",
+        body
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_prefix_color() {
+        assert_eq!(format_prefix("pod-1", false), "[pod-1] ");
+        assert_eq!(format_prefix("", true), "");
+        let color = device_color("pod-1");
+        assert_eq!(color, device_color("pod-1"));
+        assert!(PREFIX_PALETTE.contains(&color));
+        assert_eq!(format_prefix("pod-1", true), format!("\x1b[{}m[pod-1]\x1b[0m ", color));
+        assert_ne!(device_color("pod-1"), device_color("pod-2"));
+    }
+
+    #[test]
+    fn test_resolve_devices_skips_unreachable_unless_fail_fast() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let live = listener.local_addr().unwrap().to_string();
+        // Bound then dropped: nothing listens there any more
+        let dead = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .to_string();
+        let mut selection = DeviceSelection {
+            wifis: vec![live.clone(), dead.clone()],
+            timeout: Some(Duration::from_secs(1)),
+            ..Default::default()
+        };
+
+        let resolved = resolve_devices(&selection).unwrap();
+        let names: Vec<&str> = resolved.connections.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["wifi-0"]);
+        assert_eq!(resolved.unreachable, ["wifi-1"]);
+
+        selection.fail_fast = true;
+        assert!(resolve_devices(&selection).is_err());
+
+        selection.fail_fast = false;
+        selection.wifis = vec![dead];
+        assert!(resolve_devices(&selection).is_err());
+    }
+
+    #[test]
+    fn test_command_line_overrides_registry_settings() {
+        let weak_link = DeviceEntry {
+            timeout_ms: Some(8000),
+            connect_retries: Some(3),
+            scan_timeout_secs: Some(30),
+            ..Default::default()
+        };
+        let config_default = DeviceSelection {
+            timeout: Some(Duration::from_millis(2000)),
+            ..Default::default()
+        };
+        assert_eq!(
+            registry_settings(&weak_link, &config_default),
+            RegistrySettings {
+                timeout: Some(Duration::from_millis(8000)),
+                scan_timeout: Some(Duration::from_secs(30)),
+                connect_retries: 3,
+            }
+        );
+        let unset = registry_settings(&DeviceEntry::default(), &config_default);
+        assert_eq!(unset.timeout, Some(Duration::from_millis(2000)));
+
+        let flags = DeviceSelection {
+            timeout_from_flag: true,
+            connect_retries: Some(0),
+            scan_timeout: Some(Duration::from_secs(5)),
+            ..config_default
+        };
+        assert_eq!(
+            registry_settings(&weak_link, &flags),
+            RegistrySettings {
+                timeout: Some(Duration::from_millis(2000)),
+                scan_timeout: Some(Duration::from_secs(5)),
+                connect_retries: 0,
+            }
+        );
+
+        let mut attempts = 0;
+        let result = retry_connect("pod1", 2, Duration::ZERO, || {
+            attempts += 1;
+            if attempts < 3 {
+                anyhow::bail!("not found")
+            }
+            Ok(attempts)
+        });
+        assert_eq!(result.unwrap(), 3);
+        let result: Result<()> =
+            retry_connect("pod1", 1, Duration::ZERO, || anyhow::bail!("not found"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_expand_port_globs() {
+        let dir = std::env::temp_dir().join(format!("domes-port-glob-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["domes-pod-2", "domes-pod-1", "other"] {
+            fs::write(dir.join(name), b"").unwrap();
+        }
+        let pattern = format!("{}/domes-pod-*", dir.display());
+
+        // The same pattern twice must not produce duplicates
+        let ports = expand_port_globs(&[pattern.clone(), pattern]).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<String> = ports.iter().map(|p| port_label(p)).collect();
+        assert_eq!(names, ["domes-pod-1", "domes-pod-2"]);
+    }
+
+    #[test]
+    fn test_registry_groups_round_trip() {
+        let content = r#"
+[devices.pod1]
+transport = "serial"
+address = "/dev/ttyACM0"
+
+[groups]
+living-room = ["pod1", "pod2"]
+empty = []
+
+[devices.pod2]
+transport = "wifi"
+address = "192.168.1.100:5000"
+"#;
+        let registry = parse_devices_toml(content).unwrap();
+        assert_eq!(registry.devices.len(), 2);
+        assert_eq!(registry.devices["pod2"].transport_type, "wifi");
+        assert_eq!(registry.groups["living-room"], ["pod1", "pod2"]);
+        assert!(registry.groups["empty"].is_empty());
+
+        let reparsed = parse_devices_toml(&serialize_devices_toml(&registry).unwrap()).unwrap();
+        assert_eq!(reparsed.devices.len(), 2);
+        assert_eq!(reparsed.groups, registry.groups);
+    }
+
+    #[test]
+    fn test_registry_round_trip_unicode_and_quotes() {
+        let mut registry = DeviceRegistry::default();
+        let name = "küche \"pod\" = 1";
+        registry.devices.insert(
+            name.to_string(),
+            DeviceEntry {
+                name: name.to_string(),
+                transport_type: "ble".into(),
+                address: "DOMES-Pod-\"01\"".into(),
+                description: Some("Shelf by the window, a=b # not a comment".into()),
+                timeout_ms: Some(2500),
+                baud: Some(921600),
+                tags: Some(vec!["kitchen".into(), "日本".into()]),
+                connect_retries: Some(3),
+                scan_timeout_secs: Some(30),
+            },
+        );
+        registry
+            .groups
+            .insert("wohnzimmer é".into(), vec![name.to_string()]);
+
+        let text = serialize_devices_toml(&registry).unwrap();
+        let reparsed = parse_devices_toml(&text).unwrap();
+        let entry = &reparsed.devices[name];
+        assert_eq!(entry.name, name);
+        assert_eq!(entry.address, "DOMES-Pod-\"01\"");
+        assert_eq!(
+            entry.description.as_deref(),
+            Some("Shelf by the window, a=b # not a comment")
+        );
+        assert_eq!(entry.timeout_ms, Some(2500));
+        assert_eq!(entry.baud, Some(921600));
+        assert_eq!((entry.connect_retries, entry.scan_timeout_secs), (Some(3), Some(30)));
+        assert_eq!(entry.tags.as_deref(), Some(&["kitchen".to_string(), "日本".to_string()][..]));
+        assert_eq!(reparsed.groups, registry.groups);
+    }
+
+    #[test]
+    fn test_registry_multiline_values() {
+        let content = r#"
+# comment before any table
+[devices.pod1] # trailing comment
+transport = "serial"
+address = "/dev/ttyACM0"
+tags = [
+    "bench",   # inline comment
+    "rev-b",
+]
+
+[groups]
+all = [
+    "pod1",
+]
+"#;
+        let registry = parse_devices_toml(content).unwrap();
+        assert_eq!(
+            registry.devices["pod1"].tags.as_deref(),
+            Some(&["bench".to_string(), "rev-b".to_string()][..])
+        );
+        let pod1 = &registry.devices["pod1"];
+        assert!(pod1.has_tags(&[]) && pod1.has_tags(&["rev-b".into(), "bench".into()]));
+        assert!(!pod1.has_tags(&["bench".into(), "outdoor".into()]));
+        assert_eq!(registry.groups["all"], ["pod1"]);
+        assert!(parse_devices_toml("[devices.pod1]\ntransport = ").is_err());
+    }
+
+    #[test]
+    fn test_expand_groups() {
+        let mut registry = DeviceRegistry::default();
+        registry
+            .groups
+            .insert("kitchen".into(), vec!["pod2".into(), "pod3".into()]);
+
+        let names = expand_groups(&registry, &["pod2".into()], &["kitchen".into()]).unwrap();
+        assert_eq!(names, ["pod2", "pod3"]);
+        assert!(expand_groups(&registry, &[], &["garage".into()]).is_err());
+    }
+
+    #[test]
+    fn test_concurrent_registry_writes() {
+        let dir = std::env::temp_dir().join(format!("domes-registry-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("devices.toml");
+
+        std::thread::scope(|s| {
+            for writer in ["a", "b"] {
+                let path = &path;
+                s.spawn(move || {
+                    for i in 0..25 {
+                        let name = format!("{}-{}", writer, i);
+                        update_registry(path, |registry| {
+                            let entry = DeviceEntry {
+                                name: name.clone(),
+                                transport_type: "serial".into(),
+                                address: format!("/dev/ttyACM{}", i),
+                                ..Default::default()
+                            };
+                            registry.devices.insert(name.clone(), entry);
+                            Ok(true)
+                        })
+                        .unwrap();
+                    }
+                });
+            }
+        });
+
+        let registry = load_registry_at(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(registry.devices.len(), 50);
+        assert_eq!(registry.devices["b-24"].address, "/dev/ttyACM24");
+    }
+
+    #[test]
+    fn test_assign_scanned_names() {
+        let serial = |address: &str, pod_id: u32| ScannedDevice {
+            transport_type: "serial".into(),
+            address: address.into(),
+            pod_id: Some(pod_id),
+            advertised_name: None,
+        };
+        let mut registry = DeviceRegistry::default();
+        registry.devices.insert(
+            "bench".into(),
+            DeviceEntry {
+                name: "bench".into(),
+                transport_type: "serial".into(),
+                address: "/dev/ttyACM0".into(),
+                ..Default::default()
+            },
+        );
+
+        let found = [
+            serial("/dev/ttyACM0", 1),
+            serial("/dev/ttyACM1", 3),
+            serial("/dev/ttyACM2", 3),
+            serial("/dev/ttyACM3", 0),
+            serial("/dev/ttyACM4", 0),
+            ScannedDevice {
+                transport_type: "ble".into(),
+                address: "AA:BB:CC:DD:EE:FF".into(),
+                pod_id: None,
+                advertised_name: Some("DOMES-Pod-07".into()),
+            },
+        ];
+        let outcomes = assign_scanned_names(&mut registry, &found, None);
+        assert_eq!(
+            outcomes,
+            [
+                ScanRegistration::AlreadyRegistered("bench".into()),
+                ScanRegistration::Added("pod-3".into()),
+                ScanRegistration::Added("pod-3-2".into()),
+                ScanRegistration::Added("unknown-1".into()),
+                ScanRegistration::Added("unknown-2".into()),
+                ScanRegistration::Added("DOMES-Pod-07".into()),
+            ]
+        );
+        assert_eq!(registry.devices["pod-3-2"].address, "/dev/ttyACM2");
+
+        let found = [serial("/dev/ttyUSB0", 9)];
+        let outcomes = assign_scanned_names(&mut registry, &found, Some("lab"));
+        assert_eq!(outcomes, [ScanRegistration::Added("lab-9".into())]);
+    }
+
+    #[test]
+    fn test_expand_port_globs_invalid_pattern() {
+        assert!(expand_port_globs(&["/dev/tty[".to_string()]).is_err());
+    }
+}
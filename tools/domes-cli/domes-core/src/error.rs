@@ -0,0 +1,208 @@
+//! Error type returned by the command API
+//!
+//! Transports report failures as `anyhow::Error`; the command functions
+//! classify them into `DomesError` so callers can match on what went wrong
+//! (e.g. retry only on `Timeout`) instead of inspecting message strings.
+//! `DomesError` implements `std::error::Error`, so `?` still converts it
+//! into `anyhow::Error`.
+
+use crate::commands::ota::OtaStatus;
+use crate::proto::config::Status;
+use crate::protocol::ProtocolError;
+use crate::transport::{ResponseTimeout, UnexpectedFrame};
+use thiserror::Error;
+
+/// Result type of the command API
+pub type Result<T, E = DomesError> = std::result::Result<T, E>;
+
+/// Boxed transport error, keeping its `anyhow` context chain as sources
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Why a command failed
+#[derive(Debug, Error)]
+pub enum DomesError {
+    /// Sending or receiving a frame failed (port closed, I/O error, ...)
+    #[error("{context}")]
+    Transport {
+        context: String,
+        #[source]
+        source: BoxError,
+    },
+
+    /// The device did not answer within the timeout
+    #[error("{context}")]
+    Timeout {
+        context: String,
+        #[source]
+        source: BoxError,
+    },
+
+    /// The device answered with an error status
+    #[error("Device returned error: {0:?}")]
+    Status(Status),
+
+    /// The device refused the request (trace reports its own status codes)
+    #[error("{0}")]
+    Rejected(String),
+
+    /// The device refused or aborted an OTA update at byte `offset` of the image
+    #[error("{message}")]
+    OtaRejected {
+        status: OtaStatus,
+        offset: usize,
+        message: String,
+    },
+
+    /// The response payload could not be decoded
+    #[error("{context}")]
+    Protocol {
+        context: String,
+        #[source]
+        source: ProtocolError,
+    },
+
+    /// The device answered with a different message type than expected
+    #[error("Unexpected response type: 0x{actual:02X}, expected 0x{expected:02X}")]
+    UnexpectedResponse { expected: u8, actual: u8 },
+
+    /// An argument was rejected before anything was sent
+    #[error("{0}")]
+    InvalidArgument(String),
+
+    /// Reading or writing a local file failed
+    #[error("{context}")]
+    Io {
+        context: String,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+impl DomesError {
+    /// Classify a transport failure as `Timeout`, `UnexpectedResponse` or
+    /// `Transport`
+    pub fn transport(context: impl Into<String>, error: anyhow::Error) -> Self {
+        if let Some(stray) = error.chain().find_map(|e| e.downcast_ref::<UnexpectedFrame>()) {
+            return DomesError::UnexpectedResponse {
+                expected: stray.expected,
+                actual: stray.actual,
+            };
+        }
+        let context = context.into();
+        let timed_out = error.chain().any(|e| e.is::<ResponseTimeout>());
+        let source = error.into();
+        if timed_out {
+            DomesError::Timeout { context, source }
+        } else {
+            DomesError::Transport { context, source }
+        }
+    }
+
+    /// Whether the device did not answer in time
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, DomesError::Timeout { .. })
+    }
+}
+
+impl From<ProtocolError> for DomesError {
+    fn from(error: ProtocolError) -> Self {
+        protocol_error("Invalid response".to_string(), error)
+    }
+}
+
+/// A device error status is surfaced as `Status`, anything else as `Protocol`
+fn protocol_error(context: String, error: ProtocolError) -> DomesError {
+    match error {
+        ProtocolError::DeviceError(status) => DomesError::Status(status),
+        source => DomesError::Protocol { context, source },
+    }
+}
+
+/// `anyhow::Context`-style helpers that convert into `DomesError`
+///
+/// Implemented for transport results (`anyhow::Result`), protocol and
+/// protobuf decode results, and file I/O.
+pub trait Context<T> {
+    /// Wrap the error with a message describing what was being done
+    fn context(self, context: impl Into<String>) -> Result<T>;
+
+    /// Like `context`, with the message built only on failure
+    fn with_context<C: Into<String>>(self, f: impl FnOnce() -> C) -> Result<T>;
+}
+
+impl<T> Context<T> for anyhow::Result<T> {
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.map_err(|e| DomesError::transport(context, e))
+    }
+
+    fn with_context<C: Into<String>>(self, f: impl FnOnce() -> C) -> Result<T> {
+        self.map_err(|e| DomesError::transport(f(), e))
+    }
+}
+
+impl<T> Context<T> for Result<T, ProtocolError> {
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.map_err(|e| protocol_error(context.into(), e))
+    }
+
+    fn with_context<C: Into<String>>(self, f: impl FnOnce() -> C) -> Result<T> {
+        self.map_err(|e| protocol_error(f().into(), e))
+    }
+}
+
+impl<T> Context<T> for Result<T, prost::DecodeError> {
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.map_err(ProtocolError::from).context(context)
+    }
+
+    fn with_context<C: Into<String>>(self, f: impl FnOnce() -> C) -> Result<T> {
+        self.map_err(ProtocolError::from).with_context(f)
+    }
+}
+
+impl<T> Context<T> for std::io::Result<T> {
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.map_err(|source| DomesError::Io {
+            context: context.into(),
+            source,
+        })
+    }
+
+    fn with_context<C: Into<String>>(self, f: impl FnOnce() -> C) -> Result<T> {
+        self.map_err(|source| DomesError::Io {
+            context: f().into(),
+            source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transport_timeout_is_classified() {
+        let timeout: anyhow::Result<()> = Err(anyhow::Error::new(ResponseTimeout));
+        let err = timeout.context("Failed to send get mode command").unwrap_err();
+        assert!(err.is_timeout());
+        assert_eq!(
+            format!("{:#}", anyhow::Error::new(err)),
+            "Failed to send get mode command: Timeout waiting for response"
+        );
+
+        let closed: anyhow::Result<()> = Err(anyhow::anyhow!("Connection closed by peer"));
+        let err = closed.context("Failed to send get mode command").unwrap_err();
+        assert!(matches!(err, DomesError::Transport { .. }));
+    }
+
+    #[test]
+    fn test_device_status_is_surfaced() {
+        let parsed: Result<(), ProtocolError> = Err(ProtocolError::DeviceError(Status::Busy));
+        let err = parsed.context("Failed to parse set mode response").unwrap_err();
+        assert!(matches!(err, DomesError::Status(Status::Busy)));
+
+        let parsed: Result<(), ProtocolError> = Err(ProtocolError::UnknownStatus(99));
+        let err = parsed.context("Failed to parse set mode response").unwrap_err();
+        assert!(matches!(err, DomesError::Protocol { .. }));
+    }
+}
@@ -0,0 +1,24 @@
+//! DOMES core library
+//!
+//! Transport, protocol and command API shared by `domes-cli` and any other
+//! host tooling (GUIs, test harnesses) that talks to DOMES firmware.
+//!
+//! ```no_run
+//! use domes_core::commands;
+//! use domes_core::transport::SerialTransport;
+//!
+//! let mut transport = SerialTransport::open_default("/dev/ttyACM0")?;
+//! let info = commands::system_info(&mut transport)?;
+//! println!("firmware {}", info.firmware_version);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+#[macro_use]
+pub mod progress;
+
+pub mod commands;
+pub mod device;
+pub mod error;
+pub mod proto;
+pub mod protocol;
+pub mod transport;
@@ -0,0 +1,142 @@
+//! Progress and informational output
+//!
+//! Status chatter ("Connecting to...", "Scanning...", OTA progress bar) is
+//! written to stderr so stdout only carries command results. The global
+//! `--quiet` flag silences it entirely.
+//!
+//! When several devices run at once, each worker thread sets a label (e.g.
+//! "[pod1] ") that is prepended to its progress lines.
+//!
+//! `--human-readable` switches byte counts (here and in command results) from
+//! exact counts to `1.2 MB`-style sizes; see `format_bytes`.
+//!
+//! While the CLI draws its own progress bars, `set_writer` routes lines to
+//! it so they are printed above the bars instead of through them.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+/// Where progress lines go instead of stderr
+pub type Writer = Box<dyn Fn(&str) + Send + Sync>;
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+static INTERLEAVED: AtomicBool = AtomicBool::new(false);
+static HUMAN_READABLE: AtomicBool = AtomicBool::new(false);
+static WRITER: RwLock<Option<Writer>> = RwLock::new(None);
+
+thread_local! {
+    static LABEL: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Enable or disable progress output for the rest of the process
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether progress output is currently suppressed
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Mark progress from several threads as interleaved on one terminal
+///
+/// Redrawing a line with `\r` only works for a single writer, so in this
+/// mode progress bars fall back to printing whole lines.
+pub fn set_interleaved(interleaved: bool) {
+    INTERLEAVED.store(interleaved, Ordering::Relaxed);
+}
+
+/// Whether progress from several devices shares the terminal
+pub fn is_interleaved() -> bool {
+    INTERLEAVED.load(Ordering::Relaxed)
+}
+
+/// Show byte counts as `1.2 MB`-style sizes instead of exact counts
+pub fn set_human_readable(human: bool) {
+    HUMAN_READABLE.store(human, Ordering::Relaxed);
+}
+
+/// Whether byte counts are shown as `1.2 MB`-style sizes
+pub fn is_human_readable() -> bool {
+    HUMAN_READABLE.load(Ordering::Relaxed)
+}
+
+/// Format a size with 1024-based units: `512 B`, `183.0 KB`, `1.2 MB`
+pub fn format_bytes_human(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// A byte count for display: `183.0 KB` with `--human-readable`, else `187432 bytes`
+pub fn format_bytes(bytes: u64) -> String {
+    if is_human_readable() {
+        format_bytes_human(bytes)
+    } else {
+        format!("{} bytes", bytes)
+    }
+}
+
+/// Set the label prepended to progress lines from the current thread
+pub fn set_label(label: &str) {
+    LABEL.with(|l| *l.borrow_mut() = label.to_string());
+}
+
+/// Label prepended to progress lines from the current thread
+pub fn label() -> String {
+    LABEL.with(|l| l.borrow().clone())
+}
+
+/// Send progress lines to `writer` instead of stderr; `None` restores stderr
+pub fn set_writer(writer: Option<Writer>) {
+    *WRITER.write().unwrap() = writer;
+}
+
+/// Write one progress line to stderr with the thread's label
+#[doc(hidden)]
+pub fn emit(args: std::fmt::Arguments<'_>) {
+    let line = args.to_string();
+    let line = if line.is_empty() { line } else { format!("{}{}", label(), line) };
+    match &*WRITER.read().unwrap() {
+        Some(writer) => writer(&line),
+        None => eprintln!("{}", line),
+    }
+}
+
+/// Print a progress line to stderr unless `--quiet` is set
+#[macro_export]
+macro_rules! progress {
+    () => {
+        $crate::progress!("")
+    };
+    ($($arg:tt)*) => {
+        if !$crate::progress::is_quiet() {
+            $crate::progress::emit(format_args!($($arg)*));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes_human_units() {
+        assert_eq!(format_bytes_human(0), "0 B");
+        assert_eq!(format_bytes_human(1023), "1023 B");
+        assert_eq!(format_bytes_human(1024), "1.0 KB");
+        assert_eq!(format_bytes_human(187_432), "183.0 KB");
+        assert_eq!(format_bytes_human(1_258_291), "1.2 MB");
+        assert_eq!(format_bytes_human(3 << 30), "3.0 GB");
+        assert_eq!(format_bytes_human(u64::MAX), "16777216.0 TB");
+    }
+}
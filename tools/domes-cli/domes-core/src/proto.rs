@@ -9,7 +9,7 @@
 //! 3. Update code that uses the types
 
 /// Config protocol types (generated from config.proto)
-#[allow(dead_code)]
+#[allow(dead_code, clippy::enum_variant_names)]
 pub mod config {
     include!(concat!(env!("OUT_DIR"), "/domes.config.rs"));
 
@@ -57,6 +57,20 @@ pub mod config {
         }
     }
 
+    /// Serialized by CLI name (`"led-effects"`), as in feature profiles
+    impl serde::Serialize for Feature {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self.cli_name())
+        }
+    }
+
+    impl<'de> serde::Deserialize<'de> for Feature {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let name = String::deserialize(deserializer)?;
+            name.parse().map_err(serde::de::Error::custom)
+        }
+    }
+
     impl MsgType {
         /// Convert to u8 for frame encoding
         pub fn as_u8(&self) -> u8 {
@@ -104,6 +118,46 @@ pub mod config {
             Self::from_cli_name(s).ok_or_else(|| format!("Unknown mode: {}", s))
         }
     }
+
+    impl EventCategory {
+        /// Categories that can be subscribed to
+        pub const ALL: [EventCategory; 5] = [
+            EventCategory::Mode,
+            EventCategory::Led,
+            EventCategory::Wifi,
+            EventCategory::Feature,
+            EventCategory::Touch,
+        ];
+
+        /// Get user-friendly name for CLI display
+        pub fn cli_name(&self) -> &'static str {
+            match self {
+                EventCategory::None => "none",
+                EventCategory::Mode => "mode",
+                EventCategory::Led => "led",
+                EventCategory::Wifi => "wifi",
+                EventCategory::Feature => "feature",
+                EventCategory::Touch => "touch",
+            }
+        }
+
+        /// Parse from CLI input string
+        pub fn from_cli_name(s: &str) -> Option<EventCategory> {
+            match s.to_lowercase().as_str() {
+                "mode" => Some(EventCategory::Mode),
+                "led" | "leds" => Some(EventCategory::Led),
+                "wifi" | "wi-fi" => Some(EventCategory::Wifi),
+                "feature" | "features" => Some(EventCategory::Feature),
+                "touch" => Some(EventCategory::Touch),
+                _ => None,
+            }
+        }
+
+        /// Bit for this category in a subscription mask
+        pub fn mask(&self) -> u32 {
+            *self as i32 as u32
+        }
+    }
 }
 
 /// Trace protocol types (generated from trace.proto)
@@ -127,6 +181,7 @@ pub mod trace {
                 Status::AlreadyOn => "already-on",
                 Status::AlreadyOff => "already-off",
                 Status::BufferEmpty => "buffer-empty",
+                Status::NoMemory => "no-memory",
                 Status::Error => "error",
             }
         }
@@ -166,3 +221,11 @@ pub mod trace {
         }
     }
 }
+
+/// Perfetto trace file types (generated from proto/perfetto_trace.proto)
+///
+/// A subset of upstream `perfetto.protos`, for writing `.pftrace` files.
+#[allow(dead_code)]
+pub mod perfetto {
+    include!(concat!(env!("OUT_DIR"), "/perfetto.protos.rs"));
+}
@@ -0,0 +1,127 @@
+//! Batch envelope for sending several config requests in one round trip
+//!
+//! A batch request carries `(msg_type, payload)` pairs exactly as they would
+//! be sent on their own. The device answers with one `(msg_type, payload)`
+//! pair per request, in the same order, each in its usual
+//! `[status][protobuf]` response format.
+
+use super::ProtocolError;
+use crate::proto::config::{BatchCommand, BatchRequest, BatchResponse, Status};
+use prost::Message;
+
+/// Maximum commands per batch (matches firmware nanopb max_count)
+pub const BATCH_MAX_COMMANDS: usize = 3;
+
+/// Maximum payload size of one command in a batch (matches firmware nanopb max_size)
+pub const BATCH_MAX_COMMAND_PAYLOAD: usize = 256;
+
+/// Serialize a BatchRequest from `(msg_type, payload)` pairs
+pub fn serialize_batch_request(commands: &[(u8, Vec<u8>)]) -> Vec<u8> {
+    let req = BatchRequest {
+        commands: commands
+            .iter()
+            .map(|(msg_type, payload)| BatchCommand {
+                msg_type: *msg_type as u32,
+                payload: payload.clone(),
+            })
+            .collect(),
+    };
+    req.encode_to_vec()
+}
+
+/// Parse BatchResponse payload into `(msg_type, payload)` pairs
+/// Format: [status_byte][protobuf_BatchResponse]
+pub fn parse_batch_response(payload: &[u8]) -> Result<Vec<(u8, Vec<u8>)>, ProtocolError> {
+    if payload.is_empty() {
+        return Err(ProtocolError::PayloadTooShort {
+            expected: 1,
+            actual: 0,
+        });
+    }
+
+    let status_val = payload[0] as i32;
+    let status =
+        Status::try_from(status_val).map_err(|_| ProtocolError::UnknownStatus(status_val))?;
+
+    if status != Status::Ok {
+        return Err(ProtocolError::DeviceError(status));
+    }
+
+    let resp = BatchResponse::decode(&payload[1..])?;
+
+    resp.responses
+        .into_iter()
+        .map(|r| {
+            let msg_type = u8::try_from(r.msg_type)
+                .map_err(|_| ProtocolError::MessageTypeOutOfRange(r.msg_type))?;
+            Ok((msg_type, r.payload))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_request_round_trip() {
+        let commands = vec![(0x22, vec![0x08, 0x02, 0x10, 0x01]), (0x28, vec![])];
+        let bytes = serialize_batch_request(&commands);
+
+        let req = BatchRequest::decode(bytes.as_slice()).unwrap();
+        assert_eq!(req.commands.len(), 2);
+        assert_eq!(req.commands[0].msg_type, 0x22);
+        assert_eq!(req.commands[0].payload, vec![0x08, 0x02, 0x10, 0x01]);
+        assert_eq!(req.commands[1].msg_type, 0x28);
+        assert!(req.commands[1].payload.is_empty());
+    }
+
+    #[test]
+    fn test_parse_batch_response() {
+        let resp = BatchResponse {
+            responses: vec![
+                BatchCommand {
+                    msg_type: 0x23,
+                    payload: vec![0x00, 0x08, 0x02],
+                },
+                BatchCommand {
+                    msg_type: 0x27,
+                    payload: vec![0x00],
+                },
+            ],
+        };
+        let mut payload = vec![Status::Ok as u8];
+        payload.extend(resp.encode_to_vec());
+
+        let parsed = parse_batch_response(&payload).unwrap();
+        assert_eq!(
+            parsed,
+            vec![(0x23, vec![0x00, 0x08, 0x02]), (0x27, vec![0x00])]
+        );
+    }
+
+    #[test]
+    fn test_parse_batch_response_error_status() {
+        let payload = [Status::Busy as u8];
+        assert!(matches!(
+            parse_batch_response(&payload),
+            Err(ProtocolError::DeviceError(Status::Busy))
+        ));
+    }
+
+    #[test]
+    fn test_parse_batch_response_rejects_wide_msg_type() {
+        let resp = BatchResponse {
+            responses: vec![BatchCommand {
+                msg_type: 0x1AA,
+                payload: vec![0x00],
+            }],
+        };
+        let mut payload = vec![Status::Ok as u8];
+        payload.extend(resp.encode_to_vec());
+
+        let err = parse_batch_response(&payload).unwrap_err();
+        assert!(matches!(err, ProtocolError::MessageTypeOutOfRange(0x1AA)));
+        assert_eq!(err.to_string(), "Message type out of range: 0x1AA");
+    }
+}
@@ -6,18 +6,28 @@
 //! IMPORTANT: All types come from proto modules, generated from
 //! firmware/common/proto/*.proto. DO NOT hand-roll protocol types here.
 
+pub mod batch;
+
 use crate::proto::config::{
-    CheckUpdateResponse, ClearCrashDumpResponse, Color, CrashDumpResponse, EspNowBenchRequest,
-    EspNowBenchResponse, Feature, GetEspNowStatusResponse, GetHealthResponse,
+    CheckUpdateResponse, ClearCoreDumpResponse, ClearCrashDumpResponse, Color,
+    CoreDumpReadResponse, CrashDumpResponse, EspNowBenchRequest,
+    EspNowBenchResponse, EventCategory, EventNotification, Feature, GetEspNowStatusResponse, GetHealthResponse,
     GetLedPatternResponse, GetMemoryProfileResponse, GetModeResponse, GetSystemInfoResponse,
+    GetPartitionTableResponse, GetTaskListResponse,
     LedPattern, LedPatternType, ListFeaturesResponse, SelfTestResponse, SetAutoUpdateRequest,
-    SetAutoUpdateResponse, SetFeatureRequest, SetFeatureResponse,
+    SetAutoUpdateResponse, SetBaudRateRequest, SetBaudRateResponse, SetDeviceNameRequest,
+    SetDeviceNameResponse, SetFeatureRequest, SetFeatureResponse,
     SetImuTriageRequest, SetImuTriageResponse, SetLedPatternRequest, SetLedPatternResponse,
     SetModeRequest, SetModeResponse, SetPodIdRequest, SetPodIdResponse,
     SetSimModeRequest, SetSimModeResponse, SimulateTouchRequest, SimulateTouchResponse,
-    Status, SystemMode,
+    SubscribeEventsRequest, SubscribeEventsResponse, WifiApConfigRequest, WifiApConfigResponse,
+    WifiApStatusResponse,
+    PartitionType, Status, SystemMode, TaskState,
 };
 use prost::Message;
+use anyhow::Context;
+use std::net::Ipv4Addr;
+use std::path::Path;
 use thiserror::Error;
 
 // Re-export config MsgType with clearer name for use in commands
@@ -70,6 +80,62 @@ impl TryFrom<u8> for ConfigMsgType {
             0x4D => Ok(Self::SimulateTouchRsp),
             0x4E => Ok(Self::SetSimModeReq),
             0x4F => Ok(Self::SetSimModeRsp),
+            0x50 => Ok(Self::BatchReq),
+            0x51 => Ok(Self::BatchRsp),
+            0x52 => Ok(Self::SetDeviceNameReq),
+            0x53 => Ok(Self::SetDeviceNameRsp),
+            0x54 => Ok(Self::SubscribeEventsReq),
+            0x55 => Ok(Self::SubscribeEventsRsp),
+            0x56 => Ok(Self::EventNotification),
+            0x57 => Ok(Self::PingReq),
+            0x58 => Ok(Self::PingRsp),
+            0x59 => Ok(Self::SetBaudRateReq),
+            0x5A => Ok(Self::SetBaudRateRsp),
+            0x5B => Ok(Self::GetTaskListReq),
+            0x5C => Ok(Self::GetTaskListRsp),
+            0x5D => Ok(Self::GetPartitionTableReq),
+            0x5E => Ok(Self::GetPartitionTableRsp),
+            0x5F => Ok(Self::CoreDumpReadReq),
+            0x60 => Ok(Self::CoreDumpReadRsp),
+            0x61 => Ok(Self::CoreDumpData),
+            0x62 => Ok(Self::CoreDumpEnd),
+            0x63 => Ok(Self::CoreDumpClearReq),
+            0x64 => Ok(Self::CoreDumpClearRsp),
+            0x65 => Ok(Self::WifiApConfigReq),
+            0x66 => Ok(Self::WifiApConfigRsp),
+            0x67 => Ok(Self::WifiApStatusReq),
+            0x68 => Ok(Self::WifiApStatusRsp),
+            0x69 => Ok(Self::WifiStaticIpReq),
+            0x6A => Ok(Self::WifiStaticIpRsp),
+            _ => Err(ProtocolError::UnknownMessageType(value)),
+        }
+    }
+}
+
+// Re-export trace MsgType alongside ConfigMsgType
+pub use crate::proto::trace::MsgType as TraceMsgType;
+
+/// Trace message types the CLI sends or parses (`trace` commands)
+impl TryFrom<u8> for TraceMsgType {
+    type Error = ProtocolError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x10 => Ok(Self::Start),
+            0x11 => Ok(Self::Stop),
+            0x12 => Ok(Self::Dump),
+            0x13 => Ok(Self::Data),
+            0x14 => Ok(Self::End),
+            0x15 => Ok(Self::Clear),
+            0x16 => Ok(Self::StatusReq),
+            0x17 => Ok(Self::StatusResp),
+            0x19 => Ok(Self::StreamData),
+            0x1A => Ok(Self::SessionInfo),
+            0x1B => Ok(Self::Ack),
+            0x1C => Ok(Self::SetBufferSizeReq),
+            0x1D => Ok(Self::SetBufferSizeRsp),
+            0x1E => Ok(Self::AnnotateReq),
+            0x1F => Ok(Self::AnnotateRsp),
             _ => Err(ProtocolError::UnknownMessageType(value)),
         }
     }
@@ -81,24 +147,36 @@ pub enum ProtocolError {
     #[error("Unknown message type: 0x{0:02X}")]
     UnknownMessageType(u8),
 
+    #[error("Message type out of range: 0x{0:X}")]
+    MessageTypeOutOfRange(u32),
+
     #[error("Unknown feature ID: {0}")]
     UnknownFeature(i32),
 
     #[error("Unknown status code: {0}")]
     UnknownStatus(i32),
 
+    #[error("Unknown event category: {0}")]
+    UnknownEventCategory(i32),
+
     #[error("Payload too short: expected {expected}, got {actual}")]
     PayloadTooShort { expected: usize, actual: usize },
 
     #[error("Device returned error: {0:?}")]
     DeviceError(Status),
 
+    #[error("Checksum mismatch: expected 0x{expected:08X}, got 0x{actual:08X}")]
+    ChecksumMismatch { expected: u32, actual: u32 },
+
     #[error("Protobuf decode error: {0}")]
     DecodeError(#[from] prost::DecodeError),
 }
 
 /// Feature state for CLI use
-#[derive(Debug, Clone, Copy)]
+///
+/// Serializes as `{"feature": "wifi", "enabled": true}`, the form of
+/// `feature list --format json` entries and feature profiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct CliFeatureState {
     pub feature: Feature,
     pub enabled: bool,
@@ -166,9 +244,19 @@ pub fn parse_feature_response(payload: &[u8]) -> Result<CliFeatureState, Protoco
     })
 }
 
+/// Maximum colors in a color cycle (matches `LedPattern.colors max_count` in config.options)
+pub const LED_CYCLE_MAX_COLORS: usize = 8;
+
 /// LED pattern state for CLI use
-#[derive(Debug, Clone)]
+///
+/// In JSON (`led export`/`led import`) colors are `[r, g, b, w]` arrays and
+/// the type is named: `{"pattern_type":"breathing","color":[0,255,0,0],
+/// "colors":[],"period_ms":2000,"brightness":128}`. Missing fields take their
+/// defaults.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct CliLedPattern {
+    #[serde(with = "led_pattern_type_name")]
     pub pattern_type: LedPatternType,
     pub color: Option<(u8, u8, u8, u8)>, // RGBW
     pub colors: Vec<(u8, u8, u8, u8)>,   // Color list for cycles
@@ -225,6 +313,93 @@ impl CliLedPattern {
             ..Default::default()
         }
     }
+
+    /// Check that the colors the pattern type needs are set: `color` for
+    /// solid and breathing, 1 to `LED_CYCLE_MAX_COLORS` `colors` for a cycle
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let name = led_pattern_type_name::name(self.pattern_type);
+        match self.pattern_type {
+            LedPatternType::LedPatternSolid | LedPatternType::LedPatternBreathing => {
+                if self.color.is_none() {
+                    anyhow::bail!("A {} pattern needs a color", name);
+                }
+            }
+            LedPatternType::LedPatternColorCycle => {
+                if self.colors.is_empty() {
+                    anyhow::bail!("A {} pattern needs at least one color in colors", name);
+                }
+                if self.colors.len() > LED_CYCLE_MAX_COLORS {
+                    anyhow::bail!(
+                        "A {} pattern has at most {} colors, got {}",
+                        name,
+                        LED_CYCLE_MAX_COLORS,
+                        self.colors.len()
+                    );
+                }
+            }
+            LedPatternType::LedPatternOff => {}
+        }
+        Ok(())
+    }
+
+    /// Read a pattern from a JSON file, checking it with `validate`
+    pub fn from_json_file(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let pattern: Self = serde_json::from_str(&text)
+            .with_context(|| format!("Invalid LED pattern in {}", path.display()))?;
+        pattern
+            .validate()
+            .with_context(|| format!("Invalid LED pattern in {}", path.display()))?;
+        Ok(pattern)
+    }
+
+    /// Write the pattern to a JSON file
+    pub fn to_json_file(&self, path: &Path) -> anyhow::Result<()> {
+        // Plain numbers and strings, so serializing cannot fail
+        let json = serde_json::to_string_pretty(self).unwrap_or_default();
+        std::fs::write(path, json + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// `CliLedPattern::pattern_type` in JSON: "off", "solid", "breathing" or
+/// "color_cycle" (`led cycle`'s "color-cycle" is accepted too)
+mod led_pattern_type_name {
+    use super::LedPatternType;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn name(pattern_type: LedPatternType) -> &'static str {
+        match pattern_type {
+            LedPatternType::LedPatternOff => "off",
+            LedPatternType::LedPatternSolid => "solid",
+            LedPatternType::LedPatternBreathing => "breathing",
+            LedPatternType::LedPatternColorCycle => "color_cycle",
+        }
+    }
+
+    pub fn serialize<S: Serializer>(
+        pattern_type: &LedPatternType,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(name(*pattern_type))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<LedPatternType, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        match value.to_lowercase().as_str() {
+            "off" => Ok(LedPatternType::LedPatternOff),
+            "solid" => Ok(LedPatternType::LedPatternSolid),
+            "breathing" => Ok(LedPatternType::LedPatternBreathing),
+            "color_cycle" | "color-cycle" => Ok(LedPatternType::LedPatternColorCycle),
+            _ => Err(serde::de::Error::custom(format!(
+                "unknown pattern_type '{}' (expected off, solid, breathing or color_cycle)",
+                value
+            ))),
+        }
+    }
 }
 
 /// Serialize SetLedPatternRequest using protobuf encoding
@@ -258,6 +433,12 @@ pub fn serialize_set_led_pattern(pattern: &CliLedPattern) -> Vec<u8> {
 /// Parse SetLedPatternResponse or GetLedPatternResponse payload
 /// Format: [status_byte][protobuf_response]
 pub fn parse_led_pattern_response(payload: &[u8]) -> Result<CliLedPattern, ProtocolError> {
+    parse_led_pattern_proto(payload).map(led_pattern_from_proto)
+}
+
+/// Like `parse_led_pattern_response`, but the protobuf `LedPattern` as the
+/// device sent it: unknown types and out-of-range channels are kept
+pub fn parse_led_pattern_proto(payload: &[u8]) -> Result<LedPattern, ProtocolError> {
     if payload.is_empty() {
         return Err(ProtocolError::PayloadTooShort {
             expected: 1,
@@ -291,6 +472,11 @@ pub fn parse_led_pattern_response(payload: &[u8]) -> Result<CliLedPattern, Proto
         actual: payload.len(),
     })?;
 
+    Ok(pattern)
+}
+
+/// Convert a protobuf LedPattern into the CLI representation
+fn led_pattern_from_proto(pattern: LedPattern) -> CliLedPattern {
     let pattern_type = LedPatternType::try_from(pattern.r#type)
         .unwrap_or(LedPatternType::LedPatternOff);
 
@@ -309,13 +495,13 @@ pub fn parse_led_pattern_response(payload: &[u8]) -> Result<CliLedPattern, Proto
         .map(|c| (c.r as u8, c.g as u8, c.b as u8, c.w as u8))
         .collect();
 
-    Ok(CliLedPattern {
+    CliLedPattern {
         pattern_type,
         color,
         colors,
         period_ms: pattern.period_ms,
         brightness: pattern.brightness as u8,
-    })
+    }
 }
 
 /// Serialize SetImuTriageRequest using protobuf encoding
@@ -354,6 +540,9 @@ pub fn parse_imu_triage_response(payload: &[u8]) -> Result<bool, ProtocolError>
 pub struct CliModeInfo {
     pub mode: SystemMode,
     pub time_in_mode_ms: u32,
+    /// Mode before the last transition; `None` before the first one, or
+    /// from firmware that does not report it
+    pub previous_mode: Option<SystemMode>,
 }
 
 /// System info for CLI use
@@ -366,12 +555,21 @@ pub struct CliSystemInfo {
     pub mode: SystemMode,
     pub feature_mask: u32,
     pub pod_id: u32,
+    /// Largest frame payload the device accepts, if it reports one
+    pub max_payload: Option<u32>,
+    /// Only `mode`, `feature_mask` and `pod_id` are known: the device (older
+    /// firmware) did not answer `GetSystemInfo`, so the rest is empty or zero
+    pub partial: bool,
 }
 
 /// Serialize SetModeRequest using protobuf encoding
-pub fn serialize_set_mode(mode: SystemMode) -> Vec<u8> {
+///
+/// `force` asks the firmware to skip its transition rules (e.g. game to
+/// idle directly); firmware without support for it applies them anyway.
+pub fn serialize_set_mode(mode: SystemMode, force: bool) -> Vec<u8> {
     let req = SetModeRequest {
         mode: mode as i32,
+        force,
     };
     req.encode_to_vec()
 }
@@ -397,9 +595,14 @@ pub fn parse_get_mode_response(payload: &[u8]) -> Result<CliModeInfo, ProtocolEr
     let resp = GetModeResponse::decode(&payload[1..])?;
     let mode = SystemMode::try_from(resp.mode).unwrap_or(SystemMode::Booting);
 
+    let previous_mode = resp
+        .has_previous_mode
+        .then(|| SystemMode::try_from(resp.previous_mode).unwrap_or(SystemMode::Booting));
+
     Ok(CliModeInfo {
         mode,
         time_in_mode_ms: resp.time_in_mode_ms,
+        previous_mode,
     })
 }
 
@@ -456,6 +659,8 @@ pub fn parse_get_system_info_response(payload: &[u8]) -> Result<CliSystemInfo, P
         mode,
         feature_mask: resp.feature_mask,
         pod_id: resp.pod_id,
+        max_payload: (resp.max_payload != 0).then_some(resp.max_payload),
+        partial: false,
     })
 }
 
@@ -487,6 +692,64 @@ pub fn parse_set_pod_id_response(payload: &[u8]) -> Result<u32, ProtocolError> {
     Ok(resp.pod_id)
 }
 
+/// Serialize SetBaudRateRequest
+pub fn serialize_set_baud_rate(baud_rate: u32) -> Vec<u8> {
+    let req = SetBaudRateRequest { baud_rate };
+    req.encode_to_vec()
+}
+
+/// Parse SetBaudRateResponse payload
+/// Format: [status_byte][protobuf_SetBaudRateResponse]
+pub fn parse_set_baud_rate_response(payload: &[u8]) -> Result<u32, ProtocolError> {
+    if payload.is_empty() {
+        return Err(ProtocolError::PayloadTooShort {
+            expected: 1,
+            actual: 0,
+        });
+    }
+
+    let status_val = payload[0] as i32;
+    let status =
+        Status::try_from(status_val).map_err(|_| ProtocolError::UnknownStatus(status_val))?;
+
+    if status != Status::Ok {
+        return Err(ProtocolError::DeviceError(status));
+    }
+
+    let resp = SetBaudRateResponse::decode(&payload[1..])?;
+    Ok(resp.baud_rate)
+}
+
+/// Serialize SetDeviceNameRequest
+pub fn serialize_set_device_name(name: &str) -> Vec<u8> {
+    let req = SetDeviceNameRequest {
+        name: name.to_string(),
+    };
+    req.encode_to_vec()
+}
+
+/// Parse SetDeviceNameResponse payload
+/// Format: [status_byte][protobuf_SetDeviceNameResponse]
+pub fn parse_set_device_name_response(payload: &[u8]) -> Result<String, ProtocolError> {
+    if payload.is_empty() {
+        return Err(ProtocolError::PayloadTooShort {
+            expected: 1,
+            actual: 0,
+        });
+    }
+
+    let status_val = payload[0] as i32;
+    let status =
+        Status::try_from(status_val).map_err(|_| ProtocolError::UnknownStatus(status_val))?;
+
+    if status != Status::Ok {
+        return Err(ProtocolError::DeviceError(status));
+    }
+
+    let resp = SetDeviceNameResponse::decode(&payload[1..])?;
+    Ok(resp.name)
+}
+
 // ============================================================================
 // Observability types and parsers
 // ============================================================================
@@ -510,6 +773,35 @@ pub struct CliHealthInfo {
     pub tasks: Vec<CliTaskHealth>,
 }
 
+/// One FreeRTOS task from `system tasks`
+#[derive(Debug, Clone)]
+pub struct TaskInfo {
+    pub name: String,
+    pub state: TaskState,
+    pub priority: u8,
+    /// Least free stack the task has had, in bytes
+    pub stack_watermark_bytes: u32,
+    /// Share of CPU time since boot
+    pub runtime_percent: f32,
+    /// Core the task is pinned to, `None` if it runs on either
+    pub core_id: Option<u8>,
+}
+
+/// One flash partition from `system partitions`
+#[derive(Debug, Clone)]
+pub struct PartitionInfo {
+    pub name: String,
+    pub type_: PartitionType,
+    /// Raw ESP-IDF subtype (e.g. 0x10 for ota_0)
+    pub subtype: u8,
+    /// Flash address in bytes
+    pub offset: u32,
+    pub size: u32,
+    pub encrypted: bool,
+    /// The app partition currently running
+    pub active: bool,
+}
+
 /// ESP-NOW peer info for CLI use
 #[derive(Debug, Clone)]
 pub struct CliEspNowPeer {
@@ -571,6 +863,77 @@ pub fn parse_get_health_response(payload: &[u8]) -> Result<CliHealthInfo, Protoc
     })
 }
 
+/// Parse GetTaskListResponse payload
+/// Format: [status_byte][protobuf_GetTaskListResponse]
+pub fn parse_get_task_list_response(payload: &[u8]) -> Result<Vec<TaskInfo>, ProtocolError> {
+    if payload.is_empty() {
+        return Err(ProtocolError::PayloadTooShort {
+            expected: 1,
+            actual: 0,
+        });
+    }
+
+    let status_val = payload[0] as i32;
+    let status =
+        Status::try_from(status_val).map_err(|_| ProtocolError::UnknownStatus(status_val))?;
+
+    if status != Status::Ok {
+        return Err(ProtocolError::DeviceError(status));
+    }
+
+    let resp = GetTaskListResponse::decode(&payload[1..])?;
+
+    Ok(resp
+        .tasks
+        .into_iter()
+        .map(|t| TaskInfo {
+            state: TaskState::try_from(t.state).unwrap_or(TaskState::Unknown),
+            priority: u8::try_from(t.priority).unwrap_or(u8::MAX),
+            stack_watermark_bytes: t.stack_watermark_bytes,
+            runtime_percent: t.runtime_percent,
+            core_id: t.has_core_id.then_some(t.core_id as u8),
+            name: t.name,
+        })
+        .collect())
+}
+
+/// Parse GetPartitionTableResponse payload
+/// Format: [status_byte][protobuf_GetPartitionTableResponse]
+pub fn parse_get_partition_table_response(
+    payload: &[u8],
+) -> Result<Vec<PartitionInfo>, ProtocolError> {
+    if payload.is_empty() {
+        return Err(ProtocolError::PayloadTooShort {
+            expected: 1,
+            actual: 0,
+        });
+    }
+
+    let status_val = payload[0] as i32;
+    let status =
+        Status::try_from(status_val).map_err(|_| ProtocolError::UnknownStatus(status_val))?;
+
+    if status != Status::Ok {
+        return Err(ProtocolError::DeviceError(status));
+    }
+
+    let resp = GetPartitionTableResponse::decode(&payload[1..])?;
+
+    Ok(resp
+        .partitions
+        .into_iter()
+        .map(|p| PartitionInfo {
+            type_: PartitionType::try_from(p.r#type).unwrap_or(PartitionType::Unknown),
+            subtype: u8::try_from(p.subtype).unwrap_or(u8::MAX),
+            offset: p.offset,
+            size: p.size,
+            encrypted: p.encrypted,
+            active: p.active,
+            name: p.name,
+        })
+        .collect())
+}
+
 /// Parse GetEspNowStatusResponse payload
 /// Format: [status_byte][protobuf_GetEspNowStatusResponse]
 pub fn parse_get_espnow_status_response(
@@ -757,6 +1120,171 @@ pub struct CliBenchResult {
     pub p99_rtt_us: u32,
 }
 
+// ============================================================================
+// WiFi access point types and parsers
+// ============================================================================
+
+/// WiFi access point configuration applied by the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WifiApConfig {
+    pub ssid: String,
+    pub channel: u8,
+    pub max_connections: u8,
+    /// False for an open network (no password)
+    pub secured: bool,
+    /// The AP's gateway address, typically 192.168.4.1
+    pub ip: Ipv4Addr,
+}
+
+/// A station connected to the access point
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WifiApClientInfo {
+    pub mac: [u8; 6],
+    pub rssi: i32,
+}
+
+/// Access point state and connected stations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WifiApStatus {
+    /// False if the pod is not running as an access point
+    pub active: bool,
+    pub ssid: String,
+    pub channel: u8,
+    pub ip: Ipv4Addr,
+    pub clients: Vec<WifiApClientInfo>,
+}
+
+/// Serialize WifiApConfigRequest
+pub fn serialize_wifi_ap_config(
+    ssid: &str,
+    password: &str,
+    channel: u8,
+    max_connections: u8,
+) -> Vec<u8> {
+    let req = WifiApConfigRequest {
+        ssid: ssid.to_string(),
+        password: password.to_string(),
+        channel: channel as u32,
+        max_connections: max_connections as u32,
+    };
+    req.encode_to_vec()
+}
+
+/// Parse WifiApConfigResponse payload
+/// Format: [status_byte][protobuf_WifiApConfigResponse]
+pub fn parse_wifi_ap_config_response(payload: &[u8]) -> Result<WifiApConfig, ProtocolError> {
+    if payload.is_empty() {
+        return Err(ProtocolError::PayloadTooShort {
+            expected: 1,
+            actual: 0,
+        });
+    }
+
+    let status_val = payload[0] as i32;
+    let status =
+        Status::try_from(status_val).map_err(|_| ProtocolError::UnknownStatus(status_val))?;
+
+    if status != Status::Ok {
+        return Err(ProtocolError::DeviceError(status));
+    }
+
+    let resp = WifiApConfigResponse::decode(&payload[1..])?;
+    Ok(WifiApConfig {
+        ssid: resp.ssid,
+        channel: u8::try_from(resp.channel).unwrap_or(u8::MAX),
+        max_connections: u8::try_from(resp.max_connections).unwrap_or(u8::MAX),
+        secured: resp.secured,
+        ip: Ipv4Addr::from(resp.ip),
+    })
+}
+
+/// Parse WifiApStatusResponse payload
+/// Format: [status_byte][protobuf_WifiApStatusResponse]
+pub fn parse_wifi_ap_status_response(payload: &[u8]) -> Result<WifiApStatus, ProtocolError> {
+    if payload.is_empty() {
+        return Err(ProtocolError::PayloadTooShort {
+            expected: 1,
+            actual: 0,
+        });
+    }
+
+    let status_val = payload[0] as i32;
+    let status =
+        Status::try_from(status_val).map_err(|_| ProtocolError::UnknownStatus(status_val))?;
+
+    if status != Status::Ok {
+        return Err(ProtocolError::DeviceError(status));
+    }
+
+    let resp = WifiApStatusResponse::decode(&payload[1..])?;
+    let clients = resp
+        .clients
+        .iter()
+        .map(|c| {
+            let mut mac = [0u8; 6];
+            let len = c.mac.len().min(6);
+            mac[..len].copy_from_slice(&c.mac[..len]);
+            WifiApClientInfo { mac, rssi: c.rssi }
+        })
+        .collect();
+    Ok(WifiApStatus {
+        active: resp.active,
+        ssid: resp.ssid,
+        channel: u8::try_from(resp.channel).unwrap_or(u8::MAX),
+        ip: Ipv4Addr::from(resp.ip),
+        clients,
+    })
+}
+
+/// Static IPv4 settings for WiFi station mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WifiStaticIp {
+    pub ip: Ipv4Addr,
+    pub netmask: Ipv4Addr,
+    pub gateway: Ipv4Addr,
+    pub dns1: Ipv4Addr,
+    pub dns2: Option<Ipv4Addr>,
+}
+
+/// Serialize WifiStaticIpReq payload; `None` reverts to DHCP
+/// Format: [ip][netmask][gateway][dns1][dns2], each 4 bytes big-endian,
+/// with 0.0.0.0 for no `dns2` and all zeros for DHCP
+pub fn serialize_wifi_static_ip(config: Option<&WifiStaticIp>) -> Vec<u8> {
+    let Some(config) = config else {
+        return vec![0; 20];
+    };
+    [
+        config.ip,
+        config.netmask,
+        config.gateway,
+        config.dns1,
+        config.dns2.unwrap_or(Ipv4Addr::UNSPECIFIED),
+    ]
+    .iter()
+    .flat_map(|addr| addr.octets())
+    .collect()
+}
+
+/// Parse WifiStaticIpRsp payload
+/// Format: [status_byte]
+pub fn parse_wifi_static_ip_response(payload: &[u8]) -> Result<(), ProtocolError> {
+    let Some(&status_val) = payload.first() else {
+        return Err(ProtocolError::PayloadTooShort {
+            expected: 1,
+            actual: 0,
+        });
+    };
+
+    let status = Status::try_from(status_val as i32)
+        .map_err(|_| ProtocolError::UnknownStatus(status_val as i32))?;
+
+    if status != Status::Ok {
+        return Err(ProtocolError::DeviceError(status));
+    }
+
+    Ok(())
+}
+
 // ============================================================================
 // Crash dump types and parsers
 // ============================================================================
@@ -826,6 +1354,63 @@ pub fn parse_clear_crash_dump_response(payload: &[u8]) -> Result<bool, ProtocolE
     Ok(resp.cleared)
 }
 
+/// Core dump stored in the coredump partition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoreDumpInfo {
+    pub size: u32,
+    pub valid: bool,
+    /// Reset reason of the boot that crashed (e.g. "panic")
+    pub reset_reason: String,
+}
+
+/// Parse CoreDumpReadResponse payload
+/// Format: [status_byte][protobuf_CoreDumpReadResponse]
+pub fn parse_core_dump_read_response(payload: &[u8]) -> Result<CoreDumpInfo, ProtocolError> {
+    if payload.is_empty() {
+        return Err(ProtocolError::PayloadTooShort {
+            expected: 1,
+            actual: 0,
+        });
+    }
+
+    let status_val = payload[0] as i32;
+    let status =
+        Status::try_from(status_val).map_err(|_| ProtocolError::UnknownStatus(status_val))?;
+
+    if status != Status::Ok {
+        return Err(ProtocolError::DeviceError(status));
+    }
+
+    let resp = CoreDumpReadResponse::decode(&payload[1..])?;
+    Ok(CoreDumpInfo {
+        size: resp.size,
+        valid: resp.valid,
+        reset_reason: resp.reset_reason,
+    })
+}
+
+/// Parse ClearCoreDumpResponse payload
+/// Format: [status_byte][protobuf_ClearCoreDumpResponse]
+pub fn parse_clear_core_dump_response(payload: &[u8]) -> Result<bool, ProtocolError> {
+    if payload.is_empty() {
+        return Err(ProtocolError::PayloadTooShort {
+            expected: 1,
+            actual: 0,
+        });
+    }
+
+    let status_val = payload[0] as i32;
+    let status =
+        Status::try_from(status_val).map_err(|_| ProtocolError::UnknownStatus(status_val))?;
+
+    if status != Status::Ok {
+        return Err(ProtocolError::DeviceError(status));
+    }
+
+    let resp = ClearCoreDumpResponse::decode(&payload[1..])?;
+    Ok(resp.erased)
+}
+
 // ============================================================================
 // Memory profile types and parsers
 // ============================================================================
@@ -1005,3 +1590,101 @@ pub fn parse_set_sim_mode_response(payload: &[u8]) -> Result<CliSimModeState, Pr
         pad_index: resp.pad_index,
     })
 }
+
+// ============================================================================
+// Event subscription types and parsers
+// ============================================================================
+
+/// A state change pushed by the device
+#[derive(Debug, Clone)]
+pub enum CliEvent {
+    Mode(SystemMode),
+    Led(CliLedPattern),
+    Wifi { connected: bool },
+    Feature(CliFeatureState),
+    Touch { pad_index: u32 },
+}
+
+/// Event notification for CLI use
+#[derive(Debug, Clone)]
+pub struct CliEventNotification {
+    pub timestamp_ms: u32,
+    pub event: CliEvent,
+}
+
+/// Serialize SubscribeEventsRequest (bitmask of EventCategory, 0 unsubscribes)
+pub fn serialize_subscribe_events(categories: u32) -> Vec<u8> {
+    let req = SubscribeEventsRequest { categories };
+    req.encode_to_vec()
+}
+
+/// Parse SubscribeEventsResponse payload
+/// Format: [status_byte][protobuf_SubscribeEventsResponse]
+pub fn parse_subscribe_events_response(payload: &[u8]) -> Result<u32, ProtocolError> {
+    if payload.is_empty() {
+        return Err(ProtocolError::PayloadTooShort {
+            expected: 1,
+            actual: 0,
+        });
+    }
+
+    let status_val = payload[0] as i32;
+    let status =
+        Status::try_from(status_val).map_err(|_| ProtocolError::UnknownStatus(status_val))?;
+
+    if status != Status::Ok {
+        return Err(ProtocolError::DeviceError(status));
+    }
+
+    let resp = SubscribeEventsResponse::decode(&payload[1..])?;
+    Ok(resp.categories)
+}
+
+/// Parse EventNotification payload
+/// Format: [protobuf_EventNotification] (unsolicited, no status byte)
+pub fn parse_event_notification(payload: &[u8]) -> Result<CliEventNotification, ProtocolError> {
+    let notification = EventNotification::decode(payload)?;
+
+    let category = EventCategory::try_from(notification.category)
+        .map_err(|_| ProtocolError::UnknownEventCategory(notification.category))?;
+    let event = match category {
+        EventCategory::Mode => {
+            let mode = SystemMode::try_from(notification.mode)
+                .unwrap_or(SystemMode::Booting);
+            CliEvent::Mode(mode)
+        }
+        EventCategory::Led => {
+            let pattern = notification.led_pattern.ok_or(ProtocolError::PayloadTooShort {
+                expected: 1,
+                actual: 0,
+            })?;
+            CliEvent::Led(led_pattern_from_proto(pattern))
+        }
+        EventCategory::Wifi => CliEvent::Wifi {
+            connected: notification.wifi_connected,
+        },
+        EventCategory::Feature => {
+            let state = notification.feature.ok_or(ProtocolError::PayloadTooShort {
+                expected: 1,
+                actual: 0,
+            })?;
+            let feature = Feature::try_from(state.feature)
+                .map_err(|_| ProtocolError::UnknownFeature(state.feature))?;
+            CliEvent::Feature(CliFeatureState {
+                feature,
+                enabled: state.enabled,
+            })
+        }
+        EventCategory::Touch => CliEvent::Touch {
+            pad_index: notification.pad_index,
+        },
+        EventCategory::None => {
+            return Err(ProtocolError::UnknownEventCategory(notification.category))
+        }
+    };
+
+    Ok(CliEventNotification {
+        timestamp_ms: notification.timestamp_ms,
+        event,
+    })
+}
@@ -0,0 +1,141 @@
+//! Batched config requests
+//!
+//! `send_batch` packs several config requests into one `BatchReq` frame so
+//! they cost a single round trip. `ReplayTransport` hands the unpacked
+//! responses back one `send_command` at a time, so the regular command
+//! functions can parse and print them as if each had been sent alone.
+
+use super::{Frame, Transport};
+use crate::protocol::batch::{
+    parse_batch_response, serialize_batch_request, BATCH_MAX_COMMANDS, BATCH_MAX_COMMAND_PAYLOAD,
+};
+use crate::protocol::ConfigMsgType;
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+
+/// Send `commands` as one batch and return one response frame per command, in order
+pub fn send_batch(transport: &mut dyn Transport, commands: Vec<(u8, Vec<u8>)>) -> Result<Vec<Frame>> {
+    if commands.is_empty() {
+        anyhow::bail!("Batch is empty");
+    }
+    if commands.len() > BATCH_MAX_COMMANDS {
+        anyhow::bail!(
+            "Too many commands in batch: {} (firmware supports at most {})",
+            commands.len(),
+            BATCH_MAX_COMMANDS
+        );
+    }
+    if let Some((msg_type, payload)) = commands
+        .iter()
+        .find(|(_, payload)| payload.len() > BATCH_MAX_COMMAND_PAYLOAD)
+    {
+        anyhow::bail!(
+            "Payload of batched command 0x{:02X} too large: {} bytes (max {})",
+            msg_type,
+            payload.len(),
+            BATCH_MAX_COMMAND_PAYLOAD
+        );
+    }
+
+    let payload = serialize_batch_request(&commands);
+    let max_payload = transport.max_payload();
+    if payload.len() > max_payload {
+        anyhow::bail!(
+            "Batch too large: {} bytes (max {})",
+            payload.len(),
+            max_payload
+        );
+    }
+
+    let frame = transport
+        .send_command(ConfigMsgType::BatchReq as u8, &payload)
+        .context("Failed to send batch command")?;
+
+    if frame.msg_type != ConfigMsgType::BatchRsp as u8 {
+        anyhow::bail!(
+            "Unexpected response type: 0x{:02X}, expected 0x{:02X}",
+            frame.msg_type,
+            ConfigMsgType::BatchRsp as u8
+        );
+    }
+
+    let responses =
+        parse_batch_response(&frame.payload).context("Failed to parse batch response")?;
+    if responses.len() != commands.len() {
+        anyhow::bail!(
+            "Batch response has {} entries for {} commands",
+            responses.len(),
+            commands.len()
+        );
+    }
+
+    Ok(responses
+        .into_iter()
+        .map(|(msg_type, payload)| Frame { msg_type, payload })
+        .collect())
+}
+
+/// Transport that answers each command with the next response from a batch
+pub struct ReplayTransport {
+    responses: VecDeque<Frame>,
+}
+
+impl ReplayTransport {
+    pub fn new(responses: Vec<Frame>) -> Self {
+        Self {
+            responses: responses.into(),
+        }
+    }
+
+    fn next_response(&mut self) -> Result<Frame> {
+        self.responses
+            .pop_front()
+            .context("Command needs more than one round trip and cannot be batched")
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send_frame(&mut self, _msg_type: u8, _payload: &[u8]) -> Result<()> {
+        Ok(())
+    }
+
+    fn receive_frame(&mut self, _timeout_ms: u64) -> Result<Frame> {
+        self.next_response()
+    }
+
+    fn send_command(&mut self, _msg_type: u8, _payload: &[u8]) -> Result<Frame> {
+        self.next_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_batch_rejects_oversized() {
+        let mut replay = ReplayTransport::new(Vec::new());
+        let too_many = vec![(0x20, Vec::new()); BATCH_MAX_COMMANDS + 1];
+        assert!(send_batch(&mut replay, too_many).is_err());
+
+        let too_big = vec![(0x26, vec![0; BATCH_MAX_COMMAND_PAYLOAD + 1])];
+        assert!(send_batch(&mut replay, too_big).is_err());
+    }
+
+    #[test]
+    fn test_replay_transport_in_order() {
+        let mut replay = ReplayTransport::new(vec![
+            Frame {
+                msg_type: 0x23,
+                payload: vec![1],
+            },
+            Frame {
+                msg_type: 0x27,
+                payload: vec![2],
+            },
+        ]);
+        assert_eq!(replay.send_command(0x22, &[]).unwrap().msg_type, 0x23);
+        assert_eq!(replay.send_command(0x26, &[]).unwrap().msg_type, 0x27);
+        assert!(replay.send_command(0x28, &[]).is_err());
+    }
+}
@@ -0,0 +1,1067 @@
+//! BLE transport for DOMES CLI
+//!
+//! Handles Bluetooth Low Energy communication with the ESP32-S3 device.
+//! Uses btleplug for BLE Central role (connecting to the device as peripheral).
+//!
+//! The OS drops a BLE link that sits idle for about 30 seconds. With
+//! `set_keepalive`, a task on the transport's tokio runtime sends a
+//! `PingReq` whenever the link has been idle for the interval. Every write
+//! and read holds the `link` lock, so a ping never lands in the middle of a
+//! command.
+//!
+//! btleplug has no pairing API, so `pair` and `forget` are platform code:
+//! on Linux they go through BlueZ's D-Bus API, with a pairing agent that
+//! answers with the passkey; on macOS CoreBluetooth pairs by itself the
+//! first time an encrypted characteristic is used. Either way the OS keeps
+//! the bond, so later connections skip pairing. A write the device refuses
+//! for lack of authentication pairs ("just works") and is retried once.
+
+use super::frame::{encode_frame, Frame, FrameDecoder, FrameError, FRAME_OVERHEAD};
+use super::{FrameDecodeError, ResponseTimeout, TransportStats};
+use crate::protocol::ConfigMsgType;
+use anyhow::{bail, Context, Result};
+use btleplug::api::{
+    Central, Characteristic, Manager as _, Peripheral as _, ScanFilter, WriteType,
+};
+use btleplug::platform::{Adapter, Manager, Peripheral};
+use crossbeam_channel::{Receiver, Sender};
+use futures::stream::StreamExt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// OTA Service UUID: 12345678-1234-5678-1234-56789abcdef0
+const OTA_SERVICE_UUID: Uuid = Uuid::from_u128(0x12345678_1234_5678_1234_56789abcdef0);
+
+/// OTA Data Characteristic UUID: 12345678-1234-5678-1234-56789abcdef1 (Write)
+const OTA_DATA_CHAR_UUID: Uuid = Uuid::from_u128(0x12345678_1234_5678_1234_56789abcdef1);
+
+/// OTA Status Characteristic UUID: 12345678-1234-5678-1234-56789abcdef2 (Notify)
+const OTA_STATUS_CHAR_UUID: Uuid = Uuid::from_u128(0x12345678_1234_5678_1234_56789abcdef2);
+
+/// Default BLE operation timeout
+pub(super) const DEFAULT_TIMEOUT_MS: u64 = 5000;
+
+/// Default limit for each GATT handshake step (connect, service discovery,
+/// subscribe) once the device has been found
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How long a keepalive ping waits for its response
+const KEEPALIVE_PING_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long to wait for pairing, including typing in a passkey
+pub const PAIRING_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Target device identifier for BLE connection
+#[derive(Clone, Debug)]
+pub enum BleTarget {
+    /// Connect to device by advertised name (e.g., "DOMES-Pod")
+    Name(String),
+    /// Connect to device by Bluetooth address (e.g., "AA:BB:CC:DD:EE:FF")
+    Address(String),
+}
+
+impl BleTarget {
+    /// Parse a target string - if it contains colons, treat as address, otherwise name
+    pub fn parse(target: &str) -> Self {
+        if target.contains(':') && target.len() == 17 {
+            BleTarget::Address(target.to_string())
+        } else {
+            BleTarget::Name(target.to_string())
+        }
+    }
+}
+
+/// A DOMES device found by a BLE scan
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BleDeviceInfo {
+    /// Advertised name (empty if the device advertises none)
+    pub name: String,
+    /// Bluetooth address
+    pub address: String,
+    /// Latest signal strength in dBm, if the adapter reported one
+    ///
+    /// `None` sorts after every measured device in scan results and never
+    /// passes a `min_rssi` filter: an unmeasured signal can't be shown to
+    /// be strong enough.
+    pub rssi: Option<i16>,
+    /// Advertised service UUIDs
+    pub services: Vec<Uuid>,
+}
+
+/// Bluetooth address prefix to scan for, e.g. Espressif's OUI `24:0A:C4`
+///
+/// Stored as uppercase hex digits, so separators and case do not matter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MacPrefix(String);
+
+impl MacPrefix {
+    /// Whether `address` (e.g. "24:0A:C4:12:34:56") starts with the prefix
+    pub fn matches(&self, address: &str) -> bool {
+        let digits: String = address
+            .chars()
+            .filter(char::is_ascii_hexdigit)
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        digits.starts_with(&self.0)
+    }
+}
+
+impl std::str::FromStr for MacPrefix {
+    type Err = String;
+
+    /// Accepts `24:0A:C4`, `24-0a-c4` or `240AC4`
+    fn from_str(prefix: &str) -> std::result::Result<Self, String> {
+        let mut digits = String::new();
+        for c in prefix.chars() {
+            match c {
+                ':' | '-' => {}
+                c if c.is_ascii_hexdigit() => digits.push(c.to_ascii_uppercase()),
+                _ => return Err(format!("invalid MAC prefix '{}': not hex", prefix)),
+            }
+        }
+        if digits.is_empty() || digits.len() > 12 {
+            return Err(format!("invalid MAC prefix '{}': needs 1-12 hex digits", prefix));
+        }
+        Ok(Self(digits))
+    }
+}
+
+/// Sort scan results strongest signal first; devices without RSSI go last
+fn sort_by_signal(devices: &mut [BleDeviceInfo]) {
+    devices.sort_by_key(|d| std::cmp::Reverse(d.rssi));
+}
+
+/// Drop devices weaker than `min_rssi` dBm, and those without RSSI
+fn retain_min_rssi(devices: &mut Vec<BleDeviceInfo>, min_rssi: i16) {
+    devices.retain(|d| d.rssi.is_some_and(|rssi| rssi >= min_rssi));
+}
+
+/// BLE transport for communicating with DOMES device
+pub struct BleTransport {
+    runtime: Runtime,
+    peripheral: Peripheral,
+    data_char: Characteristic,
+    status_char: Characteristic,
+    rx_receiver: Receiver<Vec<u8>>,
+    decoder: FrameDecoder,
+    device_name: String,
+    auto_reconnect: bool,
+    connect_timeout: Duration,
+    stats: TransportStats,
+    /// Held for each write and read, so keepalive pings wait for commands
+    link: Arc<tokio::sync::Mutex<()>>,
+    /// When the link last carried a frame
+    last_activity: Arc<Mutex<Instant>>,
+    /// Pongs the keepalive gave up on; `receive_frame` drops them if they arrive late
+    late_pongs: Arc<AtomicUsize>,
+    keepalive: Option<Keepalive>,
+}
+
+/// Running keepalive task
+struct Keepalive {
+    interval: Duration,
+    task: JoinHandle<()>,
+}
+
+impl BleTransport {
+    /// Connect to a DOMES device via BLE
+    ///
+    /// # Arguments
+    /// * `target` - Device name or address to connect to
+    /// * `scan_timeout` - How long to scan for the device
+    /// * `auto_reconnect` - Whether to auto-reconnect on disconnect
+    pub fn connect(
+        target: BleTarget,
+        scan_timeout: Duration,
+        auto_reconnect: bool,
+    ) -> Result<Self> {
+        Self::connect_with_timeouts(target, scan_timeout, DEFAULT_CONNECT_TIMEOUT, auto_reconnect)
+    }
+
+    /// Connect to a DOMES device via BLE, limiting each handshake step
+    ///
+    /// A device can keep advertising yet never complete the GATT connection;
+    /// `connect_timeout` bounds the connect, service discovery and subscribe
+    /// calls (each, and again on reconnect) so they fail instead of hanging.
+    pub fn connect_with_timeouts(
+        target: BleTarget,
+        scan_timeout: Duration,
+        connect_timeout: Duration,
+        auto_reconnect: bool,
+    ) -> Result<Self> {
+        let runtime = Runtime::new().context("Failed to create tokio runtime")?;
+
+        let (adapter, peripheral, device_name) = runtime.block_on(async {
+            // Get BLE manager and adapter
+            let manager = Manager::new()
+                .await
+                .context("Failed to create BLE manager")?;
+
+            let adapters = manager
+                .adapters()
+                .await
+                .context("Failed to get BLE adapters")?;
+
+            let adapter = adapters
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("No Bluetooth adapter found"))?;
+
+            // Start scanning
+            adapter
+                .start_scan(ScanFilter::default())
+                .await
+                .context("Failed to start BLE scan")?;
+
+            // Find the target device
+            let (peripheral, device_name) =
+                find_device(&adapter, &target, scan_timeout).await?;
+
+            // Stop scanning
+            let _ = adapter.stop_scan().await;
+
+            // Connect to the device
+            handshake(connect_timeout, "connect", peripheral.connect())
+                .await
+                .context("Failed to connect to BLE device")?;
+
+            // Discover services
+            handshake(connect_timeout, "service discovery", peripheral.discover_services())
+                .await
+                .context("Failed to discover BLE services")?;
+
+            Ok::<_, anyhow::Error>((adapter, peripheral, device_name))
+        })?;
+
+        // Find the OTA characteristics
+        let (data_char, status_char) = find_ota_characteristics(&peripheral)?;
+
+        // Subscribe to notifications on status characteristic
+        runtime.block_on(async {
+            handshake(connect_timeout, "subscribe", peripheral.subscribe(&status_char))
+                .await
+                .context("Failed to subscribe to status notifications")
+        })?;
+
+        // Set up notification listener
+        let rx_receiver = setup_notification_listener(&runtime, &peripheral)?;
+
+        // adapter and target are not stored as they're not needed after connection
+        let _ = adapter;
+
+        Ok(Self {
+            runtime,
+            peripheral,
+            data_char,
+            status_char,
+            rx_receiver,
+            decoder: FrameDecoder::new(),
+            device_name,
+            auto_reconnect,
+            connect_timeout,
+            stats: TransportStats::default(),
+            link: Arc::new(tokio::sync::Mutex::new(())),
+            last_activity: Arc::new(Mutex::new(Instant::now())),
+            late_pongs: Arc::new(AtomicUsize::new(0)),
+            keepalive: None,
+        })
+    }
+
+    /// Scan for nearby DOMES devices, strongest signal first
+    ///
+    /// Each device is listed once per address, with the RSSI from its most
+    /// recent advertisement during the scan.
+    pub fn scan(timeout: Duration) -> Result<Vec<BleDeviceInfo>> {
+        Self::scan_filtered(timeout, None, None)
+    }
+
+    /// `scan`, keeping only devices at least as strong as `min_rssi` dBm
+    ///
+    /// Devices the adapter reported no RSSI for are left out.
+    pub fn scan_with_min_rssi(timeout: Duration, min_rssi: i16) -> Result<Vec<BleDeviceInfo>> {
+        Self::scan_filtered(timeout, Some(min_rssi), None)
+    }
+
+    /// `scan` with optional filters
+    ///
+    /// With `mac_prefix`, every device whose address starts with it is
+    /// listed instead, DOMES or not: a pod in bootloader mode advertises
+    /// neither the name nor the service. `min_rssi` works as in
+    /// `scan_with_min_rssi`.
+    pub fn scan_filtered(
+        timeout: Duration,
+        min_rssi: Option<i16>,
+        mac_prefix: Option<&MacPrefix>,
+    ) -> Result<Vec<BleDeviceInfo>> {
+        let runtime = Runtime::new().context("Failed to create tokio runtime")?;
+
+        runtime.block_on(async {
+            let manager = Manager::new()
+                .await
+                .context("Failed to create BLE manager")?;
+
+            let adapters = manager
+                .adapters()
+                .await
+                .context("Failed to get BLE adapters")?;
+
+            let adapter = adapters
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("No Bluetooth adapter found"))?;
+
+            // Start scanning
+            adapter
+                .start_scan(ScanFilter::default())
+                .await
+                .context("Failed to start BLE scan")?;
+
+            let start = Instant::now();
+            let mut devices: Vec<BleDeviceInfo> = Vec::new();
+
+            while start.elapsed() < timeout {
+                let peripherals = adapter
+                    .peripherals()
+                    .await
+                    .context("Failed to get peripherals")?;
+
+                for p in peripherals {
+                    let addr = p.address().to_string();
+                    let Ok(Some(props)) = p.properties().await else {
+                        continue;
+                    };
+
+                    // Already listed: only refresh the signal strength
+                    if let Some(known) = devices.iter_mut().find(|d| d.address == addr) {
+                        if props.rssi.is_some() {
+                            known.rssi = props.rssi;
+                        }
+                        continue;
+                    }
+
+                    // Check if this device advertises the OTA service or has DOMES in name
+                    let name = props.local_name.unwrap_or_default();
+                    let wanted = match mac_prefix {
+                        Some(prefix) => prefix.matches(&addr),
+                        None => name.contains("DOMES") || props.services.contains(&OTA_SERVICE_UUID),
+                    };
+
+                    if wanted {
+                        devices.push(BleDeviceInfo {
+                            name,
+                            address: addr,
+                            rssi: props.rssi,
+                            services: props.services,
+                        });
+                    }
+                }
+
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+
+            let _ = adapter.stop_scan().await;
+
+            if let Some(min_rssi) = min_rssi {
+                retain_min_rssi(&mut devices, min_rssi);
+            }
+            sort_by_signal(&mut devices);
+            Ok(devices)
+        })
+    }
+
+    /// Get the connected device name
+    pub fn device_name(&self) -> &str {
+        &self.device_name
+    }
+
+    /// Get the connected device address
+    pub fn device_address(&self) -> String {
+        self.peripheral.address().to_string()
+    }
+
+    /// Check if still connected
+    pub fn is_connected(&self) -> bool {
+        self.runtime
+            .block_on(self.peripheral.is_connected())
+            .unwrap_or(false)
+    }
+
+
+    /// Send a frame to the device
+    pub fn send_frame(&mut self, msg_type: u8, payload: &[u8]) -> Result<()> {
+        let link = self.link.clone();
+        let _link = link.blocking_lock();
+        self.ensure_connected()?;
+
+        let frame = encode_frame(msg_type, payload)?;
+
+        let write = |transport: &Self| {
+            transport.runtime.block_on(transport.peripheral.write(
+                &transport.data_char,
+                &frame,
+                WriteType::WithoutResponse,
+            ))
+        };
+        match write(self) {
+            Err(e) if requires_pairing(&e) => {
+                progress!("{} requires pairing, pairing...", self.device_name);
+                self.pair(None)
+                    .context("Failed to pair (try 'ble pair --passkey <PASSKEY>')")?;
+                write(self).context("Failed to write to BLE characteristic")?;
+            }
+            result => result.context("Failed to write to BLE characteristic")?,
+        }
+        self.stats.record_tx(frame.len());
+        self.touch();
+
+        Ok(())
+    }
+
+    /// Discard notifications received but not read yet, and any partial frame
+    pub fn flush(&mut self) {
+        let link = self.link.clone();
+        let _link = link.blocking_lock();
+        while self.rx_receiver.try_recv().is_ok() {}
+        self.decoder.reset();
+    }
+
+    /// Receive a frame from the device with timeout
+    pub fn receive_frame(&mut self, timeout_ms: u64) -> Result<Frame> {
+        let link = self.link.clone();
+        let _link = link.blocking_lock();
+        let result = self.receive_unlocked(timeout_ms);
+        self.touch();
+        result
+    }
+
+    fn receive_unlocked(&mut self, timeout_ms: u64) -> Result<Frame> {
+        let timeout = Duration::from_millis(timeout_ms);
+        let start = Instant::now();
+
+        loop {
+            // Bytes left over from a bad frame may hold the next one
+            while let Some(result) = self.decoder.take_buffered() {
+                if let Some(frame) = self.accept_frame(result)? {
+                    return Ok(frame);
+                }
+            }
+
+            let remaining = timeout.saturating_sub(start.elapsed());
+            if remaining.is_zero() {
+                return Err(ResponseTimeout.into());
+            }
+
+            match self.rx_receiver.recv_timeout(remaining) {
+                Ok(data) => {
+                    for byte in data {
+                        if let Some(result) = self.decoder.feed_byte(byte) {
+                            if let Some(frame) = self.accept_frame(result)? {
+                                return Ok(frame);
+                            }
+                        }
+                    }
+                }
+                Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
+                    return Err(ResponseTimeout.into());
+                }
+                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
+                    if self.auto_reconnect {
+                        self.reconnect()?;
+                    } else {
+                        bail!("BLE connection lost");
+                    }
+                }
+            }
+        }
+    }
+
+    /// A decoded frame to return, or `None` for the pong of a keepalive
+    /// ping that timed out
+    fn accept_frame(&mut self, result: Result<Frame, FrameError>) -> Result<Option<Frame>> {
+        let frame = result.map_err(FrameDecodeError)?;
+        self.stats.record_rx(FRAME_OVERHEAD + frame.payload.len());
+        if frame.msg_type == ConfigMsgType::PingRsp.as_u8() && take_one(&self.late_pongs) {
+            return Ok(None);
+        }
+        Ok(Some(frame))
+    }
+
+    /// Send a command and wait for response
+    pub fn send_command(&mut self, msg_type: u8, payload: &[u8]) -> Result<Frame> {
+        self.send_frame(msg_type, payload)?;
+        self.receive_frame(DEFAULT_TIMEOUT_MS)
+    }
+
+    /// Pair (bond) with the connected device
+    ///
+    /// `None` uses "just works" pairing; `Some(passkey)` uses passkey entry
+    /// with the six-digit passkey the pod displays. Pairing with a device
+    /// that is already bonded succeeds without doing anything.
+    pub fn pair(&mut self, passkey: Option<u32>) -> Result<()> {
+        pair_device(self, passkey)
+    }
+
+    /// Remove the bond with the device at `address`, so the next connection
+    /// has to pair again; false if it was not known
+    pub fn forget(address: &str) -> Result<bool> {
+        forget_device(address)
+    }
+
+    /// Frames and bytes sent and received so far
+    ///
+    /// Keepalive pings are not counted.
+    pub fn stats(&self) -> TransportStats {
+        self.stats
+    }
+
+    /// Ping the device whenever the link has been idle for `interval`, or
+    /// stop pinging with `None`
+    pub fn set_keepalive(&mut self, interval: Option<Duration>) {
+        if let Some(keepalive) = self.keepalive.take() {
+            keepalive.task.abort();
+        }
+        if let Some(interval) = interval {
+            let task = self.spawn_keepalive(interval);
+            self.keepalive = Some(Keepalive { interval, task });
+        }
+    }
+
+    fn spawn_keepalive(&self, interval: Duration) -> JoinHandle<()> {
+        let peripheral = self.peripheral.clone();
+        let data_char = self.data_char.clone();
+        let rx = self.rx_receiver.clone();
+        let link = self.link.clone();
+        let last_activity = self.last_activity.clone();
+        let late_pongs = self.late_pongs.clone();
+
+        self.runtime.spawn(async move {
+            loop {
+                let idle = last_activity.lock().unwrap().elapsed();
+                if idle < interval {
+                    tokio::time::sleep(interval - idle).await;
+                    continue;
+                }
+
+                let _link = link.lock().await;
+                // A command may have run while this waited for the lock
+                if last_activity.lock().unwrap().elapsed() >= interval {
+                    // A missed pong is not an error here: the next command
+                    // finds out whether the link is still up
+                    if keepalive_ping(&peripheral, &data_char, &rx).await.is_err() {
+                        late_pongs.fetch_add(1, Ordering::Relaxed);
+                    }
+                    *last_activity.lock().unwrap() = Instant::now();
+                }
+            }
+        })
+    }
+
+    /// Record that the link just carried a frame
+    fn touch(&self) {
+        *self.last_activity.lock().unwrap() = Instant::now();
+    }
+
+    /// Ensure we're still connected, reconnect if needed
+    fn ensure_connected(&mut self) -> Result<()> {
+        if !self.is_connected() {
+            if self.auto_reconnect {
+                progress!("BLE connection lost, reconnecting...");
+                self.reconnect()?;
+            } else {
+                bail!("BLE connection lost");
+            }
+        }
+        Ok(())
+    }
+
+    /// Reconnect to the device
+    pub fn reconnect(&mut self) -> Result<()> {
+        let timeout = self.connect_timeout;
+        self.runtime.block_on(async {
+            // Try to connect again
+            handshake(timeout, "connect", self.peripheral.connect())
+                .await
+                .context("Failed to reconnect to BLE device")?;
+
+            // Re-subscribe to notifications
+            handshake(timeout, "subscribe", self.peripheral.subscribe(&self.status_char))
+                .await
+                .context("Failed to re-subscribe to notifications")?;
+
+            Ok::<(), anyhow::Error>(())
+        })?;
+
+        // Set up new notification listener
+        self.rx_receiver = setup_notification_listener(&self.runtime, &self.peripheral)?;
+
+        // The keepalive task reads the old listener's channel
+        if let Some(interval) = self.keepalive.as_ref().map(|k| k.interval) {
+            self.set_keepalive(Some(interval));
+        }
+
+        progress!("Reconnected to {}", self.device_name);
+        Ok(())
+    }
+}
+
+/// Await one GATT handshake step, failing if it takes longer than `timeout`
+async fn handshake<T>(
+    timeout: Duration,
+    step: &str,
+    future: impl std::future::Future<Output = btleplug::Result<T>>,
+) -> Result<T> {
+    match tokio::time::timeout(timeout, future).await {
+        Ok(result) => Ok(result?),
+        Err(_) => bail!(
+            "Connection handshake timed out after {} ms ({})",
+            timeout.as_millis(),
+            step
+        ),
+    }
+}
+
+/// Send one `PingReq` and wait for its `PingRsp` on the notification channel
+async fn keepalive_ping(
+    peripheral: &Peripheral,
+    data_char: &Characteristic,
+    rx: &Receiver<Vec<u8>>,
+) -> Result<()> {
+    let frame = encode_frame(ConfigMsgType::PingReq.as_u8(), &[])?;
+    peripheral
+        .write(data_char, &frame, WriteType::WithoutResponse)
+        .await
+        .context("Failed to write keepalive ping")?;
+
+    let mut decoder = FrameDecoder::new();
+    let deadline = Instant::now() + KEEPALIVE_PING_TIMEOUT;
+    while Instant::now() < deadline {
+        // The channel is blocking; poll it so the runtime thread is not held
+        while let Ok(data) = rx.try_recv() {
+            for byte in data {
+                if let Some(result) = decoder.feed_byte(byte) {
+                    if matches!(result, Ok(frame) if frame.msg_type == ConfigMsgType::PingRsp.as_u8())
+                    {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    Err(ResponseTimeout.into())
+}
+
+/// Decrement `counter` if it is above zero; returns whether it was
+fn take_one(counter: &AtomicUsize) -> bool {
+    counter
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+        .is_ok()
+}
+
+/// Find a device by name or address
+async fn find_device(
+    adapter: &Adapter,
+    target: &BleTarget,
+    timeout: Duration,
+) -> Result<(Peripheral, String)> {
+    let start = Instant::now();
+
+    while start.elapsed() < timeout {
+        let peripherals = adapter
+            .peripherals()
+            .await
+            .context("Failed to get peripherals")?;
+
+        for p in peripherals {
+            if let Ok(Some(props)) = p.properties().await {
+                let name = props.local_name.clone().unwrap_or_default();
+                let addr = p.address().to_string();
+
+                let matches = match target {
+                    BleTarget::Name(target_name) => {
+                        name.contains(target_name) || name == *target_name
+                    }
+                    BleTarget::Address(target_addr) => {
+                        addr.eq_ignore_ascii_case(target_addr)
+                    }
+                };
+
+                if matches {
+                    return Ok((p, name));
+                }
+            }
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    match target {
+        BleTarget::Name(name) => bail!("Device '{}' not found after {}s", name, timeout.as_secs()),
+        BleTarget::Address(addr) => {
+            bail!("Device {} not found after {}s", addr, timeout.as_secs())
+        }
+    }
+}
+
+/// Find the OTA service characteristics
+fn find_ota_characteristics(peripheral: &Peripheral) -> Result<(Characteristic, Characteristic)> {
+    let services = peripheral.services();
+
+    let ota_service = services
+        .iter()
+        .find(|s| s.uuid == OTA_SERVICE_UUID)
+        .ok_or_else(|| {
+            anyhow::anyhow!("OTA service not found. Is the device running DOMES firmware?")
+        })?;
+
+    let data_char = ota_service
+        .characteristics
+        .iter()
+        .find(|c| c.uuid == OTA_DATA_CHAR_UUID)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("OTA Data characteristic not found"))?;
+
+    let status_char = ota_service
+        .characteristics
+        .iter()
+        .find(|c| c.uuid == OTA_STATUS_CHAR_UUID)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("OTA Status characteristic not found"))?;
+
+    Ok((data_char, status_char))
+}
+
+/// Set up a background task to listen for notifications and forward to channel
+fn setup_notification_listener(
+    runtime: &Runtime,
+    peripheral: &Peripheral,
+) -> Result<Receiver<Vec<u8>>> {
+    let (tx, rx): (Sender<Vec<u8>>, Receiver<Vec<u8>>) = crossbeam_channel::bounded(32);
+
+    let mut notification_stream = runtime
+        .block_on(peripheral.notifications())
+        .context("Failed to get notification stream")?;
+
+    runtime.spawn(async move {
+        while let Some(notification) = notification_stream.next().await {
+            // Exit once the receiver has been dropped
+            if notification.uuid == OTA_STATUS_CHAR_UUID && tx.send(notification.value).is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(rx)
+}
+
+/// Whether the device refused an operation because the link is not paired
+fn requires_pairing(error: &btleplug::Error) -> bool {
+    // BlueZ reports a D-Bus error name, CoreBluetooth an ATT error message
+    let message = format!("{} {:?}", error, error).to_ascii_lowercase();
+    [
+        "notauthorized",
+        "insufficient authentication",
+        "insufficient encryption",
+        "authentication is insufficient",
+        "encryption is insufficient",
+    ]
+    .iter()
+    .any(|reason| message.contains(reason))
+}
+
+#[cfg(target_os = "linux")]
+fn pair_device(transport: &mut BleTransport, passkey: Option<u32>) -> Result<()> {
+    bluez::pair(&transport.device_address(), passkey, PAIRING_TIMEOUT)
+}
+
+#[cfg(target_os = "linux")]
+fn forget_device(address: &str) -> Result<bool> {
+    bluez::forget(address)
+}
+
+/// CoreBluetooth pairs when an encrypted characteristic is first used and
+/// asks for a passkey in its own dialog, so writing a ping is enough to pair
+///
+/// Called from `send_frame` with the link locked, so it writes directly.
+#[cfg(target_os = "macos")]
+fn pair_device(transport: &mut BleTransport, passkey: Option<u32>) -> Result<()> {
+    if passkey.is_some() {
+        bail!("On macOS, enter the passkey in the system pairing dialog instead of --passkey");
+    }
+    let ping = encode_frame(ConfigMsgType::PingReq.as_u8(), &[])?;
+    let write = transport
+        .peripheral
+        .write(&transport.data_char, &ping, WriteType::WithResponse);
+    transport
+        .runtime
+        .block_on(tokio::time::timeout(PAIRING_TIMEOUT, write))
+        .context("Pairing did not finish in time")?
+        .context("Failed to pair")?;
+    // Nothing waits for the pong
+    transport.late_pongs.fetch_add(1, Ordering::Relaxed);
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn forget_device(_address: &str) -> Result<bool> {
+    bail!("On macOS, remove the pod in System Settings > Bluetooth")
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn pair_device(_transport: &mut BleTransport, _passkey: Option<u32>) -> Result<()> {
+    bail!("BLE pairing is not supported on this platform")
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn forget_device(_address: &str) -> Result<bool> {
+    bail!("BLE pairing is not supported on this platform")
+}
+
+/// Pairing through BlueZ's D-Bus API
+#[cfg(target_os = "linux")]
+mod bluez {
+    use anyhow::{bail, Context, Result};
+    use dbus::arg::{prop_cast, PropMap, Variant};
+    use dbus::channel::{BusType, Channel};
+    use dbus::message::MessageType;
+    use dbus::{Message, Path};
+    use std::collections::HashMap;
+    use std::ffi::CString;
+    use std::time::{Duration, Instant};
+
+    const BLUEZ: &str = "org.bluez";
+    const DEVICE: &str = "org.bluez.Device1";
+    const AGENT_MANAGER: &str = "org.bluez.AgentManager1";
+    /// Where the pairing agent answers BlueZ's requests
+    const AGENT_PATH: &str = "/org/domes/pairing_agent";
+    const CALL_TIMEOUT: Duration = Duration::from_secs(5);
+
+    type ManagedObjects = HashMap<Path<'static>, HashMap<String, PropMap>>;
+
+    /// Pair with the device at `address`, then trust it so BlueZ reconnects
+    /// without asking again
+    pub fn pair(address: &str, passkey: Option<u32>, timeout: Duration) -> Result<()> {
+        let bus = system_bus()?;
+        let (path, device) = find_device(&bus, address)?
+            .with_context(|| format!("BlueZ does not know device {}", address))?;
+        if prop_cast::<bool>(&device, "Paired") == Some(&true) {
+            return Ok(());
+        }
+
+        // Passkey entry needs an agent that can type; without one BlueZ
+        // falls back to "just works"
+        let capability = if passkey.is_some() { "KeyboardOnly" } else { "NoInputNoOutput" };
+        call(&bus, "/org/bluez", AGENT_MANAGER, "RegisterAgent", |m| {
+            m.append2(Path::from(AGENT_PATH), capability)
+        })?;
+        let paired = pair_with_agent(&bus, &path, passkey, timeout);
+        let _ = call(&bus, "/org/bluez", AGENT_MANAGER, "UnregisterAgent", |m| {
+            m.append1(Path::from(AGENT_PATH))
+        });
+        paired?;
+
+        call(&bus, &path, "org.freedesktop.DBus.Properties", "Set", |m| {
+            m.append3(DEVICE, "Trusted", Variant(true))
+        })?;
+        Ok(())
+    }
+
+    /// Remove the device at `address` and its bond; false if BlueZ does not know it
+    pub fn forget(address: &str) -> Result<bool> {
+        let bus = system_bus()?;
+        let Some((path, device)) = find_device(&bus, address)? else {
+            return Ok(false);
+        };
+        let adapter = prop_cast::<Path>(&device, "Adapter")
+            .cloned()
+            .with_context(|| format!("BlueZ lists no adapter for {}", address))?;
+        call(&bus, &adapter, "org.bluez.Adapter1", "RemoveDevice", |m| m.append1(path))?;
+        Ok(true)
+    }
+
+    fn system_bus() -> Result<Channel> {
+        Channel::get_private(BusType::System).context("Failed to connect to the D-Bus system bus")
+    }
+
+    /// Call a BlueZ method and wait for its reply
+    fn call(
+        bus: &Channel,
+        path: &str,
+        interface: &str,
+        method: &str,
+        args: impl FnOnce(Message) -> Message,
+    ) -> Result<Message> {
+        let message = Message::new_method_call(BLUEZ, path, interface, method)
+            .map_err(|e| anyhow::anyhow!(e))?;
+        bus.send_with_reply_and_block(args(message), CALL_TIMEOUT)
+            .with_context(|| format!("BlueZ {}.{} failed", interface, method))
+    }
+
+    /// The BlueZ object path and properties of the device with `address`
+    fn find_device(bus: &Channel, address: &str) -> Result<Option<(Path<'static>, PropMap)>> {
+        let object_manager = "org.freedesktop.DBus.ObjectManager";
+        let reply = call(bus, "/", object_manager, "GetManagedObjects", |m| m)?;
+        let objects: ManagedObjects = reply.read1().context("Invalid reply from BlueZ")?;
+        Ok(objects.into_iter().find_map(|(path, mut interfaces)| {
+            let device = interfaces.remove(DEVICE)?;
+            prop_cast::<String>(&device, "Address")
+                .is_some_and(|a| a.eq_ignore_ascii_case(address))
+                .then_some((path, device))
+        }))
+    }
+
+    /// Call `Pair`, answering the agent requests BlueZ makes meanwhile
+    ///
+    /// BlueZ only replies to `Pair` after the agent has answered, so the
+    /// call cannot block like the others.
+    fn pair_with_agent(
+        bus: &Channel,
+        path: &str,
+        passkey: Option<u32>,
+        timeout: Duration,
+    ) -> Result<()> {
+        let message = Message::new_method_call(BLUEZ, path, DEVICE, "Pair")
+            .map_err(|e| anyhow::anyhow!(e))?;
+        let serial = bus
+            .send(message)
+            .map_err(|()| anyhow::anyhow!("Failed to send BlueZ pairing request"))?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                let _ = call(bus, path, DEVICE, "CancelPairing", |m| m);
+                bail!("Pairing did not finish within {} s", timeout.as_secs());
+            }
+            bus.read_write(Some(remaining.min(Duration::from_millis(100))))
+                .map_err(|()| anyhow::anyhow!("Lost the D-Bus connection while pairing"))?;
+            while let Some(mut message) = bus.pop_message() {
+                match message.msg_type() {
+                    MessageType::MethodCall => {
+                        let _ = bus.send(agent_reply(&message, passkey));
+                    }
+                    _ if message.get_reply_serial() != Some(serial) => {}
+                    MessageType::Error => {
+                        let error = message.as_result().err();
+                        let name = error.as_ref().and_then(|e| e.name());
+                        if name == Some("org.bluez.Error.AlreadyExists") {
+                            return Ok(());
+                        }
+                        let reason = error
+                            .as_ref()
+                            .and_then(|e| e.message())
+                            .unwrap_or("unknown error");
+                        bail!("Pairing failed: {}", reason);
+                    }
+                    _ => return Ok(()),
+                }
+            }
+        }
+    }
+
+    /// The pairing agent's answer to a request from BlueZ
+    fn agent_reply(request: &Message, passkey: Option<u32>) -> Message {
+        match (request.member().as_deref(), passkey) {
+            (Some("RequestPasskey"), Some(passkey)) => request.method_return().append1(passkey),
+            (
+                Some(
+                    "RequestAuthorization" | "RequestConfirmation" | "AuthorizeService"
+                    | "Release" | "Cancel",
+                ),
+                _,
+            ) => request.method_return(),
+            _ => request.error(
+                &"org.bluez.Error.Rejected".into(),
+                &CString::new("Not supported by domes-cli").unwrap(),
+            ),
+        }
+    }
+}
+
+impl Drop for BleTransport {
+    fn drop(&mut self) {
+        if let Some(keepalive) = self.keepalive.take() {
+            keepalive.task.abort();
+        }
+        // Disconnect cleanly
+        self.runtime.block_on(async {
+            let _ = self.peripheral.unsubscribe(&self.status_char).await;
+            let _ = self.peripheral.disconnect().await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_results_sorted_by_signal() {
+        let device = |address: &str, rssi| BleDeviceInfo {
+            name: String::new(),
+            address: address.to_string(),
+            rssi,
+            services: vec![OTA_SERVICE_UUID],
+        };
+        let mut devices = vec![
+            device("AA:00:00:00:00:01", Some(-80)),
+            device("AA:00:00:00:00:02", None),
+            device("AA:00:00:00:00:03", Some(-45)),
+        ];
+        sort_by_signal(&mut devices);
+        let order: Vec<&str> = devices.iter().map(|d| d.address.as_str()).collect();
+        assert_eq!(order, ["AA:00:00:00:00:03", "AA:00:00:00:00:01", "AA:00:00:00:00:02"]);
+    }
+
+    #[test]
+    fn test_min_rssi_drops_devices_without_rssi() {
+        let device = |address: &str, rssi| BleDeviceInfo {
+            name: String::new(),
+            address: address.to_string(),
+            rssi,
+            services: vec![OTA_SERVICE_UUID],
+        };
+        let mut devices = vec![
+            device("AA:00:00:00:00:01", Some(-80)),
+            device("AA:00:00:00:00:02", None),
+            device("AA:00:00:00:00:03", Some(-70)),
+            device("AA:00:00:00:00:04", Some(-45)),
+        ];
+        retain_min_rssi(&mut devices, -70);
+        let kept: Vec<&str> = devices.iter().map(|d| d.address.as_str()).collect();
+        assert_eq!(kept, ["AA:00:00:00:00:03", "AA:00:00:00:00:04"]);
+
+        // The weakest possible threshold still needs a measurement
+        let mut unmeasured = vec![device("AA:00:00:00:00:02", None)];
+        retain_min_rssi(&mut unmeasured, i16::MIN);
+        assert!(unmeasured.is_empty());
+    }
+
+    #[test]
+    fn test_pairing_needed_only_for_authentication_errors() {
+        let error = |message: &str| btleplug::Error::Other(message.to_string().into());
+        assert!(requires_pairing(&error("org.bluez.Error.NotAuthorized: Not Authorized")));
+        assert!(requires_pairing(&error("Authentication is insufficient.")));
+        assert!(!requires_pairing(&error("org.bluez.Error.Failed: Not connected")));
+        assert!(!requires_pairing(&btleplug::Error::NotConnected));
+    }
+
+    #[test]
+    fn test_mac_prefix_matching() {
+        let espressif: MacPrefix = "24:0a:c4".parse().unwrap();
+        assert_eq!(espressif, "240AC4".parse().unwrap());
+        assert_eq!(espressif, "24-0A-C4".parse().unwrap());
+        assert!(espressif.matches("24:0A:C4:12:34:56"));
+        assert!(!espressif.matches("94:A9:90:0A:EA:52"));
+        // Half an octet narrows further
+        assert!("94:A".parse::<MacPrefix>().unwrap().matches("94:a9:90:0a:ea:52"));
+
+        assert!("".parse::<MacPrefix>().is_err());
+        assert!("24:0G".parse::<MacPrefix>().is_err());
+        assert!("24:0A:C4:12:34:56:78".parse::<MacPrefix>().is_err());
+    }
+}
@@ -0,0 +1,349 @@
+//! Verbose transport wrapper
+//!
+//! `DebugTransport` wraps any transport and logs each frame to stderr with a
+//! monotonic timestamp. Responses are annotated with the round-trip time
+//! since the preceding send, and the samples are kept so an aggregate can be
+//! printed when the command finishes.
+//!
+//! Sends and receives are colored differently when stderr is a terminal
+//! (unless `NO_COLOR` is set). With `hex_dump` (`-vv`) each whole frame,
+//! framing and CRC included, is dumped under its log line.
+
+use super::frame::encode_frame;
+use super::{Frame, Transport, TransportStats, TransportType};
+use anyhow::Result;
+use std::io::IsTerminal;
+use std::time::{Duration, Instant};
+
+const CYAN: &str = "\x1b[36m";
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// Bytes per hex dump line
+const HEX_DUMP_WIDTH: usize = 16;
+
+/// Aggregate request/response round-trip latency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyStats {
+    pub count: usize,
+    pub min: Duration,
+    pub avg: Duration,
+    pub max: Duration,
+    pub p95: Duration,
+}
+
+impl LatencyStats {
+    /// Compute stats from round-trip samples (None if there are no samples)
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let count = sorted.len();
+        let total: Duration = sorted.iter().sum();
+        // Nearest-rank percentile
+        let p95_rank = (count * 95).div_ceil(100);
+
+        Some(Self {
+            count,
+            min: sorted[0],
+            avg: total / count as u32,
+            max: sorted[count - 1],
+            p95: sorted[p95_rank - 1],
+        })
+    }
+}
+
+impl std::fmt::Display for LatencyStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} round trips: min {:.1}ms / avg {:.1}ms / max {:.1}ms / p95 {:.1}ms",
+            self.count,
+            as_ms(self.min),
+            as_ms(self.avg),
+            as_ms(self.max),
+            as_ms(self.p95)
+        )
+    }
+}
+
+/// Transport wrapper that logs frames and records round-trip times
+pub struct DebugTransport<T: Transport> {
+    inner: T,
+    epoch: Instant,
+    last_send: Option<Instant>,
+    round_trips: Vec<Duration>,
+    /// Address logged on disconnect, for transports created with `connected`
+    address: Option<String>,
+    hex_dump: bool,
+    color: bool,
+}
+
+impl<T: Transport> DebugTransport<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            epoch: Instant::now(),
+            last_send: None,
+            round_trips: Vec::new(),
+            address: None,
+            hex_dump: false,
+            color: std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        }
+    }
+
+    /// Wrap a transport just connected to `address`
+    ///
+    /// Logs the connection now and the disconnection when the transport is
+    /// dropped.
+    pub fn connected(inner: T, address: &str) -> Self {
+        let mut transport = Self::new(inner);
+        transport.address = Some(address.to_string());
+        transport.log_event(CYAN, &format!("Connected to {}", address));
+        transport
+    }
+
+    /// Also dump the bytes of each whole frame (`-vv`)
+    pub fn hex_dump(mut self, enabled: bool) -> Self {
+        self.hex_dump = enabled;
+        self
+    }
+
+    fn log_send(&mut self, msg_type: u8, payload: &[u8]) {
+        let now = Instant::now();
+        self.log_line(
+            now,
+            CYAN,
+            &format!("→ [0x{:02X}] {} bytes payload", msg_type, payload.len()),
+        );
+        self.log_frame_bytes(msg_type, payload);
+        self.last_send = Some(now);
+    }
+
+    fn log_receive(&mut self, frame: &Frame) {
+        let now = Instant::now();
+        let rtt = match self.last_send.take() {
+            Some(sent) => {
+                let rtt = now.duration_since(sent);
+                self.round_trips.push(rtt);
+                format!(" [+{:.1}ms]", as_ms(rtt))
+            }
+            None => String::new(),
+        };
+        self.log_line(
+            now,
+            GREEN,
+            &format!(
+                "← [0x{:02X}] {} bytes payload{}",
+                frame.msg_type,
+                frame.payload.len(),
+                rtt
+            ),
+        );
+        self.log_frame_bytes(frame.msg_type, &frame.payload);
+    }
+
+    /// Log a failed receive (timeouts, closed connections)
+    fn log_error(&self, error: &anyhow::Error) {
+        self.log_event(RED, &format!("✗ {:#}", error));
+    }
+
+    fn log_event(&self, color: &str, message: &str) {
+        self.log_line(Instant::now(), color, message);
+    }
+
+    fn log_line(&self, at: Instant, color: &str, message: &str) {
+        let line = format!(
+            "[{:>9.3}s] {}",
+            at.duration_since(self.epoch).as_secs_f64(),
+            message
+        );
+        if self.color {
+            eprintln!("{}{}{}", color, line, RESET);
+        } else {
+            eprintln!("{}", line);
+        }
+    }
+
+    fn log_frame_bytes(&self, msg_type: u8, payload: &[u8]) {
+        if !self.hex_dump {
+            return;
+        }
+        // Re-encoding gives the bytes on the wire, CRC included
+        if let Ok(bytes) = encode_frame(msg_type, payload) {
+            for line in hex_dump_lines(&bytes) {
+                eprintln!("{:13}{}", "", line);
+            }
+        }
+    }
+
+    fn logged<R>(&self, result: Result<R>) -> Result<R> {
+        if let Err(e) = &result {
+            self.log_error(e);
+        }
+        result
+    }
+}
+
+impl<T: Transport> Drop for DebugTransport<T> {
+    fn drop(&mut self) {
+        if let Some(address) = &self.address {
+            self.log_event(CYAN, &format!("Disconnected from {}", address));
+        }
+    }
+}
+
+impl<T: Transport> Transport for DebugTransport<T> {
+    fn send_frame(&mut self, msg_type: u8, payload: &[u8]) -> Result<()> {
+        self.log_send(msg_type, payload);
+        let result = self.inner.send_frame(msg_type, payload);
+        self.logged(result)
+    }
+
+    fn receive_frame(&mut self, timeout_ms: u64) -> Result<Frame> {
+        let result = self.inner.receive_frame(timeout_ms);
+        let frame = self.logged(result)?;
+        self.log_receive(&frame);
+        Ok(frame)
+    }
+
+    fn send_command(&mut self, msg_type: u8, payload: &[u8]) -> Result<Frame> {
+        self.log_send(msg_type, payload);
+        let result = self.inner.send_command(msg_type, payload);
+        let frame = self.logged(result)?;
+        self.log_receive(&frame);
+        Ok(frame)
+    }
+
+    fn send_command_with_timeout(
+        &mut self,
+        msg_type: u8,
+        payload: &[u8],
+        timeout_ms: u64,
+    ) -> Result<Frame> {
+        self.log_send(msg_type, payload);
+        let result = self
+            .inner
+            .send_command_with_timeout(msg_type, payload, timeout_ms);
+        let frame = self.logged(result)?;
+        self.log_receive(&frame);
+        Ok(frame)
+    }
+
+    fn command_timeout_ms(&self) -> u64 {
+        self.inner.command_timeout_ms()
+    }
+
+    fn max_ota_chunk_size(&self) -> usize {
+        self.inner.max_ota_chunk_size()
+    }
+
+    fn max_payload(&self) -> usize {
+        self.inner.max_payload()
+    }
+
+    fn set_keepalive(&mut self, interval: Option<Duration>) {
+        self.inner.set_keepalive(interval)
+    }
+
+    fn transport_type(&self) -> TransportType {
+        self.inner.transport_type()
+    }
+
+    fn baud_rate(&self) -> Option<u32> {
+        self.inner.baud_rate()
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        self.inner.set_baud_rate(baud_rate)
+    }
+
+    fn reconnect(&mut self) -> Result<()> {
+        let result = self.inner.reconnect();
+        self.logged(result)?;
+        if let Some(address) = &self.address {
+            self.log_event(CYAN, &format!("Reconnected to {}", address));
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let result = self.inner.flush();
+        self.logged(result)
+    }
+
+    fn latency_stats(&self) -> Option<LatencyStats> {
+        LatencyStats::from_samples(&self.round_trips)
+    }
+
+    fn stats(&self) -> TransportStats {
+        self.inner.stats()
+    }
+}
+
+fn as_ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// `0000  aa 55 05 00 20 ...`, `HEX_DUMP_WIDTH` bytes per line
+fn hex_dump_lines(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .chunks(HEX_DUMP_WIDTH)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            format!("{:04x}  {}", i * HEX_DUMP_WIDTH, hex.join(" "))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(v: &[u64]) -> Vec<Duration> {
+        v.iter().map(|&m| Duration::from_millis(m)).collect()
+    }
+
+    #[test]
+    fn test_hex_dump_lines() {
+        let frame = encode_frame(0x20, &[0u8; 10]).unwrap();
+        let lines = hex_dump_lines(&frame);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("0000  aa 55 0b 00 20 00"));
+        assert_eq!(lines[0].len(), 6 + 16 * 3 - 1);
+        assert!(lines[1].starts_with("0010  "));
+        assert_eq!(lines[1].split(' ').count() - 2, 3);
+    }
+
+    #[test]
+    fn test_latency_stats_empty() {
+        assert_eq!(LatencyStats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn test_latency_stats_single() {
+        let stats = LatencyStats::from_samples(&ms(&[7])).unwrap();
+        assert_eq!(stats.count, 1);
+        assert_eq!(stats.min, Duration::from_millis(7));
+        assert_eq!(stats.max, Duration::from_millis(7));
+        assert_eq!(stats.p95, Duration::from_millis(7));
+    }
+
+    #[test]
+    fn test_latency_stats_percentile() {
+        // 1..=20 ms, unordered: p95 by nearest rank is the 19th value
+        let mut samples: Vec<u64> = (1..=20).rev().collect();
+        samples.swap(3, 11);
+        let stats = LatencyStats::from_samples(&ms(&samples)).unwrap();
+        assert_eq!(stats.count, 20);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.max, Duration::from_millis(20));
+        assert_eq!(stats.avg, Duration::from_micros(10_500));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+    }
+}
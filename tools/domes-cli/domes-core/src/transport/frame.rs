@@ -0,0 +1,576 @@
+//! Frame codec for DOMES protocol
+//!
+//! Frame format: [0xAA][0x55][LenLE16][Type][Payload][CRC32LE]
+//! - Start bytes: 0xAA 0x55
+//! - Length: 2 bytes little-endian, contains (Type + Payload) length
+//! - Type: 1 byte message type
+//! - Payload: 0-1024 bytes
+//! - CRC32: 4 bytes little-endian, calculated over (Type + Payload)
+
+use crc32fast::Hasher;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Frame start marker bytes
+const START_BYTE_0: u8 = 0xAA;
+const START_BYTE_1: u8 = 0x55;
+
+/// Maximum payload size
+pub const MAX_PAYLOAD_SIZE: usize = 1024;
+
+/// Frame overhead: 2 start + 2 len + 1 type + 4 crc = 9 bytes
+pub const FRAME_OVERHEAD: usize = 9;
+
+/// Frame codec errors
+#[derive(Debug, Error)]
+pub enum FrameError {
+    #[error("Payload too large: {0} > {MAX_PAYLOAD_SIZE}")]
+    PayloadTooLarge(usize),
+
+    #[error("Invalid length: {0}")]
+    InvalidLength(u16),
+
+    #[error("CRC mismatch: expected 0x{expected:08X}, got 0x{actual:08X}")]
+    CrcMismatch { expected: u32, actual: u32 },
+
+    #[error("Frame timeout: the device stopped sending mid-frame")]
+    FrameTimeout,
+}
+
+/// Encode a frame with the given type and payload
+///
+/// Returns the encoded frame as a Vec<u8>
+pub fn encode_frame(msg_type: u8, payload: &[u8]) -> Result<Vec<u8>, FrameError> {
+    if payload.len() > MAX_PAYLOAD_SIZE {
+        return Err(FrameError::PayloadTooLarge(payload.len()));
+    }
+
+    // Length field = type (1 byte) + payload length
+    let length = 1 + payload.len() as u16;
+
+    // Build frame
+    let frame_size = FRAME_OVERHEAD + payload.len();
+    let mut frame = Vec::with_capacity(frame_size);
+
+    // Start bytes
+    frame.push(START_BYTE_0);
+    frame.push(START_BYTE_1);
+
+    // Length (little-endian)
+    frame.extend_from_slice(&length.to_le_bytes());
+
+    // Type
+    frame.push(msg_type);
+
+    // Payload
+    frame.extend_from_slice(payload);
+
+    // CRC32 (little-endian) over type + payload, now contiguous in the frame
+    let crc = crc32fast::hash(&frame[4..]);
+    frame.extend_from_slice(&crc.to_le_bytes());
+
+    Ok(frame)
+}
+
+/// Decoded frame
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub msg_type: u8,
+    pub payload: Vec<u8>,
+}
+
+/// Frame decoder state machine
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DecoderState {
+    WaitStart0,
+    WaitStart1,
+    WaitLenLow,
+    WaitLenHigh,
+    WaitType,
+    WaitPayload,
+    WaitCrc,
+}
+
+/// Streaming frame decoder
+///
+/// After each frame (or framing error) the decoder moves on to the next one
+/// by itself. A bad frame (invalid length or CRC mismatch) may have been a
+/// false start inside noise, or cut short by a lost byte, so the bytes after
+/// its start bytes are scanned again for the next `0xAA 0x55` rather than
+/// dropped: a valid frame that follows a corrupt one is still decoded.
+///
+/// With a maximum frame duration, a frame whose start bytes arrived longer
+/// ago than that is abandoned with `FrameError::FrameTimeout`, so a device
+/// that hangs mid-frame is noticed before the whole response timeout.
+pub struct FrameDecoder {
+    state: DecoderState,
+    length: u16,
+    msg_type: u8,
+    payload: Vec<u8>,
+    crc_bytes: [u8; 4],
+    crc_index: usize,
+    payload_index: usize,
+    max_frame_duration: Option<Duration>,
+    /// When the current frame's first start byte arrived
+    frame_start_time: Option<Instant>,
+    /// Bytes of a bad frame (after its first start byte) still to be
+    /// scanned, followed by any bytes fed meanwhile
+    rescan: VecDeque<u8>,
+}
+
+impl Default for FrameDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameDecoder {
+    /// Create a new frame decoder
+    pub fn new() -> Self {
+        Self {
+            state: DecoderState::WaitStart0,
+            length: 0,
+            msg_type: 0,
+            payload: Vec::new(),
+            crc_bytes: [0; 4],
+            crc_index: 0,
+            payload_index: 0,
+            max_frame_duration: None,
+            frame_start_time: None,
+            rescan: VecDeque::new(),
+        }
+    }
+
+    /// Create a decoder that gives up on a frame not complete within `d`
+    /// of its start bytes
+    pub fn with_max_frame_duration(d: Duration) -> Self {
+        Self {
+            max_frame_duration: Some(d),
+            ..Self::new()
+        }
+    }
+
+    /// Change the maximum frame duration (`None` waits indefinitely)
+    pub fn set_max_frame_duration(&mut self, d: Option<Duration>) {
+        self.max_frame_duration = d;
+    }
+
+    /// The maximum frame duration, if any
+    pub fn max_frame_duration(&self) -> Option<Duration> {
+        self.max_frame_duration
+    }
+
+    /// Whether a frame has been started and not completed within the
+    /// maximum frame duration
+    pub fn is_frame_overdue(&self) -> bool {
+        match (self.frame_start_time, self.max_frame_duration) {
+            (Some(start), Some(max)) => start.elapsed() > max,
+            _ => false,
+        }
+    }
+
+    /// Abandon an overdue frame and return `FrameError::FrameTimeout`
+    ///
+    /// Transports call this while waiting for bytes, since `feed_byte` only
+    /// notices once another byte arrives.
+    pub fn check_frame_timeout(&mut self) -> Result<(), FrameError> {
+        if self.is_frame_overdue() {
+            self.clear_frame();
+            return Err(FrameError::FrameTimeout);
+        }
+        Ok(())
+    }
+
+    /// Reset the decoder state, discarding everything received so far
+    /// (e.g. after a baud rate change)
+    pub fn reset(&mut self) {
+        self.clear_frame();
+        self.rescan.clear();
+    }
+
+    /// Drop the frame in progress and wait for the next start bytes
+    fn clear_frame(&mut self) {
+        self.state = DecoderState::WaitStart0;
+        self.length = 0;
+        self.msg_type = 0;
+        self.payload.clear();
+        self.crc_bytes = [0; 4];
+        self.crc_index = 0;
+        self.payload_index = 0;
+        self.frame_start_time = None;
+    }
+
+    /// Feed a byte to the decoder
+    ///
+    /// Returns Some(Frame) when a complete frame is decoded, None otherwise.
+    /// While bytes of a bad frame are being scanned again, the new byte is
+    /// queued behind them; the frame returned can then end before it. The
+    /// same goes for the byte that reveals a frame timeout, so it can start
+    /// the next frame.
+    pub fn feed_byte(&mut self, byte: u8) -> Option<Result<Frame, FrameError>> {
+        if let Err(e) = self.check_frame_timeout() {
+            self.rescan.push_back(byte);
+            return Some(Err(e));
+        }
+        if self.rescan.is_empty() {
+            return self.step(byte);
+        }
+        self.rescan.push_back(byte);
+        self.take_buffered()
+    }
+
+    /// The next frame (or error) found in bytes already received, without
+    /// feeding more; transports check this before reading
+    pub fn take_buffered(&mut self) -> Option<Result<Frame, FrameError>> {
+        while let Some(byte) = self.rescan.pop_front() {
+            if let Some(result) = self.step(byte) {
+                return Some(result);
+            }
+        }
+        None
+    }
+
+    /// Give up on the current frame as corrupt: scan its bytes after the
+    /// first start byte again
+    fn resync(&mut self, error: FrameError) -> Option<Result<Frame, FrameError>> {
+        let mut bytes = vec![START_BYTE_1];
+        bytes.extend_from_slice(&self.length.to_le_bytes());
+        if matches!(error, FrameError::CrcMismatch { .. }) {
+            bytes.push(self.msg_type);
+            bytes.extend_from_slice(&self.payload);
+            bytes.extend_from_slice(&self.crc_bytes);
+        }
+        for &byte in bytes.iter().rev() {
+            self.rescan.push_front(byte);
+        }
+        self.clear_frame();
+        Some(Err(error))
+    }
+
+    /// Advance the state machine by one byte
+    fn step(&mut self, byte: u8) -> Option<Result<Frame, FrameError>> {
+        match self.state {
+            DecoderState::WaitStart0 => {
+                if byte == START_BYTE_0 {
+                    self.state = DecoderState::WaitStart1;
+                    self.frame_start_time = Some(Instant::now());
+                }
+                // Ignore non-start bytes (noise resilience)
+                None
+            }
+            DecoderState::WaitStart1 => {
+                if byte == START_BYTE_1 {
+                    self.state = DecoderState::WaitLenLow;
+                } else if byte == START_BYTE_0 {
+                    // Stay in WaitStart1 (might be repeated start byte)
+                } else {
+                    self.clear_frame();
+                }
+                None
+            }
+            DecoderState::WaitLenLow => {
+                self.length = byte as u16;
+                self.state = DecoderState::WaitLenHigh;
+                None
+            }
+            DecoderState::WaitLenHigh => {
+                self.length |= (byte as u16) << 8;
+
+                // Validate length
+                if self.length == 0 || self.length > (MAX_PAYLOAD_SIZE + 1) as u16 {
+                    return self.resync(FrameError::InvalidLength(self.length));
+                }
+
+                self.state = DecoderState::WaitType;
+                None
+            }
+            DecoderState::WaitType => {
+                self.msg_type = byte;
+
+                // Payload length = total length - type byte
+                let payload_len = (self.length - 1) as usize;
+                self.payload = Vec::with_capacity(payload_len);
+                self.payload_index = 0;
+
+                if payload_len == 0 {
+                    self.state = DecoderState::WaitCrc;
+                    self.crc_index = 0;
+                } else {
+                    self.state = DecoderState::WaitPayload;
+                }
+                None
+            }
+            DecoderState::WaitPayload => {
+                self.payload.push(byte);
+                self.payload_index += 1;
+
+                let payload_len = (self.length - 1) as usize;
+                if self.payload_index >= payload_len {
+                    self.state = DecoderState::WaitCrc;
+                    self.crc_index = 0;
+                }
+                None
+            }
+            DecoderState::WaitCrc => {
+                self.crc_bytes[self.crc_index] = byte;
+                self.crc_index += 1;
+
+                if self.crc_index < 4 {
+                    return None;
+                }
+
+                // Verify CRC
+                let received_crc = u32::from_le_bytes(self.crc_bytes);
+
+                let mut hasher = Hasher::new();
+                hasher.update(&[self.msg_type]);
+                hasher.update(&self.payload);
+                let calculated_crc = hasher.finalize();
+
+                if received_crc != calculated_crc {
+                    return self.resync(FrameError::CrcMismatch {
+                        expected: calculated_crc,
+                        actual: received_crc,
+                    });
+                }
+
+                let frame = Frame {
+                    msg_type: self.msg_type,
+                    payload: std::mem::take(&mut self.payload),
+                };
+                self.clear_frame();
+                Some(Ok(frame))
+            }
+        }
+    }
+
+    /// Feed a block of bytes, returning every frame (or error) decoded
+    ///
+    /// Payload bytes are copied in one go rather than one call per byte.
+    pub fn feed_slice(&mut self, data: &[u8]) -> Vec<Result<Frame, FrameError>> {
+        let mut results = Vec::new();
+        let mut i = 0;
+        while i < data.len() {
+            if self.state == DecoderState::WaitPayload && self.rescan.is_empty() {
+                if let Err(e) = self.check_frame_timeout() {
+                    results.push(Err(e));
+                    continue;
+                }
+                let payload_len = (self.length - 1) as usize;
+                let take = (payload_len - self.payload_index).min(data.len() - i);
+                self.payload.extend_from_slice(&data[i..i + take]);
+                self.payload_index += take;
+                if self.payload_index >= payload_len {
+                    self.state = DecoderState::WaitCrc;
+                    self.crc_index = 0;
+                }
+                i += take;
+                continue;
+            }
+            if let Some(result) = self.feed_byte(data[i]) {
+                results.push(result);
+            }
+            i += 1;
+        }
+        while let Some(result) = self.take_buffered() {
+            results.push(result);
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_empty_payload() {
+        let frame = encode_frame(0x20, &[]).unwrap();
+        assert_eq!(frame[0], START_BYTE_0);
+        assert_eq!(frame[1], START_BYTE_1);
+        assert_eq!(frame[2], 1); // Length low byte (just type)
+        assert_eq!(frame[3], 0); // Length high byte
+        assert_eq!(frame[4], 0x20); // Type
+        // CRC follows
+        assert_eq!(frame.len(), FRAME_OVERHEAD);
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let payload = [0x01, 0x02, 0x03, 0x04];
+        let frame = encode_frame(0x21, &payload).unwrap();
+
+        let mut decoder = FrameDecoder::new();
+        let mut result = None;
+
+        for byte in frame {
+            if let Some(r) = decoder.feed_byte(byte) {
+                result = Some(r);
+            }
+        }
+
+        let decoded = result.unwrap().unwrap();
+        assert_eq!(decoded.msg_type, 0x21);
+        assert_eq!(decoded.payload, payload);
+    }
+
+    #[test]
+    fn test_crc_mismatch() {
+        let mut frame = encode_frame(0x20, &[0x01]).unwrap();
+        // Corrupt the CRC
+        let len = frame.len();
+        frame[len - 1] ^= 0xFF;
+
+        let mut decoder = FrameDecoder::new();
+        let mut result = None;
+
+        for byte in frame {
+            if let Some(r) = decoder.feed_byte(byte) {
+                result = Some(r);
+            }
+        }
+
+        assert!(matches!(result.unwrap(), Err(FrameError::CrcMismatch { .. })));
+    }
+
+    #[test]
+    fn test_noise_resilience() {
+        let frame = encode_frame(0x20, &[]).unwrap();
+
+        let mut decoder = FrameDecoder::new();
+
+        // Feed garbage before frame
+        decoder.feed_byte(0x00);
+        decoder.feed_byte(0xFF);
+        decoder.feed_byte(0x12);
+
+        // Feed frame
+        let mut result = None;
+        for byte in frame {
+            if let Some(r) = decoder.feed_byte(byte) {
+                result = Some(r);
+            }
+        }
+
+        assert!(result.unwrap().is_ok());
+    }
+
+    /// Every result of feeding `bytes` one at a time, then what is buffered
+    fn feed_all(decoder: &mut FrameDecoder, bytes: &[u8]) -> Vec<Result<Frame, FrameError>> {
+        let mut results: Vec<_> = bytes.iter().filter_map(|&b| decoder.feed_byte(b)).collect();
+        results.extend(std::iter::from_fn(|| decoder.take_buffered()));
+        results
+    }
+
+    #[test]
+    fn test_resync_after_corrupt_frame() {
+        let good = encode_frame(0x21, &[7, 8, 9]).unwrap();
+
+        // A corrupt CRC immediately followed by a valid frame
+        let mut bad = encode_frame(0x20, &[1, 2]).unwrap();
+        let last = bad.len() - 1;
+        bad[last] ^= 0xFF;
+        let results = feed_all(&mut FrameDecoder::new(), &[&bad[..], &good].concat());
+        assert_eq!(results.len(), 2);
+        assert!(matches!(results[0], Err(FrameError::CrcMismatch { .. })));
+        assert_eq!(results[1].as_ref().unwrap().payload, [7, 8, 9]);
+
+        // A false start in noise with an impossible length
+        let noise = [0xAA, 0x55, 0xFF, 0xFF];
+        let results = feed_all(&mut FrameDecoder::new(), &[&noise[..], &good].concat());
+        assert!(matches!(results[0], Err(FrameError::InvalidLength(0xFFFF))));
+        assert_eq!(results[1].as_ref().unwrap().msg_type, 0x21);
+        assert_eq!(results.len(), 2);
+
+        // A corrupted length that swallows the next frame: it is found
+        // again inside the bad frame's bytes
+        let mut stretched = encode_frame(0x20, &[1, 2]).unwrap();
+        stretched[2] = 20;
+        let padding = [0u8; 16];
+        let stream = [&stretched[..], &good, &padding].concat();
+        let results = feed_all(&mut FrameDecoder::new(), &stream);
+        assert!(matches!(results[0], Err(FrameError::CrcMismatch { .. })));
+        assert_eq!(results[1].as_ref().unwrap().payload, [7, 8, 9]);
+        assert_eq!(results.len(), 2);
+
+        // feed_slice resyncs the same way
+        let results = FrameDecoder::new().feed_slice(&stream);
+        assert_eq!(results.len(), 2);
+        assert!(results[1].is_ok());
+    }
+
+    #[test]
+    fn test_feed_slice_matches_feed_byte() {
+        let big = encode_frame(0x21, &[0x5A; MAX_PAYLOAD_SIZE]).unwrap();
+        let mut bad = encode_frame(0x20, &[1, 2]).unwrap();
+        bad[5] ^= 0xFF;
+        let small = encode_frame(0x22, &[]).unwrap();
+        let stream: Vec<u8> = [&[0x00, 0xAA][..], &big, &bad, &small].concat();
+
+        // Split at awkward points, including mid-payload
+        let mut decoder = FrameDecoder::new();
+        let mut results = Vec::new();
+        for chunk in stream.chunks(100) {
+            results.extend(decoder.feed_slice(chunk));
+        }
+        assert_eq!(results.len(), 3);
+        let first = results[0].as_ref().unwrap();
+        assert_eq!((first.msg_type, first.payload.len()), (0x21, MAX_PAYLOAD_SIZE));
+        assert!(matches!(results[1], Err(FrameError::CrcMismatch { .. })));
+        assert_eq!(results[2].as_ref().unwrap().msg_type, 0x22);
+
+        // Same CRC as the streaming hasher used before
+        let mut hasher = Hasher::new();
+        hasher.update(&[0x21]);
+        hasher.update(&[0x5A; MAX_PAYLOAD_SIZE]);
+        assert_eq!(big[big.len() - 4..], hasher.finalize().to_le_bytes());
+    }
+
+    #[test]
+    fn test_stalled_frame_times_out() {
+        let frame = encode_frame(0x21, &[1, 2, 3]).unwrap();
+        let mut decoder = FrameDecoder::with_max_frame_duration(Duration::from_millis(20));
+
+        // The device stops after the header
+        for &byte in &frame[..5] {
+            assert!(decoder.feed_byte(byte).is_none());
+        }
+        assert!(decoder.check_frame_timeout().is_ok());
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(decoder.is_frame_overdue());
+        assert!(matches!(
+            decoder.feed_byte(frame[5]),
+            Some(Err(FrameError::FrameTimeout))
+        ));
+
+        // The decoder was reset: the next frame decodes normally
+        let result = frame.iter().find_map(|&b| decoder.feed_byte(b));
+        assert_eq!(result.unwrap().unwrap().payload, [1, 2, 3]);
+
+        // Noise between frames does not start the clock
+        decoder.reset();
+        decoder.feed_byte(0x00);
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(decoder.check_frame_timeout().is_ok());
+    }
+
+    #[test]
+    fn test_byte_after_stall_starts_next_frame() {
+        let frame = encode_frame(0x21, &[1, 2, 3]).unwrap();
+        let mut decoder = FrameDecoder::with_max_frame_duration(Duration::from_millis(20));
+
+        for &byte in &frame[..5] {
+            assert!(decoder.feed_byte(byte).is_none());
+        }
+        std::thread::sleep(Duration::from_millis(30));
+
+        // The first start byte of the next frame reports the timeout but is kept
+        assert!(matches!(
+            decoder.feed_byte(frame[0]),
+            Some(Err(FrameError::FrameTimeout))
+        ));
+        let result = frame[1..].iter().find_map(|&b| decoder.feed_byte(b));
+        assert_eq!(result.unwrap().unwrap().payload, [1, 2, 3]);
+    }
+}
@@ -0,0 +1,262 @@
+//! Keepalive transport wrapper
+//!
+//! BLE and WiFi links can drop without either side noticing until the next
+//! command times out. `KeepaliveTransport` pings the device from a background
+//! thread and marks the connection stale when a ping goes unanswered.
+//!
+//! The wrapped transport sits behind a mutex shared with the ping thread, so
+//! pings and commands never interleave on the wire. A ping can still land
+//! between a separate `send_frame` and `receive_frame`; use `send_command`
+//! for request/response traffic while keepalive is running.
+
+use super::{Frame, LatencyStats, Transport, TransportStats, TransportType};
+use crate::protocol::ConfigMsgType;
+use anyhow::{Context, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Ping cadence and how long to wait for each response
+#[derive(Debug, Clone, Copy)]
+pub struct KeepaliveConfig {
+    pub interval: Duration,
+    pub timeout: Duration,
+}
+
+impl Default for KeepaliveConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(5000),
+            timeout: Duration::from_millis(2000),
+        }
+    }
+}
+
+/// Send one ping and wait up to `timeout_ms` for the response
+pub fn ping(transport: &mut dyn Transport, timeout_ms: u64) -> Result<()> {
+    let frame = transport
+        .send_command_with_timeout(ConfigMsgType::PingReq as u8, &[], timeout_ms)
+        .context("No ping response")?;
+
+    if frame.msg_type != ConfigMsgType::PingRsp as u8 {
+        anyhow::bail!(
+            "Unexpected response type: 0x{:02X}, expected 0x{:02X}",
+            frame.msg_type,
+            ConfigMsgType::PingRsp as u8
+        );
+    }
+    Ok(())
+}
+
+/// State shared with the ping thread
+struct Shared {
+    alive: AtomicBool,
+    stop: AtomicBool,
+    /// Why the last ping failed, cleared when a ping succeeds
+    last_error: Mutex<Option<String>>,
+}
+
+/// Transport wrapper that pings the device in the background
+pub struct KeepaliveTransport<T: Transport + 'static> {
+    inner: Arc<Mutex<T>>,
+    shared: Arc<Shared>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl<T: Transport + 'static> KeepaliveTransport<T> {
+    /// Wrap `inner` and start pinging it every `config.interval`
+    pub fn new(inner: T, config: KeepaliveConfig) -> Self {
+        let inner = Arc::new(Mutex::new(inner));
+        let shared = Arc::new(Shared {
+            alive: AtomicBool::new(true),
+            stop: AtomicBool::new(false),
+            last_error: Mutex::new(None),
+        });
+
+        let thread_inner = inner.clone();
+        let thread_shared = shared.clone();
+        let handle = std::thread::spawn(move || {
+            let timeout_ms = config.timeout.as_millis() as u64;
+            while !thread_shared.stop.load(Ordering::Relaxed) {
+                let result = ping(&mut *thread_inner.lock().unwrap(), timeout_ms);
+                *thread_shared.last_error.lock().unwrap() =
+                    result.as_ref().err().map(|e| format!("{:#}", e));
+                thread_shared.alive.store(result.is_ok(), Ordering::Relaxed);
+
+                // Sleep in short steps so dropping the transport is prompt
+                let deadline = Instant::now() + config.interval;
+                while !thread_shared.stop.load(Ordering::Relaxed) {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        break;
+                    }
+                    std::thread::sleep(remaining.min(Duration::from_millis(50)));
+                }
+            }
+        });
+
+        Self {
+            inner,
+            shared,
+            handle: Some(handle),
+        }
+    }
+
+    /// Whether the most recent ping was answered
+    pub fn is_alive(&self) -> bool {
+        self.shared.alive.load(Ordering::Relaxed)
+    }
+
+    /// Why the most recent ping failed, if it did
+    pub fn last_error(&self) -> Option<String> {
+        self.shared.last_error.lock().unwrap().clone()
+    }
+
+    fn with_inner<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.inner.lock().unwrap())
+    }
+}
+
+impl<T: Transport + 'static> Drop for KeepaliveTransport<T> {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl<T: Transport + 'static> Transport for KeepaliveTransport<T> {
+    fn send_frame(&mut self, msg_type: u8, payload: &[u8]) -> Result<()> {
+        self.with_inner(|t| t.send_frame(msg_type, payload))
+    }
+
+    fn receive_frame(&mut self, timeout_ms: u64) -> Result<Frame> {
+        self.with_inner(|t| t.receive_frame(timeout_ms))
+    }
+
+    fn send_command(&mut self, msg_type: u8, payload: &[u8]) -> Result<Frame> {
+        self.with_inner(|t| t.send_command(msg_type, payload))
+    }
+
+    fn send_command_with_timeout(
+        &mut self,
+        msg_type: u8,
+        payload: &[u8],
+        timeout_ms: u64,
+    ) -> Result<Frame> {
+        self.with_inner(|t| t.send_command_with_timeout(msg_type, payload, timeout_ms))
+    }
+
+    fn command_timeout_ms(&self) -> u64 {
+        self.with_inner(|t| t.command_timeout_ms())
+    }
+
+    fn max_ota_chunk_size(&self) -> usize {
+        self.with_inner(|t| t.max_ota_chunk_size())
+    }
+
+    fn max_payload(&self) -> usize {
+        self.with_inner(|t| t.max_payload())
+    }
+
+    fn latency_stats(&self) -> Option<LatencyStats> {
+        self.with_inner(|t| t.latency_stats())
+    }
+
+    fn stats(&self) -> TransportStats {
+        self.with_inner(|t| t.stats())
+    }
+
+    fn set_keepalive(&mut self, interval: Option<Duration>) {
+        self.with_inner(|t| t.set_keepalive(interval))
+    }
+
+    fn transport_type(&self) -> TransportType {
+        self.with_inner(|t| t.transport_type())
+    }
+
+    fn baud_rate(&self) -> Option<u32> {
+        self.with_inner(|t| t.baud_rate())
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        self.with_inner(|t| t.set_baud_rate(baud_rate))
+    }
+
+    fn reconnect(&mut self) -> Result<()> {
+        self.with_inner(|t| t.reconnect())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.with_inner(|t| t.flush())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::ResponseTimeout;
+
+    /// Answers pings until `answer` is cleared
+    struct PingDevice {
+        answer: Arc<AtomicBool>,
+    }
+
+    impl Transport for PingDevice {
+        fn send_frame(&mut self, _msg_type: u8, _payload: &[u8]) -> Result<()> {
+            Ok(())
+        }
+
+        fn receive_frame(&mut self, _timeout_ms: u64) -> Result<Frame> {
+            if self.answer.load(Ordering::Relaxed) {
+                Ok(Frame {
+                    msg_type: ConfigMsgType::PingRsp as u8,
+                    payload: Vec::new(),
+                })
+            } else {
+                Err(ResponseTimeout.into())
+            }
+        }
+
+        fn send_command(&mut self, msg_type: u8, payload: &[u8]) -> Result<Frame> {
+            self.send_frame(msg_type, payload)?;
+            self.receive_frame(0)
+        }
+    }
+
+    fn wait_for(mut condition: impl FnMut() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while Instant::now() < deadline {
+            if condition() {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        false
+    }
+
+    #[test]
+    fn test_keepalive_marks_stale_and_recovers() {
+        let answer = Arc::new(AtomicBool::new(true));
+        let transport = KeepaliveTransport::new(
+            PingDevice {
+                answer: answer.clone(),
+            },
+            KeepaliveConfig {
+                interval: Duration::from_millis(10),
+                timeout: Duration::from_millis(10),
+            },
+        );
+        assert!(transport.is_alive());
+
+        answer.store(false, Ordering::Relaxed);
+        assert!(wait_for(|| !transport.is_alive()));
+        assert!(transport.last_error().unwrap().contains("No ping response"));
+
+        answer.store(true, Ordering::Relaxed);
+        assert!(wait_for(|| transport.is_alive()));
+        assert_eq!(transport.last_error(), None);
+    }
+}
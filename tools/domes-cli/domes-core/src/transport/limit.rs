@@ -0,0 +1,137 @@
+//! Payload limit transport wrapper
+//!
+//! The frame codec accepts payloads up to `MAX_PAYLOAD_SIZE`, the limit the
+//! CLI was built with. Firmware built with a smaller limit would drop or
+//! reject a larger frame without saying why. `PayloadLimitTransport` holds
+//! the device's actual limit (from `--max-payload`, or what the device
+//! reports in its system info) and refuses larger payloads before sending.
+
+use super::frame::MAX_PAYLOAD_SIZE;
+use super::{Frame, LatencyStats, Transport, TransportStats, TransportType};
+use anyhow::Result;
+use std::time::Duration;
+
+/// Rejects payloads larger than the device accepts
+pub struct PayloadLimitTransport<T> {
+    inner: T,
+    max_payload: usize,
+}
+
+impl<T: Transport> PayloadLimitTransport<T> {
+    /// Wrap `inner`; `max_payload` is capped at `MAX_PAYLOAD_SIZE`
+    pub fn new(inner: T, max_payload: usize) -> Self {
+        Self {
+            inner,
+            max_payload: max_payload.min(MAX_PAYLOAD_SIZE),
+        }
+    }
+
+    fn check(&self, msg_type: u8, payload: &[u8]) -> Result<()> {
+        if payload.len() > self.max_payload {
+            anyhow::bail!(
+                "Payload of message 0x{:02X} is {} bytes, more than the device accepts ({} bytes)",
+                msg_type,
+                payload.len(),
+                self.max_payload
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<T: Transport> Transport for PayloadLimitTransport<T> {
+    fn send_frame(&mut self, msg_type: u8, payload: &[u8]) -> Result<()> {
+        self.check(msg_type, payload)?;
+        self.inner.send_frame(msg_type, payload)
+    }
+
+    fn receive_frame(&mut self, timeout_ms: u64) -> Result<Frame> {
+        self.inner.receive_frame(timeout_ms)
+    }
+
+    fn send_command(&mut self, msg_type: u8, payload: &[u8]) -> Result<Frame> {
+        self.check(msg_type, payload)?;
+        self.inner.send_command(msg_type, payload)
+    }
+
+    fn send_command_with_timeout(
+        &mut self,
+        msg_type: u8,
+        payload: &[u8],
+        timeout_ms: u64,
+    ) -> Result<Frame> {
+        self.check(msg_type, payload)?;
+        self.inner
+            .send_command_with_timeout(msg_type, payload, timeout_ms)
+    }
+
+    fn command_timeout_ms(&self) -> u64 {
+        self.inner.command_timeout_ms()
+    }
+
+    fn max_ota_chunk_size(&self) -> usize {
+        self.inner.max_ota_chunk_size()
+    }
+
+    fn max_payload(&self) -> usize {
+        self.max_payload.min(self.inner.max_payload())
+    }
+
+    fn latency_stats(&self) -> Option<LatencyStats> {
+        self.inner.latency_stats()
+    }
+
+    fn stats(&self) -> TransportStats {
+        self.inner.stats()
+    }
+
+    fn set_keepalive(&mut self, interval: Option<Duration>) {
+        self.inner.set_keepalive(interval)
+    }
+
+    fn transport_type(&self) -> TransportType {
+        self.inner.transport_type()
+    }
+
+    fn baud_rate(&self) -> Option<u32> {
+        self.inner.baud_rate()
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        self.inner.set_baud_rate(baud_rate)
+    }
+
+    fn reconnect(&mut self) -> Result<()> {
+        self.inner.reconnect()
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::ReplayTransport;
+
+    #[test]
+    fn test_oversized_payload_rejected_before_sending() {
+        let ok = Frame {
+            msg_type: 0x21,
+            payload: vec![0],
+        };
+        let mut transport = PayloadLimitTransport::new(ReplayTransport::new(vec![ok]), 512);
+        assert_eq!(transport.max_payload(), 512);
+
+        let err = transport.send_command(0x20, &[0; 513]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Payload of message 0x20 is 513 bytes, more than the device accepts (512 bytes)"
+        );
+        assert!(transport.send_command(0x20, &[0; 512]).is_ok());
+
+        let unlimited = PayloadLimitTransport::new(ReplayTransport::new(Vec::new()), 4096);
+        assert_eq!(unlimited.max_payload(), MAX_PAYLOAD_SIZE);
+    }
+}
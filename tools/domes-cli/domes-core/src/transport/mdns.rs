@@ -0,0 +1,122 @@
+//! WiFi pod discovery over mDNS/DNS-SD
+//!
+//! Pods on WiFi advertise a `_domes._tcp` service, named after the pod
+//! (e.g. `DOMES-Pod-03._domes._tcp.local.`) and pointing at the config
+//! server port, with the pod ID in a `pod_id` TXT record. Browsing for it
+//! finds pods without knowing their IP addresses in advance, the way a BLE
+//! scan finds pods by their advertised name.
+
+use anyhow::{Context, Result};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+/// DNS-SD service type pods advertise
+pub const SERVICE_TYPE: &str = "_domes._tcp.local.";
+
+/// A DOMES pod found by mDNS discovery
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WifiDeviceInfo {
+    /// Service instance name (e.g. "DOMES-Pod-03")
+    pub name: String,
+    /// Resolved address of the config server
+    pub address: SocketAddr,
+    /// Pod ID from the `pod_id` TXT record, if the pod advertises one
+    pub pod_id: Option<u32>,
+}
+
+impl WifiDeviceInfo {
+    /// Build from a resolved service; `None` if it has no address yet
+    ///
+    /// IPv4 addresses are preferred, since the pod's TCP server listens on
+    /// IPv4 only.
+    fn from_service(
+        fullname: &str,
+        addresses: impl IntoIterator<Item = IpAddr>,
+        port: u16,
+        pod_id: Option<&str>,
+    ) -> Option<Self> {
+        let ip = addresses.into_iter().min_by_key(|ip| (!ip.is_ipv4(), *ip))?;
+        let name = fullname
+            .strip_suffix(SERVICE_TYPE)
+            .map(|n| n.trim_end_matches('.'))
+            .unwrap_or(fullname);
+        Some(WifiDeviceInfo {
+            name: name.to_string(),
+            address: SocketAddr::new(ip, port),
+            pod_id: pod_id.and_then(|id| id.trim().parse().ok()),
+        })
+    }
+}
+
+/// Browse for DOMES pods for `timeout`, sorted by name
+///
+/// Each pod is listed once, with the address from its latest announcement.
+pub fn discover(timeout: Duration) -> Result<Vec<WifiDeviceInfo>> {
+    let daemon = ServiceDaemon::new().context("Failed to start mDNS discovery")?;
+    let events = daemon
+        .browse(SERVICE_TYPE)
+        .context("Failed to browse for DOMES pods via mDNS")?;
+
+    let mut found: BTreeMap<String, WifiDeviceInfo> = BTreeMap::new();
+    let deadline = Instant::now() + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        match events.recv_timeout(remaining) {
+            Ok(ServiceEvent::ServiceResolved(info)) => {
+                if let Some(device) = device_from(&info) {
+                    found.insert(info.get_fullname().to_string(), device);
+                }
+            }
+            Ok(ServiceEvent::ServiceRemoved(_, fullname)) => {
+                found.remove(&fullname);
+            }
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+    let _ = daemon.shutdown();
+
+    let mut devices: Vec<WifiDeviceInfo> = found.into_values().collect();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(devices)
+}
+
+fn device_from(info: &ServiceInfo) -> Option<WifiDeviceInfo> {
+    WifiDeviceInfo::from_service(
+        info.get_fullname(),
+        info.get_addresses().iter().copied(),
+        info.get_port(),
+        info.get_property_val_str("pod_id"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    #[test]
+    fn test_resolved_service_prefers_ipv4() {
+        let v6 = IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1));
+        let v4 = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 42));
+        let device = WifiDeviceInfo::from_service(
+            "DOMES-Pod-03._domes._tcp.local.",
+            [v6, v4],
+            5000,
+            Some("3"),
+        )
+        .unwrap();
+        assert_eq!(device.name, "DOMES-Pod-03");
+        assert_eq!(device.address.to_string(), "192.168.1.42:5000");
+        assert_eq!(device.pod_id, Some(3));
+
+        let unnamed = WifiDeviceInfo::from_service("pod", [v6], 5000, Some("x")).unwrap();
+        assert_eq!((unnamed.name.as_str(), unnamed.pod_id), ("pod", None));
+        assert!(WifiDeviceInfo::from_service("pod", [], 5000, None).is_none());
+    }
+}
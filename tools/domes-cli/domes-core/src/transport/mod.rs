@@ -0,0 +1,523 @@
+//! Transport layer for DOMES CLI
+//!
+//! Provides frame encoding/decoding and communication over serial, TCP, or BLE.
+
+pub mod batch;
+pub mod ble;
+pub mod debug;
+pub mod frame;
+pub mod keepalive;
+pub mod limit;
+pub mod mdns;
+pub mod notifications;
+pub mod serial;
+pub mod stats;
+pub mod tcp;
+
+pub use batch::{send_batch, ReplayTransport};
+pub use ble::{BleDeviceInfo, BleTarget, BleTransport, MacPrefix};
+pub use debug::{DebugTransport, LatencyStats};
+pub use frame::Frame;
+pub use keepalive::{KeepaliveConfig, KeepaliveTransport};
+pub use limit::PayloadLimitTransport;
+pub use mdns::WifiDeviceInfo;
+pub use notifications::NotificationReceiver;
+pub use serial::{is_common_baud_rate, SerialConfig, SerialTransport, DEFAULT_BAUD_RATE};
+pub use stats::TransportStats;
+pub use tcp::TcpTransport;
+
+use anyhow::Result;
+use std::time::{Duration, Instant};
+
+/// `receive_frame` gave up waiting for a frame
+///
+/// Transports return this (inside the `anyhow::Error`) on timeout, so the
+/// command layer can tell a silent device from a broken link.
+#[derive(Debug, thiserror::Error)]
+#[error("Timeout waiting for response")]
+pub struct ResponseTimeout;
+
+/// `receive_frame` got bytes that did not decode into a valid frame
+///
+/// The link itself still works, so callers draining stale data can skip it.
+#[derive(Debug, thiserror::Error)]
+#[error("Frame decode error: {0}")]
+pub struct FrameDecodeError(#[from] pub frame::FrameError);
+
+/// Only frames of other types arrived while waiting for a response
+///
+/// Returned by `send_command_expect` when the timeout runs out after
+/// discarding at least one frame; `actual` is the last one discarded.
+#[derive(Debug, thiserror::Error)]
+#[error("Unexpected response type: 0x{actual:02X}, expected 0x{expected:02X}")]
+pub struct UnexpectedFrame {
+    pub expected: u8,
+    pub actual: u8,
+}
+
+/// Kind of link a transport runs over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportType {
+    Serial,
+    Tcp,
+    Ble,
+    /// Replayed or simulated devices
+    Other,
+}
+
+/// Default OTA chunk size for serial/TCP (matches firmware kOtaChunkSize)
+pub const OTA_CHUNK_SIZE_DEFAULT: usize = 1016;
+
+/// BLE OTA chunk size - smaller to fit within BLE MTU limits
+/// BLE MTU is typically 512 bytes max, with ATT overhead of 3 bytes = 509 bytes usable
+/// Frame overhead is 9 bytes, so max payload is ~500 bytes
+/// Using 400 bytes to leave margin for safety
+pub const OTA_CHUNK_SIZE_BLE: usize = 400;
+
+/// Response timeout of transports that do not set their own
+pub const DEFAULT_COMMAND_TIMEOUT_MS: u64 = 1000;
+
+/// Silence after which `Transport::flush` considers the link drained (ms)
+const FLUSH_QUIET_MS: u64 = 50;
+
+/// Longest `Transport::flush` discards frames from a device that keeps sending
+const FLUSH_MAX: Duration = Duration::from_millis(500);
+
+/// Transport trait for abstracting serial vs TCP vs BLE communication
+///
+/// Transports are `Send` so devices can be driven from worker threads.
+pub trait Transport: Send {
+    /// Send a frame to the device
+    fn send_frame(&mut self, msg_type: u8, payload: &[u8]) -> Result<()>;
+
+    /// Receive a frame from the device with timeout
+    fn receive_frame(&mut self, timeout_ms: u64) -> Result<Frame>;
+
+    /// Send a command and wait for response (uses transport-specific default timeout)
+    fn send_command(&mut self, msg_type: u8, payload: &[u8]) -> Result<Frame>;
+
+    /// Send a command and wait for response with explicit timeout
+    ///
+    /// Use this for slow commands (e.g., self-test, OTA check) that need
+    /// more time than the transport's default timeout.
+    fn send_command_with_timeout(
+        &mut self,
+        msg_type: u8,
+        payload: &[u8],
+        timeout_ms: u64,
+    ) -> Result<Frame> {
+        self.send_frame(msg_type, payload)?;
+        self.receive_frame(timeout_ms)
+    }
+
+    /// Timeout `send_command` waits for a response
+    fn command_timeout_ms(&self) -> u64 {
+        DEFAULT_COMMAND_TIMEOUT_MS
+    }
+
+    /// Send a command and wait for a response of type `expected`
+    ///
+    /// Frames of other types (trace or IMU stream data, events) that arrive
+    /// first are discarded until `command_timeout_ms` runs out. A device that
+    /// never answers fails with `ResponseTimeout`; one that only sent other
+    /// frames fails with `UnexpectedFrame`.
+    fn send_command_expect(&mut self, msg_type: u8, payload: &[u8], expected: u8) -> Result<Frame> {
+        let deadline = Instant::now() + Duration::from_millis(self.command_timeout_ms());
+        let frame = self.send_command(msg_type, payload)?;
+        skip_stray_frames(self, frame, expected, deadline)
+    }
+
+    /// `send_command_expect` with an explicit timeout for slow commands
+    fn send_command_expect_with_timeout(
+        &mut self,
+        msg_type: u8,
+        payload: &[u8],
+        expected: u8,
+        timeout_ms: u64,
+    ) -> Result<Frame> {
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        let frame = self.send_command_with_timeout(msg_type, payload, timeout_ms)?;
+        skip_stray_frames(self, frame, expected, deadline)
+    }
+
+    /// Get the maximum OTA chunk size for this transport
+    /// BLE has lower limits due to MTU constraints
+    fn max_ota_chunk_size(&self) -> usize {
+        OTA_CHUNK_SIZE_DEFAULT
+    }
+
+    /// Largest frame payload the device accepts (`PayloadLimitTransport`
+    /// lowers it to the device's limit)
+    fn max_payload(&self) -> usize {
+        frame::MAX_PAYLOAD_SIZE
+    }
+
+    /// Round-trip latency recorded so far (only `DebugTransport` records samples)
+    fn latency_stats(&self) -> Option<LatencyStats> {
+        None
+    }
+
+    /// Frames and bytes sent and received so far
+    fn stats(&self) -> TransportStats {
+        TransportStats::default()
+    }
+
+    /// Ping the device whenever the link has been idle for `interval`, or
+    /// stop with `None`
+    ///
+    /// Only BLE links are dropped for being idle; other transports ignore it.
+    fn set_keepalive(&mut self, _interval: Option<Duration>) {}
+
+    /// Kind of link underneath any wrappers
+    fn transport_type(&self) -> TransportType {
+        TransportType::Other
+    }
+
+    /// Current serial baud rate; `None` for other transports
+    fn baud_rate(&self) -> Option<u32> {
+        None
+    }
+
+    /// Switch the serial port to `baud_rate`; other transports fail
+    fn set_baud_rate(&mut self, _baud_rate: u32) -> Result<()> {
+        anyhow::bail!("Baud rate only applies to serial transports")
+    }
+
+    /// Open the link again after the device dropped it (e.g. rebooted): the
+    /// same serial port, TCP address or BLE peripheral; others fail
+    fn reconnect(&mut self) -> Result<()> {
+        anyhow::bail!("This transport cannot reconnect")
+    }
+
+    /// Discard whatever the device sent unasked (log output, trace events,
+    /// part of a frame), so the next response is read from a clean state
+    ///
+    /// The default receives and drops frames until none arrives for 50 ms.
+    fn flush(&mut self) -> Result<()> {
+        discard_frames(self)
+    }
+}
+
+/// Receive and drop frames until the link is quiet for `FLUSH_QUIET_MS`,
+/// or for at most `FLUSH_MAX` from a device that never stops sending
+///
+/// Fails if the link itself fails (e.g. the device disconnected).
+fn discard_frames<T: Transport + ?Sized>(transport: &mut T) -> Result<()> {
+    let deadline = Instant::now() + FLUSH_MAX;
+    while Instant::now() < deadline {
+        match transport.receive_frame(FLUSH_QUIET_MS) {
+            Ok(_) => {}
+            Err(e) if e.is::<ResponseTimeout>() => break,
+            // A garbled frame is stale data as well
+            Err(e) if e.is::<FrameDecodeError>() => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Receive until a frame of type `expected` arrives or `deadline` passes
+fn skip_stray_frames<T: Transport + ?Sized>(
+    transport: &mut T,
+    mut frame: Frame,
+    expected: u8,
+    deadline: Instant,
+) -> Result<Frame> {
+    while frame.msg_type != expected {
+        let stray = UnexpectedFrame {
+            expected,
+            actual: frame.msg_type,
+        };
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(stray.into());
+        }
+        frame = match transport.receive_frame(remaining.as_millis().max(1) as u64) {
+            Ok(frame) => frame,
+            Err(e) if e.is::<ResponseTimeout>() => return Err(stray.into()),
+            Err(e) => return Err(e),
+        };
+    }
+    Ok(frame)
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn send_frame(&mut self, msg_type: u8, payload: &[u8]) -> Result<()> {
+        (**self).send_frame(msg_type, payload)
+    }
+
+    fn receive_frame(&mut self, timeout_ms: u64) -> Result<Frame> {
+        (**self).receive_frame(timeout_ms)
+    }
+
+    fn send_command(&mut self, msg_type: u8, payload: &[u8]) -> Result<Frame> {
+        (**self).send_command(msg_type, payload)
+    }
+
+    fn send_command_with_timeout(
+        &mut self,
+        msg_type: u8,
+        payload: &[u8],
+        timeout_ms: u64,
+    ) -> Result<Frame> {
+        (**self).send_command_with_timeout(msg_type, payload, timeout_ms)
+    }
+
+    fn command_timeout_ms(&self) -> u64 {
+        (**self).command_timeout_ms()
+    }
+
+    fn max_ota_chunk_size(&self) -> usize {
+        (**self).max_ota_chunk_size()
+    }
+
+    fn max_payload(&self) -> usize {
+        (**self).max_payload()
+    }
+
+    fn latency_stats(&self) -> Option<LatencyStats> {
+        (**self).latency_stats()
+    }
+
+    fn stats(&self) -> TransportStats {
+        (**self).stats()
+    }
+
+    fn set_keepalive(&mut self, interval: Option<Duration>) {
+        (**self).set_keepalive(interval)
+    }
+
+    fn transport_type(&self) -> TransportType {
+        (**self).transport_type()
+    }
+
+    fn baud_rate(&self) -> Option<u32> {
+        (**self).baud_rate()
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        (**self).set_baud_rate(baud_rate)
+    }
+
+    fn reconnect(&mut self) -> Result<()> {
+        (**self).reconnect()
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        (**self).flush()
+    }
+}
+
+impl Transport for SerialTransport {
+    fn send_frame(&mut self, msg_type: u8, payload: &[u8]) -> Result<()> {
+        self.send_frame(msg_type, payload)
+    }
+
+    fn receive_frame(&mut self, timeout_ms: u64) -> Result<Frame> {
+        self.receive_frame(timeout_ms)
+    }
+
+    fn send_command(&mut self, msg_type: u8, payload: &[u8]) -> Result<Frame> {
+        self.send_command(msg_type, payload)
+    }
+
+    fn command_timeout_ms(&self) -> u64 {
+        self.timeout().as_millis() as u64
+    }
+
+    fn stats(&self) -> TransportStats {
+        self.stats()
+    }
+
+    fn transport_type(&self) -> TransportType {
+        TransportType::Serial
+    }
+
+    fn baud_rate(&self) -> Option<u32> {
+        self.baud_rate().ok()
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        self.set_baud_rate(baud_rate)
+    }
+
+    fn reconnect(&mut self) -> Result<()> {
+        self.reopen()
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.clear_buffers()?;
+        discard_frames(self)
+    }
+}
+
+impl Transport for TcpTransport {
+    fn send_frame(&mut self, msg_type: u8, payload: &[u8]) -> Result<()> {
+        self.send_frame(msg_type, payload)
+    }
+
+    fn receive_frame(&mut self, timeout_ms: u64) -> Result<Frame> {
+        self.receive_frame(timeout_ms)
+    }
+
+    fn send_command(&mut self, msg_type: u8, payload: &[u8]) -> Result<Frame> {
+        self.send_command(msg_type, payload)
+    }
+
+    fn command_timeout_ms(&self) -> u64 {
+        tcp::DEFAULT_TIMEOUT_MS
+    }
+
+    fn stats(&self) -> TransportStats {
+        self.stats()
+    }
+
+    fn transport_type(&self) -> TransportType {
+        TransportType::Tcp
+    }
+
+    fn reconnect(&mut self) -> Result<()> {
+        self.reconnect()
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.flush()
+    }
+}
+
+impl Transport for BleTransport {
+    fn send_frame(&mut self, msg_type: u8, payload: &[u8]) -> Result<()> {
+        self.send_frame(msg_type, payload)
+    }
+
+    fn receive_frame(&mut self, timeout_ms: u64) -> Result<Frame> {
+        self.receive_frame(timeout_ms)
+    }
+
+    fn send_command(&mut self, msg_type: u8, payload: &[u8]) -> Result<Frame> {
+        self.send_command(msg_type, payload)
+    }
+
+    fn command_timeout_ms(&self) -> u64 {
+        ble::DEFAULT_TIMEOUT_MS
+    }
+
+    fn stats(&self) -> TransportStats {
+        self.stats()
+    }
+
+    fn max_ota_chunk_size(&self) -> usize {
+        OTA_CHUNK_SIZE_BLE
+    }
+
+    fn set_keepalive(&mut self, interval: Option<Duration>) {
+        self.set_keepalive(interval)
+    }
+
+    fn transport_type(&self) -> TransportType {
+        TransportType::Ble
+    }
+
+    fn reconnect(&mut self) -> Result<()> {
+        self.reconnect()
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.flush();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{Context, DomesError};
+    use std::collections::VecDeque;
+
+    /// Hands out queued frames, then times out
+    struct StreamingDevice {
+        frames: VecDeque<Result<Frame>>,
+    }
+
+    impl Transport for StreamingDevice {
+        fn send_frame(&mut self, _msg_type: u8, _payload: &[u8]) -> Result<()> {
+            Ok(())
+        }
+
+        fn receive_frame(&mut self, _timeout_ms: u64) -> Result<Frame> {
+            self.frames
+                .pop_front()
+                .unwrap_or_else(|| Err(ResponseTimeout.into()))
+        }
+
+        fn send_command(&mut self, _msg_type: u8, _payload: &[u8]) -> Result<Frame> {
+            self.receive_frame(0)
+        }
+    }
+
+    fn device(types: &[u8]) -> StreamingDevice {
+        let frames = types
+            .iter()
+            .map(|&msg_type| {
+                Ok(Frame {
+                    msg_type,
+                    payload: Vec::new(),
+                })
+            })
+            .collect();
+        StreamingDevice { frames }
+    }
+
+    #[test]
+    fn test_flush_discards_unsolicited_frames() {
+        // Trace data and an event pushed before the first command
+        let mut streaming = device(&[0x12, 0x12, 0x61]);
+        streaming.flush().unwrap();
+        assert!(streaming.frames.is_empty());
+    }
+
+    #[test]
+    fn test_flush_skips_garbled_frames_but_not_link_errors() {
+        let crc = frame::FrameError::CrcMismatch {
+            expected: 1,
+            actual: 2,
+        };
+        let mut garbled = device(&[0x12, 0x12]);
+        garbled.frames.insert(1, Err(FrameDecodeError(crc).into()));
+        garbled.flush().unwrap();
+        assert!(garbled.frames.is_empty());
+
+        let mut closed = device(&[0x12, 0x12]);
+        closed.frames.insert(1, Err(anyhow::anyhow!("Connection closed by peer")));
+        let err = closed.flush().unwrap_err();
+        assert_eq!(err.to_string(), "Connection closed by peer");
+        assert_eq!(closed.frames.len(), 1);
+    }
+
+    #[test]
+    fn test_send_command_expect_skips_stray_frames() {
+        // Trace data frames arriving ahead of the config response
+        let mut streaming = device(&[0x12, 0x12, 0x23]);
+        let frame = streaming.send_command_expect(0x22, &[], 0x23).unwrap();
+        assert_eq!(frame.msg_type, 0x23);
+        assert!(streaming.frames.is_empty());
+
+        let err = device(&[0x12])
+            .send_command_expect(0x22, &[], 0x23)
+            .context("Failed to send get mode command")
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DomesError::UnexpectedResponse {
+                expected: 0x23,
+                actual: 0x12
+            }
+        ));
+
+        let err = device(&[])
+            .send_command_expect(0x22, &[], 0x23)
+            .context("Failed to send get mode command")
+            .unwrap_err();
+        assert!(err.is_timeout());
+    }
+}
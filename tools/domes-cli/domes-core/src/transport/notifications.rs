@@ -0,0 +1,78 @@
+//! Background receiver for unsolicited frames
+//!
+//! Once subscribed to events, the device pushes `EventNotification` frames
+//! without being asked. `NotificationReceiver` borrows the transport for the
+//! lifetime of a thread scope, reads frames on a background thread and
+//! forwards them over a channel, so the caller can wait on them with a
+//! timeout (e.g. to check for Ctrl-C) instead of blocking in the transport.
+
+use super::{Frame, Transport};
+use anyhow::Result;
+use crossbeam_channel::{Receiver, Sender};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{Scope, ScopedJoinHandle};
+
+/// How long each background read waits before checking for `stop`
+const POLL_MS: u64 = 100;
+
+/// Frames received on a background thread until stopped
+pub struct NotificationReceiver<'scope> {
+    frames: Receiver<Frame>,
+    stop: Arc<AtomicBool>,
+    handle: ScopedJoinHandle<'scope, Result<()>>,
+}
+
+impl<'scope> NotificationReceiver<'scope> {
+    /// Start receiving frames from `transport` on a thread in `scope`
+    ///
+    /// Read timeouts are expected while the device is quiet and are ignored;
+    /// an I/O error (e.g. the device went away) ends the thread, after which
+    /// `frames()` reports disconnected and `stop()` returns the error.
+    pub fn spawn<'env>(
+        scope: &'scope Scope<'scope, 'env>,
+        transport: &'env mut dyn Transport,
+    ) -> Self {
+        let (tx, rx): (Sender<Frame>, Receiver<Frame>) = crossbeam_channel::unbounded();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+
+        let handle = scope.spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                match transport.receive_frame(POLL_MS) {
+                    Ok(frame) => {
+                        if tx.send(frame).is_err() {
+                            // Receiver dropped, nobody is listening
+                            break;
+                        }
+                    }
+                    Err(e) if e.chain().any(|c| c.is::<std::io::Error>()) => return Err(e),
+                    // Timeout or a corrupt frame: keep listening
+                    Err(_) => {}
+                }
+            }
+            Ok(())
+        });
+
+        Self {
+            frames: rx,
+            stop,
+            handle,
+        }
+    }
+
+    /// Channel of received frames, in arrival order
+    pub fn frames(&self) -> &Receiver<Frame> {
+        &self.frames
+    }
+
+    /// Stop the background thread and wait for it, returning the transport's
+    /// error if reading failed
+    pub fn stop(self) -> Result<()> {
+        self.stop.store(true, Ordering::Relaxed);
+        match self.handle.join() {
+            Ok(result) => result,
+            Err(_) => anyhow::bail!("Notification receiver thread panicked"),
+        }
+    }
+}
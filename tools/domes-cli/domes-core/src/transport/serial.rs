@@ -0,0 +1,535 @@
+//! Serial port transport for DOMES CLI
+//!
+//! Handles USB CDC communication with the ESP32-S3 device.
+
+use super::frame::{encode_frame, Frame, FrameDecoder, FRAME_OVERHEAD};
+use super::{FrameDecodeError, ResponseTimeout, TransportStats};
+use anyhow::{Context, Result};
+use serialport::{ClearBuffer, SerialPort};
+use std::io::{Read, Write};
+use std::time::Duration;
+
+/// Default serial port settings
+pub const DEFAULT_BAUD_RATE: u32 = 115200;
+const DEFAULT_TIMEOUT_MS: u64 = 1000;
+
+/// Shortest read timeout set on the port, so a 0 ms poll does not spin
+const MIN_READ_TIMEOUT: Duration = Duration::from_millis(1);
+
+/// Baud rates DOMES boards and USB-serial bridges are usually run at
+pub const COMMON_BAUD_RATES: &[u32] = &[
+    9600, 19200, 38400, 57600, 115200, 230400, 460800, 921600, 1500000, 2000000,
+];
+
+/// Whether `baud_rate` is one of `COMMON_BAUD_RATES`
+pub fn is_common_baud_rate(baud_rate: u32) -> bool {
+    COMMON_BAUD_RATES.contains(&baud_rate)
+}
+
+/// Error message when the device goes away mid-command
+const DISCONNECTED: &str = "Serial device disconnected";
+
+/// Whether a read/write error means the port itself is gone (e.g. USB unplugged)
+fn is_disconnect(e: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+
+    if matches!(
+        e.kind(),
+        ErrorKind::BrokenPipe | ErrorKind::NotFound | ErrorKind::NotConnected
+    ) {
+        return true;
+    }
+
+    // Linux/macOS report a vanished tty as EIO, ENXIO or ENODEV
+    #[cfg(unix)]
+    if matches!(e.raw_os_error(), Some(5 | 6 | 19)) {
+        return true;
+    }
+
+    false
+}
+
+/// How long EN (and GPIO0) are held during a reset, as esptool.py does
+const RESET_PULSE: Duration = Duration::from_millis(100);
+
+/// A step of a reset sequence
+#[derive(Clone, Copy)]
+enum ResetStep {
+    /// Set RTS, wired to EN (reset) on ESP32 boards
+    Rts(bool),
+    /// Set DTR, wired to GPIO0 (boot mode) on ESP32 boards
+    Dtr(bool),
+    /// Wait `RESET_PULSE`
+    Wait,
+}
+
+/// Serial port settings
+#[derive(Debug, Clone)]
+pub struct SerialConfig {
+    pub baud_rate: u32,
+    /// Read timeout, also used as the response timeout for `send_command`
+    pub timeout: Duration,
+    /// Give up on a frame the device stops sending partway through after
+    /// this long, rather than waiting out the response timeout
+    pub max_frame_duration: Option<Duration>,
+}
+
+impl Default for SerialConfig {
+    fn default() -> Self {
+        Self {
+            baud_rate: DEFAULT_BAUD_RATE,
+            timeout: Duration::from_millis(DEFAULT_TIMEOUT_MS),
+            max_frame_duration: None,
+        }
+    }
+}
+
+/// Serial transport for communicating with DOMES device
+pub struct SerialTransport {
+    port: Box<dyn SerialPort>,
+    /// Name the port was opened by, for `reopen`
+    port_name: Option<String>,
+    /// Baud rate the port was last set to
+    baud: u32,
+    decoder: FrameDecoder,
+    stats: TransportStats,
+    /// Response timeout for `send_command`
+    timeout_ms: u64,
+    /// What the port's read timeout is currently set to
+    read_timeout: Duration,
+}
+
+impl SerialTransport {
+    /// Open a serial connection to the device at `DEFAULT_BAUD_RATE`
+    pub fn open_default(port_name: &str) -> Result<Self> {
+        Self::open_with_config(port_name, &SerialConfig::default())
+    }
+
+    /// Open a serial connection to the device at `baud_rate`
+    pub fn open(port_name: &str, baud_rate: u32) -> Result<Self> {
+        let config = SerialConfig {
+            baud_rate,
+            ..Default::default()
+        };
+        Self::open_with_config(port_name, &config)
+    }
+
+    /// Open a serial connection to the device
+    pub fn open_with_config(port_name: &str, config: &SerialConfig) -> Result<Self> {
+        let port = serialport::new(port_name, config.baud_rate)
+            .timeout(config.timeout)
+            .open()
+            .with_context(|| format!("Failed to open serial port: {}", port_name))?;
+
+        let mut transport = Self::with_port(port, config.timeout);
+        transport.decoder.set_max_frame_duration(config.max_frame_duration);
+        Ok(transport)
+    }
+
+    fn with_port(port: Box<dyn SerialPort>, timeout: Duration) -> Self {
+        Self {
+            port_name: port.name(),
+            baud: port.baud_rate().unwrap_or(DEFAULT_BAUD_RATE),
+            port,
+            decoder: FrameDecoder::new(),
+            stats: TransportStats::default(),
+            timeout_ms: timeout.as_millis() as u64,
+            read_timeout: timeout,
+        }
+    }
+
+    /// Change the response timeout for `send_command` (and the port's read
+    /// timeout); `send_command_with_timeout` still overrides it per call
+    pub fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.set_read_timeout(timeout)?;
+        self.timeout_ms = timeout.as_millis() as u64;
+        Ok(())
+    }
+
+    /// Response timeout for `send_command`
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+
+    /// The port's current baud rate
+    pub fn baud_rate(&self) -> Result<u32> {
+        self.port.baud_rate().context("Failed to read serial baud rate")
+    }
+
+    /// Switch the port to `baud_rate` (the device must switch too, see
+    /// `commands::set_baud_rate`)
+    pub fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        self.port
+            .set_baud_rate(baud_rate)
+            .with_context(|| format!("Failed to set baud rate to {}", baud_rate))?;
+        self.baud = baud_rate;
+        self.decoder.reset();
+        Ok(())
+    }
+
+    /// Drop the bytes waiting in the OS buffers and any partial frame (part
+    /// of `Transport::flush`, which then drains what is still arriving)
+    pub fn clear_buffers(&mut self) -> Result<()> {
+        self.port
+            .clear(ClearBuffer::All)
+            .context("Failed to clear serial port buffers")?;
+        self.decoder.reset();
+        Ok(())
+    }
+
+    /// Open the port again by name, at the same baud rate and timeout
+    ///
+    /// A USB serial device that reboots comes back as a new device under
+    /// the same name; the old handle stays dead.
+    pub fn reopen(&mut self) -> Result<()> {
+        let name = self.port_name.clone().context("Serial port has no name to reopen")?;
+        self.port = serialport::new(&name, self.baud)
+            .timeout(self.read_timeout)
+            .open()
+            .with_context(|| format!("Failed to open serial port: {}", name))?;
+        self.decoder.reset();
+        Ok(())
+    }
+
+    /// Set the port's read timeout, skipping the call if it is unchanged
+    fn set_read_timeout(&mut self, timeout: Duration) -> Result<()> {
+        if timeout != self.read_timeout {
+            self.port
+                .set_timeout(timeout)
+                .context("Failed to set serial port timeout")?;
+            self.read_timeout = timeout;
+        }
+        Ok(())
+    }
+
+    /// Send a frame to the device
+    pub fn send_frame(&mut self, msg_type: u8, payload: &[u8]) -> Result<()> {
+        let frame = encode_frame(msg_type, payload)?;
+        if let Err(e) = self.port.write_all(&frame).and_then(|_| self.port.flush()) {
+            if is_disconnect(&e) {
+                return Err(e).context(DISCONNECTED);
+            }
+            return Err(e).context("Failed to write frame to serial port");
+        }
+        self.stats.record_tx(frame.len());
+        Ok(())
+    }
+
+    /// Receive a frame from the device with timeout
+    pub fn receive_frame(&mut self, timeout_ms: u64) -> Result<Frame> {
+        // Bytes left over from a bad frame may hold the next one
+        if let Some(result) = self.decoder.take_buffered() {
+            let frame = result.map_err(FrameDecodeError)?;
+            self.stats.record_rx(FRAME_OVERHEAD + frame.payload.len());
+            return Ok(frame);
+        }
+
+        let start = std::time::Instant::now();
+        let timeout = Duration::from_millis(timeout_ms);
+        // A blocking read must not outlast a shorter per-call timeout, nor
+        // the time a started frame has left
+        let read_timeout = match self.decoder.max_frame_duration() {
+            Some(max) => timeout.min(max),
+            None => timeout,
+        };
+        self.set_read_timeout(read_timeout.max(MIN_READ_TIMEOUT))?;
+
+        let mut buf = [0u8; 1];
+
+        loop {
+            if start.elapsed() > timeout {
+                return Err(ResponseTimeout.into());
+            }
+            // A device that stopped mid-frame is not waited out
+            self.decoder
+                .check_frame_timeout()
+                .map_err(FrameDecodeError)?;
+
+            match self.port.read(&mut buf) {
+                Ok(1) => {
+                    if let Some(result) = self.decoder.feed_byte(buf[0]) {
+                        let frame =
+                            result.map_err(FrameDecodeError)?;
+                        self.stats.record_rx(FRAME_OVERHEAD + frame.payload.len());
+                        return Ok(frame);
+                    }
+                }
+                Ok(0) => {
+                    // No data available, continue waiting
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                Ok(_) => {
+                    // Unexpected: more bytes than buffer size (shouldn't happen with 1-byte buffer)
+                    continue;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+                    // Timeout on read, continue loop and check overall timeout
+                    continue;
+                }
+                Err(e) if is_disconnect(&e) => {
+                    // Unplugged: fail now rather than waiting out the timeout
+                    return Err(e).context(DISCONNECTED);
+                }
+                Err(e) => {
+                    return Err(e).context("Failed to read from serial port");
+                }
+            }
+        }
+    }
+
+    /// Send a command and wait for response
+    pub fn send_command(&mut self, msg_type: u8, payload: &[u8]) -> Result<Frame> {
+        self.send_frame(msg_type, payload)?;
+        self.receive_frame(self.timeout_ms)
+    }
+
+    /// Frames and bytes sent and received so far
+    pub fn stats(&self) -> TransportStats {
+        self.stats
+    }
+
+    /// Hold the TX line in break for `duration`
+    ///
+    /// The ESP32 ROM and USB-Serial-JTAG bridge treat a long break as a
+    /// reset request; a DOMES pod reboots.
+    pub fn send_break(&mut self, duration: Duration) -> Result<()> {
+        self.port.set_break().context("Failed to start serial break")?;
+        std::thread::sleep(duration);
+        self.port.clear_break().context("Failed to end serial break")?;
+        self.decoder.reset();
+        Ok(())
+    }
+
+    /// Reset the device into its application by pulsing EN through RTS,
+    /// the way `esptool.py --after hard_reset` does
+    pub fn hard_reset(&mut self) -> Result<()> {
+        self.set_lines(&[
+            ResetStep::Dtr(false),
+            ResetStep::Rts(true),
+            ResetStep::Wait,
+            ResetStep::Rts(false),
+        ])
+    }
+
+    /// Reset the device into the ROM download mode
+    ///
+    /// The esptool.py classic reset: EN is held low through RTS, then
+    /// released while DTR holds GPIO0 low, so the chip samples GPIO0 low at
+    /// boot. DTR is released once the bootloader has started.
+    pub fn enter_bootloader(&mut self) -> Result<()> {
+        self.set_lines(&[
+            ResetStep::Rts(true),
+            ResetStep::Dtr(false),
+            ResetStep::Wait,
+            ResetStep::Dtr(true),
+            ResetStep::Rts(false),
+            ResetStep::Wait,
+            ResetStep::Dtr(false),
+        ])
+    }
+
+    /// Drive the modem control lines in order
+    fn set_lines(&mut self, steps: &[ResetStep]) -> Result<()> {
+        for step in steps {
+            match *step {
+                ResetStep::Rts(level) => self
+                    .port
+                    .write_request_to_send(level)
+                    .context("Failed to set RTS")?,
+                ResetStep::Dtr(level) => self
+                    .port
+                    .write_data_terminal_ready(level)
+                    .context("Failed to set DTR")?,
+                ResetStep::Wait => std::thread::sleep(RESET_PULSE),
+            }
+        }
+        self.decoder.reset();
+        Ok(())
+    }
+
+    /// List available serial ports
+    pub fn list_ports() -> Result<Vec<String>> {
+        let ports = serialport::available_ports().context("Failed to enumerate serial ports")?;
+
+        Ok(ports.into_iter().map(|p| p.port_name).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serialport::{ClearBuffer, DataBits, FlowControl, Parity, StopBits};
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    /// Serial port whose reads and writes fail with a fixed error kind
+    struct FailingPort {
+        kind: io::ErrorKind,
+        /// Modem control line changes and breaks, in order
+        lines: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Read for FailingPort {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::from(self.kind))
+        }
+    }
+
+    impl Write for FailingPort {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::from(self.kind))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SerialPort for FailingPort {
+        fn name(&self) -> Option<String> {
+            Some("mock".into())
+        }
+        fn baud_rate(&self) -> serialport::Result<u32> {
+            Ok(DEFAULT_BAUD_RATE)
+        }
+        fn data_bits(&self) -> serialport::Result<DataBits> {
+            Ok(DataBits::Eight)
+        }
+        fn flow_control(&self) -> serialport::Result<FlowControl> {
+            Ok(FlowControl::None)
+        }
+        fn parity(&self) -> serialport::Result<Parity> {
+            Ok(Parity::None)
+        }
+        fn stop_bits(&self) -> serialport::Result<StopBits> {
+            Ok(StopBits::One)
+        }
+        fn timeout(&self) -> Duration {
+            Duration::from_millis(DEFAULT_TIMEOUT_MS)
+        }
+        fn set_baud_rate(&mut self, _: u32) -> serialport::Result<()> {
+            Ok(())
+        }
+        fn set_data_bits(&mut self, _: DataBits) -> serialport::Result<()> {
+            Ok(())
+        }
+        fn set_flow_control(&mut self, _: FlowControl) -> serialport::Result<()> {
+            Ok(())
+        }
+        fn set_parity(&mut self, _: Parity) -> serialport::Result<()> {
+            Ok(())
+        }
+        fn set_stop_bits(&mut self, _: StopBits) -> serialport::Result<()> {
+            Ok(())
+        }
+        fn set_timeout(&mut self, _: Duration) -> serialport::Result<()> {
+            Ok(())
+        }
+        fn write_request_to_send(&mut self, level: bool) -> serialport::Result<()> {
+            self.lines.lock().unwrap().push(format!("RTS={}", level as u8));
+            Ok(())
+        }
+        fn write_data_terminal_ready(&mut self, level: bool) -> serialport::Result<()> {
+            self.lines.lock().unwrap().push(format!("DTR={}", level as u8));
+            Ok(())
+        }
+        fn read_clear_to_send(&mut self) -> serialport::Result<bool> {
+            Ok(false)
+        }
+        fn read_data_set_ready(&mut self) -> serialport::Result<bool> {
+            Ok(false)
+        }
+        fn read_ring_indicator(&mut self) -> serialport::Result<bool> {
+            Ok(false)
+        }
+        fn read_carrier_detect(&mut self) -> serialport::Result<bool> {
+            Ok(false)
+        }
+        fn bytes_to_read(&self) -> serialport::Result<u32> {
+            Ok(0)
+        }
+        fn bytes_to_write(&self) -> serialport::Result<u32> {
+            Ok(0)
+        }
+        fn clear(&self, _: ClearBuffer) -> serialport::Result<()> {
+            Ok(())
+        }
+        fn try_clone(&self) -> serialport::Result<Box<dyn SerialPort>> {
+            Ok(Box::new(FailingPort {
+                kind: self.kind,
+                lines: self.lines.clone(),
+            }))
+        }
+        fn set_break(&self) -> serialport::Result<()> {
+            self.lines.lock().unwrap().push("BREAK".into());
+            Ok(())
+        }
+        fn clear_break(&self) -> serialport::Result<()> {
+            self.lines.lock().unwrap().push("END".into());
+            Ok(())
+        }
+    }
+
+    fn transport(kind: io::ErrorKind) -> SerialTransport {
+        let port = FailingPort {
+            kind,
+            lines: Arc::default(),
+        };
+        SerialTransport::with_port(Box::new(port), Duration::from_secs(10))
+    }
+
+    #[test]
+    fn test_receive_fails_fast_on_disconnect() {
+        let mut transport = transport(io::ErrorKind::BrokenPipe);
+        let started = std::time::Instant::now();
+        let err = transport.receive_frame(10_000).unwrap_err();
+        assert_eq!(err.to_string(), DISCONNECTED);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_send_reports_disconnect() {
+        let mut transport = transport(io::ErrorKind::NotFound);
+        let err = transport.send_frame(0x20, &[]).unwrap_err();
+        assert_eq!(err.to_string(), DISCONNECTED);
+    }
+
+    #[test]
+    fn test_set_timeout_changes_default_only() {
+        let mut transport = transport(io::ErrorKind::TimedOut);
+        transport.set_timeout(Duration::from_millis(50)).unwrap();
+        assert_eq!(transport.timeout(), Duration::from_millis(50));
+
+        // The per-call timeout wins, in both directions
+        let started = std::time::Instant::now();
+        transport.receive_frame(200).unwrap_err();
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert_eq!(transport.read_timeout, Duration::from_millis(200));
+        assert_eq!(transport.timeout(), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_timeout_is_not_disconnect() {
+        let mut transport = transport(io::ErrorKind::TimedOut);
+        let err = transport.receive_frame(50).unwrap_err();
+        assert_eq!(err.to_string(), "Timeout waiting for response");
+    }
+
+    #[test]
+    fn test_reset_sequences_match_esptool() {
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let port = FailingPort {
+            kind: io::ErrorKind::TimedOut,
+            lines: lines.clone(),
+        };
+        let mut transport = SerialTransport::with_port(Box::new(port), Duration::from_secs(1));
+
+        transport.enter_bootloader().unwrap();
+        assert_eq!(*lines.lock().unwrap(), ["RTS=1", "DTR=0", "DTR=1", "RTS=0", "DTR=0"]);
+
+        lines.lock().unwrap().clear();
+        transport.hard_reset().unwrap();
+        transport.send_break(Duration::from_millis(1)).unwrap();
+        assert_eq!(*lines.lock().unwrap(), ["DTR=0", "RTS=1", "RTS=0", "BREAK", "END"]);
+    }
+}
@@ -0,0 +1,94 @@
+//! Per-transport traffic counters
+//!
+//! Every transport counts the frames and wire bytes (including framing and
+//! CRC) it sends and receives, so a command can report how much data it
+//! moved and over what time span.
+
+use std::time::{Duration, Instant};
+
+/// Frames and bytes moved over a transport
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TransportStats {
+    pub bytes_tx: u64,
+    pub bytes_rx: u64,
+    pub frames_tx: u64,
+    pub frames_rx: u64,
+    first_activity: Option<Instant>,
+    last_activity: Option<Instant>,
+}
+
+impl TransportStats {
+    /// Count one frame of `bytes` wire bytes sent
+    pub fn record_tx(&mut self, bytes: usize) {
+        self.bytes_tx += bytes as u64;
+        self.frames_tx += 1;
+        self.touch();
+    }
+
+    /// Count one frame of `bytes` wire bytes received
+    pub fn record_rx(&mut self, bytes: usize) {
+        self.bytes_rx += bytes as u64;
+        self.frames_rx += 1;
+        self.touch();
+    }
+
+    /// Time between the first and last frame
+    pub fn duration(&self) -> Duration {
+        match (self.first_activity, self.last_activity) {
+            (Some(first), Some(last)) => last.duration_since(first),
+            _ => Duration::ZERO,
+        }
+    }
+
+    fn touch(&mut self) {
+        let now = Instant::now();
+        self.first_activity.get_or_insert(now);
+        self.last_activity = Some(now);
+    }
+}
+
+impl std::fmt::Display for TransportStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "sent {} in {} frames, received {} in {} frames over {:.1}s",
+            format_bytes(self.bytes_tx),
+            self.frames_tx,
+            format_bytes(self.bytes_rx),
+            self.frames_rx,
+            self.duration().as_secs_f64()
+        )
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_counters() {
+        let mut stats = TransportStats::default();
+        assert_eq!(stats.duration(), Duration::ZERO);
+
+        stats.record_tx(1025);
+        stats.record_tx(9);
+        stats.record_rx(12);
+        assert_eq!(stats.bytes_tx, 1034);
+        assert_eq!(stats.frames_tx, 2);
+        assert_eq!(stats.bytes_rx, 12);
+        assert_eq!(stats.frames_rx, 1);
+        assert!(stats
+            .to_string()
+            .starts_with("sent 1.0 KiB in 2 frames, received 12 B in 1 frames over"));
+    }
+}
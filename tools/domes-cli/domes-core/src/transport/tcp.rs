@@ -0,0 +1,480 @@
+//! TCP socket transport for DOMES CLI
+//!
+//! Handles WiFi communication with the ESP32-S3 device over TCP.
+//!
+//! For testing over a slow link, `set_rate_limit` paces the bytes sent and
+//! received to a fixed rate in each direction, as a degraded WiFi connection
+//! would deliver them.
+
+use super::frame::{encode_frame, Frame, FrameDecoder, FRAME_OVERHEAD};
+use super::{FrameDecodeError, ResponseTimeout, TransportStats};
+use crate::error::DomesError;
+use anyhow::{Context, Result};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+/// Default TCP connection settings
+pub(super) const DEFAULT_TIMEOUT_MS: u64 = 2000;
+
+/// Bytes written at a time when sending is rate limited
+const THROTTLE_CHUNK_SIZE: usize = 64;
+
+/// TCP transport for communicating with DOMES device over WiFi
+pub struct TcpTransport {
+    stream: TcpStream,
+    /// Address connected to, for `reconnect`
+    peer: Option<SocketAddr>,
+    decoder: FrameDecoder,
+    stats: TransportStats,
+    /// Pacing for sent and received bytes, when rate limited
+    throttle: Option<(Throttle, Throttle)>,
+}
+
+impl TcpTransport {
+    /// Connect to the device at the given address, giving up after
+    /// `DEFAULT_TIMEOUT_MS`
+    ///
+    /// Address format: "ip:port" (e.g., "192.168.1.100:5000")
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<Self> {
+        let addrs: Vec<SocketAddr> = addr
+            .to_socket_addrs()
+            .context("Failed to resolve address")?
+            .collect();
+        let label = match addrs.first() {
+            Some(first) => first.to_string(),
+            None => anyhow::bail!("No addresses to connect to"),
+        };
+        Self::connect_addrs(&label, &addrs, Duration::from_millis(DEFAULT_TIMEOUT_MS))
+    }
+
+    /// Connect to the device, giving up after `timeout`
+    ///
+    /// Each resolved address is tried in turn with the full timeout.
+    pub fn connect_with_timeout(addr: &str, timeout: Duration) -> Result<Self> {
+        let addrs: Vec<_> = addr
+            .to_socket_addrs()
+            .with_context(|| format!("Failed to resolve {}", addr))?
+            .collect();
+        Self::connect_addrs(addr, &addrs, timeout)
+    }
+
+    /// Try each of `addrs` in turn with the full `timeout`; a failure is a
+    /// `DomesError::Transport` naming `label`
+    fn connect_addrs(label: &str, addrs: &[SocketAddr], timeout: Duration) -> Result<Self> {
+        let mut last_err = None;
+        for socket_addr in addrs {
+            match TcpStream::connect_timeout(socket_addr, timeout) {
+                Ok(stream) => return Self::from_stream(stream),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => {
+                let context =
+                    format!("Failed to connect to {} within {} ms", label, timeout.as_millis());
+                Err(DomesError::Transport { context, source: e.into() }.into())
+            }
+            None => anyhow::bail!("No addresses found for {}", label),
+        }
+    }
+
+    /// Connect to the device, retrying while the connection is refused
+    ///
+    /// A device that has just booted may not be listening yet. Each refused
+    /// attempt waits `base_delay`, doubling every time, before the next, up
+    /// to `max_attempts` in all. Any other error is returned at once.
+    pub fn connect_retry<A: ToSocketAddrs>(
+        addr: A,
+        max_attempts: u32,
+        base_delay: Duration,
+    ) -> Result<Self> {
+        let addrs: Vec<SocketAddr> = addr
+            .to_socket_addrs()
+            .context("Failed to resolve address")?
+            .collect();
+        let label = match addrs.first() {
+            Some(first) => first.to_string(),
+            None => anyhow::bail!("No addresses to connect to"),
+        };
+        retry_refused(&label, max_attempts, base_delay, || Self::connect(&addrs[..]))
+    }
+
+    fn from_stream(stream: TcpStream) -> Result<Self> {
+        Self::configure(&stream)?;
+        Ok(Self {
+            peer: stream.peer_addr().ok(),
+            stream,
+            decoder: FrameDecoder::new(),
+            stats: TransportStats::default(),
+            throttle: None,
+        })
+    }
+
+    /// Connect again to the same address, keeping any rate limit
+    ///
+    /// The device drops the connection when it reboots.
+    pub fn reconnect(&mut self) -> Result<()> {
+        let peer = self.peer.context("No address to reconnect to")?;
+        let timeout = Duration::from_millis(DEFAULT_TIMEOUT_MS);
+        let stream = TcpStream::connect_timeout(&peer, timeout)
+            .with_context(|| format!("Failed to connect to {}", peer))?;
+        Self::configure(&stream)?;
+        self.stream = stream;
+        self.decoder.reset();
+        Ok(())
+    }
+
+    /// Discard the bytes already received and any partial frame
+    pub fn flush(&mut self) -> Result<()> {
+        self.stream
+            .set_nonblocking(true)
+            .context("Failed to clear TCP receive buffer")?;
+        let mut buf = [0u8; 256];
+        let drained = loop {
+            match self.stream.read(&mut buf) {
+                Ok(0) => {
+                    let closed = std::io::ErrorKind::UnexpectedEof;
+                    break Err(std::io::Error::new(closed, "Connection closed by peer"));
+                }
+                Ok(_) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break Ok(()),
+                Err(e) => break Err(e),
+            }
+        };
+        self.stream
+            .set_nonblocking(false)
+            .context("Failed to clear TCP receive buffer")?;
+        drained.context("Failed to clear TCP receive buffer")?;
+        self.decoder.reset();
+        Ok(())
+    }
+
+    fn configure(stream: &TcpStream) -> Result<()> {
+        // Set timeouts
+        stream
+            .set_read_timeout(Some(Duration::from_millis(DEFAULT_TIMEOUT_MS)))
+            .context("Failed to set read timeout")?;
+        stream
+            .set_write_timeout(Some(Duration::from_millis(DEFAULT_TIMEOUT_MS)))
+            .context("Failed to set write timeout")?;
+
+        // Disable Nagle's algorithm for low latency
+        stream
+            .set_nodelay(true)
+            .context("Failed to set TCP_NODELAY")
+    }
+
+    /// Pace traffic to `kbps` kilobits per second in each direction, or
+    /// lift the limit with `None`
+    pub fn set_rate_limit(&mut self, kbps: Option<u32>) {
+        self.throttle = kbps.map(|kbps| (Throttle::new(kbps), Throttle::new(kbps)));
+    }
+
+    /// Give up on a frame the device stops sending partway through after
+    /// `max`, rather than waiting out the response timeout (`None` waits)
+    pub fn set_max_frame_duration(&mut self, max: Option<Duration>) {
+        self.decoder.set_max_frame_duration(max);
+    }
+
+    /// Get the peer address
+    pub fn peer_addr(&self) -> Result<String> {
+        Ok(self.stream.peer_addr()?.to_string())
+    }
+
+    /// Send a frame to the device
+    pub fn send_frame(&mut self, msg_type: u8, payload: &[u8]) -> Result<()> {
+        let frame = encode_frame(msg_type, payload)?;
+        match &mut self.throttle {
+            Some((tx, _)) => {
+                for chunk in frame.chunks(THROTTLE_CHUNK_SIZE) {
+                    tx.pace(chunk.len());
+                    self.stream
+                        .write_all(chunk)
+                        .context("Failed to write frame to TCP socket")?;
+                }
+            }
+            None => self
+                .stream
+                .write_all(&frame)
+                .context("Failed to write frame to TCP socket")?,
+        }
+        self.stream.flush().context("Failed to flush TCP socket")?;
+        self.stats.record_tx(frame.len());
+        Ok(())
+    }
+
+    /// Receive a frame from the device with timeout
+    pub fn receive_frame(&mut self, timeout_ms: u64) -> Result<Frame> {
+        // Bytes left over from a bad frame may hold the next one
+        if let Some(result) = self.decoder.take_buffered() {
+            let frame = result.map_err(FrameDecodeError)?;
+            self.stats.record_rx(FRAME_OVERHEAD + frame.payload.len());
+            return Ok(frame);
+        }
+
+        let start = std::time::Instant::now();
+        let timeout = Duration::from_millis(timeout_ms);
+
+        // Set the read timeout for this receive; a started frame is checked
+        // at least every `max_frame_duration`
+        let read_timeout = match self.decoder.max_frame_duration() {
+            Some(max) => timeout.min(max),
+            None => timeout,
+        };
+        self.stream
+            .set_read_timeout(Some(read_timeout))
+            .context("Failed to set read timeout")?;
+
+        let mut buf = [0u8; 1];
+
+        loop {
+            if start.elapsed() > timeout {
+                return Err(ResponseTimeout.into());
+            }
+            // A device that stopped mid-frame is not waited out
+            self.decoder
+                .check_frame_timeout()
+                .map_err(FrameDecodeError)?;
+
+            match self.stream.read(&mut buf) {
+                Ok(1) => {
+                    if let Some((_, rx)) = &mut self.throttle {
+                        rx.pace(1);
+                    }
+                    if let Some(result) = self.decoder.feed_byte(buf[0]) {
+                        let frame =
+                            result.map_err(FrameDecodeError)?;
+                        self.stats.record_rx(FRAME_OVERHEAD + frame.payload.len());
+                        return Ok(frame);
+                    }
+                }
+                Ok(0) => {
+                    // Connection closed
+                    anyhow::bail!("Connection closed by peer");
+                }
+                Ok(_) => {
+                    // Unexpected: more bytes than buffer size (shouldn't happen with 1-byte buffer)
+                    continue;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+                    // Continue loop and check overall timeout
+                    continue;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    // Non-blocking read would block, continue
+                    std::thread::sleep(Duration::from_millis(1));
+                    continue;
+                }
+                Err(e) => {
+                    return Err(e).context("Failed to read from TCP socket");
+                }
+            }
+        }
+    }
+
+    /// Send a command and wait for response
+    pub fn send_command(&mut self, msg_type: u8, payload: &[u8]) -> Result<Frame> {
+        self.send_frame(msg_type, payload)?;
+        self.receive_frame(DEFAULT_TIMEOUT_MS)
+    }
+
+    /// Frames and bytes sent and received so far
+    pub fn stats(&self) -> TransportStats {
+        self.stats
+    }
+}
+
+/// Paces bytes to a fixed rate
+struct Throttle {
+    byte_time: Duration,
+    /// When the bytes paced so far have gone through at the rate
+    next: Instant,
+}
+
+impl Throttle {
+    fn new(kbps: u32) -> Self {
+        Self {
+            byte_time: Duration::from_secs_f64(8.0 / (kbps.max(1) as f64 * 1000.0)),
+            next: Instant::now(),
+        }
+    }
+
+    /// Wait until `bytes` more would have gone through at the rate
+    ///
+    /// Idle time is not saved up, so a burst after a pause is paced too.
+    fn pace(&mut self, bytes: usize) {
+        let now = Instant::now();
+        self.next = self.next.max(now) + self.byte_time.mul_f64(bytes as f64);
+        std::thread::sleep(self.next - now);
+    }
+}
+
+/// Run `connect` up to `max_attempts` times while it fails with
+/// `ConnectionRefused`, sleeping `base_delay * 2^(n-1)` after the nth
+///
+/// Attempts after the first are logged as "Connecting to ADDR (attempt 2/5)...".
+pub(crate) fn retry_refused<T>(
+    label: &str,
+    max_attempts: u32,
+    base_delay: Duration,
+    mut connect: impl FnMut() -> Result<T>,
+) -> Result<T> {
+    let max_attempts = max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match connect() {
+            Err(e) if attempt < max_attempts && is_refused(&e) => {
+                std::thread::sleep(base_delay.saturating_mul(1 << (attempt - 1).min(16)));
+                attempt += 1;
+                crate::progress!("Connecting to {} (attempt {}/{})...", label, attempt, max_attempts);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Whether nothing was listening at the address
+fn is_refused(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(|e| e.kind() == std::io::ErrorKind::ConnectionRefused)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_connect_retry_only_retries_refused() {
+        // Bound then dropped: nothing listens there any more
+        let dead = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let start = Instant::now();
+        let err = TcpTransport::connect_retry(dead, 3, Duration::from_millis(20)).err().unwrap();
+        assert!(is_refused(&err));
+        // 20 ms then 40 ms between the three attempts
+        assert!(start.elapsed() >= Duration::from_millis(60));
+
+        let mut attempts = 0;
+        let result: Result<()> = retry_refused("pod", 5, Duration::ZERO, || {
+            attempts += 1;
+            Err(std::io::Error::from(std::io::ErrorKind::NetworkUnreachable).into())
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let live = listener.local_addr().unwrap();
+        assert!(TcpTransport::connect_retry(live, 3, Duration::from_millis(20)).is_ok());
+    }
+
+    #[test]
+    fn test_connect_failure_names_address_and_timeout() {
+        let dead = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let err = TcpTransport::connect(dead).err().unwrap();
+        assert!(matches!(err.downcast_ref(), Some(DomesError::Transport { .. })));
+        let expected = format!("Failed to connect to {} within {} ms", dead, DEFAULT_TIMEOUT_MS);
+        assert_eq!(err.to_string(), expected);
+
+        let addr = dead.to_string();
+        let err = TcpTransport::connect_with_timeout(&addr, Duration::from_millis(300))
+            .err()
+            .unwrap();
+        assert!(matches!(err.downcast_ref(), Some(DomesError::Transport { .. })));
+        assert_eq!(err.to_string(), format!("Failed to connect to {} within 300 ms", addr));
+    }
+
+    #[test]
+    fn test_flush_fails_once_the_device_disconnects() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut transport = TcpTransport::connect(listener.local_addr().unwrap()).unwrap();
+        let (mut device, _) = listener.accept().unwrap();
+
+        device.write_all(b"log output").unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        transport.flush().unwrap();
+
+        device.write_all(b"last words").unwrap();
+        drop(device);
+        std::thread::sleep(Duration::from_millis(50));
+        let err = transport.flush().unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "Failed to clear TCP receive buffer: Connection closed by peer"
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_paces_both_directions() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut transport = TcpTransport::connect(listener.local_addr().unwrap()).unwrap();
+        let (mut device, _) = listener.accept().unwrap();
+        // 40 kbps is 5000 bytes/s, so 500 bytes take 100 ms each way
+        transport.set_rate_limit(Some(40));
+
+        let start = Instant::now();
+        transport.send_frame(0x20, &[0; 491]).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        let mut sent = [0; 500];
+        device.read_exact(&mut sent).unwrap();
+
+        device.write_all(&encode_frame(0x21, &[0; 491]).unwrap()).unwrap();
+        let start = Instant::now();
+        let frame = transport.receive_frame(1000).unwrap();
+        assert_eq!(frame.payload.len(), 491);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        transport.set_rate_limit(None);
+        device.write_all(&encode_frame(0x21, &[0; 491]).unwrap()).unwrap();
+        let start = Instant::now();
+        transport.receive_frame(1000).unwrap();
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_stalled_frame_fails_before_response_timeout() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut transport = TcpTransport::connect(listener.local_addr().unwrap()).unwrap();
+        let (mut device, _) = listener.accept().unwrap();
+        transport.set_max_frame_duration(Some(Duration::from_millis(50)));
+
+        // Header and part of the payload, then nothing
+        let frame = encode_frame(0x21, &[0; 32]).unwrap();
+        device.write_all(&frame[..10]).unwrap();
+        let start = Instant::now();
+        let err = transport.receive_frame(5000).unwrap_err();
+        assert!(err.to_string().contains("Frame timeout"), "{}", err);
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        // A whole frame afterwards is received normally
+        device.write_all(&frame).unwrap();
+        assert_eq!(transport.receive_frame(1000).unwrap().payload.len(), 32);
+    }
+
+    #[test]
+    fn test_frame_after_corrupt_frame_is_kept() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut transport = TcpTransport::connect(listener.local_addr().unwrap()).unwrap();
+        let (mut device, _) = listener.accept().unwrap();
+
+        // The first frame's corrupt length covers the whole second frame,
+        // which is only read while decoding the first
+        let mut bad = encode_frame(0x21, &[1]).unwrap();
+        bad[2] = 12;
+        let good = encode_frame(0x21, &[2]).unwrap();
+        device.write_all(&[bad, good].concat()).unwrap();
+
+        let err = transport.receive_frame(1000).unwrap_err();
+        assert!(err.to_string().contains("CRC mismatch"), "{}", err);
+        assert_eq!(transport.receive_frame(1000).unwrap().payload, [2]);
+    }
+}
@@ -0,0 +1,112 @@
+//! Per-device progress bars for `--parallel ota flash`
+//!
+//! With several devices flashing at once, each gets its own bar in one
+//! `MultiProgress` on stderr. Progress lines (retries, baud rate changes)
+//! are printed above the bars while they are drawn. Without a terminal, or
+//! with `--quiet`, devices report progress as labelled lines instead.
+//!
+//! Each `BleTransport` owns its tokio runtime and only blocks on it from
+//! the worker thread that holds the transport, so pods flashing over BLE
+//! do not share or contend for a runtime.
+
+use domes_core::commands::{OtaProgress, OtaStage};
+use domes_core::progress;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+use std::sync::Mutex;
+
+/// The bars being drawn, and the width of the device name column
+static BARS: Mutex<Option<(MultiProgress, usize)>> = Mutex::new(None);
+
+/// Whether bars can be drawn: stderr is a terminal and progress is not quiet
+pub fn available() -> bool {
+    std::io::stderr().is_terminal() && !progress::is_quiet()
+}
+
+/// Start drawing bars for `names` until `finish`
+pub fn start(names: &[String]) {
+    let multi = MultiProgress::new();
+    let width = names.iter().map(|n| n.chars().count()).max().unwrap_or(0);
+    let lines = multi.clone();
+    progress::set_writer(Some(Box::new(move |line| {
+        let _ = lines.println(line);
+    })));
+    *BARS.lock().unwrap() = Some((multi, width));
+}
+
+/// Stop drawing bars; progress lines go to stderr again
+pub fn finish() {
+    progress::set_writer(None);
+    if BARS.lock().unwrap().take().is_some() {
+        // The cursor is left at the end of the last bar
+        eprintln!();
+    }
+}
+
+/// An OTA progress callback drawing `name`'s bar, if bars are being drawn
+pub fn ota_callback(name: &str) -> Option<impl FnMut(OtaProgress)> {
+    let (multi, width) = BARS.lock().unwrap().clone()?;
+    let template = format!(
+        "{{prefix:{}}} [{{bar:40}}] {{bytes:>10}}/{{total_bytes:<10}} {{msg}}",
+        width
+    );
+    let style = ProgressStyle::with_template(&template)
+        .unwrap_or_else(|_| ProgressStyle::default_bar())
+        .progress_chars("=> ");
+    let bar = multi.add(ProgressBar::new(0).with_style(style).with_prefix(name.to_string()));
+    bar.set_message("preparing");
+
+    Some(move |p: OtaProgress| match p.stage {
+        OtaStage::Preparing => bar.set_length(p.bytes_total as u64),
+        OtaStage::Sending => {
+            bar.set_message("sending");
+            bar.set_position(p.bytes_sent as u64);
+        }
+        OtaStage::Verifying => bar.set_message("verifying"),
+        OtaStage::Complete => bar.finish_with_message("done"),
+        OtaStage::Failed => bar.abandon_with_message("failed"),
+    })
+}
+
+/// Which devices were flashed: `None` for success, else the error
+pub type Summary = [(String, Option<String>)];
+
+/// The table printed after a parallel OTA
+pub fn format_summary(rows: &Summary) -> String {
+    let width = rows
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .chain(std::iter::once("DEVICE".len()))
+        .max()
+        .unwrap_or(0);
+    let mut out = format!("{:<width$}  RESULT\n", "DEVICE");
+    for (name, error) in rows {
+        match error {
+            None => out.push_str(&format!("{:<width$}  ok\n", name)),
+            Some(e) => out.push_str(&format!("{:<width$}  failed: {}\n", name, e)),
+        }
+    }
+    let failed = rows.iter().filter(|(_, e)| e.is_some()).count();
+    out.push_str(&format!("{} of {} devices flashed\n", rows.len() - failed, rows.len()));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_lists_each_device() {
+        let rows = vec![
+            ("pod1".to_string(), None),
+            ("kitchen-pod".to_string(), Some("Timeout waiting for OTA response".to_string())),
+        ];
+        assert_eq!(
+            format_summary(&rows),
+            "DEVICE       RESULT\n\
+             pod1         ok\n\
+             kitchen-pod  failed: Timeout waiting for OTA response\n\
+             1 of 2 devices flashed\n"
+        );
+    }
+}
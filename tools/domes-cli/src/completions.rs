@@ -0,0 +1,117 @@
+//! `completions <shell>`: shell completion scripts
+//!
+//! The scripts are generated by clap_complete from the `Cli` definition, so
+//! they follow every flag and subcommand. For bash, zsh and fish the value of
+//! `--target` is then completed from the registry at completion time, by
+//! running `domes-cli devices list --format names-only`. PowerShell gets the
+//! static script only.
+
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+const BIN_NAME: &str = "domes-cli";
+
+/// Lists the registered device names, one per line
+const LIST_NAMES: &str = "domes-cli devices list --format names-only 2>/dev/null";
+
+/// `completions --help` text after the argument list
+pub const INSTALL_HELP: &str = "\
+Installation:
+  bash        domes-cli completions bash > ~/.local/share/bash-completion/completions/domes-cli
+  zsh         domes-cli completions zsh > ~/.zfunc/_domes-cli
+              (with fpath+=~/.zfunc before compinit in ~/.zshrc)
+  fish        domes-cli completions fish > ~/.config/fish/completions/domes-cli.fish
+  powershell  domes-cli completions powershell >> $PROFILE
+
+Open a new shell afterwards. --target <TAB> completes the names in
+~/.domes/devices.toml (not in PowerShell).";
+
+/// Completion script for `shell`
+pub fn script(shell: Shell) -> String {
+    let mut buf = Vec::new();
+    clap_complete::generate(shell, &mut crate::Cli::command(), BIN_NAME, &mut buf);
+    let script = String::from_utf8_lossy(&buf).into_owned();
+    match shell {
+        Shell::Bash => bash_targets(script),
+        Shell::Zsh => zsh_targets(script),
+        Shell::Fish => fish_targets(script),
+        _ => script,
+    }
+}
+
+/// Register a wrapper that answers `--target`/`-t` itself and hands
+/// everything else to the generated function
+fn bash_targets(mut script: String) -> String {
+    script.push_str(&format!(
+        r#"
+_domes-cli_targets() {{
+    local prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    if [[ "${{prev}}" == "--target" || "${{prev}}" == "-t" ]]; then
+        COMPREPLY=($(compgen -W "$({})" -- "${{COMP_WORDS[COMP_CWORD]}}"))
+        return 0
+    fi
+    _domes-cli "$@"
+}}
+complete -F _domes-cli_targets -o bashdefault -o default {}
+"#,
+        LIST_NAMES, BIN_NAME
+    ));
+    script
+}
+
+/// Point the `--target` value spec at a function listing the devices
+fn zsh_targets(script: String) -> String {
+    let function = format!(
+        r#"(( $+functions[_domes-cli_targets] )) ||
+_domes-cli_targets() {{
+    local -a targets
+    targets=(${{(f)"$({})"}})
+    _describe -t targets 'registered device' targets
+}}
+"#,
+        LIST_NAMES
+    );
+    let script = script.replace(":TARGET:_default'", ":TARGET:_domes-cli_targets'");
+    // Defined before the script's closing `_domes-cli "$@"` / `compdef` call
+    match script.find("if [ \"$funcstack[1]\"") {
+        Some(end) => format!("{}{}\n{}", &script[..end], function, &script[end..]),
+        None => format!("{}\n{}", script, function),
+    }
+}
+
+/// Offer the device names as the `--target` arguments instead of files
+fn fish_targets(script: String) -> String {
+    script
+        .lines()
+        .map(|line| {
+            if line.contains(" -l target ") {
+                format!("{} -f -a \"({})\"\n", line, LIST_NAMES)
+            } else {
+                format!("{}\n", line)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts_complete_targets_from_registry() {
+        let bash = script(Shell::Bash);
+        assert!(bash.contains("complete -F _domes-cli_targets"));
+        assert!(bash.contains(LIST_NAMES));
+
+        let zsh = script(Shell::Zsh);
+        assert!(zsh.contains("--target=[") && zsh.contains(":TARGET:_domes-cli_targets'"));
+        assert!(!zsh.contains(":TARGET:_default'"));
+        assert!(zsh.find("_domes-cli_targets() {") < zsh.find("compdef _domes-cli domes-cli"));
+
+        let fish = script(Shell::Fish);
+        let target = fish.lines().find(|l| l.contains(" -l target ")).unwrap();
+        assert!(target.ends_with(&format!("-f -a \"({})\"", LIST_NAMES)));
+
+        assert!(script(Shell::PowerShell).contains("'--target'"));
+    }
+}
@@ -0,0 +1,439 @@
+//! Persistent CLI defaults in `~/.domes/config.toml`
+//!
+//! Every setting is optional; command-line flags always take precedence.
+//!
+//! ```toml
+//! # Device used when no --port/--wifi/--ble/--target/--group/--all is given.
+//! # Only the first one set is used, in this order.
+//! default_port = "/dev/ttyACM0"
+//! default_wifi = "192.168.1.100:5000"
+//! default_ble = "DOMES-Pod"
+//!
+//! # --timeout (ms) and --baud when not given (default: per-transport, 115200)
+//! default_timeout_ms = 2000
+//! default_baud_rate = 921600
+//!
+//! # Highlight differences in --diff tables (NO_COLOR also turns this off)
+//! color_output = true
+//!
+//! # --format when not given: "table" or "json"
+//! output_format = "table"
+//!
+//! # Named environments, selected with --profile (or DOMES_PROFILE)
+//! [profiles.dev]
+//! default_port = "/dev/ttyACM0"
+//!
+//! [profiles.staging]
+//! default_wifi = "192.168.10.5:5000"
+//! default_timeout_ms = 5000
+//! ```
+//!
+//! `domes-cli config set <key> <value>` and `config get <key>` take the keys
+//! with dashes (`default-port`); `config edit` opens the file in `$EDITOR`.
+//!
+//! A profile overrides the top-level defaults it sets. If it names a device,
+//! that replaces the top-level device rather than competing with it.
+//! `config profile add/list/remove` manage the `[profiles]` tables.
+//!
+//! Environment variables sit between the two, for CI and containers: a flag
+//! wins over its variable, and a variable over the file.
+//!
+//! | Variable           | Flag        | Value                |
+//! |--------------------|-------------|----------------------|
+//! | `DOMES_PORT`       | `--port`    | comma-separated list |
+//! | `DOMES_WIFI`       | `--wifi`    | comma-separated list |
+//! | `DOMES_BLE`        | `--ble`     | comma-separated list |
+//! | `DOMES_TARGET`     | `--target`  | comma-separated list |
+//! | `DOMES_TIMEOUT_MS` | `--timeout` | milliseconds         |
+//! | `DOMES_FORMAT`     | `--format`  | `table` or `json`    |
+//! | `DOMES_PROFILE`    | `--profile` | profile name         |
+//!
+//! e.g. `DOMES_PORT=/dev/ttyACM0,/dev/ttyACM1`. The device variables are
+//! only read when no targeting flag is given.
+//! `config env` prints the file's defaults as `export` lines.
+
+use crate::output::Format;
+use anyhow::Context;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Keys accepted by `config set` and `config get`
+pub const KEYS: &[&str] = &[
+    "default-port",
+    "default-wifi",
+    "default-ble",
+    "default-timeout-ms",
+    "default-baud-rate",
+    "color-output",
+    "output-format",
+];
+
+/// Contents of `~/.domes/config.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CliConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_port: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_wifi: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_ble: Option<String>,
+    /// `None` keeps the per-transport default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_baud_rate: Option<u32>,
+    pub color_output: bool,
+    pub output_format: Format,
+    /// Named sets of defaults, by profile name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// One `[profiles.<name>]` table: the defaults it overrides
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_port: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_wifi: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_ble: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_baud_rate: Option<u32>,
+}
+
+impl Profile {
+    fn has_device(&self) -> bool {
+        self.default_port.is_some() || self.default_wifi.is_some() || self.default_ble.is_some()
+    }
+
+    /// `key=value` for each setting, in `config set` key order
+    pub fn settings(&self) -> Vec<String> {
+        let settings = [
+            ("default-port", self.default_port.clone()),
+            ("default-wifi", self.default_wifi.clone()),
+            ("default-ble", self.default_ble.clone()),
+            ("default-timeout-ms", self.default_timeout_ms.map(|v| v.to_string())),
+            ("default-baud-rate", self.default_baud_rate.map(|v| v.to_string())),
+        ];
+        settings
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|value| format!("{}={}", key, value)))
+            .collect()
+    }
+}
+
+impl Default for CliConfig {
+    fn default() -> Self {
+        CliConfig {
+            default_port: None,
+            default_wifi: None,
+            default_ble: None,
+            default_timeout_ms: None,
+            default_baud_rate: None,
+            color_output: true,
+            output_format: Format::Table,
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+impl CliConfig {
+    /// Read the config file; a missing file gives the defaults
+    pub fn load() -> anyhow::Result<Self> {
+        let path = config_path();
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content)
+                .with_context(|| format!("Invalid config file {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    fn parse(content: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Write the config file, creating `~/.domes` if needed
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = config_path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let body = toml::to_string_pretty(self).context("Failed to serialize config")?;
+        std::fs::write(&path, format!("{}{}", HEADER, body))
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// The value of `key` as `config set` accepts it, or `None` if unset
+    pub fn get(&self, key: &str) -> anyhow::Result<Option<String>> {
+        let value = match key {
+            "default-port" => self.default_port.clone(),
+            "default-wifi" => self.default_wifi.clone(),
+            "default-ble" => self.default_ble.clone(),
+            "default-timeout-ms" => self.default_timeout_ms.map(|v| v.to_string()),
+            "default-baud-rate" => self.default_baud_rate.map(|v| v.to_string()),
+            "color-output" => Some(self.color_output.to_string()),
+            "output-format" => Some(format_name(self.output_format)),
+            _ => return Err(unknown_key(key)),
+        };
+        Ok(value)
+    }
+
+    /// Set `key` from its string form
+    pub fn set(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        let invalid = || format!("Invalid value for {}: '{}'", key, value);
+        match key {
+            "default-port" => self.default_port = Some(value.to_string()),
+            "default-wifi" => self.default_wifi = Some(value.to_string()),
+            "default-ble" => self.default_ble = Some(value.to_string()),
+            "default-timeout-ms" => {
+                self.default_timeout_ms = Some(value.parse().with_context(invalid)?)
+            }
+            "default-baud-rate" => {
+                let baud: u32 = value.parse().with_context(invalid)?;
+                anyhow::ensure!(baud > 0, "{}", invalid());
+                self.default_baud_rate = Some(baud);
+            }
+            "color-output" => self.color_output = value.parse().with_context(invalid)?,
+            "output-format" => {
+                self.output_format = Format::parse_default(value)
+                    .with_context(|| format!("{} (expected table or json)", invalid()))?
+            }
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+
+    /// These defaults with profile `name` laid over them
+    pub fn with_profile(&self, name: &str) -> anyhow::Result<Self> {
+        let profile = self.profiles.get(name).ok_or_else(|| {
+            let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            if known.is_empty() {
+                anyhow::anyhow!("Unknown profile '{}' (see 'config profile add')", name)
+            } else {
+                anyhow::anyhow!("Unknown profile '{}' (defined: {})", name, known.join(", "))
+            }
+        })?;
+        let mut config = self.clone();
+        if profile.has_device() {
+            config.default_port = profile.default_port.clone();
+            config.default_wifi = profile.default_wifi.clone();
+            config.default_ble = profile.default_ble.clone();
+        }
+        config.default_timeout_ms = profile.default_timeout_ms.or(self.default_timeout_ms);
+        config.default_baud_rate = profile.default_baud_rate.or(self.default_baud_rate);
+        Ok(config)
+    }
+
+    /// The device to use when no transport flag was given, as
+    /// `(ports, wifis, bles)` with at most one entry in total
+    pub fn default_device(&self) -> (Vec<String>, Vec<String>, Vec<String>) {
+        match (&self.default_port, &self.default_wifi, &self.default_ble) {
+            (Some(port), _, _) => (vec![port.clone()], Vec::new(), Vec::new()),
+            (None, Some(wifi), _) => (Vec::new(), vec![wifi.clone()], Vec::new()),
+            (None, None, Some(ble)) => (Vec::new(), Vec::new(), vec![ble.clone()]),
+            (None, None, None) => (Vec::new(), Vec::new(), Vec::new()),
+        }
+    }
+
+    /// `(variable, value)` for each default in the file with an environment
+    /// variable, for `config env`
+    pub fn env_exports(&self) -> Vec<(&'static str, String)> {
+        let mut exports = Vec::new();
+        let settings = [
+            (ENV_PORT, self.default_port.clone()),
+            (ENV_WIFI, self.default_wifi.clone()),
+            (ENV_BLE, self.default_ble.clone()),
+            (ENV_TIMEOUT_MS, self.default_timeout_ms.map(|v| v.to_string())),
+            (ENV_FORMAT, Some(format_name(self.output_format))),
+        ];
+        for (name, value) in settings {
+            if let Some(value) = value {
+                exports.push((name, value));
+            }
+        }
+        exports
+    }
+}
+
+pub const ENV_PORT: &str = "DOMES_PORT";
+pub const ENV_WIFI: &str = "DOMES_WIFI";
+pub const ENV_BLE: &str = "DOMES_BLE";
+pub const ENV_TARGET: &str = "DOMES_TARGET";
+pub const ENV_TIMEOUT_MS: &str = "DOMES_TIMEOUT_MS";
+pub const ENV_FORMAT: &str = "DOMES_FORMAT";
+pub const ENV_PROFILE: &str = "DOMES_PROFILE";
+pub const ENV_FAIL_FAST: &str = "DOMES_FAIL_FAST";
+
+/// The value of environment variable `name`, if set and not blank
+pub fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// A comma-separated list variable (e.g. `DOMES_PORT`); empty if unset
+pub fn env_list(name: &str) -> Vec<String> {
+    env_var(name).map(|v| split_list(&v)).unwrap_or_default()
+}
+
+/// An on/off variable (e.g. `DOMES_FAIL_FAST`): 1/true/yes or 0/false/no
+pub fn parse_switch(value: &str) -> anyhow::Result<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => anyhow::bail!("expected 1/true/yes or 0/false/no"),
+    }
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+const HEADER: &str = "# DOMES CLI defaults (command-line flags take precedence)\n\
+                      # Managed by: domes-cli config set/edit\n\n";
+
+/// Open the config file in `$EDITOR` (or `vi`), then check it still parses
+pub fn edit() -> anyhow::Result<()> {
+    let path = config_path();
+    if !path.exists() {
+        CliConfig::default().save()?;
+    }
+    let editor = std::env::var("EDITOR")
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    // EDITOR may carry arguments, e.g. "code --wait"
+    let mut words =
+        shlex::split(&editor).with_context(|| format!("Invalid quoting in EDITOR: {}", editor))?;
+    anyhow::ensure!(!words.is_empty(), "EDITOR is empty");
+    let program = words.remove(0);
+    let status = std::process::Command::new(&program)
+        .args(&words)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to run editor '{}'", program))?;
+    anyhow::ensure!(status.success(), "Editor '{}' exited with {}", program, status);
+
+    CliConfig::load()?;
+    Ok(())
+}
+
+pub fn config_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".domes").join("config.toml")
+}
+
+fn format_name(format: Format) -> String {
+    format
+        .to_possible_value()
+        .map(|v| v.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn unknown_key(key: &str) -> anyhow::Error {
+    anyhow::anyhow!("Unknown config key '{}' (expected one of: {})", key, KEYS.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_set_get_round_trip() {
+        let mut config = CliConfig::default();
+        config.set("default-port", "/dev/ttyACM0").unwrap();
+        config.set("default-baud-rate", "921600").unwrap();
+        config.set("output-format", "json").unwrap();
+        config.set("color-output", "false").unwrap();
+        assert!(config.set("default-baud-rate", "0").is_err());
+        assert!(config.set("output-format", "yaml").is_err());
+        assert!(config.set("output-format", "names-only").is_err());
+        assert!(config.set("default-prot", "/dev/ttyACM0").is_err());
+
+        let text = toml::to_string_pretty(&config).unwrap();
+        assert!(text.contains("default_port = \"/dev/ttyACM0\""));
+        assert!(!text.contains("default_wifi"));
+        let parsed = CliConfig::parse(&text).unwrap();
+        assert_eq!(parsed, config);
+        assert_eq!(parsed.get("output-format").unwrap().as_deref(), Some("json"));
+        assert_eq!(parsed.get("default-timeout-ms").unwrap(), None);
+
+        // Every setting is optional; a typo is an error rather than ignored
+        assert_eq!(CliConfig::parse("").unwrap(), CliConfig::default());
+        assert!(CliConfig::parse("defualt_port = \"/dev/ttyACM0\"").is_err());
+    }
+
+    #[test]
+    fn test_env_exports_and_lists() {
+        let mut config = CliConfig::default();
+        config.set("default-wifi", "192.168.1.100:5000").unwrap();
+        config.set("default-timeout-ms", "3000").unwrap();
+        assert_eq!(
+            config.env_exports(),
+            [
+                (ENV_WIFI, "192.168.1.100:5000".to_string()),
+                (ENV_TIMEOUT_MS, "3000".to_string()),
+                (ENV_FORMAT, "table".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            split_list("/dev/ttyACM0, /dev/ttyACM1,"),
+            ["/dev/ttyACM0", "/dev/ttyACM1"]
+        );
+
+        assert!(parse_switch(" Yes").unwrap());
+        assert!(!parse_switch("0").unwrap());
+        assert!(parse_switch("sometimes").is_err());
+    }
+
+    #[test]
+    fn test_profile_overrides_defaults() {
+        let config = CliConfig::parse(
+            r#"
+default_port = "/dev/ttyACM0"
+default_timeout_ms = 2000
+
+[profiles.dev]
+default_baud_rate = 921600
+
+[profiles.staging]
+default_wifi = "192.168.10.5:5000"
+default_timeout_ms = 5000
+"#,
+        )
+        .unwrap();
+
+        // No device in the profile: the top-level one stays
+        let dev = config.with_profile("dev").unwrap();
+        assert_eq!(dev.default_device().0, ["/dev/ttyACM0"]);
+        assert_eq!(dev.default_timeout_ms, Some(2000));
+        assert_eq!(dev.default_baud_rate, Some(921600));
+
+        // A device in the profile replaces it, even of another transport
+        let staging = config.with_profile("staging").unwrap();
+        let (ports, wifis, _) = staging.default_device();
+        assert!(ports.is_empty());
+        assert_eq!(wifis, ["192.168.10.5:5000"]);
+        assert_eq!(staging.default_timeout_ms, Some(5000));
+        assert_eq!(config.profiles["staging"].settings().len(), 2);
+
+        let err = config.with_profile("prod").unwrap_err().to_string();
+        assert!(err.contains("dev, staging"), "{}", err);
+
+        let text = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(CliConfig::parse(&text).unwrap(), config);
+    }
+}
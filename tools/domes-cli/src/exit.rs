@@ -0,0 +1,88 @@
+//! Process exit codes
+//!
+//! | Code | Meaning                                                     |
+//! |------|-------------------------------------------------------------|
+//! | 0    | Success                                                     |
+//! | 1    | Any other failure (local file, ...)                         |
+//! | 2    | Usage: bad arguments, environment variables or config file  |
+//! | 3    | Connection: a device could not be reached or the link broke |
+//! | 4    | Device error: the device refused the request or answered    |
+//! |      | with something unexpected                                   |
+//! | 5    | Timeout: a connected device did not answer in time          |
+//!
+//! When several devices fail, the most severe code wins, in the order
+//! 3, 5, 4, 2, 1. `--script` keeps exiting with the number of failed lines.
+
+use domes_core::error::DomesError;
+use domes_core::transport::ResponseTimeout;
+
+pub const FAILURE: i32 = 1;
+pub const USAGE: i32 = 2;
+pub const CONNECTION: i32 = 3;
+pub const DEVICE: i32 = 4;
+pub const TIMEOUT: i32 = 5;
+
+/// Exit codes from most to least severe
+const SEVERITY: [i32; 5] = [CONNECTION, TIMEOUT, DEVICE, USAGE, FAILURE];
+
+/// The exit code for `error`, from the first `DomesError` in its chain
+pub fn code_for(error: &anyhow::Error) -> i32 {
+    for cause in error.chain() {
+        if let Some(e) = cause.downcast_ref::<DomesError>() {
+            return match e {
+                DomesError::Transport { .. } => CONNECTION,
+                DomesError::Timeout { .. } => TIMEOUT,
+                DomesError::Status(_)
+                | DomesError::Rejected(_)
+                | DomesError::OtaRejected { .. }
+                | DomesError::Protocol { .. }
+                | DomesError::UnexpectedResponse { .. } => DEVICE,
+                DomesError::InvalidArgument(_) => USAGE,
+                DomesError::Io { .. } => FAILURE,
+            };
+        }
+        if cause.is::<ResponseTimeout>() {
+            return TIMEOUT;
+        }
+    }
+    FAILURE
+}
+
+/// The more severe of two exit codes (0 for success is the least)
+pub fn most_severe(a: i32, b: i32) -> i32 {
+    let rank = |code| SEVERITY.iter().position(|&c| c == code).unwrap_or(SEVERITY.len());
+    if rank(b) < rank(a) {
+        b
+    } else {
+        a
+    }
+}
+
+/// An error for a bad argument or flag combination, exiting with `USAGE`
+pub fn usage(message: impl Into<String>) -> anyhow::Error {
+    DomesError::InvalidArgument(message.into()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_exit_codes_follow_error_kind() {
+        let timeout = DomesError::transport("Failed to send", anyhow::Error::new(ResponseTimeout));
+        assert_eq!(code_for(&timeout.into()), TIMEOUT);
+        let broken = DomesError::transport("Failed to send", anyhow::anyhow!("port closed"));
+        let broken = anyhow::Error::from(broken).context("Failed to connect to pod1");
+        assert_eq!(code_for(&broken), CONNECTION);
+        assert_eq!(code_for(&DomesError::Rejected("busy".into()).into()), DEVICE);
+        assert_eq!(code_for(&usage("--diff needs two devices")), USAGE);
+        let plain: anyhow::Result<()> = Err(anyhow::anyhow!("checksum mismatch"));
+        assert_eq!(code_for(&plain.context("Trace dump").unwrap_err()), FAILURE);
+
+        assert_eq!(most_severe(0, DEVICE), DEVICE);
+        assert_eq!(most_severe(DEVICE, TIMEOUT), TIMEOUT);
+        assert_eq!(most_severe(CONNECTION, TIMEOUT), CONNECTION);
+        assert_eq!(most_severe(USAGE, FAILURE), USAGE);
+    }
+}
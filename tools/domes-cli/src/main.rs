@@ -12,11 +12,15 @@
 //!   domes-cli --port /dev/ttyACM0 led solid --color ff0000
 //!   domes-cli --port /dev/ttyACM0 led breathing --color 00ff00 --period 2000
 //!   domes-cli --port /dev/ttyACM0 led cycle --period 3000
+//!   domes-cli --port /dev/ttyACM0 led cycle --colors ff0000,00ff00,0000ff
 //!   domes-cli --port /dev/ttyACM0 ota flash firmware.bin --version v1.2.3
 //!   domes-cli --port /dev/ttyACM0 trace start
+//!   domes-cli --port /dev/ttyACM0 trace start --duration 10 -o trace.json
 //!   domes-cli --port /dev/ttyACM0 trace stop
 //!   domes-cli --port /dev/ttyACM0 trace status
+//!   domes-cli --port /dev/ttyACM0 trace set-buffer-size 8192
 //!   domes-cli --port /dev/ttyACM0 trace dump -o trace.json
+//!   domes-cli --port /dev/ttyACM0 trace dump -o - | python3 analyze.py
 //!   domes-cli --port /dev/ttyACM0 system mode
 //!   domes-cli --port /dev/ttyACM0 system set-mode triage
 //!   domes-cli --port /dev/ttyACM0 system info
@@ -27,6 +31,7 @@
 //!   domes-cli --wifi 192.168.1.100:5000 wifi status
 //!   domes-cli --wifi 192.168.1.100:5000 led cycle --period 2000
 //!   domes-cli --wifi 192.168.1.100:5000 ota flash firmware.bin
+//!   domes-cli --wifi-discover                      # Find WiFi pods via mDNS
 //!
 //! Usage (BLE):
 //!   domes-cli --scan-ble                           # Scan for nearby DOMES devices
@@ -37,6 +42,7 @@
 //!   domes-cli --port /dev/ttyACM0 --port /dev/ttyACM1 feature list
 //!   domes-cli --target pod1 --target pod2 led solid --color ff0000
 //!   domes-cli --all feature list
+//!   domes-cli --all --parallel --parallel-max 4 system info
 //!
 //! Device registry:
 //!   domes-cli devices scan
@@ -44,18 +50,51 @@
 //!   domes-cli devices add pod2 serial /dev/ttyACM1
 //!   domes-cli devices list
 //!   domes-cli devices remove pod1
+//!
+//! Shell completion:
+//!   domes-cli completions bash > ~/.local/share/bash-completion/completions/domes-cli
+//!
+//! Defaults (~/.domes/config.toml):
+//!   domes-cli config set default-port /dev/ttyACM0
+//!   domes-cli config get default-port
+//!   domes-cli config edit
+//!   domes-cli config profile add dev --port /dev/ttyACM0
+//!   domes-cli --profile dev system info
+//!
+//! Exit codes (see `exit.rs`):
+//!   0 success, 1 other failure, 2 usage, 3 connection/transport,
+//!   4 device error status, 5 timeout
 
-mod commands;
-mod device;
-mod proto;
-mod protocol;
-mod transport;
+#[macro_use]
+mod output;
+mod bars;
+mod completions;
+mod config;
+mod exit;
+mod repl;
+mod script;
+mod watch;
 
+use anyhow::Context;
 use clap::{Parser, Subcommand};
-use proto::config::{Feature, SystemMode};
-use std::path::PathBuf;
-use std::time::Duration;
-use transport::{BleTransport, SerialTransport};
+use domes_core::proto::config::{Feature, SystemMode};
+use domes_core::transport::frame::MAX_PAYLOAD_SIZE;
+use domes_core::transport::{
+    keepalive, mdns, BleDeviceInfo, BleTarget, BleTransport, KeepaliveConfig, KeepaliveTransport,
+    MacPrefix, PayloadLimitTransport, ResponseTimeout, SerialTransport, Transport,
+    WifiDeviceInfo, DEFAULT_BAUD_RATE,
+};
+use domes_core::{commands, device, progress, protocol};
+use output::{Format, Printer};
+use script::OnError;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::io::IsTerminal;
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 #[derive(Parser)]
 #[command(name = "domes-cli")]
@@ -65,6 +104,10 @@ struct Cli {
     #[arg(short, long)]
     port: Vec<String>,
 
+    /// Serial port pattern(s) to connect to (e.g., '/dev/ttyACM*'). Ports that fail to open are skipped.
+    #[arg(long)]
+    port_glob: Vec<String>,
+
     /// WiFi address(es) to connect to (e.g., 192.168.1.100:5000). Can be specified multiple times.
     #[arg(short, long)]
     wifi: Vec<String>,
@@ -77,10 +120,19 @@ struct Cli {
     #[arg(short, long)]
     target: Vec<String>,
 
+    /// Target all devices in a registry group. Can be specified multiple times.
+    #[arg(short, long)]
+    group: Vec<String>,
+
     /// Target all registered devices
     #[arg(long)]
     all: bool,
 
+    /// Use the defaults of [profiles.<NAME>] in ~/.domes/config.toml
+    /// (falls back to DOMES_PROFILE)
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
     /// Scan for nearby BLE devices
     #[arg(long)]
     scan_ble: bool,
@@ -89,16 +141,203 @@ struct Cli {
     #[arg(long)]
     connect_all_ble: bool,
 
+    /// Find WiFi pods advertising _domes._tcp via mDNS and target all of
+    /// them; without a command, list them with their IP:port
+    #[arg(long)]
+    wifi_discover: bool,
+
+    /// Target the pod with this ID (see system set-pod-id): each serial port
+    /// is probed and the one reporting it is used, wherever it enumerated
+    #[arg(
+        long,
+        value_name = "ID",
+        value_parser = clap::value_parser!(u32).range(1..=255),
+        conflicts_with_all = ["port", "ble", "wifi", "all"]
+    )]
+    pod_id: Option<u32>,
+
+    /// With --pod-id, also look for a BLE pod advertising DOMES-Pod-<ID>
+    #[arg(long, requires = "pod_id")]
+    pod_id_ble: bool,
+
+    /// Leave out BLE devices weaker than this in scans (--scan-ble,
+    /// --connect-all-ble, devices scan), in dBm, e.g. --min-rssi -70
+    #[arg(long, value_name = "DBM", allow_hyphen_values = true)]
+    min_rssi: Option<i16>,
+
+    /// List BLE devices whose address starts with this prefix in scans
+    /// instead of DOMES devices, e.g. Espressif's 24:0A:C4 for pods in
+    /// bootloader mode that do not advertise yet
+    #[arg(long, value_name = "PREFIX")]
+    mac_filter: Option<MacPrefix>,
+
     /// List available serial ports
     #[arg(long)]
     list_ports: bool,
 
+    /// Suppress progress output; only command results are printed
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Show sizes as 183.0 KB, 1.2 MB, ... instead of exact byte counts
+    /// (table output and progress; JSON keeps exact counts)
+    #[arg(short = 'H', long, global = true)]
+    human_readable: bool,
+
+    /// Do not color output (device prefixes, --diff highlights); NO_COLOR
+    /// in the environment does the same
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Log each frame with timestamps and round-trip times, and connection
+    /// events, to stderr; repeat (-vv) to also hex-dump each frame
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Output format for command results [default: table]
+    #[arg(long, global = true, value_enum)]
+    format: Option<output::Format>,
+
+    /// Shorthand for --format json
+    #[arg(long)]
+    json: bool,
+
+    /// Run the command on all targeted devices concurrently
+    #[arg(long)]
+    parallel: bool,
+
+    /// Maximum number of devices to run concurrently with --parallel
+    #[arg(long, requires = "parallel", value_parser = clap::value_parser!(u32).range(1..))]
+    parallel_max: Option<u32>,
+
+    /// Stop at the first device that fails and skip the remaining devices
+    /// (a device that fails to connect is then an error, not a warning)
+    #[arg(long)]
+    fail_fast: bool,
+
+    /// Run every device and report the failures at the end (the default;
+    /// overrides DOMES_FAIL_FAST)
+    #[arg(long, conflicts_with = "fail_fast")]
+    continue_on_error: bool,
+
+    /// Retry a failed device this many times before counting it as failed
+    #[arg(long, default_value = "0")]
+    retry: u32,
+
+    /// Re-run a read command every --interval-ms, redrawing the output,
+    /// until q or Ctrl-C
+    #[arg(long)]
+    watch: bool,
+
+    /// How often --watch re-runs the command (ms)
+    #[arg(long, default_value = "1000", requires = "watch", value_parser = clap::value_parser!(u64).range(1..))]
+    interval_ms: u64,
+
+    /// Send up to 3 commands in one round trip. Repeat for each command,
+    /// e.g. --batch "feature enable ble" --batch "led solid --color ff0000"
+    #[arg(long, value_name = "COMMAND")]
+    batch: Vec<String>,
+
+    /// Run commands from a file, one subcommand per line ('-' reads stdin).
+    /// Exits with the number of failed lines.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["batch", "watch"])]
+    script: Option<String>,
+
+    /// With --script: stop at the first failed line, or run the rest anyway
+    #[arg(long, value_enum, default_value_t = OnError::Abort, requires = "script")]
+    on_error: OnError,
+
+    /// With --script: print the commands it would run, without connecting
+    #[arg(long, requires = "script")]
+    dry_run: bool,
+
+    /// Compare devices side by side (system info, feature list, led get)
+    #[arg(long)]
+    diff: bool,
+
+    /// Connection timeout in ms (serial read, TCP connect, BLE scan), also
+    /// for devices with a registry timeout_ms. Default: per-transport.
+    #[arg(long)]
+    timeout: Option<u64>,
+
+    /// Whether --timeout was given on the command line rather than filled
+    /// in from DOMES_TIMEOUT_MS or the config file, which registry
+    /// timeouts take precedence over
+    #[arg(skip)]
+    timeout_from_flag: bool,
+
+    /// Try connecting to a device up to N more times after a failure
+    /// (overrides registry connect_retries)
+    #[arg(long, value_name = "N")]
+    connect_retries: Option<u32>,
+
+    /// How long to scan for a BLE device (overrides registry
+    /// scan_timeout_secs; default: --timeout, or 10 s)
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    scan_timeout_secs: Option<u64>,
+
+    /// Give up on a device that has not connected after this many ms and go
+    /// on without it (devices connect concurrently)
+    #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+    connect_timeout_ms: Option<u64>,
+
+    /// Try a WiFi device up to N times while it refuses the connection (its
+    /// TCP server may not be up yet after boot)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    wifi_retry: Option<u32>,
+
+    /// Wait before the first --wifi-retry attempt, doubling each time
+    #[arg(long, value_name = "MS", default_value_t = 500, requires = "wifi_retry")]
+    wifi_retry_delay_ms: u64,
+
+    /// Pace WiFi traffic to this many kilobits per second in each direction,
+    /// to test over a slow link
+    #[arg(long, value_name = "KBPS", value_parser = clap::value_parser!(u32).range(1..))]
+    tcp_rate_limit: Option<u32>,
+
+    /// Largest frame payload the device accepts; larger payloads fail before
+    /// sending. Without it, ota flash and --batch ask the device for its limit
+    #[arg(long, value_name = "BYTES", value_parser = clap::value_parser!(u32).range(1..))]
+    max_payload: Option<u32>,
+
+    /// Serial baud rate for devices without a registry baud (default: 115200)
+    #[arg(long, value_name = "RATE", value_parser = clap::value_parser!(u32).range(1..))]
+    baud: Option<u32>,
+
+    /// Ping BLE devices after this many idle seconds so the OS does not drop
+    /// the link during a long repl or --watch session
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    keepalive: Option<u64>,
+
+    /// How often `devices health` pings each device (ms)
+    #[arg(long, default_value = "5000", value_parser = clap::value_parser!(u64).range(1..))]
+    keepalive_interval_ms: u64,
+
+    /// How long to wait for a ping response before marking a device stale (ms)
+    #[arg(long, default_value = "2000", value_parser = clap::value_parser!(u64).range(1..))]
+    keepalive_timeout_ms: u64,
+
+    /// How long to wait for another process's lock on the device registry (ms)
+    #[arg(long, default_value = "5000")]
+    registry_lock_timeout: u64,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Commands from --batch, sent together in one round trip
+    #[command(skip)]
+    Batch { commands: Vec<Commands> },
+
+    /// Lines from --script, run one after another; `failed` lines did not parse
+    #[command(skip)]
+    Script {
+        lines: Vec<script::ScriptLine>,
+        failed: usize,
+    },
+
     /// Manage runtime features
     Feature {
         #[command(subcommand)]
@@ -153,12 +392,94 @@ enum Commands {
         action: TouchAction,
     },
 
+    /// Real-time device events pushed by the firmware
+    Events {
+        #[command(subcommand)]
+        action: EventsAction,
+    },
+
     /// Manage device registry
     Devices {
         #[command(subcommand)]
         action: DevicesAction,
     },
 
+    /// BLE pairing (bonding), kept by the OS for later connections
+    Ble {
+        #[command(subcommand)]
+        action: BleAction,
+    },
+
+    /// Reset the --port device(s) through the serial line (break, RTS/DTR)
+    Serial {
+        /// Confirm the command: it reboots the device
+        #[arg(long)]
+        dangerous: bool,
+
+        #[command(subcommand)]
+        action: SerialAction,
+    },
+
+    /// Read or change CLI defaults (~/.domes/config.toml)
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Print a shell completion script to stdout
+    #[command(after_help = completions::INSTALL_HELP)]
+    Completions {
+        /// Shell to generate the script for
+        shell: clap_complete::Shell,
+    },
+
+    /// Interactive session: run commands without reconnecting each time
+    Repl,
+
+    /// Send a raw frame and print the response (protocol bring-up)
+    Raw {
+        /// Message type as hex (e.g., 0x20)
+        #[arg(long = "type")]
+        msg_type: String,
+
+        /// Payload as hex bytes (e.g., 0a021001)
+        #[arg(long, default_value = "")]
+        payload: String,
+    },
+
+    /// Decode a captured frame hexdump offline (no device needed)
+    Decode {
+        /// Frame bytes as hex (e.g., aa5501002000...). Spaces and separators are allowed.
+        #[arg(required = true)]
+        hex: Vec<String>,
+    },
+
+    /// Wait until a device is connected and answering (for boot scripts).
+    /// Exits 5 if it does not show up in time.
+    WaitForDevice {
+        /// Serial port to wait for (default: --port)
+        #[arg(long, conflicts_with = "ble")]
+        port: Option<String>,
+
+        /// BLE device name or address to scan for (default: --ble)
+        #[arg(long)]
+        ble: Option<String>,
+
+        /// Seconds to wait before giving up
+        #[arg(long, default_value = "30", value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: u64,
+
+        /// Shell command to run once the device answers; exits with its status
+        #[arg(long, value_name = "COMMAND")]
+        exec: Option<String>,
+    },
+
+    /// Protocol introspection (no device needed)
+    Proto {
+        #[command(subcommand)]
+        action: ProtoAction,
+    },
+
     /// Protocol sniffer - capture and decode DOMES frames
     Sniff {
         /// Filter by protocol (config, trace, ota). Comma-separated.
@@ -182,7 +503,35 @@ enum Commands {
 #[derive(Subcommand)]
 enum FeatureAction {
     /// List all features and their current state
-    List,
+    List {
+        /// Order by name, or enabled features first (default: device order)
+        #[arg(long, value_name = "name|status")]
+        sort: Option<commands::FeatureSort>,
+
+        /// Only show enabled or disabled features
+        #[arg(long, value_name = "enabled|disabled|all", default_value = "all")]
+        filter: commands::FeatureFilter,
+    },
+
+    /// Exit 0 if a feature is enabled and 1 if it is disabled, e.g.
+    /// `domes-cli feature check ble && echo "BLE is on"`
+    Check {
+        /// Feature name (e.g., led-effects, ble, wifi, esp-now, touch, haptic, audio)
+        feature: String,
+    },
+
+    /// Save the current feature states to a JSON profile
+    SaveProfile {
+        /// Profile file to write
+        file: PathBuf,
+    },
+
+    /// Compare feature states to a saved profile, print the differences and
+    /// exit 1 if there are any
+    CheckProfile {
+        /// Profile file written by save-profile
+        file: PathBuf,
+    },
 
     /// Enable a feature
     Enable {
@@ -207,18 +556,116 @@ enum WifiAction {
 
     /// Show WiFi subsystem status
     Status,
+
+    /// Run WiFi as an access point for standalone deployments (persisted to NVS)
+    ConfigureAp {
+        /// Network name (1-32 bytes)
+        #[arg(long)]
+        ssid: String,
+
+        /// WPA2 passphrase (8-63 characters); empty for an open network
+        #[arg(long, default_value = "")]
+        password: String,
+
+        /// 2.4 GHz channel (1-13)
+        #[arg(long, default_value = "1")]
+        channel: u8,
+
+        /// Most stations connected at once (1-10)
+        #[arg(long, default_value = "4")]
+        max_connections: u8,
+    },
+
+    /// Access point state
+    Ap {
+        #[command(subcommand)]
+        action: WifiApAction,
+    },
+
+    /// Use a static IP address in station mode instead of DHCP (persisted to NVS)
+    Static {
+        /// Address for the pod
+        #[arg(long)]
+        ip: Ipv4Addr,
+
+        /// Subnet mask
+        #[arg(long)]
+        netmask: Ipv4Addr,
+
+        /// Default gateway, on the same subnet as --ip
+        #[arg(long)]
+        gateway: Ipv4Addr,
+
+        /// Primary DNS server
+        #[arg(long)]
+        dns: Ipv4Addr,
+
+        /// Secondary DNS server
+        #[arg(long)]
+        dns2: Option<Ipv4Addr>,
+    },
+
+    /// Get the station IP address from DHCP again
+    Dhcp,
+}
+
+#[derive(Subcommand)]
+enum WifiApAction {
+    /// Show the access point and its connected stations with their signal strength
+    Status,
 }
 
 #[derive(Subcommand)]
 enum OtaAction {
     /// Flash firmware to device
     Flash {
-        /// Path to firmware binary (.bin file)
+        /// Path to firmware binary (.bin file), or @<alias> from
+        /// ~/.domes/firmware.toml (e.g. @latest)
         firmware: PathBuf,
 
-        /// Version string (e.g., v1.2.3)
-        #[arg(short, long)]
+        /// Version string (e.g., v1.2.3); `-v` is the global --verbose
+        #[arg(long)]
         version: Option<String>,
+
+        /// Mark the new image bootable but keep running until the next reboot
+        /// (serial and BLE only; firmware without support for this flag
+        /// reboots anyway)
+        #[arg(long)]
+        no_reboot: bool,
+
+        /// Firmware bytes per OTA data frame (default and maximum: what the
+        /// transport supports, 1016 for serial/WiFi and 400 for BLE)
+        #[arg(long, value_name = "BYTES", value_parser = clap::value_parser!(u32).range(1..))]
+        ota_chunk_size: Option<u32>,
+
+        /// If the device is busy with an earlier (interrupted) update, abort
+        /// that session and start over instead of failing
+        #[arg(long)]
+        force: bool,
+
+        /// Serial baud rate for sending the image; the link switches back when
+        /// done. Pass the current rate (e.g. 115200) to stay at it
+        #[arg(
+            long,
+            value_name = "RATE",
+            default_value_t = 921600,
+            value_parser = clap::value_parser!(u32).range(1..)
+        )]
+        ota_baud: u32,
+
+        /// Wait for the device to reboot, reconnect and check that it runs
+        /// --version (the bootloader rolls back an image that fails to boot)
+        #[arg(long, requires = "version", conflicts_with = "no_reboot")]
+        verify_boot: bool,
+
+        /// How long --verify-boot waits for the device to come back (ms)
+        #[arg(
+            long,
+            default_value = "15000",
+            requires = "verify_boot",
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        reboot_timeout_ms: u64,
     },
 
     /// Check for available firmware updates (via GitHub releases)
@@ -238,8 +685,20 @@ enum OtaAction {
 
 #[derive(Subcommand)]
 enum TraceAction {
-    /// Start trace recording
-    Start,
+    /// Start trace recording (or run a timed capture with --duration)
+    Start {
+        /// Capture for this many seconds, then stop and dump automatically
+        #[arg(short, long)]
+        duration: Option<u64>,
+
+        /// Output file for the timed capture (default: trace.json, `-` for stdout)
+        #[arg(short, long, requires = "duration")]
+        output: Option<PathBuf>,
+
+        /// Span name mapping file (e.g., trace_names.json)
+        #[arg(short, long, requires = "duration")]
+        names: Option<PathBuf>,
+    },
 
     /// Stop trace recording
     Stop,
@@ -250,15 +709,54 @@ enum TraceAction {
     /// Show trace system status
     Status,
 
-    /// Dump traces to JSON file (Perfetto compatible)
+    /// Record a user marker in the trace timeline (e.g., where you tapped)
+    Annotate {
+        /// Marker name shown in the dump (up to 32 bytes)
+        label: String,
+    },
+
+    /// Resize the trace ring buffer (clears recorded events)
+    SetBufferSize {
+        /// Requested capacity in events
+        event_count: u32,
+
+        /// Resize even if the buffer holds recorded events
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Dump traces to a JSON or Perfetto protobuf file
     Dump {
-        /// Output file path (default: trace.json)
+        /// Output file path (default: trace.json). Use `-` to write to stdout.
         #[arg(short, long, default_value = "trace.json")]
         output: PathBuf,
 
+        /// File format (default: perfetto-proto for .pftrace files, else json)
+        #[arg(long, value_parser = ["json", "perfetto-proto"])]
+        trace_format: Option<String>,
+
         /// Span name mapping file (e.g., trace_names.json)
         #[arg(short, long)]
         names: Option<PathBuf>,
+
+        /// Treat a checksum mismatch as an error instead of a warning; nothing
+        /// is written
+        #[arg(long)]
+        strict: bool,
+
+        /// Only dump events newer than this timestamp (µs since boot), e.g. the
+        /// latest timestamp printed by the previous dump
+        #[arg(long, value_name = "TIMESTAMP_US")]
+        since: Option<u32>,
+
+        /// Add the events after those already in the output file instead of
+        /// overwriting it (JSON only)
+        #[arg(long)]
+        append: bool,
+
+        /// Name this session on a "sessions" track in the timeline (JSON only)
+        #[arg(long, value_name = "LABEL")]
+        session_label: Option<String>,
     },
 
     /// Stream trace events in real-time over WiFi/TCP
@@ -285,13 +783,18 @@ enum ImuAction {
 
 #[derive(Subcommand)]
 enum SystemAction {
-    /// Get current system mode
+    /// Get current system mode (with --watch, log each mode transition)
     Mode,
 
     /// Set system mode (e.g., idle, triage, connected, game, error)
     SetMode {
         /// Mode name (idle, triage, connected, game, error)
         mode: String,
+
+        /// Bypass the firmware's transition rules (for testing; older
+        /// firmware ignores it and may still reject the transition)
+        #[arg(long)]
+        force: bool,
     },
 
     /// Get system information (version, uptime, heap, etc.)
@@ -303,9 +806,32 @@ enum SystemAction {
         id: u32,
     },
 
+    /// Set a custom BLE advertised name (persisted to NVS, reboot for BLE name change)
+    SetName {
+        /// Name to advertise (printable ASCII, up to 20 characters)
+        name: String,
+    },
+
     /// Get system health diagnostics (heap, tasks, RSSI)
     Health,
 
+    /// List FreeRTOS tasks with their state, priority, stack watermark and CPU use
+    Tasks {
+        /// Flag tasks with less free stack than this (red below half of it)
+        #[arg(long, value_name = "BYTES")]
+        warn_watermark: Option<u32>,
+    },
+
+    /// Show the flash partition table and layout (the running app is marked *)
+    Partitions,
+
+    /// Measure round-trip latency with a series of pings
+    Ping {
+        /// Number of pings to send
+        #[arg(short, long, default_value = "10", value_parser = clap::value_parser!(u32).range(1..))]
+        count: u32,
+    },
+
     /// Get crash dump (last panic backtrace from NVS)
     CrashDump {
         /// Clear the crash dump after displaying
@@ -313,6 +839,17 @@ enum SystemAction {
         clear: bool,
     },
 
+    /// Download the ESP-IDF core dump left by the last panic (clear: erase it)
+    #[command(args_conflicts_with_subcommands = true)]
+    CoreDump {
+        /// File to write the raw core dump to (one file per device with several)
+        #[arg(short, long, default_value = "core.bin")]
+        output: PathBuf,
+
+        #[command(subcommand)]
+        action: Option<CoreDumpAction>,
+    },
+
     /// Get memory profile (heap stats + historical samples)
     Memory {
         /// Output as JSON
@@ -324,17 +861,34 @@ enum SystemAction {
     SelfTest,
 }
 
+#[derive(Subcommand)]
+enum CoreDumpAction {
+    /// Erase the stored core dump
+    Clear,
+}
+
 #[derive(Subcommand)]
 enum LedAction {
     /// Get current LED pattern
-    Get,
+    Get {
+        /// Show every protobuf field as the device sent it, including each
+        /// color of a cycle and the numeric pattern type
+        #[arg(long)]
+        raw: bool,
+    },
 
     /// Turn LEDs off
     Off,
 
+    /// Change brightness without changing the current pattern
+    Brightness {
+        /// Brightness (0-255)
+        value: u8,
+    },
+
     /// Set solid color (e.g., led solid --color ff0000)
     Solid {
-        /// Hex color (e.g., ff0000 for red)
+        /// Hex color: rrggbb or rgb, optional '#' (e.g., ff0000 or f00 for red)
         #[arg(short, long, default_value = "ffffff")]
         color: String,
 
@@ -345,7 +899,7 @@ enum LedAction {
 
     /// Set breathing pattern (pulsing brightness)
     Breathing {
-        /// Hex color (e.g., 00ff00 for green)
+        /// Hex color: rrggbb or rgb, optional '#' (e.g., 00ff00 or 0f0 for green)
         #[arg(short, long, default_value = "00ff00")]
         color: String,
 
@@ -364,10 +918,37 @@ enum LedAction {
         #[arg(short, long, default_value = "2000")]
         period: u32,
 
+        /// Comma-separated hex colors (rgb, rrggbb or rrggbbww). Default: rainbow
+        #[arg(long, value_delimiter = ',')]
+        colors: Vec<String>,
+
+        /// Brightness (0-255)
+        #[arg(short, long, default_value = "128")]
+        brightness: u8,
+    },
+
+    /// Hardware check: solid red, green, blue and white in turn, then off
+    Test {
+        /// How long each color stays on (ms)
+        #[arg(long, default_value = "1000", value_parser = clap::value_parser!(u64).range(1..))]
+        dwell: u64,
+
         /// Brightness (0-255)
         #[arg(short, long, default_value = "128")]
         brightness: u8,
     },
+
+    /// Save the current LED pattern to a JSON file
+    Export {
+        /// File to write (e.g., breathing.json)
+        file: PathBuf,
+    },
+
+    /// Set the LED pattern saved in a JSON file (see led export)
+    Import {
+        /// File to read
+        file: PathBuf,
+    },
 }
 
 #[derive(Subcommand)]
@@ -398,6 +979,16 @@ enum EspnowAction {
     },
 }
 
+#[derive(Subcommand)]
+enum EventsAction {
+    /// Print events as they happen until Ctrl+C
+    Subscribe {
+        /// Event categories, comma-separated (mode, led, wifi, feature, touch, all)
+        #[arg(long, default_value = "all")]
+        category: String,
+    },
+}
+
 #[derive(Subcommand)]
 enum TouchAction {
     /// Inject a simulated touch on a specific pad
@@ -410,8 +1001,13 @@ enum TouchAction {
 
 #[derive(Subcommand)]
 enum DevicesAction {
-    /// List registered devices
-    List,
+    /// List registered devices; with --verbose, also connect to each one and
+    /// show whether it answers, with its firmware version, mode and pod ID
+    List {
+        /// Only devices with this tag. Repeat to require several.
+        #[arg(long)]
+        tag: Vec<String>,
+    },
 
     /// Add a device to the registry
     Add {
@@ -423,6 +1019,31 @@ enum DevicesAction {
 
         /// Address (e.g., /dev/ttyACM0, 192.168.1.100:5000, "DOMES-Pod-01")
         address: String,
+
+        /// Connection timeout in ms for this device (--timeout overrides it)
+        #[arg(long)]
+        timeout_ms: Option<u64>,
+
+        /// Connection attempts to retry for this device after a failure
+        /// (--connect-retries overrides it)
+        #[arg(long, value_name = "N")]
+        connect_retries: Option<u32>,
+
+        /// BLE scan timeout for this device (--scan-timeout-secs overrides it)
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        scan_timeout_secs: Option<u64>,
+
+        /// Serial baud rate for this device (overrides --baud)
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        baud: Option<u32>,
+
+        /// Where the device is or what it is for (e.g., "Living room pod")
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Tag for filtering with `devices list --tag`. Can be specified multiple times.
+        #[arg(long)]
+        tag: Vec<String>,
     },
 
     /// Remove a device from the registry
@@ -432,85 +1053,528 @@ enum DevicesAction {
     },
 
     /// Scan for all connected DOMES devices
-    Scan,
-}
+    Scan {
+        /// Add each found device to the registry (pod-<id>, BLE name, or unknown-<n>)
+        #[arg(long)]
+        register: bool,
 
-fn main() -> anyhow::Result<()> {
-    let mut cli = Cli::parse();
+        /// Name prefix for registered serial devices (<prefix>-<pod id>, default "pod")
+        #[arg(long, requires = "register")]
+        prefix: Option<String>,
+    },
 
-    // Handle --list-ports
-    if cli.list_ports {
-        let ports = SerialTransport::list_ports()?;
-        if ports.is_empty() {
-            println!("No serial ports found");
-        } else {
-            println!("Available serial ports:");
-            for port in ports {
-                println!("  {}", port);
-            }
-        }
-        return Ok(());
-    }
+    /// Manage device groups
+    Group {
+        #[command(subcommand)]
+        action: GroupAction,
+    },
 
-    // Handle --connect-all-ble: scan and add DOMES devices to BLE targets
-    if cli.connect_all_ble {
-        println!("Scanning for DOMES BLE devices (10 seconds)...");
-        let ble_devices = BleTransport::scan_devices(Duration::from_secs(10))?;
-        let existing: std::collections::HashSet<String> = cli.ble.iter().cloned().collect();
-        for (name, addr) in &ble_devices {
-            if name.starts_with("DOMES-Pod") && !existing.contains(addr) {
-                println!("  Found: {} ({})", name, addr);
-                cli.ble.push(addr.clone());
-            }
-        }
-        let has_other_transports =
-            !cli.port.is_empty() || !cli.wifi.is_empty() || !cli.target.is_empty() || cli.all;
-        if cli.ble.is_empty() && !has_other_transports {
-            eprintln!("No DOMES BLE devices found");
-            std::process::exit(1);
-        } else if cli.ble.is_empty() {
-            eprintln!("Warning: no DOMES BLE devices found via scan, using other transports");
-        }
-        println!();
-    }
+    /// Ping the targeted devices; with --watch, keep pinging and report
+    /// connections going stale or recovering
+    Health,
+}
 
-    // Handle --scan-ble
-    if cli.scan_ble {
-        println!("Scanning for DOMES devices via BLE (10 seconds)...");
-        let devices = BleTransport::scan_devices(Duration::from_secs(10))?;
-        if devices.is_empty() {
-            println!("No DOMES devices found");
-        } else {
-            println!("Found DOMES devices:");
-            println!("{:<20} {}", "NAME", "ADDRESS");
-            println!("{:-<20} {:-<17}", "", "");
-            for (name, addr) in devices {
-                let display_name = if name.is_empty() { "(unknown)" } else { &name };
-                println!("{:<20} {}", display_name, addr);
-            }
-        }
-        return Ok(());
-    }
+#[derive(Subcommand)]
+enum GroupAction {
+    /// List groups and their devices
+    List,
 
-    // Handle sniff subcommand (manages its own transport)
-    if let Some(Commands::Sniff {
-        filter,
-        raw,
-        json,
-        count,
-    }) = &cli.command
-    {
-        use commands::sniff::{OutputFormat, ProtocolFilter, SniffOptions};
+    /// Create or replace a group of registered devices
+    Add {
+        /// Group name (e.g., living-room)
+        name: String,
 
-        let filters: Vec<ProtocolFilter> = filter
-            .as_deref()
-            .unwrap_or("")
+        /// Registered device names in the group
+        #[arg(required = true)]
+        devices: Vec<String>,
+    },
+
+    /// Remove a group (its devices stay registered)
+    Remove {
+        /// Group name to remove
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProtoAction {
+    /// List the config and trace message types and whether the CLI handles them
+    List,
+}
+
+#[derive(Subcommand)]
+enum BleAction {
+    /// Pair with the --ble device(s); without --passkey, "just works" pairing
+    Pair {
+        /// Six-digit passkey the pod displays
+        #[arg(long, value_parser = clap::value_parser!(u32).range(0..=999_999))]
+        passkey: Option<u32>,
+    },
+
+    /// Remove the bond with a device, so it has to pair again
+    Forget {
+        /// Bluetooth address (e.g. AA:BB:CC:DD:EE:FF)
+        #[arg(long)]
+        device: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum SerialAction {
+    /// Hold the line in break, which resets an ESP32
+    Break {
+        /// How long to hold the break
+        #[arg(long, default_value = "100")]
+        duration_ms: u64,
+    },
+
+    /// Pulse EN through RTS to restart the firmware (esptool.py hard reset)
+    Reset,
+
+    /// Reset with GPIO0 held low through DTR, entering ROM download mode
+    ForceBootloader,
+}
+
+impl SerialAction {
+    fn name(&self) -> &'static str {
+        match self {
+            SerialAction::Break { .. } => "break",
+            SerialAction::Reset => "reset",
+            SerialAction::ForceBootloader => "force-bootloader",
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Set a default (keys: default-port, default-wifi, default-ble,
+    /// default-timeout-ms, default-baud-rate, color-output, output-format)
+    Set {
+        /// Setting name (e.g., default-port)
+        key: String,
+
+        /// New value (e.g., /dev/ttyACM0)
+        value: String,
+    },
+
+    /// Print a default (nothing if unset)
+    Get {
+        /// Setting name (e.g., default-port)
+        key: String,
+    },
+
+    /// Open the config file in $EDITOR
+    Edit,
+
+    /// Print the defaults as shell exports (DOMES_PORT=..., for CI)
+    Env,
+
+    /// Manage named profiles ([profiles.<name>], selected with --profile)
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProfileAction {
+    /// Create a profile, or replace one with the same name
+    Add {
+        /// Profile name (e.g., dev)
+        name: String,
+
+        /// Serial port (e.g., /dev/ttyACM0)
+        #[arg(long)]
+        port: Option<String>,
+
+        /// WiFi address (e.g., 192.168.10.5:5000)
+        #[arg(long)]
+        wifi: Option<String>,
+
+        /// BLE device name or address
+        #[arg(long)]
+        ble: Option<String>,
+
+        /// Connection timeout (ms)
+        #[arg(long)]
+        timeout: Option<u64>,
+
+        /// Serial baud rate
+        #[arg(long, value_name = "RATE", value_parser = clap::value_parser!(u32).range(1..))]
+        baud: Option<u32>,
+    },
+
+    /// List the profiles and their settings
+    List,
+
+    /// Remove a profile
+    Remove {
+        /// Profile name
+        name: String,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    let mut cli = Cli::parse();
+    // `config edit` is how a broken file gets fixed, so it must not need one
+    let editing = matches!(
+        cli.command,
+        Some(Commands::Config {
+            action: ConfigAction::Edit
+        })
+    );
+    if let Err(e) = cli.apply_env() {
+        cli.output_format().printer(None).error(&e);
+        std::process::exit(exit::USAGE);
+    }
+    // `config ...` must still work when the selected profile is gone
+    let configuring = matches!(cli.command, Some(Commands::Config { .. }));
+    match config::CliConfig::load().and_then(|config| cli.select_profile(config)) {
+        Ok(config) => cli.apply_config(&config),
+        Err(e) if editing || configuring => eprintln!("Warning: {:#}", e),
+        Err(e) => {
+            cli.output_format().printer(None).error(&e);
+            std::process::exit(exit::USAGE);
+        }
+    }
+    let format = cli.output_format();
+    if let Err(e) = run(cli) {
+        if format == Format::Json {
+            format.printer(None).error(&e);
+        } else {
+            // The same report as returning the error from main
+            eprintln!("Error: {:?}", e);
+        }
+        std::process::exit(exit::code_for(&e));
+    }
+    Ok(())
+}
+
+impl Cli {
+    /// `--format`, with `--json` as a shorthand for `--format json`
+    fn output_format(&self) -> Format {
+        if self.json {
+            Format::Json
+        } else {
+            self.format.unwrap_or_default()
+        }
+    }
+
+    /// Whether any flag picks the devices to connect to
+    fn has_targets(&self) -> bool {
+        !self.port.is_empty()
+            || !self.port_glob.is_empty()
+            || !self.wifi.is_empty()
+            || !self.ble.is_empty()
+            || !self.target.is_empty()
+            || !self.group.is_empty()
+            || self.all
+            || self.connect_all_ble
+            || self.wifi_discover
+            || self.pod_id.is_some()
+    }
+
+    /// Fill in settings not given on the command line from `DOMES_*`
+    /// environment variables
+    fn apply_env(&mut self) -> anyhow::Result<()> {
+        self.timeout_from_flag = self.timeout.is_some();
+        if !self.has_targets() {
+            self.port = config::env_list(config::ENV_PORT);
+            self.wifi = config::env_list(config::ENV_WIFI);
+            self.ble = config::env_list(config::ENV_BLE);
+            self.target = config::env_list(config::ENV_TARGET);
+        }
+        if self.timeout.is_none() {
+            if let Some(value) = config::env_var(config::ENV_TIMEOUT_MS) {
+                let ms = value.trim().parse().with_context(|| {
+                    format!("Invalid {}: '{}'", config::ENV_TIMEOUT_MS, value)
+                })?;
+                self.timeout = Some(ms);
+            }
+        }
+        if self.format.is_none() {
+            if let Some(value) = config::env_var(config::ENV_FORMAT) {
+                let format = output::Format::parse_default(&value).with_context(|| {
+                    format!(
+                        "Invalid {}: '{}' (expected table or json)",
+                        config::ENV_FORMAT,
+                        value
+                    )
+                })?;
+                self.format = Some(format);
+            }
+        }
+        if !self.fail_fast && !self.continue_on_error {
+            if let Some(value) = config::env_var(config::ENV_FAIL_FAST) {
+                self.fail_fast = config::parse_switch(&value).with_context(|| {
+                    format!("Invalid {}: '{}'", config::ENV_FAIL_FAST, value)
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `config` with the profile from `--profile` or `DOMES_PROFILE` applied
+    fn select_profile(&self, config: config::CliConfig) -> anyhow::Result<config::CliConfig> {
+        let name = self.profile.clone().or_else(|| config::env_var(config::ENV_PROFILE));
+        match name {
+            Some(name) => config.with_profile(name.trim()),
+            None => Ok(config),
+        }
+    }
+
+    /// Fill in settings not given on the command line from the config file
+    fn apply_config(&mut self, config: &config::CliConfig) {
+        if self.format.is_none() {
+            self.format = Some(config.output_format);
+        }
+        self.timeout = self.timeout.or(config.default_timeout_ms);
+        self.baud = self.baud.or(config.default_baud_rate);
+        output::set_color(config.color_output);
+
+        if !self.has_targets() {
+            (self.port, self.wifi, self.ble) = config.default_device();
+        }
+    }
+}
+
+fn run(mut cli: Cli) -> anyhow::Result<()> {
+    progress::set_quiet(cli.quiet);
+    progress::set_human_readable(cli.human_readable);
+    if cli.no_color {
+        output::set_color(false);
+    }
+    device::set_prefix_color(output::use_color());
+    let format = cli.output_format();
+    if format == Format::NamesOnly
+        && !matches!(
+            cli.command,
+            Some(Commands::Devices {
+                action: DevicesAction::List { .. }
+            })
+        )
+    {
+        return Err(exit::usage("--format names-only is only supported by 'devices list'"));
+    }
+    if format == Format::Prometheus
+        && !matches!(
+            cli.command,
+            Some(Commands::System {
+                action: SystemAction::Info
+            })
+        )
+    {
+        return Err(exit::usage("--format prometheus is only supported by 'system info'"));
+    }
+    let printer = format.printer(None);
+    device::set_registry_lock_timeout(Duration::from_millis(cli.registry_lock_timeout));
+
+    // Handle --list-ports
+    if cli.list_ports {
+        let ports = SerialTransport::list_ports()?;
+        printer.result(json!(ports), &mut || {
+            if ports.is_empty() {
+                outln!("No serial ports found");
+            } else {
+                outln!("Available serial ports:");
+                for port in &ports {
+                    outln!("  {}", port);
+                }
+            }
+        });
+        return Ok(());
+    }
+
+    // Handle --connect-all-ble: scan and add DOMES devices to BLE targets
+    if cli.connect_all_ble {
+        progress!("Scanning for DOMES BLE devices (10 seconds)...");
+        let ble_devices = scan_ble(&cli)?;
+        let existing: std::collections::HashSet<String> = cli.ble.iter().cloned().collect();
+        for d in &ble_devices {
+            if d.name.starts_with("DOMES-Pod") && !existing.contains(&d.address) {
+                progress!("  Found: {} ({}, {})", d.name, d.address, format_rssi(d.rssi));
+                cli.ble.push(d.address.clone());
+            }
+        }
+        let has_other_transports =
+            !cli.port.is_empty() || !cli.wifi.is_empty() || !cli.target.is_empty() || cli.all;
+        if cli.ble.is_empty() && !has_other_transports {
+            eprintln!("No DOMES BLE devices found");
+            std::process::exit(exit::CONNECTION);
+        } else if cli.ble.is_empty() {
+            eprintln!("Warning: no DOMES BLE devices found via scan, using other transports");
+        }
+        progress!();
+    }
+
+    // Handle --wifi-discover: list the pods found, or add them to the WiFi targets
+    if cli.wifi_discover {
+        progress!(
+            "Discovering DOMES pods via mDNS ({} seconds)...",
+            WIFI_DISCOVER_TIMEOUT.as_secs()
+        );
+        let pods = discover_wifi()?;
+        if cli.command.is_none() && cli.batch.is_empty() && cli.script.is_none() {
+            let value = pods
+                .iter()
+                .map(|p| {
+                    json!({ "name": p.name, "address": p.address.to_string(), "pod_id": p.pod_id })
+                })
+                .collect();
+            printer.result(Value::Array(value), &mut || {
+                if pods.is_empty() {
+                    outln!("No DOMES pods found via mDNS");
+                } else {
+                    outln!("{:<20} {:<21} POD ID", "NAME", "ADDRESS");
+                    outln!("{:-<20} {:-<21} {:-<6}", "", "", "");
+                    for p in &pods {
+                        let pod_id = p.pod_id.map_or_else(|| "-".to_string(), |id| id.to_string());
+                        outln!("{:<20} {:<21} {}", p.name, p.address.to_string(), pod_id);
+                    }
+                }
+            });
+            return Ok(());
+        }
+        for p in &pods {
+            let address = p.address.to_string();
+            if !cli.wifi.contains(&address) {
+                progress!("  Found: {} ({})", p.name, address);
+                cli.wifi.push(address);
+            }
+        }
+        let has_other_transports = !cli.port.is_empty()
+            || !cli.ble.is_empty()
+            || !cli.target.is_empty()
+            || cli.all
+            || cli.connect_all_ble;
+        if pods.is_empty() && cli.wifi.is_empty() && !has_other_transports {
+            eprintln!("No DOMES pods found via mDNS");
+            std::process::exit(exit::CONNECTION);
+        } else if pods.is_empty() {
+            eprintln!("Warning: no DOMES pods found via mDNS, using other transports");
+        }
+        progress!();
+    }
+
+    // Handle --pod-id: find the one pod reporting the ID and add it as a target
+    if let Some(pod_id) = cli.pod_id {
+        progress!("Looking for pod {} on serial ports...", pod_id);
+        let ports = SerialTransport::list_ports().unwrap_or_default();
+        let baud = cli.baud.unwrap_or(DEFAULT_BAUD_RATE);
+        let mut matches: Vec<(&str, String)> = probe_serial_ports(&ports, baud)
+            .into_iter()
+            .filter(|(_, info)| info.as_ref().is_some_and(|info| info.pod_id == pod_id))
+            .map(|(port, _)| ("serial", port))
+            .collect();
+        let mut searched = format!("{} serial port(s)", ports.len());
+        if cli.pod_id_ble {
+            let name = format!("DOMES-Pod-{:02}", pod_id);
+            progress!("Scanning BLE for {} (10 seconds)...", name);
+            let ble_devices = scan_ble(&cli)?;
+            matches.extend(
+                ble_devices
+                    .into_iter()
+                    .filter(|d| d.name == name)
+                    .map(|d| ("ble", d.address)),
+            );
+            searched.push_str(" and BLE");
+        }
+        match single_pod_match(pod_id, matches, &searched)? {
+            ("ble", address) => cli.ble.push(address),
+            (_, port) => cli.port.push(port),
+        }
+        progress!();
+    }
+
+    // Handle --scan-ble
+    if cli.scan_ble {
+        // --mac-filter lists any device with the prefix, DOMES or not
+        let what = if cli.mac_filter.is_some() { "devices" } else { "DOMES devices" };
+        progress!("Scanning for {} via BLE (10 seconds)...", what);
+        let devices = scan_ble(&cli)?;
+        let value = devices
+            .iter()
+            .map(|d| {
+                let services: Vec<String> = d.services.iter().map(|u| u.to_string()).collect();
+                json!({ "name": d.name, "address": d.address, "rssi": d.rssi, "services": services })
+            })
+            .collect();
+        printer.result(Value::Array(value), &mut || {
+            if devices.is_empty() {
+                outln!("No {} found", what);
+            } else {
+                outln!("Found {} (strongest signal first):", what);
+                outln!("{:<20} {:<17} {:<8} SIGNAL", "NAME", "ADDRESS", "RSSI");
+                outln!("{:-<20} {:-<17} {:-<8} {:-<10}", "", "", "", "");
+                for d in &devices {
+                    let display_name = if d.name.is_empty() { "(unknown)" } else { &d.name };
+                    let bar = d.rssi.map(signal_bar).unwrap_or_default();
+                    outln!(
+                        "{:<20} {:<17} {:<8} {}",
+                        display_name,
+                        d.address,
+                        format_rssi(d.rssi),
+                        bar
+                    );
+                }
+            }
+        });
+        return Ok(());
+    }
+
+    // Handle wait-for-device (polls on its own until the device answers)
+    if let Some(Commands::WaitForDevice {
+        port,
+        ble,
+        timeout,
+        exec,
+    }) = &cli.command
+    {
+        let target = match (port, ble, cli.port.as_slice(), cli.ble.as_slice()) {
+            (Some(port), _, _, _) => WaitTarget::Serial(port.clone()),
+            (None, Some(ble), _, _) => WaitTarget::Ble(ble.clone()),
+            (None, None, [port], []) => WaitTarget::Serial(port.clone()),
+            (None, None, [], [ble]) => WaitTarget::Ble(ble.clone()),
+            _ => {
+                return Err(exit::usage(
+                    "wait-for-device needs one device: --port <PORT> or --ble <NAME>",
+                ))
+            }
+        };
+        let baud = cli.baud.unwrap_or(DEFAULT_BAUD_RATE);
+        wait_for_device(&target, Duration::from_secs(*timeout), baud, printer.as_ref())?;
+        if let Some(command) = exec {
+            let status = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .status()
+                .with_context(|| format!("Failed to run '{}'", command))?;
+            if !status.success() {
+                std::process::exit(status.code().unwrap_or(1));
+            }
+        }
+        return Ok(());
+    }
+
+    // Handle sniff subcommand (manages its own transport)
+    if let Some(Commands::Sniff {
+        filter,
+        raw,
+        json,
+        count,
+    }) = &cli.command
+    {
+        use commands::sniff::{OutputFormat, ProtocolFilter, SniffOptions};
+
+        let filters: Vec<ProtocolFilter> = filter
+            .as_deref()
+            .unwrap_or("")
             .split(',')
             .filter(|s| !s.is_empty())
-            .filter_map(ProtocolFilter::from_str)
+            .filter_map(ProtocolFilter::from_cli_name)
             .collect();
 
-        let format = if *json {
+        let format = if *json || format == Format::Json {
             OutputFormat::Json
         } else if *raw {
             OutputFormat::Raw
@@ -527,61 +1591,157 @@ fn main() -> anyhow::Result<()> {
         // Sniff requires exactly one serial port
         if cli.port.len() != 1 {
             eprintln!("Sniff requires exactly one serial port (--port /dev/ttyACM0)");
-            std::process::exit(1);
+            std::process::exit(exit::USAGE);
         }
 
         return commands::sniff::sniff_serial(&cli.port[0], &opts);
     }
 
+    // Handle decode subcommand (offline, no transport needed)
+    if let Some(Commands::Decode { hex }) = &cli.command {
+        let bytes = commands::raw::parse_hex_bytes(&hex.join(" "))?;
+        return print_decode_report(&commands::decode::decode_bytes(&bytes), printer.as_ref());
+    }
+
+    // Handle proto subcommand (offline, no transport needed)
+    if let Some(Commands::Proto { action: ProtoAction::List }) = &cli.command {
+        print_message_types(printer.as_ref());
+        return Ok(());
+    }
+
+    // Handle completions subcommand (no transport needed)
+    if let Some(Commands::Completions { shell }) = &cli.command {
+        print!("{}", completions::script(*shell));
+        return Ok(());
+    }
+
+    // Handle config subcommand (no transport needed)
+    if let Some(Commands::Config { action }) = &cli.command {
+        return run_config(action, printer.as_ref());
+    }
+
+    // Handle ble subcommand (pairs through the OS, not over a DOMES transport)
+    if let Some(Commands::Ble { action }) = &cli.command {
+        return run_ble(&cli, action, printer.as_ref());
+    }
+
+    // Handle serial subcommand (drives the port's lines, not DOMES frames)
+    if let Some(Commands::Serial { dangerous, action }) = &cli.command {
+        return run_serial(&cli, *dangerous, action, printer.as_ref());
+    }
+
     // Handle devices subcommand (no transport needed)
     if let Some(Commands::Devices { action }) = &cli.command {
         match action {
-            DevicesAction::List => {
-                let registry = device::load_device_registry()?;
-                if registry.is_empty() {
-                    println!("No devices registered.");
-                    println!(
-                        "Use 'domes-cli devices add <name> <transport> <address>' to register."
-                    );
-                } else {
-                    println!("{:<12} {:<10} {}", "NAME", "TRANSPORT", "ADDRESS");
-                    println!("{:-<12} {:-<10} {:-<30}", "", "", "");
-                    let mut names: Vec<&String> = registry.keys().collect();
-                    names.sort();
-                    for name in names {
-                        let entry = &registry[name];
-                        println!(
-                            "{:<12} {:<10} {}",
-                            name, entry.transport_type, entry.address
-                        );
-                    }
+            DevicesAction::List { tag } if format == Format::NamesOnly => {
+                let mut names: Vec<String> = device::load_device_registry()?
+                    .into_iter()
+                    .filter(|(_, entry)| entry.has_tags(tag))
+                    .map(|(name, _)| name)
+                    .collect();
+                names.sort();
+                for name in names {
+                    outln!("{}", name);
                 }
                 return Ok(());
             }
+            DevicesAction::List { tag } => {
+                let probe = (cli.verbose > 0).then(|| {
+                    let timeout = cli.timeout.map_or(PROBE_TIMEOUT, Duration::from_millis);
+                    (timeout, cli.baud)
+                });
+                print_device_list(tag, probe, printer.as_ref())?;
+                return Ok(());
+            }
             DevicesAction::Add {
                 name,
                 transport,
                 address,
+                timeout_ms,
+                connect_retries,
+                scan_timeout_secs,
+                baud,
+                description,
+                tag,
             } => {
+                if baud.is_some() && transport != "serial" {
+                    return Err(exit::usage("--baud only applies to serial devices"));
+                }
+                if scan_timeout_secs.is_some() && transport != "ble" {
+                    return Err(exit::usage("--scan-timeout-secs only applies to BLE devices"));
+                }
+                if tag.iter().any(|t| t.trim().is_empty()) {
+                    return Err(exit::usage("--tag must not be empty"));
+                }
                 let entry = device::DeviceEntry {
                     name: name.clone(),
                     transport_type: transport.clone(),
                     address: address.clone(),
+                    timeout_ms: *timeout_ms,
+                    baud: *baud,
+                    description: description.clone().filter(|d| !d.trim().is_empty()),
+                    tags: (!tag.is_empty()).then(|| tag.clone()),
+                    connect_retries: *connect_retries,
+                    scan_timeout_secs: *scan_timeout_secs,
                 };
                 device::save_device_entry(name, &entry)?;
-                println!("Added device '{}' ({} @ {})", name, transport, address);
+                printer.result(json!({ "added": name }), &mut || {
+                    outln!("Added device '{}' ({} @ {})", name, transport, address)
+                });
                 return Ok(());
             }
             DevicesAction::Remove { name } => {
-                if device::remove_device_entry(name)? {
-                    println!("Removed device '{}'", name);
-                } else {
-                    println!("Device '{}' not found", name);
+                let removed = device::remove_device_entry(name)?;
+                printer.result(json!({ "name": name, "removed": removed }), &mut || {
+                    if removed {
+                        outln!("Removed device '{}'", name);
+                    } else {
+                        outln!("Device '{}' not found", name);
+                    }
+                });
+                return Ok(());
+            }
+            DevicesAction::Group { action } => {
+                match action {
+                    GroupAction::List => {
+                        let registry = device::load_registry()?;
+                        printer.result(json!(registry.groups), &mut || {
+                            if registry.groups.is_empty() {
+                                outln!("No groups defined.");
+                                outln!(
+                                    "Use 'domes-cli devices group add <group> <device>...' to create one."
+                                );
+                            } else {
+                                outln!("{:<16} DEVICES", "GROUP");
+                                outln!("{:-<16} {:-<30}", "", "");
+                                for (name, members) in &registry.groups {
+                                    outln!("{:<16} {}", name, members.join(", "));
+                                }
+                            }
+                        });
+                    }
+                    GroupAction::Add { name, devices } => {
+                        device::save_group(name, devices)?;
+                        printer.result(json!({ "group": name, "devices": devices }), &mut || {
+                            outln!("Group '{}': {}", name, devices.join(", "))
+                        });
+                    }
+                    GroupAction::Remove { name } => {
+                        let removed = device::remove_group(name)?;
+                        printer.result(json!({ "group": name, "removed": removed }), &mut || {
+                            if removed {
+                                outln!("Removed group '{}'", name);
+                            } else {
+                                outln!("Group '{}' not found", name);
+                            }
+                        });
+                    }
                 }
                 return Ok(());
             }
-            DevicesAction::Scan => {
-                println!("Scanning for DOMES devices...\n");
+            DevicesAction::Scan { register, prefix } => {
+                progress!("Scanning for DOMES devices...\n");
+                let mut found: Vec<device::ScannedDevice> = Vec::new();
 
                 // Scan serial ports (ttyACM* and domes-pod-* symlinks)
                 let ports = SerialTransport::list_ports().unwrap_or_default();
@@ -601,152 +1761,1872 @@ fn main() -> anyhow::Result<()> {
                     })
                     .unwrap_or_default();
 
-                if !ports.is_empty() || !domes_symlinks.is_empty() {
-                    println!("Serial devices:");
-                    for port in &ports {
-                        // Try to probe the device for identity
-                        let pod_info = SerialTransport::open(port)
-                            .ok()
-                            .and_then(|mut t| commands::system_info(&mut t).ok());
-                        if let Some(info) = pod_info {
-                            let pod_label = if info.pod_id > 0 {
-                                format!("pod-{}", info.pod_id)
+                // Probe each port for its identity
+                let probed = probe_serial_ports(&ports, cli.baud.unwrap_or(DEFAULT_BAUD_RATE));
+                for (port, info) in &probed {
+                    if let Some(info) = info {
+                        found.push(device::ScannedDevice {
+                            transport_type: "serial".to_string(),
+                            address: port.clone(),
+                            pod_id: Some(info.pod_id),
+                            advertised_name: None,
+                        });
+                    }
+                }
+                let symlinks: Vec<&String> =
+                    domes_symlinks.iter().filter(|s| !ports.contains(s)).collect();
+
+                // Scan BLE
+                progress!("Scanning BLE (10 seconds)...");
+                let ble_devices = scan_ble(&cli).unwrap_or_default();
+                for d in &ble_devices {
+                    if d.name.starts_with("DOMES-Pod") {
+                        found.push(device::ScannedDevice {
+                            transport_type: "ble".to_string(),
+                            address: d.address.clone(),
+                            pod_id: None,
+                            advertised_name: Some(d.name.clone()),
+                        });
+                    }
+                }
+
+                // Discover WiFi pods via mDNS
+                progress!(
+                    "Discovering WiFi pods via mDNS ({} seconds)...",
+                    WIFI_DISCOVER_TIMEOUT.as_secs()
+                );
+                let wifi_pods = discover_wifi().unwrap_or_default();
+                for p in &wifi_pods {
+                    found.push(device::ScannedDevice {
+                        transport_type: "wifi".to_string(),
+                        address: p.address.to_string(),
+                        pod_id: p.pod_id,
+                        advertised_name: None,
+                    });
+                }
+
+                let outcomes = if *register {
+                    device::register_scanned(&found, prefix.as_deref())?
+                } else {
+                    Vec::new()
+                };
+
+                let serial_json: Vec<Value> = probed
+                    .iter()
+                    .map(|(port, info)| match info {
+                        Some(info) => json!({
+                            "port": port,
+                            "pod_id": info.pod_id,
+                            "firmware_version": firmware_version(info),
+                            "mode": info.mode.to_string(),
+                        }),
+                        None => json!({ "port": port, "domes": false }),
+                    })
+                    .chain(symlinks.iter().map(|s| json!({ "port": s, "symlink": true })))
+                    .collect();
+                let ble_json: Vec<Value> = ble_devices
+                    .iter()
+                    .map(|d| {
+                        json!({
+                            "name": d.name,
+                            "address": d.address,
+                            "rssi": d.rssi,
+                            "domes": d.name.starts_with("DOMES-Pod"),
+                        })
+                    })
+                    .collect();
+                let registered_json: Vec<Value> = found
+                    .iter()
+                    .zip(&outcomes)
+                    .map(|(dev, outcome)| match outcome {
+                        device::ScanRegistration::Added(name) => {
+                            json!({ "address": dev.address, "name": name, "added": true })
+                        }
+                        device::ScanRegistration::AlreadyRegistered(name) => {
+                            json!({ "address": dev.address, "name": name, "added": false })
+                        }
+                    })
+                    .collect();
+                let wifi_json: Vec<Value> = wifi_pods
+                    .iter()
+                    .map(|p| {
+                        json!({
+                            "name": p.name,
+                            "address": p.address.to_string(),
+                            "pod_id": p.pod_id,
+                        })
+                    })
+                    .collect();
+                let mut value =
+                    json!({ "serial": serial_json, "ble": ble_json, "wifi": wifi_json });
+                if *register {
+                    value["registered"] = Value::Array(registered_json);
+                }
+
+                printer.result(value, &mut || {
+                    if !probed.is_empty() || !symlinks.is_empty() {
+                        outln!("Serial devices:");
+                        for (port, info) in &probed {
+                            if let Some(info) = info {
+                                let pod_label = if info.pod_id > 0 {
+                                    format!("pod-{}", info.pod_id)
+                                } else {
+                                    "unknown-id".to_string()
+                                };
+                                outln!(
+                                    "  {:<20} {} (fw: {}, mode: {:?})",
+                                    port,
+                                    pod_label,
+                                    firmware_version(info).unwrap_or("unknown"),
+                                    info.mode
+                                );
+                            } else {
+                                outln!("  {:<20} (not a DOMES device or busy)", port);
+                            }
+                        }
+                        for symlink in &symlinks {
+                            outln!("  {:<20} (udev symlink)", symlink);
+                        }
+                        outln!();
+                    } else {
+                        outln!("No serial devices found\n");
+                    }
+
+                    if !ble_devices.is_empty() {
+                        outln!("BLE devices:");
+                        for d in &ble_devices {
+                            let display_name = if d.name.is_empty() {
+                                "(unknown)"
                             } else {
-                                "unknown-id".to_string()
+                                &d.name
                             };
-                            println!(
-                                "  {:<20} {} (fw: {}, mode: {:?})",
-                                port, pod_label, info.firmware_version, info.mode
+                            outln!(
+                                "  {:<20} {:<17} {:<8} {:<10}{}",
+                                display_name,
+                                d.address,
+                                format_rssi(d.rssi),
+                                d.rssi.map(signal_bar).unwrap_or_default(),
+                                if display_name.starts_with("DOMES-Pod") { " <-- DOMES" } else { "" }
                             );
-                        } else {
-                            println!("  {:<20} (not a DOMES device or busy)", port);
                         }
+                    } else {
+                        outln!("No BLE devices found");
                     }
-                    for symlink in &domes_symlinks {
-                        if !ports.contains(symlink) {
-                            println!("  {:<20} (udev symlink)", symlink);
+                    outln!();
+
+                    if !wifi_pods.is_empty() {
+                        outln!("WiFi devices:");
+                        for p in &wifi_pods {
+                            let pod_label = match p.pod_id {
+                                Some(id) if id > 0 => format!("pod-{}", id),
+                                _ => "unknown-id".to_string(),
+                            };
+                            outln!("  {:<20} {:<21} {}", p.name, p.address.to_string(), pod_label);
                         }
+                    } else {
+                        outln!("No WiFi devices found");
                     }
-                    println!();
-                } else {
-                    println!("No serial devices found\n");
-                }
 
-                // Scan BLE
-                println!("Scanning BLE (10 seconds)...");
-                let ble_devices =
-                    BleTransport::scan_devices(Duration::from_secs(10)).unwrap_or_default();
-                if !ble_devices.is_empty() {
-                    println!("BLE devices:");
-                    for (name, addr) in &ble_devices {
-                        let display_name = if name.is_empty() {
-                            "(unknown)"
-                        } else {
-                            name
-                        };
-                        let is_domes = display_name.starts_with("DOMES-Pod");
-                        println!(
-                            "  {:<20} {}{}",
-                            display_name,
-                            addr,
-                            if is_domes { " <-- DOMES" } else { "" }
-                        );
+                    if *register {
+                        outln!();
+                        for (dev, outcome) in found.iter().zip(&outcomes) {
+                            match outcome {
+                                device::ScanRegistration::Added(name) => {
+                                    outln!("Registered {} as '{}'", dev.address, name)
+                                }
+                                device::ScanRegistration::AlreadyRegistered(name) => {
+                                    outln!("{} already registered (as '{}')", dev.address, name)
+                                }
+                            }
+                        }
+                        outln!();
                     }
-                } else {
-                    println!("No BLE devices found");
+                });
+                if *register && printer.is_table() {
+                    print_device_list(&[], None, printer.as_ref())?;
                 }
 
                 return Ok(());
             }
+            // Needs device connections; handled after they are resolved
+            DevicesAction::Health => {}
         }
     }
 
     // All other commands require at least one transport
-    let Some(command) = cli.command else {
-        eprintln!("No command specified. Use --help for usage.");
-        std::process::exit(1);
+    let command = if let Some(path) = &cli.script {
+        if cli.command.is_some() {
+            return Err(exit::usage("--script cannot be combined with a subcommand; put it in the script"));
+        }
+        let (lines, failed) = script::parse_script(&script::read_script(path)?);
+        if cli.dry_run {
+            script::print_dry_run(&lines);
+            std::process::exit(script_exit_code(failed));
+        }
+        if failed > 0 && cli.on_error == OnError::Abort {
+            std::process::exit(script_exit_code(failed));
+        }
+        Commands::Script { lines, failed }
+    } else if cli.batch.is_empty() {
+        let Some(command) = cli.command else {
+            eprintln!("No command specified. Use --help for usage.");
+            std::process::exit(exit::USAGE);
+        };
+        command
+    } else {
+        if cli.command.is_some() {
+            return Err(exit::usage("--batch cannot be combined with a subcommand; pass each command to --batch"));
+        }
+        Commands::Batch {
+            commands: cli
+                .batch
+                .iter()
+                .map(|line| parse_batch_command(line))
+                .collect::<anyhow::Result<_>>()?,
+        }
     };
 
-    // Resolve device connections
-    let mut devices = device::resolve_devices(
-        &cli.port,
-        &cli.wifi,
-        &cli.ble,
-        &cli.target,
-        cli.all,
-    )?;
+    validate_command(&command)?;
 
-    if devices.is_empty() {
-        eprintln!("No transport specified. Use --port, --wifi, --ble, --target, or --all");
+    if cli.watch && !is_read_only(&command) {
+        return Err(exit::usage(
+            "--watch only works with read commands (e.g. feature list, led get, trace status, system info)",
+        ));
+    }
+
+    // Resolve device connections
+    let resolved = device::resolve_devices(&device::DeviceSelection {
+        ports: cli.port.clone(),
+        port_globs: cli.port_glob.clone(),
+        wifis: cli.wifi.clone(),
+        bles: cli.ble.clone(),
+        targets: cli.target.clone(),
+        groups: cli.group.clone(),
+        all: cli.all,
+        timeout: cli.timeout.map(Duration::from_millis),
+        timeout_from_flag: cli.timeout_from_flag,
+        connect_retries: cli.connect_retries,
+        scan_timeout: cli.scan_timeout_secs.map(Duration::from_secs),
+        baud: cli.baud,
+        verbosity: cli.verbose,
+        connect_timeout: cli.connect_timeout_ms.map(Duration::from_millis),
+        fail_fast: cli.fail_fast,
+        wifi_retry: cli
+            .wifi_retry
+            .map(|attempts| (attempts, Duration::from_millis(cli.wifi_retry_delay_ms))),
+        tcp_rate_limit: cli.tcp_rate_limit,
+    })?;
+    let mut devices = resolved.connections;
+    let unreachable = resolved.unreachable;
+
+    if cli.max_payload.is_some() || sends_large_payloads(&command) {
+        devices = devices
+            .into_iter()
+            .map(|dev| limit_payload(dev, cli.max_payload))
+            .collect();
+    }
+
+    if let Some(secs) = cli.keepalive {
+        for dev in &mut devices {
+            dev.transport.set_keepalive(Some(Duration::from_secs(secs)));
+        }
+    }
+
+    if devices.is_empty() {
+        eprintln!("No transport specified. Use --port, --port-glob, --wifi, --ble, --target, --group, or --all");
         eprintln!("Use --list-ports to see serial ports, --scan-ble for BLE devices.");
         eprintln!("Use 'domes-cli devices add <name> <type> <addr>' to register devices.");
-        std::process::exit(1);
+        eprintln!("Use 'domes-cli config set default-port <port>' to set a default device.");
+        std::process::exit(exit::USAGE);
     }
 
-    let multi = devices.len() > 1;
-    let mut failures: Vec<String> = Vec::new();
 
-    // Execute command on each device
-    for dev in devices.iter_mut() {
-        let prefix = if multi {
-            device::device_prefix(&dev.name)
-        } else {
-            String::new()
+    // Ctrl-C ends a timed trace capture early (it still stops and dumps),
+    // ends an event subscription, stops --watch and, in the REPL, stops the
+    // running command without leaving the session
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let timed_trace = matches!(
+        &command,
+        Commands::Trace {
+            action: TraceAction::Start { duration: Some(_), .. },
+        }
+    );
+    let listening = matches!(
+        &command,
+        Commands::Events { .. } | Commands::Repl | Commands::Script { .. }
+    );
+    let health = matches!(
+        &command,
+        Commands::Devices {
+            action: DevicesAction::Health,
+        }
+    );
+    if timed_trace || listening || cli.watch {
+        let flag = interrupted.clone();
+        ctrlc::set_handler(move || flag.store(true, Ordering::Relaxed))
+            .context("Failed to install Ctrl-C handler")?;
+    }
+
+    if health {
+        let config = KeepaliveConfig {
+            interval: Duration::from_millis(cli.keepalive_interval_ms),
+            timeout: Duration::from_millis(cli.keepalive_timeout_ms),
         };
-        let transport = dev.transport.as_mut();
-        let dev_label = if dev.name.is_empty() {
-            "device".to_string()
-        } else {
-            dev.name.clone()
+        if cli.watch {
+            return monitor_health(devices, config, cli.verbose > 0, format, &interrupted);
+        }
+        if !check_health(&mut devices, config, printer.as_ref()) || !unreachable.is_empty() {
+            std::process::exit(exit::CONNECTION);
+        }
+        return Ok(());
+    }
+
+    if cli.diff && devices.len() > 1 && !supports_diff(&command) {
+        return Err(exit::usage("--diff is only supported for system info, feature list and led get"));
+    }
+
+    let opts = RunOptions {
+        verbose: cli.verbose > 0,
+        retries: cli.retry,
+        fail_fast: cli.fail_fast,
+        parallel: cli.parallel,
+        parallel_max: cli.parallel_max,
+        diff: cli.diff,
+        format,
+    };
+
+    if matches!(command, Commands::Repl) {
+        return repl::run(&mut devices, &opts, &interrupted);
+    }
+
+    if let Commands::Script { lines, failed } = &command {
+        let failed = failed + script::run(lines, cli.on_error, &mut devices, &opts, &interrupted);
+        if failed > 0 {
+            std::process::exit(script_exit_code(failed));
+        }
+        return Ok(());
+    }
+
+    if cli.watch {
+        let interval = Duration::from_millis(cli.interval_ms);
+        if matches!(command, Commands::System { action: SystemAction::Mode }) {
+            return watch_mode_transitions(&mut devices, interval, format, &interrupted);
+        }
+        if matches!(command, Commands::Trace { action: TraceAction::Status }) {
+            return watch_trace_rates(&mut devices, interval, format, &interrupted);
+        }
+        return watch(&command, &mut devices, &opts, interval, &interrupted);
+    }
+
+    let mut summary = run_once(&command, &mut devices, &opts, &interrupted)?;
+    // Devices that never connected count as failed
+    if !unreachable.is_empty() {
+        summary.exit_code = exit::most_severe(summary.exit_code, exit::CONNECTION);
+    }
+    summary.failures.extend(unreachable);
+    summary.report();
+    if !summary.failures.is_empty() {
+        std::process::exit(summary.exit_code);
+    }
+
+    Ok(())
+}
+
+/// `--script` exit status: the number of failed lines, saturated to fit
+fn script_exit_code(failed: usize) -> i32 {
+    failed.min(255) as i32
+}
+
+/// Reject bad arguments (colors, modes, flag combinations, pod ID, name, AP
+/// settings) before connecting, so they exit with `exit::USAGE`
+fn validate_command(command: &Commands) -> anyhow::Result<()> {
+    match command {
+        Commands::Batch { commands } => commands.iter().try_for_each(validate_command)?,
+        Commands::Led {
+            action: action @ (LedAction::Solid { .. }
+            | LedAction::Breathing { .. }
+            | LedAction::Cycle { .. }),
+        } => {
+            requested_led_pattern(action)?;
+        }
+        Commands::System {
+            action: SystemAction::SetMode { mode, .. },
+        } => {
+            parse_mode(mode)?;
+        }
+        Commands::Ota {
+            action: OtaAction::AutoUpdate { enable, disable },
+        }
+        | Commands::Imu {
+            action: ImuAction::Triage { enable, disable },
+        } => {
+            enable_flag(*enable, *disable)?;
+        }
+        Commands::Trace {
+            action: TraceAction::Dump { output, trace_format, append, session_label, .. },
+        } => {
+            let label = session_label.as_deref();
+            trace_dump_format(output, trace_format.as_deref(), *append, label)?;
+        }
+        Commands::System {
+            action: SystemAction::SetPodId { id },
+        } => commands::validate_pod_id(*id)?,
+        Commands::System {
+            action: SystemAction::SetName { name },
+        } => commands::validate_device_name(name)?,
+        Commands::Wifi {
+            action: WifiAction::ConfigureAp { ssid, password, channel, max_connections },
+        } => check_ap_config(ssid, password, *channel, *max_connections)?,
+        Commands::Wifi {
+            action: WifiAction::Static { ip, netmask, gateway, .. },
+        } => commands::validate_static_ip(*ip, *netmask, *gateway)?,
+        // A missing or invalid profile fails before connecting
+        Commands::Feature {
+            action: FeatureAction::CheckProfile { file },
+        } => {
+            commands::load_feature_profile(file)?;
+        }
+        // An unknown `@alias` fails before connecting
+        Commands::Ota {
+            action: OtaAction::Flash { firmware, .. },
+        } => {
+            commands::ota::resolve_firmware_path(firmware)?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Validate `wifi configure-ap` settings, warning about a weak or missing password
+fn check_ap_config(
+    ssid: &str,
+    password: &str,
+    channel: u8,
+    max_connections: u8,
+) -> anyhow::Result<()> {
+    commands::validate_ap_config(ssid, password, channel, max_connections)?;
+    if password.is_empty() {
+        eprintln!("Warning: no password given; '{}' will be an open network", ssid);
+    } else if password.len() < commands::WPA2_MIN_PASSWORD_LEN {
+        eprintln!(
+            "Warning: WPA2-PSK needs a password of at least {} characters; \
+             the pod may refuse to start the access point",
+            commands::WPA2_MIN_PASSWORD_LEN
+        );
+    }
+    Ok(())
+}
+
+/// How often `wait-for-device` checks for the device
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Device `wait-for-device` waits for
+enum WaitTarget {
+    Serial(String),
+    Ble(String),
+}
+
+/// Poll until the device enumerates and answers `GetSystemInfoReq`
+///
+/// A serial port must appear (in `list_ports`, or as a path for symlinks such
+/// as `/dev/domes-pod-1`) and answer; a BLE device is scanned for until found
+/// and then asked too. Fails once `timeout` has passed.
+fn wait_for_device(
+    target: &WaitTarget,
+    timeout: Duration,
+    baud: u32,
+    printer: &dyn Printer,
+) -> anyhow::Result<()> {
+    let start = Instant::now();
+    let deadline = start + timeout;
+    let (address, transport_type) = match target {
+        WaitTarget::Serial(port) => (port.as_str(), "serial"),
+        WaitTarget::Ble(name) => (name.as_str(), "ble"),
+    };
+    progress!("Waiting for {} (up to {} s)...", address, timeout.as_secs());
+
+    let info = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            let waited = format!("Timed out after {} s waiting for {}", timeout.as_secs(), address);
+            return Err(anyhow::Error::new(ResponseTimeout).context(waited));
+        }
+        let attempt: anyhow::Result<protocol::CliSystemInfo> = match target {
+            WaitTarget::Serial(port) => {
+                let listed = SerialTransport::list_ports()
+                    .map(|ports| ports.contains(port))
+                    .unwrap_or(false);
+                if listed || Path::new(port).exists() {
+                    SerialTransport::open(port, baud)
+                        .and_then(|mut t| Ok(commands::system_info(&mut t)?))
+                } else {
+                    Err(anyhow::anyhow!("{} not present", port))
+                }
+            }
+            // The scan itself keeps going until the device shows up
+            WaitTarget::Ble(name) => {
+                BleTransport::connect(BleTarget::parse(name), remaining, false)
+                    .and_then(|mut t| Ok(commands::system_info(&mut t)?))
+            }
         };
+        match attempt {
+            Ok(info) => break info,
+            Err(_) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                std::thread::sleep(remaining.min(WAIT_POLL_INTERVAL));
+            }
+        }
+    };
+
+    let elapsed = start.elapsed();
+    let value = json!({
+        "transport": transport_type,
+        "address": address,
+        "pod_id": info.pod_id,
+        "firmware_version": firmware_version(&info),
+        "elapsed_ms": elapsed.as_millis() as u64,
+    });
+    printer.result(value, &mut || {
+        outln!(
+            "{} is ready after {:.1} s (pod {}, firmware {})",
+            address,
+            elapsed.as_secs_f64(),
+            info.pod_id,
+            firmware_version(&info).unwrap_or("unknown")
+        )
+    });
+    Ok(())
+}
+
+/// `config set/get/edit`
+fn run_config(action: &ConfigAction, printer: &dyn Printer) -> anyhow::Result<()> {
+    match action {
+        ConfigAction::Set { key, value } => {
+            let mut config = config::CliConfig::load()?;
+            config.set(key, value)?;
+            config.save()?;
+            printer.result(json!({ "key": key, "value": value }), &mut || {
+                outln!("Set {} = {}", key, value)
+            });
+        }
+        ConfigAction::Get { key } => {
+            let value = config::CliConfig::load()?.get(key)?;
+            printer.result(json!({ "key": key, "value": value }), &mut || {
+                if let Some(value) = &value {
+                    outln!("{}", value);
+                }
+            });
+        }
+        ConfigAction::Env => {
+            let exports = config::CliConfig::load()?.env_exports();
+            let value = exports.iter().map(|(name, value)| (name.to_string(), json!(value))).collect();
+            printer.result(Value::Object(value), &mut || {
+                for (name, value) in &exports {
+                    let quoted = shlex::try_quote(value).unwrap_or_else(|_| value.into());
+                    outln!("export {}={}", name, quoted);
+                }
+            });
+        }
+        ConfigAction::Edit => {
+            config::edit()?;
+            let path = config::config_path();
+            printer.result(json!({ "path": path }), &mut || {
+                outln!("Saved {}", path.display())
+            });
+        }
+        ConfigAction::Profile { action } => run_profile(action, printer)?,
+    }
+    Ok(())
+}
+
+/// `config profile add/list/remove`
+fn run_profile(action: &ProfileAction, printer: &dyn Printer) -> anyhow::Result<()> {
+    let mut config = config::CliConfig::load()?;
+    match action {
+        ProfileAction::Add {
+            name,
+            port,
+            wifi,
+            ble,
+            timeout,
+            baud,
+        } => {
+            let profile = config::Profile {
+                default_port: port.clone(),
+                default_wifi: wifi.clone(),
+                default_ble: ble.clone(),
+                default_timeout_ms: *timeout,
+                default_baud_rate: *baud,
+            };
+            anyhow::ensure!(
+                profile != config::Profile::default(),
+                "Profile '{}' needs at least one of --port, --wifi, --ble, --timeout, --baud",
+                name
+            );
+            let settings = profile.settings();
+            let replaced = config.profiles.insert(name.clone(), profile).is_some();
+            config.save()?;
+            printer.result(json!({ "name": name, "settings": settings }), &mut || {
+                let verb = if replaced { "Replaced" } else { "Added" };
+                outln!("{} profile '{}': {}", verb, name, settings.join(", "))
+            });
+        }
+        ProfileAction::List => {
+            let value: Vec<Value> = config
+                .profiles
+                .iter()
+                .map(|(name, profile)| json!({ "name": name, "settings": profile.settings() }))
+                .collect();
+            printer.result(Value::Array(value), &mut || {
+                if config.profiles.is_empty() {
+                    outln!("No profiles (add one with 'config profile add <name> --port ...')");
+                }
+                for (name, profile) in &config.profiles {
+                    outln!("{:<12}  {}", name, profile.settings().join(", "));
+                }
+            });
+        }
+        ProfileAction::Remove { name } => {
+            anyhow::ensure!(
+                config.profiles.remove(name).is_some(),
+                "Profile '{}' not found",
+                name
+            );
+            config.save()?;
+            printer.result(json!({ "name": name, "removed": true }), &mut || {
+                outln!("Removed profile '{}'", name)
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Failures from one pass over the targeted devices
+#[derive(Default)]
+struct RunSummary {
+    /// Devices whose command failed
+    failures: Vec<String>,
+    /// Device whose failure triggered a `--fail-fast` abort
+    aborted_by: Option<String>,
+    /// Devices skipped because of the abort
+    skipped: Vec<String>,
+    /// Most severe exit code of the failures (see `exit`)
+    exit_code: i32,
+}
+
+impl RunSummary {
+    /// Print the abort and failure summary lines to stderr
+    fn report(&self) {
+        if let Some(name) = &self.aborted_by {
+            eprintln!("Aborted after failure on {} (--fail-fast)", name);
+            if !self.skipped.is_empty() {
+                eprintln!(
+                    "Skipped {} device(s): {}",
+                    self.skipped.len(),
+                    self.skipped.join(", ")
+                );
+            }
+        }
+
+        if !self.failures.is_empty() {
+            eprintln!(
+                "Failed on {} device(s): {}",
+                self.failures.len(),
+                self.failures.join(", ")
+            );
+        }
+    }
+}
+
+/// Run `command` once on every device (sequentially, in parallel, or as a diff)
+///
+/// With a single device its error is returned; with several, failures are
+/// reported per device and collected in the summary.
+///
+/// `ota flash` in JSON prints one array with an entry per device, including
+/// failed ones (which are then in the summary even with a single device).
+fn run_once(
+    command: &Commands,
+    devices: &mut [device::DeviceConnection],
+    opts: &RunOptions,
+    interrupted: &AtomicBool,
+) -> anyhow::Result<RunSummary> {
+    let multi = devices.len() > 1;
+    let mut failures: Vec<String> = Vec::new();
+    // Set by the first failing device under --fail-fast
+    let mut aborted_by: Option<String> = None;
+    let mut skipped: Vec<String> = Vec::new();
+    let mut exit_code = 0;
+    let report = (opts.format == Format::Json && reports_as_array(command))
+        .then(output::JsonReport::default);
+    let printer_for = |name: &str, multi: bool| -> Box<dyn Printer> {
+        match &report {
+            Some(report) => Box::new(output::ReportPrinter::new(name, report.clone())),
+            None => opts.format.printer(multi.then_some(name)),
+        }
+    };
+
+    if opts.diff && multi {
+        // Collect fields from every device, then print a single comparison table
+        let mut rows: Vec<output::DiffRow> = Vec::new();
+        let no_abort = AtomicBool::new(false);
+        for (i, dev) in devices.iter_mut().enumerate() {
+            let prefix = device::device_prefix(&dev.name);
+            progress::set_label(&prefix);
+            let result = with_retries(opts.retries, &no_abort, || {
+                let fields = diff_fields(command, dev.transport.as_mut())?;
+                rows.push((dev.name.clone(), fields));
+                Ok(())
+            });
+            if let Err(e) = result {
+                opts.format.printer(Some(&dev.name)).error(&e);
+                failures.push(dev.name.clone());
+                exit_code = exit::most_severe(exit_code, exit::code_for(&e));
+                if opts.fail_fast {
+                    aborted_by = Some(dev.name.clone());
+                    skipped = devices[i + 1..].iter().map(|d| d.name.clone()).collect();
+                    break;
+                }
+            }
+        }
+        progress::set_label("");
+        if !rows.is_empty() {
+            let value = rows
+                .iter()
+                .map(|(name, fields)| {
+                    let fields = fields.iter().map(|(f, v)| (f.clone(), json!(v))).collect();
+                    (name.clone(), Value::Object(fields))
+                })
+                .collect();
+            opts.format.printer(None).result(Value::Object(value), &mut || {
+                for line in output::format_diff_table(&rows).lines() {
+                    outln!("{}", line);
+                }
+            });
+        }
+    } else if opts.parallel && multi {
+        // Run devices concurrently; print each device's buffered output in name order
+        progress::set_interleaved(true);
+        let flashing = reports_as_array(command);
+        let draw_bars = flashing && bars::available();
+        if draw_bars {
+            let names: Vec<String> = devices.iter().map(|d| device_label(&d.name)).collect();
+            bars::start(&names);
+        }
+        let max_threads = opts.parallel_max.map_or(devices.len(), |n| n as usize);
+        let run = run_parallel(command, devices, max_threads, opts, report.as_ref(), interrupted);
+        if draw_bars {
+            bars::finish();
+        }
+        aborted_by = run.aborted_by;
+        skipped = run.skipped;
+        let mut outcomes = run.outcomes;
+        outcomes.sort_by(|a, b| a.0.cmp(&b.0));
+        let table = opts.format == Format::Table;
+        let mut summary = Vec::new();
+        for (name, result, lines) in outcomes {
+            if table {
+                outln!("--- {} ---", name);
+            }
+            for line in lines {
+                outln!("{}", line);
+            }
+            summary.push((device_label(&name), result.as_ref().err().map(|e| e.to_string())));
+            if let Err(e) = result {
+                printer_for(&name, true).error(&e);
+                failures.push(name);
+                exit_code = exit::most_severe(exit_code, exit::code_for(&e));
+            }
+            if table {
+                outln!(); // Blank line between devices
+            }
+        }
+        if flashing && table {
+            for line in bars::format_summary(&summary).lines() {
+                outln!("{}", line);
+            }
+        }
+        if let Some(report) = &report {
+            // Workers added their entries as they finished
+            report.lock().unwrap().sort_by(|a, b| a["device"].as_str().cmp(&b["device"].as_str()));
+        }
+    } else {
+        // Execute command on each device sequentially
+        let names: Vec<String> = devices.iter().map(|d| d.name.clone()).collect();
+        let no_abort = AtomicBool::new(false);
+        for (i, dev) in devices.iter_mut().enumerate() {
+            let prefix = if multi {
+                device::device_prefix(&dev.name)
+            } else {
+                String::new()
+            };
+            let dev_label = device_label(&dev.name);
+            let printer = printer_for(&dev_label, multi);
+
+            if multi {
+                if printer.is_table() {
+                    outln!("--- {} ---", dev_label);
+                }
+                progress::set_label(&prefix);
+            }
+
+            let result = with_retries(opts.retries, &no_abort, || {
+                execute(
+                    command,
+                    dev.transport.as_mut(),
+                    &dev.name,
+                    &prefix,
+                    multi,
+                    printer.as_ref(),
+                    interrupted,
+                )
+            });
+
+            if opts.verbose {
+                print_transport_summary(&prefix, dev.transport.as_ref());
+            }
+
+            if let Err(e) = result {
+                if multi || report.is_some() {
+                    printer.error(&e);
+                    failures.push(dev_label.clone());
+                    exit_code = exit::most_severe(exit_code, exit::code_for(&e));
+                    if opts.fail_fast {
+                        aborted_by = Some(dev_label);
+                        skipped = names[i + 1..].to_vec();
+                        break;
+                    }
+                } else {
+                    return Err(e);
+                }
+            }
+
+            if multi && printer.is_table() {
+                outln!(); // Blank line between devices
+            }
+        }
+    }
+
+    if let Some(report) = &report {
+        output::write_report(report);
+    }
+    if opts.format == Format::Prometheus {
+        output::write_metrics();
+    }
+
+    Ok(RunSummary {
+        failures,
+        aborted_by,
+        skipped,
+        exit_code,
+    })
+}
+
+/// Re-run a read command every `interval`, redrawing the screen, until q or Ctrl-C
+///
+/// On a terminal with several devices each device gets its own panel;
+/// otherwise the output is what a single run would print.
+fn watch(
+    command: &Commands,
+    devices: &mut [device::DeviceConnection],
+    opts: &RunOptions,
+    interval: Duration,
+    interrupted: &AtomicBool,
+) -> anyhow::Result<()> {
+    // JSON output is a stream of documents: no screen clearing or header
+    let table = opts.format == Format::Table;
+    let redraw = table && std::io::stdout().is_terminal();
+    let invocation = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let panels = redraw && devices.len() > 1 && !opts.diff;
+
+    let render: watch::Render = if panels {
+        Box::new(move || {
+            let max_threads = match (opts.parallel, opts.parallel_max) {
+                (true, Some(n)) => n as usize,
+                (true, None) => devices.len(),
+                (false, _) => 1,
+            };
+            let run = run_parallel(command, devices, max_threads, opts, None, interrupted);
+            let mut outcomes = run.outcomes;
+            outcomes.sort_by(|a, b| a.0.cmp(&b.0));
+            let panels: Vec<(String, Vec<String>)> = outcomes
+                .into_iter()
+                .map(|(name, result, mut lines)| {
+                    if let Err(e) = result {
+                        lines.push(format!("Error: {:#}", e));
+                    }
+                    (device_label(&name), lines)
+                })
+                .collect();
+            let width = crossterm::terminal::size().map_or(80, |(cols, _)| cols as usize);
+            Ok(watch::layout_panels(&panels, width))
+        })
+    } else {
+        Box::new(move || {
+            let buffer = output::OutputBuffer::default();
+            output::capture_into(buffer.clone());
+            let mut failed = None;
+            match run_once(command, devices, opts, interrupted) {
+                Ok(summary) => summary.report(),
+                // A table error is shown by the runner in place of the output
+                Err(e) if table => failed = Some(e),
+                Err(e) => opts.format.printer(None).error(&e),
+            }
+            output::end_capture();
+            if let Some(e) = failed {
+                return Err(e);
+            }
+            let lines = std::mem::take(&mut *buffer.lock().unwrap());
+            Ok(lines.iter().map(|line| format!("{}\n", line)).collect())
+        })
+    };
+
+    let quit_hint = if redraw { "q to quit" } else { "Ctrl-C to stop" };
+    watch::WatchRunner {
+        title: table.then(|| {
+            format!(
+                "Every {} ms: domes-cli {}  ({})",
+                interval.as_millis(),
+                invocation,
+                quit_hint
+            )
+        }),
+        interval,
+        redraw,
+        render,
+    }
+    .run(interrupted)
+}
+
+/// Ping each device once and print whether it answered (`devices health`)
+///
+/// Returns false if any device did not respond.
+fn check_health(
+    devices: &mut [device::DeviceConnection],
+    config: KeepaliveConfig,
+    printer: &dyn Printer,
+) -> bool {
+    let timeout_ms = config.timeout.as_millis() as u64;
+
+    // (device, round-trip ms or the ping error)
+    let results: Vec<(String, Result<f64, String>)> = devices
+        .iter_mut()
+        .map(|dev| {
+            let started = Instant::now();
+            let result = keepalive::ping(dev.transport.as_mut(), timeout_ms)
+                .map(|()| started.elapsed().as_secs_f64() * 1000.0)
+                .map_err(|e| format!("{:#}", e));
+            (device_label(&dev.name), result)
+        })
+        .collect();
+
+    let value = results
+        .iter()
+        .map(|(name, result)| match result {
+            Ok(ms) => json!({ "device": name, "alive": true, "latency_ms": ms }),
+            Err(e) => json!({ "device": name, "alive": false, "error": e }),
+        })
+        .collect();
+    printer.result(Value::Array(value), &mut || {
+        outln!("{:<20} {:<8} DETAIL", "DEVICE", "STATUS");
+        outln!("{:-<20} {:-<8} {:-<30}", "", "", "");
+        for (name, result) in &results {
+            match result {
+                Ok(ms) => outln!("{:<20} {:<8} {:.1} ms", name, "alive", ms),
+                Err(e) => outln!("{:<20} {:<8} {}", name, "stale", e),
+            }
+        }
+    });
+    results.iter().all(|(_, result)| result.is_ok())
+}
+
+/// Keep every device pinged in the background and print each connection
+/// that goes stale or recovers, until Ctrl-C (`devices health --watch`)
+fn monitor_health(
+    devices: Vec<device::DeviceConnection>,
+    config: KeepaliveConfig,
+    verbose: bool,
+    format: Format,
+    interrupted: &AtomicBool,
+) -> anyhow::Result<()> {
+    let started = Instant::now();
+    let printer = format.printer(None);
+    let monitored: Vec<(String, KeepaliveTransport<Box<dyn Transport>>)> = devices
+        .into_iter()
+        .map(|dev| (device_label(&dev.name), KeepaliveTransport::new(dev.transport, config)))
+        .collect();
+
+    progress!(
+        "Pinging {} device(s) every {} ms (timeout {} ms, Ctrl-C to stop)",
+        monitored.len(),
+        config.interval.as_millis(),
+        config.timeout.as_millis()
+    );
+
+    let mut last: Vec<Option<bool>> = vec![None; monitored.len()];
+    while !interrupted.load(Ordering::Relaxed) {
+        for ((name, transport), last) in monitored.iter().zip(last.iter_mut()) {
+            let alive = transport.is_alive();
+            if *last == Some(alive) {
+                continue;
+            }
+            let elapsed = started.elapsed().as_secs_f64();
+            let value = json!({ "elapsed_s": elapsed, "device": name, "alive": alive });
+            printer.result(value, &mut || {
+                outln!(
+                    "[{:>9.3}s] {}: {}",
+                    elapsed,
+                    name,
+                    if alive { "alive" } else { "stale" }
+                )
+            });
+            if !alive && verbose {
+                if let Some(reason) = transport.last_error() {
+                    eprintln!("[{:>9.3}s] {}: {}", elapsed, name, reason);
+                }
+            }
+            *last = Some(alive);
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    Ok(())
+}
+
+/// `system mode --watch`: poll each device's mode every `interval` and print
+/// one timestamped line per transition, until Ctrl-C
+///
+/// The first line per device is its current mode. A mode that was left and
+/// re-entered between two polls is caught by its time in mode going down.
+/// A device that stops answering is reported once and picked up again when
+/// it recovers.
+fn watch_mode_transitions(
+    devices: &mut [device::DeviceConnection],
+    interval: Duration,
+    format: Format,
+    interrupted: &AtomicBool,
+) -> anyhow::Result<()> {
+    let printer = format.printer(None);
+    progress!(
+        "Polling the mode of {} device(s) every {} ms (Ctrl-C to stop)",
+        devices.len(),
+        interval.as_millis()
+    );
+
+    let mut last: Vec<Option<protocol::CliModeInfo>> = vec![None; devices.len()];
+    let mut failing = vec![false; devices.len()];
+    while !interrupted.load(Ordering::Relaxed) {
+        let deadline = Instant::now() + interval;
+        for (i, dev) in devices.iter_mut().enumerate() {
+            let name = device_label(&dev.name);
+            let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string();
+            let info = match commands::system_get_mode(dev.transport.as_mut()) {
+                Ok(info) => info,
+                Err(e) => {
+                    if !failing[i] {
+                        eprintln!("{} {}: {:#}", timestamp, name, e);
+                    }
+                    (failing[i], last[i]) = (true, None);
+                    continue;
+                }
+            };
+            failing[i] = false;
+            // None for the first answer, which prints the current mode
+            let entered = match last[i].as_ref().map(|last| mode_entered_from(last, &info)) {
+                Some(None) => {
+                    // Same mode, still counting up
+                    last[i] = Some(info);
+                    continue;
+                }
+                entered => entered.flatten(),
+            };
+            let value = json!({
+                "timestamp": timestamp,
+                "device": name,
+                "mode": info.mode.to_string(),
+                "transition": entered.is_some(),
+                "from": entered.flatten().map(|mode| mode.to_string()),
+                "time_in_mode_ms": info.time_in_mode_ms,
+            });
+            printer.result(value, &mut || match entered {
+                Some(from) => outln!(
+                    "{} {}: {} -> {}",
+                    timestamp,
+                    name,
+                    from.map_or("?".to_string(), |mode| mode.to_string()),
+                    info.mode
+                ),
+                None => outln!(
+                    "{} {}: {} (for {} ms)",
+                    timestamp,
+                    name,
+                    info.mode,
+                    info.time_in_mode_ms
+                ),
+            });
+            last[i] = Some(info);
+        }
+        while Instant::now() < deadline && !interrupted.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+    Ok(())
+}
+
+/// Event rate samples shown in the `trace status --watch` sparkline
+const TRACE_RATE_SAMPLES: usize = 40;
+
+/// `trace status --watch`: poll each device's trace counters every
+/// `interval` and show events and drops per second since the last poll,
+/// with a sparkline of recent event rates, until q or Ctrl-C
+///
+/// With `--format json` each poll is one document per device, with null
+/// rates for the first.
+fn watch_trace_rates(
+    devices: &mut [device::DeviceConnection],
+    interval: Duration,
+    format: Format,
+    interrupted: &AtomicBool,
+) -> anyhow::Result<()> {
+    let table = format == Format::Table;
+    let redraw = table && std::io::stdout().is_terminal();
+    let panels = redraw && devices.len() > 1;
+    let multi = devices.len() > 1;
+    let invocation = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+
+    // Per device: the last answer and when it came, and recent event rates
+    let mut last: Vec<Option<(commands::trace::TraceStatusInfo, Instant)>> =
+        devices.iter().map(|_| None).collect();
+    let mut history: Vec<VecDeque<f64>> = vec![VecDeque::new(); devices.len()];
+
+    let render: watch::Render = Box::new(move || {
+        let mut out = String::new();
+        let mut boxed = Vec::new();
+        for (i, dev) in devices.iter_mut().enumerate() {
+            let name = device_label(&dev.name);
+            let lines = match commands::trace_status(dev.transport.as_mut()) {
+                Ok(status) => {
+                    let now = Instant::now();
+                    let rates = last[i]
+                        .as_ref()
+                        .map(|(prev, at)| commands::TraceRates::between(prev, &status, now - *at));
+                    if let Some(rates) = rates {
+                        if history[i].len() == TRACE_RATE_SAMPLES {
+                            history[i].pop_front();
+                        }
+                        history[i].push_back(rates.events_per_sec);
+                    }
+                    let samples: Vec<f64> = history[i].iter().copied().collect();
+                    let lines = if table {
+                        format_trace_rates(&status, rates, &samples)
+                    } else {
+                        let value = json!({
+                            "device": name,
+                            "event_count": status.event_count,
+                            "dropped_count": status.dropped_count,
+                            "buffer_size": status.buffer_size,
+                            "events_per_sec": rates.map(|r| r.events_per_sec),
+                            "drops_per_sec": rates.map(|r| r.drops_per_sec),
+                        });
+                        vec![value.to_string()]
+                    };
+                    last[i] = Some((status, now));
+                    lines
+                }
+                Err(e) => {
+                    // Rates resume from the next answer
+                    last[i] = None;
+                    let error = format!("{:#}", e);
+                    if table {
+                        vec![format!("Error: {}", error)]
+                    } else {
+                        vec![json!({ "device": name, "error": error }).to_string()]
+                    }
+                }
+            };
+            if panels {
+                boxed.push((name, lines));
+                continue;
+            }
+            if table && multi {
+                out.push_str(&format!("{}:\n", name));
+            }
+            for line in lines {
+                let indent = if table { "  " } else { "" };
+                out.push_str(&format!("{}{}\n", indent, line));
+            }
+        }
+        if panels {
+            let width = crossterm::terminal::size().map_or(80, |(cols, _)| cols as usize);
+            out = watch::layout_panels(&boxed, width);
+        }
+        Ok(out)
+    });
+
+    let quit_hint = if redraw { "q to quit" } else { "Ctrl-C to stop" };
+    watch::WatchRunner {
+        title: table.then(|| {
+            format!(
+                "Every {} ms: domes-cli {}  ({})",
+                interval.as_millis(),
+                invocation,
+                quit_hint
+            )
+        }),
+        interval,
+        redraw,
+        render,
+    }
+    .run(interrupted)
+}
+
+/// The `trace status --watch` lines for one device; `rates` is None for the
+/// first poll
+fn format_trace_rates(
+    status: &commands::trace::TraceStatusInfo,
+    rates: Option<commands::TraceRates>,
+    samples: &[f64],
+) -> Vec<String> {
+    let rate = |per_sec: Option<f64>| per_sec.map_or("-".to_string(), |r| format!("{:.1}", r));
+    vec![
+        format!(
+            "Events:  {:>10}  {:>9}/s  {}",
+            status.event_count,
+            rate(rates.map(|r| r.events_per_sec)),
+            watch::sparkline(samples)
+        ),
+        format!(
+            "Dropped: {:>10}  {:>9}/s",
+            status.dropped_count,
+            rate(rates.map(|r| r.drops_per_sec))
+        ),
+        format!(
+            "Buffer:  {:>10}  {}",
+            output::format_bytes(status.buffer_size as u64),
+            if status.enabled { "recording" } else { "stopped" }
+        ),
+    ]
+}
+
+/// Whether the device changed mode between two polls, and from which mode
+///
+/// `Some(None)` is a transition whose origin is unknown: the mode was left and
+/// re-entered, and the firmware does not report the previous mode.
+fn mode_entered_from(
+    last: &protocol::CliModeInfo,
+    info: &protocol::CliModeInfo,
+) -> Option<Option<SystemMode>> {
+    if info.mode != last.mode {
+        Some(Some(info.previous_mode.unwrap_or(last.mode)))
+    } else if info.time_in_mode_ms < last.time_in_mode_ms {
+        Some(info.previous_mode)
+    } else {
+        None
+    }
+}
 
-        if multi {
-            println!("--- {} ---", dev_label);
+/// Scan BLE for 10 s with the --min-rssi and --mac-filter filters
+fn scan_ble(cli: &Cli) -> anyhow::Result<Vec<BleDeviceInfo>> {
+    let timeout = Duration::from_secs(10);
+    BleTransport::scan_filtered(timeout, cli.min_rssi, cli.mac_filter.as_ref())
+}
+
+/// Open each serial port at `baud` and ask for its system info, all at once;
+/// `None` for a port that could not be opened or did not answer
+fn probe_serial_ports(
+    ports: &[String],
+    baud: u32,
+) -> Vec<(String, Option<protocol::CliSystemInfo>)> {
+    std::thread::scope(|s| {
+        let handles: Vec<_> = ports
+            .iter()
+            .map(|port| {
+                s.spawn(move || {
+                    SerialTransport::open(port, baud)
+                        .ok()
+                        .and_then(|mut t| commands::system_info(&mut t).ok())
+                })
+            })
+            .collect();
+        ports
+            .iter()
+            .zip(handles)
+            .map(|(port, handle)| (port.clone(), handle.join().ok().flatten()))
+            .collect()
+    })
+}
+
+/// The one (transport, address) that reported `pod_id` (`--pod-id`)
+///
+/// No match is a connection error naming what was `searched`; several are a
+/// usage error, since the pods would need telling apart by address.
+fn single_pod_match<'a>(
+    pod_id: u32,
+    mut matches: Vec<(&'a str, String)>,
+    searched: &str,
+) -> anyhow::Result<(&'a str, String)> {
+    match matches.len() {
+        0 => Err(domes_core::error::DomesError::Transport {
+            context: format!("No pod with ID {} found", pod_id),
+            source: anyhow::anyhow!("searched {}", searched).into(),
+        }
+        .into()),
+        1 => Ok(matches.remove(0)),
+        _ => {
+            let found: Vec<String> = matches.iter().map(|(_, address)| address.clone()).collect();
+            Err(exit::usage(format!(
+                "Pod ID {} is reported by {} devices ({}); target one with --port or --ble",
+                pod_id,
+                found.len(),
+                found.join(", ")
+            )))
+        }
+    }
+}
+
+/// How long --wifi-discover and devices scan browse for pods via mDNS
+const WIFI_DISCOVER_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Browse for WiFi pods via mDNS for `WIFI_DISCOVER_TIMEOUT`
+fn discover_wifi() -> anyhow::Result<Vec<WifiDeviceInfo>> {
+    mdns::discover(WIFI_DISCOVER_TIMEOUT)
+}
+
+/// Firmware version from system info; `None` from older firmware that only
+/// reported its mode
+fn firmware_version(info: &protocol::CliSystemInfo) -> Option<&str> {
+    (!info.partial).then_some(info.firmware_version.as_str())
+}
+
+/// `value` for a system info counter, or `unknown` if the device did not report it
+fn known(info: &protocol::CliSystemInfo, value: String) -> String {
+    if info.partial {
+        "unknown".to_string()
+    } else {
+        value
+    }
+}
+
+/// `-62 dBm`, or `-` if the adapter reported no RSSI
+fn format_rssi(rssi: Option<i16>) -> String {
+    rssi.map_or_else(|| "-".to_string(), |r| format!("{} dBm", r))
+}
+
+/// Ten-block signal bar: empty at -100 dBm or weaker, full at -40 dBm or stronger
+fn signal_bar(rssi: i16) -> String {
+    let filled = ((rssi.clamp(-100, -40) + 100) as usize).div_ceil(6);
+    format!("{}{}", "█".repeat(filled), "░".repeat(10 - filled))
+}
+
+/// Display name for a device (direct connections have no registry name)
+fn device_label(name: &str) -> String {
+    if name.is_empty() {
+        "device".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Whether `command` sends payloads near the frame limit (OTA data, batches)
+fn sends_large_payloads(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::Ota { action: OtaAction::Flash { .. } } | Commands::Batch { .. }
+    )
+}
+
+/// Make payloads above the device's frame limit fail before they are sent
+///
+/// The limit is `max_payload` if given, else what the device reports in its
+/// system info; a device that reports none keeps the CLI's built-in limit.
+fn limit_payload(
+    mut dev: device::DeviceConnection,
+    max_payload: Option<u32>,
+) -> device::DeviceConnection {
+    let limit = max_payload.or_else(|| {
+        commands::system_info(dev.transport.as_mut())
+            .ok()
+            .and_then(|info| info.max_payload)
+    });
+    match limit.map(|limit| limit as usize) {
+        Some(limit) if limit < MAX_PAYLOAD_SIZE => {
+            if max_payload.is_none() {
+                progress!(
+                    "{}Device accepts payloads up to {} bytes",
+                    device::device_prefix(&dev.name),
+                    limit
+                );
+            }
+            dev.transport = Box::new(PayloadLimitTransport::new(dev.transport, limit));
+            dev
+        }
+        _ => dev,
+    }
+}
+
+/// Whether `command` only reads device state, so it is safe to repeat with `--watch`
+fn is_read_only(command: &Commands) -> bool {
+    if let Commands::Batch { commands } = command {
+        return commands.iter().all(is_read_only);
+    }
+    matches!(
+        command,
+        Commands::Feature {
+            action: FeatureAction::List { .. } | FeatureAction::CheckProfile { .. }
+        } | Commands::Wifi { action: WifiAction::Status | WifiAction::Ap { .. } }
+            | Commands::Led { action: LedAction::Get { .. } }
+            | Commands::Trace { action: TraceAction::Status }
+            | Commands::System {
+                action: SystemAction::Mode
+                    | SystemAction::Info
+                    | SystemAction::Health
+                    | SystemAction::Tasks { .. }
+                    | SystemAction::Partitions
+                    | SystemAction::Ping { .. }
+                    | SystemAction::Memory { .. }
+            }
+            | Commands::Espnow { action: EspnowAction::Status }
+            | Commands::Devices { action: DevicesAction::Health }
+    )
+}
+
+/// Parse one `--batch` argument (e.g. "led solid --color ff0000") as a command
+fn parse_batch_command(line: &str) -> anyhow::Result<Commands> {
+    let words = shlex::split(line)
+        .ok_or_else(|| exit::usage(format!("Invalid quoting in batch command: {}", line)))?;
+    let parsed = Cli::try_parse_from(std::iter::once("domes-cli".to_string()).chain(words))
+        .map_err(|e| exit::usage(format!("Invalid batch command '{}': {}", line, e)))?;
+    let command = parsed
+        .command
+        .ok_or_else(|| exit::usage(format!("Batch command '{}' has no subcommand", line)))?;
+
+    // Reject commands that cannot be batched before connecting to anything
+    batch_request(&command).with_context(|| format!("Cannot batch '{}'", line))?;
+    Ok(command)
+}
+
+/// The single request `command` sends, as a (msg_type, payload) pair for a batch
+///
+/// Only commands that complete in one request/response round trip can be
+/// batched.
+fn batch_request(command: &Commands) -> anyhow::Result<(u8, Vec<u8>)> {
+    use protocol::ConfigMsgType;
+
+    let request = match command {
+        Commands::Feature { action } => match action {
+            FeatureAction::List { .. }
+            | FeatureAction::Check { .. }
+            | FeatureAction::SaveProfile { .. }
+            | FeatureAction::CheckProfile { .. } => (ConfigMsgType::ListFeaturesReq, Vec::new()),
+            FeatureAction::Enable { feature } => (
+                ConfigMsgType::SetFeatureReq,
+                protocol::serialize_set_feature(parse_feature(feature)?, true),
+            ),
+            FeatureAction::Disable { feature } => (
+                ConfigMsgType::SetFeatureReq,
+                protocol::serialize_set_feature(parse_feature(feature)?, false),
+            ),
+        },
+        Commands::Wifi { action } => match action {
+            WifiAction::Enable => (
+                ConfigMsgType::SetFeatureReq,
+                protocol::serialize_set_feature(Feature::Wifi, true),
+            ),
+            WifiAction::Disable => (
+                ConfigMsgType::SetFeatureReq,
+                protocol::serialize_set_feature(Feature::Wifi, false),
+            ),
+            WifiAction::Status => (ConfigMsgType::ListFeaturesReq, Vec::new()),
+            WifiAction::ConfigureAp { ssid, password, channel, max_connections } => {
+                commands::validate_ap_config(ssid, password, *channel, *max_connections)?;
+                (
+                    ConfigMsgType::WifiApConfigReq,
+                    protocol::serialize_wifi_ap_config(ssid, password, *channel, *max_connections),
+                )
+            }
+            WifiAction::Ap { action: WifiApAction::Status } => {
+                (ConfigMsgType::WifiApStatusReq, Vec::new())
+            }
+            WifiAction::Static { ip, netmask, gateway, dns, dns2 } => {
+                commands::validate_static_ip(*ip, *netmask, *gateway)?;
+                let config = protocol::WifiStaticIp {
+                    ip: *ip,
+                    netmask: *netmask,
+                    gateway: *gateway,
+                    dns1: *dns,
+                    dns2: *dns2,
+                };
+                (ConfigMsgType::WifiStaticIpReq, protocol::serialize_wifi_static_ip(Some(&config)))
+            }
+            WifiAction::Dhcp => {
+                (ConfigMsgType::WifiStaticIpReq, protocol::serialize_wifi_static_ip(None))
+            }
+        },
+        Commands::Led { action: LedAction::Get { .. } } => {
+            (ConfigMsgType::GetLedPatternReq, Vec::new())
+        }
+        Commands::Led { action } => match requested_led_pattern(action)? {
+            Some(pattern) => (
+                ConfigMsgType::SetLedPatternReq,
+                protocol::serialize_set_led_pattern(&pattern),
+            ),
+            None if matches!(action, LedAction::Export { .. }) => {
+                return Err(exit::usage("led export writes a file; run it on its own"))
+            }
+            None => {
+                return Err(exit::usage("led brightness and led test need more than one request"))
+            }
+        },
+        Commands::System { action } => match action {
+            SystemAction::Mode => (ConfigMsgType::GetModeReq, Vec::new()),
+            SystemAction::SetMode { mode, force } => (
+                ConfigMsgType::SetModeReq,
+                protocol::serialize_set_mode(parse_mode(mode)?, *force),
+            ),
+            SystemAction::Info => (ConfigMsgType::GetSystemInfoReq, Vec::new()),
+            SystemAction::SetPodId { id } => {
+                commands::validate_pod_id(*id)?;
+                (ConfigMsgType::SetPodIdReq, protocol::serialize_set_pod_id(*id))
+            }
+            SystemAction::SetName { name } => {
+                commands::validate_device_name(name)?;
+                (
+                    ConfigMsgType::SetDeviceNameReq,
+                    protocol::serialize_set_device_name(name),
+                )
+            }
+            _ => {
+                return Err(exit::usage(
+                    "only system mode, set-mode, info, set-pod-id and set-name can be batched",
+                ))
+            }
+        },
+        _ => {
+            return Err(exit::usage("only feature, wifi, led and system commands can be batched"))
+        }
+    };
+    Ok((request.0 as u8, request.1))
+}
+
+/// Per-run execution options shared by sequential and parallel modes
+struct RunOptions {
+    verbose: bool,
+    retries: u32,
+    fail_fast: bool,
+    parallel: bool,
+    parallel_max: Option<u32>,
+    diff: bool,
+    format: Format,
+}
+
+/// Run `attempt`, retrying up to `retries` more times while `abort` is unset
+fn with_retries(
+    retries: u32,
+    abort: &AtomicBool,
+    mut attempt: impl FnMut() -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut tries = 0;
+    loop {
+        match attempt() {
+            Err(e) if tries < retries && !abort.load(Ordering::Relaxed) => {
+                tries += 1;
+                progress!("Attempt failed: {:#}; retrying ({}/{})", e, tries, retries);
+            }
+            result => return result,
         }
+    }
+}
+
+/// Print `--verbose` round-trip latency and traffic totals for one device
+fn print_transport_summary(prefix: &str, transport: &dyn Transport) {
+    if let Some(latency) = transport.latency_stats() {
+        eprintln!("{}{}", prefix, latency);
+    }
+    eprintln!("{}{}", prefix, transport.stats());
+}
+
+/// Outcome of running a command on one device: (name, result, captured output)
+type DeviceOutcome = (String, anyhow::Result<()>, Vec<String>);
+
+/// Result of a `--parallel` run
+struct ParallelRun {
+    outcomes: Vec<DeviceOutcome>,
+    /// Device whose failure triggered a `--fail-fast` abort
+    aborted_by: Option<String>,
+    /// Devices never started because of the abort
+    skipped: Vec<String>,
+}
+
+/// Run `command` on every device using at most `max_threads` worker threads
+///
+/// Results go into `report` instead of each device's output when it is set.
+fn run_parallel(
+    command: &Commands,
+    devices: &mut [device::DeviceConnection],
+    max_threads: usize,
+    opts: &RunOptions,
+    report: Option<&output::JsonReport>,
+    interrupted: &AtomicBool,
+) -> ParallelRun {
+    let queue = Mutex::new(devices.iter_mut());
+    let outcomes = Mutex::new(Vec::new());
+    let abort = AtomicBool::new(false);
+    let aborted_by = Mutex::new(None);
 
-        let result: anyhow::Result<()> = (|| {
-        match &command {
-            Commands::Feature { action } => match action {
-                FeatureAction::List => {
-                    let features = commands::feature_list(transport)?;
-                    println!("{}Features:", prefix);
-                    println!("{}{:<16} {}", prefix, "NAME", "STATUS");
-                    println!("{}{:-<16} {:-<8}", prefix, "", "");
-                    for state in features {
+    std::thread::scope(|s| {
+        for _ in 0..max_threads {
+            s.spawn(|| loop {
+                // Workers check the abort flag before picking up each device
+                if abort.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Some(dev) = queue.lock().unwrap().next() else {
+                    break;
+                };
+                let prefix = device::device_prefix(&dev.name);
+                let printer: Box<dyn Printer> = match report {
+                    Some(report) => Box::new(output::ReportPrinter::new(&dev.name, report.clone())),
+                    None => opts.format.printer(Some(&dev.name)),
+                };
+                let buffer = output::OutputBuffer::default();
+                output::capture_into(buffer.clone());
+                progress::set_label(&prefix);
+
+                let result = with_retries(opts.retries, &abort, || {
+                    // Only keep output from the final attempt
+                    buffer.lock().unwrap().clear();
+                    execute(
+                        command,
+                        dev.transport.as_mut(),
+                        &dev.name,
+                        &prefix,
+                        true,
+                        printer.as_ref(),
+                        interrupted,
+                    )
+                });
+                if opts.verbose {
+                    print_transport_summary(&prefix, dev.transport.as_ref());
+                }
+                if result.is_err() && opts.fail_fast && !abort.swap(true, Ordering::Relaxed) {
+                    *aborted_by.lock().unwrap() = Some(dev.name.clone());
+                }
+
+                output::end_capture();
+                let lines = std::mem::take(&mut *buffer.lock().unwrap());
+                outcomes.lock().unwrap().push((dev.name.clone(), result, lines));
+            });
+        }
+    });
+
+    ParallelRun {
+        outcomes: outcomes.into_inner().unwrap(),
+        aborted_by: aborted_by.into_inner().unwrap(),
+        skipped: queue.into_inner().unwrap().map(|d| d.name.clone()).collect(),
+    }
+}
+
+/// Whether `command`'s JSON results are printed as one array across devices
+fn reports_as_array(command: &Commands) -> bool {
+    matches!(command, Commands::Ota { action: OtaAction::Flash { .. } })
+}
+
+/// Whether `command` can be shown as a `--diff` comparison table
+fn supports_diff(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::System { action: SystemAction::Info }
+            | Commands::Feature { action: FeatureAction::List { .. } }
+            | Commands::Led { action: LedAction::Get { .. } }
+    )
+}
+
+/// Query one device for the (field, value) pairs compared by `--diff`
+fn diff_fields(
+    command: &Commands,
+    transport: &mut dyn Transport,
+) -> anyhow::Result<Vec<(String, String)>> {
+    let field = |name: &str, value: String| (name.to_string(), value);
+    let fields = match command {
+        Commands::System { action: SystemAction::Info } => {
+            let info = commands::system_info(transport)?;
+            vec![
+                field("Firmware", firmware_version(&info).unwrap_or("unknown").to_string()),
+                field(
+                    "Pod ID",
+                    if info.pod_id == 0 {
+                        "not set".to_string()
+                    } else {
+                        info.pod_id.to_string()
+                    },
+                ),
+                field("Mode", info.mode.to_string()),
+                field("Uptime", known(&info, format!("{} s", info.uptime_s))),
+                field("Free heap", known(&info, output::format_bytes(info.free_heap as u64))),
+                field("Boot count", known(&info, info.boot_count.to_string())),
+                field("Features", format!("0x{:08X}", info.feature_mask)),
+            ]
+        }
+        Commands::Feature { action: FeatureAction::List { .. } } => commands::feature_list(transport)?
+            .into_iter()
+            .map(|state| {
+                let status = if state.enabled { "enabled" } else { "disabled" };
+                field(state.feature.cli_name(), status.to_string())
+            })
+            .collect(),
+        Commands::Led { action: LedAction::Get { .. } } => {
+            let pattern = commands::led_get(transport)?;
+            let color = if let Some((r, g, b, _)) = pattern.color {
+                format!("#{:02x}{:02x}{:02x}", r, g, b)
+            } else if !pattern.colors.is_empty() {
+                format!("{} colors", pattern.colors.len())
+            } else {
+                "-".to_string()
+            };
+            vec![
+                field("Type", led_type_name(&pattern).to_string()),
+                field("Color", color),
+                field("Period", format!("{} ms", pattern.period_ms)),
+                field("Brightness", pattern.brightness.to_string()),
+            ]
+        }
+        _ => anyhow::bail!("--diff is not supported for this command"),
+    };
+    Ok(fields)
+}
+
+/// Run one command against one device
+fn execute(
+    command: &Commands,
+    transport: &mut dyn Transport,
+    dev_name: &str,
+    prefix: &str,
+    multi: bool,
+    printer: &dyn Printer,
+    interrupted: &AtomicBool,
+) -> anyhow::Result<()> {
+    match command {
+        Commands::Batch { commands } => {
+            let requests = commands
+                .iter()
+                .map(batch_request)
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let responses = domes_core::transport::send_batch(transport, requests)?;
+
+            // Parse and print each response as if its command had run alone
+            let mut replay = domes_core::transport::ReplayTransport::new(responses);
+            for command in commands {
+                execute(command, &mut replay, dev_name, prefix, multi, printer, interrupted)?;
+            }
+        }
+
+        Commands::Feature { action } => match action {
+            FeatureAction::List { sort, filter } => {
+                let features = commands::feature_list(transport)?;
+                let features = commands::sort_and_filter_features(features, *sort, *filter);
+                let value = features.iter().map(feature_json).collect();
+                printer.result(Value::Array(value), &mut || {
+                    outln!("{}Features:", prefix);
+                    outln!("{}{:<16} STATUS", prefix, "NAME");
+                    outln!("{}{:-<16} {:-<8}", prefix, "", "");
+                    for state in &features {
                         let status = if state.enabled { "enabled" } else { "disabled" };
-                        println!("{}{:<16} {}", prefix, state.feature.cli_name(), status);
+                        outln!("{}{:<16} {}", prefix, state.feature.cli_name(), status);
                     }
+                });
+            }
+            FeatureAction::Check { feature } => {
+                let feature = parse_feature(feature)?;
+                let state = commands::feature_list(transport)?
+                    .into_iter()
+                    .find(|state| state.feature == feature)
+                    .with_context(|| {
+                        format!("Device did not report feature '{}'", feature.cli_name())
+                    })?;
+                // Disabled exits 1 through the error
+                if !state.enabled {
+                    anyhow::bail!("Feature '{}' is disabled", feature.cli_name());
                 }
-                FeatureAction::Enable { feature } => {
-                    let feature: Feature = feature
-                        .parse()
-                        .map_err(|_| anyhow::anyhow!("Unknown feature: {}", feature))?;
-                    let state = commands::feature_enable(transport, feature)?;
-                    println!(
-                        "{}Feature '{}' is now {}",
-                        prefix,
-                        state.feature.cli_name(),
-                        if state.enabled { "enabled" } else { "disabled" }
-                    );
+                printer.result(feature_json(&state), &mut || {
+                    outln!("{}Feature '{}' is enabled", prefix, feature.cli_name())
+                });
+            }
+            FeatureAction::SaveProfile { file } => {
+                if multi {
+                    return Err(exit::usage("feature save-profile needs a single device"));
+                }
+                let features = commands::feature_list(transport)?;
+                commands::save_feature_profile(file, &features)?;
+                let value = json!({
+                    "file": file.display().to_string(),
+                    "features": features.len(),
+                });
+                printer.result(value, &mut || {
+                    outln!("Saved {} feature states to {}", features.len(), file.display())
+                });
+            }
+            FeatureAction::CheckProfile { file } => {
+                let profile = commands::load_feature_profile(file)?;
+                let live = commands::feature_list(transport)?;
+                let drift = commands::diff_feature_profile(&profile, &live);
+                let state_name = |enabled: Option<bool>| match enabled {
+                    Some(true) => "enabled",
+                    Some(false) => "disabled",
+                    None => "missing",
+                };
+                let differences: Vec<Value> = drift
+                    .iter()
+                    .map(|d| {
+                        json!({
+                            "feature": d.feature.cli_name(),
+                            "expected": d.expected,
+                            "actual": d.actual,
+                        })
+                    })
+                    .collect();
+                let value = json!({ "matches": drift.is_empty(), "differences": differences });
+                printer.result(value, &mut || {
+                    if drift.is_empty() {
+                        outln!("{}Features match {}", prefix, file.display());
+                    }
+                    for d in &drift {
+                        match (d.expected, d.actual) {
+                            (None, _) => outln!("{}{}: {} (not in profile)",
+                                prefix, d.feature.cli_name(), state_name(d.actual)),
+                            (_, None) => outln!("{}{}: expected {}, not reported by device",
+                                prefix, d.feature.cli_name(), state_name(d.expected)),
+                            _ => outln!("{}{}: expected {}, found {}", prefix,
+                                d.feature.cli_name(), state_name(d.expected), state_name(d.actual)),
+                        }
+                    }
+                });
+                // Drift exits 1 through the error
+                if !drift.is_empty() {
+                    anyhow::bail!("{} feature(s) differ from {}", drift.len(), file.display());
                 }
-                FeatureAction::Disable { feature } => {
-                    let feature: Feature = feature
-                        .parse()
-                        .map_err(|_| anyhow::anyhow!("Unknown feature: {}", feature))?;
-                    let state = commands::feature_disable(transport, feature)?;
-                    println!(
+            }
+            FeatureAction::Enable { feature } | FeatureAction::Disable { feature } => {
+                let feature = parse_feature(feature)?;
+                let state = if matches!(action, FeatureAction::Enable { .. }) {
+                    commands::feature_enable(transport, feature)?
+                } else {
+                    commands::feature_disable(transport, feature)?
+                };
+                printer.result(feature_json(&state), &mut || {
+                    outln!(
                         "{}Feature '{}' is now {}",
                         prefix,
                         state.feature.cli_name(),
                         if state.enabled { "enabled" } else { "disabled" }
-                    );
-                }
-            },
+                    )
+                });
+            }
+        },
 
-            Commands::Wifi { action } => match action {
-                WifiAction::Enable => {
-                    let enabled = commands::wifi_enable(transport)?;
-                    println!(
+        Commands::Wifi { action } => match action {
+            WifiAction::Enable => {
+                let enabled = commands::wifi_enable(transport)?;
+                printer.result(json!({ "enabled": enabled }), &mut || {
+                    outln!(
                         "{}WiFi subsystem {}",
                         prefix,
                         if enabled {
@@ -754,11 +3634,13 @@ fn main() -> anyhow::Result<()> {
                         } else {
                             "failed to enable"
                         }
-                    );
-                }
-                WifiAction::Disable => {
-                    let disabled = commands::wifi_disable(transport)?;
-                    println!(
+                    )
+                });
+            }
+            WifiAction::Disable => {
+                let disabled = commands::wifi_disable(transport)?;
+                printer.result(json!({ "enabled": !disabled }), &mut || {
+                    outln!(
                         "{}WiFi subsystem {}",
                         prefix,
                         if disabled {
@@ -766,465 +3648,2000 @@ fn main() -> anyhow::Result<()> {
                         } else {
                             "failed to disable"
                         }
-                    );
-                }
-                WifiAction::Status => {
-                    let enabled = commands::wifi_status(transport)?;
-                    println!(
+                    )
+                });
+            }
+            WifiAction::Status => {
+                let enabled = commands::wifi_status(transport)?;
+                printer.result(json!({ "enabled": enabled }), &mut || {
+                    outln!(
                         "{}WiFi subsystem: {}",
                         prefix,
                         if enabled { "enabled" } else { "disabled" }
+                    )
+                });
+            }
+            WifiAction::ConfigureAp { ssid, password, channel, max_connections } => {
+                let config = commands::wifi_configure_ap(
+                    transport,
+                    ssid,
+                    password,
+                    *channel,
+                    *max_connections,
+                )?;
+                let value = json!({
+                    "ssid": config.ssid,
+                    "channel": config.channel,
+                    "max_connections": config.max_connections,
+                    "secured": config.secured,
+                    "ip": config.ip.to_string(),
+                });
+                printer.result(value, &mut || {
+                    outln!("{}Access point configured:", prefix);
+                    outln!("{}  SSID:            {}", prefix, config.ssid);
+                    outln!(
+                        "{}  Security:        {}",
+                        prefix,
+                        if config.secured { "WPA2-PSK" } else { "open" }
                     );
-                }
-            },
+                    outln!("{}  Channel:         {}", prefix, config.channel);
+                    outln!("{}  Max connections: {}", prefix, config.max_connections);
+                    outln!("{}  IP:              {}", prefix, config.ip);
+                });
+            }
+            WifiAction::Ap { action: WifiApAction::Status } => {
+                let status = commands::wifi_ap_status(transport)?;
+                let clients: Vec<Value> = status
+                    .clients
+                    .iter()
+                    .map(|c| json!({ "mac": mac_string(&c.mac), "rssi": c.rssi }))
+                    .collect();
+                let value = if status.active {
+                    json!({
+                        "active": true,
+                        "ssid": status.ssid,
+                        "channel": status.channel,
+                        "ip": status.ip.to_string(),
+                        "clients": clients,
+                    })
+                } else {
+                    json!({ "active": false })
+                };
+                printer.result(value, &mut || {
+                    if !status.active {
+                        outln!("{}Access point: not running", prefix);
+                        return;
+                    }
+                    outln!("{}Access point: active", prefix);
+                    outln!("{}  SSID:    {}", prefix, status.ssid);
+                    outln!("{}  Channel: {}", prefix, status.channel);
+                    outln!("{}  IP:      {}", prefix, status.ip);
+                    outln!("{}  Clients: {}", prefix, status.clients.len());
+                    for c in &status.clients {
+                        let rssi = i16::try_from(c.rssi).ok();
+                        outln!(
+                            "{}    {}  {:<8} {}",
+                            prefix,
+                            mac_string(&c.mac),
+                            format_rssi(rssi),
+                            rssi.map(signal_bar).unwrap_or_default()
+                        );
+                    }
+                });
+            }
+            WifiAction::Static { ip, netmask, gateway, dns, dns2 } => {
+                commands::wifi_set_static_ip(transport, *ip, *netmask, *gateway, *dns, *dns2)?;
+                let dns_servers: Vec<String> =
+                    std::iter::once(dns).chain(dns2).map(|d| d.to_string()).collect();
+                let prefix_len = u32::from(*netmask).leading_ones();
+                let value = json!({
+                    "ip": ip.to_string(),
+                    "netmask": netmask.to_string(),
+                    "gateway": gateway.to_string(),
+                    "dns": dns_servers,
+                });
+                printer.result(value, &mut || {
+                    outln!("{}Static IP configured:", prefix);
+                    outln!("{}  IP:      {}/{}", prefix, ip, prefix_len);
+                    outln!("{}  Netmask: {}", prefix, netmask);
+                    outln!("{}  Gateway: {}", prefix, gateway);
+                    outln!("{}  DNS:     {}", prefix, dns_servers.join(", "));
+                });
+            }
+            WifiAction::Dhcp => {
+                commands::wifi_clear_static_ip(transport)?;
+                printer.result(json!({ "dhcp": true }), &mut || {
+                    outln!("{}Static IP cleared, WiFi uses DHCP", prefix)
+                });
+            }
+        },
 
-            Commands::Led { action } => match action {
-                LedAction::Get => {
-                    let pattern = commands::led_get(transport)?;
+        Commands::Led { action } => match action {
+            LedAction::Get { raw: true } => {
+                let pattern = commands::led_get_raw(transport)?;
+                printer.result(raw_led_pattern_json(&pattern), &mut || {
                     if multi {
-                        println!("{}LED pattern:", prefix);
+                        outln!("{}LED pattern:", prefix);
+                    }
+                    for line in format_raw_led_pattern(&pattern) {
+                        outln!("  {}", line);
+                    }
+                });
+            }
+            LedAction::Get { raw: false } => {
+                let pattern = commands::led_get(transport)?;
+                printer.result(led_pattern_json(&pattern), &mut || {
+                    if multi {
+                        outln!("{}LED pattern:", prefix);
                     }
                     print_led_pattern(&pattern);
-                }
-                LedAction::Off => {
-                    let pattern = commands::led_off(transport)?;
-                    println!("{}LEDs turned off", prefix);
-                    print_led_pattern(&pattern);
-                }
-                LedAction::Solid { color, brightness } => {
-                    let (r, g, b) = parse_hex_color(color)?;
-                    let mut pattern = crate::protocol::CliLedPattern::solid(r, g, b);
-                    pattern.brightness = *brightness;
-                    let pattern = commands::led_set(transport, &pattern)?;
-                    println!("{}LED pattern set to solid", prefix);
+                });
+            }
+            LedAction::Off => {
+                let pattern = commands::led_off(transport)?;
+                printer.result(led_pattern_json(&pattern), &mut || {
+                    outln!("{}LEDs turned off", prefix);
                     print_led_pattern(&pattern);
+                });
+            }
+            LedAction::Brightness { value } => {
+                match commands::led_set_brightness(transport, *value)? {
+                    Some(pattern) => printer.result(led_pattern_json(&pattern), &mut || {
+                        outln!("{}LED brightness set to {}", prefix, pattern.brightness);
+                        print_led_pattern(&pattern);
+                    }),
+                    // The LEDs stay off; report that rather than the unapplied brightness
+                    None => printer.result(json!({ "type": "off" }), &mut || {
+                        outln!(
+                            "{}LEDs are off; set a pattern first (e.g., led solid --color ff0000)",
+                            prefix
+                        )
+                    }),
                 }
-                LedAction::Breathing {
-                    color,
-                    period,
-                    brightness,
-                } => {
-                    let (r, g, b) = parse_hex_color(color)?;
-                    let mut pattern =
-                        crate::protocol::CliLedPattern::breathing(r, g, b, *period);
-                    pattern.brightness = *brightness;
-                    let pattern = commands::led_set(transport, &pattern)?;
-                    println!("{}LED pattern set to breathing", prefix);
+            }
+            LedAction::Test { dwell, brightness } => {
+                let dwell = Duration::from_millis(*dwell);
+                let total = commands::led::LED_TEST_COLORS.len();
+                let mut step = 0;
+                let mut on_step = |name: &str| {
+                    step += 1;
+                    progress!("{}LED test {}/{}: {}", prefix, step, total + 1, name);
+                };
+                let shown =
+                    commands::led_test(transport, dwell, *brightness, interrupted, &mut on_step)?;
+                let value = json!({ "colors_shown": shown, "completed": shown == total });
+                printer.result(value, &mut || {
+                    if shown == total {
+                        outln!("{}LED test complete ({} colors, LEDs off)", prefix, shown);
+                    } else {
+                        outln!(
+                            "{}LED test stopped after {} of {} colors (LEDs off)",
+                            prefix,
+                            shown,
+                            total
+                        );
+                    }
+                });
+            }
+            LedAction::Export { file } => {
+                let pattern = commands::led_get(transport)?;
+                pattern.to_json_file(file)?;
+                let value = json!({ "file": file, "pattern": led_pattern_json(&pattern) });
+                printer.result(value, &mut || {
+                    outln!("{}LED pattern saved to {}", prefix, file.display());
                     print_led_pattern(&pattern);
-                }
-                LedAction::Cycle { period, brightness } => {
-                    let colors = vec![
-                        (255, 0, 0, 0),
-                        (255, 127, 0, 0),
-                        (255, 255, 0, 0),
-                        (0, 255, 0, 0),
-                        (0, 0, 255, 0),
-                        (75, 0, 130, 0),
-                        (148, 0, 211, 0),
-                    ];
-                    let mut pattern =
-                        crate::protocol::CliLedPattern::color_cycle(colors, *period);
-                    pattern.brightness = *brightness;
-                    let pattern = commands::led_set(transport, &pattern)?;
-                    println!("{}LED pattern set to color cycle", prefix);
+                });
+            }
+            LedAction::Solid { .. }
+            | LedAction::Breathing { .. }
+            | LedAction::Cycle { .. }
+            | LedAction::Import { .. } => {
+                let pattern = requested_led_pattern(action)?
+                    .context("LED action does not set a pattern")?;
+                let pattern = commands::led_set(transport, &pattern)?;
+                let name = match action {
+                    LedAction::Solid { .. } => "solid".to_string(),
+                    LedAction::Breathing { .. } => "breathing".to_string(),
+                    LedAction::Import { file } => format!("{}", file.display()),
+                    _ => "color cycle".to_string(),
+                };
+                printer.result(led_pattern_json(&pattern), &mut || {
+                    outln!("{}LED pattern set to {}", prefix, name);
                     print_led_pattern(&pattern);
-                }
-            },
+                });
+            }
+        },
 
-            Commands::Ota { action } => match action {
-                OtaAction::Flash { firmware, version } => {
-                    if multi {
-                        println!("{}Flashing OTA...", prefix);
-                    }
-                    commands::ota_flash(transport, firmware, version.as_deref())?;
+        Commands::Ota { action } => match action {
+            OtaAction::Flash {
+                firmware,
+                version,
+                no_reboot,
+                ota_chunk_size,
+                force,
+                ota_baud,
+                verify_boot,
+                reboot_timeout_ms,
+            } => {
+                if multi {
+                    progress!("Flashing OTA...");
                 }
-                OtaAction::Check => {
-                    println!("{}Checking for firmware updates...", prefix);
-                    let info = commands::ota_check(transport)?;
-                    println!("{}Current version:  {}", prefix,
+                let options = commands::OtaOptions {
+                    version: version.as_deref(),
+                    no_reboot: *no_reboot,
+                    chunk_size: ota_chunk_size.map(|n| n as usize),
+                    force: *force,
+                    baud: Some(*ota_baud),
+                };
+                let result = match bars::ota_callback(&device_label(dev_name)) {
+                    Some(callback) => commands::ota_flash(transport, firmware, &options, callback)?,
+                    None => commands::ota_flash(
+                        transport,
+                        firmware,
+                        &options,
+                        commands::stdout_progress_callback(),
+                    )?,
+                };
+                let verified = if *verify_boot {
+                    let timeout = Duration::from_millis(*reboot_timeout_ms);
+                    let running = commands::ota_verify_boot(transport, &result.version, timeout)
+                        .context("The update was sent, but the boot check failed")?;
+                    Some(running)
+                } else {
+                    None
+                };
+                let value = json!({
+                    "firmware": firmware.display().to_string(),
+                    "bytes": result.bytes,
+                    "sha256": result.sha256,
+                    "version": result.version,
+                    "duration_ms": result.duration.as_millis() as u64,
+                    "status": "ok",
+                    "rebooting": !no_reboot,
+                    "verified_version": verified,
+                });
+                printer.result(value, &mut || {
+                    if *no_reboot {
+                        outln!("{}OTA complete! New firmware runs after the next reboot.", prefix);
+                    } else if verified.is_some() {
+                        outln!("{}OTA complete! ✓ New firmware verified running.", prefix);
+                    } else {
+                        outln!("{}OTA complete! Device will reboot.", prefix);
+                    }
+                });
+            }
+            OtaAction::Check => {
+                progress!("Checking for firmware updates...");
+                let info = commands::ota_check(transport)?;
+                let value = json!({
+                    "current_version": info.current_version,
+                    "auto_update": info.auto_update_enabled,
+                    "update_available": info.update_available,
+                    "available_version": info.available_version,
+                    "firmware_size": info.firmware_size,
+                });
+                printer.result(value, &mut || {
+                    outln!("{}Current version:  {}", prefix,
                         if info.current_version.is_empty() { "unknown" } else { &info.current_version });
-                    println!("{}Auto-update:      {}", prefix,
+                    outln!("{}Auto-update:      {}", prefix,
                         if info.auto_update_enabled { "enabled" } else { "disabled" });
                     if info.update_available {
-                        println!("{}Update available: {} ({} bytes)", prefix,
-                            info.available_version, info.firmware_size);
+                        outln!("{}Update available: {} ({})", prefix,
+                            info.available_version, output::format_bytes(info.firmware_size as u64));
                     } else {
-                        println!("{}No update available", prefix);
+                        outln!("{}No update available", prefix);
                     }
-                }
-                OtaAction::AutoUpdate { enable, disable } => {
-                    let enabled = if *enable && *disable {
-                        anyhow::bail!("Cannot specify both --enable and --disable");
-                    } else if *enable {
-                        true
-                    } else if *disable {
-                        false
-                    } else {
-                        anyhow::bail!("Must specify either --enable or --disable");
-                    };
-                    let result = commands::ota_auto_update(transport, enabled)?;
-                    println!("{}Auto-update {}", prefix,
+                });
+            }
+            OtaAction::AutoUpdate { enable, disable } => {
+                let enabled = enable_flag(*enable, *disable)?;
+                let result = commands::ota_auto_update(transport, enabled)?;
+                printer.result(json!({ "auto_update": result }), &mut || {
+                    outln!("{}Auto-update {}", prefix,
                         if result { "enabled" } else { "disabled" });
-                }
-            },
+                });
+            }
+        },
 
-            Commands::Trace { action } => match action {
-                TraceAction::Start => {
-                    commands::trace_start(transport)?;
-                    println!("{}Tracing started", prefix);
-                }
-                TraceAction::Stop => {
-                    commands::trace_stop(transport)?;
-                    println!("{}Tracing stopped", prefix);
-                }
-                TraceAction::Clear => {
-                    commands::trace_clear(transport)?;
-                    println!("{}Trace buffer cleared", prefix);
-                }
-                TraceAction::Status => {
+        Commands::Trace { action } => match action {
+            TraceAction::Start { duration: None, .. } => {
+                commands::trace_start(transport)?;
+                printer.result(json!({ "tracing": true }), &mut || {
+                    outln!("{}Tracing started", prefix)
+                });
+            }
+            TraceAction::Start { duration: Some(secs), output, names } => {
+                let output = output.as_deref().unwrap_or(Path::new("trace.json"));
+                let dump_output = trace_output_for(output, dev_name, multi)?;
+                progress!("Capturing trace for {}s into {}...", secs, dump_output);
+                let result = commands::trace_capture(
+                    transport,
+                    Duration::from_secs(*secs),
+                    dump_output,
+                    commands::TraceFormat::for_path(output),
+                    names.as_deref(),
+                    interrupted,
+                )?;
+                print_dump_result(prefix, &result, printer);
+            }
+            TraceAction::Stop => {
+                commands::trace_stop(transport)?;
+                printer.result(json!({ "tracing": false }), &mut || {
+                    outln!("{}Tracing stopped", prefix)
+                });
+            }
+            TraceAction::Clear => {
+                commands::trace_clear(transport)?;
+                printer.result(json!({ "cleared": true }), &mut || {
+                    outln!("{}Trace buffer cleared", prefix)
+                });
+            }
+            TraceAction::Annotate { label } => {
+                let annotation = commands::trace_annotate(transport, label)?;
+                let value = json!({
+                    "label": label,
+                    "marker": annotation.marker,
+                    "timestamp_us": annotation.timestamp_us,
+                });
+                printer.result(value, &mut || {
+                    outln!("{}Marker #{} '{}' recorded at {} us", prefix,
+                        annotation.marker, label, annotation.timestamp_us)
+                });
+            }
+            TraceAction::Status => {
+                let status = commands::trace_status(transport)?;
+                let value = json!({
+                    "initialized": status.initialized,
+                    "enabled": status.enabled,
+                    "streaming": status.streaming,
+                    "event_count": status.event_count,
+                    "dropped_count": status.dropped_count,
+                    "buffer_size": status.buffer_size,
+                });
+                printer.result(value, &mut || {
+                    outln!("{}Trace status:", prefix);
+                    outln!("{}  Initialized: {}", prefix, status.initialized);
+                    outln!("{}  Enabled:     {}", prefix, status.enabled);
+                    outln!("{}  Streaming:   {}", prefix, status.streaming);
+                    outln!("{}  Events:      {}", prefix, status.event_count);
+                    outln!("{}  Dropped:     {}", prefix, status.dropped_count);
+                    outln!("{}  Buffer size: {}", prefix, output::format_bytes(status.buffer_size as u64));
+                });
+            }
+            TraceAction::SetBufferSize { event_count, force } => {
+                if !*force {
                     let status = commands::trace_status(transport)?;
-                    println!("{}Trace status:", prefix);
-                    println!("{}  Initialized: {}", prefix, status.initialized);
-                    println!("{}  Enabled:     {}", prefix, status.enabled);
-                    println!("{}  Streaming:   {}", prefix, status.streaming);
-                    println!("{}  Events:      {}", prefix, status.event_count);
-                    println!("{}  Dropped:     {}", prefix, status.dropped_count);
-                    println!("{}  Buffer size: {} bytes", prefix, status.buffer_size);
+                    if status.event_count > 0 {
+                        anyhow::bail!(
+                            "Resizing clears {} recorded events; dump them first or pass --force",
+                            status.event_count
+                        );
+                    }
                 }
-                TraceAction::Stream { wifi } => {
-                    commands::trace_stream(wifi)?;
+                let result = commands::trace_set_buffer(transport, *event_count)?;
+                if result.allocated_count < *event_count {
+                    eprintln!(
+                        "{}Warning: requested {} events, device allocated only {}",
+                        prefix, event_count, result.allocated_count
+                    );
                 }
-                TraceAction::Dump { output, names } => {
-                    let dump_path = if multi {
-                        // Per-device output file
-                        let stem = output
-                            .file_stem()
-                            .unwrap_or_default()
-                            .to_string_lossy();
-                        let ext = output
-                            .extension()
-                            .unwrap_or_default()
-                            .to_string_lossy();
-                        output.with_file_name(format!("{}-{}.{}", stem, dev.name, ext))
-                    } else {
-                        output.clone()
-                    };
-                    println!("{}Dumping traces to {}...", prefix, dump_path.display());
-                    let result = commands::trace_dump(transport, &dump_path, names.as_deref())?;
-                    println!("{}Dump complete: {} events (pod_id={})", prefix, result.event_count, result.pod_id);
-                    if result.dropped_count > 0 {
-                        println!("{}  Dropped: {} events", prefix, result.dropped_count);
+                let value = json!({
+                    "allocated_count": result.allocated_count,
+                    "bytes_used": result.bytes_used,
+                    "was_cleared": result.was_cleared,
+                });
+                printer.result(value, &mut || {
+                    outln!(
+                        "{}Trace buffer resized: {} events ({})",
+                        prefix, result.allocated_count, output::format_bytes(result.bytes_used as u64)
+                    );
+                    if result.was_cleared {
+                        outln!("{}  Existing events were cleared", prefix);
                     }
-                    println!("{}Output: {}", prefix, result.output_path.display());
-                }
-            },
-
-            Commands::Imu { action } => match action {
-                ImuAction::Triage { enable, disable } => {
-                    let enabled = if *enable && *disable {
-                        anyhow::bail!("Cannot specify both --enable and --disable");
-                    } else if *enable {
-                        true
-                    } else if *disable {
-                        false
-                    } else {
-                        anyhow::bail!("Must specify either --enable or --disable");
-                    };
-                    let result = commands::imu_triage_set(transport, enabled)?;
-                    println!(
+                });
+            }
+            TraceAction::Stream { wifi } => {
+                commands::trace_stream(wifi)?;
+            }
+            TraceAction::Dump {
+                output,
+                trace_format,
+                names,
+                strict,
+                since,
+                append,
+                session_label,
+            } => {
+                let label = session_label.as_deref();
+                let format = trace_dump_format(output, trace_format.as_deref(), *append, label)?;
+                let dump_output = trace_output_for(output, dev_name, multi)?;
+                progress!("Dumping traces to {} ({})...", dump_output, format);
+                let options = commands::DumpOptions {
+                    since: *since,
+                    append: *append,
+                    session_label: session_label.as_deref(),
+                    strict: *strict,
+                };
+                let names = names.as_deref();
+                let result = commands::trace_dump(transport, dump_output, format, names, &options)?;
+                print_dump_result(prefix, &result, printer);
+            }
+        },
+
+        Commands::Imu { action } => match action {
+            ImuAction::Triage { enable, disable } => {
+                let enabled = enable_flag(*enable, *disable)?;
+                let result = commands::imu_triage_set(transport, enabled)?;
+                printer.result(json!({ "triage": result }), &mut || {
+                    outln!(
                         "{}IMU triage mode {}",
                         prefix,
                         if result { "enabled" } else { "disabled" }
-                    );
-                }
-            },
+                    )
+                });
+            }
+        },
 
-            Commands::System { action } => match action {
-                SystemAction::Mode => {
-                    let info = commands::system_get_mode(transport)?;
-                    println!("{}System mode: {}", prefix, info.mode);
-                    println!("{}  Time in mode: {} ms", prefix, info.time_in_mode_ms);
-                }
-                SystemAction::SetMode { mode } => {
-                    let mode: SystemMode = mode.parse().map_err(|_| {
-                        anyhow::anyhow!(
-                            "Unknown mode: {}. Valid: idle, triage, connected, game, error",
-                            mode
-                        )
-                    })?;
-                    let (new_mode, ok) = commands::system_set_mode(transport, mode)?;
+        Commands::System { action } => match action {
+            SystemAction::Mode => {
+                let info = commands::system_get_mode(transport)?;
+                let value = json!({
+                    "mode": info.mode.to_string(),
+                    "time_in_mode_ms": info.time_in_mode_ms,
+                    "previous_mode": info.previous_mode.map(|mode| mode.to_string()),
+                });
+                printer.result(value, &mut || {
+                    outln!("{}System mode: {}", prefix, info.mode);
+                    outln!("{}  Time in mode: {} ms", prefix, info.time_in_mode_ms);
+                    if let Some(previous) = info.previous_mode {
+                        outln!("{}  Previous mode: {}", prefix, previous);
+                    }
+                });
+            }
+            SystemAction::SetMode { mode, force } => {
+                let mode = parse_mode(mode)?;
+                let (new_mode, ok) = commands::system_set_mode(transport, mode, *force)?;
+                let value = json!({
+                    "mode": new_mode.to_string(),
+                    "accepted": ok,
+                    "forced": force,
+                });
+                printer.result(value, &mut || {
                     if ok {
-                        println!("{}System mode set to: {}", prefix, new_mode);
+                        outln!("{}System mode set to: {}", prefix, new_mode);
+                    } else if *force {
+                        outln!(
+                            "{}Mode transition rejected despite --force (current mode: {}); \
+                             the firmware may not support forced transitions",
+                            prefix, new_mode
+                        );
                     } else {
-                        println!(
+                        outln!(
                             "{}Mode transition rejected (current mode: {})",
                             prefix, new_mode
                         );
                     }
-                }
-                SystemAction::Info => {
-                    let info = commands::system_info(transport)?;
-                    println!("{}System Information:", prefix);
-                    println!("{}  Firmware:   {}", prefix, info.firmware_version);
-                    println!("{}  Pod ID:     {}", prefix, if info.pod_id == 0 { "not set".to_string() } else { info.pod_id.to_string() });
-                    println!("{}  Mode:       {}", prefix, info.mode);
-                    println!("{}  Uptime:     {} s", prefix, info.uptime_s);
-                    println!("{}  Free heap:  {} bytes", prefix, info.free_heap);
-                    println!("{}  Boot count: {}", prefix, info.boot_count);
-                    println!("{}  Features:   0x{:08X}", prefix, info.feature_mask);
-                }
-                SystemAction::SetPodId { id } => {
-                    let new_id = commands::system_set_pod_id(transport, *id)?;
-                    println!("{}Pod ID set to {} (reboot device for BLE name change)", prefix, new_id);
-                }
-                SystemAction::Health => {
-                    let health = commands::system_health(transport)?;
-                    println!("{}System Health:", prefix);
-                    println!("{}  Free heap:     {} bytes", prefix, health.free_heap);
-                    println!("{}  Min free heap: {} bytes", prefix, health.min_free_heap);
-                    println!("{}  Uptime:        {} s", prefix, health.uptime_seconds);
+                });
+            }
+            SystemAction::Info => {
+                let info = commands::system_info(transport)?;
+                let counter = |value: u32| (!info.partial).then_some(value);
+                let value = json!({
+                    "firmware_version": firmware_version(&info),
+                    "pod_id": info.pod_id,
+                    "mode": info.mode.to_string(),
+                    "uptime_s": counter(info.uptime_s),
+                    "free_heap": counter(info.free_heap),
+                    "boot_count": counter(info.boot_count),
+                    "feature_mask": info.feature_mask,
+                    "max_payload": info.max_payload,
+                    "partial": info.partial,
+                });
+                printer.result(value, &mut || {
+                    outln!("{}System Information:", prefix);
+                    outln!("{}  Firmware:   {}", prefix, firmware_version(&info).unwrap_or("unknown"));
+                    outln!("{}  Pod ID:     {}", prefix, if info.pod_id == 0 { "not set".to_string() } else { info.pod_id.to_string() });
+                    outln!("{}  Mode:       {}", prefix, info.mode);
+                    outln!("{}  Uptime:     {}", prefix, known(&info, format!("{} s", info.uptime_s)));
+                    outln!("{}  Free heap:  {}", prefix, known(&info, output::format_bytes(info.free_heap as u64)));
+                    outln!("{}  Boot count: {}", prefix, known(&info, info.boot_count.to_string()));
+                    outln!("{}  Features:   0x{:08X}", prefix, info.feature_mask);
+                    if let Some(max_payload) = info.max_payload {
+                        outln!("{}  Max payload: {} bytes", prefix, max_payload);
+                    }
+                    if info.partial {
+                        outln!("{}  (older firmware: only mode, pod ID and features reported)", prefix);
+                    }
+                });
+            }
+            SystemAction::SetPodId { id } => {
+                let new_id = commands::system_set_pod_id(transport, *id)?;
+                printer.result(json!({ "pod_id": new_id }), &mut || {
+                    outln!("{}Pod ID set to {} (reboot device for BLE name change)", prefix, new_id)
+                });
+            }
+            SystemAction::SetName { name } => {
+                let new_name = commands::system_set_name(transport, name)?;
+                printer.result(json!({ "name": new_name }), &mut || {
+                    outln!("{}Device name set to '{}' (reboot device for BLE name change)", prefix, new_name)
+                });
+            }
+            SystemAction::Health => {
+                let health = commands::system_health(transport)?;
+                let tasks: Vec<Value> = health
+                    .tasks
+                    .iter()
+                    .map(|task| {
+                        json!({
+                            "name": task.name,
+                            "stack_high_water": task.stack_high_water,
+                            "priority": task.priority,
+                            "core": task.core,
+                        })
+                    })
+                    .collect();
+                let value = json!({
+                    "free_heap": health.free_heap,
+                    "min_free_heap": health.min_free_heap,
+                    "uptime_s": health.uptime_seconds,
+                    // 0 means not connected
+                    "wifi_rssi": (health.wifi_rssi != 0).then_some(health.wifi_rssi),
+                    "tasks": tasks,
+                });
+                printer.result(value, &mut || {
+                    outln!("{}System Health:", prefix);
+                    outln!("{}  Free heap:     {}", prefix, output::format_bytes(health.free_heap as u64));
+                    outln!("{}  Min free heap: {}", prefix, output::format_bytes(health.min_free_heap as u64));
+                    outln!("{}  Uptime:        {} s", prefix, health.uptime_seconds);
                     if health.wifi_rssi != 0 {
-                        println!("{}  WiFi RSSI:     {} dBm", prefix, health.wifi_rssi);
+                        outln!("{}  WiFi RSSI:     {} dBm", prefix, health.wifi_rssi);
                     } else {
-                        println!("{}  WiFi RSSI:     n/a (not connected)", prefix);
+                        outln!("{}  WiFi RSSI:     n/a (not connected)", prefix);
                     }
                     if !health.tasks.is_empty() {
-                        println!("{}  Tasks ({}):", prefix, health.tasks.len());
-                        println!("{}    {:<16} {:>6} {:>4} {:>4}", prefix, "NAME", "STACK", "PRI", "CORE");
-                        println!("{}    {:-<16} {:->6} {:->4} {:->4}", prefix, "", "", "", "");
+                        outln!("{}  Tasks ({}):", prefix, health.tasks.len());
+                        outln!("{}    {:<16} {:>6} {:>4} {:>4}", prefix, "NAME", "STACK", "PRI", "CORE");
+                        outln!("{}    {:-<16} {:->6} {:->4} {:->4}", prefix, "", "", "", "");
                         for task in &health.tasks {
-                            println!("{}    {:<16} {:>6} {:>4} {:>4}",
+                            outln!("{}    {:<16} {:>6} {:>4} {:>4}",
                                 prefix, task.name, task.stack_high_water, task.priority, task.core);
                         }
                     }
+                });
+            }
+            SystemAction::Tasks { warn_watermark } => {
+                let tasks = commands::system_get_tasks(transport)?;
+                let value: Vec<Value> = tasks
+                    .iter()
+                    .map(|task| {
+                        let mut entry = json!({
+                            "name": task.name,
+                            "state": output::task_state_name(task.state),
+                            "priority": task.priority,
+                            "stack_watermark_bytes": task.stack_watermark_bytes,
+                            "runtime_percent": task.runtime_percent,
+                            "core_id": task.core_id,
+                        });
+                        if warn_watermark.is_some() {
+                            let warning =
+                                output::stack_warning(task.stack_watermark_bytes, *warn_watermark);
+                            entry["stack_warning"] = json!(warning);
+                        }
+                        entry
+                    })
+                    .collect();
+                printer.result(Value::Array(value), &mut || {
+                    for line in output::format_task_table(&tasks, *warn_watermark).lines() {
+                        outln!("{}{}", prefix, line);
+                    }
+                });
+            }
+            SystemAction::Partitions => {
+                let partitions = commands::system_get_partitions(transport)?;
+                let value: Vec<Value> = partitions
+                    .iter()
+                    .map(|p| {
+                        json!({
+                            "name": p.name,
+                            "type": output::partition_type_name(p.type_),
+                            "subtype": p.subtype,
+                            "offset": p.offset,
+                            "size": p.size,
+                            "encrypted": p.encrypted,
+                            "active": p.active,
+                        })
+                    })
+                    .collect();
+                printer.result(Value::Array(value), &mut || {
+                    for line in output::format_partition_table(&partitions).lines() {
+                        outln!("{}{}", prefix, line);
+                    }
+                });
+            }
+            SystemAction::Ping { count } => {
+                let name = device_label(dev_name);
+                let table = printer.is_table();
+                if table {
+                    outln!("{}PING {}: {} pings", prefix, name, count);
+                }
+                let stats = commands::system_ping_with(transport, *count, |seq, rtt| {
+                    if !table {
+                        return;
+                    }
+                    match rtt {
+                        Some(rtt) => outln!("{}seq={} time={:.2} ms",
+                            prefix, seq, rtt.as_secs_f64() * 1000.0),
+                        None => outln!("{}seq={} no response within {} ms",
+                            prefix, seq, commands::PING_TIMEOUT_MS),
+                    }
+                })?;
+                let received = stats.count - stats.lost;
+                let loss_percent = stats.lost as f64 / stats.count as f64 * 100.0;
+                let value = json!({
+                    "count": stats.count,
+                    "received": received,
+                    "lost": stats.lost,
+                    "loss_percent": loss_percent,
+                    "min_ms": stats.min_ms,
+                    "avg_ms": stats.avg_ms,
+                    "max_ms": stats.max_ms,
+                    "stddev_ms": stats.stddev_ms,
+                });
+                printer.result(value, &mut || {
+                    outln!("{}--- {} ping statistics ---", prefix, name);
+                    outln!("{}{} sent, {} received, {:.0}% lost",
+                        prefix, stats.count, received, loss_percent);
+                    if received > 0 {
+                        outln!("{}rtt min/avg/max/stddev = {:.2}/{:.2}/{:.2}/{:.2} ms",
+                            prefix, stats.min_ms, stats.avg_ms, stats.max_ms, stats.stddev_ms);
+                    }
+                });
+                if received == 0 {
+                    return Err(anyhow::Error::new(ResponseTimeout)
+                        .context(format!("No response to {} pings", stats.count)));
                 }
-                SystemAction::CrashDump { clear } => {
-                    let dump = commands::system_crash_dump(transport)?;
+            }
+            SystemAction::CrashDump { clear } => {
+                let dump = commands::system_crash_dump(transport)?;
+                let cleared = if dump.has_dump && *clear {
+                    Some(commands::system_clear_crash_dump(transport)?)
+                } else {
+                    None
+                };
+                let value = if dump.has_dump {
+                    json!({
+                        "has_dump": true,
+                        "reason": dump.reason,
+                        "task_name": dump.task_name,
+                        "uptime_s": dump.uptime_s,
+                        "free_heap": dump.free_heap,
+                        "backtrace": dump.backtrace.iter().map(|a| format!("0x{:08X}", a)).collect::<Vec<_>>(),
+                        "cleared": cleared,
+                    })
+                } else {
+                    json!({ "has_dump": false })
+                };
+                printer.result(value, &mut || {
                     if dump.has_dump {
-                        println!("{}Crash Dump:", prefix);
-                        println!("{}  Reason:    {}", prefix, dump.reason);
-                        println!("{}  Task:      {}", prefix, dump.task_name);
-                        println!("{}  Uptime:    {} s", prefix, dump.uptime_s);
-                        println!("{}  Free heap: {} bytes", prefix, dump.free_heap);
+                        outln!("{}Crash Dump:", prefix);
+                        outln!("{}  Reason:    {}", prefix, dump.reason);
+                        outln!("{}  Task:      {}", prefix, dump.task_name);
+                        outln!("{}  Uptime:    {} s", prefix, dump.uptime_s);
+                        outln!("{}  Free heap: {}", prefix, output::format_bytes(dump.free_heap as u64));
                         if !dump.backtrace.is_empty() {
-                            println!("{}  Backtrace:", prefix);
+                            outln!("{}  Backtrace:", prefix);
                             for (i, addr) in dump.backtrace.iter().enumerate() {
-                                println!("{}    #{}: 0x{:08X}", prefix, i, addr);
+                                outln!("{}    #{}: 0x{:08X}", prefix, i, addr);
                             }
-                            println!("{}  (use addr2line -e build/domes.elf to resolve)", prefix);
+                            outln!("{}  (use addr2line -e build/domes.elf to resolve)", prefix);
                         }
-                        if *clear {
-                            let cleared = commands::system_clear_crash_dump(transport)?;
-                            if cleared {
-                                println!("{}Crash dump cleared.", prefix);
-                            } else {
-                                println!("{}Failed to clear crash dump.", prefix);
-                            }
+                        match cleared {
+                            Some(true) => outln!("{}Crash dump cleared.", prefix),
+                            Some(false) => outln!("{}Failed to clear crash dump.", prefix),
+                            None => {}
                         }
                     } else {
-                        println!("{}No crash dump stored.", prefix);
+                        outln!("{}No crash dump stored.", prefix);
                     }
-                }
-                SystemAction::Memory { json } => {
-                    let profile = commands::system_memory_profile(transport)?;
-                    if *json {
-                        // JSON output
-                        println!("{{");
-                        println!("  \"current_free_heap\": {},", profile.current_free_heap);
-                        println!("  \"current_min_free_heap\": {},", profile.current_min_free_heap);
-                        println!("  \"current_largest_block\": {},", profile.current_largest_block);
-                        println!("  \"total_heap\": {},", profile.total_heap);
-                        println!("  \"usage_pct\": {:.1},",
-                            if profile.total_heap > 0 {
-                                (1.0 - profile.current_free_heap as f64 / profile.total_heap as f64) * 100.0
-                            } else { 0.0 });
-                        println!("  \"samples\": [");
-                        for (i, s) in profile.samples.iter().enumerate() {
-                            let comma = if i + 1 < profile.samples.len() { "," } else { "" };
-                            println!("    {{\"t\": {}, \"free\": {}, \"largest\": {}, \"min_free\": {}}}{}",
-                                s.timestamp_s, s.free_heap, s.largest_block, s.min_free_heap, comma);
-                        }
-                        println!("  ]");
-                        println!("}}");
+                });
+            }
+            SystemAction::CoreDump { output: _, action: Some(CoreDumpAction::Clear) } => {
+                let erased = commands::system_clear_core_dump(transport)?;
+                printer.result(json!({ "erased": erased }), &mut || {
+                    if erased {
+                        outln!("{}Core dump erased.", prefix);
                     } else {
-                        let usage_pct = if profile.total_heap > 0 {
-                            (1.0 - profile.current_free_heap as f64 / profile.total_heap as f64) * 100.0
-                        } else { 0.0 };
-                        println!("{}Memory Profile:", prefix);
-                        println!("{}  Total heap:      {} bytes", prefix, profile.total_heap);
-                        println!("{}  Free heap:       {} bytes ({:.1}% used)", prefix, profile.current_free_heap, usage_pct);
-                        println!("{}  Min free heap:   {} bytes", prefix, profile.current_min_free_heap);
-                        println!("{}  Largest block:   {} bytes", prefix, profile.current_largest_block);
-                        if !profile.samples.is_empty() {
-                            println!("{}  History ({} samples):", prefix, profile.samples.len());
-                            // Sparkline using free heap values
-                            let values: Vec<u32> = profile.samples.iter().map(|s| s.free_heap).collect();
-                            let min_val = *values.iter().min().unwrap_or(&0);
-                            let max_val = *values.iter().max().unwrap_or(&1);
-                            let range = if max_val > min_val { max_val - min_val } else { 1 };
-                            let spark_chars = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
-                            let sparkline: String = values.iter().map(|v| {
-                                let idx = (((*v - min_val) as f64 / range as f64) * 7.0) as usize;
-                                spark_chars[idx.min(7)]
-                            }).collect();
-                            println!("{}    Free heap: {} ({}-{} bytes)", prefix, sparkline, min_val, max_val);
-                        }
+                        outln!("{}No core dump to erase.", prefix);
+                    }
+                });
+            }
+            SystemAction::CoreDump { output, action: None } => {
+                let path = if multi { per_device_file(output, dev_name) } else { output.clone() };
+                let info = commands::system_get_core_dump(transport, &path)?;
+                let value = if info.valid {
+                    json!({
+                        "valid": true,
+                        "size": info.size,
+                        "reset_reason": info.reset_reason,
+                        "output": path,
+                    })
+                } else {
+                    json!({ "valid": false })
+                };
+                printer.result(value, &mut || {
+                    if info.valid {
+                        outln!(
+                            "{}Core dump saved to {} ({}, reset reason: {})",
+                            prefix,
+                            path.display(),
+                            output::format_bytes(info.size as u64),
+                            info.reset_reason
+                        );
+                        outln!(
+                            "{}Decode with: esptool.py --chip esp32s3 core_dump info --core {} \
+                             --elf firmware.elf",
+                            prefix,
+                            path.display()
+                        );
+                    } else {
+                        outln!("{}No core dump available.", prefix);
+                    }
+                });
+            }
+            SystemAction::Memory { json } => {
+                let profile = commands::system_memory_profile(transport)?;
+                let usage_pct = if profile.total_heap > 0 {
+                    (1.0 - profile.current_free_heap as f64 / profile.total_heap as f64) * 100.0
+                } else { 0.0 };
+                let samples: Vec<Value> = profile
+                    .samples
+                    .iter()
+                    .map(|s| {
+                        json!({
+                            "t": s.timestamp_s,
+                            "free": s.free_heap,
+                            "largest": s.largest_block,
+                            "min_free": s.min_free_heap,
+                        })
+                    })
+                    .collect();
+                let value = json!({
+                    "current_free_heap": profile.current_free_heap,
+                    "current_min_free_heap": profile.current_min_free_heap,
+                    "current_largest_block": profile.current_largest_block,
+                    "total_heap": profile.total_heap,
+                    "usage_pct": (usage_pct * 10.0).round() / 10.0,
+                    "samples": samples,
+                });
+                if *json {
+                    // Pretty-printed JSON, regardless of --format
+                    for line in serde_json::to_string_pretty(&value)?.lines() {
+                        outln!("{}", line);
                     }
+                    return Ok(());
                 }
-                SystemAction::SelfTest => {
-                    println!("{}Running on-device self-test suite...", prefix);
-                    let info = commands::system_self_test(transport)?;
-                    println!("{}Self-Test Results: {}/{} passed", prefix, info.tests_passed, info.tests_run);
-                    println!("{}{:<8} {:<6} {}", prefix, "TEST", "STATUS", "MESSAGE");
-                    println!("{}{:-<8} {:-<6} {:-<40}", prefix, "", "", "");
+                printer.result(value, &mut || {
+                    outln!("{}Memory Profile:", prefix);
+                    outln!("{}  Total heap:      {}", prefix, output::format_bytes(profile.total_heap as u64));
+                    outln!("{}  Free heap:       {} ({:.1}% used)", prefix, output::format_bytes(profile.current_free_heap as u64), usage_pct);
+                    outln!("{}  Min free heap:   {}", prefix, output::format_bytes(profile.current_min_free_heap as u64));
+                    outln!("{}  Largest block:   {}", prefix, output::format_bytes(profile.current_largest_block as u64));
+                    if !profile.samples.is_empty() {
+                        outln!("{}  History ({} samples):", prefix, profile.samples.len());
+                        // Sparkline using free heap values
+                        let values: Vec<u32> = profile.samples.iter().map(|s| s.free_heap).collect();
+                        let min_val = *values.iter().min().unwrap_or(&0);
+                        let max_val = *values.iter().max().unwrap_or(&1);
+                        let range = if max_val > min_val { max_val - min_val } else { 1 };
+                        let spark_chars = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+                        let sparkline: String = values.iter().map(|v| {
+                            let idx = (((*v - min_val) as f64 / range as f64) * 7.0) as usize;
+                            spark_chars[idx.min(7)]
+                        }).collect();
+                        outln!("{}    Free heap: {} ({} - {})", prefix, sparkline,
+                            output::format_bytes(min_val as u64), output::format_bytes(max_val as u64));
+                    }
+                });
+            }
+            SystemAction::SelfTest => {
+                progress!("Running on-device self-test suite...");
+                let info = commands::system_self_test(transport)?;
+                let results: Vec<Value> = info
+                    .results
+                    .iter()
+                    .map(|r| json!({ "name": r.name, "passed": r.passed, "message": r.message }))
+                    .collect();
+                let value = json!({
+                    "tests_run": info.tests_run,
+                    "tests_passed": info.tests_passed,
+                    "results": results,
+                });
+                printer.result(value, &mut || {
+                    outln!("{}Self-Test Results: {}/{} passed", prefix, info.tests_passed, info.tests_run);
+                    outln!("{}{:<8} {:<6} MESSAGE", prefix, "TEST", "STATUS");
+                    outln!("{}{:-<8} {:-<6} {:-<40}", prefix, "", "", "");
                     for result in &info.results {
                         let status = if result.passed { "PASS" } else { "FAIL" };
-                        println!("{}{:<8} {:<6} {}", prefix, result.name, status, result.message);
+                        outln!("{}{:<8} {:<6} {}", prefix, result.name, status, result.message);
                     }
                     if info.tests_passed == info.tests_run {
-                        println!("{}All tests passed!", prefix);
+                        outln!("{}All tests passed!", prefix);
                     } else {
-                        println!("{}{} test(s) FAILED", prefix, info.tests_run - info.tests_passed);
+                        outln!("{}{} test(s) FAILED", prefix, info.tests_run - info.tests_passed);
                     }
-                }
-            },
-
-            Commands::Espnow { action } => match action {
-                EspnowAction::Status => {
-                    let status = commands::espnow_status(transport)?;
-                    println!("{}ESP-NOW Status:", prefix);
-                    println!("{}  State:      {}", prefix, status.discovery_state);
-                    println!("{}  Channel:    {}", prefix, status.channel);
-                    println!("{}  Peers:      {}", prefix, status.peer_count);
-                    println!("{}  TX packets: {}", prefix, status.tx_count);
-                    println!("{}  RX packets: {}", prefix, status.rx_count);
-                    println!("{}  TX fails:   {}", prefix, status.tx_fail_count);
+                });
+            }
+        },
+
+        Commands::Espnow { action } => match action {
+            EspnowAction::Status => {
+                let status = commands::espnow_status(transport)?;
+                let peers: Vec<Value> = status
+                    .peers
+                    .iter()
+                    .map(|peer| {
+                        json!({
+                            "mac": mac_string(&peer.mac),
+                            "rssi": peer.rssi,
+                            "last_seen_ms": peer.last_seen_ms,
+                        })
+                    })
+                    .collect();
+                let value = json!({
+                    "state": status.discovery_state,
+                    "channel": status.channel,
+                    "peer_count": status.peer_count,
+                    "tx_count": status.tx_count,
+                    "rx_count": status.rx_count,
+                    "tx_fail_count": status.tx_fail_count,
+                    "last_rtt_us": status.last_rtt_us,
+                    "peers": peers,
+                });
+                printer.result(value, &mut || {
+                    outln!("{}ESP-NOW Status:", prefix);
+                    outln!("{}  State:      {}", prefix, status.discovery_state);
+                    outln!("{}  Channel:    {}", prefix, status.channel);
+                    outln!("{}  Peers:      {}", prefix, status.peer_count);
+                    outln!("{}  TX packets: {}", prefix, status.tx_count);
+                    outln!("{}  RX packets: {}", prefix, status.rx_count);
+                    outln!("{}  TX fails:   {}", prefix, status.tx_fail_count);
                     if status.last_rtt_us > 0 {
-                        println!("{}  Last RTT:   {} us", prefix, status.last_rtt_us);
+                        outln!("{}  Last RTT:   {} us", prefix, status.last_rtt_us);
                     }
                     if !status.peers.is_empty() {
-                        println!("{}  Discovered peers:", prefix);
-                        println!("{}    {:<20} {:>6} {:>10}", prefix, "MAC", "RSSI", "LAST SEEN");
-                        println!("{}    {:-<20} {:->6} {:->10}", prefix, "", "", "");
+                        outln!("{}  Discovered peers:", prefix);
+                        outln!("{}    {:<20} {:>6} {:>10}", prefix, "MAC", "RSSI", "LAST SEEN");
+                        outln!("{}    {:-<20} {:->6} {:->10}", prefix, "", "", "");
                         for peer in &status.peers {
-                            println!("{}    {:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}   {:>4} {:>8} ms",
-                                prefix,
-                                peer.mac[0], peer.mac[1], peer.mac[2],
-                                peer.mac[3], peer.mac[4], peer.mac[5],
-                                peer.rssi, peer.last_seen_ms);
+                            outln!("{}    {}   {:>4} {:>8} ms",
+                                prefix, mac_string(&peer.mac), peer.rssi, peer.last_seen_ms);
                         }
                     }
-                }
-                EspnowAction::Bench { rounds } => {
-                    println!("{}Running ESP-NOW latency benchmark ({} rounds)...", prefix, rounds);
-                    let result = commands::espnow_bench(transport, *rounds)?;
-                    println!("{}ESP-NOW Benchmark Results:", prefix);
-                    println!("{}  Rounds:     {}/{} completed ({} failed)",
+                });
+            }
+            EspnowAction::Bench { rounds } => {
+                progress!("Running ESP-NOW latency benchmark ({} rounds)...", rounds);
+                let result = commands::espnow_bench(transport, *rounds)?;
+                let value = json!({
+                    "rounds_completed": result.rounds_completed,
+                    "rounds_failed": result.rounds_failed,
+                    "min_rtt_us": result.min_rtt_us,
+                    "max_rtt_us": result.max_rtt_us,
+                    "mean_rtt_us": result.mean_rtt_us,
+                    "p50_rtt_us": result.p50_rtt_us,
+                    "p95_rtt_us": result.p95_rtt_us,
+                    "p99_rtt_us": result.p99_rtt_us,
+                });
+                printer.result(value, &mut || {
+                    outln!("{}ESP-NOW Benchmark Results:", prefix);
+                    outln!("{}  Rounds:     {}/{} completed ({} failed)",
                         prefix, result.rounds_completed,
                         result.rounds_completed + result.rounds_failed,
                         result.rounds_failed);
                     if result.rounds_completed > 0 {
-                        println!("{}  Min RTT:    {} us ({:.2} ms)",
+                        outln!("{}  Min RTT:    {} us ({:.2} ms)",
                             prefix, result.min_rtt_us, result.min_rtt_us as f64 / 1000.0);
-                        println!("{}  Max RTT:    {} us ({:.2} ms)",
+                        outln!("{}  Max RTT:    {} us ({:.2} ms)",
                             prefix, result.max_rtt_us, result.max_rtt_us as f64 / 1000.0);
-                        println!("{}  Mean RTT:   {} us ({:.2} ms)",
+                        outln!("{}  Mean RTT:   {} us ({:.2} ms)",
                             prefix, result.mean_rtt_us, result.mean_rtt_us as f64 / 1000.0);
-                        println!("{}  P50 RTT:    {} us ({:.2} ms)",
+                        outln!("{}  P50 RTT:    {} us ({:.2} ms)",
                             prefix, result.p50_rtt_us, result.p50_rtt_us as f64 / 1000.0);
-                        println!("{}  P95 RTT:    {} us ({:.2} ms)",
+                        outln!("{}  P95 RTT:    {} us ({:.2} ms)",
                             prefix, result.p95_rtt_us, result.p95_rtt_us as f64 / 1000.0);
-                        println!("{}  P99 RTT:    {} us ({:.2} ms)",
+                        outln!("{}  P99 RTT:    {} us ({:.2} ms)",
                             prefix, result.p99_rtt_us, result.p99_rtt_us as f64 / 1000.0);
                     }
-                }
-                EspnowAction::SimMode { state, delay_ms, pad } => {
-                    let enabled = state == "on";
-                    let result = commands::espnow_sim_mode(transport, enabled, *delay_ms, *pad)?;
-                    println!("{}Sim mode: {}", prefix, if result.enabled { "ON" } else { "OFF" });
+                });
+            }
+            EspnowAction::SimMode { state, delay_ms, pad } => {
+                let enabled = state == "on";
+                let result = commands::espnow_sim_mode(transport, enabled, *delay_ms, *pad)?;
+                let value = json!({
+                    "enabled": result.enabled,
+                    "delay_ms": result.delay_ms,
+                    "pad": result.pad_index,
+                });
+                printer.result(value, &mut || {
+                    outln!("{}Sim mode: {}", prefix, if result.enabled { "ON" } else { "OFF" });
                     if result.enabled {
-                        println!("{}  Delay:  {} ms", prefix, result.delay_ms);
-                        println!("{}  Pad:    {}", prefix, result.pad_index);
+                        outln!("{}  Delay:  {} ms", prefix, result.delay_ms);
+                        outln!("{}  Pad:    {}", prefix, result.pad_index);
                     }
+                });
+            }
+        },
+
+        Commands::Touch { action } => match action {
+            TouchAction::Simulate { pad } => {
+                commands::touch_simulate(transport, *pad)?;
+                printer.result(json!({ "pad": pad }), &mut || {
+                    outln!("{}Injected touch on pad {}", prefix, pad)
+                });
+            }
+        },
+
+        Commands::Events { action } => match action {
+            EventsAction::Subscribe { category } => {
+                let mask = commands::events::parse_event_categories(category)?;
+                commands::events_listen(transport, mask, interrupted, |notification| {
+                    printer.result(event_json(notification), &mut || {
+                        outln!("{}{}", prefix, format_event(notification))
+                    });
+                })?;
+            }
+        },
+
+        Commands::Raw { msg_type, payload } => {
+            let msg_type = commands::raw::parse_msg_type(msg_type)?;
+            let payload = commands::raw::parse_hex_payload(payload)?;
+            let frame = commands::raw_command(transport, msg_type, &payload)?;
+            let value = json!({
+                "msg_type": frame.msg_type,
+                "payload": hex_string(&frame.payload),
+            });
+            printer.result(value, &mut || {
+                outln!("{}Response type: 0x{:02X}", prefix, frame.msg_type);
+                outln!("{}Payload ({} bytes): {}", prefix, frame.payload.len(), hex_string(&frame.payload));
+            });
+        }
+
+        Commands::Devices { .. }
+        | Commands::Ble { .. }
+        | Commands::Serial { .. }
+        | Commands::Config { .. }
+        | Commands::Completions { .. }
+        | Commands::Proto { .. }
+        | Commands::WaitForDevice { .. }
+        | Commands::Sniff { .. }
+        | Commands::Decode { .. }
+        | Commands::Repl
+        | Commands::Script { .. } => {
+            unreachable!() // Handled above
+        }
+    }
+    Ok(())
+}
+
+/// `ble pair` with each --ble device, or `ble forget`
+fn run_ble(cli: &Cli, action: &BleAction, printer: &dyn Printer) -> anyhow::Result<()> {
+    match action {
+        BleAction::Pair { passkey } => {
+            if cli.ble.is_empty() {
+                return Err(exit::usage("ble pair needs --ble <NAME or ADDRESS>"));
+            }
+            let scan_timeout = cli.timeout.map_or(Duration::from_secs(10), Duration::from_millis);
+            for target in &cli.ble {
+                progress!("Scanning for BLE device '{}'...", target);
+                let mut transport =
+                    BleTransport::connect(BleTarget::parse(target), scan_timeout, false)
+                        .with_context(|| format!("Failed to connect to {}", target))?;
+                if passkey.is_some() {
+                    progress!("Pairing with {} (passkey entry)...", transport.device_name());
+                }
+                transport.pair(*passkey)?;
+                let value = json!({
+                    "name": transport.device_name(),
+                    "address": transport.device_address(),
+                    "paired": true,
+                });
+                printer.result(value, &mut || {
+                    outln!(
+                        "Paired with {} ({})",
+                        transport.device_name(),
+                        transport.device_address()
+                    )
+                });
+            }
+        }
+        BleAction::Forget { device } => {
+            let removed = BleTransport::forget(device)?;
+            printer.result(json!({ "address": device, "removed": removed }), &mut || {
+                if removed {
+                    outln!("Removed bond with {}", device);
+                } else {
+                    outln!("{} is not paired", device);
                 }
-            },
+            });
+        }
+    }
+    Ok(())
+}
+
+fn run_serial(
+    cli: &Cli,
+    dangerous: bool,
+    action: &SerialAction,
+    printer: &dyn Printer,
+) -> anyhow::Result<()> {
+    if cli.port.is_empty() {
+        return Err(exit::usage(format!("serial {} needs --port <PORT>", action.name())));
+    }
+    if !dangerous {
+        return Err(exit::usage(format!(
+            "serial {} reboots the device; pass --dangerous to confirm",
+            action.name()
+        )));
+    }
+    let baud = cli.baud.unwrap_or(DEFAULT_BAUD_RATE);
+    for port in &cli.port {
+        eprintln!("Warning: this will reboot the device on {}", port);
+        let mut transport = SerialTransport::open(port, baud)?;
+        match action {
+            SerialAction::Break { duration_ms } => {
+                transport.send_break(Duration::from_millis(*duration_ms))?
+            }
+            SerialAction::Reset => transport.hard_reset()?,
+            SerialAction::ForceBootloader => transport.enter_bootloader()?,
+        }
+        let value = json!({ "port": port, "action": action.name() });
+        printer.result(value, &mut || match action {
+            SerialAction::ForceBootloader => {
+                outln!("{}: reset into ROM download mode", port)
+            }
+            _ => outln!("{}: reset", port),
+        });
+    }
+    Ok(())
+}
 
-            Commands::Touch { action } => match action {
-                TouchAction::Simulate { pad } => {
-                    commands::touch_simulate(transport, *pad)?;
-                    println!("{}Injected touch on pad {}", prefix, pad);
+/// Connection timeout for `devices list --verbose` probes of devices
+/// without a registry timeout, when --timeout is not given
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Connect to a registry entry and read its system info
+fn probe_device(
+    entry: &device::DeviceEntry,
+    timeout: Duration,
+    baud: Option<u32>,
+) -> anyhow::Result<protocol::CliSystemInfo> {
+    let mut transport = device::connect_device(entry, Some(timeout), baud)?;
+    Ok(commands::system_info(transport.as_mut())?)
+}
+
+/// Print the registry as a table (`devices list`), only the devices with
+/// every one of `tags`
+///
+/// With `probe` (`--verbose`: connection timeout and baud), every device is
+/// probed concurrently and the table gains its live status. A device that
+/// does not answer is marked offline; the others are still listed.
+fn print_device_list(
+    tags: &[String],
+    probe: Option<(Duration, Option<u32>)>,
+    printer: &dyn Printer,
+) -> anyhow::Result<()> {
+    let registry = device::load_device_registry()?;
+    let mut names: Vec<&String> = registry
+        .iter()
+        .filter(|(_, entry)| entry.has_tags(tags))
+        .map(|(name, _)| name)
+        .collect();
+    names.sort();
+
+    let probes: Option<Vec<anyhow::Result<protocol::CliSystemInfo>>> =
+        probe.map(|(timeout, baud)| {
+            if !names.is_empty() {
+                progress!("Probing {} device(s)...", names.len());
+            }
+            std::thread::scope(|s| {
+                let handles: Vec<_> = names
+                    .iter()
+                    .map(|name| {
+                        let entry = &registry[*name];
+                        s.spawn(move || probe_device(entry, timeout, baud))
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|h| h.join().unwrap_or_else(|_| Err(anyhow::anyhow!("probe panicked"))))
+                    .collect()
+            })
+        });
+
+    let value = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let entry = &registry[*name];
+            let mut value = json!({
+                "name": name,
+                "transport": entry.transport_type,
+                "address": entry.address,
+                "timeout_ms": entry.timeout_ms,
+                "connect_retries": entry.connect_retries,
+                "scan_timeout_secs": entry.scan_timeout_secs,
+                "baud": entry.baud,
+                "description": entry.description,
+                "tags": entry.tags.as_deref().unwrap_or_default(),
+            });
+            match probes.as_ref().map(|probes| &probes[i]) {
+                Some(Ok(info)) => {
+                    value["reachable"] = json!(true);
+                    value["firmware_version"] = json!(firmware_version(info));
+                    value["mode"] = json!(info.mode.to_string());
+                    value["pod_id"] = json!(info.pod_id);
+                }
+                Some(Err(e)) => {
+                    value["reachable"] = json!(false);
+                    value["error"] = json!(format!("{:#}", e));
                 }
-            },
+                None => {}
+            }
+            value
+        })
+        .collect();
+    printer.result(Value::Array(value), &mut || {
+        if registry.is_empty() {
+            outln!("No devices registered.");
+            outln!("Use 'domes-cli devices add <name> <transport> <address>' to register.");
+            return;
+        }
+        if names.is_empty() {
+            outln!("No devices tagged {}.", tags.join(" and "));
+            return;
+        }
+        let Some(probes) = &probes else {
+            outln!(
+                "{:<12} {:<10} {:<30} {:<8} DESCRIPTION",
+                "NAME", "TRANSPORT", "ADDRESS", "TIMEOUT"
+            );
+            outln!("{:-<12} {:-<10} {:-<30} {:-<8} {:-<30}", "", "", "", "", "");
+            for name in &names {
+                let entry = &registry[*name];
+                let timeout = entry
+                    .timeout_ms
+                    .map_or_else(|| "default".to_string(), |ms| format!("{} ms", ms));
+                let description = entry.description.as_deref().unwrap_or("");
+                let line = format!(
+                    "{:<12} {:<10} {:<30} {:<8} {}",
+                    name,
+                    entry.transport_type,
+                    entry.address,
+                    timeout,
+                    truncate_description(description)
+                );
+                outln!("{}", line.trim_end());
+            }
+            return;
+        };
+        outln!(
+            "{:<12} {:<10} {:<24} {:<8} {:<12} {:<10} POD",
+            "NAME", "TRANSPORT", "ADDRESS", "STATUS", "FIRMWARE", "MODE"
+        );
+        outln!(
+            "{:-<12} {:-<10} {:-<24} {:-<8} {:-<12} {:-<10} {:-<4}",
+            "", "", "", "", "", "", ""
+        );
+        for (name, probe) in names.iter().zip(probes) {
+            let entry = &registry[*name];
+            match probe {
+                Ok(info) => outln!(
+                    "{:<12} {:<10} {:<24} {:<8} {:<12} {:<10} {}",
+                    name,
+                    entry.transport_type,
+                    entry.address,
+                    "online",
+                    firmware_version(info).unwrap_or("unknown"),
+                    info.mode.to_string(),
+                    info.pod_id
+                ),
+                Err(_) => outln!(
+                    "{:<12} {:<10} {:<24} OFFLINE",
+                    name, entry.transport_type, entry.address
+                ),
+            }
+        }
+        let offline: Vec<(&&String, &anyhow::Error)> = names
+            .iter()
+            .zip(probes)
+            .filter_map(|(name, probe)| probe.as_ref().err().map(|e| (name, e)))
+            .collect();
+        if !offline.is_empty() {
+            outln!();
+            outln!("{} of {} device(s) offline:", offline.len(), names.len());
+            for (name, e) in offline {
+                outln!("  {}: {:#}", name, e);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// `description` cut to the DESCRIPTION column's 30 characters
+fn truncate_description(description: &str) -> String {
+    const WIDTH: usize = 30;
+    if description.chars().count() <= WIDTH {
+        return description.to_string();
+    }
+    let kept: String = description.chars().take(WIDTH - 3).collect();
+    format!("{}...", kept.trim_end())
+}
+
+/// Print `proto list`: message types grouped by namespace, gaps summarized
+fn print_message_types(printer: &dyn Printer) {
+    use commands::proto::Namespace;
 
-            Commands::Devices { .. } | Commands::Sniff { .. } => unreachable!(), // Handled above
+    let types = commands::proto::list_message_types();
+    let value = types
+        .iter()
+        .map(|t| {
+            json!({
+                "namespace": t.namespace.name(),
+                "name": t.name,
+                "value": t.value,
+                "handled": t.handled,
+            })
+        })
+        .collect();
+    printer.result(Value::Array(value), &mut || {
+        for (namespace, title) in [(Namespace::Config, "Config"), (Namespace::Trace, "Trace")] {
+            outln!("{} messages:", title);
+            outln!("  {:<6} {:<28} HANDLER", "VALUE", "NAME");
+            outln!("  {:-<6} {:-<28} {:-<7}", "", "", "");
+            for t in types.iter().filter(|t| t.namespace == namespace) {
+                let handled = if t.handled { "yes" } else { "no" };
+                outln!("  0x{:02X}   {:<28} {}", t.value, t.name, handled);
+            }
+            outln!();
         }
-        Ok(())
-        })();
+        let gaps: Vec<&str> = types.iter().filter(|t| !t.handled).map(|t| t.name).collect();
+        if gaps.is_empty() {
+            outln!("Every message type has a CLI handler");
+        } else {
+            outln!("{} message type(s) without a CLI handler: {}", gaps.len(), gaps.join(", "));
+        }
+    });
+}
 
-        if let Err(e) = result {
-            if multi {
-                eprintln!("{}Error: {:#}", prefix, e);
-                failures.push(dev_label);
+/// Print the frames found by `decode`; fails if none were valid
+fn print_decode_report(
+    report: &commands::decode::DecodeReport,
+    printer: &dyn Printer,
+) -> anyhow::Result<()> {
+    let value = report
+        .frames
+        .iter()
+        .map(|frame| {
+            let fields = frame
+                .fields
+                .iter()
+                .map(|(key, value)| (key.clone(), json!(value)))
+                .collect();
+            json!({
+                "offset": frame.offset,
+                "size": frame.size,
+                "msg_type": frame.msg_type,
+                "msg_name": frame.msg_name,
+                "protocol": frame.protocol,
+                "direction": frame.direction,
+                "payload": hex_string(&frame.payload),
+                "crc_valid": frame.crc_valid(),
+                "fields": Value::Object(fields),
+            })
+        })
+        .collect();
+    printer.result(Value::Array(value), &mut || {
+        for (i, frame) in report.frames.iter().enumerate() {
+            outln!(
+                "Frame {} @ offset {} ({} bytes)",
+                i + 1,
+                frame.offset,
+                frame.size
+            );
+            outln!(
+                "  Type:    0x{:02X} {} ({}, {})",
+                frame.msg_type, frame.msg_name, frame.protocol, frame.direction
+            );
+            outln!(
+                "  Payload: {} ({} bytes)",
+                if frame.payload.is_empty() {
+                    "-".to_string()
+                } else {
+                    hex_string(&frame.payload)
+                },
+                frame.payload.len()
+            );
+            if frame.crc_valid() {
+                outln!("  CRC:     ✓ valid (0x{:08X})", frame.crc_received);
             } else {
-                return Err(e);
+                outln!(
+                    "  CRC:     ✗ mismatch (frame 0x{:08X}, computed 0x{:08X})",
+                    frame.crc_received, frame.crc_computed
+                );
+            }
+            if !frame.fields.is_empty() {
+                outln!("  Fields:");
+                for (key, value) in &frame.fields {
+                    outln!("    {} = {}", key, value);
+                }
             }
+            outln!();
         }
+    });
 
-        if multi {
-            println!(); // Blank line between devices
-        }
+    for (offset, error) in &report.errors {
+        eprintln!("Frame error at offset {}: {}", offset, error);
     }
-
-    if !failures.is_empty() {
+    if report.trailing > 0 {
         eprintln!(
-            "Failed on {} device(s): {}",
-            failures.len(),
-            failures.join(", ")
+            "{} trailing byte(s) did not form a complete frame",
+            report.trailing
         );
-        std::process::exit(1);
     }
 
+    let invalid = report.frames.iter().filter(|f| !f.crc_valid()).count();
+    if report.frames.is_empty() {
+        anyhow::bail!("No DOMES frame found in input");
+    } else if invalid > 0 {
+        anyhow::bail!(
+            "{} of {} frame(s) failed the CRC check",
+            invalid,
+            report.frames.len()
+        );
+    }
     Ok(())
 }
 
-/// Parse hex color string (e.g., "ff0000" or "FF0000") to RGB
-fn parse_hex_color(color: &str) -> anyhow::Result<(u8, u8, u8)> {
-    let color = color.trim_start_matches('#');
-    if color.len() != 6 {
-        anyhow::bail!("Color must be 6 hex characters (e.g., ff0000)");
+/// Format bytes as lowercase hex with no separators
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The file format of a trace dump, rejecting `--append` and `--session-label`
+/// where they need a JSON output file
+fn trace_dump_format(
+    output: &Path,
+    trace_format: Option<&str>,
+    append: bool,
+    session_label: Option<&str>,
+) -> anyhow::Result<commands::TraceFormat> {
+    let format = match trace_format {
+        Some(name) => name.parse().map_err(exit::usage)?,
+        None => commands::TraceFormat::for_path(output),
+    };
+    if format != commands::TraceFormat::Json && (append || session_label.is_some()) {
+        return Err(exit::usage("--append and --session-label need JSON output"));
+    }
+    if append && commands::TraceOutput::from_arg(output) == commands::TraceOutput::Stdout {
+        return Err(exit::usage("--append needs an output file, not stdout"));
+    }
+    Ok(format)
+}
+
+/// Resolve `--output` for a trace dump, giving each device its own file in multi mode
+fn trace_output_for(output: &Path, dev_name: &str, multi: bool) -> anyhow::Result<commands::TraceOutput> {
+    let to_stdout = commands::TraceOutput::from_arg(output) == commands::TraceOutput::Stdout;
+    if to_stdout && multi {
+        return Err(exit::usage("--output - is only supported with a single device"));
     }
+    if !multi {
+        return Ok(commands::TraceOutput::from_arg(output));
+    }
+    Ok(commands::TraceOutput::File(per_device_file(output, dev_name)))
+}
+
+/// `output` with the device name added to the file stem (trace.json -> trace-pod1.json)
+fn per_device_file(output: &Path, dev_name: &str) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = output.extension().unwrap_or_default().to_string_lossy();
+    output.with_file_name(format!("{}-{}.{}", stem, dev_name, ext))
+}
+
+/// Print the trace dump summary, warning if the checksum did not match
+fn print_dump_result(prefix: &str, result: &commands::trace::DumpResult, printer: &dyn Printer) {
+    if !result.checksum_valid {
+        eprintln!("{}Warning: trace checksum mismatch, dump data may be corrupt", prefix);
+    }
+    let checksum_label = if result.checksum_valid {
+        "✓ checksum verified"
+    } else {
+        "✗ checksum mismatch"
+    };
+    let skipped = (result.skipped_count > 0)
+        .then(|| format!("  Skipped: {} events already dumped (--since)", result.skipped_count));
+    let latest = result
+        .max_timestamp_us
+        .map(|ts| format!("Latest event: {} us (next time: --since {})", ts, ts));
+    if result.output == commands::TraceOutput::Stdout {
+        // stdout carries the JSON; keep the summary on stderr
+        progress!("{}Dump complete: {} events (pod_id={}) {}", prefix, result.event_count, result.pod_id, checksum_label);
+        if result.dropped_count > 0 {
+            progress!("{}  Dropped: {} events", prefix, result.dropped_count);
+        }
+        if let Some(skipped) = &skipped {
+            progress!("{}{}", prefix, skipped);
+        }
+        if let Some(latest) = &latest {
+            progress!("{}{}", prefix, latest);
+        }
+    } else {
+        let value = json!({
+            "event_count": result.event_count,
+            "dropped_count": result.dropped_count,
+            "skipped_count": result.skipped_count,
+            "max_timestamp_us": result.max_timestamp_us,
+            "pod_id": result.pod_id,
+            "checksum_valid": result.checksum_valid,
+            "output": result.output.to_string(),
+            "appended_after_us": result.appended_after_us,
+        });
+        printer.result(value, &mut || {
+            outln!("{}Dump complete: {} events (pod_id={}) {}", prefix, result.event_count, result.pod_id, checksum_label);
+            if result.dropped_count > 0 {
+                outln!("{}  Dropped: {} events", prefix, result.dropped_count);
+            }
+            if let Some(skipped) = &skipped {
+                outln!("{}{}", prefix, skipped);
+            }
+            outln!("{}Output: {}", prefix, result.output);
+            if let Some(offset) = result.appended_after_us {
+                outln!("{}  Appended after the existing trace (offset {} us)", prefix, offset);
+            }
+            if let Some(latest) = &latest {
+                outln!("{}{}", prefix, latest);
+            }
+        });
+    }
+}
+
+/// Parse a feature name as accepted by `feature enable/disable`
+fn parse_feature(name: &str) -> anyhow::Result<Feature> {
+    name.parse()
+        .map_err(|_| anyhow::anyhow!("Unknown feature: {}", name))
+}
+
+/// Parse a system mode name as accepted by `system set-mode`
+fn parse_mode(name: &str) -> anyhow::Result<SystemMode> {
+    name.parse().map_err(|_| {
+        exit::usage(format!(
+            "Unknown mode: {}. Valid: idle, triage, connected, game, error",
+            name
+        ))
+    })
+}
+
+/// The state an `--enable`/`--disable` flag pair asks for
+fn enable_flag(enable: bool, disable: bool) -> anyhow::Result<bool> {
+    match (enable, disable) {
+        (true, true) => Err(exit::usage("Cannot specify both --enable and --disable")),
+        (true, false) => Ok(true),
+        (false, true) => Ok(false),
+        (false, false) => Err(exit::usage("Must specify either --enable or --disable")),
+    }
+}
+
+/// The pattern an LED action sets, or None for actions that only read
+/// (get) or depend on the current pattern (brightness)
+fn requested_led_pattern(action: &LedAction) -> anyhow::Result<Option<protocol::CliLedPattern>> {
+    let pattern = match action {
+        LedAction::Get { .. }
+        | LedAction::Brightness { .. }
+        | LedAction::Test { .. }
+        | LedAction::Export { .. } => return Ok(None),
+        LedAction::Off => protocol::CliLedPattern::off(),
+        LedAction::Import { file } => protocol::CliLedPattern::from_json_file(file)?,
+        LedAction::Solid { color, brightness } => {
+            let (r, g, b) = parse_hex_color(color)?;
+            let mut pattern = protocol::CliLedPattern::solid(r, g, b);
+            pattern.brightness = *brightness;
+            pattern
+        }
+        LedAction::Breathing {
+            color,
+            period,
+            brightness,
+        } => {
+            let (r, g, b) = parse_hex_color(color)?;
+            let mut pattern = protocol::CliLedPattern::breathing(r, g, b, *period);
+            pattern.brightness = *brightness;
+            pattern
+        }
+        LedAction::Cycle { period, colors, brightness } => {
+            let colors = if colors.is_empty() {
+                vec![
+                    (255, 0, 0, 0),
+                    (255, 127, 0, 0),
+                    (255, 255, 0, 0),
+                    (0, 255, 0, 0),
+                    (0, 0, 255, 0),
+                    (75, 0, 130, 0),
+                    (148, 0, 211, 0),
+                ]
+            } else {
+                if colors.len() > protocol::LED_CYCLE_MAX_COLORS {
+                    return Err(exit::usage(format!(
+                        "Too many colors: {} (firmware supports at most {})",
+                        colors.len(),
+                        protocol::LED_CYCLE_MAX_COLORS
+                    )));
+                }
+                colors
+                    .iter()
+                    .map(|c| parse_color(c))
+                    .collect::<anyhow::Result<Vec<_>>>()?
+            };
+            let mut pattern = protocol::CliLedPattern::color_cycle(colors, *period);
+            pattern.brightness = *brightness;
+            pattern
+        }
+    };
+    Ok(Some(pattern))
+}
+
+/// Parse hex color string to RGB: "rrggbb" or CSS shorthand "rgb" (each
+/// digit doubled, so "f00" is "ff0000"), with or without a leading '#'
+fn parse_hex_color(color: &str) -> anyhow::Result<(u8, u8, u8)> {
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    // from_str_radix would also take a sign, e.g. "+1+2+3"
+    let is_hex = hex.chars().all(|c| c.is_ascii_hexdigit());
+    let expanded: String = match hex.len() {
+        3 if is_hex => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 if is_hex => hex.to_string(),
+        _ => {
+            return Err(exit::usage(format!(
+                "Invalid color '{}': expected 3 or 6 hex characters (rgb, rrggbb, #rgb or #rrggbb)",
+                color
+            )))
+        }
+    };
 
-    let r = u8::from_str_radix(&color[0..2], 16)
-        .map_err(|_| anyhow::anyhow!("Invalid red component"))?;
-    let g = u8::from_str_radix(&color[2..4], 16)
-        .map_err(|_| anyhow::anyhow!("Invalid green component"))?;
-    let b = u8::from_str_radix(&color[4..6], 16)
-        .map_err(|_| anyhow::anyhow!("Invalid blue component"))?;
+    let r = u8::from_str_radix(&expanded[0..2], 16)
+        .map_err(|_| exit::usage("Invalid red component"))?;
+    let g = u8::from_str_radix(&expanded[2..4], 16)
+        .map_err(|_| exit::usage("Invalid green component"))?;
+    let b = u8::from_str_radix(&expanded[4..6], 16)
+        .map_err(|_| exit::usage("Invalid blue component"))?;
 
     Ok((r, g, b))
 }
 
+/// Parse an RGBW color: anything `parse_hex_color` accepts (white 0), or
+/// "rrggbbww" with a white channel
+fn parse_color(color: &str) -> anyhow::Result<(u8, u8, u8, u8)> {
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    let (rgb, w) = match hex.len() {
+        8 if hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+            let w = u8::from_str_radix(&hex[6..8], 16)
+                .map_err(|_| exit::usage("Invalid white component"))?;
+            (&hex[..6], w)
+        }
+        3 | 6 => (hex, 0),
+        _ => {
+            return Err(exit::usage(format!(
+                "Invalid color '{}': expected 3, 6 or 8 hex characters (rgb, rrggbb or rrggbbww)",
+                color
+            )))
+        }
+    };
+    let (r, g, b) = parse_hex_color(rgb)?;
+    Ok((r, g, b, w))
+}
+
 /// Print LED pattern in a human-readable format
-fn print_led_pattern(pattern: &crate::protocol::CliLedPattern) {
-    use crate::proto::config::LedPatternType;
+fn led_type_name(pattern: &protocol::CliLedPattern) -> &'static str {
+    use domes_core::proto::config::LedPatternType;
 
-    let type_name = match pattern.pattern_type {
+    match pattern.pattern_type {
         LedPatternType::LedPatternOff => "off",
         LedPatternType::LedPatternSolid => "solid",
         LedPatternType::LedPatternBreathing => "breathing",
         LedPatternType::LedPatternColorCycle => "color-cycle",
+    }
+}
+
+/// Format a pushed event as one line: device timestamp, category, new state
+fn format_event(notification: &protocol::CliEventNotification) -> String {
+    use protocol::CliEvent;
+
+    let detail = match &notification.event {
+        CliEvent::Mode(mode) => format!("mode     {}", mode),
+        CliEvent::Led(pattern) => {
+            let color = pattern
+                .color
+                .map(|(r, g, b, _)| format!(" #{:02x}{:02x}{:02x}", r, g, b))
+                .unwrap_or_default();
+            format!(
+                "led      {}{} brightness {}",
+                led_type_name(pattern),
+                color,
+                pattern.brightness
+            )
+        }
+        CliEvent::Wifi { connected } => format!(
+            "wifi     {}",
+            if *connected { "connected" } else { "disconnected" }
+        ),
+        CliEvent::Feature(state) => format!(
+            "feature  {} {}",
+            state.feature.cli_name(),
+            if state.enabled { "enabled" } else { "disabled" }
+        ),
+        CliEvent::Touch { pad_index } => format!("touch    pad {}", pad_index),
+    };
+    format!(
+        "[{:>9.3}s] {}",
+        notification.timestamp_ms as f64 / 1000.0,
+        detail
+    )
+}
+
+/// Format an RGBW color as "rrggbb", or "rrggbbww" when the white channel is set
+fn color_hex((r, g, b, w): (u8, u8, u8, u8)) -> String {
+    if w == 0 {
+        format!("{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        format!("{:02x}{:02x}{:02x}{:02x}", r, g, b, w)
+    }
+}
+
+/// A protobuf color as "#rrggbbww" and its channels; a channel above 255
+/// (which the LEDs cannot show) is flagged
+fn raw_color(color: &domes_core::proto::config::Color) -> String {
+    let mut text = format!(
+        "#{:02x}{:02x}{:02x}{:02x} (r={} g={} b={} w={})",
+        color.r, color.g, color.b, color.w, color.r, color.g, color.b, color.w
+    );
+    if [color.r, color.g, color.b, color.w].iter().any(|&c| c > 255) {
+        text.push_str(" out of range");
+    }
+    text
+}
+
+/// Every field of an LED pattern as the device sent it (`led get --raw`)
+fn format_raw_led_pattern(pattern: &domes_core::proto::config::LedPattern) -> Vec<String> {
+    use domes_core::proto::config::LedPatternType;
+
+    let type_name = LedPatternType::try_from(pattern.r#type)
+        .map(|t| t.as_str_name())
+        .unwrap_or("unknown");
+    let mut lines = vec![format!("type:       {} ({})", pattern.r#type, type_name)];
+    match &pattern.color {
+        Some(color) => lines.push(format!("color:      {}", raw_color(color))),
+        None => lines.push("color:      (not set)".to_string()),
+    }
+    lines.push(format!("colors:     {} entries", pattern.colors.len()));
+    for (i, color) in pattern.colors.iter().enumerate() {
+        lines.push(format!("  [{}]       {}", i, raw_color(color)));
+    }
+    lines.push(format!("period_ms:  {}", pattern.period_ms));
+    lines.push(format!("brightness: {}", pattern.brightness));
+    lines
+}
+
+/// JSON form of `led get --raw`: the protobuf fields, unconverted
+fn raw_led_pattern_json(pattern: &domes_core::proto::config::LedPattern) -> Value {
+    let color = |c: &domes_core::proto::config::Color| {
+        json!({ "r": c.r, "g": c.g, "b": c.b, "w": c.w })
+    };
+    json!({
+        "type": pattern.r#type,
+        "color": pattern.color.as_ref().map(color),
+        "colors": pattern.colors.iter().map(color).collect::<Vec<_>>(),
+        "period_ms": pattern.period_ms,
+        "brightness": pattern.brightness,
+    })
+}
+
+/// Format a MAC address as colon-separated uppercase hex
+fn mac_string(mac: &[u8; 6]) -> String {
+    mac.iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// JSON form of a feature state: `{"feature": "ble", "enabled": true}`
+fn feature_json(state: &protocol::CliFeatureState) -> Value {
+    json!({ "feature": state.feature.cli_name(), "enabled": state.enabled })
+}
+
+/// JSON form of an LED pattern, with colors in the hex form `led` accepts
+fn led_pattern_json(pattern: &protocol::CliLedPattern) -> Value {
+    let mut value = json!({ "type": led_type_name(pattern) });
+    if let Some(color) = pattern.color {
+        value["color"] = json!(color_hex(color));
+    }
+    if !pattern.colors.is_empty() {
+        value["colors"] = pattern.colors.iter().map(|&c| color_hex(c)).collect();
+    }
+    value["period_ms"] = json!(pattern.period_ms);
+    value["brightness"] = json!(pattern.brightness);
+    value
+}
+
+/// JSON form of a pushed event: device timestamp, category and new state
+fn event_json(notification: &protocol::CliEventNotification) -> Value {
+    use protocol::CliEvent;
+
+    let (category, detail) = match &notification.event {
+        CliEvent::Mode(mode) => ("mode", json!({ "mode": mode.to_string() })),
+        CliEvent::Led(pattern) => ("led", json!({ "pattern": led_pattern_json(pattern) })),
+        CliEvent::Wifi { connected } => ("wifi", json!({ "connected": connected })),
+        CliEvent::Feature(state) => ("feature", feature_json(state)),
+        CliEvent::Touch { pad_index } => ("touch", json!({ "pad": pad_index })),
     };
+    let mut value = json!({
+        "timestamp_ms": notification.timestamp_ms,
+        "category": category,
+    });
+    if let (Value::Object(value), Value::Object(detail)) = (&mut value, detail) {
+        value.extend(detail);
+    }
+    value
+}
 
-    println!("  Type:       {}", type_name);
+fn print_led_pattern(pattern: &protocol::CliLedPattern) {
+    outln!("  Type:       {}", led_type_name(pattern));
 
     if let Some((r, g, b, w)) = pattern.color {
-        println!("  Color:      #{:02x}{:02x}{:02x} (RGBW: {},{},{},{})", r, g, b, r, g, b, w);
+        outln!("  Color:      #{:02x}{:02x}{:02x} (RGBW: {},{},{},{})", r, g, b, r, g, b, w);
     }
 
     if !pattern.colors.is_empty() {
-        println!("  Colors:     {} colors in cycle", pattern.colors.len());
+        outln!("  Colors:     {} colors in cycle", pattern.colors.len());
+    }
+
+    outln!("  Period:     {} ms", pattern.period_ms);
+    outln!("  Brightness: {}", pattern.brightness);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        // Catches clashing flags (e.g. a subcommand -v shadowing --verbose)
+        <Cli as clap::CommandFactory>::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_color_formats() {
+        assert_eq!(parse_color("f00").unwrap(), (255, 0, 0, 0));
+        assert_eq!(parse_color("#00ff00").unwrap(), (0, 255, 0, 0));
+        assert_eq!(parse_color("0000ff80").unwrap(), (0, 0, 255, 128));
+        assert_eq!(parse_color("ABC").unwrap(), (170, 187, 204, 0));
+    }
+
+    #[test]
+    fn test_raw_led_pattern_shows_every_color() {
+        use domes_core::proto::config::{Color, LedPattern};
+
+        let pattern = LedPattern {
+            r#type: 3,
+            color: None,
+            colors: vec![
+                Color { r: 255, g: 0, b: 0, w: 0 },
+                Color { r: 0, g: 300, b: 0, w: 16 },
+            ],
+            period_ms: 2000,
+            brightness: 128,
+        };
+        assert_eq!(
+            format_raw_led_pattern(&pattern),
+            [
+                "type:       3 (LED_PATTERN_COLOR_CYCLE)",
+                "color:      (not set)",
+                "colors:     2 entries",
+                "  [0]       #ff000000 (r=255 g=0 b=0 w=0)",
+                "  [1]       #0012c0010 (r=0 g=300 b=0 w=16) out of range",
+                "period_ms:  2000",
+                "brightness: 128",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pod_id_needs_exactly_one_match() {
+        let port = |p: &str| ("serial", p.to_string());
+        let found = single_pod_match(3, vec![port("/dev/ttyACM1")], "2 serial port(s)").unwrap();
+        assert_eq!(found, port("/dev/ttyACM1"));
+
+        let none = single_pod_match(3, Vec::new(), "2 serial port(s) and BLE").unwrap_err();
+        assert_eq!(exit::code_for(&none), exit::CONNECTION);
+        let message = format!("{:#}", none);
+        assert_eq!(message, "No pod with ID 3 found: searched 2 serial port(s) and BLE");
+
+        let both = vec![port("/dev/ttyACM0"), ("ble", "AA:BB:CC:DD:EE:FF".to_string())];
+        let err = single_pod_match(3, both, "2 serial port(s) and BLE").unwrap_err();
+        assert_eq!(exit::code_for(&err), exit::USAGE);
+        assert!(err.to_string().contains("(/dev/ttyACM0, AA:BB:CC:DD:EE:FF)"));
+    }
+
+    #[test]
+    fn test_pod_id_conflicts_with_explicit_targets() {
+        let parse = |args: &[&str]| {
+            let argv = ["domes-cli", "--pod-id", "3"].iter().chain(args);
+            Cli::try_parse_from(argv).err().map(|e| e.kind())
+        };
+        assert_eq!(parse(&["feature", "list"]), None);
+        let conflict = Some(clap::error::ErrorKind::ArgumentConflict);
+        for target in [&["--port", "/dev/ttyACM0"][..], &["--ble", "pod"], &["--wifi", "x:5000"]] {
+            assert_eq!(parse(&[target, &["feature", "list"]].concat()), conflict);
+        }
+        assert_eq!(parse(&["--all", "feature", "list"]), conflict);
+    }
+
+    #[test]
+    fn test_watch_only_read_commands() {
+        let command = |args: &[&str]| {
+            let argv = ["domes-cli"].iter().chain(args);
+            Cli::parse_from(argv).command.unwrap()
+        };
+        assert!(is_read_only(&command(&["feature", "list"])));
+        assert!(is_read_only(&command(&["trace", "status"])));
+        assert!(is_read_only(&command(&["system", "memory"])));
+        assert!(!is_read_only(&command(&["led", "solid"])));
+        assert!(!is_read_only(&command(&["system", "set-pod-id", "3"])));
+    }
+
+    #[test]
+    fn test_bad_arguments_exit_with_usage_before_connecting() {
+        let validate = |args: &[&str]| {
+            let argv = ["domes-cli"].iter().chain(args);
+            validate_command(&Cli::parse_from(argv).command.unwrap())
+        };
+        let code = |args: &[&str]| exit::code_for(&validate(args).unwrap_err());
+
+        assert_eq!(code(&["led", "solid", "--color", "zz"]), exit::USAGE);
+        assert_eq!(code(&["led", "breathing", "--color", "ff00"]), exit::USAGE);
+        let nine_colors = "f00,0f0,00f,fff,000,f0f,0ff,ff0,888";
+        assert_eq!(code(&["led", "cycle", "--colors", nine_colors]), exit::USAGE);
+        assert_eq!(code(&["system", "set-mode", "sleep"]), exit::USAGE);
+        assert_eq!(code(&["imu", "triage"]), exit::USAGE);
+        assert_eq!(code(&["imu", "triage", "--enable", "--disable"]), exit::USAGE);
+        assert_eq!(code(&["trace", "dump", "-o", "-", "--append"]), exit::USAGE);
+        let labeled_proto = ["trace", "dump", "-o", "t.pftrace", "--session-label", "a"];
+        assert_eq!(code(&labeled_proto), exit::USAGE);
+
+        assert!(validate(&["led", "solid", "--color", "f00"]).is_ok());
+        assert!(validate(&["system", "set-mode", "game"]).is_ok());
+
+        let batch = parse_batch_command("led brightness 10").err().unwrap();
+        assert_eq!(exit::code_for(&batch), exit::USAGE);
+        let batch = parse_batch_command("led solid --bogus").err().unwrap();
+        assert_eq!(exit::code_for(&batch), exit::USAGE);
+    }
+
+    #[test]
+    fn test_parse_batch_command() {
+        let command = parse_batch_command("led solid --color 'ff0000'").unwrap();
+        let (msg_type, payload) = batch_request(&command).unwrap();
+        assert_eq!(msg_type, protocol::ConfigMsgType::SetLedPatternReq as u8);
+        assert!(!payload.is_empty());
+
+        let command = parse_batch_command("feature enable ble").unwrap();
+        assert_eq!(
+            batch_request(&command).unwrap().0,
+            protocol::ConfigMsgType::SetFeatureReq as u8
+        );
+
+        // Multi-round-trip and non-config commands are rejected up front
+        assert!(parse_batch_command("led brightness 10").is_err());
+        assert!(parse_batch_command("system health").is_err());
+        assert!(parse_batch_command("feature enable nope").is_err());
+    }
+
+    #[test]
+    fn test_with_retries_stops_on_success() {
+        let mut calls = 0;
+        let result = with_retries(2, &AtomicBool::new(false), || {
+            calls += 1;
+            if calls < 2 {
+                anyhow::bail!("flaky")
+            }
+            Ok(())
+        });
+        assert!(result.is_ok());
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn test_with_retries_gives_up() {
+        let mut calls = 0;
+        let result = with_retries(2, &AtomicBool::new(false), || {
+            calls += 1;
+            anyhow::bail!("offline")
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+
+        // An abort from another device stops retrying immediately
+        calls = 0;
+        let _ = with_retries(2, &AtomicBool::new(true), || {
+            calls += 1;
+            anyhow::bail!("offline")
+        });
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_truncate_description() {
+        assert_eq!(truncate_description("Garage"), "Garage");
+        let long = "Living room pod, on the shelf next to the big window";
+        assert_eq!(truncate_description(long), "Living room pod, on the she...");
+        assert_eq!(truncate_description(&"é".repeat(30)), "é".repeat(30));
+    }
+
+    #[test]
+    fn test_parse_hex_color_shorthand() {
+        assert_eq!(parse_hex_color("f00").unwrap(), (255, 0, 0));
+        assert_eq!(parse_hex_color("0f0").unwrap(), (0, 255, 0));
+        assert_eq!(parse_hex_color("00f").unwrap(), (0, 0, 255));
+        assert_eq!(parse_hex_color("fff").unwrap(), (255, 255, 255));
+        assert_eq!(parse_hex_color("000").unwrap(), (0, 0, 0));
+        assert_eq!(parse_hex_color("abc").unwrap(), (170, 187, 204));
+        assert_eq!(parse_hex_color("#abc").unwrap(), (170, 187, 204));
+        assert_eq!(parse_hex_color("ff8000").unwrap(), (255, 128, 0));
+        assert_eq!(parse_hex_color("#FF8000").unwrap(), (255, 128, 0));
+
+        let signed = ["+1+2+3", "#+1+2+3", "+f+", "-1-2-3"];
+        for bad in ["", "ff", "ff00", "ff00000", "##f00", "fé0", "gg0000"].iter().chain(&signed) {
+            assert!(parse_hex_color(bad).is_err(), "{}", bad);
+        }
+        let err = parse_hex_color("ff00").unwrap_err().to_string();
+        assert!(err.contains("rgb, rrggbb, #rgb or #rrggbb"), "{}", err);
     }
 
-    println!("  Period:     {} ms", pattern.period_ms);
-    println!("  Brightness: {}", pattern.brightness);
+    #[test]
+    fn test_parse_color_rejects_invalid() {
+        assert!(parse_color("").is_err());
+        assert!(parse_color("ff00").is_err());
+        assert!(parse_color("gg0000").is_err());
+        assert!(parse_color("fé0").is_err());
+        assert!(parse_color("ff0000zz").is_err());
+        assert!(parse_color("##f00").is_err());
+        assert!(parse_color("#+1+2+3").is_err());
+        assert!(parse_color("ff0000+1").is_err());
+        assert_eq!(parse_color("#0000ff80").unwrap(), (0, 0, 255, 128));
+    }
+
+    #[test]
+    fn test_mode_entered_from() {
+        let mode = |mode, time_in_mode_ms, previous_mode| protocol::CliModeInfo {
+            mode,
+            time_in_mode_ms,
+            previous_mode,
+        };
+        let idle = mode(SystemMode::Idle, 5000, None);
+        assert_eq!(mode_entered_from(&idle, &mode(SystemMode::Idle, 6000, None)), None);
+        assert_eq!(
+            mode_entered_from(&idle, &mode(SystemMode::Triage, 10, None)),
+            Some(Some(SystemMode::Idle))
+        );
+        // Left and came back between polls
+        assert_eq!(mode_entered_from(&idle, &mode(SystemMode::Idle, 10, None)), Some(None));
+        assert_eq!(
+            mode_entered_from(&idle, &mode(SystemMode::Idle, 10, Some(SystemMode::Game))),
+            Some(Some(SystemMode::Game))
+        );
+    }
+
+    #[test]
+    fn test_signal_bar() {
+        assert_eq!(signal_bar(-30), "██████████");
+        assert_eq!(signal_bar(-70), "█████░░░░░");
+        assert_eq!(signal_bar(-100), "░░░░░░░░░░");
+        assert_eq!(signal_bar(i16::MIN), "░░░░░░░░░░");
+    }
 }
@@ -0,0 +1,819 @@
+//! Command result output
+//!
+//! Results go to stdout through `outln!`. With `--parallel`, each worker
+//! thread captures its lines into a per-device buffer instead, and the main
+//! thread prints the buffers in device order once every device has finished,
+//! so output from different devices never interleaves.
+//!
+//! Each result goes through a `Printer`: `TablePrinter` writes the
+//! human-readable lines, `JsonPrinter` (`--format json`) writes one JSON
+//! document per result instead.
+//!
+//! `ota flash` collects its JSON results with `ReportPrinter` and prints them
+//! as one array covering every device.
+//!
+//! `system info --format prometheus` collects each device's info with
+//! `PrometheusPrinter` and prints the metrics once every device has
+//! answered, since Prometheus wants each metric's samples in one group.
+//!
+//! `--diff` output is rendered here as a device-per-column comparison table,
+//! `system tasks` output as a task table with stack warnings, and
+//! `system partitions` output as a table with a flash layout chart.
+
+use domes_core::error::DomesError;
+pub use domes_core::progress::format_bytes;
+use domes_core::progress::format_bytes_human;
+use domes_core::proto::config::{PartitionType, TaskState};
+use domes_core::protocol::{PartitionInfo, TaskInfo};
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Buffer of captured output lines for one device
+pub type OutputBuffer = Arc<Mutex<Vec<String>>>;
+
+/// Cleared by `color_output = false` in the config file or `--no-color`
+static COLOR: AtomicBool = AtomicBool::new(true);
+
+/// Enable or disable colored output
+pub fn set_color(enabled: bool) {
+    COLOR.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether to color stdout: allowed by the config and `--no-color`, a
+/// terminal, and no `NO_COLOR`
+pub fn use_color() -> bool {
+    COLOR.load(Ordering::Relaxed)
+        && std::io::stdout().is_terminal()
+        && std::env::var_os("NO_COLOR").is_none()
+}
+
+thread_local! {
+    static CAPTURE: RefCell<Option<OutputBuffer>> = const { RefCell::new(None) };
+}
+
+/// Capture `outln!` output on the current thread into `buffer`
+pub fn capture_into(buffer: OutputBuffer) {
+    CAPTURE.with(|c| *c.borrow_mut() = Some(buffer));
+}
+
+/// Stop capturing on the current thread; `outln!` prints to stdout again
+pub fn end_capture() {
+    CAPTURE.with(|c| *c.borrow_mut() = None);
+}
+
+/// Write a result line to the capture buffer, or stdout if not capturing
+pub fn write_line(line: String) {
+    CAPTURE.with(|c| match &*c.borrow() {
+        Some(buffer) => buffer.lock().unwrap().push(line),
+        None => println!("{}", line),
+    });
+}
+
+/// `println!` replacement for command results
+macro_rules! outln {
+    () => {
+        $crate::output::write_line(String::new())
+    };
+    ($($arg:tt)*) => {
+        $crate::output::write_line(format!($($arg)*))
+    };
+}
+
+/// Output format selected with `--format` (or `output_format` in the config file)
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// Human-readable tables and messages
+    #[default]
+    Table,
+    /// One JSON document per line, for scripts
+    Json,
+    /// One device name per line (`devices list` only, used by shell completion)
+    #[serde(skip)]
+    NamesOnly,
+    /// Prometheus text exposition format (`system info` only)
+    #[serde(skip)]
+    Prometheus,
+}
+
+impl Format {
+    /// Parse a default format from the config file or `DOMES_FORMAT`
+    /// (`table` or `json`; `names-only` and `prometheus` are for one
+    /// command only)
+    pub fn parse_default(value: &str) -> Option<Format> {
+        match clap::ValueEnum::from_str(value.trim(), true) {
+            Ok(Format::NamesOnly | Format::Prometheus) | Err(_) => None,
+            Ok(format) => Some(format),
+        }
+    }
+
+    /// Printer for one device's results; `device` is set in multi-device runs
+    pub fn printer(self, device: Option<&str>) -> Box<dyn Printer> {
+        match self {
+            Format::Table | Format::NamesOnly => Box::new(TablePrinter {
+                prefix: device.map(domes_core::device::device_prefix).unwrap_or_default(),
+            }),
+            Format::Json => Box::new(JsonPrinter {
+                device: device.map(str::to_string),
+            }),
+            Format::Prometheus => Box::new(PrometheusPrinter {
+                device: device.map(str::to_string),
+            }),
+        }
+    }
+}
+
+/// Writes command results and errors in the selected output format
+pub trait Printer: Send + Sync {
+    /// Print one command result
+    ///
+    /// `value` is the structured form of the result; `table` writes the
+    /// human-readable form with `outln!`. A printer uses one or the other.
+    fn result(&self, value: Value, table: &mut dyn FnMut());
+
+    /// Print the error a command failed with
+    fn error(&self, error: &anyhow::Error);
+
+    /// Whether output is for people (device headers, blank separator lines)
+    fn is_table(&self) -> bool;
+}
+
+/// Human-readable output (the default)
+pub struct TablePrinter {
+    prefix: String,
+}
+
+impl Printer for TablePrinter {
+    fn result(&self, _value: Value, table: &mut dyn FnMut()) {
+        table();
+    }
+
+    fn error(&self, error: &anyhow::Error) {
+        eprintln!("{}Error: {:#}", self.prefix, error);
+    }
+
+    fn is_table(&self) -> bool {
+        true
+    }
+}
+
+/// JSON Lines output: each result (or error) is one JSON value on one line
+///
+/// In multi-device runs each line is wrapped as
+/// `{"device": <name>, "result": <value>}` so the lines can be told apart.
+pub struct JsonPrinter {
+    device: Option<String>,
+}
+
+impl JsonPrinter {
+    fn write(&self, key: &str, value: Value) {
+        let line = match &self.device {
+            Some(device) => json!({ "device": device, key: value }),
+            None if key == "result" => value,
+            None => json!({ key: value }),
+        };
+        write_line(line.to_string());
+    }
+}
+
+impl Printer for JsonPrinter {
+    fn result(&self, value: Value, _table: &mut dyn FnMut()) {
+        self.write("result", value);
+    }
+
+    fn error(&self, error: &anyhow::Error) {
+        self.write("error", Value::String(format!("{:#}", error)));
+    }
+
+    fn is_table(&self) -> bool {
+        false
+    }
+}
+
+/// JSON results collected across devices, printed as one array by `write_report`
+pub type JsonReport = Arc<Mutex<Vec<Value>>>;
+
+/// Collects each result (or error) as one entry of a `JsonReport`
+///
+/// A result object gets a leading `"device"` field. An error becomes
+/// `{"device", "status": "failed", "error"}`; a refused OTA update reports
+/// the device's OTA status instead, and the image `offset` it stopped at.
+pub struct ReportPrinter {
+    device: String,
+    report: JsonReport,
+}
+
+impl ReportPrinter {
+    pub fn new(device: &str, report: JsonReport) -> Self {
+        Self {
+            device: device.to_string(),
+            report,
+        }
+    }
+}
+
+impl Printer for ReportPrinter {
+    fn result(&self, value: Value, _table: &mut dyn FnMut()) {
+        let mut entry = serde_json::Map::new();
+        entry.insert("device".to_string(), json!(self.device));
+        match value {
+            Value::Object(fields) => entry.extend(fields),
+            value => {
+                entry.insert("result".to_string(), value);
+            }
+        }
+        self.report.lock().unwrap().push(Value::Object(entry));
+    }
+
+    fn error(&self, error: &anyhow::Error) {
+        let mut entry = json!({
+            "device": self.device,
+            "status": "failed",
+            "error": format!("{:#}", error),
+        });
+        if let Some(DomesError::OtaRejected { status, offset, .. }) = error.downcast_ref() {
+            entry["status"] = json!(status.cli_name());
+            entry["offset"] = json!(offset);
+        }
+        self.report.lock().unwrap().push(entry);
+    }
+
+    fn is_table(&self) -> bool {
+        false
+    }
+}
+
+/// Print the collected entries as a single JSON array
+pub fn write_report(report: &JsonReport) {
+    let entries = std::mem::take(&mut *report.lock().unwrap());
+    write_line(Value::Array(entries).to_string());
+}
+
+/// `system info` results waiting for `write_metrics`: (device, result)
+static METRICS: Mutex<Vec<(Option<String>, Value)>> = Mutex::new(Vec::new());
+
+/// Collects `system info` results for `write_metrics`; errors go to stderr
+pub struct PrometheusPrinter {
+    device: Option<String>,
+}
+
+impl Printer for PrometheusPrinter {
+    fn result(&self, value: Value, _table: &mut dyn FnMut()) {
+        METRICS.lock().unwrap().push((self.device.clone(), value));
+    }
+
+    fn error(&self, error: &anyhow::Error) {
+        let prefix = self.device.as_deref().map(domes_core::device::device_prefix);
+        eprintln!("{}Error: {:#}", prefix.unwrap_or_default(), error);
+    }
+
+    fn is_table(&self) -> bool {
+        false
+    }
+}
+
+/// Print the collected `system info` results as Prometheus metrics
+pub fn write_metrics() {
+    let results = std::mem::take(&mut *METRICS.lock().unwrap());
+    if !results.is_empty() {
+        for line in format_prometheus(&results) {
+            write_line(line);
+        }
+    }
+}
+
+/// Gauges from `system info`: (metric, JSON field, help text)
+const GAUGES: [(&str, &str, &str); 3] = [
+    ("domes_free_heap_bytes", "free_heap", "Free heap on the pod in bytes"),
+    ("domes_uptime_seconds", "uptime_s", "Time since the pod booted in seconds"),
+    ("domes_boot_count", "boot_count", "Number of times the pod has booted"),
+];
+
+/// Render `system info` results in the Prometheus text exposition format
+///
+/// Every sample is labelled with the pod ID, and with the device name in
+/// multi-device runs. Counters older firmware does not report are left out.
+fn format_prometheus(results: &[(Option<String>, Value)]) -> Vec<String> {
+    let labels = |device: &Option<String>, info: &Value| {
+        let mut labels = format!("pod=\"{}\"", info["pod_id"]);
+        if let Some(device) = device {
+            labels.push_str(&format!(",device=\"{}\"", escape_label(device)));
+        }
+        labels
+    };
+
+    let mut lines = Vec::new();
+    for (metric, field, help) in GAUGES {
+        lines.push(format!("# HELP {} {}", metric, help));
+        lines.push(format!("# TYPE {} gauge", metric));
+        for (device, info) in results {
+            if let Some(value) = info[field].as_u64() {
+                lines.push(format!("{}{{{}}} {}", metric, labels(device, info), value));
+            }
+        }
+    }
+    lines.push("# HELP domes_mode Current system mode of the pod (always 1)".to_string());
+    lines.push("# TYPE domes_mode gauge".to_string());
+    for (device, info) in results {
+        let mode = info["mode"].as_str().unwrap_or("unknown");
+        lines.push(format!(
+            "domes_mode{{{},mode=\"{}\"}} 1",
+            labels(device, info),
+            escape_label(mode)
+        ));
+    }
+    lines
+}
+
+/// Escape a Prometheus label value
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Per-device field values for `--diff`: (device name, [(field, value)])
+pub type DiffRow = (String, Vec<(String, String)>);
+
+const YELLOW: &str = "\x1b[33m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+/// Format a comparison table with one column per device and one row per field
+///
+/// Rows where the devices disagree are marked with `*` and, when stdout is a
+/// terminal, their cells are highlighted in yellow. A device that did not
+/// report a field shows `-`.
+pub fn format_diff_table(rows: &[DiffRow]) -> String {
+    render_diff_table(rows, use_color())
+}
+
+fn render_diff_table(rows: &[DiffRow], color: bool) -> String {
+    // Fields in first-seen order across all devices
+    let mut fields: Vec<&str> = Vec::new();
+    for (_, values) in rows {
+        for (field, _) in values {
+            if !fields.contains(&field.as_str()) {
+                fields.push(field);
+            }
+        }
+    }
+
+    let lookup = |values: &[(String, String)], field: &str| -> String {
+        values
+            .iter()
+            .find(|(f, _)| f == field)
+            .map_or_else(|| "-".to_string(), |(_, v)| v.clone())
+    };
+
+    let field_width = fields
+        .iter()
+        .map(|f| width(f))
+        .chain(std::iter::once(width("FIELD")))
+        .max()
+        .unwrap_or(0);
+    let column_widths: Vec<usize> = rows
+        .iter()
+        .map(|(name, values)| {
+            fields
+                .iter()
+                .map(|f| width(&lookup(values, f)))
+                .chain(std::iter::once(width(name)))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut out = String::new();
+    let mut push_line = |gutter: &str, cells: Vec<String>| {
+        let line = format!("{}{}", gutter, cells.join("  "));
+        out.push_str(line.trim_end());
+        out.push('\n');
+    };
+
+    let mut header = vec![pad("FIELD", field_width)];
+    header.extend(rows.iter().zip(&column_widths).map(|((name, _), &w)| pad(name, w)));
+    push_line("  ", header);
+
+    let mut rule = vec!["-".repeat(field_width)];
+    rule.extend(column_widths.iter().map(|&w| "-".repeat(w)));
+    push_line("  ", rule);
+
+    for field in &fields {
+        let values: Vec<String> = rows.iter().map(|(_, v)| lookup(v, field)).collect();
+        let differs = values.iter().any(|v| *v != values[0]);
+
+        let mut cells = vec![pad(field, field_width)];
+        for (value, &w) in values.iter().zip(&column_widths) {
+            let cell = pad(value, w);
+            if differs && color {
+                cells.push(format!("{}{}{}", YELLOW, cell, RESET));
+            } else {
+                cells.push(cell);
+            }
+        }
+        push_line(if differs { "* " } else { "  " }, cells);
+    }
+
+    out
+}
+
+/// How close a stack watermark is to `threshold`: `low` below it, `critical`
+/// below half of it
+pub fn stack_warning(watermark_bytes: u32, threshold: Option<u32>) -> Option<&'static str> {
+    let threshold = threshold?;
+    if watermark_bytes < threshold / 2 {
+        Some("critical")
+    } else if watermark_bytes < threshold {
+        Some("low")
+    } else {
+        None
+    }
+}
+
+/// Format `system tasks` output, one row per task
+///
+/// With `warn_watermark`, a WARNING column flags tasks low on stack (see
+/// `stack_warning`); on a terminal their rows are yellow, or red if critical.
+pub fn format_task_table(tasks: &[TaskInfo], warn_watermark: Option<u32>) -> String {
+    render_task_table(tasks, warn_watermark, use_color())
+}
+
+fn render_task_table(tasks: &[TaskInfo], warn_watermark: Option<u32>, color: bool) -> String {
+    let mut out = format!(
+        "{:<16} {:<9} {:>3} {:>7} {:>6} {:>4}",
+        "NAME", "STATE", "PRI", "STACK", "CPU%", "CORE"
+    );
+    let mut rule = format!("{:-<16} {:-<9} {:->3} {:->7} {:->6} {:->4}", "", "", "", "", "", "");
+    if warn_watermark.is_some() {
+        out.push_str(" WARNING");
+        rule.push_str(" -------");
+    }
+    out.push('\n');
+    out.push_str(&rule);
+    out.push('\n');
+
+    for task in tasks {
+        let core = task.core_id.map_or_else(|| "-".to_string(), |c| c.to_string());
+        let mut line = format!(
+            "{:<16} {:<9} {:>3} {:>7} {:>6.1} {:>4}",
+            task.name,
+            task_state_name(task.state),
+            task.priority,
+            task.stack_watermark_bytes,
+            task.runtime_percent,
+            core
+        );
+        let warning = stack_warning(task.stack_watermark_bytes, warn_watermark);
+        if let Some(warning) = warning {
+            line.push(' ');
+            line.push_str(warning);
+        }
+        match warning {
+            Some("critical") if color => out.push_str(&format!("{}{}{}", RED, line, RESET)),
+            Some(_) if color => out.push_str(&format!("{}{}{}", YELLOW, line, RESET)),
+            _ => out.push_str(&line),
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Short lowercase task state for tables and JSON (`running`, `blocked`, ...)
+pub fn task_state_name(state: TaskState) -> &'static str {
+    match state {
+        TaskState::Unknown => "unknown",
+        TaskState::Running => "running",
+        TaskState::Ready => "ready",
+        TaskState::Blocked => "blocked",
+        TaskState::Suspended => "suspended",
+        TaskState::Deleted => "deleted",
+    }
+}
+
+/// Width of the `system partitions` layout chart, in characters
+const LAYOUT_WIDTH: usize = 48;
+
+/// Format `system partitions` output: a table, then a chart of where each
+/// partition sits in flash
+///
+/// The running app partition is marked with `*`, and green on a terminal.
+pub fn format_partition_table(partitions: &[PartitionInfo]) -> String {
+    render_partition_table(partitions, use_color())
+}
+
+fn render_partition_table(partitions: &[PartitionInfo], color: bool) -> String {
+    let name_width = partitions
+        .iter()
+        .map(|p| width(&p.name))
+        .chain(std::iter::once(width("NAME")))
+        .max()
+        .unwrap_or(0);
+    let mut lines = vec![
+        format!(
+            "  {:<name_width$} {:<8} {:<4} {:<10} {:>10} FLAGS",
+            "NAME", "TYPE", "SUB", "OFFSET", "SIZE"
+        ),
+        format!(
+            "  {:-<name_width$} {:-<8} {:-<4} {:-<10} {:->10} {:-<9}",
+            "", "", "", "", "", ""
+        ),
+    ];
+    for p in partitions {
+        let mut flags = Vec::new();
+        if p.active {
+            flags.push("active");
+        }
+        if p.encrypted {
+            flags.push("encrypted");
+        }
+        let line = format!(
+            "{} {:<name_width$} {:<8} 0x{:02x} 0x{:08x} {:>10} {}",
+            if p.active { "*" } else { " " },
+            p.name,
+            partition_type_name(p.type_),
+            p.subtype,
+            p.offset,
+            format_bytes_human(p.size as u64),
+            flags.join(",")
+        );
+        lines.push(highlight_active(line.trim_end().to_string(), p.active, color));
+    }
+
+    // One row per partition, its bar spanning its share of the flash used
+    let end = partitions
+        .iter()
+        .map(|p| p.offset as u64 + p.size as u64)
+        .max()
+        .unwrap_or(0);
+    if end > 0 {
+        lines.push(String::new());
+        let end_label = format!("0x{:x}", end);
+        lines.push(format!(
+            "  {:<name_width$}  {:<w$}{}",
+            "",
+            "0x0",
+            end_label,
+            w = LAYOUT_WIDTH.saturating_sub(end_label.len())
+        ));
+        let column = |address: u64| (address * LAYOUT_WIDTH as u64 / end) as usize;
+        for p in partitions {
+            let start = column(p.offset as u64).min(LAYOUT_WIDTH - 1);
+            let stop = column(p.offset as u64 + p.size as u64).clamp(start + 1, LAYOUT_WIDTH);
+            let bar = format!(
+                "{}{}{}",
+                " ".repeat(start),
+                "#".repeat(stop - start),
+                " ".repeat(LAYOUT_WIDTH - stop)
+            );
+            let line = format!(
+                "{} {:<name_width$} |{}| {}",
+                if p.active { "*" } else { " " },
+                p.name,
+                bar,
+                format_bytes_human(p.size as u64)
+            );
+            lines.push(highlight_active(line, p.active, color));
+        }
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+fn highlight_active(line: String, active: bool, color: bool) -> String {
+    if active && color {
+        format!("{}{}{}", GREEN, line, RESET)
+    } else {
+        line
+    }
+}
+
+/// Short lowercase partition type for tables and JSON (`app`, `nvs`, ...)
+pub fn partition_type_name(type_: PartitionType) -> &'static str {
+    match type_ {
+        PartitionType::Unknown => "unknown",
+        PartitionType::App => "app",
+        PartitionType::Data => "data",
+        PartitionType::Nvs => "nvs",
+        PartitionType::Otadata => "otadata",
+        PartitionType::Phy => "phy",
+        PartitionType::Coredump => "coredump",
+        PartitionType::Spiffs => "spiffs",
+        PartitionType::Fat => "fat",
+    }
+}
+
+fn width(s: &str) -> usize {
+    s.chars().count()
+}
+
+fn pad(s: &str, w: usize) -> String {
+    format!("{}{}", s, " ".repeat(w.saturating_sub(width(s))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domes_core::commands::ota::OtaStatus;
+
+    fn row(name: &str, values: &[(&str, &str)]) -> DiffRow {
+        (
+            name.to_string(),
+            values
+                .iter()
+                .map(|(f, v)| (f.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    fn captured(printer: &dyn Printer, print: impl FnOnce(&dyn Printer)) -> Vec<String> {
+        let buffer = OutputBuffer::default();
+        capture_into(buffer.clone());
+        print(printer);
+        end_capture();
+        let lines = buffer.lock().unwrap().clone();
+        lines
+    }
+
+    #[test]
+    fn test_prometheus_metrics_grouped_by_metric() {
+        let info = |pod_id: u32, mode: &str, free_heap: Option<u32>| {
+            json!({ "pod_id": pod_id, "mode": mode, "uptime_s": 60, "free_heap": free_heap,
+                    "boot_count": 4 })
+        };
+        let results = vec![
+            (Some("pod1".to_string()), info(1, "idle", Some(123456))),
+            (Some("pod\"2".to_string()), info(2, "game", None)),
+        ];
+        let lines = format_prometheus(&results);
+        assert_eq!(
+            lines[..4],
+            [
+                "# HELP domes_free_heap_bytes Free heap on the pod in bytes",
+                "# TYPE domes_free_heap_bytes gauge",
+                r#"domes_free_heap_bytes{pod="1",device="pod1"} 123456"#,
+                "# HELP domes_uptime_seconds Time since the pod booted in seconds",
+            ]
+        );
+        assert_eq!(lines[6], r#"domes_uptime_seconds{pod="2",device="pod\"2"} 60"#);
+        assert_eq!(lines[lines.len() - 2], r#"domes_mode{pod="1",device="pod1",mode="idle"} 1"#);
+
+        let single = format_prometheus(&[(None, info(3, "idle", Some(1)))]);
+        assert!(single.contains(&r#"domes_boot_count{pod="3"} 4"#.to_string()));
+    }
+
+    #[test]
+    fn test_json_printer_results_and_errors() {
+        let single = Format::Json.printer(None);
+        let lines = captured(single.as_ref(), |p| {
+            p.result(json!([{"feature": "ble", "enabled": true}]), &mut || {
+                outln!("table output")
+            });
+            p.error(&anyhow::anyhow!("Device rejected OTA_BEGIN: Busy"));
+        });
+        assert_eq!(
+            lines,
+            [
+                r#"[{"feature":"ble","enabled":true}]"#,
+                r#"{"error":"Device rejected OTA_BEGIN: Busy"}"#,
+            ]
+        );
+
+        let multi = Format::Json.printer(Some("pod1"));
+        let lines = captured(multi.as_ref(), |p| p.result(json!({"pod_id": 3}), &mut || {}));
+        assert_eq!(lines, [r#"{"device":"pod1","result":{"pod_id":3}}"#]);
+    }
+
+    #[test]
+    fn test_report_printer_collects_one_array() {
+        let report = JsonReport::default();
+        let pod1 = ReportPrinter::new("pod1", report.clone());
+        let pod2 = ReportPrinter::new("pod2", report.clone());
+        let lines = captured(&pod1, |p| {
+            p.result(json!({"bytes": 1024, "status": "ok"}), &mut || {});
+            pod2.error(&anyhow::Error::new(DomesError::OtaRejected {
+                status: OtaStatus::OffsetMismatch,
+                offset: 4096,
+                message: "Device rejected chunk at offset 4096".to_string(),
+            }));
+            pod2.error(&anyhow::anyhow!("Timeout waiting for OTA response"));
+            write_report(&report);
+        });
+        assert_eq!(
+            lines,
+            [concat!(
+                r#"[{"device":"pod1","bytes":1024,"status":"ok"},"#,
+                r#"{"device":"pod2","status":"offset-mismatch","#,
+                r#""error":"Device rejected chunk at offset 4096","offset":4096},"#,
+                r#"{"device":"pod2","status":"failed","error":"Timeout waiting for OTA response"}]"#,
+            )]
+        );
+    }
+
+    #[test]
+    fn test_table_printer_runs_table() {
+        let printer = Format::Table.printer(None);
+        let lines = captured(printer.as_ref(), |p| {
+            p.result(json!({}), &mut || outln!("Pod ID set to 3"))
+        });
+        assert_eq!(lines, ["Pod ID set to 3"]);
+    }
+
+    #[test]
+    fn test_diff_table_marks_differences() {
+        let rows = [
+            row("pod1", &[("Firmware", "v1.2.0"), ("Pod ID", "1")]),
+            row("living-room-pod", &[("Firmware", "v1.2.0"), ("Pod ID", "2")]),
+        ];
+        let table = render_diff_table(&rows, false);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "  FIELD     pod1    living-room-pod");
+        assert_eq!(lines[1], "  --------  ------  ---------------");
+        assert_eq!(lines[2], "  Firmware  v1.2.0  v1.2.0");
+        assert_eq!(lines[3], "* Pod ID    1       2");
+    }
+
+    #[test]
+    fn test_task_table_stack_warnings() {
+        let task = |name: &str, watermark: u32| TaskInfo {
+            name: name.to_string(),
+            state: TaskState::Blocked,
+            priority: 5,
+            stack_watermark_bytes: watermark,
+            runtime_percent: 1.25,
+            core_id: Some(0),
+        };
+        let tasks = [task("ok", 2048), task("tight", 900), task("danger", 400)];
+        assert_eq!(stack_warning(2048, None), None);
+
+        let table = render_task_table(&tasks, Some(1024), true);
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0].ends_with("CORE WARNING"));
+        assert_eq!(lines[2], "ok               blocked     5    2048    1.2    0");
+        assert_eq!(
+            lines[3],
+            format!("{}tight            blocked     5     900    1.2    0 low{}", YELLOW, RESET)
+        );
+        assert!(lines[4].starts_with(RED) && lines[4].contains(" critical"));
+
+        // Without a threshold nothing is flagged
+        let plain = render_task_table(&tasks, None, true);
+        assert!(!plain.contains(YELLOW) && !plain.contains("WARNING"));
+    }
+
+    #[test]
+    fn test_partition_table_layout() {
+        let part = |name: &str, type_, subtype, offset, size, active| PartitionInfo {
+            name: name.to_string(),
+            type_,
+            subtype,
+            offset,
+            size,
+            encrypted: false,
+            active,
+        };
+        let partitions = [
+            part("nvs", PartitionType::Nvs, 0x02, 0x9000, 0x6000, false),
+            part("otadata", PartitionType::Otadata, 0x00, 0xf000, 0x2000, false),
+            part("ota_0", PartitionType::App, 0x10, 0x20000, 0x180000, true),
+            part("ota_1", PartitionType::App, 0x11, 0x1a0000, 0x180000, false),
+        ];
+        let table = render_partition_table(&partitions, false);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[2], "  nvs     nvs      0x02 0x00009000    24.0 KB");
+        assert_eq!(lines[4], "* ota_0   app      0x10 0x00020000     1.5 MB active");
+        assert_eq!(lines[7], format!("           0x0{}0x320000", " ".repeat(37)));
+        assert_eq!(lines[8], format!("  nvs     |#{}| 24.0 KB", " ".repeat(47)));
+        assert_eq!(
+            lines[10],
+            format!("* ota_0   | {}{}| 1.5 MB", "#".repeat(23), " ".repeat(24))
+        );
+
+        let colored = render_partition_table(&partitions, true);
+        assert_eq!(colored.matches(GREEN).count(), 2);
+    }
+
+    #[test]
+    fn test_diff_table_missing_field_and_color() {
+        let rows = [
+            row("a", &[("wifi", "enabled"), ("ble", "enabled")]),
+            row("b", &[("wifi", "enabled")]),
+        ];
+        let table = render_diff_table(&rows, true);
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(!lines[2].contains(YELLOW));
+        assert_eq!(
+            lines[3],
+            format!("* ble    {}enabled{}  {}-      {}", YELLOW, RESET, YELLOW, RESET)
+        );
+    }
+}
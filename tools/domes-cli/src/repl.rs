@@ -0,0 +1,218 @@
+//! `repl`: interactive session over an open connection
+//!
+//! Each line is parsed as the subcommand part of a normal invocation
+//! (`led solid --color ff0000`) and runs through the same code path, on the
+//! devices that were connected when the session started. Line editing and
+//! history come from rustyline; history persists in `~/.domes/history`.
+//!
+//! Errors are printed and the session continues. Ctrl-C stops the running
+//! command (or clears the line); `exit`, `quit` or Ctrl-D ends the session.
+//!
+//! `--script` runs its lines through the same `parse_line`/`run_command`.
+
+use crate::{Commands, RunOptions, RunSummary};
+use anyhow::Context;
+use clap::{CommandFactory, Parser};
+use domes_core::device::DeviceConnection;
+use domes_core::{commands, progress};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// One line typed at the prompt: a subcommand, without connection flags
+#[derive(Parser)]
+#[command(name = "", no_binary_name = true)]
+struct ReplLine {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+/// Split `line` shell-style and parse it as a subcommand
+///
+/// The inner error is clap's, for `--help` output and usage errors. A line
+/// that is blank or only a `#` comment has no words and fails to parse.
+pub fn parse_line(line: &str) -> anyhow::Result<Result<Commands, clap::Error>> {
+    let words = shlex::split(line).with_context(|| format!("Invalid quoting: {}", line))?;
+    Ok(ReplLine::try_parse_from(words).map(|parsed| parsed.command))
+}
+
+/// Run one parsed line on the session's devices
+///
+/// As with `run_once`, a multi-device failure is in the summary rather than
+/// the error.
+pub fn run_command(
+    command: &Commands,
+    devices: &mut [DeviceConnection],
+    opts: &RunOptions,
+    interrupted: &AtomicBool,
+) -> anyhow::Result<RunSummary> {
+    match command {
+        Commands::Repl
+        | Commands::Sniff { .. }
+        | Commands::Devices { .. }
+        | Commands::Config { .. }
+        | Commands::Completions { .. }
+        | Commands::WaitForDevice { .. } => {
+            anyhow::bail!("This command is not available in a session; run it on its own")
+        }
+        Commands::Decode { hex } => {
+            let bytes = commands::raw::parse_hex_bytes(&hex.join(" "))?;
+            let report = commands::decode::decode_bytes(&bytes);
+            crate::print_decode_report(&report, opts.format.printer(None).as_ref())?;
+            return Ok(RunSummary::default());
+        }
+        Commands::Proto { .. } => {
+            crate::print_message_types(opts.format.printer(None).as_ref());
+            return Ok(RunSummary::default());
+        }
+        _ => {}
+    }
+    crate::validate_command(command)?;
+
+    // A Ctrl-C from the previous command must not stop this one
+    interrupted.store(false, Ordering::Relaxed);
+    crate::run_once(command, devices, opts, interrupted)
+}
+
+/// Run the interactive session until `exit`, `quit` or Ctrl-D
+pub fn run(
+    devices: &mut [DeviceConnection],
+    opts: &RunOptions,
+    interrupted: &AtomicBool,
+) -> anyhow::Result<()> {
+    let mut editor = DefaultEditor::new().context("Failed to start line editor")?;
+    let history = history_path();
+    // Missing on first use
+    let _ = editor.load_history(&history);
+
+    let mut prompt = prompt(devices);
+    progress!("Type 'help' for commands, 'exit' to leave.");
+
+    loop {
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            // Ctrl-C at the prompt only clears the line
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e).context("Failed to read input"),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        match line {
+            "exit" | "quit" => break,
+            "help" => print_help(),
+            "history" => {
+                for (i, entry) in editor.history().iter().enumerate() {
+                    println!("{:>4}  {}", i + 1, entry);
+                }
+            }
+            _ => match run_line(line, devices, opts, interrupted) {
+                Ok(renamed) => {
+                    if renamed {
+                        prompt = self::prompt(devices);
+                    }
+                }
+                Err(e) => opts.format.printer(None).error(&e),
+            },
+        }
+    }
+
+    if let Some(dir) = history.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Err(e) = editor.save_history(&history) {
+        eprintln!("Warning: failed to save {}: {}", history.display(), e);
+    }
+    Ok(())
+}
+
+/// Parse and run one line; returns true if the pod ID changed
+fn run_line(
+    line: &str,
+    devices: &mut [DeviceConnection],
+    opts: &RunOptions,
+    interrupted: &AtomicBool,
+) -> anyhow::Result<bool> {
+    let command = match parse_line(line)? {
+        Ok(command) => command,
+        Err(e) => {
+            // Usage errors and `<command> --help` output
+            e.print()?;
+            return Ok(false);
+        }
+    };
+    run_command(&command, devices, opts, interrupted)?.report();
+
+    Ok(matches!(
+        command,
+        Commands::System {
+            action: crate::SystemAction::SetPodId { .. }
+        }
+    ))
+}
+
+/// `domes[pod-3]> ` for one device (from its system info), else the device names
+fn prompt(devices: &mut [DeviceConnection]) -> String {
+    let label = match devices {
+        [dev] => match commands::system_info(dev.transport.as_mut()) {
+            Ok(info) if info.pod_id != 0 => format!("pod-{}", info.pod_id),
+            _ => crate::device_label(&dev.name),
+        },
+        _ => devices
+            .iter()
+            .map(|dev| crate::device_label(&dev.name))
+            .collect::<Vec<_>>()
+            .join(","),
+    };
+    format!("domes[{}]> ", label)
+}
+
+fn print_help() {
+    println!("Run any device command without the connection flags, e.g.:");
+    println!("  led solid --color ff0000");
+    println!("  feature list");
+    println!();
+    println!("Session commands:");
+    println!("  help              Show this help ('<command> --help' for details)");
+    println!("  history           List previous lines");
+    println!("  exit, quit        Leave the session (or Ctrl-D)");
+    println!();
+    println!("Device commands:");
+    for sub in ReplLine::command().get_subcommands() {
+        let name = sub.get_name();
+        if matches!(
+            name,
+            "repl" | "sniff" | "devices" | "config" | "completions" | "wait-for-device" | "help"
+        ) {
+            continue;
+        }
+        let about = sub.get_about().map(|a| a.to_string()).unwrap_or_default();
+        println!("  {:<16}  {}", name, about);
+    }
+}
+
+fn history_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".domes").join("history")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repl_line_is_a_subcommand() {
+        let command = parse_line("led solid --color 'ff0000'").unwrap().unwrap();
+        assert!(matches!(command, Commands::Led { .. }));
+
+        // Connection flags belong to the session, not to each line
+        assert!(parse_line("--port /dev/ttyACM0 feature list").unwrap().is_err());
+        assert!(parse_line("bogus").unwrap().is_err());
+        assert!(parse_line("led solid --color 'ff0000").is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! `--script`: run a file of commands, one subcommand per line
+//!
+//! Lines are split shell-style, so quoted arguments work and `#` starts a
+//! comment; blank and comment-only lines are skipped. Every line is parsed
+//! before anything is sent, so a typo late in the file is caught up front.
+//! The process exits with the number of failed lines.
+
+use crate::repl;
+use crate::{Commands, RunOptions};
+use anyhow::Context;
+use domes_core::device::DeviceConnection;
+use domes_core::progress;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// What to do when a script line fails (`--on-error`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OnError {
+    /// Stop at the first failed line
+    #[default]
+    Abort,
+    /// Run the remaining lines anyway
+    Continue,
+}
+
+/// One command from a script
+pub struct ScriptLine {
+    /// 1-based line number in the file
+    pub number: usize,
+    /// The line as written, for messages
+    pub text: String,
+    pub command: Commands,
+}
+
+/// Read a script from `path` (`-` for stdin)
+pub fn read_script(path: &str) -> anyhow::Result<String> {
+    if path == "-" {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .context("Failed to read script from stdin")?;
+        return Ok(text);
+    }
+    std::fs::read_to_string(path).with_context(|| format!("Failed to read script {}", path))
+}
+
+/// Parse every command line of `text`
+///
+/// Returns the parsed lines and the number that failed to parse; each
+/// failure is reported as it is found.
+pub fn parse_script(text: &str) -> (Vec<ScriptLine>, usize) {
+    let mut lines = Vec::new();
+    let mut failed = 0;
+    for (i, line) in text.lines().enumerate() {
+        let number = i + 1;
+        let text = line.trim();
+        // Blank or comment only: nothing left after splitting
+        if shlex::split(text).is_some_and(|words| words.is_empty()) {
+            continue;
+        }
+        match repl::parse_line(text) {
+            Ok(Ok(command)) => lines.push(ScriptLine {
+                number,
+                text: text.to_string(),
+                command,
+            }),
+            Ok(Err(e)) => {
+                // clap's message without its usage footer
+                let message = e.to_string();
+                let message = message.lines().next().unwrap_or_default();
+                report_failure(number, text, message.trim_start_matches("error: "));
+                failed += 1;
+            }
+            Err(e) => {
+                report_failure(number, text, &format!("{:#}", e));
+                failed += 1;
+            }
+        }
+    }
+    (lines, failed)
+}
+
+/// Print each line that would run (`--dry-run`)
+pub fn print_dry_run(lines: &[ScriptLine]) {
+    for line in lines {
+        println!("{:>4}  {}", line.number, line.text);
+    }
+}
+
+/// Run each line on the devices; returns the number of failed lines
+///
+/// Stops after the first failure with `OnError::Abort`, and after a line
+/// interrupted by Ctrl-C.
+pub fn run(
+    lines: &[ScriptLine],
+    on_error: OnError,
+    devices: &mut [DeviceConnection],
+    opts: &RunOptions,
+    interrupted: &AtomicBool,
+) -> usize {
+    let mut failed = 0;
+    for line in lines {
+        progress!("Line {}: {}", line.number, line.text);
+        let ok = match repl::run_command(&line.command, devices, opts, interrupted) {
+            Ok(summary) => {
+                summary.report();
+                summary.failures.is_empty()
+            }
+            Err(e) => {
+                report_failure(line.number, &line.text, &format!("{:#}", e));
+                false
+            }
+        };
+        if !ok {
+            failed += 1;
+            if on_error == OnError::Abort {
+                break;
+            }
+        }
+        if interrupted.load(Ordering::Relaxed) {
+            progress!("Interrupted, skipping the rest of the script");
+            break;
+        }
+    }
+    failed
+}
+
+fn report_failure(number: usize, text: &str, error: &str) {
+    eprintln!("Error on line {}: {}", number, text);
+    eprintln!("  {}", error);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_script_skips_comments_and_counts_errors() {
+        let script = "\
+# set up the pod
+feature enable ble
+
+led solid --color '#ff0000'   # quoted, so not a comment
+feature frobnicate
+system info
+";
+        let (lines, failed) = parse_script(script);
+        let numbers: Vec<usize> = lines.iter().map(|l| l.number).collect();
+        assert_eq!(numbers, [2, 4, 6]);
+        assert!(matches!(lines[1].command, Commands::Led { .. }));
+        assert_eq!(failed, 1);
+    }
+}
@@ -0,0 +1,286 @@
+//! `--watch`: re-run a read command on an interval and redraw its output
+//!
+//! On a terminal the output is drawn in place on the alternate screen, under
+//! a header with the invocation on the left and a "last updated" clock in the
+//! top-right corner; `q` (or Ctrl-C) exits. When stdout is not a terminal, or
+//! with `--format json`, each refresh is appended instead so the stream can
+//! be read by scripts.
+//!
+//! With several devices the output is laid out as one panel per device (see
+//! `layout_panels`).
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Produces one refresh of the watched output
+pub type Render<'a> = Box<dyn FnMut() -> anyhow::Result<String> + 'a>;
+
+/// Panels narrower than this wrap onto the next row
+const MIN_PANEL_WIDTH: usize = 40;
+
+/// Re-runs `render` every `interval` until `q`, Ctrl-C or `interrupted`
+pub struct WatchRunner<'a> {
+    /// Header text; `None` prints the output alone (JSON streams)
+    pub title: Option<String>,
+    pub interval: Duration,
+    /// Redraw in place on the alternate screen instead of appending
+    pub redraw: bool,
+    pub render: Render<'a>,
+}
+
+impl WatchRunner<'_> {
+    /// Loop until the user quits
+    ///
+    /// A failed refresh is shown in place of the output and does not stop
+    /// the loop, so a device that drops off comes back once it recovers.
+    pub fn run(mut self, interrupted: &AtomicBool) -> anyhow::Result<()> {
+        let _screen = if self.redraw {
+            Some(AlternateScreen::enter()?)
+        } else {
+            None
+        };
+
+        while !interrupted.load(Ordering::Relaxed) {
+            let deadline = Instant::now() + self.interval;
+            let body = (self.render)().unwrap_or_else(|e| format!("Error: {:#}\n", e));
+            let updated = chrono::Local::now().format("%H:%M:%S").to_string();
+            self.draw(&body, &updated)?;
+            if self.wait_until(deadline, interrupted)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&self, body: &str, updated: &str) -> anyhow::Result<()> {
+        let mut out = std::io::stdout().lock();
+        if self.redraw {
+            queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+        }
+        if let Some(title) = &self.title {
+            let width = self
+                .redraw
+                .then(|| terminal::size().map(|(cols, _)| cols as usize).ok())
+                .flatten();
+            writeln!(out, "{}", header_line(title, updated, width))?;
+            writeln!(out)?;
+        }
+        write!(out, "{}", body)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Sleep until `deadline`; returns true if the user asked to quit
+    fn wait_until(&self, deadline: Instant, interrupted: &AtomicBool) -> anyhow::Result<bool> {
+        if !self.redraw {
+            // Sleep in short steps so Ctrl-C is handled promptly
+            while !interrupted.load(Ordering::Relaxed) {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Ok(false);
+                }
+                std::thread::sleep(remaining.min(Duration::from_millis(50)));
+            }
+            return Ok(true);
+        }
+
+        // Raw mode delivers single key presses (and Ctrl-C as a key); it is
+        // only enabled while waiting so command output renders normally
+        terminal::enable_raw_mode()?;
+        let quit = loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if interrupted.load(Ordering::Relaxed) {
+                break true;
+            }
+            if remaining.is_zero() {
+                break false;
+            }
+            if event::poll(remaining.min(Duration::from_millis(50)))? {
+                if let Event::Key(key) = event::read()? {
+                    if is_quit_key(&key) {
+                        break true;
+                    }
+                }
+            }
+        };
+        terminal::disable_raw_mode()?;
+        Ok(quit)
+    }
+}
+
+/// Alternate screen with a hidden cursor, restored on drop (including on error)
+struct AlternateScreen;
+
+impl AlternateScreen {
+    fn enter() -> anyhow::Result<Self> {
+        execute!(std::io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(AlternateScreen)
+    }
+}
+
+impl Drop for AlternateScreen {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+        let _ = execute!(std::io::stdout(), Show, LeaveAlternateScreen);
+    }
+}
+
+/// Whether `key` exits watch mode: `q`, Esc or Ctrl-C
+fn is_quit_key(key: &KeyEvent) -> bool {
+    if key.kind != KeyEventKind::Press {
+        return false;
+    }
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => true,
+        KeyCode::Char('c') => key.modifiers.contains(KeyModifiers::CONTROL),
+        _ => false,
+    }
+}
+
+/// Header with `title` on the left and the update time right-aligned to
+/// `width` columns (appended after the title when the width is unknown)
+fn header_line(title: &str, updated: &str, width: Option<usize>) -> String {
+    let stamp = format!("Last updated: {}", updated);
+    let Some(width) = width else {
+        return format!("{}  {}", title, stamp);
+    };
+    let room = width.saturating_sub(stamp.chars().count() + 2);
+    let title = truncate(title, room);
+    let gap = width.saturating_sub(title.chars().count() + stamp.chars().count());
+    format!("{}{}{}", title, " ".repeat(gap.max(2)), stamp)
+}
+
+/// Lay out one boxed panel per device, side by side as far as `width` allows
+///
+/// Each panel is `(device name, output lines)`. Lines too long for a panel
+/// are cut off; panels in a row are padded to the tallest one.
+pub fn layout_panels(panels: &[(String, Vec<String>)], width: usize) -> String {
+    if panels.is_empty() {
+        return String::new();
+    }
+    let columns = (width / MIN_PANEL_WIDTH).clamp(1, panels.len());
+    let panel_width = (width.saturating_sub(columns - 1) / columns).max(MIN_PANEL_WIDTH / 2);
+    let inner = panel_width - 4;
+
+    let mut out = String::new();
+    for row in panels.chunks(columns) {
+        let height = row.iter().map(|(_, lines)| lines.len()).max().unwrap_or(0);
+        let boxed: Vec<Vec<String>> = row
+            .iter()
+            .map(|(name, lines)| {
+                let name = truncate(name, inner.saturating_sub(2));
+                let rule = "─".repeat(panel_width - 5 - name.chars().count());
+                let mut boxed = vec![format!("┌─ {} {}┐", name, rule)];
+                for i in 0..height {
+                    let line = truncate(lines.get(i).map_or("", String::as_str), inner);
+                    let pad = inner - line.chars().count();
+                    boxed.push(format!("│ {}{} │", line, " ".repeat(pad)));
+                }
+                boxed.push(format!("└{}┘", "─".repeat(panel_width - 2)));
+                boxed
+            })
+            .collect();
+        for i in 0..height + 2 {
+            let line: Vec<&str> = boxed.iter().map(|panel| panel[i].as_str()).collect();
+            out.push_str(&line.join(" "));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// One block character per value, from ▁ for zero to █ for the largest
+///
+/// All-zero values draw as a flat ▁ line.
+pub fn sparkline(values: &[f64]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().fold(0.0, f64::max);
+    values
+        .iter()
+        .map(|&v| {
+            if max <= 0.0 {
+                return BLOCKS[0];
+            }
+            let level = (v.max(0.0) / max * (BLOCKS.len() - 1) as f64).round() as usize;
+            BLOCKS[level.min(BLOCKS.len() - 1)]
+        })
+        .collect()
+}
+
+/// First `max` characters of `s`
+fn truncate(s: &str, max: usize) -> &str {
+    match s.char_indices().nth(max) {
+        Some((end, _)) => &s[..end],
+        None => s,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_line_right_aligns_timestamp() {
+        let line = header_line("Every 1000 ms: domes-cli trace status", "12:34:56", Some(80));
+        assert_eq!(line.chars().count(), 80);
+        assert!(line.starts_with("Every 1000 ms"));
+        assert!(line.ends_with("Last updated: 12:34:56"));
+
+        // A narrow terminal cuts the title, never the timestamp
+        let line = header_line("Every 1000 ms: domes-cli trace status", "12:34:56", Some(30));
+        assert!(line.ends_with("Last updated: 12:34:56"));
+        assert!(line.chars().count() <= 30);
+
+        assert_eq!(
+            header_line("Every 1000 ms", "12:34:56", None),
+            "Every 1000 ms  Last updated: 12:34:56"
+        );
+    }
+
+    #[test]
+    fn test_layout_panels_side_by_side_and_stacked() {
+        let panels = vec![
+            ("pod1".to_string(), vec!["Mode: idle".to_string()]),
+            (
+                "pod2".to_string(),
+                vec!["Mode: game".to_string(), "Uptime: 12s".to_string()],
+            ),
+        ];
+
+        let wide = layout_panels(&panels, 80);
+        let lines: Vec<&str> = wide.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("┌─ pod1 ") && lines[0].contains("┌─ pod2 "));
+        assert!(lines[2].starts_with("│            ") && lines[2].contains("│ Uptime: 12s"));
+        assert!(lines.iter().all(|l| l.chars().count() <= 80));
+
+        let narrow = layout_panels(&panels, 50);
+        let lines: Vec<&str> = narrow.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert!(lines[0].starts_with("┌─ pod1 "));
+        assert!(lines[3].starts_with("┌─ pod2 "));
+        assert!(lines.iter().all(|l| l.chars().count() == 50));
+    }
+
+    #[test]
+    fn test_sparkline_scales_to_largest_value() {
+        assert_eq!(sparkline(&[0.0, 50.0, 100.0, 25.0]), "▁▅█▃");
+        assert_eq!(sparkline(&[0.0, 0.0]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn test_quit_keys() {
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+        assert!(is_quit_key(&key(KeyCode::Char('q'), KeyModifiers::NONE)));
+        assert!(is_quit_key(&key(KeyCode::Esc, KeyModifiers::NONE)));
+        assert!(is_quit_key(&key(KeyCode::Char('c'), KeyModifiers::CONTROL)));
+        assert!(!is_quit_key(&key(KeyCode::Char('c'), KeyModifiers::NONE)));
+        assert!(!is_quit_key(&key(KeyCode::Enter, KeyModifiers::NONE)));
+    }
+}